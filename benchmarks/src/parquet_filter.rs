@@ -89,16 +89,19 @@ impl RunOpt {
                 pushdown_filters: false,
                 reorder_filters: false,
                 enable_page_index: false,
+                late_materialization: false,
             },
             ParquetScanOptions {
                 pushdown_filters: true,
                 reorder_filters: true,
                 enable_page_index: true,
+                late_materialization: false,
             },
             ParquetScanOptions {
                 pushdown_filters: true,
                 reorder_filters: true,
                 enable_page_index: false,
+                late_materialization: false,
             },
         ];
 
@@ -70,6 +70,9 @@ async fn main() -> Result<()> {
         limit: Some(5),
         table_partition_cols: vec![],
         output_ordering: vec![],
+        schema_adapter_factory: None,
+        metadata_cols: vec![],
+        projection_paths: vec![],
     };
 
     let result =
@@ -21,6 +21,7 @@ use crate::{DataFusionError, Result};
 use std::any::Any;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
+use std::str::FromStr;
 
 /// A macro that wraps a configuration struct and automatically derives
 /// [`Default`] and [`ConfigField`] for it, allowing it to be used
@@ -167,6 +168,10 @@ config_namespace! {
         /// virtual tables for displaying schema information
         pub information_schema: bool, default = false
 
+        /// Should DataFusion provide access to the `system` virtual tables
+        /// for displaying running and recent queries, memory consumers and caches
+        pub system_schema: bool, default = false
+
         /// Location scanned to load tables for `default` schema
         pub location: Option<String>, default = None
 
@@ -278,6 +283,51 @@ config_namespace! {
         /// Hive. Note that this setting does not affect reading partitioned
         /// tables (e.g. `/table/year=2021/month=01/data.parquet`).
         pub listing_table_ignore_subdirectory: bool, default = true
+
+        /// Should the partition columns of the external tables created without
+        /// a schema or partition columns be inferred from their hive style
+        /// directories (e.g. `/table/year=2021/month=01/data.parquet`), along
+        /// with the types of their values
+        pub listing_table_infer_partitions: bool, default = false
+
+        /// Maximum number of bytes all queries of a session may reserve
+        /// together. If not set, the session is only limited by the memory
+        /// pool of the runtime. Applied when the session is created
+        pub session_memory_limit: Option<usize>, default = None
+
+        /// Maximum number of bytes a single query may reserve. If not set,
+        /// the query is only limited by its session
+        pub query_memory_limit: Option<usize>, default = None
+
+        /// How the session and query memory limits are shared between memory
+        /// consumers. Valid values are "greedy", which grants reservations
+        /// first-come first-serve, and "fair", which gives every spillable
+        /// consumer an even share of the memory left by unspillable consumers
+        pub memory_pool_policy: MemoryPoolPolicy, default = MemoryPoolPolicy::Greedy
+
+        /// Maximum time in milliseconds a query may run before it is cancelled.
+        /// If not set, queries run until they complete
+        pub statement_timeout: Option<u64>, default = None
+
+        /// Number of finished queries kept in the history of a session,
+        /// which is shown in the `system.queries` table
+        pub query_history_size: usize, default = 100
+
+        /// Should queries be executed by the morsel-driven scheduler instead of
+        /// one tokio task per partition. The scheduler splits the plan into
+        /// pipelines at pipeline breakers, and runs them one batch at a time on
+        /// a shared pool of worker threads with work stealing
+        pub use_morsel_scheduler: bool, default = false
+
+        /// Should a projection over filters evaluate the filter predicates and the
+        /// projection on selection vectors of the input batches, instead of copying
+        /// the rows that pass each filter. Only the columns of the projection are
+        /// copied, for the rows that pass all the filters
+        pub enable_selection_vectors: bool, default = false
+
+        /// With selection vectors, batches are copied after a filter if less than
+        /// this fraction of their rows pass the filter
+        pub selection_compaction_threshold: f64, default = 0.25
     }
 }
 
@@ -314,6 +364,11 @@ config_namespace! {
         /// the filters are applied in the same order as written in the query
         pub reorder_filters: bool, default = false
 
+        /// (reading) If true, the parquet reader first reads the columns needed to
+        /// evaluate the pushed down filters, dynamic filters and TopK above the scan,
+        /// and reads the other columns only for the rows that are selected with them
+        pub late_materialization: bool, default = false
+
         // The following map to parquet::file::properties::WriterProperties
 
         /// Sets best effort maximum size of data page in bytes
@@ -445,6 +500,18 @@ config_namespace! {
         /// during aggregations, if possible
         pub enable_topk_aggregation: bool, default = true
 
+        /// When set to true, the optimizer will push the threshold of a sort with
+        /// a limit down to the parquet scans below it as a dynamic filter, which
+        /// the scans use to skip row groups and pages
+        pub enable_topk_dynamic_filter_pushdown: bool, default = true
+
+        /// When set to true, the optimizer will rewrite aggregates with DISTINCT
+        /// aggregates on more than one argument to two ordinary aggregates over
+        /// a union of one copy of the input per distinct argument. This avoids
+        /// keeping a hash set of distinct values per group, but reads and
+        /// projects the input once per distinct argument
+        pub enable_multi_distinct_to_group_by: bool, default = false
+
         /// When set to true, the optimizer will insert filters before a join between
         /// a nullable and non-nullable column to filter out nulls on the nullable side. This
         /// filter can add additional overhead when the file format does not fully support
@@ -510,6 +577,12 @@ config_namespace! {
         /// `RepartitionExec` even if this requires subsequently resorting data using a `SortExec`.
         pub prefer_existing_sort: bool, default = false
 
+        /// Should DataFusion sort the data globally by range partitioning it and
+        /// sorting the ranges in parallel, instead of merging sorted partitions.
+        /// The split points of the ranges are chosen by sampling a bounded prefix
+        /// of each input partition. Requires `repartition_sorts`.
+        pub range_partition_sorts: bool, default = false
+
         /// When set to true, the logical plan optimizer will produce warning
         /// messages if any optimization rules produce errors and then proceed to the next
         /// rule. When set to false, any rules that produce errors will cause the query to fail
@@ -889,6 +962,42 @@ config_field!(usize);
 config_field!(f64);
 config_field!(u8);
 config_field!(u64);
+config_field!(MemoryPoolPolicy);
+
+/// How a memory limit is shared between memory consumers, see
+/// `datafusion.execution.memory_pool_policy`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MemoryPoolPolicy {
+    /// Reservations are granted first-come first-serve
+    #[default]
+    Greedy,
+    /// Spillable consumers get an even share of the memory not used by
+    /// unspillable consumers
+    Fair,
+}
+
+impl FromStr for MemoryPoolPolicy {
+    type Err = DataFusionError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "greedy" => Ok(Self::Greedy),
+            "fair" => Ok(Self::Fair),
+            _ => Err(DataFusionError::Configuration(format!(
+                "Invalid memory pool policy {s}, expected greedy or fair"
+            ))),
+        }
+    }
+}
+
+impl Display for MemoryPoolPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Greedy => write!(f, "greedy"),
+            Self::Fair => write!(f, "fair"),
+        }
+    }
+}
 
 /// An implementation trait used to recursively walk configuration
 trait Visit {
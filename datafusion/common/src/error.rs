@@ -91,6 +91,9 @@ pub enum DataFusionError {
     /// This error is thrown when a consumer cannot acquire memory from the Memory Manager
     /// we can just cancel the execution of the partition.
     ResourcesExhausted(String),
+    /// This error is returned when a query is cancelled, either explicitly or
+    /// because it exceeded its statement timeout
+    Cancelled(String),
     /// Errors originating from outside DataFusion's core codebase.
     /// For example, a custom S3Error from the crate datafusion-objectstore-s3
     External(GenericError),
@@ -299,6 +302,9 @@ impl Display for DataFusionError {
             DataFusionError::ResourcesExhausted(ref desc) => {
                 write!(f, "Resources exhausted: {desc}")
             }
+            DataFusionError::Cancelled(ref desc) => {
+                write!(f, "Query cancelled: {desc}")
+            }
             DataFusionError::External(ref desc) => {
                 write!(f, "External error: {desc}")
             }
@@ -335,6 +341,7 @@ impl Error for DataFusionError {
             DataFusionError::SchemaError(e, _) => Some(e),
             DataFusionError::Execution(_) => None,
             DataFusionError::ResourcesExhausted(_) => None,
+            DataFusionError::Cancelled(_) => None,
             DataFusionError::External(e) => Some(e.as_ref()),
             DataFusionError::Context(_, e) => Some(e.as_ref()),
             DataFusionError::Substrait(_) => None,
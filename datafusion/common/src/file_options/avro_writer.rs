@@ -17,6 +17,8 @@
 
 //! Options related to how avro files should be written
 
+use std::str::FromStr;
+
 use crate::{
     config::ConfigOptions,
     error::{DataFusionError, Result},
@@ -24,13 +26,74 @@ use crate::{
 
 use super::StatementOptions;
 
+/// The codec used to compress the data blocks of avro files
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AvroCodec {
+    /// Uncompressed blocks
+    #[default]
+    Null,
+    /// Deflate (zlib) compressed blocks
+    Deflate,
+    /// Snappy compressed blocks
+    Snappy,
+    /// Zstandard compressed blocks
+    Zstandard,
+    /// Bzip2 compressed blocks
+    Bzip2,
+    /// Xz (lzma) compressed blocks
+    Xz,
+}
+
+impl FromStr for AvroCodec {
+    type Err = DataFusionError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "" | "null" | "uncompressed" => Ok(Self::Null),
+            "deflate" => Ok(Self::Deflate),
+            "snappy" => Ok(Self::Snappy),
+            "zstd" | "zstandard" => Ok(Self::Zstandard),
+            "bzip2" => Ok(Self::Bzip2),
+            "xz" => Ok(Self::Xz),
+            _ => Err(DataFusionError::Configuration(format!(
+                "Unknown or unsupported avro codec {s}. \
+                Valid values are: null, deflate, snappy, zstd, bzip2 and xz."
+            ))),
+        }
+    }
+}
+
+/// Options for writing avro files
 #[derive(Clone, Debug)]
-pub struct AvroWriterOptions {}
+pub struct AvroWriterOptions {
+    /// The codec used to compress the data blocks
+    pub codec: AvroCodec,
+}
+
+impl AvroWriterOptions {
+    pub fn new(codec: AvroCodec) -> Self {
+        Self { codec }
+    }
+}
 
 impl TryFrom<(&ConfigOptions, &StatementOptions)> for AvroWriterOptions {
     type Error = DataFusionError;
 
-    fn try_from(_value: (&ConfigOptions, &StatementOptions)) -> Result<Self> {
-        Ok(AvroWriterOptions {})
+    fn try_from(value: (&ConfigOptions, &StatementOptions)) -> Result<Self> {
+        let statement_options = value.1;
+        let mut codec = AvroCodec::default();
+        for (option, value) in &statement_options.options {
+            match option.to_lowercase().as_str() {
+                "compression" | "codec" => {
+                    codec = AvroCodec::from_str(value.replace('\'', "").as_str())?;
+                }
+                _ => {
+                    return Err(DataFusionError::Configuration(format!(
+                        "Found unsupported option {option} with value {value} for AVRO format!"
+                    )))
+                }
+            }
+        }
+        Ok(AvroWriterOptions { codec })
     }
 }
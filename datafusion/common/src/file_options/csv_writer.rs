@@ -97,6 +97,10 @@ impl TryFrom<(&ConfigOptions, &StatementOptions)> for CsvWriterOptions {
                     // To avoid error
                     builder
                 },
+                "bad_rows" | "rejects_table" | "newlines_in_values" | "infer_decimals" => {
+                    // Only used when reading csv files
+                    builder
+                },
                 _ => return Err(DataFusionError::Configuration(format!("Found unsupported option {option} with value {value} for CSV format!")))
             }
         }
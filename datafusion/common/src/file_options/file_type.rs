@@ -31,6 +31,8 @@ pub const DEFAULT_AVRO_EXTENSION: &str = ".avro";
 pub const DEFAULT_CSV_EXTENSION: &str = ".csv";
 /// The default file extension of json files
 pub const DEFAULT_JSON_EXTENSION: &str = ".json";
+/// The default file extension of orc files
+pub const DEFAULT_ORC_EXTENSION: &str = ".orc";
 /// The default file extension of parquet files
 pub const DEFAULT_PARQUET_EXTENSION: &str = ".parquet";
 
@@ -54,6 +56,9 @@ pub enum FileType {
     CSV,
     /// JSON file
     JSON,
+    /// Apache ORC file
+    #[cfg(feature = "orc")]
+    ORC,
 }
 
 impl GetExt for FileType {
@@ -65,6 +70,8 @@ impl GetExt for FileType {
             FileType::PARQUET => DEFAULT_PARQUET_EXTENSION.to_owned(),
             FileType::CSV => DEFAULT_CSV_EXTENSION.to_owned(),
             FileType::JSON => DEFAULT_JSON_EXTENSION.to_owned(),
+            #[cfg(feature = "orc")]
+            FileType::ORC => DEFAULT_ORC_EXTENSION.to_owned(),
         }
     }
 }
@@ -78,6 +85,8 @@ impl Display for FileType {
             FileType::PARQUET => "parquet",
             FileType::AVRO => "avro",
             FileType::ARROW => "arrow",
+            #[cfg(feature = "orc")]
+            FileType::ORC => "orc",
         };
         write!(f, "{}", out)
     }
@@ -95,6 +104,8 @@ impl FromStr for FileType {
             "PARQUET" => Ok(FileType::PARQUET),
             "CSV" => Ok(FileType::CSV),
             "JSON" | "NDJSON" => Ok(FileType::JSON),
+            #[cfg(feature = "orc")]
+            "ORC" => Ok(FileType::ORC),
             _ => Err(DataFusionError::NotImplemented(format!(
                 "Unknown FileType: {s}"
             ))),
@@ -123,6 +134,10 @@ mod tests {
         ] {
             assert_eq!(FileType::from_str(ext).unwrap(), file_type);
         }
+        #[cfg(feature = "orc")]
+        for ext in ["orc", "ORC"] {
+            assert_eq!(FileType::from_str(ext).unwrap(), FileType::ORC);
+        }
 
         assert!(matches!(
             FileType::from_str("Unknown"),
@@ -51,6 +51,8 @@ impl TryFrom<(&ConfigOptions, &StatementOptions)> for JsonWriterOptions {
                 "compression" => {
                     compression = CompressionTypeVariant::from_str(value.replace('\'', "").as_str())?;
                 },
+                // Only used when reading json files
+                "array" | "unknown_fields_column" => {},
                 _ => return Err(DataFusionError::Configuration(format!("Found unsupported option {option} with value {value} for JSON format!")))
             }
         }
@@ -22,6 +22,8 @@ pub mod avro_writer;
 pub mod csv_writer;
 pub mod file_type;
 pub mod json_writer;
+#[cfg(feature = "orc")]
+pub mod orc_writer;
 #[cfg(feature = "parquet")]
 pub mod parquet_writer;
 pub(crate) mod parse_utils;
@@ -41,6 +43,8 @@ use crate::{
 #[cfg(feature = "parquet")]
 use self::parquet_writer::ParquetWriterOptions;
 
+#[cfg(feature = "orc")]
+use self::orc_writer::OrcWriterOptions;
 use self::{
     arrow_writer::ArrowWriterOptions, avro_writer::AvroWriterOptions,
     csv_writer::CsvWriterOptions, json_writer::JsonWriterOptions,
@@ -154,6 +158,8 @@ pub enum FileTypeWriterOptions {
     JSON(JsonWriterOptions),
     Avro(AvroWriterOptions),
     Arrow(ArrowWriterOptions),
+    #[cfg(feature = "orc")]
+    Orc(OrcWriterOptions),
 }
 
 impl FileTypeWriterOptions {
@@ -184,6 +190,10 @@ impl FileTypeWriterOptions {
             FileType::ARROW => {
                 FileTypeWriterOptions::Arrow(ArrowWriterOptions::try_from(options)?)
             }
+            #[cfg(feature = "orc")]
+            FileType::ORC => {
+                FileTypeWriterOptions::Orc(OrcWriterOptions::try_from(options)?)
+            }
         };
 
         Ok(file_type_write_options)
@@ -214,6 +224,10 @@ impl FileTypeWriterOptions {
             FileType::ARROW => {
                 FileTypeWriterOptions::Arrow(ArrowWriterOptions::try_from(options)?)
             }
+            #[cfg(feature = "orc")]
+            FileType::ORC => {
+                FileTypeWriterOptions::Orc(OrcWriterOptions::try_from(options)?)
+            }
         };
 
         Ok(file_type_write_options)
@@ -279,6 +293,19 @@ impl FileTypeWriterOptions {
             ))),
         }
     }
+
+    /// Tries to extract OrcWriterOptions from this FileTypeWriterOptions enum.
+    /// Returns an error if a different type from orc is set.
+    #[cfg(feature = "orc")]
+    pub fn try_into_orc(&self) -> Result<&OrcWriterOptions> {
+        match self {
+            FileTypeWriterOptions::Orc(opt) => Ok(opt),
+            _ => Err(DataFusionError::Internal(format!(
+                "Expected orc options but found options for {}",
+                self
+            ))),
+        }
+    }
 }
 
 impl Display for FileTypeWriterOptions {
@@ -288,6 +315,8 @@ impl Display for FileTypeWriterOptions {
             FileTypeWriterOptions::Avro(_) => "AvroWriterOptions",
             FileTypeWriterOptions::CSV(_) => "CsvWriterOptions",
             FileTypeWriterOptions::JSON(_) => "JsonWriterOptions",
+            #[cfg(feature = "orc")]
+            FileTypeWriterOptions::Orc(_) => "OrcWriterOptions",
             #[cfg(feature = "parquet")]
             FileTypeWriterOptions::Parquet(_) => "ParquetWriterOptions",
         };
@@ -308,7 +337,11 @@ mod tests {
 
     use crate::{
         config::ConfigOptions,
-        file_options::{csv_writer::CsvWriterOptions, json_writer::JsonWriterOptions},
+        file_options::{
+            avro_writer::{AvroCodec, AvroWriterOptions},
+            csv_writer::CsvWriterOptions,
+            json_writer::JsonWriterOptions,
+        },
         parsers::CompressionTypeVariant,
     };
 
@@ -548,4 +581,45 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    // for StatementOptions
+    fn test_writeroptions_avro_from_statement_options() -> Result<()> {
+        let mut option_map: HashMap<String, String> = HashMap::new();
+        option_map.insert("compression".to_owned(), "snappy".to_owned());
+
+        let options = StatementOptions::from(&option_map);
+        let config = ConfigOptions::new();
+
+        let avro_options = AvroWriterOptions::try_from((&config, &options))?;
+        assert_eq!(avro_options.codec, AvroCodec::Snappy);
+
+        option_map.insert("compression".to_owned(), "lz4".to_owned());
+        let options = StatementOptions::from(&option_map);
+        assert!(AvroWriterOptions::try_from((&config, &options)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "orc")]
+    // for StatementOptions
+    fn test_writeroptions_orc_from_statement_options() -> Result<()> {
+        use crate::file_options::orc_writer::OrcWriterOptions;
+
+        let mut option_map: HashMap<String, String> = HashMap::new();
+        option_map.insert("stripe_size".to_owned(), "1048576".to_owned());
+
+        let options = StatementOptions::from(&option_map);
+        let config = ConfigOptions::new();
+
+        let orc_options = OrcWriterOptions::try_from((&config, &options))?;
+        assert_eq!(orc_options.stripe_size, Some(1048576));
+
+        option_map.insert("stripe_size".to_owned(), "1MB".to_owned());
+        let options = StatementOptions::from(&option_map);
+        assert!(OrcWriterOptions::try_from((&config, &options)).is_err());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,66 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Options related to how ORC files should be written
+
+use crate::{
+    config::ConfigOptions,
+    error::{DataFusionError, Result},
+};
+
+use super::StatementOptions;
+
+/// Options for writing ORC files
+#[derive(Clone, Debug, Default)]
+pub struct OrcWriterOptions {
+    /// The number of bytes of encoded data after which a stripe is written,
+    /// or `None` to use the default of the writer
+    pub stripe_size: Option<usize>,
+}
+
+impl OrcWriterOptions {
+    pub fn new(stripe_size: Option<usize>) -> Self {
+        Self { stripe_size }
+    }
+}
+
+impl TryFrom<(&ConfigOptions, &StatementOptions)> for OrcWriterOptions {
+    type Error = DataFusionError;
+
+    fn try_from(value: (&ConfigOptions, &StatementOptions)) -> Result<Self> {
+        let statement_options = value.1;
+        let mut stripe_size = None;
+        for (option, value) in &statement_options.options {
+            match option.to_lowercase().as_str() {
+                "stripe_size" => {
+                    let size = value.replace('\'', "").parse().map_err(|_| {
+                        DataFusionError::Configuration(format!(
+                            "Unable to parse {value} as a number of bytes as required for {option}!"
+                        ))
+                    })?;
+                    stripe_size = Some(size);
+                }
+                _ => {
+                    return Err(DataFusionError::Configuration(format!(
+                        "Found unsupported option {option} with value {value} for ORC format!"
+                    )))
+                }
+            }
+        }
+        Ok(OrcWriterOptions { stripe_size })
+    }
+}
@@ -20,6 +20,7 @@
 pub mod information_schema;
 pub mod listing_schema;
 pub mod schema;
+pub mod system_schema;
 
 pub use datafusion_sql::{ResolvedTableReference, TableReference};
 
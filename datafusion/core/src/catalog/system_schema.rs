@@ -0,0 +1,750 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Implements the `system` schema, whose tables expose the state of the
+//! session, such as its running and recent queries, the memory consumers
+//! and the caches.
+
+use async_trait::async_trait;
+use std::{any::Any, sync::Arc};
+
+use arrow::{
+    array::{
+        BooleanBuilder, Float64Builder, StringBuilder, TimestampNanosecondBuilder,
+        UInt64Builder,
+    },
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+    record_batch::RecordBatch,
+};
+use chrono::{DateTime, Utc};
+
+use crate::datasource::streaming::StreamingTable;
+use crate::datasource::TableProvider;
+use crate::execution::cache::CacheStats;
+use crate::execution::context::TaskContext;
+use crate::execution::memory_pool::{MemoryConsumerUsage, MemoryPool};
+use crate::execution::running_queries::{QueryRecord, RunningQueries, RunningQuery};
+use crate::execution::runtime_env::RuntimeEnv;
+use crate::physical_plan::stream::RecordBatchStreamAdapter;
+use crate::physical_plan::streaming::PartitionStream;
+use crate::physical_plan::{displayable, SendableRecordBatchStream};
+
+use super::schema::SchemaProvider;
+
+pub(crate) const SYSTEM_SCHEMA: &str = "system";
+pub(crate) const RUNNING_QUERIES: &str = "running_queries";
+pub(crate) const QUERIES: &str = "queries";
+pub(crate) const MEMORY_CONSUMERS: &str = "memory_consumers";
+pub(crate) const CACHES: &str = "caches";
+
+/// All system tables
+pub const SYSTEM_TABLES: &[&str] = &[RUNNING_QUERIES, QUERIES, MEMORY_CONSUMERS, CACHES];
+
+/// The time zone of the timestamps in the system tables
+const UTC: &str = "+00:00";
+
+/// Implements the `system` virtual schema and tables
+///
+/// The tables are computed when they are queried, so they show the state
+/// of the session at that time. The `system` schema is only used when
+/// [`CatalogOptions::system_schema`] is enabled and the catalog does not
+/// contain a schema of the same name.
+///
+/// [`CatalogOptions::system_schema`]: crate::config::CatalogOptions::system_schema
+pub struct SystemSchemaProvider {
+    config: SystemSchemaConfig,
+}
+
+impl SystemSchemaProvider {
+    /// Creates a new [`SystemSchemaProvider`] for a session with the
+    /// provided `running_queries` and `memory_pool`, using `runtime_env`
+    pub fn new(
+        running_queries: Arc<RunningQueries>,
+        runtime_env: Arc<RuntimeEnv>,
+        memory_pool: Arc<dyn MemoryPool>,
+    ) -> Self {
+        Self {
+            config: SystemSchemaConfig {
+                running_queries,
+                runtime_env,
+                memory_pool,
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+struct SystemSchemaConfig {
+    running_queries: Arc<RunningQueries>,
+    runtime_env: Arc<RuntimeEnv>,
+    /// The memory pool of the session
+    memory_pool: Arc<dyn MemoryPool>,
+}
+
+impl SystemSchemaConfig {
+    /// Construct the `system.running_queries` virtual table
+    fn make_running_queries(&self, builder: &mut SystemRunningQueriesBuilder) {
+        for query in self.running_queries.queries() {
+            builder.add_query(&query)
+        }
+    }
+
+    /// Construct the `system.queries` virtual table
+    fn make_queries(&self, builder: &mut SystemQueriesBuilder) {
+        for record in self.running_queries.history() {
+            builder.add_query(&record)
+        }
+    }
+
+    /// Construct the `system.memory_consumers` virtual table
+    fn make_memory_consumers(&self, builder: &mut SystemMemoryConsumersBuilder) {
+        for consumer in self.memory_pool.consumers() {
+            builder.add_consumer(&consumer)
+        }
+    }
+
+    /// Construct the `system.caches` virtual table
+    fn make_caches(&self, builder: &mut SystemCachesBuilder) {
+        let cache_manager = &self.runtime_env.cache_manager;
+        if let Some(cache) = cache_manager.get_file_statistic_cache() {
+            builder.add_cache(
+                "file_statistics",
+                cache.name(),
+                cache.len(),
+                cache.stats(),
+            );
+        }
+        if let Some(cache) = cache_manager.get_list_files_cache() {
+            builder.add_cache("list_files", cache.name(), cache.len(), cache.stats());
+        }
+        if let Some(cache) = cache_manager.get_parquet_metadata_cache() {
+            builder.add_cache(
+                "parquet_metadata",
+                cache.name(),
+                cache.len(),
+                cache.stats(),
+            );
+        }
+        if let Some(cache) = cache_manager.get_object_store_cache() {
+            let name = "ObjectStoreCache".to_string();
+            builder.add_cache(
+                "object_store_blocks",
+                name.clone(),
+                cache.block_count(),
+                cache.block_stats(),
+            );
+            builder.add_cache(
+                "object_store_footers",
+                name,
+                cache.footer_count(),
+                cache.footer_stats(),
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl SchemaProvider for SystemSchemaProvider {
+    fn as_any(&self) -> &(dyn Any + 'static) {
+        self
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        SYSTEM_TABLES.iter().map(|t| t.to_string()).collect()
+    }
+
+    async fn table(&self, name: &str) -> Option<Arc<dyn TableProvider>> {
+        let config = self.config.clone();
+        let table: Arc<dyn PartitionStream> =
+            if name.eq_ignore_ascii_case(RUNNING_QUERIES) {
+                Arc::new(SystemRunningQueries::new(config))
+            } else if name.eq_ignore_ascii_case(QUERIES) {
+                Arc::new(SystemQueries::new(config))
+            } else if name.eq_ignore_ascii_case(MEMORY_CONSUMERS) {
+                Arc::new(SystemMemoryConsumers::new(config))
+            } else if name.eq_ignore_ascii_case(CACHES) {
+                Arc::new(SystemCaches::new(config))
+            } else {
+                return None;
+            };
+
+        Some(Arc::new(
+            StreamingTable::try_new(table.schema().clone(), vec![table]).unwrap(),
+        ))
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        SYSTEM_TABLES.contains(&name.to_ascii_lowercase().as_str())
+    }
+}
+
+/// Returns the nanoseconds since the epoch of `time`
+fn timestamp_nanos(time: DateTime<Utc>) -> i64 {
+    time.timestamp_nanos_opt().unwrap_or_default()
+}
+
+struct SystemRunningQueries {
+    schema: SchemaRef,
+    config: SystemSchemaConfig,
+}
+
+impl SystemRunningQueries {
+    fn new(config: SystemSchemaConfig) -> Self {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("query_id", DataType::UInt64, false),
+            Field::new("session_id", DataType::Utf8, false),
+            Field::new("sql", DataType::Utf8, true),
+            Field::new(
+                "start_time",
+                DataType::Timestamp(TimeUnit::Nanosecond, Some(UTC.into())),
+                false,
+            ),
+            Field::new("rows", DataType::UInt64, false),
+            Field::new("bytes", DataType::UInt64, false),
+            Field::new("total_rows", DataType::UInt64, true),
+            Field::new("total_bytes", DataType::UInt64, true),
+            Field::new("progress", DataType::Float64, true),
+            Field::new("plan", DataType::Utf8, false),
+        ]));
+
+        Self { schema, config }
+    }
+
+    fn builder(&self) -> SystemRunningQueriesBuilder {
+        SystemRunningQueriesBuilder {
+            query_ids: UInt64Builder::new(),
+            session_ids: StringBuilder::new(),
+            sqls: StringBuilder::new(),
+            start_times: TimestampNanosecondBuilder::new().with_timezone(UTC),
+            rows: UInt64Builder::new(),
+            bytes: UInt64Builder::new(),
+            total_rows: UInt64Builder::new(),
+            total_bytes: UInt64Builder::new(),
+            progress: Float64Builder::new(),
+            plans: StringBuilder::new(),
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl PartitionStream for SystemRunningQueries {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let mut builder = self.builder();
+        let config = self.config.clone();
+        Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            futures::stream::once(async move {
+                config.make_running_queries(&mut builder);
+                Ok(builder.finish())
+            }),
+        ))
+    }
+}
+
+/// Builds the `system.running_queries` table row by row
+struct SystemRunningQueriesBuilder {
+    schema: SchemaRef,
+    query_ids: UInt64Builder,
+    session_ids: StringBuilder,
+    sqls: StringBuilder,
+    start_times: TimestampNanosecondBuilder,
+    rows: UInt64Builder,
+    bytes: UInt64Builder,
+    total_rows: UInt64Builder,
+    total_bytes: UInt64Builder,
+    progress: Float64Builder,
+    plans: StringBuilder,
+}
+
+impl SystemRunningQueriesBuilder {
+    fn add_query(&mut self, query: &RunningQuery) {
+        let progress = query.progress();
+        self.query_ids.append_value(query.id());
+        self.session_ids.append_value(query.session_id());
+        self.sqls.append_option(query.sql());
+        self.start_times
+            .append_value(timestamp_nanos(query.start_time()));
+        self.rows.append_value(progress.rows as u64);
+        self.bytes.append_value(progress.bytes as u64);
+        self.total_rows
+            .append_option(progress.total_rows.map(|v| v as u64));
+        self.total_bytes
+            .append_option(progress.total_bytes.map(|v| v as u64));
+        self.progress.append_option(query.estimated_completion());
+        let plan = displayable(query.plan().as_ref()).one_line().to_string();
+        self.plans.append_value(plan.trim_end());
+    }
+
+    fn finish(&mut self) -> RecordBatch {
+        RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(self.query_ids.finish()),
+                Arc::new(self.session_ids.finish()),
+                Arc::new(self.sqls.finish()),
+                Arc::new(self.start_times.finish()),
+                Arc::new(self.rows.finish()),
+                Arc::new(self.bytes.finish()),
+                Arc::new(self.total_rows.finish()),
+                Arc::new(self.total_bytes.finish()),
+                Arc::new(self.progress.finish()),
+                Arc::new(self.plans.finish()),
+            ],
+        )
+        .unwrap()
+    }
+}
+
+struct SystemQueries {
+    schema: SchemaRef,
+    config: SystemSchemaConfig,
+}
+
+impl SystemQueries {
+    fn new(config: SystemSchemaConfig) -> Self {
+        let timestamp = DataType::Timestamp(TimeUnit::Nanosecond, Some(UTC.into()));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("query_id", DataType::UInt64, false),
+            Field::new("session_id", DataType::Utf8, false),
+            Field::new("sql", DataType::Utf8, true),
+            Field::new("start_time", timestamp.clone(), false),
+            Field::new("end_time", timestamp, false),
+            Field::new("status", DataType::Utf8, false),
+            Field::new("error", DataType::Utf8, true),
+            Field::new("output_rows", DataType::UInt64, false),
+            Field::new("memory_used", DataType::UInt64, false),
+            Field::new("spilled_bytes", DataType::UInt64, false),
+        ]));
+
+        Self { schema, config }
+    }
+
+    fn builder(&self) -> SystemQueriesBuilder {
+        SystemQueriesBuilder {
+            query_ids: UInt64Builder::new(),
+            session_ids: StringBuilder::new(),
+            sqls: StringBuilder::new(),
+            start_times: TimestampNanosecondBuilder::new().with_timezone(UTC),
+            end_times: TimestampNanosecondBuilder::new().with_timezone(UTC),
+            statuses: StringBuilder::new(),
+            errors: StringBuilder::new(),
+            output_rows: UInt64Builder::new(),
+            memory_used: UInt64Builder::new(),
+            spilled_bytes: UInt64Builder::new(),
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl PartitionStream for SystemQueries {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let mut builder = self.builder();
+        let config = self.config.clone();
+        Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            futures::stream::once(async move {
+                config.make_queries(&mut builder);
+                Ok(builder.finish())
+            }),
+        ))
+    }
+}
+
+/// Builds the `system.queries` table row by row
+struct SystemQueriesBuilder {
+    schema: SchemaRef,
+    query_ids: UInt64Builder,
+    session_ids: StringBuilder,
+    sqls: StringBuilder,
+    start_times: TimestampNanosecondBuilder,
+    end_times: TimestampNanosecondBuilder,
+    statuses: StringBuilder,
+    errors: StringBuilder,
+    output_rows: UInt64Builder,
+    memory_used: UInt64Builder,
+    spilled_bytes: UInt64Builder,
+}
+
+impl SystemQueriesBuilder {
+    fn add_query(&mut self, record: &QueryRecord) {
+        self.query_ids.append_value(record.id);
+        self.session_ids.append_value(&record.session_id);
+        self.sqls.append_option(record.sql.as_deref());
+        self.start_times
+            .append_value(timestamp_nanos(record.start_time));
+        self.end_times
+            .append_value(timestamp_nanos(record.end_time));
+        self.statuses.append_value(record.status.to_string());
+        self.errors.append_option(record.error.as_deref());
+        self.output_rows.append_value(record.output_rows as u64);
+        self.memory_used.append_value(record.memory_used as u64);
+        self.spilled_bytes.append_value(record.spilled_bytes as u64);
+    }
+
+    fn finish(&mut self) -> RecordBatch {
+        RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(self.query_ids.finish()),
+                Arc::new(self.session_ids.finish()),
+                Arc::new(self.sqls.finish()),
+                Arc::new(self.start_times.finish()),
+                Arc::new(self.end_times.finish()),
+                Arc::new(self.statuses.finish()),
+                Arc::new(self.errors.finish()),
+                Arc::new(self.output_rows.finish()),
+                Arc::new(self.memory_used.finish()),
+                Arc::new(self.spilled_bytes.finish()),
+            ],
+        )
+        .unwrap()
+    }
+}
+
+struct SystemMemoryConsumers {
+    schema: SchemaRef,
+    config: SystemSchemaConfig,
+}
+
+impl SystemMemoryConsumers {
+    fn new(config: SystemSchemaConfig) -> Self {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("consumer_id", DataType::UInt64, false),
+            Field::new("name", DataType::Utf8, false),
+            Field::new("can_spill", DataType::Boolean, false),
+            Field::new("reserved", DataType::UInt64, false),
+            Field::new("peak", DataType::UInt64, false),
+        ]));
+
+        Self { schema, config }
+    }
+
+    fn builder(&self) -> SystemMemoryConsumersBuilder {
+        SystemMemoryConsumersBuilder {
+            consumer_ids: UInt64Builder::new(),
+            names: StringBuilder::new(),
+            can_spill: BooleanBuilder::new(),
+            reserved: UInt64Builder::new(),
+            peak: UInt64Builder::new(),
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl PartitionStream for SystemMemoryConsumers {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let mut builder = self.builder();
+        let config = self.config.clone();
+        Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            futures::stream::once(async move {
+                config.make_memory_consumers(&mut builder);
+                Ok(builder.finish())
+            }),
+        ))
+    }
+}
+
+/// Builds the `system.memory_consumers` table row by row
+struct SystemMemoryConsumersBuilder {
+    schema: SchemaRef,
+    consumer_ids: UInt64Builder,
+    names: StringBuilder,
+    can_spill: BooleanBuilder,
+    reserved: UInt64Builder,
+    peak: UInt64Builder,
+}
+
+impl SystemMemoryConsumersBuilder {
+    fn add_consumer(&mut self, consumer: &MemoryConsumerUsage) {
+        self.consumer_ids.append_value(consumer.id as u64);
+        self.names.append_value(&consumer.name);
+        self.can_spill.append_value(consumer.can_spill);
+        self.reserved.append_value(consumer.reserved as u64);
+        self.peak.append_value(consumer.peak as u64);
+    }
+
+    fn finish(&mut self) -> RecordBatch {
+        RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(self.consumer_ids.finish()),
+                Arc::new(self.names.finish()),
+                Arc::new(self.can_spill.finish()),
+                Arc::new(self.reserved.finish()),
+                Arc::new(self.peak.finish()),
+            ],
+        )
+        .unwrap()
+    }
+}
+
+struct SystemCaches {
+    schema: SchemaRef,
+    config: SystemSchemaConfig,
+}
+
+impl SystemCaches {
+    fn new(config: SystemSchemaConfig) -> Self {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("cache", DataType::Utf8, false),
+            Field::new("name", DataType::Utf8, false),
+            Field::new("entries", DataType::UInt64, false),
+            Field::new("hits", DataType::UInt64, false),
+            Field::new("misses", DataType::UInt64, false),
+            Field::new("hit_rate", DataType::Float64, true),
+        ]));
+
+        Self { schema, config }
+    }
+
+    fn builder(&self) -> SystemCachesBuilder {
+        SystemCachesBuilder {
+            caches: StringBuilder::new(),
+            names: StringBuilder::new(),
+            entries: UInt64Builder::new(),
+            hits: UInt64Builder::new(),
+            misses: UInt64Builder::new(),
+            hit_rates: Float64Builder::new(),
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl PartitionStream for SystemCaches {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let mut builder = self.builder();
+        let config = self.config.clone();
+        Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            futures::stream::once(async move {
+                config.make_caches(&mut builder);
+                Ok(builder.finish())
+            }),
+        ))
+    }
+}
+
+/// Builds the `system.caches` table row by row
+struct SystemCachesBuilder {
+    schema: SchemaRef,
+    caches: StringBuilder,
+    names: StringBuilder,
+    entries: UInt64Builder,
+    hits: UInt64Builder,
+    misses: UInt64Builder,
+    hit_rates: Float64Builder,
+}
+
+impl SystemCachesBuilder {
+    fn add_cache(
+        &mut self,
+        cache: &str,
+        name: String,
+        entries: usize,
+        stats: CacheStats,
+    ) {
+        self.caches.append_value(cache);
+        self.names.append_value(name);
+        self.entries.append_value(entries as u64);
+        self.hits.append_value(stats.hits as u64);
+        self.misses.append_value(stats.misses as u64);
+        self.hit_rates.append_option(stats.hit_rate());
+    }
+
+    fn finish(&mut self) -> RecordBatch {
+        RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(self.caches.finish()),
+                Arc::new(self.names.finish()),
+                Arc::new(self.entries.finish()),
+                Arc::new(self.hits.finish()),
+                Arc::new(self.misses.finish()),
+                Arc::new(self.hit_rates.finish()),
+            ],
+        )
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::execution::cache::cache_manager::CacheManagerConfig;
+    use crate::execution::cache::cache_unit::{
+        DefaultFileStatisticsCache, DefaultListFilesCache, DefaultParquetMetadataCache,
+    };
+    use crate::execution::cache::object_store_cache::ObjectStoreCacheConfig;
+    use crate::execution::cache::CacheAccessor;
+    use crate::execution::memory_pool::{
+        HierarchicalMemoryPool, MemoryConsumer, UnboundedMemoryPool,
+    };
+    use crate::execution::runtime_env::RuntimeConfig;
+    use crate::prelude::{SessionConfig, SessionContext};
+    use datafusion_common::Result;
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use url::Url;
+
+    #[tokio::test]
+    async fn memory_consumers_and_caches() -> Result<()> {
+        let list_files_cache = Arc::new(DefaultListFilesCache::default());
+        let cache_config = CacheManagerConfig::default()
+            .with_files_statistics_cache(Some(Arc::new(
+                DefaultFileStatisticsCache::default(),
+            )))
+            .with_list_files_cache(Some(list_files_cache.clone()))
+            .with_parquet_metadata_cache(Some(Arc::new(
+                DefaultParquetMetadataCache::default(),
+            )));
+        // the consumers are tracked by the pools of sessions with a limit
+        let memory_pool = HierarchicalMemoryPool::new(
+            "runtime",
+            Arc::new(UnboundedMemoryPool::default()),
+        );
+        let runtime = Arc::new(RuntimeEnv::new(
+            RuntimeConfig::new()
+                .with_cache_manager(cache_config)
+                .with_memory_pool(Arc::new(memory_pool)),
+        )?);
+        let mut config = SessionConfig::new().with_system_schema(true);
+        config.options_mut().execution.session_memory_limit = Some(1 << 30);
+        let ctx = SessionContext::new_with_config_rt(config.clone(), runtime.clone());
+        let other_ctx = SessionContext::new_with_config_rt(config, runtime);
+
+        let path = Path::from("data");
+        assert!(list_files_cache.get(&path).is_none());
+        list_files_cache.put(&path, Arc::new(vec![]));
+        assert!(list_files_cache.get(&path).is_some());
+
+        let mut reservation =
+            MemoryConsumer::new("test").register(ctx.state().memory_pool());
+        reservation.grow(100);
+        reservation.shrink(40);
+        // consumers of other sessions are not listed
+        let mut other_reservation =
+            MemoryConsumer::new("other").register(other_ctx.state().memory_pool());
+        other_reservation.grow(10);
+
+        let batches = ctx
+            .sql("SELECT name, can_spill, reserved, peak FROM system.memory_consumers")
+            .await?
+            .collect()
+            .await?;
+        assert_batches_eq!(
+            [
+                "+------+-----------+----------+------+",
+                "| name | can_spill | reserved | peak |",
+                "+------+-----------+----------+------+",
+                "| test | false     | 60       | 100  |",
+                "+------+-----------+----------+------+",
+            ],
+            &batches
+        );
+
+        let batches = ctx
+            .sql("SELECT * FROM system.caches ORDER BY cache")
+            .await?
+            .collect()
+            .await?;
+        assert_batches_eq!(
+            [
+                "+------------------+-----------------------------+---------+------+--------+----------+",
+                "| cache            | name                        | entries | hits | misses | hit_rate |",
+                "+------------------+-----------------------------+---------+------+--------+----------+",
+                "| file_statistics  | DefaultFileStatisticsCache  | 0       | 0    | 0      |          |",
+                "| list_files       | DefaultListFilesCache       | 1       | 1    | 1      | 0.5      |",
+                "| parquet_metadata | DefaultParquetMetadataCache | 0       | 0    | 0      |          |",
+                "+------------------+-----------------------------+---------+------+--------+----------+",
+            ],
+            &batches
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn object_store_caches() -> Result<()> {
+        let runtime = RuntimeEnv::new(
+            RuntimeConfig::new()
+                .with_object_store_cache(ObjectStoreCacheConfig::default()),
+        )?;
+        let ctx = SessionContext::new_with_config_rt(
+            SessionConfig::new().with_system_schema(true),
+            Arc::new(runtime),
+        );
+        let url = Url::parse("s3://bucket").unwrap();
+        ctx.runtime_env()
+            .register_object_store(&url, Arc::new(InMemory::new()));
+
+        ctx.sql("COPY (VALUES (1, 'a'), (2, 'b')) TO 's3://bucket/data.parquet' (format parquet)")
+            .await?
+            .collect()
+            .await?;
+        ctx.sql("CREATE EXTERNAL TABLE t STORED AS PARQUET LOCATION 's3://bucket/data.parquet'")
+            .await?
+            .collect()
+            .await?;
+        for _ in 0..2 {
+            ctx.sql("SELECT * FROM t").await?.collect().await?;
+        }
+
+        // the file is smaller than the footers, so it is read once and then
+        // served from memory
+        let cache = ctx.runtime_env().cache_manager.get_object_store_cache();
+        let stats = cache.unwrap().footer_stats();
+        assert_eq!(stats.misses, 1);
+        assert!(stats.hits > 1);
+
+        let batches = ctx
+            .sql("SELECT cache, name, entries, misses FROM system.caches ORDER BY cache")
+            .await?
+            .collect()
+            .await?;
+        assert_batches_eq!(
+            [
+                "+----------------------+------------------+---------+--------+",
+                "| cache                | name             | entries | misses |",
+                "+----------------------+------------------+---------+--------+",
+                "| object_store_blocks  | ObjectStoreCache | 0       | 0      |",
+                "| object_store_footers | ObjectStoreCache | 1       | 1      |",
+                "+----------------------+------------------+---------+--------+",
+            ],
+            &batches
+        );
+
+        Ok(())
+    }
+}
@@ -30,6 +30,9 @@ use crate::datasource::{provider_as_source, MemTable, TableProvider};
 use crate::error::Result;
 use crate::execution::{
     context::{SessionState, TaskContext},
+    memory_pool::MemoryPool,
+    running_queries::{RunningQueryGuard, RunningQueryStream},
+    scheduler::Scheduler,
     FunctionRegistry,
 };
 use crate::logical_expr::utils::find_window_exprs;
@@ -37,7 +40,7 @@ use crate::logical_expr::{
     col, Expr, JoinType, LogicalPlan, LogicalPlanBuilder, Partitioning, TableType,
 };
 use crate::physical_plan::{
-    collect, collect_partitioned, execute_stream, execute_stream_partitioned,
+    collect_partitioned, common, execute_stream, execute_stream_partitioned,
     ExecutionPlan, SendableRecordBatchStream,
 };
 use crate::prelude::SessionContext;
@@ -60,6 +63,7 @@ use datafusion_expr::{
 };
 
 use async_trait::async_trait;
+use futures::future::try_join_all;
 
 /// Contains options that control how data is
 /// written out from a DataFrame
@@ -138,6 +142,8 @@ impl Default for DataFrameWriteOptions {
 pub struct DataFrame {
     session_state: SessionState,
     plan: LogicalPlan,
+    /// The SQL text this DataFrame was created from, if any
+    sql: Option<String>,
 }
 
 impl DataFrame {
@@ -146,9 +152,17 @@ impl DataFrame {
         Self {
             session_state,
             plan,
+            sql: None,
         }
     }
 
+    /// Set the SQL text this DataFrame was created from, which is recorded
+    /// in the history of the queries of the session
+    pub(crate) fn with_sql(mut self, sql: &str) -> Self {
+        self.sql = Some(sql.to_string());
+        self
+    }
+
     /// Create a physical plan
     pub async fn create_physical_plan(self) -> Result<Arc<dyn ExecutionPlan>> {
         self.session_state.create_physical_plan(&self.plan).await
@@ -754,9 +768,8 @@ impl DataFrame {
     /// # }
     /// ```
     pub async fn collect(self) -> Result<Vec<RecordBatch>> {
-        let task_ctx = Arc::new(self.task_ctx());
-        let plan = self.create_physical_plan().await?;
-        collect(plan, task_ctx).await
+        let stream = self.execute_stream().await?;
+        common::collect(stream).await
     }
 
     /// Print results.
@@ -800,6 +813,28 @@ impl DataFrame {
         TaskContext::from(&self.session_state)
     }
 
+    /// Creates the physical plan of this DataFrame and a [`TaskContext`] to
+    /// execute it, registering the plan in the [`RunningQueries`] of the
+    /// session until the returned guard is dropped
+    ///
+    /// [`RunningQueries`]: crate::execution::running_queries::RunningQueries
+    async fn create_running_query(
+        self,
+    ) -> Result<(Arc<dyn ExecutionPlan>, Arc<TaskContext>, RunningQueryGuard)> {
+        // a pool of its own to record the peak memory usage of the query
+        let memory_pool = Arc::new(self.session_state.query_memory_pool());
+        let task_ctx = Arc::new(
+            self.task_ctx()
+                .with_memory_pool(Arc::clone(&memory_pool) as Arc<dyn MemoryPool>),
+        );
+        let running_queries = Arc::clone(self.session_state.running_queries());
+        let sql = self.sql.clone();
+        let plan = self.create_physical_plan().await?;
+        let guard =
+            running_queries.register(Arc::clone(&plan), sql, &task_ctx, memory_pool);
+        Ok((plan, task_ctx, guard))
+    }
+
     /// Executes this DataFrame and returns a stream over a single partition
     ///
     /// # Example
@@ -820,9 +855,13 @@ impl DataFrame {
     /// Dropping the stream will abort the execution of the query, and free up
     /// any allocated resources
     pub async fn execute_stream(self) -> Result<SendableRecordBatchStream> {
-        let task_ctx = Arc::new(self.task_ctx());
-        let plan = self.create_physical_plan().await?;
-        execute_stream(plan, task_ctx)
+        let (plan, task_ctx, guard) = self.create_running_query().await?;
+        let stream = if task_ctx.session_config().morsel_scheduler() {
+            Scheduler::global().execute_stream(plan, task_ctx)?
+        } else {
+            execute_stream(plan, task_ctx)?
+        };
+        Ok(Box::pin(RunningQueryStream::new(stream, Arc::new(guard))))
     }
 
     /// Executes this DataFrame and collects all results into a vector of vector of RecordBatch
@@ -840,9 +879,24 @@ impl DataFrame {
     /// # }
     /// ```
     pub async fn collect_partitioned(self) -> Result<Vec<Vec<RecordBatch>>> {
-        let task_ctx = Arc::new(self.task_ctx());
-        let plan = self.create_physical_plan().await?;
-        collect_partitioned(plan, task_ctx).await
+        let (plan, task_ctx, guard) = self.create_running_query().await?;
+        let result = if task_ctx.session_config().morsel_scheduler() {
+            let streams =
+                Scheduler::global().execute_stream_partitioned(plan, task_ctx)?;
+            try_join_all(streams.into_iter().map(common::collect)).await
+        } else {
+            collect_partitioned(plan, task_ctx).await
+        };
+        match &result {
+            Ok(partitions) => {
+                guard.add_output_rows(
+                    partitions.iter().flatten().map(|b| b.num_rows()).sum(),
+                );
+                guard.set_completed();
+            }
+            Err(e) => guard.set_error(e),
+        }
+        result
     }
 
     /// Executes this DataFrame and returns one stream per partition.
@@ -866,9 +920,19 @@ impl DataFrame {
     pub async fn execute_stream_partitioned(
         self,
     ) -> Result<Vec<SendableRecordBatchStream>> {
-        let task_ctx = Arc::new(self.task_ctx());
-        let plan = self.create_physical_plan().await?;
-        execute_stream_partitioned(plan, task_ctx)
+        let (plan, task_ctx, guard) = self.create_running_query().await?;
+        let guard = Arc::new(guard);
+        let streams = if task_ctx.session_config().morsel_scheduler() {
+            Scheduler::global().execute_stream_partitioned(plan, task_ctx)?
+        } else {
+            execute_stream_partitioned(plan, task_ctx)?
+        };
+        Ok(streams
+            .into_iter()
+            .map(|stream| -> SendableRecordBatchStream {
+                Box::pin(RunningQueryStream::new(stream, Arc::clone(&guard)))
+            })
+            .collect())
     }
 
     /// Returns the schema describing the output of this DataFrame in terms of columns returned,
@@ -0,0 +1,205 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::arrow::datatypes::{DataType, Field, Fields, Schema, TimeUnit};
+use crate::error::{DataFusionError, Result};
+use apache_avro::Schema as AvroSchema;
+use datafusion_common::not_impl_err;
+use serde_json::{json, Value as JsonValue};
+
+/// The name of the avro record of the rows of a file
+const TOP_LEVEL_RECORD: &str = "topLevelRecord";
+
+/// Converts an arrow schema to the avro schema of a record with a field for
+/// each field of `schema`.
+///
+/// Nullable fields are written as a union of `null` and their type. Names
+/// that are not valid avro names are sanitized with [`avro_name`].
+pub fn to_avro_schema(schema: &Schema) -> Result<AvroSchema> {
+    let record = record_schema(schema.fields(), TOP_LEVEL_RECORD)?;
+    Ok(AvroSchema::parse(&record)?)
+}
+
+/// Returns `name` with the characters that are not allowed in avro names
+/// replaced with `_`, and prefixed with `_` if it starts with a digit
+pub fn avro_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !sanitized.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+/// The avro schema of a record named `name` with `fields`
+fn record_schema(fields: &Fields, name: &str) -> Result<JsonValue> {
+    let fields = fields
+        .iter()
+        .map(|field| {
+            let field_name = avro_name(field.name());
+            let schema = field_schema(field, &format!("{name}.{field_name}"))?;
+            Ok(if is_union_with_null(field) {
+                json!({"name": field_name, "type": schema, "default": null})
+            } else {
+                json!({"name": field_name, "type": schema})
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(json!({"type": "record", "name": name, "fields": fields}))
+}
+
+/// Whether `field` is written as a union of `null` and its type
+pub(super) fn is_union_with_null(field: &Field) -> bool {
+    field.is_nullable() && field.data_type() != &DataType::Null
+}
+
+/// The avro schema of the values of `field`, where `name` is the full name of
+/// the named types (records and fixed) of the field
+fn field_schema(field: &Field, name: &str) -> Result<JsonValue> {
+    let schema = data_type_schema(field.data_type(), name)?;
+    Ok(if is_union_with_null(field) {
+        json!(["null", schema])
+    } else {
+        schema
+    })
+}
+
+fn data_type_schema(data_type: &DataType, name: &str) -> Result<JsonValue> {
+    Ok(match data_type {
+        DataType::Null => json!("null"),
+        DataType::Boolean => json!("boolean"),
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::UInt8
+        | DataType::UInt16 => json!("int"),
+        DataType::Int64 | DataType::UInt32 | DataType::UInt64 => json!("long"),
+        DataType::Float16 | DataType::Float32 => json!("float"),
+        DataType::Float64 => json!("double"),
+        DataType::Utf8 | DataType::LargeUtf8 => json!("string"),
+        DataType::Binary | DataType::LargeBinary => json!("bytes"),
+        DataType::FixedSizeBinary(size) => {
+            json!({"type": "fixed", "name": name, "size": size})
+        }
+        DataType::Decimal128(precision, scale)
+        | DataType::Decimal256(precision, scale) => json!({
+            "type": "bytes",
+            "logicalType": "decimal",
+            "precision": precision,
+            "scale": scale,
+        }),
+        DataType::Date32 | DataType::Date64 => {
+            json!({"type": "int", "logicalType": "date"})
+        }
+        DataType::Time32(_) => json!({"type": "int", "logicalType": "time-millis"}),
+        DataType::Time64(_) => json!({"type": "long", "logicalType": "time-micros"}),
+        DataType::Timestamp(TimeUnit::Second | TimeUnit::Millisecond, _) => {
+            json!({"type": "long", "logicalType": "timestamp-millis"})
+        }
+        DataType::Timestamp(TimeUnit::Microsecond | TimeUnit::Nanosecond, _) => {
+            json!({"type": "long", "logicalType": "timestamp-micros"})
+        }
+        DataType::List(item)
+        | DataType::LargeList(item)
+        | DataType::FixedSizeList(item, _) => {
+            json!({"type": "array", "items": field_schema(item, name)?})
+        }
+        DataType::Map(entries, _) => {
+            let DataType::Struct(fields) = entries.data_type() else {
+                return not_impl_err!("Unsupported map entries type {entries}");
+            };
+            if !matches!(fields[0].data_type(), DataType::Utf8 | DataType::LargeUtf8) {
+                return not_impl_err!(
+                    "Avro maps require string keys, found {}",
+                    fields[0].data_type()
+                );
+            }
+            json!({"type": "map", "values": field_schema(&fields[1], name)?})
+        }
+        DataType::Struct(fields) => record_schema(fields, name)?,
+        DataType::Dictionary(_, value_type) => data_type_schema(value_type, name)?,
+        other => return not_impl_err!("Cannot write {other} values to avro"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn convert_schema() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("price", DataType::Decimal128(10, 2), true),
+            Field::new(
+                "ts",
+                DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+                false,
+            ),
+            Field::new(
+                "tags",
+                DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+                true,
+            ),
+            Field::new(
+                "point",
+                DataType::Struct(Fields::from(vec![
+                    Field::new("x", DataType::Float64, false),
+                    Field::new("y", DataType::Float64, false),
+                ])),
+                true,
+            ),
+            Field::new("COUNT(*)", DataType::Int64, false),
+        ]);
+        let AvroSchema::Record(record) = to_avro_schema(&schema)? else {
+            panic!("expected a record schema");
+        };
+        let names: Vec<_> = record.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["id", "name", "price", "ts", "tags", "point", "COUNT___"]
+        );
+        assert_eq!(record.fields[0].schema, AvroSchema::Long);
+        assert_eq!(record.fields[3].schema, AvroSchema::TimestampMicros);
+        let AvroSchema::Union(union) = &record.fields[1].schema else {
+            panic!("expected a union schema");
+        };
+        assert_eq!(union.variants(), &[AvroSchema::Null, AvroSchema::String]);
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_type() {
+        let schema = Schema::new(vec![Field::new(
+            "i",
+            DataType::Interval(arrow::datatypes::IntervalUnit::DayTime),
+            false,
+        )]);
+        assert!(to_avro_schema(&schema).is_err());
+    }
+
+    #[test]
+    fn sanitize_names() {
+        assert_eq!(avro_name("a_1"), "a_1");
+        assert_eq!(avro_name("1a"), "_1a");
+        assert_eq!(avro_name("t.a b"), "t_a_b");
+    }
+}
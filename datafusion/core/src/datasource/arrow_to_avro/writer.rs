@@ -0,0 +1,427 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use super::schema::{avro_name, is_union_with_null};
+use crate::arrow::array::{Array, ArrayRef, AsArray, GenericListArray, OffsetSizeTrait};
+use crate::arrow::compute::{cast_with_options, CastOptions};
+use crate::arrow::datatypes::{
+    DataType, Date32Type, Decimal128Type, Decimal256Type, Field, Float32Type,
+    Float64Type, Int32Type, Int64Type, Time32MillisecondType, Time64MicrosecondType,
+    TimeUnit, TimestampMicrosecondType, TimestampMillisecondType,
+};
+use crate::arrow::record_batch::RecordBatch;
+use crate::error::{DataFusionError, Result};
+use apache_avro::types::Value;
+use apache_avro::{Codec, Decimal, Schema as AvroSchema};
+use datafusion_common::file_options::avro_writer::AvroCodec;
+use datafusion_common::not_impl_err;
+
+/// Writes [`RecordBatch`]es to an avro file, with a record for each row.
+///
+/// The schema of the file must be the one returned by
+/// [`to_avro_schema`](super::to_avro_schema) for the schema of the batches.
+pub struct Writer<'a, W: Write> {
+    inner: apache_avro::Writer<'a, W>,
+}
+
+impl<'a, W: Write> Writer<'a, W> {
+    /// Create a new writer of avro data of `schema` to `writer`, compressing
+    /// the data blocks with `codec`
+    pub fn new(schema: &'a AvroSchema, writer: W, codec: AvroCodec) -> Self {
+        let codec = match codec {
+            AvroCodec::Null => Codec::Null,
+            AvroCodec::Deflate => Codec::Deflate,
+            AvroCodec::Snappy => Codec::Snappy,
+            AvroCodec::Zstandard => Codec::Zstandard,
+            AvroCodec::Bzip2 => Codec::Bzip2,
+            AvroCodec::Xz => Codec::Xz,
+        };
+        Self {
+            inner: apache_avro::Writer::with_codec(schema, writer, codec),
+        }
+    }
+
+    /// Appends the rows of `batch`, returning the number of bytes written to
+    /// the underlying writer
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<usize> {
+        let columns = batch
+            .schema()
+            .fields()
+            .iter()
+            .zip(batch.columns())
+            .map(|(field, column)| {
+                Ok((avro_name(field.name()), to_values(column, field)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let records = records(batch.num_rows(), None, columns);
+        Ok(self.inner.extend(records)?)
+    }
+
+    /// Flushes the remaining rows and returns the underlying writer
+    pub fn into_inner(self) -> Result<W> {
+        Ok(self.inner.into_inner()?)
+    }
+}
+
+/// Zips `columns` into `num_rows` records, which are null where `nulls` is
+fn records(
+    num_rows: usize,
+    nulls: Option<&dyn Array>,
+    columns: Vec<(String, Vec<Value>)>,
+) -> Vec<Value> {
+    let mut columns: Vec<_> = columns
+        .into_iter()
+        .map(|(name, values)| (name, values.into_iter()))
+        .collect();
+    (0..num_rows)
+        .map(|row| {
+            let fields: Vec<_> = columns
+                .iter_mut()
+                .map(|(name, values)| (name.clone(), values.next().unwrap()))
+                .collect();
+            match nulls {
+                Some(nulls) if nulls.is_null(row) => Value::Null,
+                _ => Value::Record(fields),
+            }
+        })
+        .collect()
+}
+
+/// Casts `array` to the type its values are converted from
+fn normalize(array: &ArrayRef) -> Result<ArrayRef> {
+    let to_type = match array.data_type() {
+        DataType::Int8 | DataType::Int16 | DataType::UInt8 | DataType::UInt16 => {
+            DataType::Int32
+        }
+        DataType::UInt32 | DataType::UInt64 => DataType::Int64,
+        DataType::Float16 => DataType::Float32,
+        DataType::LargeUtf8 => DataType::Utf8,
+        DataType::LargeBinary => DataType::Binary,
+        DataType::Date64 => DataType::Date32,
+        DataType::Time32(TimeUnit::Second) => DataType::Time32(TimeUnit::Millisecond),
+        DataType::Time64(TimeUnit::Nanosecond) => DataType::Time64(TimeUnit::Microsecond),
+        DataType::Timestamp(TimeUnit::Second, tz) => {
+            DataType::Timestamp(TimeUnit::Millisecond, tz.clone())
+        }
+        // avro timestamps have at most microsecond precision
+        DataType::Timestamp(TimeUnit::Nanosecond, tz) => {
+            DataType::Timestamp(TimeUnit::Microsecond, tz.clone())
+        }
+        DataType::Dictionary(_, value_type) => value_type.as_ref().clone(),
+        _ => return Ok(array.clone()),
+    };
+    // values that do not fit the avro type are errors rather than nulls
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    normalize(&cast_with_options(array, &to_type, &options)?)
+}
+
+/// Converts the values of `array` to the avro values of `field`
+fn to_values(array: &ArrayRef, field: &Field) -> Result<Vec<Value>> {
+    let array = normalize(array)?;
+    let values: Vec<Value> = match array.data_type() {
+        DataType::Null => return Ok(vec![Value::Null; array.len()]),
+        DataType::Boolean => array
+            .as_boolean()
+            .iter()
+            .map(|v| v.map_or(Value::Null, Value::Boolean))
+            .collect(),
+        DataType::Int32 => array
+            .as_primitive::<Int32Type>()
+            .iter()
+            .map(|v| v.map_or(Value::Null, Value::Int))
+            .collect(),
+        DataType::Int64 => array
+            .as_primitive::<Int64Type>()
+            .iter()
+            .map(|v| v.map_or(Value::Null, Value::Long))
+            .collect(),
+        DataType::Float32 => array
+            .as_primitive::<Float32Type>()
+            .iter()
+            .map(|v| v.map_or(Value::Null, Value::Float))
+            .collect(),
+        DataType::Float64 => array
+            .as_primitive::<Float64Type>()
+            .iter()
+            .map(|v| v.map_or(Value::Null, Value::Double))
+            .collect(),
+        DataType::Utf8 => array
+            .as_string::<i32>()
+            .iter()
+            .map(|v| v.map_or(Value::Null, |v| Value::String(v.to_string())))
+            .collect(),
+        DataType::Binary => array
+            .as_binary::<i32>()
+            .iter()
+            .map(|v| v.map_or(Value::Null, |v| Value::Bytes(v.to_vec())))
+            .collect(),
+        DataType::FixedSizeBinary(size) => array
+            .as_fixed_size_binary()
+            .iter()
+            .map(|v| v.map_or(Value::Null, |v| Value::Fixed(*size as usize, v.to_vec())))
+            .collect(),
+        // avro decimals are big-endian two's complement integers
+        DataType::Decimal128(_, _) => array
+            .as_primitive::<Decimal128Type>()
+            .iter()
+            .map(|v| {
+                v.map_or(Value::Null, |v| {
+                    Value::Decimal(Decimal::from(v.to_be_bytes().to_vec()))
+                })
+            })
+            .collect(),
+        DataType::Decimal256(_, _) => array
+            .as_primitive::<Decimal256Type>()
+            .iter()
+            .map(|v| {
+                v.map_or(Value::Null, |v| {
+                    Value::Decimal(Decimal::from(v.to_be_bytes().to_vec()))
+                })
+            })
+            .collect(),
+        DataType::Date32 => array
+            .as_primitive::<Date32Type>()
+            .iter()
+            .map(|v| v.map_or(Value::Null, Value::Date))
+            .collect(),
+        DataType::Time32(TimeUnit::Millisecond) => array
+            .as_primitive::<Time32MillisecondType>()
+            .iter()
+            .map(|v| v.map_or(Value::Null, Value::TimeMillis))
+            .collect(),
+        DataType::Time64(TimeUnit::Microsecond) => array
+            .as_primitive::<Time64MicrosecondType>()
+            .iter()
+            .map(|v| v.map_or(Value::Null, Value::TimeMicros))
+            .collect(),
+        DataType::Timestamp(TimeUnit::Millisecond, _) => array
+            .as_primitive::<TimestampMillisecondType>()
+            .iter()
+            .map(|v| v.map_or(Value::Null, Value::TimestampMillis))
+            .collect(),
+        DataType::Timestamp(TimeUnit::Microsecond, _) => array
+            .as_primitive::<TimestampMicrosecondType>()
+            .iter()
+            .map(|v| v.map_or(Value::Null, Value::TimestampMicros))
+            .collect(),
+        DataType::List(item) => list_values(array.as_list::<i32>(), item)?,
+        DataType::LargeList(item) => list_values(array.as_list::<i64>(), item)?,
+        DataType::FixedSizeList(item, size) => {
+            let list = array.as_fixed_size_list();
+            let items = to_values(list.values(), item)?;
+            let size = *size as usize;
+            (0..list.len())
+                .map(|row| match list.is_null(row) {
+                    true => Value::Null,
+                    false => Value::Array(items[row * size..(row + 1) * size].to_vec()),
+                })
+                .collect()
+        }
+        DataType::Map(_, _) => {
+            let map = array.as_map();
+            let keys = normalize(map.keys())?;
+            let keys = keys.as_string::<i32>();
+            let DataType::Struct(fields) = map.entries().data_type() else {
+                unreachable!("map entries are structs")
+            };
+            let values = to_values(map.values(), &fields[1])?;
+            let offsets = map.value_offsets();
+            (0..map.len())
+                .map(|row| {
+                    if map.is_null(row) {
+                        return Value::Null;
+                    }
+                    let (start, end) = (offsets[row] as usize, offsets[row + 1] as usize);
+                    let entries: HashMap<_, _> = (start..end)
+                        .map(|i| (keys.value(i).to_string(), values[i].clone()))
+                        .collect();
+                    Value::Map(entries)
+                })
+                .collect()
+        }
+        DataType::Struct(fields) => {
+            let array = array.as_struct();
+            let columns = fields
+                .iter()
+                .zip(array.columns())
+                .map(|(field, column)| {
+                    Ok((avro_name(field.name()), to_values(column, field)?))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            records(array.len(), Some(array), columns)
+        }
+        other => return not_impl_err!("Cannot write {other} values to avro"),
+    };
+
+    if is_union_with_null(field) {
+        // `null` is the first variant of the union
+        Ok(values
+            .into_iter()
+            .map(|v| match v {
+                Value::Null => Value::Union(0, Box::new(Value::Null)),
+                v => Value::Union(1, Box::new(v)),
+            })
+            .collect())
+    } else {
+        Ok(values)
+    }
+}
+
+fn list_values<O: OffsetSizeTrait>(
+    list: &GenericListArray<O>,
+    item: &Field,
+) -> Result<Vec<Value>> {
+    let items = to_values(list.values(), item)?;
+    let offsets = list.value_offsets();
+    Ok((0..list.len())
+        .map(|row| match list.is_null(row) {
+            true => Value::Null,
+            false => {
+                let (start, end) = (offsets[row].as_usize(), offsets[row + 1].as_usize());
+                Value::Array(items[start..end].to_vec())
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::to_avro_schema;
+    use super::*;
+    use crate::arrow::array::{
+        Decimal128Array, Int32Array, Int64Array, ListArray, StringArray, StructArray,
+        TimestampNanosecondArray, UInt8Array,
+    };
+    use crate::arrow::datatypes::{Fields, Int32Type as I32, Schema};
+    use crate::datasource::avro_to_arrow::ReaderBuilder;
+    use std::sync::Arc;
+
+    fn round_trip(batch: &RecordBatch, codec: AvroCodec) -> Result<RecordBatch> {
+        let schema = to_avro_schema(&batch.schema())?;
+        let mut writer = Writer::new(&schema, vec![], codec);
+        writer.write(batch)?;
+        let bytes = writer.into_inner()?;
+
+        let mut reader = ReaderBuilder::new()
+            .read_schema()
+            .with_batch_size(1024)
+            .build(std::io::Cursor::new(bytes))?;
+        Ok(reader.next().unwrap()?)
+    }
+
+    #[test]
+    fn write_primitives() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("small", DataType::UInt8, true),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+                Arc::new(UInt8Array::from(vec![Some(1), None, Some(255)])),
+                Arc::new(StringArray::from(vec![Some("a"), Some("b"), None])),
+            ],
+        )?;
+
+        for codec in [AvroCodec::Null, AvroCodec::Deflate, AvroCodec::Snappy] {
+            let read = round_trip(&batch, codec)?;
+            assert_eq!(read.num_rows(), 3);
+            assert_eq!(read.column(0).as_ref(), batch.column(0).as_ref());
+            assert_eq!(
+                read.column(1).as_ref(),
+                &Int32Array::from(vec![Some(1), None, Some(255)]) as &dyn Array
+            );
+            assert_eq!(read.column(2).as_ref(), batch.column(2).as_ref());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn write_nested() -> Result<()> {
+        let list = ListArray::from_iter_primitive::<I32, _, _>(vec![
+            Some(vec![Some(1), Some(2)]),
+            None,
+            Some(vec![]),
+        ]);
+        let point = StructArray::from(vec![(
+            Arc::new(Field::new("x", DataType::Int32, false)),
+            Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef,
+        )]);
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("list", list.data_type().clone(), true),
+            Field::new(
+                "point",
+                DataType::Struct(Fields::from(vec![Field::new(
+                    "x",
+                    DataType::Int32,
+                    false,
+                )])),
+                false,
+            ),
+        ]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(list), Arc::new(point)])?;
+
+        let read = round_trip(&batch, AvroCodec::Null)?;
+        assert_eq!(read.num_rows(), 3);
+        let point = read.column(1).as_struct();
+        assert_eq!(
+            point.column(0).as_ref(),
+            &Int32Array::from(vec![1, 2, 3]) as &dyn Array
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn write_logical_types() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("d", DataType::Decimal128(10, 2), false),
+            Field::new("ts", DataType::Timestamp(TimeUnit::Nanosecond, None), false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(
+                    Decimal128Array::from(vec![12345, -1])
+                        .with_precision_and_scale(10, 2)?,
+                ),
+                Arc::new(TimestampNanosecondArray::from(vec![1_000_001_000, 0])),
+            ],
+        )?;
+
+        let read = round_trip(&batch, AvroCodec::Null)?;
+        assert_eq!(read.num_rows(), 2);
+        assert_eq!(
+            read.column(1).data_type(),
+            &DataType::Timestamp(TimeUnit::Microsecond, None)
+        );
+        assert_eq!(
+            read.column(1)
+                .as_primitive::<TimestampMicrosecondType>()
+                .values(),
+            &[1_000_001, 0]
+        );
+        Ok(())
+    }
+}
@@ -18,22 +18,29 @@
 //! [`AvroFormat`] Apache Avro [`FileFormat`] abstractions
 
 use std::any::Any;
+use std::fmt::{self, Debug};
 use std::sync::Arc;
 
 use arrow::datatypes::Schema;
 use arrow::{self, datatypes::SchemaRef};
 use async_trait::async_trait;
-use datafusion_common::FileType;
-use datafusion_physical_expr::PhysicalExpr;
+use datafusion_common::{not_impl_err, FileType};
+use datafusion_execution::{SendableRecordBatchStream, TaskContext};
+use datafusion_physical_expr::{PhysicalExpr, PhysicalSortRequirement};
+use datafusion_physical_plan::insert::{DataSink, FileSinkExec};
+use datafusion_physical_plan::metrics::MetricsSet;
 use object_store::{GetResultPayload, ObjectMeta, ObjectStore};
 
 use super::FileFormat;
 use crate::datasource::avro_to_arrow::read_avro_schema_from_reader;
-use crate::datasource::physical_plan::{AvroExec, FileScanConfig};
-use crate::error::Result;
+use crate::datasource::physical_plan::{
+    AvroExec, FileGroupDisplay, FileScanConfig, FileSinkConfig,
+};
+use crate::error::{DataFusionError, Result};
 use crate::execution::context::SessionState;
 use crate::physical_plan::ExecutionPlan;
 use crate::physical_plan::Statistics;
+use crate::physical_plan::{DisplayAs, DisplayFormatType};
 
 /// Avro `FileFormat` implementation.
 #[derive(Default, Debug)]
@@ -90,11 +97,237 @@ impl FileFormat for AvroFormat {
         Ok(Arc::new(exec))
     }
 
+    async fn create_writer_physical_plan(
+        &self,
+        input: Arc<dyn ExecutionPlan>,
+        _state: &SessionState,
+        conf: FileSinkConfig,
+        order_requirements: Option<Vec<PhysicalSortRequirement>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if conf.overwrite {
+            return not_impl_err!("Overwrites are not implemented yet for Avro format");
+        }
+
+        let sink_schema = conf.output_schema().clone();
+        let sink = Arc::new(AvroSink::new(conf));
+
+        Ok(Arc::new(FileSinkExec::new(
+            input,
+            sink,
+            sink_schema,
+            order_requirements,
+        )) as _)
+    }
+
     fn file_type(&self) -> FileType {
         FileType::AVRO
     }
 }
 
+/// Implements [`DataSink`] for writing to avro files
+pub struct AvroSink {
+    /// Config options for writing data
+    config: FileSinkConfig,
+}
+
+impl AvroSink {
+    /// Create from config.
+    pub fn new(config: FileSinkConfig) -> Self {
+        Self { config }
+    }
+
+    /// Retrieve the inner [`FileSinkConfig`].
+    pub fn config(&self) -> &FileSinkConfig {
+        &self.config
+    }
+
+    /// Converts table schema to writer schema, which may differ in the case
+    /// of hive style partitioning where some columns are removed from the
+    /// underlying files.
+    #[cfg_attr(not(feature = "avro"), allow(dead_code))]
+    fn get_writer_schema(&self) -> Arc<Schema> {
+        if !self.config.table_partition_cols.is_empty() {
+            let schema = self.config.output_schema();
+            let partition_names: Vec<_> = self
+                .config
+                .table_partition_cols
+                .iter()
+                .map(|(s, _)| s)
+                .collect();
+            Arc::new(Schema::new(
+                schema
+                    .fields()
+                    .iter()
+                    .filter(|f| !partition_names.contains(&f.name()))
+                    .map(|f| (**f).clone())
+                    .collect::<Vec<_>>(),
+            ))
+        } else {
+            self.config.output_schema().clone()
+        }
+    }
+}
+
+impl Debug for AvroSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AvroSink").finish()
+    }
+}
+
+impl DisplayAs for AvroSink {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "AvroSink(file_groups=",)?;
+                FileGroupDisplay(&self.config.file_groups).fmt_as(t, f)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DataSink for AvroSink {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        None
+    }
+
+    #[cfg(not(feature = "avro"))]
+    async fn write_all(
+        &self,
+        _data: SendableRecordBatchStream,
+        _context: &Arc<TaskContext>,
+    ) -> Result<u64> {
+        not_impl_err!("Cannot write avro files without avro feature enabled")
+    }
+
+    #[cfg(feature = "avro")]
+    async fn write_all(
+        &self,
+        data: SendableRecordBatchStream,
+        context: &Arc<TaskContext>,
+    ) -> Result<u64> {
+        private::write_all(self, data, context).await
+    }
+}
+
+#[cfg(feature = "avro")]
+mod private {
+    use super::*;
+    use crate::datasource::arrow_to_avro::{to_avro_schema, Writer};
+    use crate::datasource::file_format::file_compression_type::FileCompressionType;
+    use crate::datasource::file_format::write::demux::start_demuxer_task;
+    use crate::datasource::file_format::write::{create_writer, SharedBuffer};
+    use tokio::io::AsyncWriteExt;
+    use tokio::task::JoinSet;
+
+    /// Initial writing buffer size. Note this is just a size hint for efficiency. It
+    /// will grow beyond the set value if needed.
+    const INITIAL_BUFFER_BYTES: usize = 1048576;
+
+    /// If the buffered Avro data exceeds this size, it is flushed to object store
+    const BUFFER_FLUSH_BYTES: usize = 1024000;
+
+    pub(super) async fn write_all(
+        sink: &AvroSink,
+        data: SendableRecordBatchStream,
+        context: &Arc<TaskContext>,
+    ) -> Result<u64> {
+        let codec = sink.config.file_type_writer_options.try_into_avro()?.codec;
+        // fail before writing any file if the schema has no avro equivalent
+        let writer_schema = sink.get_writer_schema();
+        to_avro_schema(&writer_schema)?;
+
+        let object_store = context
+            .runtime_env()
+            .object_store(&sink.config.object_store_url)?;
+
+        let part_col = if !sink.config.table_partition_cols.is_empty() {
+            Some(sink.config.table_partition_cols.clone())
+        } else {
+            None
+        };
+
+        let (demux_task, mut file_stream_rx) = start_demuxer_task(
+            data,
+            context,
+            part_col,
+            sink.config.table_paths[0].clone(),
+            "avro".into(),
+            sink.config.single_file_output,
+        );
+
+        let mut file_write_tasks: JoinSet<std::result::Result<usize, DataFusionError>> =
+            JoinSet::new();
+
+        while let Some((path, mut rx)) = file_stream_rx.recv().await {
+            let shared_buffer = SharedBuffer::new(INITIAL_BUFFER_BYTES);
+            let mut object_store_writer = create_writer(
+                FileCompressionType::UNCOMPRESSED,
+                &path,
+                object_store.clone(),
+            )
+            .await?;
+            let writer_schema = writer_schema.clone();
+            file_write_tasks.spawn(async move {
+                let avro_schema = to_avro_schema(&writer_schema)?;
+                let mut avro_writer =
+                    Writer::new(&avro_schema, shared_buffer.clone(), codec);
+                let mut row_count = 0;
+                while let Some(batch) = rx.recv().await {
+                    row_count += batch.num_rows();
+                    avro_writer.write(&batch)?;
+                    let mut buff_to_flush = shared_buffer.buffer.try_lock().unwrap();
+                    if buff_to_flush.len() > BUFFER_FLUSH_BYTES {
+                        object_store_writer
+                            .write_all(buff_to_flush.as_slice())
+                            .await?;
+                        buff_to_flush.clear();
+                    }
+                }
+                avro_writer.into_inner()?;
+                let final_buff = shared_buffer.buffer.try_lock().unwrap();
+
+                object_store_writer.write_all(final_buff.as_slice()).await?;
+                object_store_writer.shutdown().await?;
+                Ok(row_count)
+            });
+        }
+
+        let mut row_count = 0;
+        while let Some(result) = file_write_tasks.join_next().await {
+            match result {
+                Ok(r) => {
+                    row_count += r?;
+                }
+                Err(e) => {
+                    if e.is_panic() {
+                        std::panic::resume_unwind(e.into_panic());
+                    } else {
+                        unreachable!();
+                    }
+                }
+            }
+        }
+
+        match demux_task.await {
+            Ok(r) => r?,
+            Err(e) => {
+                if e.is_panic() {
+                    std::panic::resume_unwind(e.into_panic());
+                } else {
+                    unreachable!();
+                }
+            }
+        }
+        Ok(row_count as u64)
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "avro")]
 mod tests {
@@ -27,7 +27,7 @@ use super::{FileFormat, DEFAULT_SCHEMA_INFER_MAX_RECORD};
 use crate::datasource::file_format::file_compression_type::FileCompressionType;
 use crate::datasource::file_format::write::BatchSerializer;
 use crate::datasource::physical_plan::{
-    CsvExec, FileGroupDisplay, FileScanConfig, FileSinkConfig,
+    CsvBadRowPolicy, CsvExec, FileGroupDisplay, FileScanConfig, FileSinkConfig,
 };
 use crate::error::Result;
 use crate::execution::context::SessionState;
@@ -37,7 +37,9 @@ use crate::physical_plan::{ExecutionPlan, SendableRecordBatchStream};
 
 use arrow::array::RecordBatch;
 use arrow::csv::WriterBuilder;
-use arrow::datatypes::{DataType, Field, Fields, Schema};
+use arrow::datatypes::{
+    DataType, Field, Fields, Schema, TimeUnit, DECIMAL128_MAX_PRECISION,
+};
 use arrow::{self, datatypes::SchemaRef};
 use datafusion_common::{exec_err, not_impl_err, DataFusionError, FileType};
 use datafusion_execution::TaskContext;
@@ -59,6 +61,9 @@ pub struct CsvFormat {
     escape: Option<u8>,
     schema_infer_max_rec: Option<usize>,
     file_compression_type: FileCompressionType,
+    bad_rows: CsvBadRowPolicy,
+    newlines_in_values: bool,
+    infer_decimals: bool,
 }
 
 impl Default for CsvFormat {
@@ -70,6 +75,9 @@ impl Default for CsvFormat {
             quote: b'"',
             escape: None,
             file_compression_type: FileCompressionType::UNCOMPRESSED,
+            bad_rows: CsvBadRowPolicy::default(),
+            newlines_in_values: false,
+            infer_decimals: false,
         }
     }
 }
@@ -181,6 +189,40 @@ impl CsvFormat {
         self
     }
 
+    /// Set the policy for the rows that can not be read as rows of the
+    /// schema, see [`CsvBadRowPolicy`]
+    /// - defaults to `CsvBadRowPolicy::Fail`
+    pub fn with_bad_rows(mut self, bad_rows: CsvBadRowPolicy) -> Self {
+        self.bad_rows = bad_rows;
+        self
+    }
+
+    /// Set true to indicate that quoted values may contain newlines, which
+    /// disables reading the ranges of files in parallel
+    /// - defaults to false
+    pub fn with_newlines_in_values(mut self, newlines_in_values: bool) -> Self {
+        self.newlines_in_values = newlines_in_values;
+        self
+    }
+
+    /// Set true to infer `Decimal128` rather than `Float64` for columns whose
+    /// values are all decimal numbers that fit in 38 digits
+    /// - defaults to false
+    pub fn with_infer_decimals(mut self, infer_decimals: bool) -> Self {
+        self.infer_decimals = infer_decimals;
+        self
+    }
+
+    /// The policy for the rows that can not be read.
+    pub fn bad_rows(&self) -> &CsvBadRowPolicy {
+        &self.bad_rows
+    }
+
+    /// True if quoted values may contain newlines.
+    pub fn newlines_in_values(&self) -> bool {
+        self.newlines_in_values
+    }
+
     /// The delimiter character.
     pub fn delimiter(&self) -> u8 {
         self.delimiter
@@ -252,7 +294,9 @@ impl FileFormat for CsvFormat {
             self.quote,
             self.escape,
             self.file_compression_type.to_owned(),
-        );
+        )
+        .with_bad_rows(self.bad_rows.clone())
+        .with_newlines_in_values(self.newlines_in_values);
         Ok(Arc::new(exec))
     }
 
@@ -299,6 +343,7 @@ impl CsvFormat {
         let mut total_records_read = 0;
         let mut column_names = vec![];
         let mut column_type_possibilities = vec![];
+        let mut column_shapes = vec![];
         let mut first_chunk = true;
 
         pin_mut!(stream);
@@ -309,7 +354,7 @@ impl CsvFormat {
                 .with_delimiter(self.delimiter);
 
             let (Schema { fields, .. }, records_read) =
-                format.infer_schema(chunk.reader(), Some(records_to_read))?;
+                format.infer_schema(chunk.clone().reader(), Some(records_to_read))?;
 
             records_to_read -= records_read;
             total_records_read += records_read;
@@ -327,6 +372,14 @@ impl CsvFormat {
                         (field.name().clone(), possibilities)
                     })
                     .unzip();
+                column_shapes = vec![ValueShape::default(); fields.len()];
+                self.scan_values(
+                    &chunk,
+                    true,
+                    records_read,
+                    &fields,
+                    &mut column_shapes,
+                )?;
                 first_chunk = false;
             } else {
                 if fields.len() != column_type_possibilities.len() {
@@ -343,6 +396,13 @@ impl CsvFormat {
                         possibilities.insert(field.data_type().clone());
                     },
                 );
+                self.scan_values(
+                    &chunk,
+                    false,
+                    records_read,
+                    &fields,
+                    &mut column_shapes,
+                )?;
             }
 
             if records_to_read == 0 {
@@ -351,8 +411,148 @@ impl CsvFormat {
         }
 
         let schema = build_schema_helper(column_names, &column_type_possibilities);
-        Ok((schema, total_records_read))
+        let fields = schema
+            .fields()
+            .iter()
+            .zip(&column_shapes)
+            .map(|(field, shape)| {
+                let data_type = shape.refine(field.data_type(), self.infer_decimals);
+                Field::new(field.name(), data_type, true)
+            })
+            .collect::<Fields>();
+        Ok((Schema::new(fields), total_records_read))
     }
+
+    /// Scan the values of the `records_read` records of `chunk` whose type
+    /// arrow inferred as one that [`ValueShape::refine`] may refine
+    fn scan_values(
+        &self,
+        chunk: &Bytes,
+        first_chunk: bool,
+        records_read: usize,
+        fields: &Fields,
+        shapes: &mut [ValueShape],
+    ) -> Result<()> {
+        let mut scanned = vec![];
+        for (idx, (field, shape)) in fields.iter().zip(shapes.iter_mut()).enumerate() {
+            match field.data_type() {
+                DataType::Null => {}
+                DataType::Utf8 | DataType::Timestamp(_, None) => scanned.push(idx),
+                DataType::Int64 | DataType::Float64 if self.infer_decimals => {
+                    scanned.push(idx)
+                }
+                _ => shape.unscanned = true,
+            }
+        }
+        if scanned.is_empty() || records_read == 0 {
+            return Ok(());
+        }
+
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(self.has_header && first_chunk)
+            .flexible(true)
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .escape(self.escape)
+            .from_reader(chunk.as_ref());
+        for record in reader.byte_records().take(records_read) {
+            let record = record.map_err(|e| DataFusionError::External(Box::new(e)))?;
+            for idx in &scanned {
+                match record.get(*idx).map(std::str::from_utf8) {
+                    None | Some(Ok("")) => {}
+                    Some(Ok(value)) => shapes[*idx].add(value),
+                    Some(Err(_)) => shapes[*idx].unscanned = true,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What the values of a column read to infer the schema have in common, to
+/// refine the type that arrow infers for them
+#[derive(Debug, Clone, Default)]
+struct ValueShape {
+    /// Whether some values of the column were not scanned
+    unscanned: bool,
+    /// The number of values scanned
+    values: usize,
+    /// The number of timestamps with a time zone
+    timestamps_tz: usize,
+    /// The number of decimal numbers
+    decimals: usize,
+    /// The most digits before the point of the decimal numbers
+    integer_digits: usize,
+    /// The most digits after the point of the decimal numbers
+    scale: usize,
+}
+
+impl ValueShape {
+    fn add(&mut self, value: &str) {
+        self.values += 1;
+        if is_timestamp_with_tz(value) {
+            self.timestamps_tz += 1;
+        }
+        if let Some((integer_digits, scale)) = decimal_digits(value) {
+            self.decimals += 1;
+            self.integer_digits = self.integer_digits.max(integer_digits);
+            self.scale = self.scale.max(scale);
+        }
+    }
+
+    /// Refine `data_type`, the type inferred for the values:
+    /// - strings or timestamps that are all timestamps with a time zone are UTC
+    ///   timestamps
+    /// - floats that are all decimal numbers are decimals, if `infer_decimals`
+    fn refine(&self, data_type: &DataType, infer_decimals: bool) -> DataType {
+        if self.unscanned || self.values == 0 {
+            return data_type.clone();
+        }
+        match data_type {
+            DataType::Utf8 | DataType::Timestamp(_, None)
+                if self.timestamps_tz == self.values =>
+            {
+                DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into()))
+            }
+            DataType::Float64 if infer_decimals && self.decimals == self.values => {
+                let precision = (self.integer_digits + self.scale).max(1);
+                if precision <= DECIMAL128_MAX_PRECISION as usize {
+                    DataType::Decimal128(precision as u8, self.scale as i8)
+                } else {
+                    data_type.clone()
+                }
+            }
+            _ => data_type.clone(),
+        }
+    }
+}
+
+/// Whether `value` is a timestamp with a time zone offset or `Z`, such as
+/// `2023-01-31T10:00:00Z`, `2023-01-31 10:00:00.5+01:00` or
+/// `2023-01-31T10:00:00-0500`
+fn is_timestamp_with_tz(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    if bytes.len() < 20 || !matches!(bytes[10], b'T' | b' ') {
+        return false;
+    }
+    let value = value.replacen(' ', "T", 1);
+    chrono::DateTime::parse_from_rfc3339(&value).is_ok()
+        || chrono::DateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M:%S%.f%z").is_ok()
+}
+
+/// The number of digits before and after the point of `value`, if it is a
+/// plain decimal number such as `-12.345`, without an exponent
+fn decimal_digits(value: &str) -> Option<(usize, usize)> {
+    let value = value.strip_prefix(['-', '+']).unwrap_or(value);
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if integer.len() + fraction.len() == 0
+        || !all_digits(integer)
+        || !all_digits(fraction)
+    {
+        return None;
+    }
+    Some((integer.trim_start_matches('0').len(), fraction.len()))
 }
 
 fn build_schema_helper(names: Vec<String>, types: &[HashSet<DataType>]) -> Schema {
@@ -369,24 +569,49 @@ fn build_schema_helper(names: Vec<String>, types: &[HashSet<DataType>]) -> Schem
                     data_type_possibilities.iter().next().unwrap().clone(),
                     true,
                 ),
-                2 => {
-                    if data_type_possibilities.contains(&DataType::Int64)
-                        && data_type_possibilities.contains(&DataType::Float64)
-                    {
-                        // we have an integer and double, fall down to double
-                        Field::new(field_name, DataType::Float64, true)
-                    } else {
-                        // default to Utf8 for conflicting datatypes (e.g bool and int)
-                        Field::new(field_name, DataType::Utf8, true)
-                    }
-                }
-                _ => Field::new(field_name, DataType::Utf8, true),
+                _ => Field::new(field_name, merge_types(data_type_possibilities), true),
             }
         })
         .collect::<Fields>();
     Schema::new(fields)
 }
 
+/// The type of a column whose chunks were inferred as different `types`
+fn merge_types(types: &HashSet<DataType>) -> DataType {
+    // the chunks with only empty values do not constrain the type
+    let types: Vec<_> = types.iter().filter(|t| **t != DataType::Null).collect();
+    match types.as_slice() {
+        [] => DataType::Utf8,
+        [data_type] => (*data_type).clone(),
+        // we have an integer and double, fall down to double
+        [DataType::Int64, DataType::Float64] | [DataType::Float64, DataType::Int64] => {
+            DataType::Float64
+        }
+        // dates and timestamps, fall down to the finest timestamps
+        _ if types
+            .iter()
+            .all(|t| matches!(t, DataType::Date32 | DataType::Timestamp(_, None))) =>
+        {
+            let unit = types
+                .iter()
+                .filter_map(|t| match t {
+                    DataType::Timestamp(unit, None) => Some(unit.clone()),
+                    _ => None,
+                })
+                .max_by_key(|unit| match unit {
+                    TimeUnit::Second => 0,
+                    TimeUnit::Millisecond => 1,
+                    TimeUnit::Microsecond => 2,
+                    TimeUnit::Nanosecond => 3,
+                })
+                .unwrap_or(TimeUnit::Second);
+            DataType::Timestamp(unit, None)
+        }
+        // default to Utf8 for conflicting datatypes (e.g bool and int)
+        _ => DataType::Utf8,
+    }
+}
+
 impl Default for CsvSerializer {
     fn default() -> Self {
         Self::new()
@@ -707,6 +932,46 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn infer_schema_richer_types() -> Result<()> {
+        let chunks = vec![
+            Ok(Bytes::from(
+                "a,b,c,d\n1.5,2023-01-31T10:00:00Z,2023-01-31,\n",
+            )),
+            Ok(Bytes::from(
+                "-12.25,2023-01-31 11:00:00+01:00,2023-02-01T10:00:00,5\n",
+            )),
+        ];
+        let csv_format = CsvFormat::default().with_infer_decimals(true);
+        let (schema, records_read) = csv_format
+            .infer_schema_from_stream(usize::MAX, futures::stream::iter(chunks))
+            .await?;
+        assert_eq!(records_read, 2);
+
+        let actual_fields: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|f| format!("{}: {:?}", f.name(), f.data_type()))
+            .collect();
+        assert_eq!(
+            vec![
+                "a: Decimal128(4, 2)",
+                "b: Timestamp(Nanosecond, Some(\"UTC\"))",
+                "c: Timestamp(Second, None)",
+                "d: Int64",
+            ],
+            actual_fields
+        );
+
+        // without infer_decimals, the decimals are floats
+        let chunks = vec![Ok(Bytes::from("a\n1.5\n-12.25\n"))];
+        let (schema, _) = CsvFormat::default()
+            .infer_schema_from_stream(usize::MAX, futures::stream::iter(chunks))
+            .await?;
+        assert_eq!(schema.field(0).data_type(), &DataType::Float64);
+        Ok(())
+    }
+
     #[rstest(
         file_compression_type,
         case(FileCompressionType::UNCOMPRESSED),
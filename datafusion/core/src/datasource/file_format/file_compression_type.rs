@@ -250,6 +250,13 @@ impl FileTypeExt for FileType {
                     "FileCompressionType can be specified for CSV/JSON FileType.".into(),
                 )),
             },
+            #[cfg(feature = "orc")]
+            FileType::ORC => match c.variant {
+                UNCOMPRESSED => Ok(ext),
+                _ => Err(DataFusionError::Internal(
+                    "FileCompressionType can be specified for CSV/JSON FileType.".into(),
+                )),
+            },
         }
     }
 }
@@ -285,6 +292,8 @@ mod tests {
 
         let mut ty_ext_tuple = vec![];
         ty_ext_tuple.push((FileType::AVRO, ".avro"));
+        #[cfg(feature = "orc")]
+        ty_ext_tuple.push((FileType::ORC, ".orc"));
         #[cfg(feature = "parquet")]
         ty_ext_tuple.push((FileType::PARQUET, ".parquet"));
 
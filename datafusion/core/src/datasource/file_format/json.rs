@@ -15,12 +15,13 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! [`JsonFormat`]: Line delimited JSON [`FileFormat`] abstractions
+//! [`JsonFormat`]: Line delimited JSON and JSON array [`FileFormat`] abstractions
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Debug;
-use std::io::BufReader;
+use std::io::{BufReader, Read};
 use std::sync::Arc;
 
 use super::write::orchestration::stateless_multipart_put;
@@ -29,7 +30,9 @@ use crate::datasource::file_format::file_compression_type::FileCompressionType;
 use crate::datasource::file_format::write::BatchSerializer;
 use crate::datasource::file_format::DEFAULT_SCHEMA_INFER_MAX_RECORD;
 use crate::datasource::physical_plan::FileGroupDisplay;
-use crate::datasource::physical_plan::{FileSinkConfig, NdJsonExec};
+use crate::datasource::physical_plan::{
+    FileSinkConfig, JsonArrayReader, NdJsonExec, JSON_RAW_VALUES_METADATA_KEY,
+};
 use crate::error::Result;
 use crate::execution::context::SessionState;
 use crate::physical_plan::insert::{DataSink, FileSinkExec};
@@ -37,12 +40,10 @@ use crate::physical_plan::{
     DisplayAs, DisplayFormatType, SendableRecordBatchStream, Statistics,
 };
 
-use arrow::datatypes::Schema;
-use arrow::datatypes::SchemaRef;
+use arrow::datatypes::{DataType, Field, Fields, Schema, SchemaRef};
 use arrow::json;
-use arrow::json::reader::{infer_json_schema_from_iterator, ValueIter};
 use arrow_array::RecordBatch;
-use datafusion_common::{not_impl_err, DataFusionError, FileType};
+use datafusion_common::{exec_err, not_impl_err, plan_err, DataFusionError, FileType};
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::{PhysicalExpr, PhysicalSortRequirement};
 use datafusion_physical_plan::metrics::MetricsSet;
@@ -51,12 +52,18 @@ use datafusion_physical_plan::ExecutionPlan;
 use async_trait::async_trait;
 use bytes::{Buf, Bytes};
 use object_store::{GetResultPayload, ObjectMeta, ObjectStore};
+use serde_json::Value;
 
 /// New line delimited JSON `FileFormat` implementation.
+///
+/// Files can also be a single JSON array of records with
+/// [`JsonFormat::with_array`].
 #[derive(Debug)]
 pub struct JsonFormat {
     schema_infer_max_rec: Option<usize>,
     file_compression_type: FileCompressionType,
+    array: bool,
+    unknown_fields_column: Option<String>,
 }
 
 impl Default for JsonFormat {
@@ -64,6 +71,8 @@ impl Default for JsonFormat {
         Self {
             schema_infer_max_rec: Some(DEFAULT_SCHEMA_INFER_MAX_RECORD),
             file_compression_type: FileCompressionType::UNCOMPRESSED,
+            array: false,
+            unknown_fields_column: None,
         }
     }
 }
@@ -85,6 +94,36 @@ impl JsonFormat {
         self.file_compression_type = file_compression_type;
         self
     }
+
+    /// Set true to indicate that each file is a single JSON array of records
+    /// rather than newline delimited records
+    /// - defaults to false
+    pub fn with_array(mut self, array: bool) -> Self {
+        self.array = array;
+        self
+    }
+
+    /// Set a `Utf8` column to collect the fields of the records that are not
+    /// in the schema into, as a JSON object. Inferred schemas end with this
+    /// column.
+    /// - defaults to None, the unknown fields are ignored
+    pub fn with_unknown_fields_column(
+        mut self,
+        unknown_fields_column: Option<String>,
+    ) -> Self {
+        self.unknown_fields_column = unknown_fields_column;
+        self
+    }
+
+    /// True if each file is a single JSON array of records.
+    pub fn array(&self) -> bool {
+        self.array
+    }
+
+    /// The column of the fields of the records that are not in the schema.
+    pub fn unknown_fields_column(&self) -> Option<&str> {
+        self.unknown_fields_column.as_deref()
+    }
 }
 
 #[async_trait]
@@ -99,43 +138,62 @@ impl FileFormat for JsonFormat {
         store: &Arc<dyn ObjectStore>,
         objects: &[ObjectMeta],
     ) -> Result<SchemaRef> {
-        let mut schemas = Vec::new();
+        // the records of all the files are inferred together, to merge their
+        // nested objects and widen their conflicting types
+        let mut inferred = InferredType::Object(vec![]);
         let mut records_to_read = self.schema_infer_max_rec.unwrap_or(usize::MAX);
         let file_compression_type = self.file_compression_type.to_owned();
         for object in objects {
-            let mut take_while = || {
-                let should_take = records_to_read > 0;
-                if should_take {
-                    records_to_read -= 1;
-                }
-                should_take
-            };
-
             let r = store.as_ref().get(&object.location).await?;
-            let schema = match r.payload {
+            let decoder = match r.payload {
                 GetResultPayload::File(file, _) => {
-                    let decoder = file_compression_type.convert_read(file)?;
-                    let mut reader = BufReader::new(decoder);
-                    let iter = ValueIter::new(&mut reader, None);
-                    infer_json_schema_from_iterator(iter.take_while(|_| take_while()))?
+                    file_compression_type.convert_read(file)?
                 }
                 GetResultPayload::Stream(_) => {
                     let data = r.bytes().await?;
-                    let decoder = file_compression_type.convert_read(data.reader())?;
-                    let mut reader = BufReader::new(decoder);
-                    let iter = ValueIter::new(&mut reader, None);
-                    infer_json_schema_from_iterator(iter.take_while(|_| take_while()))?
+                    file_compression_type.convert_read(data.reader())?
                 }
             };
+            let reader: Box<dyn Read + Send> = if self.array {
+                Box::new(JsonArrayReader::new(decoder))
+            } else {
+                decoder
+            };
+            let records = serde_json::Deserializer::from_reader(BufReader::new(reader))
+                .into_iter::<Value>();
+            for record in records.take(records_to_read) {
+                let record =
+                    record.map_err(|e| DataFusionError::External(Box::new(e)))?;
+                if !record.is_object() {
+                    return exec_err!(
+                        "Expected JSON records to be objects, found {record}"
+                    );
+                }
+                inferred = inferred.merge(InferredType::of(&record));
+                records_to_read -= 1;
+            }
 
-            schemas.push(schema);
             if records_to_read == 0 {
                 break;
             }
         }
 
-        let schema = Schema::try_merge(schemas)?;
-        Ok(Arc::new(schema))
+        let InferredType::Object(fields) = inferred else {
+            unreachable!("the records are objects")
+        };
+        let mut fields: Vec<_> = fields
+            .into_iter()
+            .map(|(name, inferred)| inferred.into_field(name))
+            .collect();
+        if let Some(column) = &self.unknown_fields_column {
+            if fields.iter().any(|f| f.name() == column) {
+                return plan_err!(
+                    "The unknown fields column {column} is a field of the JSON records"
+                );
+            }
+            fields.push(Field::new(column, DataType::Utf8, true));
+        }
+        Ok(Arc::new(Schema::new(fields)))
     }
 
     async fn infer_stats(
@@ -154,7 +212,9 @@ impl FileFormat for JsonFormat {
         conf: FileScanConfig,
         _filters: Option<&Arc<dyn PhysicalExpr>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        let exec = NdJsonExec::new(conf, self.file_compression_type.to_owned());
+        let exec = NdJsonExec::new(conf, self.file_compression_type.to_owned())
+            .with_array(self.array)
+            .with_unknown_fields_column(self.unknown_fields_column.clone());
         Ok(Arc::new(exec))
     }
 
@@ -188,6 +248,101 @@ impl FileFormat for JsonFormat {
     }
 }
 
+/// The type of the values of a field of the JSON records read to infer the
+/// schema
+#[derive(Debug, Clone, PartialEq)]
+enum InferredType {
+    /// Only nulls
+    Null,
+    /// `Boolean`, `Int64`, `Float64` or `Utf8` values
+    Scalar(DataType),
+    List(Box<InferredType>),
+    /// The types of the fields of objects, in the order they were found
+    Object(Vec<(String, InferredType)>),
+    /// Values of conflicting types, such as objects and numbers, read as JSON
+    /// text
+    Raw,
+}
+
+impl InferredType {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(_) => Self::Scalar(DataType::Boolean),
+            Value::Number(n) if n.is_i64() => Self::Scalar(DataType::Int64),
+            Value::Number(_) => Self::Scalar(DataType::Float64),
+            Value::String(_) => Self::Scalar(DataType::Utf8),
+            Value::Array(values) => Self::List(Box::new(
+                values
+                    .iter()
+                    .map(Self::of)
+                    .fold(Self::Null, |item, value| item.merge(value)),
+            )),
+            Value::Object(fields) => Self::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), Self::of(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// The type of the values of both types, widening conflicting types
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Null, other) | (other, Self::Null) => other,
+            (Self::Scalar(a), Self::Scalar(b)) => Self::Scalar(match (a, b) {
+                (a, b) if a == b => a,
+                (DataType::Int64, DataType::Float64)
+                | (DataType::Float64, DataType::Int64) => DataType::Float64,
+                _ => DataType::Utf8,
+            }),
+            (Self::List(a), Self::List(b)) => Self::List(Box::new(a.merge(*b))),
+            (Self::Object(mut fields), Self::Object(other_fields)) => {
+                for (name, other) in other_fields {
+                    match fields.iter_mut().find(|(n, _)| *n == name) {
+                        Some((_, inferred)) => {
+                            *inferred =
+                                std::mem::replace(inferred, Self::Null).merge(other)
+                        }
+                        None => fields.push((name, other)),
+                    }
+                }
+                Self::Object(fields)
+            }
+            _ => Self::Raw,
+        }
+    }
+
+    fn into_field(self, name: String) -> Field {
+        let field = Field::new(name, self.data_type(), true);
+        match self {
+            Self::Raw => field.with_metadata(HashMap::from([(
+                JSON_RAW_VALUES_METADATA_KEY.to_string(),
+                "true".to_string(),
+            )])),
+            _ => field,
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            Self::Null => DataType::Null,
+            Self::Scalar(data_type) => data_type.clone(),
+            Self::List(item) => {
+                DataType::List(Arc::new(item.as_ref().clone().into_field("item".into())))
+            }
+            Self::Object(fields) => DataType::Struct(
+                fields
+                    .iter()
+                    .map(|(name, inferred)| inferred.clone().into_field(name.clone()))
+                    .collect::<Fields>(),
+            ),
+            Self::Raw => DataType::Utf8,
+        }
+    }
+}
+
 impl Default for JsonSerializer {
     fn default() -> Self {
         Self::new()
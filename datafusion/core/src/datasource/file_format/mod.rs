@@ -27,6 +27,8 @@ pub mod csv;
 pub mod file_compression_type;
 pub mod json;
 pub mod options;
+#[cfg(feature = "orc")]
+pub mod orc;
 #[cfg(feature = "parquet")]
 pub mod parquet;
 pub mod write;
@@ -165,6 +167,9 @@ pub(crate) mod test_util {
                     limit,
                     table_partition_cols: vec![],
                     output_ordering: vec![],
+                    schema_adapter_factory: None,
+                    metadata_cols: vec![],
+                    projection_paths: vec![],
                 },
                 None,
             )
@@ -28,6 +28,7 @@ use crate::datasource::file_format::file_compression_type::FileCompressionType;
 use crate::datasource::file_format::parquet::ParquetFormat;
 use crate::datasource::file_format::DEFAULT_SCHEMA_INFER_MAX_RECORD;
 use crate::datasource::listing::ListingTableUrl;
+use crate::datasource::physical_plan::CsvBadRowPolicy;
 use crate::datasource::{
     file_format::{avro::AvroFormat, csv::CsvFormat, json::JsonFormat},
     listing::ListingOptions,
@@ -73,6 +74,13 @@ pub struct CsvReadOptions<'a> {
     pub file_compression_type: FileCompressionType,
     /// Indicates how the file is sorted
     pub file_sort_order: Vec<Vec<Expr>>,
+    /// What to do with the rows that can not be read. Defaults to
+    /// `CsvBadRowPolicy::Fail`.
+    pub bad_rows: CsvBadRowPolicy,
+    /// Whether quoted values may contain newlines. Defaults to false.
+    pub newlines_in_values: bool,
+    /// Whether to infer decimals rather than floats. Defaults to false.
+    pub infer_decimals: bool,
 }
 
 impl<'a> Default for CsvReadOptions<'a> {
@@ -95,6 +103,9 @@ impl<'a> CsvReadOptions<'a> {
             table_partition_cols: vec![],
             file_compression_type: FileCompressionType::UNCOMPRESSED,
             file_sort_order: vec![],
+            bad_rows: CsvBadRowPolicy::default(),
+            newlines_in_values: false,
+            infer_decimals: false,
         }
     }
 
@@ -171,6 +182,24 @@ impl<'a> CsvReadOptions<'a> {
         self.file_sort_order = file_sort_order;
         self
     }
+
+    /// Configure what to do with the rows that can not be read
+    pub fn bad_rows(mut self, bad_rows: CsvBadRowPolicy) -> Self {
+        self.bad_rows = bad_rows;
+        self
+    }
+
+    /// Configure whether quoted values may contain newlines
+    pub fn newlines_in_values(mut self, newlines_in_values: bool) -> Self {
+        self.newlines_in_values = newlines_in_values;
+        self
+    }
+
+    /// Configure whether to infer decimals rather than floats
+    pub fn infer_decimals(mut self, infer_decimals: bool) -> Self {
+        self.infer_decimals = infer_decimals;
+        self
+    }
 }
 
 /// Options that control the reading of Parquet files.
@@ -366,6 +395,11 @@ pub struct NdJsonReadOptions<'a> {
     pub infinite: bool,
     /// Indicates how the file is sorted
     pub file_sort_order: Vec<Vec<Expr>>,
+    /// Whether each file is a single JSON array of records. Defaults to false.
+    pub array: bool,
+    /// A column to collect the fields of the records that are not in the
+    /// schema into, as a JSON object. Defaults to None.
+    pub unknown_fields_column: Option<String>,
 }
 
 impl<'a> Default for NdJsonReadOptions<'a> {
@@ -378,6 +412,8 @@ impl<'a> Default for NdJsonReadOptions<'a> {
             file_compression_type: FileCompressionType::UNCOMPRESSED,
             infinite: false,
             file_sort_order: vec![],
+            array: false,
+            unknown_fields_column: None,
         }
     }
 }
@@ -424,6 +460,18 @@ impl<'a> NdJsonReadOptions<'a> {
         self.file_sort_order = file_sort_order;
         self
     }
+
+    /// Configure whether each file is a single JSON array of records
+    pub fn array(mut self, array: bool) -> Self {
+        self.array = array;
+        self
+    }
+
+    /// Specify the column to collect the unknown fields of the records into
+    pub fn unknown_fields_column(mut self, column: impl Into<String>) -> Self {
+        self.unknown_fields_column = Some(column.into());
+        self
+    }
 }
 
 #[async_trait]
@@ -470,7 +518,10 @@ impl ReadOptions<'_> for CsvReadOptions<'_> {
             .with_quote(self.quote)
             .with_escape(self.escape)
             .with_schema_infer_max_rec(Some(self.schema_infer_max_records))
-            .with_file_compression_type(self.file_compression_type.to_owned());
+            .with_file_compression_type(self.file_compression_type.to_owned())
+            .with_bad_rows(self.bad_rows.clone())
+            .with_newlines_in_values(self.newlines_in_values)
+            .with_infer_decimals(self.infer_decimals);
 
         ListingOptions::new(Arc::new(file_format))
             .with_file_extension(self.file_extension)
@@ -521,7 +572,9 @@ impl ReadOptions<'_> for NdJsonReadOptions<'_> {
     fn to_listing_options(&self, config: &SessionConfig) -> ListingOptions {
         let file_format = JsonFormat::default()
             .with_schema_infer_max_rec(Some(self.schema_infer_max_records))
-            .with_file_compression_type(self.file_compression_type.to_owned());
+            .with_file_compression_type(self.file_compression_type.to_owned())
+            .with_array(self.array)
+            .with_unknown_fields_column(self.unknown_fields_column.clone());
 
         ListingOptions::new(Arc::new(file_format))
             .with_file_extension(self.file_extension)
@@ -0,0 +1,361 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`OrcFormat`] Apache ORC [`FileFormat`] abstractions
+
+use std::any::Any;
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+use arrow::datatypes::{Schema, SchemaRef};
+use async_trait::async_trait;
+use datafusion_common::stats::Precision;
+use datafusion_common::{not_impl_err, ColumnStatistics, FileType};
+use datafusion_execution::{SendableRecordBatchStream, TaskContext};
+use datafusion_physical_expr::{PhysicalExpr, PhysicalSortRequirement};
+use datafusion_physical_plan::insert::{DataSink, FileSinkExec};
+use datafusion_physical_plan::metrics::MetricsSet;
+use object_store::{ObjectMeta, ObjectStore};
+use orc_rust::reader::metadata::FileMetadata;
+use orc_rust::{ArrowReaderBuilder, ArrowWriterBuilder};
+use tokio::io::AsyncWriteExt;
+use tokio::task::JoinSet;
+
+use super::file_compression_type::FileCompressionType;
+use super::write::demux::start_demuxer_task;
+use super::write::{create_writer, SharedBuffer};
+use super::FileFormat;
+use crate::datasource::physical_plan::{
+    orc_statistics, FileGroupDisplay, FileScanConfig, FileSinkConfig, ObjectStoreReader,
+    OrcExec,
+};
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::SessionState;
+use crate::physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan, Statistics};
+
+/// Initial writing buffer size. Note this is just a size hint for efficiency. It
+/// will grow beyond the set value if needed.
+const INITIAL_BUFFER_BYTES: usize = 1048576;
+
+/// If the buffered ORC data exceeds this size, it is flushed to object store
+const BUFFER_FLUSH_BYTES: usize = 1024000;
+
+/// Apache ORC `FileFormat` implementation.
+///
+/// Filters are used to skip the stripes of the files whose column statistics
+/// rule them out. Reading and writing ORC files requires the `orc` feature.
+#[derive(Default, Debug)]
+pub struct OrcFormat;
+
+#[async_trait]
+impl FileFormat for OrcFormat {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn infer_schema(
+        &self,
+        _state: &SessionState,
+        store: &Arc<dyn ObjectStore>,
+        objects: &[ObjectMeta],
+    ) -> Result<SchemaRef> {
+        let mut schemas = vec![];
+        for object in objects {
+            let builder = reader_builder(store, object).await?;
+            schemas.push(builder.schema().as_ref().clone());
+        }
+        let merged_schema = arrow::datatypes::Schema::try_merge(schemas)?;
+        Ok(Arc::new(merged_schema))
+    }
+
+    async fn infer_stats(
+        &self,
+        _state: &SessionState,
+        store: &Arc<dyn ObjectStore>,
+        table_schema: SchemaRef,
+        object: &ObjectMeta,
+    ) -> Result<Statistics> {
+        let builder = reader_builder(store, object).await?;
+        Ok(file_statistics(builder.file_metadata(), &table_schema))
+    }
+
+    async fn create_physical_plan(
+        &self,
+        _state: &SessionState,
+        conf: FileScanConfig,
+        filters: Option<&Arc<dyn PhysicalExpr>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let exec = OrcExec::new(conf, filters.cloned());
+        Ok(Arc::new(exec))
+    }
+
+    async fn create_writer_physical_plan(
+        &self,
+        input: Arc<dyn ExecutionPlan>,
+        _state: &SessionState,
+        conf: FileSinkConfig,
+        order_requirements: Option<Vec<PhysicalSortRequirement>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if conf.overwrite {
+            return not_impl_err!("Overwrites are not implemented yet for ORC format");
+        }
+
+        let sink_schema = conf.output_schema().clone();
+        let sink = Arc::new(OrcFileSink::new(conf));
+
+        Ok(Arc::new(FileSinkExec::new(
+            input,
+            sink,
+            sink_schema,
+            order_requirements,
+        )) as _)
+    }
+
+    fn file_type(&self) -> FileType {
+        FileType::ORC
+    }
+}
+
+/// Implements [`DataSink`] for writing to ORC files
+struct OrcFileSink {
+    config: FileSinkConfig,
+}
+
+impl OrcFileSink {
+    fn new(config: FileSinkConfig) -> Self {
+        Self { config }
+    }
+
+    /// Converts table schema to writer schema, which may differ in the case
+    /// of hive style partitioning where some columns are removed from the
+    /// underlying files.
+    fn get_writer_schema(&self) -> SchemaRef {
+        if !self.config.table_partition_cols.is_empty() {
+            let schema = self.config.output_schema();
+            let partition_names: Vec<_> = self
+                .config
+                .table_partition_cols
+                .iter()
+                .map(|(s, _)| s)
+                .collect();
+            Arc::new(Schema::new(
+                schema
+                    .fields()
+                    .iter()
+                    .filter(|f| !partition_names.contains(&f.name()))
+                    .map(|f| (**f).clone())
+                    .collect::<Vec<_>>(),
+            ))
+        } else {
+            self.config.output_schema().clone()
+        }
+    }
+}
+
+impl Debug for OrcFileSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OrcFileSink").finish()
+    }
+}
+
+impl DisplayAs for OrcFileSink {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "OrcFileSink(file_groups=",)?;
+                FileGroupDisplay(&self.config.file_groups).fmt_as(t, f)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DataSink for OrcFileSink {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        None
+    }
+
+    async fn write_all(
+        &self,
+        data: SendableRecordBatchStream,
+        context: &Arc<TaskContext>,
+    ) -> Result<u64> {
+        let stripe_size = self
+            .config
+            .file_type_writer_options
+            .try_into_orc()?
+            .stripe_size;
+
+        let object_store = context
+            .runtime_env()
+            .object_store(&self.config.object_store_url)?;
+
+        let part_col = if !self.config.table_partition_cols.is_empty() {
+            Some(self.config.table_partition_cols.clone())
+        } else {
+            None
+        };
+
+        let (demux_task, mut file_stream_rx) = start_demuxer_task(
+            data,
+            context,
+            part_col,
+            self.config.table_paths[0].clone(),
+            "orc".into(),
+            self.config.single_file_output,
+        );
+
+        let mut file_write_tasks: JoinSet<std::result::Result<usize, DataFusionError>> =
+            JoinSet::new();
+
+        while let Some((path, mut rx)) = file_stream_rx.recv().await {
+            let shared_buffer = SharedBuffer::new(INITIAL_BUFFER_BYTES);
+            let mut builder =
+                ArrowWriterBuilder::new(shared_buffer.clone(), self.get_writer_schema());
+            if let Some(stripe_size) = stripe_size {
+                builder = builder.with_stripe_byte_size(stripe_size);
+            }
+            let mut orc_writer = builder
+                .try_build()
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            let mut object_store_writer = create_writer(
+                FileCompressionType::UNCOMPRESSED,
+                &path,
+                object_store.clone(),
+            )
+            .await?;
+            file_write_tasks.spawn(async move {
+                let mut row_count = 0;
+                while let Some(batch) = rx.recv().await {
+                    row_count += batch.num_rows();
+                    orc_writer
+                        .write(&batch)
+                        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+                    let mut buff_to_flush = shared_buffer.buffer.try_lock().unwrap();
+                    if buff_to_flush.len() > BUFFER_FLUSH_BYTES {
+                        object_store_writer
+                            .write_all(buff_to_flush.as_slice())
+                            .await?;
+                        buff_to_flush.clear();
+                    }
+                }
+                orc_writer
+                    .close()
+                    .map_err(|e| DataFusionError::External(Box::new(e)))?;
+                let final_buff = shared_buffer.buffer.try_lock().unwrap();
+
+                object_store_writer.write_all(final_buff.as_slice()).await?;
+                object_store_writer.shutdown().await?;
+                Ok(row_count)
+            });
+        }
+
+        let mut row_count = 0;
+        while let Some(result) = file_write_tasks.join_next().await {
+            match result {
+                Ok(r) => {
+                    row_count += r?;
+                }
+                Err(e) => {
+                    if e.is_panic() {
+                        std::panic::resume_unwind(e.into_panic());
+                    } else {
+                        unreachable!();
+                    }
+                }
+            }
+        }
+
+        match demux_task.await {
+            Ok(r) => r?,
+            Err(e) => {
+                if e.is_panic() {
+                    std::panic::resume_unwind(e.into_panic());
+                } else {
+                    unreachable!();
+                }
+            }
+        }
+        Ok(row_count as u64)
+    }
+}
+
+/// Reads the metadata of the ORC file `object`
+async fn reader_builder(
+    store: &Arc<dyn ObjectStore>,
+    object: &ObjectMeta,
+) -> Result<ArrowReaderBuilder<ObjectStoreReader>> {
+    let reader = ObjectStoreReader::new(store.clone(), object.clone());
+    ArrowReaderBuilder::try_new_async(reader)
+        .await
+        .map_err(|e| DataFusionError::External(Box::new(e)))
+}
+
+/// Returns the statistics of the columns of `table_schema` recorded in
+/// the footer of an ORC file
+fn file_statistics(metadata: &FileMetadata, table_schema: &SchemaRef) -> Statistics {
+    let num_rows = metadata.number_of_rows() as usize;
+    let column_statistics = table_schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let child = metadata
+                .root_data_type()
+                .children()
+                .iter()
+                .find(|c| c.name() == field.name());
+            let Some(child) = child else {
+                // the column is missing from the file, so all its values
+                // are null
+                return ColumnStatistics {
+                    null_count: Precision::Exact(num_rows),
+                    ..ColumnStatistics::new_unknown()
+                };
+            };
+            let Some(statistics) = metadata
+                .column_file_statistics()
+                .get(child.data_type().column_index())
+            else {
+                return ColumnStatistics::new_unknown();
+            };
+            let (min, max) = orc_statistics(statistics, field.data_type());
+            let exact = |v: Option<_>| v.map(Precision::Exact).unwrap_or_default();
+            // a corrupt file may report more values than rows
+            let null_count = num_rows
+                .checked_sub(statistics.number_of_values() as usize)
+                .map(Precision::Exact)
+                .unwrap_or_default();
+            ColumnStatistics {
+                null_count,
+                max_value: exact(max),
+                min_value: exact(min),
+                distinct_count: Precision::Absent,
+            }
+        })
+        .collect();
+
+    Statistics {
+        num_rows: Precision::Exact(num_rows),
+        total_byte_size: Precision::Absent,
+        column_statistics,
+    }
+}
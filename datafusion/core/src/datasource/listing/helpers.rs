@@ -17,12 +17,14 @@
 
 //! Helper functions for the table implementation
 
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use arrow::compute::{and, cast, prep_null_mask_filter};
+use arrow::compute::{and, cast_with_options, prep_null_mask_filter, CastOptions};
 use arrow::{
-    array::{ArrayRef, StringBuilder},
-    datatypes::{DataType, Field, Schema},
+    array::{ArrayRef, BooleanArray, StringBuilder},
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
     record_batch::RecordBatch,
 };
 use arrow_array::cast::AsArray;
@@ -36,12 +38,16 @@ use crate::{error::Result, scalar::ScalarValue};
 
 use super::PartitionedFile;
 use crate::datasource::listing::ListingTableUrl;
+use crate::datasource::physical_plan::{wrap_partition_type_in_dict, MetadataColumn};
 use crate::execution::context::SessionState;
 use datafusion_common::tree_node::{TreeNode, VisitRecursion};
-use datafusion_common::{internal_err, Column, DFField, DFSchema, DataFusionError};
-use datafusion_expr::{Expr, ScalarFunctionDefinition, Volatility};
-use datafusion_physical_expr::create_physical_expr;
+use datafusion_common::{
+    internal_err, plan_err, Column, DFField, DFSchema, DataFusionError,
+};
+use datafusion_expr::expr::{BinaryExpr, Cast};
+use datafusion_expr::{Expr, Operator, ScalarFunctionDefinition, Volatility};
 use datafusion_physical_expr::execution_props::ExecutionProps;
+use datafusion_physical_expr::{create_physical_expr, PhysicalExpr};
 use object_store::path::Path;
 use object_store::{ObjectMeta, ObjectStore};
 
@@ -161,6 +167,162 @@ pub fn split_files(
         .collect()
 }
 
+/// The directory name of the partitions whose partition column is null, as
+/// written by hive, e.g. `year=__HIVE_DEFAULT_PARTITION__`
+pub const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// URL decodes the value of a partition directory, as hive escapes
+/// characters such as `:` and `/` in the directory names, e.g.
+/// `ts=2021-01-01 10%3A00%3A00`
+fn decode_partition_value(value: &str) -> Cow<'_, str> {
+    if !value.contains('%') {
+        return Cow::Borrowed(value);
+    }
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    match String::from_utf8(decoded) {
+        Ok(decoded) => Cow::Owned(decoded),
+        Err(_) => Cow::Borrowed(value),
+    }
+}
+
+/// Parses the value of a partition column of type `data_type` from the
+/// value of its partition directory, which is URL decoded, and is null if
+/// it is [`HIVE_DEFAULT_PARTITION`]
+pub(crate) fn parse_partition_value(
+    value: &str,
+    data_type: &DataType,
+) -> Result<ScalarValue> {
+    if value == HIVE_DEFAULT_PARTITION {
+        return ScalarValue::try_from(data_type);
+    }
+    ScalarValue::try_from_string(decode_partition_value(value).into_owned(), data_type)
+}
+
+/// Parses the value of the partition column `name` of the partition at
+/// `path`, see [`parse_partition_value`]. Returns an error naming the
+/// partition if the value can not be parsed.
+fn parse_partition_value_at(
+    path: &Path,
+    name: &str,
+    value: &str,
+    data_type: &DataType,
+) -> Result<ScalarValue> {
+    parse_partition_value(value, data_type).map_err(|e| {
+        e.context(format!(
+            "Invalid value of partition column {name} in {path}"
+        ))
+    })
+}
+
+/// Parses the values of a partition column of type `data_type`, see
+/// [`parse_partition_value`]. Returns an error if any of the values can not
+/// be parsed.
+fn parse_partition_values<'a>(
+    values: impl IntoIterator<Item = Option<&'a str>>,
+    data_type: &DataType,
+) -> Result<ArrayRef> {
+    let mut builder = StringBuilder::new();
+    for value in values {
+        match value.filter(|value| *value != HIVE_DEFAULT_PARTITION) {
+            Some(value) => builder.append_value(decode_partition_value(value)),
+            None => builder.append_null(),
+        }
+    }
+    let options = CastOptions {
+        safe: false,
+        format_options: Default::default(),
+    };
+    Ok(cast_with_options(&builder.finish(), data_type, &options)?)
+}
+
+/// Infers the hive partition columns of `files` from their directories in
+/// `table_path`, such as `year` and `month` for `year=2021/month=01/1.parquet`.
+///
+/// The type of each column is the first of `Int64`, `Date32` and `Timestamp`
+/// that all its values can be parsed as, or else a dictionary encoded `Utf8`.
+pub(crate) fn infer_partition_cols(
+    table_path: &ListingTableUrl,
+    files: &[ObjectMeta],
+) -> Result<Vec<(String, DataType)>> {
+    let mut names: Option<Vec<&str>> = None;
+    let mut values: Vec<Vec<Option<&str>>> = vec![];
+    for file in files {
+        let Some(segments) = table_path.strip_prefix(&file.location) else {
+            continue;
+        };
+        let mut directories: Vec<_> = segments.collect();
+        directories.pop();
+        let partitions: Vec<_> = directories
+            .into_iter()
+            .map_while(|directory| directory.split_once('='))
+            .collect();
+        let file_names: Vec<_> = partitions.iter().map(|(name, _)| *name).collect();
+        match &names {
+            None => {
+                values = vec![vec![]; file_names.len()];
+                names = Some(file_names);
+            }
+            Some(names) if *names != file_names => {
+                return plan_err!(
+                    "Found files with different partition columns in {table_path}: \
+                     {names:?} and {file_names:?}"
+                );
+            }
+            Some(_) => {}
+        }
+        for (column_values, (_, value)) in values.iter_mut().zip(partitions) {
+            column_values.push(Some(value));
+        }
+    }
+
+    let candidates = [
+        DataType::Int64,
+        DataType::Date32,
+        DataType::Timestamp(TimeUnit::Nanosecond, None),
+    ];
+    let partition_cols = names
+        .unwrap_or_default()
+        .into_iter()
+        .zip(values)
+        .map(|(name, values)| {
+            let has_values = values
+                .iter()
+                .flatten()
+                .any(|v| *v != HIVE_DEFAULT_PARTITION);
+            let data_type = candidates
+                .iter()
+                .find(|data_type| {
+                    has_values
+                        && parse_partition_values(values.iter().copied(), data_type)
+                            .is_ok()
+                })
+                .cloned()
+                .unwrap_or_else(|| wrap_partition_type_in_dict(DataType::Utf8));
+            (name.to_string(), data_type)
+        })
+        .collect();
+    Ok(partition_cols)
+}
+
 struct Partition {
     /// The path to the partition, including the table prefix
     path: Path,
@@ -183,15 +345,24 @@ impl Partition {
     }
 }
 
-/// Returns a recursive list of the partitions in `table_path` up to `max_depth`
+/// Returns a recursive list of the partitions in `table_path` up to `max_depth`,
+/// starting at the partition `prefix` below `table_path`, if any.
+///
+/// The partitions at depth `d` are only listed if they match the filters of
+/// `filters_by_depth[d - 1]`, see [`partition_filters_by_depth`].
 async fn list_partitions(
     store: &dyn ObjectStore,
     table_path: &ListingTableUrl,
     max_depth: usize,
+    prefix: Vec<String>,
+    filters_by_depth: &[Vec<&Expr>],
+    partition_cols: &[(String, DataType)],
 ) -> Result<Vec<Partition>> {
     let partition = Partition {
-        path: table_path.prefix().clone(),
-        depth: 0,
+        depth: prefix.len(),
+        path: prefix
+            .into_iter()
+            .fold(table_path.prefix().clone(), |path, part| path.child(part)),
         files: None,
     };
 
@@ -211,6 +382,23 @@ async fn list_partitions(
 
         let depth = partition.depth;
         out.push(partition);
+
+        let paths = match filters_by_depth.get(depth) {
+            Some(filters) if !filters.is_empty() && !paths.is_empty() => {
+                let cols = &partition_cols[..depth + 1];
+                let mask = partition_mask(table_path, &paths, filters, cols)?;
+                match mask {
+                    Some(mask) => paths
+                        .into_iter()
+                        .zip(mask.values())
+                        .filter_map(|(p, f)| f.then_some(p))
+                        .collect(),
+                    None => paths,
+                }
+            }
+            _ => paths,
+        };
+
         for path in paths {
             let child = Partition {
                 path,
@@ -229,40 +417,149 @@ async fn list_partitions(
     Ok(out)
 }
 
-async fn prune_partitions(
-    table_path: &ListingTableUrl,
-    partitions: Vec<Partition>,
-    filters: &[Expr],
+/// Returns the filters that can be evaluated on the partitions at depth
+/// `d` but not above, at index `d - 1`: the filters whose deepest partition
+/// column is `partition_cols[d - 1]`. The filters on other columns are ignored.
+fn partition_filters_by_depth<'a>(
+    filters: &'a [Expr],
     partition_cols: &[(String, DataType)],
-) -> Result<Vec<Partition>> {
-    if filters.is_empty() {
-        return Ok(partitions);
+) -> Vec<Vec<&'a Expr>> {
+    let mut filters_by_depth = vec![vec![]; partition_cols.len()];
+    for filter in filters {
+        let Ok(columns) = filter.to_columns() else {
+            continue;
+        };
+        let depth = columns
+            .iter()
+            .map(|column| partition_cols.iter().position(|(n, _)| *n == column.name))
+            .collect::<Option<Vec<_>>>()
+            .and_then(|positions| positions.into_iter().max());
+        if let Some(depth) = depth {
+            filters_by_depth[depth].push(filter);
+        }
     }
+    filters_by_depth
+}
 
-    let mut builders: Vec<_> = (0..partition_cols.len())
-        .map(|_| StringBuilder::with_capacity(partitions.len(), partitions.len() * 10))
-        .collect();
+/// Returns the values of the leading partition columns that `filters` require
+/// to be equal to a literal, such as `["year=2021", "month=01"]` for
+/// `year = '2021' AND month = '01'`, to list the partitions below them only.
+///
+/// Only the values of string columns are used, as the literals of other types
+/// may be written differently in the directory names, e.g. `month=01` for `1`.
+fn partition_prefix(
+    filters: &[Expr],
+    partition_cols: &[(String, DataType)],
+) -> Vec<String> {
+    fn column_name(expr: &Expr) -> Option<&str> {
+        match expr {
+            Expr::Column(column) => Some(&column.name),
+            Expr::Cast(Cast { expr, data_type }) if *data_type == DataType::Utf8 => {
+                column_name(expr)
+            }
+            _ => None,
+        }
+    }
 
-    for partition in &partitions {
-        let cols = partition_cols.iter().map(|x| x.0.as_str());
-        let parsed = parse_partitions_for_path(table_path, &partition.path, cols)
-            .unwrap_or_default();
+    fn string_value(value: &ScalarValue) -> Option<&str> {
+        match value {
+            ScalarValue::Utf8(Some(value)) | ScalarValue::LargeUtf8(Some(value)) => {
+                Some(value)
+            }
+            ScalarValue::Dictionary(_, value) => string_value(value),
+            _ => None,
+        }
+    }
 
-        let mut builders = builders.iter_mut();
-        for (p, b) in parsed.iter().zip(&mut builders) {
-            b.append_value(p);
+    /// Adds the equality of `filter` to `values`, or `None` for the columns
+    /// that are required to be equal to different values
+    fn add_equalities<'a>(
+        filter: &'a Expr,
+        values: &mut HashMap<&'a str, Option<&'a str>>,
+    ) {
+        let Expr::BinaryExpr(BinaryExpr { left, op, right }) = filter else {
+            return;
+        };
+        match op {
+            Operator::And => {
+                add_equalities(left, values);
+                add_equalities(right, values);
+            }
+            Operator::Eq => {
+                let equality = match (left.as_ref(), right.as_ref()) {
+                    (column, Expr::Literal(value)) | (Expr::Literal(value), column) => {
+                        column_name(column).zip(string_value(value))
+                    }
+                    _ => None,
+                };
+                if let Some((name, value)) = equality {
+                    let entry = values.entry(name).or_insert(Some(value));
+                    if *entry != Some(value) {
+                        *entry = None;
+                    }
+                }
+            }
+            _ => {}
         }
-        builders.for_each(|b| b.append_null());
     }
 
+    let mut values = HashMap::new();
+    for filter in filters {
+        add_equalities(filter, &mut values);
+    }
+
+    partition_cols
+        .iter()
+        .map_while(|(name, data_type)| {
+            let is_string = match data_type {
+                DataType::Dictionary(_, value_type) => **value_type == DataType::Utf8,
+                data_type => *data_type == DataType::Utf8,
+            };
+            let value = values.get(name.as_str()).copied().flatten()?;
+            // the values that hive would not escape in the directory names
+            let is_plain = !value.is_empty()
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+            (is_string && is_plain).then(|| format!("{name}={value}"))
+        })
+        .collect()
+}
+
+/// Evaluates the conjunction of `filters` on the values of the partition
+/// columns of `paths`, returning `None` if none of the filters could be
+/// evaluated. The partitions with null results do not match, and the
+/// partitions whose values can not be parsed are an error, as they are when
+/// reading the partitions without filters.
+fn partition_mask(
+    table_path: &ListingTableUrl,
+    paths: &[Path],
+    filters: &[&Expr],
+    partition_cols: &[(String, DataType)],
+) -> Result<Option<BooleanArray>> {
+    let parsed: Vec<_> = paths
+        .iter()
+        .map(|path| {
+            let cols = partition_cols.iter().map(|x| x.0.as_str());
+            parse_partitions_for_path(table_path, path, cols).unwrap_or_default()
+        })
+        .collect();
+
     let arrays = partition_cols
         .iter()
-        .zip(builders)
-        .map(|((_, d), mut builder)| {
-            let array = builder.finish();
-            cast(&array, d)
+        .enumerate()
+        .map(|(i, (name, data_type))| {
+            let values = paths
+                .iter()
+                .zip(&parsed)
+                .map(|(path, values)| match values.get(i) {
+                    Some(value) => parse_partition_value_at(path, name, value, data_type),
+                    None => ScalarValue::try_from(data_type),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            ScalarValue::iter_to_array(values)
         })
-        .collect::<Result<_, _>>()?;
+        .collect::<Result<_>>()?;
 
     let fields: Fields = partition_cols
         .iter()
@@ -286,10 +583,7 @@ async fn prune_partitions(
     // Applies `filter` to `batch` returning `None` on error
     let do_filter = |filter| -> Option<ArrayRef> {
         let expr = create_physical_expr(filter, &df_schema, &schema, &props).ok()?;
-        expr.evaluate(&batch)
-            .ok()?
-            .into_array(partitions.len())
-            .ok()
+        expr.evaluate(&batch).ok()?.into_array(paths.len()).ok()
     };
 
     //.Compute the conjunction of the filters, ignoring errors
@@ -301,29 +595,72 @@ async fn prune_partitions(
             (r, None) => r,
         });
 
-    let mask = match mask {
-        Some(mask) => mask,
-        None => return Ok(partitions),
-    };
-
     // Don't retain partitions that evaluated to null
-    let prepared = match mask.null_count() {
+    let prepared = mask.map(|mask| match mask.null_count() {
         0 => mask,
         _ => prep_null_mask_filter(&mask),
-    };
+    });
 
     // Sanity check
-    assert_eq!(prepared.len(), partitions.len());
+    if let Some(prepared) = &prepared {
+        assert_eq!(prepared.len(), paths.len());
+    }
+
+    Ok(prepared)
+}
+
+async fn prune_partitions(
+    table_path: &ListingTableUrl,
+    partitions: Vec<Partition>,
+    filters: &[Expr],
+    partition_cols: &[(String, DataType)],
+) -> Result<Vec<Partition>> {
+    if filters.is_empty() {
+        return Ok(partitions);
+    }
+
+    let paths: Vec<_> = partitions.iter().map(|p| p.path.clone()).collect();
+    let filters: Vec<_> = filters.iter().collect();
+    let mask = match partition_mask(table_path, &paths, &filters, partition_cols)? {
+        Some(mask) => mask,
+        None => return Ok(partitions),
+    };
 
     let filtered = partitions
         .into_iter()
-        .zip(prepared.values())
+        .zip(mask.values())
         .filter_map(|(p, f)| f.then_some(p))
         .collect();
 
     Ok(filtered)
 }
 
+/// Evaluates `filter` on the values of the partition columns of `file`,
+/// followed by the values of its [constant](MetadataColumn::is_constant)
+/// `metadata_cols`, whose fields are `schema`. Returns whether the rows of
+/// the file match `filter`.
+pub(crate) fn file_matches_filter(
+    file: &PartitionedFile,
+    metadata_cols: &[MetadataColumn],
+    schema: &SchemaRef,
+    filter: &dyn PhysicalExpr,
+) -> Result<bool> {
+    let metadata_values = metadata_cols
+        .iter()
+        .filter_map(|c| c.file_value(&file.object_meta));
+    let columns = file
+        .partition_values
+        .iter()
+        .cloned()
+        .chain(metadata_values)
+        .map(|value| value.to_array())
+        .collect::<Result<Vec<_>>>()?;
+    let batch = RecordBatch::try_new(schema.clone(), columns)?;
+    let mask = filter.evaluate(&batch)?.into_array(1)?;
+    let mask = mask.as_boolean();
+    Ok(mask.is_valid(0) && mask.value(0))
+}
+
 /// Discover the partitions on the given path and prune out files
 /// that belong to irrelevant partitions using `filters` expressions.
 /// `filters` might contain expressions that can be resolved only at the
@@ -340,13 +677,24 @@ pub async fn pruned_partition_list<'a>(
     if partition_cols.is_empty() {
         return Ok(Box::pin(
             table_path
-                .list_all_files(ctx, store, file_extension)
+                .list_all_files(ctx, store, file_extension, false)
                 .await?
                 .map_ok(|object_meta| object_meta.into()),
         ));
     }
 
-    let partitions = list_partitions(store, table_path, partition_cols.len()).await?;
+    // only list the partitions that can match the filters
+    let prefix = partition_prefix(filters, partition_cols);
+    let filters_by_depth = partition_filters_by_depth(filters, partition_cols);
+    let partitions = list_partitions(
+        store,
+        table_path,
+        partition_cols.len(),
+        prefix,
+        &filters_by_depth,
+        partition_cols,
+    )
+    .await?;
     debug!("Listed {} partitions", partitions.len());
 
     let pruned =
@@ -363,8 +711,8 @@ pub async fn pruned_partition_list<'a>(
                 .into_iter()
                 .flatten()
                 .zip(partition_cols)
-                .map(|(parsed, (_, datatype))| {
-                    ScalarValue::try_from_string(parsed.to_string(), datatype)
+                .map(|(parsed, (name, datatype))| {
+                    parse_partition_value_at(&partition.path, name, parsed, datatype)
                 })
                 .collect::<Result<Vec<_>>>()?;
 
@@ -592,6 +940,173 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_pruned_partition_list_typed() {
+        let (store, state) = make_test_store_and_state(&[
+            ("tablepath/year=2021/month=01/file.parquet", 100),
+            ("tablepath/year=2022/month=02/file.parquet", 100),
+            ("tablepath/year=2022/month=11/file.parquet", 100),
+            (
+                "tablepath/year=__HIVE_DEFAULT_PARTITION__/month=03/file.parquet",
+                100,
+            ),
+        ]);
+        let filter1 = col("year").gt(lit(2021i64));
+        let filter2 = col("month").lt(lit(10i64));
+        let pruned = pruned_partition_list(
+            &state,
+            store.as_ref(),
+            &ListingTableUrl::parse("file:///tablepath/").unwrap(),
+            &[filter1, filter2],
+            ".parquet",
+            &[
+                (String::from("year"), DataType::Int64),
+                (String::from("month"), DataType::Int64),
+            ],
+        )
+        .await
+        .expect("partition pruning failed")
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(
+            pruned[0].object_meta.location.as_ref(),
+            "tablepath/year=2022/month=02/file.parquet"
+        );
+        assert_eq!(
+            &pruned[0].partition_values,
+            &[ScalarValue::Int64(Some(2022)), ScalarValue::Int64(Some(2))]
+        );
+
+        // the values of the partitions that are not pruned must be valid
+        let err = pruned_partition_list(
+            &state,
+            store.as_ref(),
+            &ListingTableUrl::parse("file:///tablepath/").unwrap(),
+            &[],
+            ".parquet",
+            &[(String::from("year"), DataType::Date32)],
+        )
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid value of partition column year"));
+
+        // as must the values of the partitions the filters are evaluated on
+        let err = pruned_partition_list(
+            &state,
+            store.as_ref(),
+            &ListingTableUrl::parse("file:///tablepath/").unwrap(),
+            &[col("year").gt(lit(ScalarValue::Date32(Some(0))))],
+            ".parquet",
+            &[(String::from("year"), DataType::Date32)],
+        )
+        .await
+        .err()
+        .expect("invalid partition values must not be pruned");
+        assert!(err
+            .to_string()
+            .contains("Invalid value of partition column year"));
+    }
+
+    #[test]
+    fn test_parse_partition_value() {
+        assert_eq!(
+            parse_partition_value("42", &DataType::Int64).unwrap(),
+            ScalarValue::Int64(Some(42))
+        );
+        assert_eq!(
+            parse_partition_value("2021-01-01", &DataType::Date32).unwrap(),
+            ScalarValue::Date32(Some(18628))
+        );
+        assert_eq!(
+            parse_partition_value(
+                "2021-01-01 10%3A30%3A00",
+                &DataType::Timestamp(TimeUnit::Second, None)
+            )
+            .unwrap(),
+            ScalarValue::TimestampSecond(Some(1609497000), None)
+        );
+        assert_eq!(
+            parse_partition_value("a%2Fb%", &DataType::Utf8).unwrap(),
+            ScalarValue::from("a/b%")
+        );
+        assert_eq!(
+            parse_partition_value(HIVE_DEFAULT_PARTITION, &DataType::Int64).unwrap(),
+            ScalarValue::Int64(None)
+        );
+        assert!(parse_partition_value("x", &DataType::Int64).is_err());
+    }
+
+    #[test]
+    fn test_partition_prefix() {
+        let partition_cols = [
+            (String::from("a"), DataType::Utf8),
+            (
+                String::from("b"),
+                wrap_partition_type_in_dict(DataType::Utf8),
+            ),
+            (String::from("c"), DataType::Int64),
+        ];
+        let filters = [
+            col("a").eq(lit("x")).and(lit("y").eq(col("b"))),
+            col("c").eq(lit(1i64)),
+        ];
+        assert_eq!(
+            partition_prefix(&filters, &partition_cols),
+            vec!["a=x", "b=y"]
+        );
+
+        // the values of the leading columns must be known
+        let filters = [col("b").eq(lit("y"))];
+        assert!(partition_prefix(&filters, &partition_cols).is_empty());
+
+        // conflicting and escaped values
+        let filters = [col("a").eq(lit("x")), col("a").eq(lit("z"))];
+        assert!(partition_prefix(&filters, &partition_cols).is_empty());
+        let filters = [col("a").eq(lit("x/y"))];
+        assert!(partition_prefix(&filters, &partition_cols).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_infer_partition_cols() {
+        let (store, _) = make_test_store_and_state(&[
+            ("tablepath/year=2021/day=2021-01-01/name=a/file.parquet", 100),
+            ("tablepath/year=2022/day=2022-01-01/name=1/file.parquet", 100),
+            (
+                "tablepath/year=__HIVE_DEFAULT_PARTITION__/day=2022-01-02/name=b/file.parquet",
+                100,
+            ),
+        ]);
+        let table_path = ListingTableUrl::parse("file:///tablepath/").unwrap();
+        let files: Vec<_> = store.list(None).try_collect().await.unwrap();
+        assert_eq!(
+            infer_partition_cols(&table_path, &files).unwrap(),
+            vec![
+                (String::from("year"), DataType::Int64),
+                (String::from("day"), DataType::Date32),
+                (
+                    String::from("name"),
+                    wrap_partition_type_in_dict(DataType::Utf8)
+                ),
+            ]
+        );
+
+        let (store, _) = make_test_store_and_state(&[
+            ("tablepath/year=2021/file.parquet", 100),
+            ("tablepath/month=01/file.parquet", 100),
+        ]);
+        let files: Vec<_> = store.list(None).try_collect().await.unwrap();
+        let err = infer_partition_cols(&table_path, &files).unwrap_err();
+        assert!(err.to_string().contains("different partition columns"));
+    }
+
     #[test]
     fn test_parse_partitions_for_path() {
         assert_eq!(
@@ -21,9 +21,14 @@ use std::collections::HashMap;
 use std::str::FromStr;
 use std::{any::Any, sync::Arc};
 
-use super::helpers::{expr_applicable_for_cols, pruned_partition_list, split_files};
+use super::helpers::{
+    expr_applicable_for_cols, file_matches_filter, infer_partition_cols,
+    pruned_partition_list, split_files,
+};
 use super::PartitionedFile;
 
+#[cfg(feature = "orc")]
+use crate::datasource::file_format::orc::OrcFormat;
 #[cfg(feature = "parquet")]
 use crate::datasource::file_format::parquet::ParquetFormat;
 use crate::datasource::{
@@ -38,7 +43,8 @@ use crate::datasource::{
     },
     get_statistics_with_limit,
     listing::ListingTableUrl,
-    physical_plan::{FileScanConfig, FileSinkConfig},
+    physical_plan::{FileScanConfig, FileSinkConfig, MetadataColumn},
+    schema_adapter::SchemaAdapterFactory,
     TableProvider, TableType,
 };
 use crate::{
@@ -51,13 +57,13 @@ use crate::{
 use arrow::datatypes::{DataType, Field, SchemaBuilder, SchemaRef};
 use arrow_schema::Schema;
 use datafusion_common::{
-    internal_err, plan_err, project_schema, Constraints, FileType, FileTypeWriterOptions,
-    SchemaExt, ToDFSchema,
+    internal_err, not_impl_err, plan_err, project_schema, Constraints, FileType,
+    FileTypeWriterOptions, SchemaExt, ToDFSchema,
 };
 use datafusion_execution::cache::cache_manager::FileStatisticsCache;
 use datafusion_execution::cache::cache_unit::DefaultFileStatisticsCache;
 use datafusion_physical_expr::{
-    create_physical_expr, LexOrdering, PhysicalSortRequirement,
+    create_physical_expr, LexOrdering, PhysicalExpr, PhysicalSortRequirement,
 };
 
 use async_trait::async_trait;
@@ -142,6 +148,8 @@ impl ListingTableConfig {
         let file_format: Arc<dyn FileFormat> = match file_type {
             FileType::ARROW => Arc::new(ArrowFormat),
             FileType::AVRO => Arc::new(AvroFormat),
+            #[cfg(feature = "orc")]
+            FileType::ORC => Arc::new(OrcFormat),
             FileType::CSV => Arc::new(
                 CsvFormat::default().with_file_compression_type(file_compression_type),
             ),
@@ -167,7 +175,7 @@ impl ListingTableConfig {
             .table_paths
             .first()
             .unwrap()
-            .list_all_files(state, store.as_ref(), "")
+            .list_all_files(state, store.as_ref(), "", false)
             .await?
             .next()
             .await
@@ -224,6 +232,9 @@ pub struct ListingOptions {
     /// The expected partition column names in the folder structure.
     /// See [Self::with_table_partition_cols] for details
     pub table_partition_cols: Vec<(String, DataType)>,
+    /// The virtual columns with the metadata of the files, which follow the
+    /// partition columns. See [Self::with_metadata_cols] for details
+    pub metadata_cols: Vec<MetadataColumn>,
     /// Set true to try to guess statistics from the files.
     /// This can add a lot of overhead as it will usually require files
     /// to be opened and at least partially parsed.
@@ -263,6 +274,7 @@ impl ListingOptions {
             file_extension: String::new(),
             format,
             table_partition_cols: vec![],
+            metadata_cols: vec![],
             collect_stat: true,
             target_partitions: 1,
             file_sort_order: vec![],
@@ -319,6 +331,15 @@ impl ListingOptions {
     /// - Files that don't follow this partitioning scheme will be
     /// ignored.
     ///
+    /// - The values are parsed as the types of the columns, such as
+    /// `Date32` for `date=2022-01-01`, after URL decoding them as hive
+    /// escapes characters such as `:` in directory names. The value
+    /// `__HIVE_DEFAULT_PARTITION__` is null.
+    ///
+    /// - The filters on the partition columns limit the directories
+    /// that are listed. See [`Self::infer_partitions`] to infer the
+    /// columns from the directories instead.
+    ///
     /// - Since the columns have the same value for all rows read from
     /// each individual file (such as dates), they are typically
     /// dictionary encoded for efficiency. You may use
@@ -357,6 +378,27 @@ impl ListingOptions {
         self
     }
 
+    /// Set the virtual columns with the metadata of the files, such as
+    /// `_file_path`, on [`ListingOptions`] and returns self.
+    ///
+    /// The metadata columns follow the partition columns in the schema of the
+    /// table. Like partition columns, filters on the metadata columns that
+    /// have a value per file prune the files that are read.
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use datafusion::datasource::{listing::ListingOptions, file_format::parquet::ParquetFormat};
+    /// # use datafusion::datasource::physical_plan::MetadataColumn;
+    /// let listing_options = ListingOptions::new(Arc::new(ParquetFormat::default()))
+    ///     .with_metadata_cols(vec![MetadataColumn::FilePath, MetadataColumn::RowIndex]);
+    ///
+    /// assert_eq!(listing_options.metadata_cols.len(), 2);
+    /// ```
+    pub fn with_metadata_cols(mut self, metadata_cols: Vec<MetadataColumn>) -> Self {
+        self.metadata_cols = metadata_cols;
+        self
+    }
+
     /// Set stat collection on [`ListingOptions`] and returns self.
     ///
     /// ```
@@ -439,14 +481,40 @@ impl ListingOptions {
     ) -> Result<SchemaRef> {
         let store = state.runtime_env().object_store(table_path)?;
 
+        let partitioned = !self.table_partition_cols.is_empty();
         let files: Vec<_> = table_path
-            .list_all_files(state, store.as_ref(), &self.file_extension)
+            .list_all_files(state, store.as_ref(), &self.file_extension, partitioned)
             .await?
             .try_collect()
             .await?;
 
         self.format.infer_schema(state, &store, &files).await
     }
+
+    /// Infer the partition columns of the files at the given path from their
+    /// hive style directories. For example, the partition columns of
+    /// `/mnt/nyctaxi/year=2022/month=01/tripdata.parquet` are `year` and
+    /// `month`.
+    ///
+    /// The type of each column is the first of `Int64`, `Date32` and
+    /// `Timestamp` that all its values can be parsed as, or else a dictionary
+    /// encoded `Utf8`. Returns an error if the files are in directories of
+    /// different partition columns.
+    pub async fn infer_partitions(
+        &self,
+        state: &SessionState,
+        table_path: &ListingTableUrl,
+    ) -> Result<Vec<(String, DataType)>> {
+        let store = state.runtime_env().object_store(table_path)?;
+
+        let files: Vec<_> = table_path
+            .list_all_files(state, store.as_ref(), &self.file_extension, true)
+            .await?
+            .try_collect()
+            .await?;
+
+        infer_partition_cols(table_path, &files)
+    }
 }
 
 /// Reads data from one or more files via an
@@ -525,6 +593,7 @@ pub struct ListingTable {
     collected_statistics: FileStatisticsCache,
     constraints: Constraints,
     column_defaults: HashMap<String, Expr>,
+    schema_adapter_factory: Option<Arc<dyn SchemaAdapterFactory>>,
 }
 
 impl ListingTable {
@@ -552,6 +621,10 @@ impl ListingTable {
         for (part_col_name, part_col_type) in &options.table_partition_cols {
             builder.push(Field::new(part_col_name, part_col_type.clone(), false));
         }
+        // Add the metadata columns after the partition columns
+        for metadata_col in &options.metadata_cols {
+            builder.push(metadata_col.field());
+        }
 
         let table = Self {
             table_paths: config.table_paths,
@@ -562,6 +635,7 @@ impl ListingTable {
             collected_statistics: Arc::new(DefaultFileStatisticsCache::default()),
             constraints: Constraints::empty(),
             column_defaults: HashMap::new(),
+            schema_adapter_factory: None,
         };
 
         Ok(table)
@@ -594,6 +668,19 @@ impl ListingTable {
         self
     }
 
+    /// Set the [`SchemaAdapterFactory`] that adapts the files to the schema
+    /// of the table, for example to read renamed or widened columns.
+    ///
+    /// The statistics of the files are not collected for tables with a
+    /// custom adapter, as they are recorded for the columns of the files.
+    pub fn with_schema_adapter_factory(
+        mut self,
+        schema_adapter_factory: Arc<dyn SchemaAdapterFactory>,
+    ) -> Self {
+        self.schema_adapter_factory = Some(schema_adapter_factory);
+        self
+    }
+
     /// Specify the SQL definition for this table, if any
     pub fn with_definition(mut self, defintion: Option<String>) -> Self {
         self.definition = defintion;
@@ -692,6 +779,9 @@ impl TableProvider for ListingTable {
                     limit,
                     output_ordering: self.try_create_output_ordering()?,
                     table_partition_cols,
+                    schema_adapter_factory: self.schema_adapter_factory.clone(),
+                    metadata_cols: self.options.metadata_cols.clone(),
+                    projection_paths: vec![],
                 },
                 filters.as_ref(),
             )
@@ -702,16 +792,15 @@ impl TableProvider for ListingTable {
         &self,
         filter: &Expr,
     ) -> Result<TableProviderFilterPushDown> {
-        if expr_applicable_for_cols(
-            &self
-                .options
-                .table_partition_cols
-                .iter()
-                .map(|x| x.0.clone())
-                .collect::<Vec<_>>(),
-            filter,
-        ) {
-            // if filter can be handled by partiton pruning, it is exact
+        let file_value_cols: Vec<_> = self
+            .options
+            .table_partition_cols
+            .iter()
+            .map(|x| x.0.clone())
+            .chain(self.constant_metadata_cols().map(|c| c.name().to_string()))
+            .collect();
+        if expr_applicable_for_cols(&file_value_cols, filter) {
+            // if filter can be handled by partiton or metadata pruning, it is exact
             Ok(TableProviderFilterPushDown::Exact)
         } else {
             // otherwise, we still might be able to handle the filter with file
@@ -730,6 +819,9 @@ impl TableProvider for ListingTable {
         input: Arc<dyn ExecutionPlan>,
         overwrite: bool,
     ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !self.options.metadata_cols.is_empty() {
+            return not_impl_err!("Inserting into tables with metadata columns");
+        }
         // Check that the schema of the plan matches the schema of this table.
         if !self
             .schema()
@@ -818,6 +910,61 @@ impl TableProvider for ListingTable {
 }
 
 impl ListingTable {
+    /// The metadata columns of the table that have a value per file
+    fn constant_metadata_cols(&self) -> impl Iterator<Item = MetadataColumn> + '_ {
+        self.options
+            .metadata_cols
+            .iter()
+            .copied()
+            .filter(MetadataColumn::is_constant)
+    }
+
+    /// Returns the conjunction of the `filters` that reference the metadata
+    /// columns with a value per file and can be evaluated on the values of
+    /// the partition and metadata columns of each file, along with the schema
+    /// of these values
+    fn metadata_filter(
+        &self,
+        ctx: &SessionState,
+        filters: &[Expr],
+    ) -> Result<Option<(Arc<dyn PhysicalExpr>, SchemaRef)>> {
+        let metadata_cols: Vec<_> = self.constant_metadata_cols().collect();
+        if metadata_cols.is_empty() {
+            return Ok(None);
+        }
+        let mut fields = self
+            .options
+            .table_partition_cols
+            .iter()
+            .map(|(name, _)| Ok(self.table_schema.field_with_name(name)?.clone()))
+            .collect::<Result<Vec<_>>>()?;
+        fields.extend(metadata_cols.iter().map(|c| c.field()));
+        let col_names: Vec<_> = fields.iter().map(|f| f.name().clone()).collect();
+
+        let metadata_filters = filters.iter().filter(|filter| {
+            let references_metadata = filter
+                .to_columns()
+                .map(|cols| {
+                    cols.iter()
+                        .any(|c| metadata_cols.iter().any(|m| m.name() == c.name))
+                })
+                .unwrap_or(false);
+            references_metadata && expr_applicable_for_cols(&col_names, filter)
+        });
+        let Some(expr) = conjunction(metadata_filters.cloned()) else {
+            return Ok(None);
+        };
+
+        let schema = Arc::new(Schema::new(fields));
+        let filter = create_physical_expr(
+            &expr,
+            &schema.clone().to_dfschema()?,
+            &schema,
+            ctx.execution_props(),
+        )?;
+        Ok(Some((filter, schema)))
+    }
+
     /// Get the list of files for a scan as well as the file level statistics.
     /// The list is grouped to let the execution plan know how the files should
     /// be distributed to different threads / executors.
@@ -845,12 +992,30 @@ impl ListingTable {
         }))
         .await?;
         let file_list = stream::iter(file_list).flatten();
+        // prune the files with the filters on their metadata
+        let file_list = match self.metadata_filter(ctx, filters)? {
+            Some((filter, schema)) => {
+                let metadata_cols: Vec<_> = self.constant_metadata_cols().collect();
+                file_list
+                    .try_filter_map(move |file| {
+                        let matches = file_matches_filter(
+                            &file,
+                            &metadata_cols,
+                            &schema,
+                            filter.as_ref(),
+                        );
+                        future::ready(matches.map(|m| m.then_some(file)))
+                    })
+                    .boxed()
+            }
+            None => file_list.boxed(),
+        };
         // collect the statistics if required by the config
         let files = file_list
             .map(|part_file| async {
                 let part_file = part_file?;
                 let mut statistics_result = Statistics::new_unknown(&self.file_schema);
-                if self.options.collect_stat {
+                if self.options.collect_stat && self.schema_adapter_factory.is_none() {
                     let statistics_cache = self.collected_statistics.clone();
                     match statistics_cache.get_with_extra(
                         &part_file.object_meta.location,
@@ -992,6 +1157,66 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "parquet")]
+    #[tokio::test]
+    async fn read_metadata_columns() -> Result<()> {
+        let testdata = crate::test_util::parquet_test_data();
+        let filename = format!("{}/{}", testdata, "alltypes_plain.parquet");
+        let table_path = ListingTableUrl::parse(filename).unwrap();
+
+        let ctx = SessionContext::new();
+        let state = ctx.state();
+
+        let opt = ListingOptions::new(Arc::new(ParquetFormat::default()))
+            .with_metadata_cols(vec![MetadataColumn::FilePath, MetadataColumn::RowIndex]);
+        let schema = opt.infer_schema(&state, &table_path).await?;
+        let config = ListingTableConfig::new(table_path)
+            .with_listing_options(opt)
+            .with_schema(schema);
+        let table = ListingTable::try_new(config)?;
+
+        let file_path = col("_file_path").like(lit("%alltypes_plain%"));
+        assert_eq!(
+            table.supports_filters_pushdown(&[
+                &file_path,
+                &col("_row_index").gt(lit(1))
+            ])?,
+            vec![
+                TableProviderFilterPushDown::Exact,
+                TableProviderFilterPushDown::Inexact
+            ]
+        );
+        ctx.register_table("t", Arc::new(table))?;
+
+        let batches = ctx
+            .sql(
+                "SELECT id, _row_index FROM t \
+                WHERE id > 5 AND _file_path LIKE '%alltypes_plain%' ORDER BY id",
+            )
+            .await?
+            .collect()
+            .await?;
+        let expected = [
+            "+----+------------+",
+            "| id | _row_index |",
+            "+----+------------+",
+            "| 6  | 2          |",
+            "| 7  | 3          |",
+            "+----+------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        // the file is pruned
+        let batches = ctx
+            .sql("SELECT id FROM t WHERE _file_path LIKE '%other%'")
+            .await?
+            .collect()
+            .await?;
+        assert!(batches.iter().all(|b| b.num_rows() == 0));
+
+        Ok(())
+    }
+
     #[cfg(feature = "parquet")]
     #[tokio::test]
     async fn test_try_create_output_ordering() {
@@ -1716,6 +1941,12 @@ mod tests {
                     )
                     .await?;
             }
+            #[cfg(feature = "orc")]
+            FileType::ORC => {
+                return datafusion_common::not_impl_err!(
+                    "registering ORC tables is not supported"
+                )
+            }
         }
 
         // Create and register the source table with the provided schema and inserted data
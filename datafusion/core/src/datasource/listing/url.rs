@@ -211,6 +211,24 @@ impl ListingTableUrl {
         }
     }
 
+    /// Returns `true` if `path` matches this [`ListingTableUrl`] as a file of
+    /// a partitioned table, whose hive style partition directories such as
+    /// `year=2021` are not subdirectories that `ignore_subdirectory` ignores
+    fn contains_partition_file(&self, path: &Path, ignore_subdirectory: bool) -> bool {
+        if !ignore_subdirectory {
+            return self.contains(path, false);
+        }
+        let Some(segments) = self.strip_prefix(path) else {
+            return false;
+        };
+        let mut segments: Vec<_> = segments.collect();
+        let Some(file_name) = segments.pop() else {
+            return false;
+        };
+        let in_partitions = segments.iter().all(|segment| segment.contains('='));
+        in_partitions && self.glob.as_ref().map_or(true, |g| g.matches(file_name))
+    }
+
     /// Returns `true` if `path` refers to a collection of objects
     pub fn is_collection(&self) -> bool {
         self.url.as_str().ends_with('/')
@@ -231,11 +249,15 @@ impl ListingTableUrl {
     }
 
     /// List all files identified by this [`ListingTableUrl`] for the provided `file_extension`
+    ///
+    /// The files in the hive style partition directories of the table are
+    /// listed if it is `partitioned`, even if subdirectories are ignored.
     pub(crate) async fn list_all_files<'a>(
         &'a self,
         ctx: &'a SessionState,
         store: &'a dyn ObjectStore,
         file_extension: &'a str,
+        partitioned: bool,
     ) -> Result<BoxStream<'a, Result<ObjectMeta>>> {
         let exec_options = &ctx.options().execution;
         let ignore_subdirectory = exec_options.listing_table_ignore_subdirectory;
@@ -262,7 +284,11 @@ impl ListingTableUrl {
             .try_filter(move |meta| {
                 let path = &meta.location;
                 let extension_match = path.as_ref().ends_with(file_extension);
-                let glob_match = self.contains(path, ignore_subdirectory);
+                let glob_match = if partitioned {
+                    self.contains_partition_file(path, ignore_subdirectory)
+                } else {
+                    self.contains(path, ignore_subdirectory)
+                };
                 futures::future::ready(extension_match && glob_match)
             })
             .map_err(DataFusionError::ObjectStore)
@@ -455,6 +481,24 @@ mod tests {
         assert!(url.strip_prefix(&path).is_none());
     }
 
+    #[test]
+    fn test_contains_partition_directories() {
+        let url = ListingTableUrl::parse("s3://bucket/foo/").unwrap();
+        let file = Path::from("foo/file.parquet");
+        let partition_file = Path::from("foo/year=2021/month=01/file.parquet");
+        let subdirectory_file = Path::from("foo/year=2021/tmp/file.parquet");
+
+        // partition directories are subdirectories of unpartitioned tables
+        assert!(url.contains(&file, true));
+        assert!(!url.contains(&partition_file, true));
+        assert!(url.contains(&partition_file, false));
+
+        assert!(url.contains_partition_file(&file, true));
+        assert!(url.contains_partition_file(&partition_file, true));
+        assert!(!url.contains_partition_file(&subdirectory_file, true));
+        assert!(url.contains_partition_file(&subdirectory_file, false));
+    }
+
     #[test]
     fn test_split_glob() {
         fn test(input: &str, expected: Option<(&str, &str)>) {
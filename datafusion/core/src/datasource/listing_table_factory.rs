@@ -21,6 +21,8 @@ use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
 
+#[cfg(feature = "orc")]
+use crate::datasource::file_format::orc::OrcFormat;
 #[cfg(feature = "parquet")]
 use crate::datasource::file_format::parquet::ParquetFormat;
 use crate::datasource::file_format::{
@@ -30,13 +32,16 @@ use crate::datasource::file_format::{
 use crate::datasource::listing::{
     ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl,
 };
+use crate::datasource::physical_plan::{CsvBadRowPolicy, CsvRejects};
 use crate::datasource::provider::TableProviderFactory;
 use crate::datasource::TableProvider;
 use crate::execution::context::SessionState;
 
 use arrow::datatypes::{DataType, SchemaRef};
 use datafusion_common::file_options::{FileTypeWriterOptions, StatementOptions};
-use datafusion_common::{arrow_datafusion_err, plan_err, DataFusionError, FileType};
+use datafusion_common::{
+    arrow_datafusion_err, plan_err, DataFusionError, FileType, OwnedTableReference,
+};
 use datafusion_expr::CreateExternalTable;
 
 use async_trait::async_trait;
@@ -66,6 +71,8 @@ impl TableProviderFactory for ListingTableFactory {
 
         let file_extension = get_extension(cmd.location.as_str());
 
+        // the table of the rows rejected by a CSV bad_rows policy
+        let mut rejects_table = None;
         let file_format: Arc<dyn FileFormat> = match file_type {
             FileType::CSV => {
                 let mut statement_options = StatementOptions::from(&cmd.options);
@@ -79,14 +86,67 @@ impl TableProviderFactory for ListingTableFactory {
                 if let Some(escape) = statement_options.take_str_option("escape") {
                     csv_format = csv_format.with_escape(Some(escape.as_bytes()[0]))
                 }
+                if let Some(bad_rows) = statement_options.take_str_option("bad_rows") {
+                    let bad_rows = match bad_rows.to_lowercase().as_str() {
+                        "fail" => CsvBadRowPolicy::Fail,
+                        "skip" => CsvBadRowPolicy::Skip,
+                        "null" => CsvBadRowPolicy::NullFill,
+                        "reject" => {
+                            let Some(table) =
+                                statement_options.take_str_option("rejects_table")
+                            else {
+                                return plan_err!(
+                                    "The reject bad_rows policy for CSV requires \
+                                     a rejects_table option"
+                                );
+                            };
+                            let rejects = Arc::new(CsvRejects::new());
+                            rejects_table = Some((
+                                OwnedTableReference::from(table),
+                                Arc::clone(&rejects),
+                            ));
+                            CsvBadRowPolicy::Reject(rejects)
+                        }
+                        _ => {
+                            return plan_err!(
+                                "Unsupported bad_rows policy {bad_rows} for CSV, \
+                                 expected fail, skip, null or reject"
+                            )
+                        }
+                    };
+                    csv_format = csv_format.with_bad_rows(bad_rows)
+                }
+                if let Some(newlines_in_values) =
+                    statement_options.take_bool_option("newlines_in_values")?
+                {
+                    csv_format = csv_format.with_newlines_in_values(newlines_in_values)
+                }
+                if let Some(infer_decimals) =
+                    statement_options.take_bool_option("infer_decimals")?
+                {
+                    csv_format = csv_format.with_infer_decimals(infer_decimals)
+                }
                 Arc::new(csv_format)
             }
             #[cfg(feature = "parquet")]
             FileType::PARQUET => Arc::new(ParquetFormat::default()),
             FileType::AVRO => Arc::new(AvroFormat),
-            FileType::JSON => Arc::new(
-                JsonFormat::default().with_file_compression_type(file_compression_type),
-            ),
+            #[cfg(feature = "orc")]
+            FileType::ORC => Arc::new(OrcFormat),
+            FileType::JSON => {
+                let mut statement_options = StatementOptions::from(&cmd.options);
+                let mut json_format = JsonFormat::default()
+                    .with_file_compression_type(file_compression_type);
+                if let Some(array) = statement_options.take_bool_option("array")? {
+                    json_format = json_format.with_array(array)
+                }
+                if let Some(column) =
+                    statement_options.take_str_option("unknown_fields_column")
+                {
+                    json_format = json_format.with_unknown_fields_column(Some(column))
+                }
+                Arc::new(json_format)
+            }
             FileType::ARROW => Arc::new(ArrowFormat),
         };
 
@@ -181,11 +241,13 @@ impl TableProviderFactory for ListingTableFactory {
             FileType::PARQUET => file_type_writer_options,
             FileType::ARROW => file_type_writer_options,
             FileType::AVRO => file_type_writer_options,
+            #[cfg(feature = "orc")]
+            FileType::ORC => file_type_writer_options,
         };
 
         let table_path = ListingTableUrl::parse(&cmd.location)?;
 
-        let options = ListingOptions::new(file_format)
+        let mut options = ListingOptions::new(file_format)
             .with_collect_stat(state.config().collect_statistics())
             .with_file_extension(file_extension)
             .with_target_partitions(state.config().target_partitions())
@@ -193,6 +255,18 @@ impl TableProviderFactory for ListingTableFactory {
             .with_file_sort_order(cmd.order_exprs.clone())
             .with_write_options(file_type_writer_options);
 
+        if provided_schema.is_none()
+            && options.table_partition_cols.is_empty()
+            && state
+                .config_options()
+                .execution
+                .listing_table_infer_partitions
+        {
+            let table_partition_cols =
+                options.infer_partitions(state, &table_path).await?;
+            options = options.with_table_partition_cols(table_partition_cols);
+        }
+
         let resolved_schema = match provided_schema {
             None => options.infer_schema(state, &table_path).await?,
             Some(s) => s,
@@ -206,6 +280,12 @@ impl TableProviderFactory for ListingTableFactory {
             .with_definition(cmd.definition.clone())
             .with_constraints(cmd.constraints.clone())
             .with_column_defaults(cmd.column_defaults.clone());
+
+        if let Some((table_ref, rejects)) = rejects_table {
+            state
+                .schema_for_ref(&table_ref)?
+                .register_table(table_ref.table().to_string(), rejects)?;
+        }
         Ok(Arc::new(table))
     }
 }
@@ -227,7 +307,7 @@ mod tests {
     use crate::execution::context::SessionContext;
 
     use datafusion_common::parsers::CompressionTypeVariant;
-    use datafusion_common::{Constraints, DFSchema, OwnedTableReference};
+    use datafusion_common::{Constraints, DFSchema};
 
     #[tokio::test]
     async fn test_create_using_non_std_file_ext() {
@@ -19,6 +19,7 @@
 //!
 //! [`ListingTable`]: crate::datasource::listing::ListingTable
 
+pub mod arrow_to_avro;
 pub mod avro_to_arrow;
 pub mod default_table_source;
 pub mod empty;
@@ -29,6 +30,7 @@ pub mod listing_table_factory;
 pub mod memory;
 pub mod physical_plan;
 pub mod provider;
+pub mod schema_adapter;
 mod statistics;
 pub mod stream;
 pub mod streaming;
@@ -25,8 +25,10 @@ use crate::datasource::physical_plan::{
 };
 use crate::error::Result;
 use crate::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use crate::physical_plan::stream::CooperativeStream;
 use crate::physical_plan::{
-    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+    DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionProgress, Partitioning,
+    SendableRecordBatchStream,
 };
 
 use arrow_schema::SchemaRef;
@@ -133,13 +135,17 @@ impl ExecutionPlan for ArrowExec {
         };
         let stream =
             FileStream::new(&self.base_config, partition, opener, &self.metrics)?;
-        Ok(Box::pin(stream))
+        Ok(Box::pin(CooperativeStream::new(Box::pin(stream), &context)))
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
         Some(self.metrics.clone_inner())
     }
 
+    fn progress(&self) -> ExecutionProgress {
+        self.base_config.progress(&self.metrics)
+    }
+
     fn statistics(&self) -> Result<Statistics> {
         Ok(self.projected_statistics.clone())
     }
@@ -25,8 +25,8 @@ use crate::error::Result;
 use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
 use crate::physical_plan::{
-    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
-    Statistics,
+    DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionProgress, Partitioning,
+    SendableRecordBatchStream, Statistics,
 };
 
 use arrow::datatypes::SchemaRef;
@@ -131,6 +131,7 @@ impl ExecutionPlan for AvroExec {
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         use super::file_stream::FileStream;
+        use crate::physical_plan::stream::CooperativeStream;
         let object_store = context
             .runtime_env()
             .object_store(&self.base_config.object_store_url)?;
@@ -145,7 +146,7 @@ impl ExecutionPlan for AvroExec {
 
         let stream =
             FileStream::new(&self.base_config, partition, opener, &self.metrics)?;
-        Ok(Box::pin(stream))
+        Ok(Box::pin(CooperativeStream::new(Box::pin(stream), &context)))
     }
 
     fn statistics(&self) -> Result<Statistics> {
@@ -155,6 +156,10 @@ impl ExecutionPlan for AvroExec {
     fn metrics(&self) -> Option<MetricsSet> {
         Some(self.metrics.clone_inner())
     }
+
+    fn progress(&self) -> ExecutionProgress {
+        self.base_config.progress(&self.metrics)
+    }
 }
 
 #[cfg(feature = "avro")]
@@ -272,6 +277,9 @@ mod tests {
             limit: None,
             table_partition_cols: vec![],
             output_ordering: vec![],
+            schema_adapter_factory: None,
+            metadata_cols: vec![],
+            projection_paths: vec![],
         });
         assert_eq!(avro_exec.output_partitioning().partition_count(), 1);
         let mut results = avro_exec
@@ -343,6 +351,9 @@ mod tests {
             limit: None,
             table_partition_cols: vec![],
             output_ordering: vec![],
+            schema_adapter_factory: None,
+            metadata_cols: vec![],
+            projection_paths: vec![],
         });
         assert_eq!(avro_exec.output_partitioning().partition_count(), 1);
 
@@ -413,6 +424,9 @@ mod tests {
             limit: None,
             table_partition_cols: vec![Field::new("date", DataType::Utf8, false)],
             output_ordering: vec![],
+            schema_adapter_factory: None,
+            metadata_cols: vec![],
+            projection_paths: vec![],
         });
         assert_eq!(avro_exec.output_partitioning().partition_count(), 1);
 
@@ -22,7 +22,10 @@ use std::io::{Read, Seek, SeekFrom};
 use std::sync::Arc;
 use std::task::Poll;
 
-use super::{calculate_range, FileGroupPartitioner, FileScanConfig, RangeCalculation};
+use super::csv_records::{CsvBadRowPolicy, CsvRecordReader};
+use super::{
+    calculate_range, read_payload, FileGroupPartitioner, FileScanConfig, RangeCalculation,
+};
 use crate::datasource::file_format::file_compression_type::FileCompressionType;
 use crate::datasource::listing::{FileRange, ListingTableUrl};
 use crate::datasource::physical_plan::file_stream::{
@@ -32,9 +35,10 @@ use crate::datasource::physical_plan::FileMeta;
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use crate::physical_plan::stream::CooperativeStream;
 use crate::physical_plan::{
-    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
-    Statistics,
+    DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionProgress, Partitioning,
+    SendableRecordBatchStream, Statistics,
 };
 
 use arrow::csv;
@@ -60,6 +64,8 @@ pub struct CsvExec {
     delimiter: u8,
     quote: u8,
     escape: Option<u8>,
+    bad_rows: CsvBadRowPolicy,
+    newlines_in_values: bool,
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
     /// Compression type of the file associated with CsvExec
@@ -88,11 +94,27 @@ impl CsvExec {
             delimiter,
             quote,
             escape,
+            bad_rows: CsvBadRowPolicy::default(),
+            newlines_in_values: false,
             metrics: ExecutionPlanMetricsSet::new(),
             file_compression_type,
         }
     }
 
+    /// Set the policy for the rows that can not be read, see [`CsvBadRowPolicy`]
+    pub fn with_bad_rows(mut self, bad_rows: CsvBadRowPolicy) -> Self {
+        self.bad_rows = bad_rows;
+        self
+    }
+
+    /// Set whether quoted values may contain newlines. The files are then not
+    /// split into ranges to read in parallel, as their lines may not start
+    /// records.
+    pub fn with_newlines_in_values(mut self, newlines_in_values: bool) -> Self {
+        self.newlines_in_values = newlines_in_values;
+        self
+    }
+
     /// Ref to the base configs
     pub fn base_config(&self) -> &FileScanConfig {
         &self.base_config
@@ -115,6 +137,22 @@ impl CsvExec {
     pub fn escape(&self) -> Option<u8> {
         self.escape
     }
+
+    /// The policy for the rows that can not be read
+    pub fn bad_rows(&self) -> &CsvBadRowPolicy {
+        &self.bad_rows
+    }
+
+    /// true if quoted values may contain newlines
+    pub fn newlines_in_values(&self) -> bool {
+        self.newlines_in_values
+    }
+
+    /// Whether the files must be read from their start, to find where the
+    /// records start or their line numbers
+    fn reads_whole_files(&self) -> bool {
+        self.newlines_in_values || matches!(self.bad_rows, CsvBadRowPolicy::Reject(_))
+    }
 }
 
 impl DisplayAs for CsvExec {
@@ -125,7 +163,14 @@ impl DisplayAs for CsvExec {
     ) -> std::fmt::Result {
         write!(f, "CsvExec: ")?;
         self.base_config.fmt_as(t, f)?;
-        write!(f, ", has_header={}", self.has_header)
+        write!(f, ", has_header={}", self.has_header)?;
+        if !matches!(self.bad_rows, CsvBadRowPolicy::Fail) {
+            write!(f, ", bad_rows={}", self.bad_rows)?;
+        }
+        if self.newlines_in_values {
+            write!(f, ", newlines_in_values=true")?;
+        }
+        Ok(())
     }
 }
 
@@ -185,6 +230,10 @@ impl ExecutionPlan for CsvExec {
         if self.file_compression_type.is_compressed() {
             return Ok(None);
         }
+        // Nor on files that must be read from their start
+        if self.reads_whole_files() {
+            return Ok(None);
+        }
 
         let repartitioned_file_groups_option = FileGroupPartitioner::new()
             .with_target_partitions(target_partitions)
@@ -217,6 +266,8 @@ impl ExecutionPlan for CsvExec {
             delimiter: self.delimiter,
             quote: self.quote,
             escape: self.escape,
+            bad_rows: self.bad_rows.clone(),
+            newlines_in_values: self.newlines_in_values,
             object_store,
         });
 
@@ -226,7 +277,7 @@ impl ExecutionPlan for CsvExec {
         };
         let stream =
             FileStream::new(&self.base_config, partition, opener, &self.metrics)?;
-        Ok(Box::pin(stream) as SendableRecordBatchStream)
+        Ok(Box::pin(CooperativeStream::new(Box::pin(stream), &context)))
     }
 
     fn statistics(&self) -> Result<Statistics> {
@@ -236,6 +287,10 @@ impl ExecutionPlan for CsvExec {
     fn metrics(&self) -> Option<MetricsSet> {
         Some(self.metrics.clone_inner())
     }
+
+    fn progress(&self) -> ExecutionProgress {
+        self.base_config.progress(&self.metrics)
+    }
 }
 
 /// A Config for [`CsvOpener`]
@@ -248,6 +303,8 @@ pub struct CsvConfig {
     delimiter: u8,
     quote: u8,
     escape: Option<u8>,
+    bad_rows: CsvBadRowPolicy,
+    newlines_in_values: bool,
     object_store: Arc<dyn ObjectStore>,
 }
 
@@ -270,9 +327,23 @@ impl CsvConfig {
             delimiter,
             quote,
             escape: None,
+            bad_rows: CsvBadRowPolicy::default(),
+            newlines_in_values: false,
             object_store,
         }
     }
+
+    /// Set the policy for the rows that can not be read, see [`CsvBadRowPolicy`]
+    pub fn with_bad_rows(mut self, bad_rows: CsvBadRowPolicy) -> Self {
+        self.bad_rows = bad_rows;
+        self
+    }
+
+    /// Set whether quoted values may contain newlines
+    pub fn with_newlines_in_values(mut self, newlines_in_values: bool) -> Self {
+        self.newlines_in_values = newlines_in_values;
+        self
+    }
 }
 
 impl CsvConfig {
@@ -296,6 +367,37 @@ impl CsvConfig {
 
         builder
     }
+
+    /// Whether the records are read one at a time, which the arrow reader
+    /// can not do for records with quoted newlines or to skip bad rows
+    fn reads_records(&self) -> bool {
+        self.newlines_in_values || !matches!(self.bad_rows, CsvBadRowPolicy::Fail)
+    }
+
+    /// Whether the files must be read from their start, see
+    /// [`CsvExec::reads_whole_files`]
+    fn reads_whole_files(&self) -> bool {
+        self.newlines_in_values || matches!(self.bad_rows, CsvBadRowPolicy::Reject(_))
+    }
+
+    fn record_reader<R: Read>(
+        &self,
+        reader: R,
+        file_path: String,
+    ) -> Result<CsvRecordReader<R>> {
+        Ok(CsvRecordReader::new(
+            reader,
+            self.file_schema.clone(),
+            self.file_projection.clone(),
+            self.batch_size,
+            self.delimiter,
+            self.quote,
+            self.escape,
+            self.bad_rows.clone(),
+        )?
+        .with_file_path(file_path)
+        .with_header(self.has_header))
+    }
 }
 
 /// A [`FileOpener`] that opens a CSV file and yields a [`FileOpenFuture`]
@@ -341,6 +443,12 @@ impl FileOpener for CsvOpener {
     ///  A,1,2,3,4,5,6,7,8,9\n
     ///  A},1,2,3,4,5,6,7,8,9\n
     ///  The lines read would be: [1, 2]
+    ///
+    /// Lines may not start records if quoted values contain newlines, and the
+    /// rejected rows of a [`CsvBadRowPolicy::Reject`] policy need their line
+    /// numbers. Such files can not be split into ranges: the partition of the
+    /// range starting at 0 reads the whole file, and the other partitions
+    /// nothing.
     fn open(&self, file_meta: FileMeta) -> Result<FileOpenFuture> {
         // `self.config.has_header` controls whether to skip reading the 1st line header
         // If the .csv file is read in parallel and this `CsvOpener` is only reading some middle
@@ -368,6 +476,20 @@ impl FileOpener for CsvOpener {
 
         let store = self.config.object_store.clone();
 
+        if self.config.reads_whole_files() {
+            let config = Arc::clone(&self.config);
+            return Ok(Box::pin(async move {
+                if matches!(&file_meta.range, Some(range) if range.start != 0) {
+                    return Ok(futures::stream::empty().boxed());
+                }
+                let file_path = file_meta.location().to_string();
+                let result = store.get(file_meta.location()).await?;
+                read_payload(result.payload, None, file_compression_type, move |reader| {
+                    config.record_reader(reader, file_path)
+                })
+            }));
+        }
+
         Ok(Box::pin(async move {
             // Current partition contains bytes [start_byte, end_byte) (might contain incomplete lines at boundaries)
 
@@ -390,6 +512,17 @@ impl FileOpener for CsvOpener {
 
             let result = store.get_opts(file_meta.location(), options).await?;
 
+            if config.reads_records() {
+                let range = file_meta.range.is_some().then_some(result.range);
+                let file_path = file_meta.location().to_string();
+                return read_payload(
+                    result.payload,
+                    range,
+                    file_compression_type,
+                    move |reader| config.record_reader(reader, file_path),
+                );
+            }
+
             match result.payload {
                 GetResultPayload::File(mut file, _) => {
                     let is_whole_file_scanned = file_meta.range.is_none();
@@ -979,6 +1112,34 @@ mod tests {
         crate::assert_batches_eq!(expected, &result);
     }
 
+    #[tokio::test]
+    async fn csv_exec_cancelled() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let store = object_store::memory::InMemory::new();
+        let path = object_store::path::Path::from("a.csv");
+        store
+            .put(&path, bytes::Bytes::from("a,b\n1,2\n3,4\n"))
+            .await?;
+        let url = Url::parse("memory://").unwrap();
+        session_ctx
+            .runtime_env()
+            .register_object_store(&url, Arc::new(store));
+
+        let plan = session_ctx
+            .read_csv("memory:///", CsvReadOptions::new())
+            .await?
+            .create_physical_plan()
+            .await?;
+        assert!(plan.as_any().is::<CsvExec>());
+
+        let task_ctx = session_ctx.task_ctx();
+        task_ctx.cancellation_token().cancel();
+        let mut stream = plan.execute(0, task_ctx)?;
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(matches!(err, DataFusionError::Cancelled(_)), "{err}");
+        Ok(())
+    }
+
     #[tokio::test]
     async fn write_csv_results_error_handling() -> Result<()> {
         let ctx = SessionContext::new();
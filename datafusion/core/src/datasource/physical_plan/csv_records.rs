@@ -0,0 +1,544 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Reading CSV files record by record, to handle malformed rows with a
+//! [`CsvBadRowPolicy`] and quoted newlines
+
+use std::any::Any;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::datasource::{TableProvider, TableType};
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::SessionState;
+use crate::logical_expr::Expr;
+use crate::physical_plan::memory::MemoryExec;
+use crate::physical_plan::ExecutionPlan;
+
+use arrow::array::{
+    new_null_array, Array, ArrayRef, BooleanArray, StringArray, StringBuilder,
+    UInt64Array,
+};
+use arrow::compute::{concat, filter_record_batch};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use async_trait::async_trait;
+use csv::ByteRecord;
+use datafusion_common::exec_err;
+use parking_lot::Mutex;
+
+/// What to do with the rows of CSV files that can not be read as rows of the
+/// schema of the files: rows with more or fewer fields than the schema, and
+/// rows with values that can not be parsed as the type of their column.
+#[derive(Debug, Clone, Default)]
+pub enum CsvBadRowPolicy {
+    /// Fail the query
+    #[default]
+    Fail,
+    /// Skip the row
+    Skip,
+    /// Read the values that can not be parsed and the missing fields as
+    /// nulls, and ignore the extra fields
+    NullFill,
+    /// Skip the row, and add it to a [`CsvRejects`] table
+    Reject(Arc<CsvRejects>),
+}
+
+impl fmt::Display for CsvBadRowPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Fail => write!(f, "fail"),
+            Self::Skip => write!(f, "skip"),
+            Self::NullFill => write!(f, "null"),
+            Self::Reject(_) => write!(f, "reject"),
+        }
+    }
+}
+
+/// The rows of CSV files that a [`CsvBadRowPolicy::Reject`] policy rejected,
+/// with the path of their file and their line number in it.
+///
+/// Register it as a table to query the rows rejected so far. In SQL, the
+/// `'bad_rows' 'reject'` option of `CREATE EXTERNAL TABLE` registers it with
+/// the name of the `rejects_table` option:
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use datafusion::datasource::physical_plan::{CsvBadRowPolicy, CsvRejects};
+/// # use datafusion::prelude::*;
+/// # async fn f() -> datafusion::error::Result<()> {
+/// let ctx = SessionContext::new();
+/// let rejects = Arc::new(CsvRejects::new());
+/// let options = CsvReadOptions::new()
+///     .bad_rows(CsvBadRowPolicy::Reject(Arc::clone(&rejects)));
+/// ctx.register_csv("t", "tests/data/example.csv", options).await?;
+/// ctx.register_table("t_rejects", rejects)?;
+///
+/// ctx.sql("SELECT * FROM t").await?.collect().await?;
+/// ctx.sql("SELECT file_path, line FROM t_rejects").await?.show().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct CsvRejects {
+    rows: Mutex<Vec<RejectedRow>>,
+}
+
+#[derive(Debug)]
+struct RejectedRow {
+    file_path: String,
+    line: u64,
+    reason: String,
+    row: String,
+}
+
+impl CsvRejects {
+    /// Create an empty table of rejected rows
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of rejected rows
+    pub fn len(&self) -> usize {
+        self.rows.lock().len()
+    }
+
+    /// Whether no row was rejected
+    pub fn is_empty(&self) -> bool {
+        self.rows.lock().is_empty()
+    }
+
+    /// Remove all the rejected rows
+    pub fn clear(&self) {
+        self.rows.lock().clear()
+    }
+
+    /// The rejected rows, with the columns `file_path`, `line`, `reason`
+    /// and `row`, the text of the row
+    pub fn to_batch(&self) -> Result<RecordBatch> {
+        let rows = self.rows.lock();
+        let file_paths = rows.iter().map(|r| Some(r.file_path.as_str()));
+        let lines = rows.iter().map(|r| r.line);
+        let reasons = rows.iter().map(|r| Some(r.reason.as_str()));
+        let texts = rows.iter().map(|r| Some(r.row.as_str()));
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(file_paths.collect::<arrow::array::StringArray>()),
+            Arc::new(UInt64Array::from_iter_values(lines)),
+            Arc::new(reasons.collect::<arrow::array::StringArray>()),
+            Arc::new(texts.collect::<arrow::array::StringArray>()),
+        ];
+        Ok(RecordBatch::try_new(rejects_schema(), columns)?)
+    }
+
+    fn push(&self, row: RejectedRow) {
+        self.rows.lock().push(row)
+    }
+}
+
+fn rejects_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("file_path", DataType::Utf8, false),
+        Field::new("line", DataType::UInt64, false),
+        Field::new("reason", DataType::Utf8, false),
+        Field::new("row", DataType::Utf8, false),
+    ]))
+}
+
+#[async_trait]
+impl TableProvider for CsvRejects {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        rejects_schema()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let batch = self.to_batch()?;
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![batch]],
+            rejects_schema(),
+            projection.cloned(),
+        )?))
+    }
+}
+
+/// Reads the records of a CSV file one at a time into batches of the
+/// projected columns of `file_schema`, and applies a [`CsvBadRowPolicy`] to
+/// the rows that can not be read.
+pub(crate) struct CsvRecordReader<R: Read> {
+    reader: csv::Reader<R>,
+    file_schema: SchemaRef,
+    projection: Vec<usize>,
+    projected_schema: SchemaRef,
+    batch_size: usize,
+    bad_rows: CsvBadRowPolicy,
+    delimiter: u8,
+    file_path: String,
+    /// Whether the first record is a header to skip
+    skip_header: bool,
+    done: bool,
+}
+
+impl<R: Read> CsvRecordReader<R> {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        reader: R,
+        file_schema: SchemaRef,
+        projection: Option<Vec<usize>>,
+        batch_size: usize,
+        delimiter: u8,
+        quote: u8,
+        escape: Option<u8>,
+        bad_rows: CsvBadRowPolicy,
+    ) -> Result<Self> {
+        let reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(delimiter)
+            .quote(quote)
+            .escape(escape)
+            .from_reader(reader);
+        let projection =
+            projection.unwrap_or_else(|| (0..file_schema.fields().len()).collect());
+        let projected_schema = Arc::new(file_schema.project(&projection)?);
+        Ok(Self {
+            reader,
+            file_schema,
+            projection,
+            projected_schema,
+            batch_size,
+            bad_rows,
+            delimiter,
+            file_path: String::new(),
+            skip_header: false,
+            done: false,
+        })
+    }
+
+    /// Set the path of the file in the errors and the rejected rows
+    pub(crate) fn with_file_path(mut self, file_path: String) -> Self {
+        self.file_path = file_path;
+        self
+    }
+
+    /// Skip the first record, the header of the file
+    pub(crate) fn with_header(mut self, has_header: bool) -> Self {
+        self.skip_header = has_header;
+        self
+    }
+
+    fn read_batch(&mut self) -> Result<Option<RecordBatch>> {
+        let mut builders: Vec<_> = self
+            .projection
+            .iter()
+            .map(|_| StringBuilder::new())
+            .collect();
+        // the line of each row, and its text if it may be rejected
+        let mut lines = vec![];
+        let mut texts = vec![];
+        let mut record = ByteRecord::new();
+        while lines.len() < self.batch_size {
+            if !self
+                .reader
+                .read_byte_record(&mut record)
+                .map_err(|e| DataFusionError::External(Box::new(e)))?
+            {
+                self.done = true;
+                break;
+            }
+            let line = record.position().map(|p| p.line()).unwrap_or_default();
+            if std::mem::take(&mut self.skip_header) {
+                continue;
+            }
+
+            let text = match self.bad_rows {
+                CsvBadRowPolicy::Reject(_) => self.row_text(&record),
+                _ => String::new(),
+            };
+            match self.record_values(&record) {
+                Ok(values) => {
+                    for (builder, value) in builders.iter_mut().zip(values) {
+                        builder.append_option(value);
+                    }
+                    lines.push(line);
+                    texts.push(text);
+                }
+                Err(reason) => self.bad_row(line, reason, text)?,
+            }
+        }
+        if lines.is_empty() {
+            return Ok(None);
+        }
+
+        // parse the values as the types of their columns
+        let num_rows = lines.len();
+        let mut bad = vec![None; num_rows];
+        let mut columns = Vec::with_capacity(builders.len());
+        for (mut builder, field) in
+            builders.into_iter().zip(self.projected_schema.fields())
+        {
+            let values = builder.finish();
+            let column = match parse_values(&values, field) {
+                Ok(column) => column,
+                Err(_) => {
+                    // parse the values one at a time to find the bad ones
+                    let mut parsed = Vec::with_capacity(num_rows);
+                    for (row, reason) in bad.iter_mut().enumerate() {
+                        let value = values.slice(row, 1);
+                        match parse_values(&value, field) {
+                            Ok(value) => parsed.push(value),
+                            Err(_) => {
+                                reason.get_or_insert_with(|| {
+                                    format!(
+                                        "can not parse '{}' as {} in column {}",
+                                        values.value(row),
+                                        field.data_type(),
+                                        field.name()
+                                    )
+                                });
+                                parsed.push(new_null_array(field.data_type(), 1));
+                            }
+                        }
+                    }
+                    let parsed: Vec<_> = parsed.iter().map(|a| a.as_ref()).collect();
+                    concat(&parsed)?
+                }
+            };
+            columns.push(column);
+        }
+        let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+        let batch = RecordBatch::try_new_with_options(
+            self.projected_schema.clone(),
+            columns,
+            &options,
+        )?;
+        if matches!(self.bad_rows, CsvBadRowPolicy::NullFill)
+            || bad.iter().all(Option::is_none)
+        {
+            return Ok(Some(batch));
+        }
+
+        for ((reason, line), text) in bad.iter().zip(lines).zip(texts) {
+            if let Some(reason) = reason {
+                self.bad_row(line, reason.clone(), text)?;
+            }
+        }
+        let keep: BooleanArray = bad.iter().map(|r| Some(r.is_none())).collect();
+        Ok(Some(filter_record_batch(&batch, &keep)?))
+    }
+
+    /// The values of the projected fields of `record`, with empty values as
+    /// nulls, or the reason why the record is malformed
+    fn record_values<'a>(
+        &self,
+        record: &'a ByteRecord,
+    ) -> std::result::Result<Vec<Option<&'a str>>, String> {
+        let null_fill = matches!(self.bad_rows, CsvBadRowPolicy::NullFill);
+        let num_fields = self.file_schema.fields().len();
+        if record.len() != num_fields && !null_fill {
+            return Err(format!(
+                "expected {num_fields} fields, found {}",
+                record.len()
+            ));
+        }
+        self.projection
+            .iter()
+            .map(|idx| match record.get(*idx) {
+                None | Some(b"") => Ok(None),
+                Some(value) => match std::str::from_utf8(value) {
+                    Ok(value) => Ok(Some(value)),
+                    Err(_) if null_fill => Ok(None),
+                    Err(_) => Err(format!("invalid UTF-8 in field {}", idx + 1)),
+                },
+            })
+            .collect()
+    }
+
+    fn row_text(&self, record: &ByteRecord) -> String {
+        let fields: Vec<_> = record.iter().map(String::from_utf8_lossy).collect();
+        fields.join(&char::from(self.delimiter).to_string())
+    }
+
+    fn bad_row(&self, line: u64, reason: String, text: String) -> Result<()> {
+        match &self.bad_rows {
+            CsvBadRowPolicy::Fail => {
+                exec_err!(
+                    "Malformed row at line {line} of CSV file {}: {reason}",
+                    self.file_path
+                )
+            }
+            CsvBadRowPolicy::Skip | CsvBadRowPolicy::NullFill => Ok(()),
+            CsvBadRowPolicy::Reject(rejects) => {
+                rejects.push(RejectedRow {
+                    file_path: self.file_path.clone(),
+                    line,
+                    reason,
+                    row: text,
+                });
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parse the string `values` of the column `field` with the CSV decoder of
+/// arrow, the parser of the other CSV reads
+fn parse_values(values: &StringArray, field: &Field) -> Result<ArrayRef> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for value in values {
+        // empty values are read as nulls
+        writer
+            .write_record([value.unwrap_or_default()])
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| DataFusionError::External(Box::new(e.into_error())))?;
+
+    let schema = Schema::new(vec![field.clone().with_nullable(true)]);
+    let mut decoder = arrow::csv::ReaderBuilder::new(Arc::new(schema))
+        .with_batch_size(values.len().max(1))
+        .build_decoder();
+    let mut buf = bytes.as_slice();
+    loop {
+        let decoded = decoder.decode(buf)?;
+        if decoded == 0 {
+            break;
+        }
+        buf = &buf[decoded..];
+    }
+    match decoder.flush()? {
+        Some(batch) => Ok(Arc::clone(batch.column(0))),
+        None => Ok(new_null_array(field.data_type(), 0)),
+    }
+}
+
+impl<R: Read> Iterator for CsvRecordReader<R> {
+    type Item = Result<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let batch = self.read_batch();
+        if batch.is_err() {
+            self.done = true;
+        }
+        batch.transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Date32Array, Int64Array};
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Utf8, true),
+        ]))
+    }
+
+    fn read(data: &'static str, bad_rows: CsvBadRowPolicy) -> Result<Vec<RecordBatch>> {
+        CsvRecordReader::new(
+            data.as_bytes(),
+            schema(),
+            None,
+            2,
+            b',',
+            b'"',
+            None,
+            bad_rows,
+        )?
+        .with_file_path("f.csv".to_string())
+        .with_header(true)
+        .collect()
+    }
+
+    fn column_a(batches: &[RecordBatch]) -> Vec<Option<i64>> {
+        batches
+            .iter()
+            .flat_map(|b| {
+                let a = b.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+                a.iter().collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    const DATA: &str = "a,b\n1,x\ntwo,y\n3\n4,\"multi\nline\"\n";
+
+    #[test]
+    fn bad_row_policies() -> Result<()> {
+        let err = read(DATA, CsvBadRowPolicy::Fail).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: Malformed row at line 3 of CSV file f.csv: \
+            can not parse 'two' as Int64 in column a"
+        );
+
+        let batches = read(DATA, CsvBadRowPolicy::Skip)?;
+        assert_eq!(column_a(&batches), vec![Some(1), Some(4)]);
+
+        let batches = read(DATA, CsvBadRowPolicy::NullFill)?;
+        assert_eq!(column_a(&batches), vec![Some(1), None, Some(3), Some(4)]);
+
+        let rejects = Arc::new(CsvRejects::new());
+        let batches = read(DATA, CsvBadRowPolicy::Reject(Arc::clone(&rejects)))?;
+        assert_eq!(column_a(&batches), vec![Some(1), Some(4)]);
+        let rejected = rejects.to_batch()?;
+        let lines = rejected.column(1).as_any().downcast_ref::<UInt64Array>();
+        assert_eq!(lines.unwrap().values().to_vec(), vec![3, 4]);
+        let texts = rejected.column(3).as_any().downcast_ref::<StringArray>();
+        assert_eq!(texts.unwrap().value(1), "3");
+        Ok(())
+    }
+
+    #[test]
+    fn quoted_newlines() -> Result<()> {
+        let batches = read(DATA, CsvBadRowPolicy::Skip)?;
+        let b = batches[1].column(1).as_any().downcast_ref::<StringArray>();
+        assert_eq!(b.unwrap().value(0), "multi\nline");
+        Ok(())
+    }
+
+    #[test]
+    fn parse_values_as_the_csv_reader() -> Result<()> {
+        let field = Field::new("d", DataType::Date32, false);
+        let values = StringArray::from(vec![Some("2020-01-02"), None]);
+        let parsed = parse_values(&values, &field)?;
+        let parsed = parsed.as_any().downcast_ref::<Date32Array>().unwrap();
+        assert_eq!(parsed.iter().collect::<Vec<_>>(), vec![Some(18263), None]);
+
+        let values = StringArray::from(vec!["2020-01-02", "tomorrow"]);
+        assert!(parse_values(&values, &field).is_err());
+        Ok(())
+    }
+}
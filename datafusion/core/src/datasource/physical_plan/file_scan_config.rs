@@ -22,8 +22,11 @@ use std::{
     borrow::Cow, collections::HashMap, fmt::Debug, marker::PhantomData, sync::Arc, vec,
 };
 
-use super::{get_projected_output_ordering, FileGroupPartitioner};
+use super::{get_projected_output_ordering, FileGroupPartitioner, MetadataColumn};
+use crate::datasource::schema_adapter::SchemaAdapterFactory;
 use crate::datasource::{listing::PartitionedFile, object_store::ObjectStoreUrl};
+use crate::physical_plan::metrics::ExecutionPlanMetricsSet;
+use crate::physical_plan::ExecutionProgress;
 use crate::{
     error::{DataFusionError, Result},
     scalar::ScalarValue,
@@ -33,7 +36,7 @@ use arrow::array::{ArrayData, BufferBuilder};
 use arrow::buffer::Buffer;
 use arrow::datatypes::{ArrowNativeType, UInt16Type};
 use arrow_array::{ArrayRef, DictionaryArray, RecordBatch, RecordBatchOptions};
-use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use arrow_schema::{DataType, Field, Fields, Schema, SchemaRef};
 use datafusion_common::stats::Precision;
 use datafusion_common::{exec_err, ColumnStatistics, Statistics};
 use datafusion_physical_expr::LexOrdering;
@@ -90,21 +93,42 @@ pub struct FileScanConfig {
     /// Estimated overall statistics of the files, taking `filters` into account.
     pub statistics: Statistics,
     /// Columns on which to project the data. Indexes that are higher than the
-    /// number of columns of `file_schema` refer to `table_partition_cols`.
+    /// number of columns of `file_schema` refer to `table_partition_cols`,
+    /// and then to `metadata_cols`.
     pub projection: Option<Vec<usize>>,
+    /// Nested fields to read of the projected columns, as paths of field
+    /// names from a column of `file_schema` to a field of a struct nested in
+    /// it. The struct columns that paths start at are read with only the
+    /// fields on these paths, the other columns are read whole.
+    ///
+    /// Only parquet and JSON scans support reading nested fields.
+    pub projection_paths: Vec<Vec<String>>,
     /// The maximum number of records to read from this plan. If `None`,
     /// all records after filtering are returned.
     pub limit: Option<usize>,
     /// The partitioning columns
     pub table_partition_cols: Vec<Field>,
+    /// The virtual columns with the metadata of the files, which follow the
+    /// partitioning columns
+    pub metadata_cols: Vec<MetadataColumn>,
     /// All equivalent lexicographical orderings that describe the schema.
     pub output_ordering: Vec<LexOrdering>,
+    /// Adapts the batches read from the files to `file_schema`. If `None`,
+    /// the [`DefaultSchemaAdapterFactory`] is used, which matches columns by
+    /// name.
+    ///
+    /// [`DefaultSchemaAdapterFactory`]: crate::datasource::schema_adapter::DefaultSchemaAdapterFactory
+    pub schema_adapter_factory: Option<Arc<dyn SchemaAdapterFactory>>,
 }
 
 impl FileScanConfig {
     /// Project the schema and the statistics on the given column indices
     pub fn project(&self) -> (SchemaRef, Statistics, Vec<LexOrdering>) {
-        if self.projection.is_none() && self.table_partition_cols.is_empty() {
+        if self.projection.is_none()
+            && self.projection_paths.is_empty()
+            && self.table_partition_cols.is_empty()
+            && self.metadata_cols.is_empty()
+        {
             return (
                 Arc::clone(&self.file_schema),
                 self.statistics.clone(),
@@ -115,22 +139,33 @@ impl FileScanConfig {
         let proj_iter: Box<dyn Iterator<Item = usize>> = match &self.projection {
             Some(proj) => Box::new(proj.iter().copied()),
             None => Box::new(
-                0..(self.file_schema.fields().len() + self.table_partition_cols.len()),
+                0..(self.file_schema.fields().len()
+                    + self.table_partition_cols.len()
+                    + self.metadata_cols.len()),
             ),
         };
 
+        let file_schema = prune_nested_fields(&self.file_schema, &self.projection_paths);
         let mut table_fields = vec![];
         let mut table_cols_stats = vec![];
         for idx in proj_iter {
             if idx < self.file_schema.fields().len() {
-                let field = self.file_schema.field(idx);
+                let field = file_schema.field(idx);
                 table_fields.push(field.clone());
                 table_cols_stats.push(self.statistics.column_statistics[idx].clone())
-            } else {
-                let partition_idx = idx - self.file_schema.fields().len();
-                table_fields.push(self.table_partition_cols[partition_idx].to_owned());
+            } else if let Some(field) = self
+                .table_partition_cols
+                .get(idx - self.file_schema.fields().len())
+            {
+                table_fields.push(field.to_owned());
                 // TODO provide accurate stat for partition column (#1186)
                 table_cols_stats.push(ColumnStatistics::new_unknown())
+            } else {
+                let metadata_idx = idx
+                    - self.file_schema.fields().len()
+                    - self.table_partition_cols.len();
+                table_fields.push(self.metadata_cols[metadata_idx].field());
+                table_cols_stats.push(ColumnStatistics::new_unknown())
             }
         }
 
@@ -149,6 +184,36 @@ impl FileScanConfig {
         (table_schema, table_stats, projected_output_ordering)
     }
 
+    /// Returns the progress of a scan of this configuration that recorded
+    /// its `metrics` so far.
+    ///
+    /// The total number of bytes is the size of the files, or file ranges,
+    /// to scan, and the total number of rows comes from the
+    /// [`Self::statistics`], such as the row counts in parquet footers, if
+    /// known. The bytes consumed are the `bytes_scanned` metric, which is
+    /// only recorded by some formats such as parquet.
+    pub fn progress(&self, metrics: &ExecutionPlanMetricsSet) -> ExecutionProgress {
+        let metrics = metrics.clone_inner();
+        let total_bytes = self
+            .file_groups
+            .iter()
+            .flatten()
+            .map(|file| match &file.range {
+                Some(range) => (range.end - range.start) as usize,
+                None => file.object_meta.size,
+            })
+            .sum();
+        ExecutionProgress {
+            rows: metrics.output_rows().unwrap_or(0),
+            bytes: metrics
+                .sum_by_name("bytes_scanned")
+                .map(|v| v.as_usize())
+                .unwrap_or(0),
+            total_rows: self.statistics.num_rows.get_value().copied(),
+            total_bytes: Some(total_bytes),
+        }
+    }
+
     #[allow(unused)] // Only used by avro
     pub(crate) fn projected_file_column_names(&self) -> Option<Vec<String>> {
         self.projection.as_ref().map(|p| {
@@ -160,6 +225,27 @@ impl FileScanConfig {
         })
     }
 
+    /// Returns the position of the [`MetadataColumn::RowIndex`] column among
+    /// the projected file columns, if it is projected. The batches read from
+    /// the files must include it at that position.
+    pub(crate) fn row_index_position(&self) -> Option<usize> {
+        let num_file_cols = self.file_schema.fields().len();
+        let metadata_offset = num_file_cols + self.table_partition_cols.len();
+        let row_index = metadata_offset
+            + self
+                .metadata_cols
+                .iter()
+                .position(|c| *c == MetadataColumn::RowIndex)?;
+        let projection: Vec<usize> = match &self.projection {
+            Some(projection) => projection.clone(),
+            None => (0..metadata_offset + self.metadata_cols.len()).collect(),
+        };
+        projection
+            .into_iter()
+            .filter(|idx| *idx < num_file_cols || *idx == row_index)
+            .position(|idx| idx == row_index)
+    }
+
     pub(crate) fn file_column_projection_indices(&self) -> Option<Vec<usize>> {
         self.projection.as_ref().map(|p| {
             p.iter()
@@ -183,6 +269,53 @@ impl FileScanConfig {
     }
 }
 
+/// Returns `schema` with the struct columns that `paths` start at restricted to
+/// the nested fields on the paths, see [`FileScanConfig::projection_paths`]
+pub(crate) fn prune_nested_fields(schema: &Schema, paths: &[Vec<String>]) -> Schema {
+    if paths.is_empty() {
+        return schema.clone();
+    }
+    let paths: Vec<&[String]> = paths.iter().map(|path| path.as_slice()).collect();
+    let fields: Vec<Field> = schema
+        .fields()
+        .iter()
+        .map(|field| match child_paths(&paths, field.name()) {
+            child_paths if child_paths.is_empty() => field.as_ref().clone(),
+            child_paths => prune_field(field, &child_paths),
+        })
+        .collect();
+    Schema::new(fields).with_metadata(schema.metadata().clone())
+}
+
+/// Returns the remainders of the `paths` that start at the field `name`
+fn child_paths<'a>(paths: &[&'a [String]], name: &str) -> Vec<&'a [String]> {
+    paths
+        .iter()
+        .filter_map(|path| match path.split_first() {
+            Some((first, rest)) if first == name => Some(rest),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Restricts `field` to its nested fields on `paths`, relative to it. A path
+/// that ends at `field` selects the whole field.
+fn prune_field(field: &Field, paths: &[&[String]]) -> Field {
+    match field.data_type() {
+        DataType::Struct(children) if paths.iter().all(|path| !path.is_empty()) => {
+            let children: Fields = children
+                .iter()
+                .filter_map(|child| {
+                    let paths = child_paths(paths, child.name());
+                    (!paths.is_empty()).then(|| prune_field(child, &paths))
+                })
+                .collect();
+            field.clone().with_data_type(DataType::Struct(children))
+        }
+        _ => field.clone(),
+    }
+}
+
 /// A helper that projects partition columns into the file record batches.
 ///
 /// One interesting trick is the usage of a cache for the key buffers of the partition column
@@ -492,6 +625,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn physical_plan_config_with_metadata_cols() {
+        let file_schema = aggr_test_schema();
+        let num_file_cols = file_schema.fields().len();
+        let mut conf = config_for_projection(
+            Arc::clone(&file_schema),
+            // _row_index, c2, _file_path, c1
+            Some(vec![num_file_cols + 2, 1, num_file_cols + 1, 0]),
+            Statistics::new_unknown(&file_schema),
+            to_partition_cols(vec![(
+                "date".to_owned(),
+                wrap_partition_type_in_dict(DataType::Utf8),
+            )]),
+        );
+        conf.metadata_cols = vec![MetadataColumn::FilePath, MetadataColumn::RowIndex];
+
+        let (proj_schema, proj_statistics, _) = conf.project();
+        assert_eq!(
+            columns(&proj_schema),
+            vec!["_row_index", "c2", "_file_path", "c1"]
+        );
+        assert_eq!(proj_statistics.column_statistics.len(), 4);
+        assert_eq!(proj_schema.field(0).data_type(), &DataType::UInt64);
+        // the row index is read along with c2 and c1
+        assert_eq!(conf.row_index_position(), Some(0));
+        assert_eq!(conf.file_column_projection_indices(), Some(vec![1, 0]));
+
+        conf.projection = None;
+        let (proj_schema, ..) = conf.project();
+        assert_eq!(proj_schema.fields().len(), num_file_cols + 3);
+        assert_eq!(conf.row_index_position(), Some(num_file_cols));
+    }
+
+    #[test]
+    fn physical_plan_config_with_projection_paths() {
+        let x = Field::new("x", DataType::Int32, true);
+        let y = Field::new("y", DataType::Utf8, true);
+        let inner = Field::new_struct("inner", vec![x.clone(), y], true);
+        let z = Field::new("z", DataType::Int64, true);
+        let file_schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new_struct("s", vec![inner, z.clone()], true),
+        ]));
+        let mut conf = config_for_projection(
+            Arc::clone(&file_schema),
+            Some(vec![1, 0]),
+            Statistics::new_unknown(&file_schema),
+            vec![],
+        );
+        conf.projection_paths = vec![
+            vec!["s".to_owned(), "inner".to_owned(), "x".to_owned()],
+            vec!["s".to_owned(), "z".to_owned()],
+        ];
+
+        let (proj_schema, ..) = conf.project();
+        let expected = Schema::new(vec![
+            Field::new_struct(
+                "s",
+                vec![Field::new_struct("inner", vec![x], true), z],
+                true,
+            ),
+            Field::new("a", DataType::Int32, true),
+        ]);
+        assert_eq!(proj_schema.as_ref(), &expected);
+    }
+
     #[test]
     fn physical_plan_config_with_projection() {
         let file_schema = aggr_test_schema();
@@ -705,6 +904,9 @@ mod tests {
             statistics,
             table_partition_cols,
             output_ordering: vec![],
+            schema_adapter_factory: None,
+            metadata_cols: vec![],
+            projection_paths: vec![],
         }
     }
 
@@ -29,9 +29,9 @@ use std::time::Instant;
 
 use crate::datasource::listing::PartitionedFile;
 use crate::datasource::physical_plan::{
-    FileMeta, FileScanConfig, PartitionColumnProjector,
+    FileMeta, FileScanConfig, MetadataColumn, PartitionColumnProjector,
 };
-use crate::error::Result;
+use crate::error::{DataFusionError, Result};
 use crate::physical_plan::metrics::{
     BaselineMetrics, Count, ExecutionPlanMetricsSet, MetricBuilder, Time,
 };
@@ -40,7 +40,7 @@ use crate::physical_plan::RecordBatchStream;
 use arrow::datatypes::SchemaRef;
 use arrow::error::ArrowError;
 use arrow::record_batch::RecordBatch;
-use datafusion_common::ScalarValue;
+use datafusion_common::{not_impl_err, ScalarValue};
 
 use futures::future::BoxFuture;
 use futures::stream::BoxStream;
@@ -72,6 +72,13 @@ pub trait FileOpener: Unpin {
     /// Asynchronously open the specified file and return a stream
     /// of [`RecordBatch`]
     fn open(&self, file_meta: FileMeta) -> Result<FileOpenFuture>;
+
+    /// Whether the batches of the opened files include the
+    /// [`MetadataColumn::RowIndex`] column when it is projected, at the
+    /// position returned by `FileScanConfig::row_index_position`
+    fn supports_row_index(&self) -> bool {
+        false
+    }
 }
 
 /// A stream that iterates record batch by record batch, file over file.
@@ -88,8 +95,12 @@ pub struct FileStream<F: FileOpener> {
     /// is not capable of limiting the number of records in the last batch, the file
     /// stream will take care of truncating it.
     file_reader: F,
-    /// The partition column projector
+    /// The partition column projector, which also projects the metadata
+    /// columns that have the same value for all the rows of a file
     pc_projector: PartitionColumnProjector,
+    /// The metadata columns that follow the partition columns in the values
+    /// given to `pc_projector`
+    metadata_cols: Vec<MetadataColumn>,
     /// The stream state
     state: FileStreamState,
     /// File stream specific metrics
@@ -253,13 +264,27 @@ impl<F: FileOpener> FileStream<F> {
         file_reader: F,
         metrics: &ExecutionPlanMetricsSet,
     ) -> Result<Self> {
+        if config.row_index_position().is_some() && !file_reader.supports_row_index() {
+            return not_impl_err!(
+                "The {} column is not supported by this file format",
+                MetadataColumn::RowIndex.name()
+            );
+        }
+
         let (projected_schema, ..) = config.project();
+        let metadata_cols: Vec<_> = config
+            .metadata_cols
+            .iter()
+            .copied()
+            .filter(MetadataColumn::is_constant)
+            .collect();
         let pc_projector = PartitionColumnProjector::new(
             projected_schema.clone(),
             &config
                 .table_partition_cols
                 .iter()
                 .map(|x| x.name().clone())
+                .chain(metadata_cols.iter().map(|c| c.name().to_string()))
                 .collect::<Vec<_>>(),
         );
 
@@ -271,6 +296,7 @@ impl<F: FileOpener> FileStream<F> {
             remain: config.limit,
             file_reader,
             pc_projector,
+            metadata_cols,
             state: FileStreamState::Idle,
             file_stream_metrics: FileStreamMetrics::new(metrics, partition),
             baseline_metrics: BaselineMetrics::new(metrics, partition),
@@ -292,7 +318,12 @@ impl<F: FileOpener> FileStream<F> {
     /// Since file opening is mostly IO (and may involve a
     /// bunch of sequential IO), it can be parallelized with decoding.
     fn start_next_file(&mut self) -> Option<Result<(FileOpenFuture, Vec<ScalarValue>)>> {
-        let part_file = self.file_iter.pop_front()?;
+        let mut part_file = self.file_iter.pop_front()?;
+        let metadata_values = self
+            .metadata_cols
+            .iter()
+            .filter_map(|c| c.file_value(&part_file.object_meta));
+        part_file.partition_values.extend(metadata_values);
 
         let file_meta = FileMeta {
             object_meta: part_file.object_meta,
@@ -665,6 +696,9 @@ mod tests {
                 limit: self.limit,
                 table_partition_cols: vec![],
                 output_ordering: vec![],
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
             };
             let metrics_set = ExecutionPlanMetricsSet::new();
             let file_stream = FileStream::new(&config, 0, self.opener, &metrics_set)
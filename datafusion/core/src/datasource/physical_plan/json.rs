@@ -22,7 +22,11 @@ use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::sync::Arc;
 use std::task::Poll;
 
-use super::{calculate_range, FileGroupPartitioner, FileScanConfig, RangeCalculation};
+use super::json_values::{has_raw_values, JsonArrayReader, NormalizedJsonReader};
+use super::{
+    calculate_range, prune_nested_fields, read_payload, FileGroupPartitioner,
+    FileScanConfig, RangeCalculation,
+};
 use crate::datasource::file_format::file_compression_type::FileCompressionType;
 use crate::datasource::listing::ListingTableUrl;
 use crate::datasource::physical_plan::file_stream::{
@@ -32,9 +36,10 @@ use crate::datasource::physical_plan::FileMeta;
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use crate::physical_plan::stream::CooperativeStream;
 use crate::physical_plan::{
-    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
-    Statistics,
+    DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionProgress, Partitioning,
+    SendableRecordBatchStream, Statistics,
 };
 
 use arrow::json::ReaderBuilder;
@@ -59,6 +64,8 @@ pub struct NdJsonExec {
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
     file_compression_type: FileCompressionType,
+    array: bool,
+    unknown_fields_column: Option<String>,
 }
 
 impl NdJsonExec {
@@ -77,13 +84,55 @@ impl NdJsonExec {
             projected_output_ordering,
             metrics: ExecutionPlanMetricsSet::new(),
             file_compression_type,
+            array: false,
+            unknown_fields_column: None,
         }
     }
 
+    /// Set whether each file is a single JSON array of records, rather than
+    /// newline delimited records. Such files are not split into ranges to
+    /// read in parallel.
+    pub fn with_array(mut self, array: bool) -> Self {
+        self.array = array;
+        self
+    }
+
+    /// Set the column to collect the fields of the records that are not in
+    /// the file schema into, as a JSON object
+    pub fn with_unknown_fields_column(
+        mut self,
+        unknown_fields_column: Option<String>,
+    ) -> Self {
+        self.unknown_fields_column = unknown_fields_column;
+        self
+    }
+
+    /// true if each file is a single JSON array of records
+    pub fn array(&self) -> bool {
+        self.array
+    }
+
+    /// The column of the fields of the records that are not in the file schema
+    pub fn unknown_fields_column(&self) -> Option<&str> {
+        self.unknown_fields_column.as_deref()
+    }
+
     /// Ref to the base configs
     pub fn base_config(&self) -> &FileScanConfig {
         &self.base_config
     }
+
+    /// Decode only the nested fields on `paths` of the struct columns the
+    /// paths start at, see [`FileScanConfig::projection_paths`]
+    pub fn with_projection_paths(mut self, paths: Vec<Vec<String>>) -> Self {
+        self.base_config.projection_paths = paths;
+        (
+            self.projected_schema,
+            self.projected_statistics,
+            self.projected_output_ordering,
+        ) = self.base_config.project();
+        self
+    }
 }
 
 impl DisplayAs for NdJsonExec {
@@ -93,7 +142,14 @@ impl DisplayAs for NdJsonExec {
         f: &mut std::fmt::Formatter,
     ) -> std::fmt::Result {
         write!(f, "JsonExec: ")?;
-        self.base_config.fmt_as(t, f)
+        self.base_config.fmt_as(t, f)?;
+        if self.array {
+            write!(f, ", array=true")?;
+        }
+        if let Some(column) = &self.unknown_fields_column {
+            write!(f, ", unknown_fields_column={column}")?;
+        }
+        Ok(())
     }
 }
 
@@ -139,6 +195,11 @@ impl ExecutionPlan for NdJsonExec {
         target_partitions: usize,
         config: &datafusion_common::config::ConfigOptions,
     ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        // The arrays of array files can not be split
+        if self.array {
+            return Ok(None);
+        }
+
         let repartition_file_min_size = config.optimizer.repartition_file_min_size;
         let preserve_order_within_groups = self.output_ordering().is_some();
         let file_groups = &self.base_config.file_groups;
@@ -164,22 +225,36 @@ impl ExecutionPlan for NdJsonExec {
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         let batch_size = context.session_config().batch_size();
-        let (projected_schema, ..) = self.base_config.project();
+        // the partition and metadata columns are added to the batches of the
+        // file columns by the `FileStream`
+        let file_schema = Arc::new(prune_nested_fields(
+            &self.base_config.file_schema,
+            &self.base_config.projection_paths,
+        ));
+        let projected_schema = match self.base_config.file_column_projection_indices() {
+            Some(projection) => Arc::new(file_schema.project(&projection)?),
+            None => Arc::clone(&file_schema),
+        };
 
         let object_store = context
             .runtime_env()
             .object_store(&self.base_config.object_store_url)?;
-        let opener = JsonOpener {
+        let mut opener = JsonOpener::new(
             batch_size,
             projected_schema,
-            file_compression_type: self.file_compression_type.to_owned(),
+            self.file_compression_type.to_owned(),
             object_store,
-        };
+        )
+        .with_array(self.array);
+        if self.unknown_fields_column.is_some() || has_raw_values(file_schema.fields()) {
+            opener = opener
+                .with_normalized_records(file_schema, self.unknown_fields_column.clone());
+        }
 
         let stream =
             FileStream::new(&self.base_config, partition, opener, &self.metrics)?;
 
-        Ok(Box::pin(stream) as SendableRecordBatchStream)
+        Ok(Box::pin(CooperativeStream::new(Box::pin(stream), &context)))
     }
 
     fn statistics(&self) -> Result<Statistics> {
@@ -189,6 +264,10 @@ impl ExecutionPlan for NdJsonExec {
     fn metrics(&self) -> Option<MetricsSet> {
         Some(self.metrics.clone_inner())
     }
+
+    fn progress(&self) -> ExecutionProgress {
+        self.base_config.progress(&self.metrics)
+    }
 }
 
 /// A [`FileOpener`] that opens a JSON file and yields a [`FileOpenFuture`]
@@ -197,6 +276,10 @@ pub struct JsonOpener {
     projected_schema: SchemaRef,
     file_compression_type: FileCompressionType,
     object_store: Arc<dyn ObjectStore>,
+    array: bool,
+    /// The file schema and the column of the unknown fields, to normalize the
+    /// records with
+    normalize: Option<(SchemaRef, Option<String>)>,
 }
 
 impl JsonOpener {
@@ -212,8 +295,28 @@ impl JsonOpener {
             projected_schema,
             file_compression_type,
             object_store,
+            array: false,
+            normalize: None,
         }
     }
+
+    /// Read each file as a single JSON array of records
+    pub fn with_array(mut self, array: bool) -> Self {
+        self.array = array;
+        self
+    }
+
+    /// Read the values of the `Utf8` fields of `file_schema` that are not
+    /// strings as JSON text, and collect the fields that are not in
+    /// `file_schema` into the `unknown_fields_column`, if any
+    pub fn with_normalized_records(
+        mut self,
+        file_schema: SchemaRef,
+        unknown_fields_column: Option<String>,
+    ) -> Self {
+        self.normalize = Some((file_schema, unknown_fields_column));
+        self
+    }
 }
 
 impl FileOpener for JsonOpener {
@@ -228,13 +331,37 @@ impl FileOpener for JsonOpener {
     /// 2. The last line of the partition is the line in which the byte at position `end - 1` resides.
     ///
     /// See [`CsvOpener`](super::CsvOpener) for an example.
+    ///
+    /// Array files can not be split into ranges: the partition of the range
+    /// starting at 0 reads the whole file, and the other partitions nothing.
     fn open(&self, file_meta: FileMeta) -> Result<FileOpenFuture> {
         let store = self.object_store.clone();
         let schema = self.projected_schema.clone();
         let batch_size = self.batch_size;
         let file_compression_type = self.file_compression_type.to_owned();
+        let array = self.array;
+        let normalize = self.normalize.clone();
 
         Ok(Box::pin(async move {
+            if array {
+                if matches!(&file_meta.range, Some(range) if range.start != 0) {
+                    return Ok(futures::stream::empty().boxed());
+                }
+                let result = store.get(file_meta.location()).await?;
+                return read_payload(
+                    result.payload,
+                    None,
+                    file_compression_type,
+                    move |reader| {
+                        let reader =
+                            normalized_reader(JsonArrayReader::new(reader), normalize);
+                        Ok(ReaderBuilder::new(schema)
+                            .with_batch_size(batch_size)
+                            .build(BufReader::new(reader))?)
+                    },
+                );
+            }
+
             let calculated_range = calculate_range(&file_meta, &store).await?;
 
             let range = match calculated_range {
@@ -254,6 +381,21 @@ impl FileOpener for JsonOpener {
 
             let result = store.get_opts(file_meta.location(), options).await?;
 
+            if normalize.is_some() {
+                let range = file_meta.range.is_some().then_some(result.range);
+                return read_payload(
+                    result.payload,
+                    range,
+                    file_compression_type,
+                    move |reader| {
+                        let reader = normalized_reader(reader, normalize);
+                        Ok(ReaderBuilder::new(schema)
+                            .with_batch_size(batch_size)
+                            .build(BufReader::new(reader))?)
+                    },
+                );
+            }
+
             match result.payload {
                 GetResultPayload::File(mut file, _) => {
                     let bytes = match file_meta.range {
@@ -311,6 +453,19 @@ impl FileOpener for JsonOpener {
     }
 }
 
+/// Normalize the records of `reader`, see [`JsonOpener::with_normalized_records`]
+fn normalized_reader(
+    reader: impl Read + Send + 'static,
+    normalize: Option<(SchemaRef, Option<String>)>,
+) -> Box<dyn Read + Send> {
+    match normalize {
+        Some((file_schema, unknown_fields_column)) => Box::new(
+            NormalizedJsonReader::new(reader, file_schema, unknown_fields_column),
+        ),
+        None => Box::new(reader),
+    }
+}
+
 pub async fn plan_to_json(
     task_ctx: Arc<TaskContext>,
     plan: Arc<dyn ExecutionPlan>,
@@ -376,6 +531,7 @@ mod tests {
     use crate::datasource::file_format::{json::JsonFormat, FileFormat};
     use crate::datasource::listing::PartitionedFile;
     use crate::datasource::object_store::ObjectStoreUrl;
+    use crate::datasource::physical_plan::JSON_RAW_VALUES_METADATA_KEY;
     use crate::execution::context::SessionState;
     use crate::prelude::NdJsonReadOptions;
     use crate::prelude::*;
@@ -517,6 +673,9 @@ mod tests {
                 limit: Some(3),
                 table_partition_cols: vec![],
                 output_ordering: vec![],
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
             },
             file_compression_type.to_owned(),
         );
@@ -595,6 +754,9 @@ mod tests {
                 limit: Some(3),
                 table_partition_cols: vec![],
                 output_ordering: vec![],
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
             },
             file_compression_type.to_owned(),
         );
@@ -642,6 +804,9 @@ mod tests {
                 limit: None,
                 table_partition_cols: vec![],
                 output_ordering: vec![],
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
             },
             file_compression_type.to_owned(),
         );
@@ -694,6 +859,9 @@ mod tests {
                 limit: None,
                 table_partition_cols: vec![],
                 output_ordering: vec![],
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
             },
             file_compression_type.to_owned(),
         );
@@ -846,6 +1014,50 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn json_array_files() -> Result<()> {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.path().join("data.json.gz");
+        let mut encoder = GzEncoder::new(fs::File::create(&path)?, Default::default());
+        encoder.write_all(
+            br#"[{"a": 1, "s": {"b": "x"}}, {"a": 2, "s": {"c": 3}}, {"a": 3, "s": 4}]"#,
+        )?;
+        encoder.finish()?;
+
+        // read the file in chunks, as a stream of bytes
+        let ctx = SessionContext::new();
+        let store = ChunkedStore::new(Arc::new(LocalFileSystem::new()), 8);
+        let url = Url::parse("file://").unwrap();
+        ctx.runtime_env()
+            .register_object_store(&url, Arc::new(store));
+
+        let options = NdJsonReadOptions::default()
+            .array(true)
+            .file_extension(".json.gz")
+            .file_compression_type(FileCompressionType::GZIP);
+        let df = ctx.read_json(path.to_str().unwrap(), options).await?;
+        let batches = df.collect().await?;
+        let expected = [
+            "+---+-----------+",
+            "| a | s         |",
+            "+---+-----------+",
+            "| 1 | {\"b\":\"x\"} |",
+            "| 2 | {\"c\":3}   |",
+            "| 3 | 4         |",
+            "+---+-----------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        let schema = batches[0].schema();
+        let s = schema.field_with_name("s")?;
+        assert!(s.metadata().contains_key(JSON_RAW_VALUES_METADATA_KEY));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn ndjson_schema_infer_max_records() -> Result<()> {
         async fn read_test_data(schema_infer_max_records: usize) -> Result<SchemaRef> {
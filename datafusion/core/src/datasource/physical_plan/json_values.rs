@@ -0,0 +1,337 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Reading JSON files that are a single top-level array, and JSON records
+//! whose values do not have the types of the schema of the files
+
+use std::io::{self, BufReader, Read};
+
+use crate::error::{DataFusionError, Result};
+
+use arrow::datatypes::{DataType, Field, Fields, SchemaRef};
+use datafusion_common::exec_err;
+use serde_json::{Map, Value};
+
+/// The metadata key of the `Utf8` fields of JSON files whose values have
+/// conflicting types, such as objects in some records and numbers in others.
+/// The values of such fields that are not strings are read as JSON text.
+pub const JSON_RAW_VALUES_METADATA_KEY: &str = "datafusion.json.raw_values";
+
+/// Whether some fields of `fields`, or of their nested fields, have the
+/// [`JSON_RAW_VALUES_METADATA_KEY`]
+pub(crate) fn has_raw_values(fields: &Fields) -> bool {
+    fields.iter().any(|field| field_has_raw_values(field))
+}
+
+fn field_has_raw_values(field: &Field) -> bool {
+    field.metadata().contains_key(JSON_RAW_VALUES_METADATA_KEY)
+        || match field.data_type() {
+            DataType::Struct(fields) => has_raw_values(fields),
+            DataType::List(field) | DataType::LargeList(field) => {
+                field_has_raw_values(field)
+            }
+            _ => false,
+        }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum ArrayState {
+    /// Before the `[` of the array
+    #[default]
+    Start,
+    /// Between the `[` and `]` of the array
+    Values,
+    /// After the `]` of the array
+    End,
+}
+
+/// Rewrites the bytes of a JSON array `[v1, v2, ...]` into the whitespace
+/// separated values `v1 v2 ...` that the arrow JSON reader reads
+#[derive(Debug, Default)]
+struct ArrayUnwrapper {
+    state: ArrayState,
+    /// The nesting of the objects and arrays in the values
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl ArrayUnwrapper {
+    /// Rewrite the next bytes of the array in place
+    fn unwrap(&mut self, bytes: &mut [u8]) -> Result<()> {
+        for byte in bytes.iter_mut() {
+            match self.state {
+                ArrayState::Start => match *byte {
+                    b'[' => {
+                        *byte = b' ';
+                        self.state = ArrayState::Values;
+                    }
+                    b if b.is_ascii_whitespace() => {}
+                    b => {
+                        return exec_err!(
+                            "Expected a JSON array, found '{}'",
+                            char::from(b)
+                        )
+                    }
+                },
+                ArrayState::Values if self.in_string => {
+                    if self.escaped {
+                        self.escaped = false;
+                    } else if *byte == b'\\' {
+                        self.escaped = true;
+                    } else if *byte == b'"' {
+                        self.in_string = false;
+                    }
+                }
+                ArrayState::Values => match *byte {
+                    b'"' => self.in_string = true,
+                    b'{' | b'[' => self.depth += 1,
+                    b'}' | b']' if self.depth > 0 => self.depth -= 1,
+                    b']' => {
+                        *byte = b' ';
+                        self.state = ArrayState::End;
+                    }
+                    b',' if self.depth == 0 => *byte = b'\n',
+                    _ => {}
+                },
+                ArrayState::End => {
+                    if !byte.is_ascii_whitespace() {
+                        return exec_err!(
+                            "Unexpected '{}' after the end of the JSON array",
+                            char::from(*byte)
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the array is complete, or that there was no array
+    fn finish(&self) -> Result<()> {
+        match self.state {
+            ArrayState::Values => exec_err!("Unterminated JSON array"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A [`Read`] of the values of a JSON array file, see [`ArrayUnwrapper`]
+pub(crate) struct JsonArrayReader<R> {
+    inner: R,
+    unwrapper: ArrayUnwrapper,
+}
+
+impl<R: Read> JsonArrayReader<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            inner,
+            unwrapper: ArrayUnwrapper::default(),
+        }
+    }
+}
+
+impl<R: Read> Read for JsonArrayReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        let result = match read {
+            0 => self.unwrapper.finish(),
+            _ => self.unwrapper.unwrap(&mut buf[..read]),
+        };
+        result.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(read)
+    }
+}
+
+/// A [`Read`] of JSON records as newline delimited JSON records with the
+/// types of `file_schema`:
+/// - the values of `Utf8` fields that are not strings are JSON text
+/// - the fields that are not in the schema are collected into the
+///   `unknown_fields_column` as a JSON object, or dropped
+pub(crate) struct NormalizedJsonReader<R: Read> {
+    records: serde_json::StreamDeserializer<
+        'static,
+        serde_json::de::IoRead<BufReader<R>>,
+        Value,
+    >,
+    file_schema: SchemaRef,
+    unknown_fields_column: Option<String>,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl<R: Read> NormalizedJsonReader<R> {
+    pub(crate) fn new(
+        inner: R,
+        file_schema: SchemaRef,
+        unknown_fields_column: Option<String>,
+    ) -> Self {
+        let records = serde_json::Deserializer::from_reader(BufReader::new(inner))
+            .into_iter::<Value>();
+        Self {
+            records,
+            file_schema,
+            unknown_fields_column,
+            buffer: vec![],
+            position: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for NormalizedJsonReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position == self.buffer.len() {
+            let Some(record) = self.records.next().transpose()? else {
+                return Ok(0);
+            };
+            let record = normalize_record(
+                record,
+                self.file_schema.fields(),
+                self.unknown_fields_column.as_deref(),
+            );
+            self.buffer.clear();
+            self.position = 0;
+            serde_json::to_writer(&mut self.buffer, &record)?;
+            self.buffer.push(b'\n');
+        }
+        let read = buf.len().min(self.buffer.len() - self.position);
+        buf[..read].copy_from_slice(&self.buffer[self.position..self.position + read]);
+        self.position += read;
+        Ok(read)
+    }
+}
+
+fn normalize_record(
+    record: Value,
+    fields: &Fields,
+    unknown_fields_column: Option<&str>,
+) -> Value {
+    let Value::Object(record) = record else {
+        return record;
+    };
+    let mut normalized = Map::new();
+    let mut unknown = Map::new();
+    for (name, value) in record {
+        match fields.find(&name) {
+            Some((_, field)) if Some(name.as_str()) != unknown_fields_column => {
+                let value = normalize_value(value, field.data_type());
+                normalized.insert(name, value);
+            }
+            _ => {
+                unknown.insert(name, value);
+            }
+        }
+    }
+    if let Some(column) = unknown_fields_column {
+        if !unknown.is_empty() {
+            let unknown = Value::Object(unknown).to_string();
+            normalized.insert(column.to_string(), Value::String(unknown));
+        }
+    }
+    Value::Object(normalized)
+}
+
+fn normalize_value(value: Value, data_type: &DataType) -> Value {
+    match (data_type, value) {
+        (_, Value::Null) => Value::Null,
+        (DataType::Utf8 | DataType::LargeUtf8, Value::String(value)) => {
+            Value::String(value)
+        }
+        (DataType::Utf8 | DataType::LargeUtf8, value) => Value::String(value.to_string()),
+        (DataType::Struct(fields), value @ Value::Object(_)) => {
+            normalize_record(value, fields, None)
+        }
+        (DataType::List(field) | DataType::LargeList(field), Value::Array(values)) => {
+            let values = values
+                .into_iter()
+                .map(|value| normalize_value(value, field.data_type()))
+                .collect();
+            Value::Array(values)
+        }
+        (_, value) => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::Schema;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn read_to_string(mut reader: impl Read) -> Result<String> {
+        let mut s = String::new();
+        reader.read_to_string(&mut s)?;
+        Ok(s)
+    }
+
+    #[test]
+    fn unwrap_json_array() -> Result<()> {
+        let data = r#" [{"a": [1, 2], "b": "x,]"}, {"a": [3]} ] "#;
+        let values = read_to_string(JsonArrayReader::new(data.as_bytes()))?;
+        assert_eq!(
+            values,
+            r#"  {"a": [1, 2], "b": "x,]"}
+ {"a": [3]}   "#
+        );
+
+        let err = read_to_string(JsonArrayReader::new(r#"{"a": 1}"#.as_bytes()));
+        assert!(err
+            .unwrap_err()
+            .to_string()
+            .contains("Expected a JSON array"));
+        let err = read_to_string(JsonArrayReader::new(r#"[{"a": 1}"#.as_bytes()));
+        assert!(err
+            .unwrap_err()
+            .to_string()
+            .contains("Unterminated JSON array"));
+        Ok(())
+    }
+
+    #[test]
+    fn normalize_json_records() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new(
+                "s",
+                DataType::Struct(vec![Field::new("b", DataType::Utf8, true)].into()),
+                true,
+            ),
+            Field::new("_unknown", DataType::Utf8, true),
+        ]));
+        let data = r#"{"a": {"x": 1}, "s": {"b": [1], "c": 2}, "d": true}
+            {"a": "y", "e": null}"#;
+        let reader = NormalizedJsonReader::new(
+            data.as_bytes(),
+            schema,
+            Some("_unknown".to_string()),
+        );
+        let records = read_to_string(reader)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<serde_json::Result<Vec<Value>>>()
+            .unwrap();
+        assert_eq!(
+            records,
+            vec![
+                json!({"a": "{\"x\":1}", "s": {"b": "[1]"}, "_unknown": "{\"d\":true}"}),
+                json!({"a": "y", "_unknown": "{\"e\":null}"}),
+            ]
+        );
+        Ok(())
+    }
+}
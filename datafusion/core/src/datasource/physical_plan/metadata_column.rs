@@ -0,0 +1,103 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`MetadataColumn`]: virtual columns with the metadata of the scanned files
+
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, TimeUnit};
+use datafusion_common::ScalarValue;
+use object_store::ObjectMeta;
+
+/// A virtual column of a file scan whose values describe the file each row
+/// was read from, rather than being stored in the file.
+///
+/// Metadata columns follow the partition columns in the schema of a
+/// [`FileScanConfig`](super::FileScanConfig).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MetadataColumn {
+    /// `_file_path`: the path of the file in its object store
+    FilePath,
+    /// `_file_size`: the size of the file in bytes
+    FileSize,
+    /// `_last_modified`: the time the file was last modified
+    LastModified,
+    /// `_row_index`: the index of the row in the file, starting from 0.
+    ///
+    /// Only parquet scans can read this column.
+    RowIndex,
+}
+
+impl MetadataColumn {
+    /// All the metadata columns
+    pub const ALL: [MetadataColumn; 4] = [
+        Self::FilePath,
+        Self::FileSize,
+        Self::LastModified,
+        Self::RowIndex,
+    ];
+
+    /// The name of the column
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::FilePath => "_file_path",
+            Self::FileSize => "_file_size",
+            Self::LastModified => "_last_modified",
+            Self::RowIndex => "_row_index",
+        }
+    }
+
+    /// The metadata column named `name`, if any
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    /// The type of the values of the column
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Self::FilePath => DataType::Utf8,
+            Self::FileSize | Self::RowIndex => DataType::UInt64,
+            Self::LastModified => {
+                DataType::Timestamp(TimeUnit::Nanosecond, Some(Arc::from("UTC")))
+            }
+        }
+    }
+
+    /// The field of the column in the schema of a scan
+    pub fn field(&self) -> Field {
+        Field::new(self.name(), self.data_type(), false)
+    }
+
+    /// Whether the column has the same value for all the rows of a file
+    pub fn is_constant(&self) -> bool {
+        !matches!(self, Self::RowIndex)
+    }
+
+    /// The value of the column for the rows of the file `object_meta`, or
+    /// `None` if it is not [constant](Self::is_constant)
+    pub fn file_value(&self, object_meta: &ObjectMeta) -> Option<ScalarValue> {
+        match self {
+            Self::FilePath => Some(ScalarValue::from(object_meta.location.as_ref())),
+            Self::FileSize => Some(ScalarValue::UInt64(Some(object_meta.size as u64))),
+            Self::LastModified => Some(ScalarValue::TimestampNanosecond(
+                object_meta.last_modified.timestamp_nanos_opt(),
+                Some(Arc::from("UTC")),
+            )),
+            Self::RowIndex => None,
+        }
+    }
+}
@@ -20,39 +20,58 @@
 mod arrow_file;
 mod avro;
 mod csv;
+mod csv_records;
 mod file_groups;
 mod file_scan_config;
 mod file_stream;
 mod json;
+mod json_values;
+mod metadata_column;
+#[cfg(feature = "orc")]
+mod orc;
 #[cfg(feature = "parquet")]
 pub mod parquet;
 pub use file_groups::FileGroupPartitioner;
-use futures::StreamExt;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
 
 pub(crate) use self::csv::plan_to_csv;
 pub use self::csv::{CsvConfig, CsvExec, CsvOpener};
 pub(crate) use self::json::plan_to_json;
 #[cfg(feature = "parquet")]
-pub use self::parquet::{ParquetExec, ParquetFileMetrics, ParquetFileReaderFactory};
+pub use self::parquet::{
+    CachedParquetMetaData, ParquetExec, ParquetFileMetrics, ParquetFileReaderFactory,
+    ParquetTopK,
+};
+pub use csv_records::{CsvBadRowPolicy, CsvRejects};
 
 pub use arrow_file::ArrowExec;
 pub use avro::AvroExec;
-use file_scan_config::PartitionColumnProjector;
+use file_scan_config::{prune_nested_fields, PartitionColumnProjector};
 pub use file_scan_config::{
     wrap_partition_type_in_dict, wrap_partition_value_in_dict, FileScanConfig,
 };
 pub use file_stream::{FileOpenFuture, FileOpener, FileStream, OnError};
 pub use json::{JsonOpener, NdJsonExec};
+pub(crate) use json_values::JsonArrayReader;
+pub use json_values::JSON_RAW_VALUES_METADATA_KEY;
+pub use metadata_column::MetadataColumn;
+#[cfg(feature = "orc")]
+pub use orc::OrcExec;
+#[cfg(feature = "orc")]
+pub(crate) use orc::{orc_statistics, ObjectStoreReader};
 
 use std::{
     fmt::{Debug, Formatter, Result as FmtResult},
+    io::{Read, Seek, SeekFrom},
     ops::Range,
     sync::Arc,
     vec,
 };
 
+use super::file_format::file_compression_type::FileCompressionType;
 use super::listing::ListingTableUrl;
-use crate::error::{DataFusionError, Result};
+use crate::error::Result;
 use crate::physical_plan::{DisplayAs, DisplayFormatType};
 use crate::{
     datasource::{
@@ -62,20 +81,21 @@ use crate::{
     physical_plan::display::{OutputOrderingDisplay, ProjectSchemaDisplay},
 };
 
-use arrow::{
-    array::new_null_array,
-    compute::{can_cast_types, cast},
-    datatypes::{DataType, Schema, SchemaRef},
-    record_batch::{RecordBatch, RecordBatchOptions},
-};
-use datafusion_common::{file_options::FileTypeWriterOptions, plan_err};
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::file_options::FileTypeWriterOptions;
 use datafusion_physical_expr::expressions::Column;
 use datafusion_physical_expr::PhysicalSortExpr;
+use datafusion_physical_plan::common::AbortOnDropSingle;
 use datafusion_physical_plan::ExecutionPlan;
 
+use itertools::Itertools;
 use log::debug;
 use object_store::ObjectMeta;
-use object_store::{path::Path, GetOptions, ObjectStore};
+use object_store::{path::Path, GetOptions, GetResultPayload, ObjectStore};
+use tokio::task::spawn_blocking;
+use tokio_util::io::{StreamReader, SyncIoBridge};
 
 /// The base configurations to provide when creating a physical plan for
 /// writing to any given file format.
@@ -129,6 +149,11 @@ impl DisplayAs for FileScanConfig {
             write!(f, ", projection={}", ProjectSchemaDisplay(&schema))?;
         }
 
+        if !self.projection_paths.is_empty() {
+            let paths = self.projection_paths.iter().map(|path| path.join("."));
+            write!(f, ", projection_paths=[{}]", paths.format(", "))?;
+        }
+
         if let Some(limit) = self.limit {
             write!(f, ", limit={limit}")?;
         }
@@ -268,126 +293,8 @@ where
     Ok(())
 }
 
-/// A utility which can adapt file-level record batches to a table schema which may have a schema
-/// obtained from merging multiple file-level schemas.
-///
-/// This is useful for enabling schema evolution in partitioned datasets.
-///
-/// This has to be done in two stages.
-///
-/// 1. Before reading the file, we have to map projected column indexes from the table schema to
-///    the file schema.
-///
-/// 2. After reading a record batch we need to map the read columns back to the expected columns
-///    indexes and insert null-valued columns wherever the file schema was missing a colum present
-///    in the table schema.
-#[derive(Clone, Debug)]
-pub(crate) struct SchemaAdapter {
-    /// Schema for the table
-    table_schema: SchemaRef,
-}
-
-impl SchemaAdapter {
-    pub(crate) fn new(table_schema: SchemaRef) -> SchemaAdapter {
-        Self { table_schema }
-    }
-
-    /// Map a column index in the table schema to a column index in a particular
-    /// file schema
-    ///
-    /// Panics if index is not in range for the table schema
-    pub(crate) fn map_column_index(
-        &self,
-        index: usize,
-        file_schema: &Schema,
-    ) -> Option<usize> {
-        let field = self.table_schema.field(index);
-        Some(file_schema.fields.find(field.name())?.0)
-    }
-
-    /// Creates a `SchemaMapping` that can be used to cast or map the columns from the file schema to the table schema.
-    ///
-    /// If the provided `file_schema` contains columns of a different type to the expected
-    /// `table_schema`, the method will attempt to cast the array data from the file schema
-    /// to the table schema where possible.
-    ///
-    /// Returns a [`SchemaMapping`] that can be applied to the output batch
-    /// along with an ordered list of columns to project from the file
-    pub fn map_schema(
-        &self,
-        file_schema: &Schema,
-    ) -> Result<(SchemaMapping, Vec<usize>)> {
-        let mut projection = Vec::with_capacity(file_schema.fields().len());
-        let mut field_mappings = vec![None; self.table_schema.fields().len()];
-
-        for (file_idx, file_field) in file_schema.fields.iter().enumerate() {
-            if let Some((table_idx, table_field)) =
-                self.table_schema.fields().find(file_field.name())
-            {
-                match can_cast_types(file_field.data_type(), table_field.data_type()) {
-                    true => {
-                        field_mappings[table_idx] = Some(projection.len());
-                        projection.push(file_idx);
-                    }
-                    false => {
-                        return plan_err!(
-                            "Cannot cast file schema field {} of type {:?} to table schema field of type {:?}",
-                            file_field.name(),
-                            file_field.data_type(),
-                            table_field.data_type()
-                        )
-                    }
-                }
-            }
-        }
-
-        Ok((
-            SchemaMapping {
-                table_schema: self.table_schema.clone(),
-                field_mappings,
-            },
-            projection,
-        ))
-    }
-}
-
-/// The SchemaMapping struct holds a mapping from the file schema to the table schema
-/// and any necessary type conversions that need to be applied.
-#[derive(Debug)]
-pub struct SchemaMapping {
-    /// The schema of the table. This is the expected schema after conversion and it should match the schema of the query result.
-    table_schema: SchemaRef,
-    /// Mapping from field index in `table_schema` to index in projected file_schema
-    field_mappings: Vec<Option<usize>>,
-}
-
-impl SchemaMapping {
-    /// Adapts a `RecordBatch` to match the `table_schema` using the stored mapping and conversions.
-    fn map_batch(&self, batch: RecordBatch) -> Result<RecordBatch> {
-        let batch_rows = batch.num_rows();
-        let batch_cols = batch.columns().to_vec();
-
-        let cols = self
-            .table_schema
-            .fields()
-            .iter()
-            .zip(&self.field_mappings)
-            .map(|(field, file_idx)| match file_idx {
-                Some(batch_idx) => cast(&batch_cols[*batch_idx], field.data_type()),
-                None => Ok(new_null_array(field.data_type(), batch_rows)),
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-
-        // Necessary to handle empty batches
-        let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
-
-        let schema = self.table_schema.clone();
-        let record_batch = RecordBatch::try_new_with_options(schema, cols, &options)?;
-        Ok(record_batch)
-    }
-}
-
 /// A single file or part of a file that should be read, along with its schema, statistics
+#[derive(Clone)]
 pub struct FileMeta {
     /// Path for the file (e.g. URL, filesystem path, etc)
     pub object_meta: ObjectMeta,
@@ -627,137 +534,82 @@ async fn find_first_newline(
     Ok(index)
 }
 
+/// Decode the `payload` of a GET of a file with the synchronous reader built by
+/// `open`. Only the bytes of `range` are read from a local file, or all its
+/// bytes if `range` is `None`. A streamed payload only holds the requested bytes
+/// and is read incrementally on a blocking thread, rather than being collected
+/// in memory.
+fn read_payload<F, I, E>(
+    payload: GetResultPayload,
+    range: Option<Range<usize>>,
+    file_compression_type: FileCompressionType,
+    open: F,
+) -> Result<BoxStream<'static, Result<RecordBatch, ArrowError>>>
+where
+    F: FnOnce(Box<dyn Read + Send>) -> Result<I> + Send + 'static,
+    I: Iterator<Item = Result<RecordBatch, E>> + Send + 'static,
+    E: Into<ArrowError> + 'static,
+{
+    match payload {
+        GetResultPayload::File(mut file, _) => {
+            let reader = match range {
+                // Don't seek if no range as breaks FIFO files
+                None => file_compression_type.convert_read(file)?,
+                Some(range) => {
+                    file.seek(SeekFrom::Start(range.start as _))?;
+                    file_compression_type
+                        .convert_read(file.take((range.end - range.start) as u64))?
+                }
+            };
+            let batches = open(reader)?;
+            Ok(futures::stream::iter(batches).map_err(Into::into).boxed())
+        }
+        GetResultPayload::Stream(s) => {
+            let (tx, rx) = tokio::sync::mpsc::channel(2);
+            let task = spawn_blocking(move || {
+                let reader = SyncIoBridge::new(StreamReader::new(s));
+                let batches = file_compression_type.convert_read(reader).and_then(open);
+                match batches {
+                    Ok(batches) => {
+                        for batch in batches {
+                            if tx.blocking_send(batch.map_err(Into::into)).is_err() {
+                                // the receiver hung up
+                                break;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        let _ = tx.blocking_send(Err(e.into()));
+                    }
+                }
+            });
+
+            let state = (rx, AbortOnDropSingle::new(task));
+            let stream = futures::stream::unfold(state, |(mut rx, task)| async move {
+                if let Some(batch) = rx.recv().await {
+                    return Some((batch, (rx, task)));
+                }
+                // propagate a panic of the reader instead of ending silently
+                if let Err(e) = task.await {
+                    if e.is_panic() {
+                        std::panic::resume_unwind(e.into_panic());
+                    }
+                }
+                None
+            });
+            Ok(stream.boxed())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use arrow_array::cast::AsArray;
-    use arrow_array::types::{Float32Type, Float64Type, UInt32Type};
-    use arrow_array::{
-        BinaryArray, BooleanArray, Float32Array, Int32Array, Int64Array, StringArray,
-        UInt64Array,
-    };
-    use arrow_schema::Field;
     use chrono::Utc;
 
     use crate::physical_plan::{DefaultDisplay, VerboseDisplay};
 
     use super::*;
 
-    #[test]
-    fn schema_mapping_map_batch() {
-        let table_schema = Arc::new(Schema::new(vec![
-            Field::new("c1", DataType::Utf8, true),
-            Field::new("c2", DataType::UInt32, true),
-            Field::new("c3", DataType::Float64, true),
-        ]));
-
-        let adapter = SchemaAdapter::new(table_schema.clone());
-
-        let file_schema = Schema::new(vec![
-            Field::new("c1", DataType::Utf8, true),
-            Field::new("c2", DataType::UInt64, true),
-            Field::new("c3", DataType::Float32, true),
-        ]);
-
-        let (mapping, _) = adapter.map_schema(&file_schema).expect("map schema failed");
-
-        let c1 = StringArray::from(vec!["hello", "world"]);
-        let c2 = UInt64Array::from(vec![9_u64, 5_u64]);
-        let c3 = Float32Array::from(vec![2.0_f32, 7.0_f32]);
-        let batch = RecordBatch::try_new(
-            Arc::new(file_schema),
-            vec![Arc::new(c1), Arc::new(c2), Arc::new(c3)],
-        )
-        .unwrap();
-
-        let mapped_batch = mapping.map_batch(batch).unwrap();
-
-        assert_eq!(mapped_batch.schema(), table_schema);
-        assert_eq!(mapped_batch.num_columns(), 3);
-        assert_eq!(mapped_batch.num_rows(), 2);
-
-        let c1 = mapped_batch.column(0).as_string::<i32>();
-        let c2 = mapped_batch.column(1).as_primitive::<UInt32Type>();
-        let c3 = mapped_batch.column(2).as_primitive::<Float64Type>();
-
-        assert_eq!(c1.value(0), "hello");
-        assert_eq!(c1.value(1), "world");
-        assert_eq!(c2.value(0), 9_u32);
-        assert_eq!(c2.value(1), 5_u32);
-        assert_eq!(c3.value(0), 2.0_f64);
-        assert_eq!(c3.value(1), 7.0_f64);
-    }
-
-    #[test]
-    fn schema_adapter_map_schema_with_projection() {
-        let table_schema = Arc::new(Schema::new(vec![
-            Field::new("c0", DataType::Utf8, true),
-            Field::new("c1", DataType::Utf8, true),
-            Field::new("c2", DataType::Float64, true),
-            Field::new("c3", DataType::Int32, true),
-            Field::new("c4", DataType::Float32, true),
-        ]));
-
-        let file_schema = Schema::new(vec![
-            Field::new("id", DataType::Int32, true),
-            Field::new("c1", DataType::Boolean, true),
-            Field::new("c2", DataType::Float32, true),
-            Field::new("c3", DataType::Binary, true),
-            Field::new("c4", DataType::Int64, true),
-        ]);
-
-        let indices = vec![1, 2, 4];
-        let schema = SchemaRef::from(table_schema.project(&indices).unwrap());
-        let adapter = SchemaAdapter::new(schema);
-        let (mapping, projection) = adapter.map_schema(&file_schema).unwrap();
-
-        let id = Int32Array::from(vec![Some(1), Some(2), Some(3)]);
-        let c1 = BooleanArray::from(vec![Some(true), Some(false), Some(true)]);
-        let c2 = Float32Array::from(vec![Some(2.0_f32), Some(7.0_f32), Some(3.0_f32)]);
-        let c3 = BinaryArray::from_opt_vec(vec![
-            Some(b"hallo"),
-            Some(b"danke"),
-            Some(b"super"),
-        ]);
-        let c4 = Int64Array::from(vec![1, 2, 3]);
-        let batch = RecordBatch::try_new(
-            Arc::new(file_schema),
-            vec![
-                Arc::new(id),
-                Arc::new(c1),
-                Arc::new(c2),
-                Arc::new(c3),
-                Arc::new(c4),
-            ],
-        )
-        .unwrap();
-        let rows_num = batch.num_rows();
-        let projected = batch.project(&projection).unwrap();
-        let mapped_batch = mapping.map_batch(projected).unwrap();
-
-        assert_eq!(
-            mapped_batch.schema(),
-            Arc::new(table_schema.project(&indices).unwrap())
-        );
-        assert_eq!(mapped_batch.num_columns(), indices.len());
-        assert_eq!(mapped_batch.num_rows(), rows_num);
-
-        let c1 = mapped_batch.column(0).as_string::<i32>();
-        let c2 = mapped_batch.column(1).as_primitive::<Float64Type>();
-        let c4 = mapped_batch.column(2).as_primitive::<Float32Type>();
-
-        assert_eq!(c1.value(0), "true");
-        assert_eq!(c1.value(1), "false");
-        assert_eq!(c1.value(2), "true");
-
-        assert_eq!(c2.value(0), 2.0_f64);
-        assert_eq!(c2.value(1), 7.0_f64);
-        assert_eq!(c2.value(2), 3.0_f64);
-
-        assert_eq!(c4.value(0), 1.0_f32);
-        assert_eq!(c4.value(1), 2.0_f32);
-        assert_eq!(c4.value(2), 3.0_f32);
-    }
-
     #[test]
     fn file_groups_display_empty() {
         let expected = "{0 groups: []}";
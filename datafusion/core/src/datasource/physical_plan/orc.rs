@@ -0,0 +1,556 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution plan for reading Apache ORC files
+
+use std::any::Any;
+use std::sync::Arc;
+
+use super::file_stream::{FileOpenFuture, FileOpener, FileStream};
+use super::{FileMeta, FileScanConfig};
+use crate::datasource::listing::FileRange;
+use crate::datasource::schema_adapter::{
+    DefaultSchemaAdapter, SchemaAdapter, SchemaAdapterFactory,
+};
+use crate::error::{DataFusionError, Result};
+use crate::physical_optimizer::pruning::{PruningPredicate, PruningStatistics};
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::metrics::{
+    Count, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet,
+};
+use crate::physical_plan::stream::CooperativeStream;
+use crate::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionProgress, Partitioning,
+    SendableRecordBatchStream, Statistics,
+};
+
+use arrow::array::{ArrayRef, UInt64Array};
+use arrow::datatypes::{DataType, Schema, SchemaRef};
+use arrow::error::ArrowError;
+use bytes::Bytes;
+use datafusion_common::{Column, ScalarValue};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::{EquivalenceProperties, LexOrdering, PhysicalExpr};
+use futures::future::BoxFuture;
+use futures::{FutureExt, StreamExt, TryFutureExt, TryStreamExt};
+use log::debug;
+use object_store::{ObjectMeta, ObjectStore};
+use orc_rust::projection::ProjectionMask;
+use orc_rust::reader::metadata::FileMetadata;
+use orc_rust::reader::AsyncChunkReader;
+use orc_rust::schema::RootDataType;
+use orc_rust::statistics::{ColumnStatistics, TypeStatistics};
+use orc_rust::stripe::StripeMetadata;
+use orc_rust::ArrowReaderBuilder;
+
+/// Execution plan for scanning one or more ORC partitions
+#[derive(Debug, Clone)]
+pub struct OrcExec {
+    base_config: FileScanConfig,
+    projected_statistics: Statistics,
+    projected_schema: SchemaRef,
+    projected_output_ordering: Vec<LexOrdering>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+    /// Optional predicate for pruning stripes
+    predicate: Option<Arc<dyn PhysicalExpr>>,
+    /// Optional predicate for pruning stripes using their column statistics
+    pruning_predicate: Option<Arc<PruningPredicate>>,
+}
+
+impl OrcExec {
+    /// Create a new ORC reader execution plan provided base configurations
+    /// and an optional predicate, which is used to skip stripes whose
+    /// column statistics rule it out
+    pub fn new(
+        base_config: FileScanConfig,
+        predicate: Option<Arc<dyn PhysicalExpr>>,
+    ) -> Self {
+        let metrics = ExecutionPlanMetricsSet::new();
+        let predicate_creation_errors =
+            MetricBuilder::new(&metrics).global_counter("num_predicate_creation_errors");
+
+        let file_schema = &base_config.file_schema;
+        let pruning_predicate = predicate
+            .clone()
+            .and_then(|predicate_expr| {
+                match PruningPredicate::try_new(predicate_expr, file_schema.clone()) {
+                    Ok(pruning_predicate) => Some(Arc::new(pruning_predicate)),
+                    Err(e) => {
+                        debug!("Could not create pruning predicate for: {e}");
+                        predicate_creation_errors.add(1);
+                        None
+                    }
+                }
+            })
+            .filter(|p| !p.allways_true());
+
+        let (projected_schema, projected_statistics, projected_output_ordering) =
+            base_config.project();
+
+        Self {
+            base_config,
+            projected_schema,
+            projected_statistics,
+            projected_output_ordering,
+            metrics,
+            predicate,
+            pruning_predicate,
+        }
+    }
+
+    /// Ref to the base configs
+    pub fn base_config(&self) -> &FileScanConfig {
+        &self.base_config
+    }
+
+    /// Optional predicate.
+    pub fn predicate(&self) -> Option<&Arc<dyn PhysicalExpr>> {
+        self.predicate.as_ref()
+    }
+
+    /// Optional reference to this ORC scan's pruning predicate
+    pub fn pruning_predicate(&self) -> Option<&Arc<PruningPredicate>> {
+        self.pruning_predicate.as_ref()
+    }
+}
+
+impl DisplayAs for OrcExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        let predicate_string = self
+            .predicate
+            .as_ref()
+            .map(|p| format!(", predicate={p}"))
+            .unwrap_or_default();
+
+        let pruning_predicate_string = self
+            .pruning_predicate
+            .as_ref()
+            .map(|pre| format!(", pruning_predicate={}", pre.predicate_expr()))
+            .unwrap_or_default();
+
+        write!(f, "OrcExec: ")?;
+        self.base_config.fmt_as(t, f)?;
+        write!(f, "{}{}", predicate_string, pruning_predicate_string)
+    }
+}
+
+impl ExecutionPlan for OrcExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.projected_schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(self.base_config.file_groups.len())
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.projected_output_ordering
+            .first()
+            .map(|ordering| ordering.as_slice())
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        EquivalenceProperties::new_with_orderings(
+            self.schema(),
+            &self.projected_output_ordering,
+        )
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        Vec::new()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let object_store = context
+            .runtime_env()
+            .object_store(&self.base_config.object_store_url)?;
+
+        let projection = self
+            .base_config
+            .file_column_projection_indices()
+            .unwrap_or_else(|| {
+                (0..self.base_config.file_schema.fields().len()).collect()
+            });
+
+        let opener = OrcOpener {
+            partition_index: partition,
+            projection: Arc::from(projection),
+            batch_size: context.session_config().batch_size(),
+            table_schema: self.base_config.file_schema.clone(),
+            // stripe statistics are looked up by the names of the table
+            // columns, which a custom schema adapter may read other file
+            // columns as
+            pruning_predicate: self
+                .pruning_predicate
+                .clone()
+                .filter(|_| self.base_config.schema_adapter_factory.is_none()),
+            object_store,
+            metrics: self.metrics.clone(),
+            schema_adapter_factory: self.base_config.schema_adapter_factory.clone(),
+        };
+
+        let stream =
+            FileStream::new(&self.base_config, partition, opener, &self.metrics)?;
+        Ok(Box::pin(CooperativeStream::new(Box::pin(stream), &context)))
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        Ok(self.projected_statistics.clone())
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn progress(&self) -> ExecutionProgress {
+        self.base_config.progress(&self.metrics)
+    }
+}
+
+/// An [`AsyncChunkReader`] reading an ORC file from an [`ObjectStore`]
+#[derive(Debug, Clone)]
+pub(crate) struct ObjectStoreReader {
+    store: Arc<dyn ObjectStore>,
+    file: ObjectMeta,
+}
+
+impl ObjectStoreReader {
+    pub(crate) fn new(store: Arc<dyn ObjectStore>, file: ObjectMeta) -> Self {
+        Self { store, file }
+    }
+}
+
+impl AsyncChunkReader for ObjectStoreReader {
+    fn len(&mut self) -> BoxFuture<'_, std::io::Result<u64>> {
+        let len = self.file.size as u64;
+        async move { Ok(len) }.boxed()
+    }
+
+    fn get_bytes(
+        &mut self,
+        offset_from_start: u64,
+        length: u64,
+    ) -> BoxFuture<'_, std::io::Result<Bytes>> {
+        let start = offset_from_start as usize;
+        let range = start..start + length as usize;
+        self.store
+            .get_range(&self.file.location, range)
+            .map_err(std::io::Error::from)
+            .boxed()
+    }
+}
+
+/// Returns the minimum and maximum value of `statistics` as values of
+/// `data_type`, if they are known
+pub(crate) fn orc_statistics(
+    statistics: &ColumnStatistics,
+    data_type: &DataType,
+) -> (Option<ScalarValue>, Option<ScalarValue>) {
+    let (min, max) = match statistics.type_statistics() {
+        Some(TypeStatistics::Integer { min, max, .. }) => (
+            ScalarValue::Int64(Some(*min)),
+            ScalarValue::Int64(Some(*max)),
+        ),
+        Some(TypeStatistics::Double { min, max, .. }) => (
+            ScalarValue::Float64(Some(*min)),
+            ScalarValue::Float64(Some(*max)),
+        ),
+        Some(TypeStatistics::String { min, max, .. }) => (
+            ScalarValue::Utf8(Some(min.clone())),
+            ScalarValue::Utf8(Some(max.clone())),
+        ),
+        Some(TypeStatistics::Date { min, max }) => (
+            ScalarValue::Date32(Some(*min)),
+            ScalarValue::Date32(Some(*max)),
+        ),
+        _ => return (None, None),
+    };
+    // the statistics are ignored if they cannot be represented
+    let cast = |value: ScalarValue| value.cast_to(data_type).ok();
+    (cast(min), cast(max))
+}
+
+/// The column statistics of a stripe, as [`PruningStatistics`]
+struct StripeStatistics<'a> {
+    stripe: &'a StripeMetadata,
+    root: &'a RootDataType,
+    file_schema: &'a Schema,
+}
+
+impl StripeStatistics<'_> {
+    /// The statistics and type of `column`, if it is a top level
+    /// column of the file
+    fn column(&self, column: &Column) -> Option<(&ColumnStatistics, &DataType)> {
+        let (_, field) = self.file_schema.column_with_name(&column.name)?;
+        let child = self
+            .root
+            .children()
+            .iter()
+            .find(|c| c.name() == column.name)?;
+        let statistics = self
+            .stripe
+            .column_statistics()
+            .get(child.data_type().column_index())?;
+        Some((statistics, field.data_type()))
+    }
+
+    fn value(&self, value: Option<ScalarValue>) -> Option<ArrayRef> {
+        value.and_then(|v| v.to_array().ok())
+    }
+}
+
+impl PruningStatistics for StripeStatistics<'_> {
+    fn min_values(&self, column: &Column) -> Option<ArrayRef> {
+        let (statistics, data_type) = self.column(column)?;
+        self.value(orc_statistics(statistics, data_type).0)
+    }
+
+    fn max_values(&self, column: &Column) -> Option<ArrayRef> {
+        let (statistics, data_type) = self.column(column)?;
+        self.value(orc_statistics(statistics, data_type).1)
+    }
+
+    fn num_containers(&self) -> usize {
+        1
+    }
+
+    fn null_counts(&self, column: &Column) -> Option<ArrayRef> {
+        let (statistics, _) = self.column(column)?;
+        let nulls = self
+            .stripe
+            .number_of_rows()
+            .checked_sub(statistics.number_of_values())?;
+        Some(Arc::new(UInt64Array::from(vec![nulls])))
+    }
+}
+
+/// Returns the stripes of `metadata` that start in `range`, if any, and
+/// that are not ruled out by `pruning_predicate`
+fn prune_stripes(
+    metadata: &FileMetadata,
+    file_schema: &Schema,
+    range: Option<&FileRange>,
+    pruning_predicate: Option<&PruningPredicate>,
+    stripes_pruned: &Count,
+) -> Vec<u64> {
+    let mut offsets = vec![];
+    for stripe in metadata.stripe_metadatas() {
+        let offset = stripe.offset();
+        if let Some(range) = range {
+            if (offset as i64) < range.start || (offset as i64) >= range.end {
+                continue;
+            }
+        }
+        if let Some(predicate) = pruning_predicate {
+            let statistics = StripeStatistics {
+                stripe,
+                root: metadata.root_data_type(),
+                file_schema,
+            };
+            match predicate.prune(&statistics) {
+                Ok(values) if !values[0] => {
+                    stripes_pruned.add(1);
+                    continue;
+                }
+                Ok(_) => {}
+                // stripes are read if the predicate cannot be evaluated
+                Err(e) => debug!("Error evaluating stripe predicate values {e}"),
+            }
+        }
+        offsets.push(offset);
+    }
+    offsets
+}
+
+/// Implements [`FileOpener`] for an ORC file
+pub struct OrcOpener {
+    pub partition_index: usize,
+    pub projection: Arc<[usize]>,
+    pub batch_size: usize,
+    pub table_schema: SchemaRef,
+    pub pruning_predicate: Option<Arc<PruningPredicate>>,
+    pub object_store: Arc<dyn ObjectStore>,
+    pub metrics: ExecutionPlanMetricsSet,
+    pub schema_adapter_factory: Option<Arc<dyn SchemaAdapterFactory>>,
+}
+
+impl FileOpener for OrcOpener {
+    fn open(&self, file_meta: FileMeta) -> Result<FileOpenFuture> {
+        let stripes_pruned = MetricBuilder::new(&self.metrics)
+            .with_new_label("filename", file_meta.location().to_string())
+            .counter("stripes_pruned", self.partition_index);
+
+        let reader =
+            ObjectStoreReader::new(self.object_store.clone(), file_meta.object_meta);
+        let range = file_meta.range;
+        let projected_schema =
+            SchemaRef::from(self.table_schema.project(&self.projection)?);
+        let schema_adapter: Box<dyn SchemaAdapter> = match &self.schema_adapter_factory {
+            Some(factory) => factory.create(projected_schema),
+            None => Box::new(DefaultSchemaAdapter::new(projected_schema)),
+        };
+        let pruning_predicate = self.pruning_predicate.clone();
+        let batch_size = self.batch_size;
+
+        Ok(Box::pin(async move {
+            let builder = ArrowReaderBuilder::try_new_async(reader.clone())
+                .await
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            let file_schema = builder.schema();
+            let metadata = Arc::new(builder.file_metadata().clone());
+
+            let (schema_mapping, adapted_projections) =
+                schema_adapter.map_schema(&file_schema)?;
+            let mask = ProjectionMask::roots(
+                metadata.root_data_type(),
+                adapted_projections.iter().map(|i| i + 1),
+            );
+
+            let stripes = prune_stripes(
+                &metadata,
+                &file_schema,
+                range.as_ref(),
+                pruning_predicate.as_deref(),
+                &stripes_pruned,
+            );
+
+            // read the stripes one at a time, each with a reader
+            // selecting the stripe starting at its offset
+            let stream = futures::stream::iter(stripes)
+                .map(move |offset| {
+                    let offset = offset as usize;
+                    ArrowReaderBuilder::new(reader.clone(), metadata.clone())
+                        .with_projection(mask.clone())
+                        .with_batch_size(batch_size)
+                        .with_file_byte_range(offset..offset + 1)
+                        .build_async()
+                })
+                .flatten()
+                .map(move |batch| {
+                    let batch = batch?;
+                    schema_mapping
+                        .map_batch(batch)
+                        .map_err(|e| ArrowError::ExternalError(Box::new(e)))
+                });
+            Ok(stream.boxed())
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::collect;
+    use crate::prelude::SessionContext;
+    use crate::test::object_store::local_unpartitioned_file;
+
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use datafusion_common::ScalarValue;
+    use datafusion_execution::object_store::ObjectStoreUrl;
+    use datafusion_expr::Operator;
+    use datafusion_physical_expr::expressions::{col, lit, BinaryExpr};
+    use orc_rust::ArrowWriterBuilder;
+    use tempfile::TempDir;
+
+    /// Writes `batches` to an ORC file with a stripe for each batch
+    fn write_orc_file(dir: &TempDir, batches: &[RecordBatch]) -> String {
+        let path = dir.path().join("data.orc");
+        let file = std::fs::File::create(&path).unwrap();
+        let mut writer = ArrowWriterBuilder::new(file, batches[0].schema())
+            .try_build()
+            .unwrap();
+        for batch in batches {
+            writer.write(batch).unwrap();
+            writer.flush_stripe().unwrap();
+        }
+        writer.close().unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn orc_exec_prunes_stripes() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
+        let batches: Vec<_> = [0..10, 10..20, 20..30]
+            .into_iter()
+            .map(|values| {
+                let array = Int64Array::from_iter_values(values);
+                RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap()
+            })
+            .collect();
+        let dir = TempDir::new()?;
+        let path = write_orc_file(&dir, &batches);
+
+        // a >= 15
+        let predicate = Arc::new(BinaryExpr::new(
+            col("a", &schema)?,
+            Operator::GtEq,
+            lit(ScalarValue::Int64(Some(15))),
+        ));
+        let exec = OrcExec::new(
+            FileScanConfig {
+                object_store_url: ObjectStoreUrl::local_filesystem(),
+                file_schema: schema.clone(),
+                file_groups: vec![vec![local_unpartitioned_file(path)]],
+                statistics: Statistics::new_unknown(&schema),
+                projection: None,
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: vec![],
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
+            },
+            Some(predicate),
+        );
+        let exec: Arc<dyn ExecutionPlan> = Arc::new(exec);
+
+        let task_ctx = SessionContext::new().task_ctx();
+        let batches = collect(exec.clone(), task_ctx).await?;
+        // the first stripe is pruned, the filter is applied above the scan
+        let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, 20);
+
+        let metrics = exec.metrics().unwrap();
+        assert_eq!(
+            metrics.sum_by_name("stripes_pruned").map(|v| v.as_usize()),
+            Some(1)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,361 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Pruning the row groups of a parquet file with [`DynamicFilter`]s, which
+//! are tightened while the file is read
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use arrow::compute::concat_batches;
+use arrow::datatypes::SchemaRef;
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use bytes::Bytes;
+use datafusion_expr::Operator;
+use datafusion_physical_expr::expressions::BinaryExpr;
+use datafusion_physical_expr::PhysicalExpr;
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt, TryStreamExt};
+use log::debug;
+use parquet::arrow::arrow_reader::{ArrowReaderOptions, RowSelection};
+use parquet::arrow::async_reader::AsyncFileReader;
+use parquet::arrow::{ParquetRecordBatchStreamBuilder, ProjectionMask};
+use parquet::file::metadata::ParquetMetaData;
+
+use super::late_materialization::LateMaterialization;
+use super::page_filter::PagePruningPredicate;
+use super::{row_filter, row_groups, ParquetFileMetrics, ParquetFileReaderFactory};
+use crate::datasource::physical_plan::FileMeta;
+use crate::datasource::schema_adapter::{DefaultSchemaAdapter, SchemaAdapter};
+use crate::error::Result;
+use crate::physical_optimizer::pruning::PruningPredicate;
+use crate::physical_plan::dynamic_filter::DynamicFilter;
+use crate::physical_plan::metrics::ExecutionPlanMetricsSet;
+
+/// The conjunction of the current predicates of [`DynamicFilter`]s, as
+/// pruning predicates for the row groups and pages of a file
+#[derive(Debug)]
+pub(super) struct DynamicPruningPredicate {
+    filters: Vec<DynamicFilter>,
+    table_schema: SchemaRef,
+    /// The generations of the filters the predicates were built from
+    generations: Option<Vec<u64>>,
+    /// The conjunction of the current predicates, referring to the table
+    predicate: Option<Arc<dyn PhysicalExpr>>,
+    pruning_predicate: Option<PruningPredicate>,
+    page_pruning_predicate: Option<PagePruningPredicate>,
+}
+
+impl DynamicPruningPredicate {
+    /// Create a new [`DynamicPruningPredicate`] for `filters` whose columns
+    /// refer to `table_schema`
+    pub(super) fn new(filters: Vec<DynamicFilter>, table_schema: SchemaRef) -> Self {
+        Self {
+            filters,
+            table_schema,
+            generations: None,
+            predicate: None,
+            pruning_predicate: None,
+            page_pruning_predicate: None,
+        }
+    }
+
+    /// Rebuild the pruning predicates if any of the filters has changed
+    fn refresh(&mut self, metrics: &ParquetFileMetrics) {
+        let generations: Vec<_> = self.filters.iter().map(|f| f.generation()).collect();
+        if self.generations.as_ref() == Some(&generations) {
+            return;
+        }
+        self.generations = Some(generations);
+
+        let predicate = self
+            .filters
+            .iter()
+            .filter_map(|f| match f.current() {
+                Ok(predicate) => predicate,
+                Err(e) => {
+                    debug!("Ignoring dynamic filter that could not be read: {e}");
+                    metrics.predicate_evaluation_errors.add(1);
+                    None
+                }
+            })
+            .reduce(|a, b| Arc::new(BinaryExpr::new(a, Operator::And, b)));
+        let Some(predicate) = predicate else {
+            return;
+        };
+
+        self.predicate = Some(predicate.clone());
+        self.pruning_predicate =
+            PruningPredicate::try_new(predicate.clone(), self.table_schema.clone())
+                .map_err(|e| debug!("Could not create dynamic pruning predicate: {e}"))
+                .ok()
+                .filter(|p| !p.allways_true());
+        self.page_pruning_predicate =
+            PagePruningPredicate::try_new(&predicate, self.table_schema.clone())
+                .map_err(|e| {
+                    debug!("Could not create dynamic page pruning predicate: {e}")
+                })
+                .ok()
+                .filter(|p| p.filter_number() > 0);
+    }
+}
+
+/// Reads the row groups of a parquet file one at a time, checking the
+/// current predicates of the [`DynamicFilter`]s against the statistics and
+/// page index of each row group right before it is read.
+///
+/// With [`LateMaterialization`], the columns needed to select the rows of a
+/// row group are read first, and the other columns only for the selected
+/// rows.
+pub(super) struct RowGroupReader {
+    pub(super) partition_index: usize,
+    pub(super) file_meta: FileMeta,
+    pub(super) metadata_size_hint: Option<usize>,
+    pub(super) metrics: ExecutionPlanMetricsSet,
+    pub(super) file_metrics: ParquetFileMetrics,
+    pub(super) parquet_file_reader_factory: Arc<dyn ParquetFileReaderFactory>,
+    /// The metadata of the file, read when it was opened
+    pub(super) metadata: Arc<ParquetMetaData>,
+    pub(super) file_schema: SchemaRef,
+    pub(super) table_schema: SchemaRef,
+    pub(super) mask: ProjectionMask,
+    pub(super) batch_size: usize,
+    /// The predicate to evaluate while decoding, if filters are pushed down
+    pub(super) pushdown_predicate: Option<Arc<dyn PhysicalExpr>>,
+    pub(super) reorder_predicates: bool,
+    pub(super) enable_page_index: bool,
+    pub(super) page_pruning_predicate: Option<Arc<PagePruningPredicate>>,
+    pub(super) dynamic_predicate: Option<DynamicPruningPredicate>,
+    /// Selects the rows to read before the other columns are read
+    pub(super) late_materialization: Option<LateMaterialization>,
+}
+
+impl RowGroupReader {
+    /// Returns a stream of the batches of `row_groups`
+    pub(super) fn read(
+        self,
+        row_groups: Vec<usize>,
+    ) -> BoxStream<'static, Result<RecordBatch, ArrowError>> {
+        futures::stream::unfold(
+            (self, row_groups.into_iter()),
+            |(mut reader, mut row_groups)| async move {
+                loop {
+                    let row_group = row_groups.next()?;
+                    match reader.open(row_group).await {
+                        Ok(Some(stream)) => {
+                            return Some((Ok(stream), (reader, row_groups)))
+                        }
+                        // pruned
+                        Ok(None) => continue,
+                        Err(e) => {
+                            let e = ArrowError::ExternalError(Box::new(e));
+                            return Some((Err(e), (reader, row_groups)));
+                        }
+                    }
+                }
+            },
+        )
+        .try_flatten()
+        .boxed()
+    }
+
+    /// Returns a stream of the batches of row group `idx`, or `None` if
+    /// the current dynamic predicates rule out all of its rows
+    async fn open(
+        &mut self,
+        idx: usize,
+    ) -> Result<Option<BoxStream<'static, Result<RecordBatch, ArrowError>>>> {
+        if let Some(dynamic) = &mut self.dynamic_predicate {
+            dynamic.refresh(&self.file_metrics);
+        }
+        let dynamic = self.dynamic_predicate.as_ref();
+
+        if let Some(predicate) = dynamic.and_then(|d| d.pruning_predicate.as_ref()) {
+            if !row_groups::row_group_matches(
+                &self.file_schema,
+                self.metadata.file_metadata().schema_descr(),
+                self.metadata.row_group(idx),
+                predicate,
+                &self.file_metrics,
+            ) {
+                self.file_metrics.row_groups_pruned_dynamic_filter.add(1);
+                return Ok(None);
+            }
+        }
+
+        let mut row_selection: Option<RowSelection> = None;
+        if self.enable_page_index {
+            let page_pruning_predicates = [
+                self.page_pruning_predicate.as_deref(),
+                dynamic.and_then(|d| d.page_pruning_predicate.as_ref()),
+            ];
+            for predicate in page_pruning_predicates.into_iter().flatten() {
+                let selection =
+                    predicate.prune(&[idx], &self.metadata, &self.file_metrics)?;
+                row_selection = intersect(row_selection, selection);
+            }
+        }
+
+        if let Some(late) = &self.late_materialization {
+            let selection = self.select(late, idx, row_selection.clone()).await?;
+            if !selection.selects_any() {
+                return Ok(None);
+            }
+            row_selection = Some(selection);
+        }
+
+        let mut builder = self.builder().await?;
+
+        if let Some(predicate) = &self.pushdown_predicate {
+            match row_filter::build_row_filter(
+                predicate,
+                &self.file_schema,
+                &self.table_schema,
+                &self.metadata,
+                self.reorder_predicates,
+                &self.file_metrics,
+            ) {
+                Ok(Some(filter)) => builder = builder.with_row_filter(filter),
+                Ok(None) => {}
+                Err(e) => {
+                    debug!("Ignoring error building row filter for '{predicate:?}': {e}")
+                }
+            }
+        }
+
+        if let Some(row_selection) = row_selection {
+            builder = builder.with_row_selection(row_selection);
+        }
+
+        let stream = builder
+            .with_projection(self.mask.clone())
+            .with_batch_size(self.batch_size)
+            .with_row_groups(vec![idx])
+            .build()?
+            .map_err(|e| ArrowError::ExternalError(Box::new(e)));
+        Ok(Some(stream.boxed()))
+    }
+
+    /// Reads the columns of `late` of the rows of row group `idx` in
+    /// `row_selection`, and returns the rows of the row group to read
+    async fn select(
+        &self,
+        late: &LateMaterialization,
+        idx: usize,
+        row_selection: Option<RowSelection>,
+    ) -> Result<RowSelection> {
+        let (schema_mapping, adapted_projections) =
+            DefaultSchemaAdapter::new(late.schema().clone())
+                .map_schema(&self.file_schema)?;
+        let mask = ProjectionMask::roots(
+            self.metadata.file_metadata().schema_descr(),
+            adapted_projections,
+        );
+
+        let mut builder = self.builder().await?;
+        if let Some(row_selection) = &row_selection {
+            builder = builder.with_row_selection(row_selection.clone());
+        }
+        let batches: Vec<_> = builder
+            .with_projection(mask)
+            .with_batch_size(self.batch_size)
+            .with_row_groups(vec![idx])
+            .build()?
+            .map(|batch| schema_mapping.map_batch(batch?))
+            .try_collect()
+            .await?;
+        let batch = concat_batches(late.schema(), &batches)?;
+
+        let dynamic_predicate = self
+            .dynamic_predicate
+            .as_ref()
+            .and_then(|d| d.predicate.as_ref());
+        let selected = late.select(&batch, dynamic_predicate, &self.file_metrics)?;
+        let selection = RowSelection::from_filters(&[selected]);
+
+        // `selection` refers to the rows that were read, which are the rows
+        // of `row_selection`
+        Ok(match row_selection {
+            Some(row_selection) => row_selection.and_then(&selection),
+            None => selection,
+        })
+    }
+
+    /// Returns a builder for a stream reading this file
+    async fn builder(
+        &self,
+    ) -> Result<ParquetRecordBatchStreamBuilder<ReaderWithMetadata>> {
+        let inner = self.parquet_file_reader_factory.create_reader(
+            self.partition_index,
+            self.file_meta.clone(),
+            self.metadata_size_hint,
+            &self.metrics,
+        )?;
+        let reader = ReaderWithMetadata {
+            inner,
+            metadata: Arc::clone(&self.metadata),
+        };
+        Ok(ParquetRecordBatchStreamBuilder::new_with_options(
+            reader,
+            ArrowReaderOptions::new(),
+        )
+        .await?)
+    }
+}
+
+/// Returns the rows selected by both `a` and `b`
+fn intersect(a: Option<RowSelection>, b: Option<RowSelection>) -> Option<RowSelection> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.intersection(&b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// An [`AsyncFileReader`] that returns metadata that was already read,
+/// instead of reading it again for each row group
+struct ReaderWithMetadata {
+    inner: Box<dyn AsyncFileReader + Send>,
+    metadata: Arc<ParquetMetaData>,
+}
+
+impl AsyncFileReader for ReaderWithMetadata {
+    fn get_bytes(
+        &mut self,
+        range: Range<usize>,
+    ) -> BoxFuture<'_, parquet::errors::Result<Bytes>> {
+        self.inner.get_bytes(range)
+    }
+
+    fn get_byte_ranges(
+        &mut self,
+        ranges: Vec<Range<usize>>,
+    ) -> BoxFuture<'_, parquet::errors::Result<Vec<Bytes>>>
+    where
+        Self: Send,
+    {
+        self.inner.get_byte_ranges(ranges)
+    }
+
+    fn get_metadata(
+        &mut self,
+    ) -> BoxFuture<'_, parquet::errors::Result<Arc<ParquetMetaData>>> {
+        let metadata = Arc::clone(&self.metadata);
+        async move { Ok(metadata) }.boxed()
+    }
+}
@@ -0,0 +1,366 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Late materialization: selecting the rows of a row group using only the
+//! columns of the predicates and sort keys, before the other projected
+//! columns are read for the selected rows
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use arrow::array::{Array, BooleanArray, BooleanBufferBuilder};
+use arrow::compute::SortColumn;
+use arrow::compute::{filter_record_batch, lexsort_to_indices, prep_null_mask_filter};
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use datafusion_common::cast::as_boolean_array;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{plan_datafusion_err, DataFusionError, Result};
+use datafusion_expr::Operator;
+use datafusion_physical_expr::expressions::{BinaryExpr, Column};
+use datafusion_physical_expr::utils::{collect_columns, reassign_predicate_columns};
+use datafusion_physical_expr::{PhysicalExpr, PhysicalSortExpr};
+
+use super::ParquetFileMetrics;
+use crate::physical_plan::dynamic_filter::DynamicFilter;
+
+/// Describes a TopK above a [`ParquetExec`]: the TopK only needs the top
+/// `fetch` rows by `expr` of the rows of the scan that pass `filters`.
+///
+/// With late materialization, the scan reads the columns of `expr` and
+/// `filters` first, and the other columns only for these rows.
+///
+/// [`ParquetExec`]: super::ParquetExec
+#[derive(Debug, Clone)]
+pub struct ParquetTopK {
+    /// The sort expressions of the TopK
+    pub expr: Vec<PhysicalSortExpr>,
+    /// The number of rows the TopK returns
+    pub fetch: usize,
+    /// The predicates of the filters between the TopK and the scan
+    pub filters: Vec<Arc<dyn PhysicalExpr>>,
+}
+
+impl ParquetTopK {
+    /// Returns this TopK with its columns, which refer to `schema`,
+    /// replaced by the columns of `file_schema` with the same names, or
+    /// `None` if any of the columns is not in `file_schema`
+    pub(super) fn with_file_schema(
+        &self,
+        schema: &Schema,
+        file_schema: &Schema,
+    ) -> Option<Self> {
+        let rewrite = |expr: &Arc<dyn PhysicalExpr>| {
+            expr.clone()
+                .transform_up(&|expr| {
+                    let Some(column) = expr.as_any().downcast_ref::<Column>() else {
+                        return Ok(Transformed::No(expr));
+                    };
+                    let name = schema.field(column.index()).name();
+                    let index = file_schema.index_of(name).map_err(|_| {
+                        plan_datafusion_err!("{name} is not a column of the file")
+                    })?;
+                    Ok(Transformed::Yes(Arc::new(Column::new(name, index))))
+                })
+                .ok()
+        };
+        Some(Self {
+            expr: self
+                .expr
+                .iter()
+                .map(|e| {
+                    Some(PhysicalSortExpr {
+                        expr: rewrite(&e.expr)?,
+                        options: e.options,
+                    })
+                })
+                .collect::<Option<_>>()?,
+            fetch: self.fetch,
+            filters: self.filters.iter().map(rewrite).collect::<Option<_>>()?,
+        })
+    }
+}
+
+/// Selects the rows of a row group that need to be read, using the
+/// columns of the pushed down predicate, the dynamic filters and the TopK
+/// above the scan.
+#[derive(Debug)]
+pub(super) struct LateMaterialization {
+    /// The table schema projected to the columns needed to select the rows
+    schema: SchemaRef,
+    /// The predicates the rows must pass, referring to `schema`
+    predicate: Option<Arc<dyn PhysicalExpr>>,
+    /// The sort keys and fetch of the TopK, referring to `schema`
+    topk: Option<(Vec<PhysicalSortExpr>, usize)>,
+}
+
+impl LateMaterialization {
+    /// Create a [`LateMaterialization`] for the pushed down `predicate`,
+    /// `topk` and `dynamic_filters`, whose columns refer to `table_schema`.
+    /// Returns `None` if there is nothing to select the rows with.
+    pub(super) fn try_new(
+        predicate: Option<&Arc<dyn PhysicalExpr>>,
+        topk: Option<&ParquetTopK>,
+        dynamic_filters: &[DynamicFilter],
+        table_schema: &Schema,
+    ) -> Result<Option<Self>> {
+        let predicates: Vec<_> = predicate
+            .into_iter()
+            .chain(topk.iter().flat_map(|t| t.filters.iter()))
+            .cloned()
+            .collect();
+        if predicates.is_empty() && topk.is_none() && dynamic_filters.is_empty() {
+            return Ok(None);
+        }
+
+        let mut columns = BTreeSet::new();
+        let exprs = predicates
+            .iter()
+            .chain(topk.iter().flat_map(|t| t.expr.iter().map(|e| &e.expr)));
+        for expr in exprs {
+            columns.extend(collect_columns(expr).iter().map(|c| c.index()));
+        }
+        for filter in dynamic_filters {
+            columns.extend(filter.columns().iter().map(|c| c.index()));
+        }
+        let columns: Vec<_> = columns.into_iter().collect();
+        let schema = Arc::new(table_schema.project(&columns)?);
+
+        let reassign = |expr: Arc<dyn PhysicalExpr>| {
+            reassign_predicate_columns(expr, &schema, false)
+        };
+        let predicate = predicates
+            .into_iter()
+            .reduce(|a, b| Arc::new(BinaryExpr::new(a, Operator::And, b)))
+            .map(reassign)
+            .transpose()?;
+        let topk = match topk {
+            Some(topk) => {
+                let expr = topk
+                    .expr
+                    .iter()
+                    .map(|e| {
+                        Ok(PhysicalSortExpr {
+                            expr: reassign(e.expr.clone())?,
+                            options: e.options,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                Some((expr, topk.fetch))
+            }
+            None => None,
+        };
+
+        Ok(Some(Self {
+            schema,
+            predicate,
+            topk,
+        }))
+    }
+
+    /// The schema of the columns needed to select the rows, the table
+    /// schema projected to the columns
+    pub(super) fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// Returns the rows of `batch`, the columns of [`Self::schema`] of a
+    /// row group, that need to be read. `dynamic_predicate` is the current
+    /// predicate of the dynamic filters, referring to the table schema.
+    pub(super) fn select(
+        &self,
+        batch: &RecordBatch,
+        dynamic_predicate: Option<&Arc<dyn PhysicalExpr>>,
+        metrics: &ParquetFileMetrics,
+    ) -> Result<BooleanArray> {
+        let num_rows = batch.num_rows();
+        let dynamic_predicate = dynamic_predicate
+            .map(|p| reassign_predicate_columns(p.clone(), &self.schema, false))
+            .transpose()?;
+
+        let mut selected = None;
+        let predicates = self.predicate.iter().chain(dynamic_predicate.iter());
+        for predicate in predicates {
+            // scoped timer updates on drop
+            let _timer = metrics.pushdown_eval_time.timer();
+            let array = predicate.evaluate(batch)?.into_array(num_rows)?;
+            let mut mask = as_boolean_array(&array)?.clone();
+            if mask.null_count() > 0 {
+                mask = prep_null_mask_filter(&mask);
+            }
+            selected = Some(match selected {
+                Some(selected) => arrow::compute::and(&selected, &mask)?,
+                None => mask,
+            });
+        }
+        let selected =
+            selected.unwrap_or_else(|| BooleanArray::from(vec![true; num_rows]));
+        metrics
+            .pushdown_rows_filtered
+            .add(num_rows - selected.true_count());
+
+        match &self.topk {
+            Some((expr, fetch)) if selected.true_count() > *fetch => {
+                let top = self.top_rows(batch, &selected, expr, *fetch)?;
+                metrics
+                    .topk_rows_pruned
+                    .add(selected.true_count() - top.true_count());
+                Ok(top)
+            }
+            _ => Ok(selected),
+        }
+    }
+
+    /// Returns the `fetch` rows of `batch` with the lowest sort keys
+    /// among the `selected` rows
+    fn top_rows(
+        &self,
+        batch: &RecordBatch,
+        selected: &BooleanArray,
+        expr: &[PhysicalSortExpr],
+        fetch: usize,
+    ) -> Result<BooleanArray> {
+        let filtered = filter_record_batch(batch, selected)?;
+        let sort_columns = expr
+            .iter()
+            .map(|e| {
+                Ok(SortColumn {
+                    values: e
+                        .expr
+                        .evaluate(&filtered)?
+                        .into_array(filtered.num_rows())?,
+                    options: Some(e.options),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let top = lexsort_to_indices(&sort_columns, Some(fetch))?;
+
+        // the positions of the selected rows in `batch`
+        let positions: Vec<_> =
+            (0..selected.len()).filter(|i| selected.value(*i)).collect();
+        let mut builder = BooleanBufferBuilder::new(batch.num_rows());
+        builder.append_n(batch.num_rows(), false);
+        for i in top.values().iter() {
+            builder.set_bit(positions[*i as usize], true);
+        }
+        Ok(BooleanArray::new(builder.finish(), None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::expressions::{binary, col, lit};
+    use crate::physical_plan::metrics::ExecutionPlanMetricsSet;
+    use arrow::array::Int32Array;
+    use arrow::compute::SortOptions;
+    use arrow::datatypes::{DataType, Field};
+
+    fn table_schema() -> Schema {
+        Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("wide", DataType::Utf8, true),
+            Field::new("b", DataType::Int32, true),
+        ])
+    }
+
+    #[test]
+    fn select_rows() -> Result<()> {
+        let table_schema = table_schema();
+        // a > 1
+        let predicate = binary(
+            col("a", &table_schema)?,
+            Operator::Gt,
+            lit(1),
+            &table_schema,
+        )?;
+        // ORDER BY b DESC LIMIT 2
+        let topk = ParquetTopK {
+            expr: vec![PhysicalSortExpr {
+                expr: col("b", &table_schema)?,
+                options: SortOptions {
+                    descending: true,
+                    nulls_first: false,
+                },
+            }],
+            fetch: 2,
+            filters: vec![],
+        };
+        let late = LateMaterialization::try_new(
+            Some(&predicate),
+            Some(&topk),
+            &[],
+            &table_schema,
+        )?
+        .unwrap();
+
+        // the wide column is not read
+        let schema = late.schema().clone();
+        assert_eq!(
+            schema.fields().iter().map(|f| f.name()).collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![
+                    Some(1),
+                    Some(2),
+                    None,
+                    Some(4),
+                    Some(5),
+                ])),
+                Arc::new(Int32Array::from(vec![10, 2, 30, 4, 5])),
+            ],
+        )?;
+        let metrics = ExecutionPlanMetricsSet::new();
+        let file_metrics = ParquetFileMetrics::new(0, "file", &metrics);
+        let selected = late.select(&batch, None, &file_metrics)?;
+
+        // rows 0 and 2 fail the predicate, row 1 is not in the top 2
+        assert_eq!(
+            selected,
+            BooleanArray::from(vec![false, false, false, true, true])
+        );
+        assert_eq!(file_metrics.pushdown_rows_filtered.value(), 2);
+        assert_eq!(file_metrics.topk_rows_pruned.value(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn topk_with_file_schema() {
+        let table_schema = table_schema();
+        let scan_schema = table_schema.project(&[2, 0]).unwrap();
+        let topk = ParquetTopK {
+            expr: vec![PhysicalSortExpr {
+                expr: col("b", &scan_schema).unwrap(),
+                options: Default::default(),
+            }],
+            fetch: 2,
+            filters: vec![col("a", &scan_schema).unwrap()],
+        };
+        let topk = topk.with_file_schema(&scan_schema, &table_schema).unwrap();
+        assert_eq!(topk.expr[0].expr.to_string(), "b@2");
+        assert_eq!(topk.filters[0].to_string(), "a@0");
+
+        let partition_schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        assert!(topk
+            .with_file_schema(&table_schema, &partition_schema)
+            .is_none());
+    }
+}
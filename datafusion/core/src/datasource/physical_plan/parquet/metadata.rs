@@ -0,0 +1,172 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`CachedParquetMetaData`], the decoded metadata of parquet files stored in
+//! a [`ParquetMetadataCache`]
+//!
+//! [`ParquetMetadataCache`]: crate::execution::cache::cache_manager::ParquetMetadataCache
+
+use std::any::Any;
+use std::mem::{size_of, size_of_val};
+use std::sync::Arc;
+
+use crate::execution::cache::cache_manager::FileMetadata;
+
+use parquet::file::metadata::{ParquetMetaData, RowGroupMetaData};
+use parquet::file::page_index::index::Index;
+use parquet::format::PageLocation;
+use parquet::schema::types::ColumnDescriptor;
+
+/// The decoded [`ParquetMetaData`] of a parquet file, including its page
+/// indexes if it has any, stored in a [`ParquetMetadataCache`]
+///
+/// [`ParquetMetadataCache`]: crate::execution::cache::cache_manager::ParquetMetadataCache
+#[derive(Debug)]
+pub struct CachedParquetMetaData {
+    metadata: Arc<ParquetMetaData>,
+    memory_size: usize,
+}
+
+impl CachedParquetMetaData {
+    /// Create a cache entry of `metadata`
+    pub fn new(metadata: Arc<ParquetMetaData>) -> Self {
+        let memory_size = metadata_memory_size(&metadata);
+        Self {
+            metadata,
+            memory_size,
+        }
+    }
+
+    /// The decoded metadata
+    pub fn metadata(&self) -> &Arc<ParquetMetaData> {
+        &self.metadata
+    }
+}
+
+impl FileMetadata for CachedParquetMetaData {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn memory_size(&self) -> usize {
+        self.memory_size
+    }
+}
+
+/// Estimates the number of bytes of memory used by `metadata`, counting the
+/// schema, the column chunks with their statistics, and the page indexes
+fn metadata_memory_size(metadata: &ParquetMetaData) -> usize {
+    let file_metadata = metadata.file_metadata();
+    let schema: usize = file_metadata
+        .schema_descr()
+        .columns()
+        .iter()
+        .map(|column| {
+            let path = column.path().parts();
+            size_of::<ColumnDescriptor>()
+                + path.iter().map(|part| part.len()).sum::<usize>()
+        })
+        .sum();
+    let key_value: usize = file_metadata
+        .key_value_metadata()
+        .map(|key_value| {
+            key_value
+                .iter()
+                .map(|kv| kv.key.len() + kv.value.as_ref().map_or(0, |v| v.len()))
+                .sum()
+        })
+        .unwrap_or_default();
+
+    let row_groups: usize = metadata
+        .row_groups()
+        .iter()
+        .map(|row_group| {
+            let columns: usize = row_group
+                .columns()
+                .iter()
+                .map(|column| match column.statistics() {
+                    Some(stats) if stats.has_min_max_set() => {
+                        stats.min_bytes().len() + stats.max_bytes().len()
+                    }
+                    _ => 0,
+                })
+                .sum();
+            size_of::<RowGroupMetaData>() + size_of_val(row_group.columns()) + columns
+        })
+        .sum();
+
+    let column_index: usize = metadata
+        .column_index()
+        .map(|row_groups| {
+            row_groups
+                .iter()
+                .flatten()
+                .map(|index| size_of::<Index>() + index_memory_size(index))
+                .sum()
+        })
+        .unwrap_or_default();
+    let offset_index: usize = metadata
+        .offset_index()
+        .map(|row_groups| {
+            row_groups
+                .iter()
+                .flatten()
+                .map(|pages| {
+                    size_of::<Vec<PageLocation>>() + size_of_val(pages.as_slice())
+                })
+                .sum()
+        })
+        .unwrap_or_default();
+
+    size_of::<ParquetMetaData>()
+        + schema
+        + key_value
+        + row_groups
+        + column_index
+        + offset_index
+}
+
+/// The number of bytes of memory used by the pages of a column index
+fn index_memory_size(index: &Index) -> usize {
+    match index {
+        Index::NONE => 0,
+        Index::BOOLEAN(index) => size_of_val(index.indexes.as_slice()),
+        Index::INT32(index) => size_of_val(index.indexes.as_slice()),
+        Index::INT64(index) => size_of_val(index.indexes.as_slice()),
+        Index::INT96(index) => size_of_val(index.indexes.as_slice()),
+        Index::FLOAT(index) => size_of_val(index.indexes.as_slice()),
+        Index::DOUBLE(index) => size_of_val(index.indexes.as_slice()),
+        Index::BYTE_ARRAY(index) => {
+            let values: usize = index
+                .indexes
+                .iter()
+                .flat_map(|page| [&page.min, &page.max])
+                .map(|value| value.as_ref().map_or(0, |v| v.len()))
+                .sum();
+            size_of_val(index.indexes.as_slice()) + values
+        }
+        Index::FIXED_LEN_BYTE_ARRAY(index) => {
+            let values: usize = index
+                .indexes
+                .iter()
+                .flat_map(|page| [&page.min, &page.max])
+                .map(|value| value.as_ref().map_or(0, |v| v.len()))
+                .sum();
+            size_of_val(index.indexes.as_slice()) + values
+        }
+    }
+}
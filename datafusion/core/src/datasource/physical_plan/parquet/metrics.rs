@@ -31,12 +31,18 @@ pub struct ParquetFileMetrics {
     pub predicate_evaluation_errors: Count,
     /// Number of row groups pruned using
     pub row_groups_pruned: Count,
+    /// Number of row groups pruned using dynamic filters, such as the
+    /// threshold of a TopK above the scan
+    pub row_groups_pruned_dynamic_filter: Count,
     /// Total number of bytes scanned
     pub bytes_scanned: Count,
     /// Total rows filtered out by predicates pushed into parquet scan
     pub pushdown_rows_filtered: Count,
     /// Total time spent evaluating pushdown filters
     pub pushdown_eval_time: Time,
+    /// Total rows not read because they cannot be part of the result of a
+    /// TopK above the scan, found by late materialization
+    pub topk_rows_pruned: Count,
     /// Total rows filtered out by parquet page index
     pub page_index_rows_filtered: Count,
     /// Total time spent evaluating parquet page index filters
@@ -58,6 +64,10 @@ impl ParquetFileMetrics {
             .with_new_label("filename", filename.to_string())
             .counter("row_groups_pruned", partition);
 
+        let row_groups_pruned_dynamic_filter = MetricBuilder::new(metrics)
+            .with_new_label("filename", filename.to_string())
+            .counter("row_groups_pruned_dynamic_filter", partition);
+
         let bytes_scanned = MetricBuilder::new(metrics)
             .with_new_label("filename", filename.to_string())
             .counter("bytes_scanned", partition);
@@ -69,6 +79,9 @@ impl ParquetFileMetrics {
         let pushdown_eval_time = MetricBuilder::new(metrics)
             .with_new_label("filename", filename.to_string())
             .subset_time("pushdown_eval_time", partition);
+        let topk_rows_pruned = MetricBuilder::new(metrics)
+            .with_new_label("filename", filename.to_string())
+            .counter("topk_rows_pruned", partition);
         let page_index_rows_filtered = MetricBuilder::new(metrics)
             .with_new_label("filename", filename.to_string())
             .counter("page_index_rows_filtered", partition);
@@ -80,9 +93,11 @@ impl ParquetFileMetrics {
         Self {
             predicate_evaluation_errors,
             row_groups_pruned,
+            row_groups_pruned_dynamic_filter,
             bytes_scanned,
             pushdown_rows_filtered,
             pushdown_eval_time,
+            topk_rows_pruned,
             page_index_rows_filtered,
             page_index_eval_time,
         }
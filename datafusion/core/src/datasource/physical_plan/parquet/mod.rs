@@ -26,26 +26,32 @@ use crate::datasource::physical_plan::file_stream::{
     FileOpenFuture, FileOpener, FileStream,
 };
 use crate::datasource::physical_plan::{
-    parquet::page_filter::PagePruningPredicate, DisplayAs, FileGroupPartitioner,
-    FileMeta, FileScanConfig, SchemaAdapter,
+    parquet::page_filter::PagePruningPredicate, prune_nested_fields, DisplayAs,
+    FileGroupPartitioner, FileMeta, FileScanConfig,
+};
+use crate::datasource::schema_adapter::{
+    DefaultSchemaAdapter, SchemaAdapter, SchemaAdapterFactory,
 };
 use crate::{
     config::ConfigOptions,
     datasource::listing::ListingTableUrl,
     error::{DataFusionError, Result},
-    execution::context::TaskContext,
+    execution::{cache::cache_manager::ParquetMetadataCache, context::TaskContext},
     physical_optimizer::pruning::PruningPredicate,
     physical_plan::{
+        dynamic_filter::DynamicFilter,
         metrics::{ExecutionPlanMetricsSet, MetricBuilder, MetricsSet},
-        DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
-        Statistics,
+        stream::CooperativeStream,
+        DisplayFormatType, ExecutionPlan, ExecutionProgress, Partitioning,
+        SendableRecordBatchStream, Statistics,
     },
 };
 
-use arrow::datatypes::{DataType, SchemaRef};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use arrow::error::ArrowError;
 use datafusion_physical_expr::{
-    EquivalenceProperties, LexOrdering, PhysicalExpr, PhysicalSortExpr,
+    expressions::Column, EquivalenceProperties, LexOrdering, PhysicalExpr,
+    PhysicalSortExpr,
 };
 
 use bytes::Bytes;
@@ -53,23 +59,33 @@ use futures::future::BoxFuture;
 use futures::{StreamExt, TryStreamExt};
 use log::debug;
 use object_store::path::Path;
-use object_store::ObjectStore;
-use parquet::arrow::arrow_reader::ArrowReaderOptions;
+use object_store::{ObjectMeta, ObjectStore};
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions};
 use parquet::arrow::async_reader::{AsyncFileReader, ParquetObjectReader};
 use parquet::arrow::{AsyncArrowWriter, ParquetRecordBatchStreamBuilder, ProjectionMask};
 use parquet::basic::{ConvertedType, LogicalType};
 use parquet::file::{metadata::ParquetMetaData, properties::WriterProperties};
-use parquet::schema::types::ColumnDescriptor;
+use parquet::schema::types::{ColumnDescriptor, SchemaDescriptor};
 use tokio::task::JoinSet;
 
+mod dynamic_pruning;
+mod late_materialization;
+mod metadata;
 mod metrics;
 pub mod page_filter;
 mod row_filter;
 mod row_groups;
+mod row_index;
 mod statistics;
 
+pub use late_materialization::ParquetTopK;
+pub use metadata::CachedParquetMetaData;
 pub use metrics::ParquetFileMetrics;
 
+use dynamic_pruning::{DynamicPruningPredicate, RowGroupReader};
+use late_materialization::LateMaterialization;
+use row_index::RowIndexes;
+
 /// Execution plan for scanning one or more Parquet partitions
 #[derive(Debug, Clone)]
 pub struct ParquetExec {
@@ -85,6 +101,9 @@ pub struct ParquetExec {
     /// Override for `Self::with_enable_bloom_filter`. If None, uses
     /// values from base_config
     enable_bloom_filter: Option<bool>,
+    /// Override for `Self::with_late_materialization`. If None, uses
+    /// values from base_config
+    late_materialization: Option<bool>,
     /// Base configuration for this scan
     base_config: FileScanConfig,
     projected_statistics: Statistics,
@@ -102,6 +121,10 @@ pub struct ParquetExec {
     metadata_size_hint: Option<usize>,
     /// Optional user defined parquet file reader factory
     parquet_file_reader_factory: Option<Arc<dyn ParquetFileReaderFactory>>,
+    /// Filters tightened during execution, referring to the file schema
+    dynamic_filters: Vec<DynamicFilter>,
+    /// The TopK above this scan, referring to the file schema
+    topk: Option<ParquetTopK>,
 }
 
 impl ParquetExec {
@@ -155,6 +178,7 @@ impl ParquetExec {
             reorder_filters: None,
             enable_page_index: None,
             enable_bloom_filter: None,
+            late_materialization: None,
             base_config,
             projected_schema,
             projected_statistics,
@@ -165,6 +189,8 @@ impl ParquetExec {
             page_pruning_predicate,
             metadata_size_hint,
             parquet_file_reader_factory: None,
+            dynamic_filters: vec![],
+            topk: None,
         }
     }
 
@@ -259,6 +285,82 @@ impl ParquetExec {
         self.enable_bloom_filter
             .unwrap_or(config_options.execution.parquet.bloom_filter_enabled)
     }
+
+    /// If enabled, the reader first reads the columns of the pushed down
+    /// predicate, the dynamic filters and the TopK above the scan, and then
+    /// the other projected columns only for the rows that are selected
+    /// with them
+    pub fn with_late_materialization(mut self, late_materialization: bool) -> Self {
+        self.late_materialization = Some(late_materialization);
+        self
+    }
+
+    /// Return the value described in [`Self::with_late_materialization`]
+    fn late_materialization(&self, config_options: &ConfigOptions) -> bool {
+        self.late_materialization
+            .unwrap_or(config_options.execution.parquet.late_materialization)
+    }
+
+    /// Add a [`DynamicFilter`] whose columns refer to the output schema of
+    /// this scan, such as the threshold of a TopK above it.
+    ///
+    /// The row groups of each file are then read one at a time, and skipped
+    /// if the current predicate of the filter rules them out using their
+    /// statistics or page index. Filters on partition columns are ignored.
+    pub fn with_dynamic_filter(mut self, filter: DynamicFilter) -> Result<Self> {
+        let file_schema = &self.base_config.file_schema;
+        let columns = filter
+            .columns()
+            .iter()
+            .map(|c| {
+                let name = self.projected_schema.field(c.index()).name();
+                file_schema
+                    .index_of(name)
+                    .ok()
+                    .map(|index| Column::new(name, index))
+            })
+            .collect::<Option<Vec<_>>>();
+        if let Some(columns) = columns {
+            self.dynamic_filters.push(filter.with_columns(columns)?);
+        }
+        Ok(self)
+    }
+
+    /// The dynamic filters of this scan, see [`Self::with_dynamic_filter`]
+    pub fn dynamic_filters(&self) -> &[DynamicFilter] {
+        &self.dynamic_filters
+    }
+
+    /// Set the TopK above this scan, whose columns refer to the output
+    /// schema of this scan.
+    ///
+    /// With late materialization, only the rows of each row group that can
+    /// be among the top rows are read. The TopK is ignored if it refers to
+    /// partition columns.
+    pub fn with_topk(mut self, topk: ParquetTopK) -> Self {
+        self.topk =
+            topk.with_file_schema(&self.projected_schema, &self.base_config.file_schema);
+        self
+    }
+
+    /// The TopK above this scan, see [`Self::with_topk`]
+    pub fn topk(&self) -> Option<&ParquetTopK> {
+        self.topk.as_ref()
+    }
+
+    /// Read only the nested fields on `paths` of the struct columns the
+    /// paths start at, see [`FileScanConfig::projection_paths`].
+    ///
+    /// The leaves of the other fields of these columns are not decoded.
+    pub fn with_projection_paths(mut self, paths: Vec<Vec<String>>) -> Self {
+        self.base_config.projection_paths = paths;
+        (
+            self.projected_schema,
+            self.projected_statistics,
+            self.projected_output_ordering,
+        ) = self.base_config.project();
+        self
+    }
 }
 
 impl DisplayAs for ParquetExec {
@@ -368,13 +470,25 @@ impl ExecutionPlan for ParquetExec {
                 ctx.runtime_env()
                     .object_store(&self.base_config.object_store_url)
                     .map(|store| {
-                        Arc::new(DefaultParquetFileReaderFactory::new(store))
-                            as Arc<dyn ParquetFileReaderFactory>
+                        let metadata_cache =
+                            ctx.runtime_env().cache_manager.get_parquet_metadata_cache();
+                        Arc::new(
+                            DefaultParquetFileReaderFactory::new(store)
+                                .with_metadata_cache(metadata_cache),
+                        ) as Arc<dyn ParquetFileReaderFactory>
                     })
             })?;
 
         let config_options = ctx.session_config().options();
 
+        // filters are evaluated on the file columns with the names of the
+        // table columns, which a custom schema adapter may read other
+        // file columns as
+        let default_adapter = self.base_config.schema_adapter_factory.is_none();
+        // the row indexes are computed from the row groups and pages that
+        // are read, so rows may not be filtered in any other way
+        let row_index = self.base_config.row_index_position();
+
         let opener = ParquetOpener {
             partition_index,
             projection: Arc::from(projection),
@@ -387,27 +501,108 @@ impl ExecutionPlan for ParquetExec {
             metadata_size_hint: self.metadata_size_hint,
             metrics: self.metrics.clone(),
             parquet_file_reader_factory,
-            pushdown_filters: self.pushdown_filters(config_options),
+            pushdown_filters: self.pushdown_filters(config_options)
+                && default_adapter
+                && row_index.is_none(),
             reorder_filters: self.reorder_filters(config_options),
-            enable_page_index: self.enable_page_index(config_options),
+            enable_page_index: self.enable_page_index(config_options) && default_adapter,
             enable_bloom_filter: self.enable_bloom_filter(config_options),
+            dynamic_filters: self.dynamic_filters.clone(),
+            late_materialization: self.late_materialization(config_options)
+                && default_adapter
+                && row_index.is_none(),
+            topk: self.topk.clone(),
+            schema_adapter_factory: self.base_config.schema_adapter_factory.clone(),
+            row_index,
+            projection_paths: self.base_config.projection_paths.clone(),
         };
 
         let stream =
             FileStream::new(&self.base_config, partition_index, opener, &self.metrics)?;
 
-        Ok(Box::pin(stream))
+        Ok(Box::pin(CooperativeStream::new(Box::pin(stream), &ctx)))
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
         Some(self.metrics.clone_inner())
     }
 
+    fn progress(&self) -> ExecutionProgress {
+        self.base_config.progress(&self.metrics)
+    }
+
     fn statistics(&self) -> Result<Statistics> {
         Ok(self.projected_statistics.clone())
     }
 }
 
+/// Returns `file_schema` with the fields that `schema_adapter` reads as a
+/// column of `table_schema` renamed to the name of that column, and the other
+/// fields renamed to the empty string
+fn pruning_file_schema(
+    file_schema: &Schema,
+    table_schema: &Schema,
+    schema_adapter: &dyn SchemaAdapter,
+) -> SchemaRef {
+    let mut names = vec![String::new(); file_schema.fields().len()];
+    for (table_idx, table_field) in table_schema.fields().iter().enumerate() {
+        if let Some(file_idx) = schema_adapter.map_column_index(table_idx, file_schema) {
+            names[file_idx] = table_field.name().clone();
+        }
+    }
+    let fields: Vec<Field> = file_schema
+        .fields()
+        .iter()
+        .zip(names)
+        .map(|(field, name)| field.as_ref().clone().with_name(name))
+        .collect();
+    Arc::new(Schema::new(fields))
+}
+
+/// Returns the `paths` to nested fields that lead to columns of the parquet
+/// file `parquet_schema`, see [`FileScanConfig::projection_paths`]
+fn file_projection_paths(
+    parquet_schema: &SchemaDescriptor,
+    paths: &[Vec<String>],
+) -> Vec<Vec<String>> {
+    paths
+        .iter()
+        .filter(|path| {
+            parquet_schema
+                .columns()
+                .iter()
+                .any(|column| column.path().parts().starts_with(path))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Returns the mask of the columns of the parquet file `parquet_schema` that
+/// are the leaves of its root columns `roots`, restricted to the leaves on
+/// `paths` for the root columns the paths start at
+fn leaf_projection_mask(
+    parquet_schema: &SchemaDescriptor,
+    roots: &[usize],
+    paths: &[Vec<String>],
+) -> ProjectionMask {
+    if paths.is_empty() {
+        return ProjectionMask::roots(parquet_schema, roots.iter().cloned());
+    }
+    let leaves = (0..parquet_schema.num_columns()).filter(|leaf| {
+        if !roots.contains(&parquet_schema.get_column_root_idx(*leaf)) {
+            return false;
+        }
+        let column = parquet_schema.column(*leaf);
+        let parts = column.path().parts();
+        let mut root_paths = paths
+            .iter()
+            .filter(|path| path.first() == parts.first())
+            .peekable();
+        root_paths.peek().is_none() || root_paths.any(|path| parts.starts_with(path))
+    });
+    ProjectionMask::leaves(parquet_schema, leaves)
+}
+
 /// Implements [`FileOpener`] for a parquet file
 struct ParquetOpener {
     partition_index: usize,
@@ -425,9 +620,22 @@ struct ParquetOpener {
     reorder_filters: bool,
     enable_page_index: bool,
     enable_bloom_filter: bool,
+    dynamic_filters: Vec<DynamicFilter>,
+    late_materialization: bool,
+    topk: Option<ParquetTopK>,
+    schema_adapter_factory: Option<Arc<dyn SchemaAdapterFactory>>,
+    /// The position of the `_row_index` column in the batches, if projected
+    row_index: Option<usize>,
+    /// The nested fields to read of the projected columns, see
+    /// [`FileScanConfig::projection_paths`]
+    projection_paths: Vec<Vec<String>>,
 }
 
 impl FileOpener for ParquetOpener {
+    fn supports_row_index(&self) -> bool {
+        true
+    }
+
     fn open(&self, file_meta: FileMeta) -> Result<FileOpenFuture> {
         let file_range = file_meta.range.clone();
 
@@ -437,6 +645,32 @@ impl FileOpener for ParquetOpener {
             &self.metrics,
         );
 
+        let late_materialization = match self.late_materialization {
+            true if self.limit.is_none() => LateMaterialization::try_new(
+                self.pushdown_filters
+                    .then_some(self.predicate.as_ref())
+                    .flatten(),
+                self.topk.as_ref(),
+                &self.dynamic_filters,
+                &self.table_schema,
+            )?,
+            _ => None,
+        };
+
+        // read the row groups one at a time to prune them with the
+        // dynamic filters as they are when each row group is reached
+        let dynamic_predicate = (!self.dynamic_filters.is_empty()).then(|| {
+            DynamicPruningPredicate::new(
+                self.dynamic_filters.clone(),
+                self.table_schema.clone(),
+            )
+        });
+        let row_group_reader = ((dynamic_predicate.is_some()
+            || late_materialization.is_some())
+            && self.limit.is_none()
+            && self.row_index.is_none())
+        .then(|| (file_meta.clone(), dynamic_predicate, late_materialization));
+
         let reader: Box<dyn AsyncFileReader> =
             self.parquet_file_reader_factory.create_reader(
                 self.partition_index,
@@ -447,8 +681,20 @@ impl FileOpener for ParquetOpener {
 
         let batch_size = self.batch_size;
         let projection = self.projection.clone();
-        let projected_schema = SchemaRef::from(self.table_schema.project(&projection)?);
-        let schema_adapter = SchemaAdapter::new(projected_schema);
+        let projection_paths = self.projection_paths.clone();
+        let projected_schema = Arc::new(prune_nested_fields(
+            &self.table_schema.project(&projection)?,
+            &projection_paths,
+        ));
+        let schema_adapter: Box<dyn SchemaAdapter> = match &self.schema_adapter_factory {
+            Some(factory) => factory.create(projected_schema.clone()),
+            None => Box::new(DefaultSchemaAdapter::new(projected_schema.clone())),
+        };
+        let row_index = self.row_index;
+        let table_schema_adapter = self
+            .schema_adapter_factory
+            .as_ref()
+            .map(|factory| factory.create(self.table_schema.clone()));
         let predicate = self.predicate.clone();
         let pruning_predicate = self.pruning_predicate.clone();
         let page_pruning_predicate = self.page_pruning_predicate.clone();
@@ -458,9 +704,14 @@ impl FileOpener for ParquetOpener {
         let enable_page_index = should_enable_page_index(
             self.enable_page_index,
             &self.page_pruning_predicate,
-        );
+        ) || (self.enable_page_index
+            && row_group_reader.is_some());
         let enable_bloom_filter = self.enable_bloom_filter;
         let limit = self.limit;
+        let partition_index = self.partition_index;
+        let metadata_size_hint = self.metadata_size_hint;
+        let metrics = self.metrics.clone();
+        let parquet_file_reader_factory = self.parquet_file_reader_factory.clone();
 
         Ok(Box::pin(async move {
             let options = ArrowReaderOptions::new().with_page_index(enable_page_index);
@@ -470,17 +721,31 @@ impl FileOpener for ParquetOpener {
 
             let file_schema = builder.schema().clone();
 
-            let (schema_mapping, adapted_projections) =
-                schema_adapter.map_schema(&file_schema)?;
+            // the nested fields that are missing from the file are read as
+            // part of whole columns
+            let file_paths =
+                file_projection_paths(builder.parquet_schema(), &projection_paths);
+            let (schema_mapping, adapted_projections) = schema_adapter
+                .map_schema(&prune_nested_fields(&file_schema, &file_paths))?;
+            // the statistics of the file columns are looked up with the
+            // names of the table columns they are read as
+            let file_schema = match &table_schema_adapter {
+                Some(adapter) => {
+                    pruning_file_schema(&file_schema, &table_schema, adapter.as_ref())
+                }
+                None => file_schema,
+            };
             // let predicate = predicate.map(|p| reassign_predicate_columns(p, builder.schema(), true)).transpose()?;
 
-            let mask = ProjectionMask::roots(
+            let mask = leaf_projection_mask(
                 builder.parquet_schema(),
-                adapted_projections.iter().cloned(),
+                &adapted_projections,
+                &file_paths,
             );
 
             // Filter pushdown: evaluate predicates during scan
-            if let Some(predicate) = pushdown_filters.then_some(predicate).flatten() {
+            let pushdown_predicate = pushdown_filters.then_some(predicate).flatten();
+            if let Some(predicate) = pushdown_predicate.clone() {
                 let row_filter = row_filter::build_row_filter(
                     &predicate,
                     &file_schema,
@@ -533,19 +798,67 @@ impl FileOpener for ParquetOpener {
                 }
             }
 
+            if let Some((file_meta, dynamic_predicate, late_materialization)) =
+                row_group_reader
+            {
+                // the pushed down predicate is evaluated when selecting the
+                // rows with late materialization
+                let pushdown_predicate = match &late_materialization {
+                    Some(_) => None,
+                    None => pushdown_predicate,
+                };
+                let stream = RowGroupReader {
+                    partition_index,
+                    file_meta,
+                    metadata_size_hint,
+                    metrics,
+                    file_metrics,
+                    parquet_file_reader_factory,
+                    metadata: file_metadata,
+                    file_schema,
+                    table_schema,
+                    mask,
+                    batch_size,
+                    pushdown_predicate,
+                    reorder_predicates,
+                    enable_page_index,
+                    page_pruning_predicate,
+                    dynamic_predicate,
+                    late_materialization,
+                }
+                .read(row_groups)
+                .map(move |maybe_batch| {
+                    maybe_batch
+                        .and_then(|b| schema_mapping.map_batch(b).map_err(Into::into))
+                });
+                return Ok(stream.boxed());
+            }
+
             // page index pruning: if all data on individual pages can
             // be ruled using page metadata, rows from other columns
             // with that range can be skipped as well
+            let mut row_selection = None;
             if enable_page_index && !row_groups.is_empty() {
                 if let Some(p) = page_pruning_predicate {
                     let pruned =
                         p.prune(&row_groups, file_metadata.as_ref(), &file_metrics)?;
-                    if let Some(row_selection) = pruned {
-                        builder = builder.with_row_selection(row_selection);
+                    if let Some(selection) = pruned {
+                        builder = builder.with_row_selection(selection.clone());
+                        row_selection = Some(selection);
                     }
                 }
             }
 
+            let mut row_indexes = row_index.map(|position| {
+                RowIndexes::new(
+                    file_metadata.row_groups(),
+                    &row_groups,
+                    row_selection.as_ref(),
+                    position,
+                    &projected_schema,
+                )
+            });
+
             if let Some(limit) = limit {
                 builder = builder.with_limit(limit)
             }
@@ -559,8 +872,14 @@ impl FileOpener for ParquetOpener {
             let adapted = stream
                 .map_err(|e| ArrowError::ExternalError(Box::new(e)))
                 .map(move |maybe_batch| {
-                    maybe_batch
-                        .and_then(|b| schema_mapping.map_batch(b).map_err(Into::into))
+                    let batch = maybe_batch
+                        .and_then(|b| schema_mapping.map_batch(b).map_err(Into::into))?;
+                    match &mut row_indexes {
+                        Some(row_indexes) => {
+                            row_indexes.insert(batch).map_err(Into::into)
+                        }
+                        None => Ok(batch),
+                    }
                 });
 
             Ok(adapted.boxed())
@@ -595,15 +914,35 @@ pub trait ParquetFileReaderFactory: Debug + Send + Sync + 'static {
 }
 
 /// Default parquet reader factory.
+///
+/// If it has a [`ParquetMetadataCache`], the readers get the metadata of the
+/// files, including their page indexes, from the cache, and read and decode it
+/// only for the files that are not cached yet.
 #[derive(Debug)]
 pub struct DefaultParquetFileReaderFactory {
     store: Arc<dyn ObjectStore>,
+    metadata_cache: Option<ParquetMetadataCache>,
 }
 
 impl DefaultParquetFileReaderFactory {
     /// Create a factory.
     pub fn new(store: Arc<dyn ObjectStore>) -> Self {
-        Self { store }
+        Self {
+            store,
+            metadata_cache: None,
+        }
+    }
+
+    /// Get the metadata of the files from `metadata_cache`, see
+    /// [`CacheManager::get_parquet_metadata_cache`]
+    ///
+    /// [`CacheManager::get_parquet_metadata_cache`]: crate::execution::cache::cache_manager::CacheManager::get_parquet_metadata_cache
+    pub fn with_metadata_cache(
+        mut self,
+        metadata_cache: Option<ParquetMetadataCache>,
+    ) -> Self {
+        self.metadata_cache = metadata_cache;
+        self
     }
 }
 
@@ -611,6 +950,8 @@ impl DefaultParquetFileReaderFactory {
 pub(crate) struct ParquetFileReader {
     file_metrics: ParquetFileMetrics,
     inner: ParquetObjectReader,
+    /// The cache of the metadata of the file and its [`ObjectMeta`]
+    metadata_cache: Option<(ParquetMetadataCache, ObjectMeta)>,
 }
 
 impl AsyncFileReader for ParquetFileReader {
@@ -637,7 +978,32 @@ impl AsyncFileReader for ParquetFileReader {
     fn get_metadata(
         &mut self,
     ) -> BoxFuture<'_, parquet::errors::Result<Arc<ParquetMetaData>>> {
-        self.inner.get_metadata()
+        let Some((cache, object_meta)) = self.metadata_cache.clone() else {
+            return self.inner.get_metadata();
+        };
+        Box::pin(async move {
+            let location = &object_meta.location;
+            let cached = cache.get_with_extra(location, &object_meta);
+            if let Some(cached) = cached
+                .as_ref()
+                .and_then(|c| c.as_any().downcast_ref::<CachedParquetMetaData>())
+            {
+                return Ok(cached.metadata().clone());
+            }
+
+            // the page index is cached with the metadata, so that it is read
+            // once whichever queries prune pages. The cache is detached while
+            // loading, so that the nested `get_metadata` reads the footer
+            let options = ArrowReaderOptions::new().with_page_index(true);
+            let metadata_cache = self.metadata_cache.take();
+            let loaded = ArrowReaderMetadata::load_async(&mut *self, options).await;
+            self.metadata_cache = metadata_cache;
+            let metadata = Arc::clone(loaded?.metadata());
+
+            let cached = CachedParquetMetaData::new(metadata.clone());
+            cache.put_with_extra(location, Arc::new(cached), &object_meta);
+            Ok(metadata)
+        })
     }
 }
 
@@ -655,6 +1021,10 @@ impl ParquetFileReaderFactory for DefaultParquetFileReaderFactory {
             metrics,
         );
         let store = Arc::clone(&self.store);
+        let metadata_cache = self
+            .metadata_cache
+            .clone()
+            .map(|cache| (cache, file_meta.object_meta.clone()));
         let mut inner = ParquetObjectReader::new(store, file_meta.object_meta);
 
         if let Some(hint) = metadata_size_hint {
@@ -664,6 +1034,7 @@ impl ParquetFileReaderFactory for DefaultParquetFileReaderFactory {
         Ok(Box::new(ParquetFileReader {
             inner,
             file_metrics,
+            metadata_cache,
         }))
     }
 }
@@ -755,7 +1126,11 @@ mod tests {
     use crate::datasource::file_format::test_util::scan_format;
     use crate::datasource::listing::{FileRange, ListingOptions, PartitionedFile};
     use crate::datasource::object_store::ObjectStoreUrl;
+    use crate::execution::cache::cache_manager::CacheManagerConfig;
+    use crate::execution::cache::cache_unit::DefaultParquetMetadataCache;
+    use crate::execution::cache::{CacheAccessor, CacheStats};
     use crate::execution::context::SessionState;
+    use crate::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
     use crate::physical_plan::displayable;
     use crate::prelude::{ParquetReadOptions, SessionConfig, SessionContext};
     use crate::test::object_store::local_unpartitioned_file;
@@ -768,11 +1143,12 @@ mod tests {
     use arrow::datatypes::Schema;
     use arrow::record_batch::RecordBatch;
     use arrow::{
-        array::{Int64Array, Int8Array, StringArray},
+        array::{Int64Array, Int8Array, StringArray, StructArray},
         datatypes::{DataType, Field, SchemaBuilder},
     };
     use arrow_array::Date64Array;
     use chrono::{TimeZone, Utc};
+    use datafusion_common::cast::{as_string_array, as_struct_array};
     use datafusion_common::{assert_contains, ToDFSchema};
     use datafusion_common::{FileType, GetExt, ScalarValue};
     use datafusion_expr::{col, lit, when, Expr};
@@ -800,6 +1176,7 @@ mod tests {
     #[derive(Debug, Default)]
     struct RoundTrip {
         projection: Option<Vec<usize>>,
+        projection_paths: Vec<Vec<String>>,
         schema: Option<SchemaRef>,
         predicate: Option<Expr>,
         pushdown_predicate: bool,
@@ -816,6 +1193,14 @@ mod tests {
             self
         }
 
+        fn with_projection_paths(mut self, paths: &[&[&str]]) -> Self {
+            self.projection_paths = paths
+                .iter()
+                .map(|path| path.iter().map(|name| name.to_string()).collect())
+                .collect();
+            self
+        }
+
         fn with_schema(mut self, schema: SchemaRef) -> Self {
             self.schema = Some(schema);
             self
@@ -848,6 +1233,7 @@ mod tests {
         async fn round_trip(self, batches: Vec<RecordBatch>) -> RoundTripResult {
             let Self {
                 projection,
+                projection_paths,
                 schema,
                 predicate,
                 pushdown_predicate,
@@ -883,6 +1269,9 @@ mod tests {
                     limit: None,
                     table_partition_cols: vec![],
                     output_ordering: vec![],
+                    schema_adapter_factory: None,
+                    metadata_cols: vec![],
+                    projection_paths,
                 },
                 predicate,
                 None,
@@ -1184,6 +1573,43 @@ mod tests {
         assert_batches_sorted_eq!(expected, &read);
     }
 
+    #[tokio::test]
+    async fn nested_projection_paths() -> Result<()> {
+        let c: ArrayRef = Arc::new(StringArray::from(vec![Some("x"), None]));
+        let d: ArrayRef = Arc::new(Int32Array::from(vec![1, 2]));
+        let b: ArrayRef = Arc::new(StructArray::from(vec![
+            (Arc::new(Field::new("c", DataType::Utf8, true)), c),
+            (Arc::new(Field::new("d", DataType::Int32, true)), d),
+        ]));
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![3, 4]));
+        let s: ArrayRef = Arc::new(StructArray::from(vec![
+            (Arc::new(Field::new("a", DataType::Int32, true)), a),
+            (Arc::new(Field::new("b", b.data_type().clone(), true)), b),
+        ]));
+        let i: ArrayRef = Arc::new(Int32Array::from(vec![5, 6]));
+        let batch = create_batch(vec![("i", i), ("s", s)]);
+
+        let rt = RoundTrip::new()
+            .with_projection(vec![1])
+            .with_projection_paths(&[&["s", "b", "c"]])
+            .round_trip(vec![batch])
+            .await;
+        let read = rt.batches?;
+
+        // only the leaf `s.b.c` is read
+        let c_field = Field::new("c", DataType::Utf8, true);
+        let b_field = Field::new_struct("b", vec![c_field], true);
+        let expected_schema =
+            Schema::new(vec![Field::new_struct("s", vec![b_field], true)]);
+        assert_eq!(rt.parquet_exec.schema().as_ref(), &expected_schema);
+        assert_eq!(read[0].schema().as_ref(), &expected_schema);
+
+        let s = as_struct_array(read[0].column(0))?;
+        let c = as_string_array(as_struct_array(s.column(0))?.column(0))?;
+        assert_eq!(c.iter().collect::<Vec<_>>(), vec![Some("x"), None]);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn evolved_schema_filter() {
         let c1: ArrayRef =
@@ -1539,6 +1965,9 @@ mod tests {
                     limit: None,
                     table_partition_cols: vec![],
                     output_ordering: vec![],
+                    schema_adapter_factory: None,
+                    metadata_cols: vec![],
+                    projection_paths: vec![],
                 },
                 None,
                 None,
@@ -1653,6 +2082,9 @@ mod tests {
                     ),
                 ],
                 output_ordering: vec![],
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
             },
             None,
             None,
@@ -1716,6 +2148,9 @@ mod tests {
                 limit: None,
                 table_partition_cols: vec![],
                 output_ordering: vec![],
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
             },
             None,
             None,
@@ -1785,6 +2220,50 @@ mod tests {
         create_batch(vec![("c1", c1.clone())])
     }
 
+    #[tokio::test]
+    async fn parquet_exec_metadata_cache() -> Result<()> {
+        let metadata_cache = Arc::new(DefaultParquetMetadataCache::default());
+        let cache_config = CacheManagerConfig::default()
+            .with_parquet_metadata_cache(Some(metadata_cache.clone()));
+        let runtime =
+            RuntimeEnv::new(RuntimeConfig::new().with_cache_manager(cache_config))?;
+        let ctx = SessionContext::new_with_config_rt(
+            SessionConfig::new().with_target_partitions(1),
+            Arc::new(runtime),
+        );
+
+        let testdata = crate::test_util::parquet_test_data();
+        let filename = format!("{testdata}/alltypes_tiny_pages.parquet");
+        ctx.register_parquet("t", &filename, ParquetReadOptions::default())
+            .await?;
+
+        for _ in 0..2 {
+            let batches = ctx
+                .sql("SELECT id FROM t WHERE id = 5")
+                .await?
+                .collect()
+                .await?;
+            assert_batches_sorted_eq!(
+                ["+----+", "| id |", "+----+", "| 5  |", "+----+"],
+                &batches
+            );
+        }
+
+        // the metadata is read once, with the page index
+        assert_eq!(metadata_cache.stats(), CacheStats { hits: 1, misses: 1 });
+        let location = Path::from_filesystem_path(&filename).unwrap();
+        let cached = metadata_cache.get(&location).unwrap();
+        let cached = cached
+            .as_any()
+            .downcast_ref::<CachedParquetMetaData>()
+            .unwrap();
+        assert!(cached.metadata().column_index().is_some());
+        assert!(cached.metadata().offset_index().is_some());
+        assert!(metadata_cache.memory_used() > 0);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn parquet_exec_metrics() {
         // batch1: c1(string)
@@ -72,25 +72,15 @@ pub(crate) fn prune_row_groups_by_statistics(
         }
 
         if let Some(predicate) = predicate {
-            let pruning_stats = RowGroupPruningStatistics {
-                parquet_schema,
-                row_group_metadata: metadata,
+            if !row_group_matches(
                 arrow_schema,
-            };
-            match predicate.prune(&pruning_stats) {
-                Ok(values) => {
-                    // NB: false means don't scan row group
-                    if !values[0] {
-                        metrics.row_groups_pruned.add(1);
-                        continue;
-                    }
-                }
-                // stats filter array could not be built
-                // return a closure which will not filter out any row groups
-                Err(e) => {
-                    log::debug!("Error evaluating row group predicate values {e}");
-                    metrics.predicate_evaluation_errors.add(1);
-                }
+                parquet_schema,
+                metadata,
+                predicate,
+                metrics,
+            ) {
+                metrics.row_groups_pruned.add(1);
+                continue;
             }
         }
 
@@ -99,6 +89,33 @@ pub(crate) fn prune_row_groups_by_statistics(
     filtered
 }
 
+/// Returns false if the statistics of the row group with `metadata` show
+/// that `predicate` rules out all of its rows
+pub(crate) fn row_group_matches(
+    arrow_schema: &Schema,
+    parquet_schema: &SchemaDescriptor,
+    metadata: &RowGroupMetaData,
+    predicate: &PruningPredicate,
+    metrics: &ParquetFileMetrics,
+) -> bool {
+    let pruning_stats = RowGroupPruningStatistics {
+        parquet_schema,
+        row_group_metadata: metadata,
+        arrow_schema,
+    };
+    match predicate.prune(&pruning_stats) {
+        // NB: false means don't scan row group
+        Ok(values) => values[0],
+        // stats filter array could not be built
+        // so the row group can not be filtered out
+        Err(e) => {
+            log::debug!("Error evaluating row group predicate values {e}");
+            metrics.predicate_evaluation_errors.add(1);
+            true
+        }
+    }
+}
+
 /// Prune row groups by bloom filters
 ///
 /// Returns a vector of indexes into `groups` which should be scanned.
@@ -1259,6 +1276,7 @@ mod tests {
         let reader = ParquetFileReader {
             inner: ParquetObjectReader::new(Arc::new(in_memory), object_meta),
             file_metrics: file_metrics.clone(),
+            metadata_cache: None,
         };
         let mut builder = ParquetRecordBatchStreamBuilder::new(reader).await.unwrap();
 
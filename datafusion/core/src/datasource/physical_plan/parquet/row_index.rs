@@ -0,0 +1,201 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Computes the `_row_index` metadata column of parquet scans
+
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, UInt64Array};
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use datafusion_common::{internal_err, DataFusionError, Result};
+use parquet::arrow::arrow_reader::RowSelection;
+use parquet::file::metadata::RowGroupMetaData;
+
+use crate::datasource::physical_plan::MetadataColumn;
+
+/// Generates the indexes in the file of the rows read from a parquet file,
+/// and inserts them in the batches read from the file.
+///
+/// The rows are read from the row groups `row_groups`, in that order, and,
+/// within them, from the rows of the [`RowSelection`], if any. Rows may not be
+/// filtered in any other way than by a limit.
+pub(crate) struct RowIndexes {
+    /// The ranges of the indexes of the rows that remain to be read
+    ranges: VecDeque<Range<u64>>,
+    /// The position of the row index column in the batches
+    position: usize,
+    /// The schema of the batches with the row index column
+    schema: SchemaRef,
+}
+
+impl RowIndexes {
+    pub(crate) fn new(
+        metadata: &[RowGroupMetaData],
+        row_groups: &[usize],
+        row_selection: Option<&RowSelection>,
+        position: usize,
+        file_schema: &Schema,
+    ) -> Self {
+        let mut starts = Vec::with_capacity(metadata.len());
+        let mut start = 0;
+        for row_group in metadata {
+            starts.push(start);
+            start += row_group.num_rows() as u64;
+        }
+        // the ranges of the rows of the row groups that are read
+        let mut groups = row_groups.iter().map(|idx| {
+            let start = starts[*idx];
+            start..start + metadata[*idx].num_rows() as u64
+        });
+
+        let ranges = match row_selection {
+            None => groups.collect(),
+            Some(selection) => {
+                // the selection spans the rows of the row groups that are read
+                let mut ranges = VecDeque::new();
+                let mut group = groups.next().unwrap_or_default();
+                for selector in selection.iter() {
+                    let mut remaining = selector.row_count as u64;
+                    while remaining > 0 {
+                        if group.is_empty() {
+                            match groups.next() {
+                                Some(next) => group = next,
+                                None => break,
+                            }
+                        }
+                        let n = remaining.min(group.end - group.start);
+                        if !selector.skip {
+                            ranges.push_back(group.start..group.start + n);
+                        }
+                        group.start += n;
+                        remaining -= n;
+                    }
+                }
+                ranges
+            }
+        };
+
+        let mut fields = file_schema.fields().to_vec();
+        fields.insert(position, Arc::new(MetadataColumn::RowIndex.field()));
+        Self {
+            ranges,
+            position,
+            schema: Arc::new(Schema::new(fields)),
+        }
+    }
+
+    /// Inserts the indexes of the rows of `batch`, the next batch read from
+    /// the file, in it
+    pub(crate) fn insert(&mut self, batch: RecordBatch) -> Result<RecordBatch> {
+        let mut indexes = Vec::with_capacity(batch.num_rows());
+        while indexes.len() < batch.num_rows() {
+            let Some(range) = self.ranges.front_mut() else {
+                return internal_err!("Read more rows than selected from parquet file");
+            };
+            let n = (batch.num_rows() - indexes.len())
+                .min((range.end - range.start) as usize);
+            indexes.extend(range.start..range.start + n as u64);
+            range.start += n as u64;
+            if range.is_empty() {
+                self.ranges.pop_front();
+            }
+        }
+
+        let mut columns = batch.columns().to_vec();
+        columns.insert(
+            self.position,
+            Arc::new(UInt64Array::from(indexes)) as ArrayRef,
+        );
+        let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+        Ok(RecordBatch::try_new_with_options(
+            self.schema.clone(),
+            columns,
+            &options,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field};
+    use parquet::arrow::arrow_reader::RowSelector;
+    use parquet::schema::types::{SchemaDescPtr, SchemaDescriptor, Type};
+
+    fn row_groups(num_rows: &[i64]) -> Vec<RowGroupMetaData> {
+        let schema: SchemaDescPtr = Arc::new(SchemaDescriptor::new(Arc::new(
+            Type::group_type_builder("schema").build().unwrap(),
+        )));
+        num_rows
+            .iter()
+            .map(|n| {
+                RowGroupMetaData::builder(schema.clone())
+                    .set_num_rows(*n)
+                    .build()
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    fn batch(num_rows: usize) -> RecordBatch {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        let a = Int32Array::from(vec![0; num_rows]);
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(a)]).unwrap()
+    }
+
+    fn indexes(batch: &RecordBatch, position: usize) -> Vec<u64> {
+        let column = batch.column(position);
+        let column = column.as_any().downcast_ref::<UInt64Array>().unwrap();
+        column.values().to_vec()
+    }
+
+    #[test]
+    fn row_indexes_of_skipped_row_groups_and_pages() -> Result<()> {
+        let metadata = row_groups(&[3, 4, 5]);
+        // skip the first row group, and rows of the others
+        let selection = RowSelection::from(vec![
+            RowSelector::skip(1),
+            RowSelector::select(2),
+            RowSelector::skip(2),
+            RowSelector::select(2),
+        ]);
+        let file_schema = batch(0).schema();
+        let mut row_indexes =
+            RowIndexes::new(&metadata, &[1, 2], Some(&selection), 1, &file_schema);
+
+        let first = row_indexes.insert(batch(3))?;
+        assert_eq!(first.schema().field(1).name(), "_row_index");
+        assert_eq!(indexes(&first, 1), vec![4, 5, 8]);
+        let second = row_indexes.insert(batch(1))?;
+        assert_eq!(indexes(&second, 1), vec![9]);
+        assert!(row_indexes.insert(batch(1)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn row_indexes_without_selection() -> Result<()> {
+        let metadata = row_groups(&[2, 2]);
+        let file_schema = batch(0).schema();
+        let mut row_indexes = RowIndexes::new(&metadata, &[1], None, 0, &file_schema);
+        assert_eq!(indexes(&row_indexes.insert(batch(2))?, 0), vec![2, 3]);
+        Ok(())
+    }
+}
@@ -0,0 +1,749 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`SchemaAdapter`]: adapting the record batches read from files to the
+//! schema of a table, which may differ from the schemas of the files it was
+//! obtained from.
+//!
+//! This is useful for enabling schema evolution in partitioned datasets. The
+//! [`SchemaAdapterFactory`] of a scan is set with
+//! [`FileScanConfig::schema_adapter_factory`] or
+//! [`ListingTable::with_schema_adapter_factory`].
+//!
+//! [`FileScanConfig::schema_adapter_factory`]: crate::datasource::physical_plan::FileScanConfig::schema_adapter_factory
+//! [`ListingTable::with_schema_adapter_factory`]: crate::datasource::listing::ListingTable::with_schema_adapter_factory
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use arrow::array::{
+    make_array, new_null_array, Array, ArrayRef, AsArray, GenericListArray,
+};
+use arrow::compute::{can_cast_types, cast};
+use arrow::datatypes::{DataType, Field, FieldRef, Fields, Schema, SchemaRef};
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use arrow_array::{OffsetSizeTrait, StructArray};
+use datafusion_common::{plan_err, DataFusionError, Result};
+
+/// Creates the [`SchemaAdapter`]s that adapt the files of a scan to the
+/// schema of the table.
+pub trait SchemaAdapterFactory: Debug + Send + Sync + 'static {
+    /// Create a [`SchemaAdapter`] from the (projected) schema of the table
+    fn create(&self, table_schema: SchemaRef) -> Box<dyn SchemaAdapter>;
+}
+
+/// Adapts the record batches read from a file to the schema of the table.
+///
+/// This has to be done in two stages.
+///
+/// 1. Before reading the file, we have to map projected column indexes from the table schema to
+///    the file schema.
+///
+/// 2. After reading a record batch we need to map the read columns back to the expected columns
+///    indexes and insert null-valued columns wherever the file schema was missing a colum present
+///    in the table schema.
+pub trait SchemaAdapter: Send + Sync {
+    /// Map a column index in the table schema to a column index in a particular
+    /// file schema
+    ///
+    /// Panics if index is not in range for the table schema
+    fn map_column_index(&self, index: usize, file_schema: &Schema) -> Option<usize>;
+
+    /// Creates a [`SchemaMapper`] that can be used to cast or map the columns
+    /// from the file schema to the table schema.
+    ///
+    /// Returns the [`SchemaMapper`] along with an ordered list of the columns
+    /// to project from the file, in the order of the file schema
+    fn map_schema(
+        &self,
+        file_schema: &Schema,
+    ) -> Result<(Arc<dyn SchemaMapper>, Vec<usize>)>;
+}
+
+/// Adapts the record batches of the columns projected from a file, as
+/// returned by [`SchemaAdapter::map_schema`], to the table schema
+pub trait SchemaMapper: Debug + Send + Sync {
+    /// Adapts a `RecordBatch` to match the `table_schema`
+    fn map_batch(&self, batch: RecordBatch) -> Result<RecordBatch>;
+}
+
+/// The [`SchemaAdapterFactory`] used when none is configured: columns are
+/// matched by name and cast to the type of the table column.
+#[derive(Clone, Debug, Default)]
+pub struct DefaultSchemaAdapterFactory;
+
+impl SchemaAdapterFactory for DefaultSchemaAdapterFactory {
+    fn create(&self, table_schema: SchemaRef) -> Box<dyn SchemaAdapter> {
+        Box::new(DefaultSchemaAdapter { table_schema })
+    }
+}
+
+/// The [`SchemaAdapter`] of [`DefaultSchemaAdapterFactory`]
+#[derive(Clone, Debug)]
+pub(crate) struct DefaultSchemaAdapter {
+    /// Schema for the table
+    table_schema: SchemaRef,
+}
+
+impl DefaultSchemaAdapter {
+    pub(crate) fn new(table_schema: SchemaRef) -> Self {
+        Self { table_schema }
+    }
+}
+
+impl SchemaAdapter for DefaultSchemaAdapter {
+    fn map_column_index(&self, index: usize, file_schema: &Schema) -> Option<usize> {
+        let field = self.table_schema.field(index);
+        Some(file_schema.fields.find(field.name())?.0)
+    }
+
+    /// If the provided `file_schema` contains columns of a different type to the expected
+    /// `table_schema`, the method will attempt to cast the array data from the file schema
+    /// to the table schema where possible.
+    fn map_schema(
+        &self,
+        file_schema: &Schema,
+    ) -> Result<(Arc<dyn SchemaMapper>, Vec<usize>)> {
+        let mut projection = Vec::with_capacity(file_schema.fields().len());
+        let mut field_mappings = vec![None; self.table_schema.fields().len()];
+
+        for (file_idx, file_field) in file_schema.fields.iter().enumerate() {
+            if let Some((table_idx, table_field)) =
+                self.table_schema.fields().find(file_field.name())
+            {
+                match can_cast_types(file_field.data_type(), table_field.data_type()) {
+                    true => {
+                        field_mappings[table_idx] = Some(projection.len());
+                        projection.push(file_idx);
+                    }
+                    false => {
+                        return plan_err!(
+                            "Cannot cast file schema field {} of type {:?} to table schema field of type {:?}",
+                            file_field.name(),
+                            file_field.data_type(),
+                            table_field.data_type()
+                        )
+                    }
+                }
+            }
+        }
+
+        Ok((
+            Arc::new(SchemaMapping {
+                table_schema: self.table_schema.clone(),
+                field_mappings,
+            }),
+            projection,
+        ))
+    }
+}
+
+/// The SchemaMapping struct holds a mapping from the file schema to the table schema
+/// and any necessary type conversions that need to be applied.
+#[derive(Debug)]
+pub(crate) struct SchemaMapping {
+    /// The schema of the table. This is the expected schema after conversion and it should match the schema of the query result.
+    table_schema: SchemaRef,
+    /// Mapping from field index in `table_schema` to index in projected file_schema
+    field_mappings: Vec<Option<usize>>,
+}
+
+impl SchemaMapper for SchemaMapping {
+    /// Adapts a `RecordBatch` to match the `table_schema` using the stored mapping and conversions.
+    fn map_batch(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        let batch_rows = batch.num_rows();
+        let batch_cols = batch.columns().to_vec();
+
+        let cols = self
+            .table_schema
+            .fields()
+            .iter()
+            .zip(&self.field_mappings)
+            .map(|(field, file_idx)| match file_idx {
+                Some(batch_idx) => cast(&batch_cols[*batch_idx], field.data_type()),
+                None => Ok(new_null_array(field.data_type(), batch_rows)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Necessary to handle empty batches
+        let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+
+        let schema = self.table_schema.clone();
+        let record_batch = RecordBatch::try_new_with_options(schema, cols, &options)?;
+        Ok(record_batch)
+    }
+}
+
+/// The metadata key of the field ids of the fields of parquet files, as set
+/// by the arrow parquet reader
+pub const PARQUET_FIELD_ID_META_KEY: &str = "PARQUET:field_id";
+
+/// What to do with a file column whose type cannot be adapted to the type of
+/// the table column it maps to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IncompatibleTypePolicy {
+    /// Fail the scan of the file
+    #[default]
+    Error,
+    /// Read the column as if it was missing from the file, as nulls
+    Null,
+}
+
+/// A [`SchemaAdapterFactory`] for tables whose columns were renamed or
+/// widened over time.
+///
+/// A table column is read from the file column that has, in order of
+/// precedence:
+///
+/// 1. the same field id, if matching by field id is enabled and both fields
+///    have one (see [`PARQUET_FIELD_ID_META_KEY`])
+/// 2. a name that is renamed to the name of the table column
+/// 3. the same name, if it is not renamed
+///
+/// Unlike [`DefaultSchemaAdapterFactory`], file columns are only converted to
+/// the type of the table column if that is lossless: integers and floats to
+/// wider integers and floats, strings and binaries to their large variants,
+/// and lists and structs whose fields can be converted. Fields of a table
+/// struct that are missing from the struct of the file are filled with nulls.
+/// Other types are handled according to the [`IncompatibleTypePolicy`].
+///
+/// Filters are not pushed into the decoding of the files of scans with a
+/// custom adapter, as they are evaluated on the columns of the file.
+#[derive(Clone, Debug, Default)]
+pub struct SchemaEvolutionAdapterFactory {
+    /// Maps the names of file columns to the names of table columns
+    renames: HashMap<String, String>,
+    /// Whether columns are matched by field id
+    match_field_ids: bool,
+    incompatible_types: IncompatibleTypePolicy,
+}
+
+impl SchemaEvolutionAdapterFactory {
+    /// Create a factory matching columns by name, with no renames
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read file columns named `file_name` as the table column `table_name`
+    pub fn with_rename(
+        mut self,
+        file_name: impl Into<String>,
+        table_name: impl Into<String>,
+    ) -> Self {
+        self.renames.insert(file_name.into(), table_name.into());
+        self
+    }
+
+    /// Read file columns as the table columns with the same field id, if
+    /// both have one
+    pub fn with_field_id_matching(mut self, match_field_ids: bool) -> Self {
+        self.match_field_ids = match_field_ids;
+        self
+    }
+
+    /// Set what to do with file columns of types that cannot be adapted to
+    /// the table
+    pub fn with_incompatible_types(mut self, policy: IncompatibleTypePolicy) -> Self {
+        self.incompatible_types = policy;
+        self
+    }
+}
+
+impl SchemaAdapterFactory for SchemaEvolutionAdapterFactory {
+    fn create(&self, table_schema: SchemaRef) -> Box<dyn SchemaAdapter> {
+        Box::new(SchemaEvolutionAdapter {
+            table_schema,
+            factory: self.clone(),
+        })
+    }
+}
+
+/// The [`SchemaAdapter`] of [`SchemaEvolutionAdapterFactory`]
+#[derive(Debug)]
+struct SchemaEvolutionAdapter {
+    table_schema: SchemaRef,
+    factory: SchemaEvolutionAdapterFactory,
+}
+
+fn field_id(field: &Field) -> Option<&String> {
+    field.metadata().get(PARQUET_FIELD_ID_META_KEY)
+}
+
+impl SchemaEvolutionAdapter {
+    /// The name of the table column that `file_field` is read as, ignoring
+    /// field ids
+    fn table_name<'a>(&'a self, file_field: &'a Field) -> &'a str {
+        self.factory
+            .renames
+            .get(file_field.name())
+            .map(|name| name.as_str())
+            .unwrap_or(file_field.name())
+    }
+}
+
+impl SchemaAdapter for SchemaEvolutionAdapter {
+    fn map_column_index(&self, index: usize, file_schema: &Schema) -> Option<usize> {
+        let field = self.table_schema.field(index);
+        let id = field_id(field).filter(|_| self.factory.match_field_ids);
+        if let Some(id) = id {
+            let by_id = file_schema
+                .fields()
+                .iter()
+                .position(|f| field_id(f) == Some(id));
+            if by_id.is_some() {
+                return by_id;
+            }
+        }
+        // a file column with another field id is another column, even if it
+        // has the same name
+        file_schema.fields().iter().position(|f| {
+            self.table_name(f) == field.name() && (id.is_none() || field_id(f).is_none())
+        })
+    }
+
+    fn map_schema(
+        &self,
+        file_schema: &Schema,
+    ) -> Result<(Arc<dyn SchemaMapper>, Vec<usize>)> {
+        let mut mapped = vec![];
+        for (table_idx, table_field) in self.table_schema.fields().iter().enumerate() {
+            let Some(file_idx) = self.map_column_index(table_idx, file_schema) else {
+                continue;
+            };
+            let file_field = file_schema.field(file_idx);
+            if can_adapt(file_field.data_type(), table_field.data_type()) {
+                mapped.push((table_idx, file_idx));
+            } else if self.factory.incompatible_types == IncompatibleTypePolicy::Error {
+                return plan_err!(
+                    "Cannot adapt file schema field {} of type {:?} to table schema field {} of type {:?}",
+                    file_field.name(),
+                    file_field.data_type(),
+                    table_field.name(),
+                    table_field.data_type()
+                );
+            }
+        }
+
+        // the columns of the file are read in the order of the file schema
+        let mut projection: Vec<_> =
+            mapped.iter().map(|(_, file_idx)| *file_idx).collect();
+        projection.sort_unstable();
+        projection.dedup();
+        let mut field_mappings = vec![None; self.table_schema.fields().len()];
+        for (table_idx, file_idx) in mapped {
+            field_mappings[table_idx] = projection.binary_search(&file_idx).ok();
+        }
+
+        Ok((
+            Arc::new(SchemaEvolutionMapper {
+                table_schema: self.table_schema.clone(),
+                field_mappings,
+            }),
+            projection,
+        ))
+    }
+}
+
+/// Whether values of `from` can be adapted to `to` without loss
+fn can_adapt(from: &DataType, to: &DataType) -> bool {
+    use DataType::*;
+    match (from, to) {
+        (from, to) if from == to => true,
+        (Int8, Int16 | Int32 | Int64)
+        | (Int16, Int32 | Int64)
+        | (Int32, Int64)
+        | (UInt8, UInt16 | UInt32 | UInt64 | Int16 | Int32 | Int64)
+        | (UInt16, UInt32 | UInt64 | Int32 | Int64)
+        | (UInt32, UInt64 | Int64)
+        | (Float16, Float32 | Float64)
+        | (Float32, Float64)
+        | (Utf8, LargeUtf8)
+        | (Binary, LargeBinary) => true,
+        (Dictionary(_, value), to) => can_adapt(value, to),
+        (List(from) | LargeList(from), List(to) | LargeList(to)) => {
+            can_adapt(from.data_type(), to.data_type())
+        }
+        (Struct(from), Struct(to)) => to.iter().all(|to| match from.find(to.name()) {
+            Some((_, from)) => can_adapt(from.data_type(), to.data_type()),
+            // missing fields are filled with nulls
+            None => to.is_nullable(),
+        }),
+        _ => false,
+    }
+}
+
+/// The [`SchemaMapper`] of [`SchemaEvolutionAdapter`]
+#[derive(Debug)]
+struct SchemaEvolutionMapper {
+    table_schema: SchemaRef,
+    /// Mapping from field index in `table_schema` to index in projected file_schema
+    field_mappings: Vec<Option<usize>>,
+}
+
+impl SchemaMapper for SchemaEvolutionMapper {
+    fn map_batch(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        let cols = self
+            .table_schema
+            .fields()
+            .iter()
+            .zip(&self.field_mappings)
+            .map(|(field, file_idx)| match file_idx {
+                Some(batch_idx) => adapt(batch.column(*batch_idx), field.data_type()),
+                None => Ok(new_null_array(field.data_type(), batch.num_rows())),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Necessary to handle empty batches
+        let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+        Ok(RecordBatch::try_new_with_options(
+            self.table_schema.clone(),
+            cols,
+            &options,
+        )?)
+    }
+}
+
+/// Converts `array` to `data_type`, which it [`can_adapt`] to
+fn adapt(array: &ArrayRef, data_type: &DataType) -> Result<ArrayRef> {
+    match (array.data_type(), data_type) {
+        (from, to) if from == to => Ok(array.clone()),
+        (DataType::Struct(_), DataType::Struct(fields)) => {
+            adapt_struct(array.as_struct(), fields)
+        }
+        (DataType::List(_), DataType::List(item)) => {
+            adapt_list::<i32, i32>(array.as_list(), item)
+        }
+        (DataType::List(_), DataType::LargeList(item)) => {
+            adapt_list::<i32, i64>(array.as_list(), item)
+        }
+        (DataType::LargeList(_), DataType::LargeList(item)) => {
+            adapt_list::<i64, i64>(array.as_list(), item)
+        }
+        _ => Ok(cast(array, data_type)?),
+    }
+}
+
+fn adapt_struct(array: &StructArray, fields: &Fields) -> Result<ArrayRef> {
+    let DataType::Struct(file_fields) = array.data_type() else {
+        unreachable!("struct arrays have struct types")
+    };
+    let columns = fields
+        .iter()
+        .map(|field| match file_fields.find(field.name()) {
+            Some((idx, _)) => {
+                let column = adapt(array.column(idx), field.data_type())?;
+                match column.nulls() {
+                    // casts may return a null buffer without nulls, which
+                    // struct arrays reject for non-nullable fields
+                    Some(nulls) if !field.is_nullable() && nulls.null_count() == 0 => {
+                        let data = column.to_data().into_builder().nulls(None).build()?;
+                        Ok(make_array(data))
+                    }
+                    _ => Ok(column),
+                }
+            }
+            None => Ok(new_null_array(field.data_type(), array.len())),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Arc::new(StructArray::try_new(
+        fields.clone(),
+        columns,
+        array.nulls().cloned(),
+    )?))
+}
+
+fn adapt_list<F: OffsetSizeTrait, T: OffsetSizeTrait>(
+    array: &GenericListArray<F>,
+    item: &FieldRef,
+) -> Result<ArrayRef> {
+    let values = adapt(array.values(), item.data_type())?;
+    let list: ArrayRef = Arc::new(GenericListArray::<F>::try_new(
+        item.clone(),
+        array.offsets().clone(),
+        values,
+        array.nulls().cloned(),
+    )?);
+    if F::IS_LARGE == T::IS_LARGE {
+        return Ok(list);
+    }
+    // widening the offsets is lossless
+    Ok(cast(&list, &DataType::LargeList(item.clone()))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Int32Array, Int64Array};
+    use arrow_array::types::{Float32Type, Float64Type, Int64Type, UInt32Type};
+    use arrow_array::{
+        BinaryArray, BooleanArray, Float32Array, StringArray, UInt64Array,
+    };
+
+    use super::*;
+
+    fn default_adapter(table_schema: SchemaRef) -> Box<dyn SchemaAdapter> {
+        DefaultSchemaAdapterFactory.create(table_schema)
+    }
+
+    #[test]
+    fn schema_mapping_map_batch() {
+        let table_schema = Arc::new(Schema::new(vec![
+            Field::new("c1", DataType::Utf8, true),
+            Field::new("c2", DataType::UInt32, true),
+            Field::new("c3", DataType::Float64, true),
+        ]));
+
+        let adapter = default_adapter(table_schema.clone());
+
+        let file_schema = Schema::new(vec![
+            Field::new("c1", DataType::Utf8, true),
+            Field::new("c2", DataType::UInt64, true),
+            Field::new("c3", DataType::Float32, true),
+        ]);
+
+        let (mapping, _) = adapter.map_schema(&file_schema).expect("map schema failed");
+
+        let c1 = StringArray::from(vec!["hello", "world"]);
+        let c2 = UInt64Array::from(vec![9_u64, 5_u64]);
+        let c3 = Float32Array::from(vec![2.0_f32, 7.0_f32]);
+        let batch = RecordBatch::try_new(
+            Arc::new(file_schema),
+            vec![Arc::new(c1), Arc::new(c2), Arc::new(c3)],
+        )
+        .unwrap();
+
+        let mapped_batch = mapping.map_batch(batch).unwrap();
+
+        assert_eq!(mapped_batch.schema(), table_schema);
+        assert_eq!(mapped_batch.num_columns(), 3);
+        assert_eq!(mapped_batch.num_rows(), 2);
+
+        let c1 = mapped_batch.column(0).as_string::<i32>();
+        let c2 = mapped_batch.column(1).as_primitive::<UInt32Type>();
+        let c3 = mapped_batch.column(2).as_primitive::<Float64Type>();
+
+        assert_eq!(c1.value(0), "hello");
+        assert_eq!(c1.value(1), "world");
+        assert_eq!(c2.value(0), 9_u32);
+        assert_eq!(c2.value(1), 5_u32);
+        assert_eq!(c3.value(0), 2.0_f64);
+        assert_eq!(c3.value(1), 7.0_f64);
+    }
+
+    #[test]
+    fn schema_adapter_map_schema_with_projection() {
+        let table_schema = Arc::new(Schema::new(vec![
+            Field::new("c0", DataType::Utf8, true),
+            Field::new("c1", DataType::Utf8, true),
+            Field::new("c2", DataType::Float64, true),
+            Field::new("c3", DataType::Int32, true),
+            Field::new("c4", DataType::Float32, true),
+        ]));
+
+        let file_schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, true),
+            Field::new("c1", DataType::Boolean, true),
+            Field::new("c2", DataType::Float32, true),
+            Field::new("c3", DataType::Binary, true),
+            Field::new("c4", DataType::Int64, true),
+        ]);
+
+        let indices = vec![1, 2, 4];
+        let schema = SchemaRef::from(table_schema.project(&indices).unwrap());
+        let adapter = default_adapter(schema);
+        let (mapping, projection) = adapter.map_schema(&file_schema).unwrap();
+
+        let id = Int32Array::from(vec![Some(1), Some(2), Some(3)]);
+        let c1 = BooleanArray::from(vec![Some(true), Some(false), Some(true)]);
+        let c2 = Float32Array::from(vec![Some(2.0_f32), Some(7.0_f32), Some(3.0_f32)]);
+        let c3 = BinaryArray::from_opt_vec(vec![
+            Some(b"hallo"),
+            Some(b"danke"),
+            Some(b"super"),
+        ]);
+        let c4 = Int64Array::from(vec![1, 2, 3]);
+        let batch = RecordBatch::try_new(
+            Arc::new(file_schema),
+            vec![
+                Arc::new(id),
+                Arc::new(c1),
+                Arc::new(c2),
+                Arc::new(c3),
+                Arc::new(c4),
+            ],
+        )
+        .unwrap();
+        let rows_num = batch.num_rows();
+        let projected = batch.project(&projection).unwrap();
+        let mapped_batch = mapping.map_batch(projected).unwrap();
+
+        assert_eq!(
+            mapped_batch.schema(),
+            Arc::new(table_schema.project(&indices).unwrap())
+        );
+        assert_eq!(mapped_batch.num_columns(), indices.len());
+        assert_eq!(mapped_batch.num_rows(), rows_num);
+
+        let c1 = mapped_batch.column(0).as_string::<i32>();
+        let c2 = mapped_batch.column(1).as_primitive::<Float64Type>();
+        let c4 = mapped_batch.column(2).as_primitive::<Float32Type>();
+
+        assert_eq!(c1.value(0), "true");
+        assert_eq!(c1.value(1), "false");
+        assert_eq!(c1.value(2), "true");
+
+        assert_eq!(c2.value(0), 2.0_f64);
+        assert_eq!(c2.value(1), 7.0_f64);
+        assert_eq!(c2.value(2), 3.0_f64);
+
+        assert_eq!(c4.value(0), 1.0_f32);
+        assert_eq!(c4.value(1), 2.0_f32);
+        assert_eq!(c4.value(2), 3.0_f32);
+    }
+
+    fn with_field_id(field: Field, id: i32) -> Field {
+        field.with_metadata(HashMap::from([(
+            PARQUET_FIELD_ID_META_KEY.to_string(),
+            id.to_string(),
+        )]))
+    }
+
+    #[test]
+    fn schema_evolution_renames_and_widening() -> Result<()> {
+        let table_schema = Arc::new(Schema::new(vec![
+            Field::new("customer_id", DataType::Int64, true),
+            Field::new("amount", DataType::Float64, true),
+            Field::new("note", DataType::Utf8, true),
+        ]));
+        let file_schema = Arc::new(Schema::new(vec![
+            Field::new("amount", DataType::Float32, true),
+            Field::new("cust_id", DataType::Int32, true),
+        ]));
+
+        let factory =
+            SchemaEvolutionAdapterFactory::new().with_rename("cust_id", "customer_id");
+        let adapter = factory.create(table_schema.clone());
+        let (mapping, projection) = adapter.map_schema(&file_schema)?;
+        assert_eq!(projection, vec![0, 1]);
+
+        let batch = RecordBatch::try_new(
+            file_schema,
+            vec![
+                Arc::new(Float32Array::from(vec![1.5, 2.5])),
+                Arc::new(Int32Array::from(vec![7, 8])),
+            ],
+        )?;
+        let mapped = mapping.map_batch(batch)?;
+        assert_eq!(mapped.schema(), table_schema);
+        assert_eq!(
+            mapped.column(0).as_primitive::<Int64Type>().values(),
+            &[7, 8]
+        );
+        assert_eq!(
+            mapped.column(1).as_primitive::<Float64Type>().values(),
+            &[1.5, 2.5]
+        );
+        assert_eq!(mapped.column(2).null_count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn schema_evolution_field_ids() {
+        let table_schema = Arc::new(Schema::new(vec![with_field_id(
+            Field::new("b", DataType::Int64, true),
+            1,
+        )]));
+        // the column with field id 1 was renamed from `a` to `b`, and a new
+        // column named `b` was added
+        let file_schema = Schema::new(vec![
+            with_field_id(Field::new("a", DataType::Int64, true), 1),
+            with_field_id(Field::new("b", DataType::Int64, true), 2),
+        ]);
+
+        let by_name = SchemaEvolutionAdapterFactory::new().create(table_schema.clone());
+        assert_eq!(by_name.map_column_index(0, &file_schema), Some(1));
+
+        let by_id = SchemaEvolutionAdapterFactory::new()
+            .with_field_id_matching(true)
+            .create(table_schema);
+        assert_eq!(by_id.map_column_index(0, &file_schema), Some(0));
+
+        // a column with another field id is not read by name
+        let file_schema = Schema::new(vec![with_field_id(
+            Field::new("b", DataType::Int64, true),
+            2,
+        )]);
+        assert_eq!(by_id.map_column_index(0, &file_schema), None);
+    }
+
+    #[test]
+    fn schema_evolution_nested_struct() -> Result<()> {
+        let file_fields = Fields::from(vec![Field::new("x", DataType::Int32, false)]);
+        let table_fields = Fields::from(vec![
+            Field::new("x", DataType::Int64, false),
+            Field::new("y", DataType::Utf8, true),
+        ]);
+        let table_schema = Arc::new(Schema::new(vec![Field::new(
+            "point",
+            DataType::Struct(table_fields),
+            true,
+        )]));
+        let file_schema = Arc::new(Schema::new(vec![Field::new(
+            "point",
+            DataType::Struct(file_fields.clone()),
+            true,
+        )]));
+
+        let adapter = SchemaEvolutionAdapterFactory::new().create(table_schema.clone());
+        let (mapping, _) = adapter.map_schema(&file_schema)?;
+        let point = StructArray::new(
+            file_fields,
+            vec![Arc::new(Int32Array::from(vec![1, 2]))],
+            None,
+        );
+        let batch = RecordBatch::try_new(file_schema, vec![Arc::new(point)])?;
+        let mapped = mapping.map_batch(batch)?;
+
+        assert_eq!(mapped.schema(), table_schema);
+        let point = mapped.column(0).as_struct();
+        assert_eq!(
+            point.column(0).as_primitive::<Int64Type>().values(),
+            &[1, 2]
+        );
+        assert_eq!(point.column(1).null_count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn schema_evolution_incompatible_types() -> Result<()> {
+        let table_schema =
+            Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let file_schema = Schema::new(vec![Field::new("a", DataType::Int64, true)]);
+
+        let factory = SchemaEvolutionAdapterFactory::new();
+        let err = factory
+            .create(table_schema.clone())
+            .map_schema(&file_schema)
+            .unwrap_err();
+        assert!(err.to_string().contains("Cannot adapt file schema field a"));
+
+        let factory = factory.with_incompatible_types(IncompatibleTypePolicy::Null);
+        let (_, projection) = factory.create(table_schema).map_schema(&file_schema)?;
+        assert!(projection.is_empty());
+        Ok(())
+    }
+}
@@ -40,6 +40,8 @@ use datafusion_common::{
     exec_err, not_impl_err, plan_datafusion_err, plan_err,
     tree_node::{TreeNode, TreeNodeVisitor, VisitRecursion},
 };
+use datafusion_execution::cancellation::CancellationToken;
+use datafusion_execution::memory_pool::{HierarchicalMemoryPool, MemoryPool};
 use datafusion_execution::registry::SerializerRegistry;
 use datafusion_expr::{
     logical_plan::{DdlStatement, Statement},
@@ -55,7 +57,7 @@ use std::{
     collections::{HashMap, HashSet},
     fmt::Debug,
 };
-use std::{ops::ControlFlow, sync::Weak};
+use std::{ops::ControlFlow, sync::Weak, time::Duration};
 
 use arrow::datatypes::{DataType, SchemaRef};
 use arrow::record_batch::RecordBatch;
@@ -84,7 +86,9 @@ use datafusion_sql::{
 use sqlparser::dialect::dialect_from_str;
 
 use crate::config::ConfigOptions;
-use crate::execution::{runtime_env::RuntimeEnv, FunctionRegistry};
+use crate::execution::{
+    running_queries::RunningQueries, runtime_env::RuntimeEnv, FunctionRegistry,
+};
 use crate::physical_plan::udaf::AggregateUDF;
 use crate::physical_plan::udf::ScalarUDF;
 use crate::physical_plan::ExecutionPlan;
@@ -102,6 +106,7 @@ use url::Url;
 
 use crate::catalog::information_schema::{InformationSchemaProvider, INFORMATION_SCHEMA};
 use crate::catalog::listing_schema::ListingSchemaProvider;
+use crate::catalog::system_schema::{SystemSchemaProvider, SYSTEM_SCHEMA};
 use crate::datasource::object_store::ObjectStoreUrl;
 use datafusion_optimizer::{
     analyzer::{Analyzer, AnalyzerRule},
@@ -361,6 +366,12 @@ impl SessionContext {
         )
     }
 
+    /// Return the registry of the queries executing in this `SessionContext`,
+    /// which can be polled for their progress
+    pub fn running_queries(&self) -> Arc<RunningQueries> {
+        self.state.read().running_queries.clone()
+    }
+
     /// Return the [RuntimeEnv] used to run queries with this `SessionContext`
     pub fn runtime_env(&self) -> Arc<RuntimeEnv> {
         self.state.read().runtime_env.clone()
@@ -460,7 +471,7 @@ impl SessionContext {
         let plan = self.state().create_logical_plan(sql).await?;
         options.verify_plan(&plan)?;
 
-        self.execute_logical_plan(plan).await
+        Ok(self.execute_logical_plan(plan).await?.with_sql(sql))
     }
 
     /// Execute the [`LogicalPlan`], return a [`DataFrame`]. This API
@@ -1154,7 +1165,8 @@ impl SessionContext {
         self.state().create_physical_plan(logical_plan).await
     }
 
-    /// Get a new TaskContext to run in this session
+    /// Get a new TaskContext to run a query in this session, with its own
+    /// query level [`MemoryPool`] if the query memory limit is set
     pub fn task_ctx(&self) -> Arc<TaskContext> {
         Arc::new(TaskContext::from(self))
     }
@@ -1269,6 +1281,12 @@ pub struct SessionState {
     table_factories: HashMap<String, Arc<dyn TableProviderFactory>>,
     /// Runtime environment
     runtime_env: Arc<RuntimeEnv>,
+    /// Memory pool shared by the queries of this session. If the session or
+    /// its queries have a memory limit, a child of the memory pool of the
+    /// runtime environment, otherwise the memory pool of the runtime
+    memory_pool: Arc<dyn MemoryPool>,
+    /// Registry of the queries executing in this session
+    running_queries: Arc<RunningQueries>,
 }
 
 impl Debug for SessionState {
@@ -1314,6 +1332,8 @@ impl SessionState {
         table_factories.insert("NDJSON".into(), Arc::new(DefaultTableFactory::new()));
         table_factories.insert("AVRO".into(), Arc::new(DefaultTableFactory::new()));
         table_factories.insert("ARROW".into(), Arc::new(DefaultTableFactory::new()));
+        #[cfg(feature = "orc")]
+        table_factories.insert("ORC".into(), Arc::new(DefaultTableFactory::new()));
 
         if config.create_default_catalog_and_schema() {
             let default_catalog = MemoryCatalogProvider::new();
@@ -1338,6 +1358,23 @@ impl SessionState {
             );
         }
 
+        let execution_options = &config.options().execution;
+        let memory_pool: Arc<dyn MemoryPool> =
+            if execution_options.session_memory_limit.is_some()
+                || execution_options.query_memory_limit.is_some()
+            {
+                Arc::new(
+                    HierarchicalMemoryPool::new_with_limit(
+                        format!("session {session_id}"),
+                        execution_options.session_memory_limit,
+                        execution_options.memory_pool_policy,
+                    )
+                    .with_parent(runtime.memory_pool.clone()),
+                )
+            } else {
+                runtime.memory_pool.clone()
+            };
+
         SessionState {
             session_id,
             analyzer: Analyzer::new(),
@@ -1354,6 +1391,8 @@ impl SessionState {
             execution_props: ExecutionProps::new(),
             runtime_env: runtime,
             table_factories,
+            memory_pool,
+            running_queries: Arc::new(RunningQueries::new()),
         }
     }
     /// Returns new [`SessionState`] using the provided
@@ -1436,7 +1475,8 @@ impl SessionState {
             )));
         }
 
-        self.catalog_list
+        let schema = self
+            .catalog_list
             .catalog(&resolved_ref.catalog)
             .ok_or_else(|| {
                 plan_datafusion_err!(
@@ -1444,10 +1484,25 @@ impl SessionState {
                     resolved_ref.catalog
                 )
             })?
-            .schema(&resolved_ref.schema)
-            .ok_or_else(|| {
-                plan_datafusion_err!("failed to resolve schema: {}", resolved_ref.schema)
-            })
+            .schema(&resolved_ref.schema);
+
+        match schema {
+            Some(schema) => Ok(schema),
+            // the system schema does not shadow a schema of the same name
+            None if self.config.system_schema()
+                && resolved_ref.schema == SYSTEM_SCHEMA =>
+            {
+                Ok(Arc::new(SystemSchemaProvider::new(
+                    self.running_queries.clone(),
+                    self.runtime_env.clone(),
+                    self.memory_pool.clone(),
+                )))
+            }
+            None => Err(plan_datafusion_err!(
+                "failed to resolve schema: {}",
+                resolved_ref.schema
+            )),
+        }
     }
 
     /// Replace the random session id.
@@ -1547,6 +1602,8 @@ impl SessionState {
         sql: &str,
         dialect: &str,
     ) -> Result<datafusion_sql::parser::Statement> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("sql_parse", dialect).entered();
         let dialect = dialect_from_str(dialect).ok_or_else(|| {
             plan_datafusion_err!(
                 "Unsupported SQL dialect: {dialect}. Available dialects: \
@@ -1798,9 +1855,14 @@ impl SessionState {
         logical_plan: &LogicalPlan,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         let logical_plan = self.optimize(logical_plan)?;
-        self.query_planner
+        let plan = self
+            .query_planner
             .create_physical_plan(&logical_plan, self)
-            .await
+            .await?;
+        // emit spans for the execution of each operator, if a subscriber records them
+        #[cfg(feature = "tracing")]
+        let plan = crate::physical_plan::instrumented::instrument_plan(plan)?;
+        Ok(plan)
     }
 
     /// Return the session ID
@@ -1813,6 +1875,35 @@ impl SessionState {
         &self.runtime_env
     }
 
+    /// Return the [`MemoryPool`] shared by the queries of this session
+    pub fn memory_pool(&self) -> &Arc<dyn MemoryPool> {
+        &self.memory_pool
+    }
+
+    /// Create the memory pool of a query of this session, a child of
+    /// [`Self::memory_pool`] enforcing the query memory limit, if any
+    pub(crate) fn query_memory_pool(&self) -> HierarchicalMemoryPool {
+        let execution_options = &self.config.options().execution;
+        HierarchicalMemoryPool::new_with_limit(
+            format!("query of session {}", self.session_id),
+            execution_options.query_memory_limit,
+            execution_options.memory_pool_policy,
+        )
+        .with_parent(self.memory_pool.clone())
+    }
+
+    /// Replace the [`MemoryPool`] shared by the queries of this session,
+    /// for example with a [`HierarchicalMemoryPool`] with a custom policy
+    pub fn with_memory_pool(mut self, memory_pool: Arc<dyn MemoryPool>) -> Self {
+        self.memory_pool = memory_pool;
+        self
+    }
+
+    /// Return the registry of the queries executing in this session
+    pub fn running_queries(&self) -> &Arc<RunningQueries> {
+        &self.running_queries
+    }
+
     /// Return the execution properties
     pub fn execution_props(&self) -> &ExecutionProps {
         &self.execution_props
@@ -1833,7 +1924,8 @@ impl SessionState {
         self.config.options()
     }
 
-    /// Get a new TaskContext to run in this session
+    /// Get a new TaskContext to run a query in this session, with its own
+    /// query level [`MemoryPool`] if the query memory limit is set
     pub fn task_ctx(&self) -> Arc<TaskContext> {
         Arc::new(TaskContext::from(self))
     }
@@ -1989,6 +2081,19 @@ impl From<&SessionContext> for TaskContext {
 impl From<&SessionState> for TaskContext {
     fn from(state: &SessionState) -> Self {
         let task_id = None;
+        let execution_options = &state.config.options().execution;
+        let memory_pool: Arc<dyn MemoryPool> = match execution_options.query_memory_limit
+        {
+            Some(_) => Arc::new(state.query_memory_pool()),
+            None => state.memory_pool.clone(),
+        };
+
+        let mut cancellation_token = CancellationToken::new();
+        if let Some(timeout) = execution_options.statement_timeout {
+            cancellation_token =
+                cancellation_token.with_timeout(Duration::from_millis(timeout));
+        }
+
         TaskContext::new(
             task_id,
             state.session_id.clone(),
@@ -1998,6 +2103,8 @@ impl From<&SessionState> for TaskContext {
             state.window_functions.clone(),
             state.runtime_env.clone(),
         )
+        .with_memory_pool(memory_pool)
+        .with_cancellation_token(cancellation_token)
     }
 }
 
@@ -2167,6 +2274,98 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn session_and_query_memory_limits() -> Result<()> {
+        let mut config = SessionConfig::new();
+        config.options_mut().execution.session_memory_limit = Some(100);
+        config.options_mut().execution.query_memory_limit = Some(60);
+        let ctx = SessionContext::new_with_config(config);
+
+        // every task context has its own query level pool
+        let task_ctx1 = ctx.task_ctx();
+        let task_ctx2 = ctx.task_ctx();
+        let mut r1 = MemoryConsumer::new("r1").register(task_ctx1.memory_pool());
+        let mut r2 = MemoryConsumer::new("r2").register(task_ctx2.memory_pool());
+
+        // limited by the query
+        r1.try_grow(70).unwrap_err();
+        r1.try_grow(60)?;
+
+        // limited by the session
+        let err = r2.try_grow(60).unwrap_err().strip_backtrace();
+        assert!(err.contains(
+            "Top memory consumers: [r1 with 60.0 B (peak 60.0 B), r2 with 0.0 B (peak 0.0 B)]"
+        ));
+        r2.try_grow(40)?;
+
+        assert_eq!(ctx.state().memory_pool().reserved(), 100);
+        assert_eq!(ctx.runtime_env().memory_pool.reserved(), 100);
+
+        let consumers: Vec<_> = ctx
+            .state()
+            .memory_pool()
+            .consumers()
+            .into_iter()
+            .map(|c| (c.name, c.reserved))
+            .collect();
+        assert_eq!(
+            consumers,
+            vec![("r1".to_string(), 60), ("r2".to_string(), 40)]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn statement_timeout() -> Result<()> {
+        let mut config = SessionConfig::new();
+        config.options_mut().execution.statement_timeout = Some(0);
+        let ctx = SessionContext::new_with_config(config);
+
+        let err = ctx
+            .sql("SELECT * FROM (VALUES (1), (2)) AS t(a)")
+            .await?
+            .collect()
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Query cancelled: statement timeout of 0ns exceeded"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cancel_query() -> Result<()> {
+        let ctx = SessionContext::new();
+        let plan = ctx
+            .sql("SELECT a, count(*) FROM (VALUES (1), (2)) AS t(a) GROUP BY a")
+            .await?
+            .create_physical_plan()
+            .await?;
+
+        let task_ctx = ctx.task_ctx();
+        task_ctx.cancellation_token().cancel();
+
+        let err = crate::physical_plan::collect(plan, task_ctx)
+            .await
+            .unwrap_err();
+        assert!(matches!(err.find_root(), DataFusionError::Cancelled(_)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn memory_pool_without_limits() {
+        let ctx = SessionContext::new();
+
+        // without limits, queries reserve from the pool of the runtime
+        let runtime_pool = &ctx.runtime_env().memory_pool;
+        assert!(Arc::ptr_eq(ctx.state().memory_pool(), runtime_pool));
+        assert!(Arc::ptr_eq(ctx.task_ctx().memory_pool(), runtime_pool));
+    }
+
     #[tokio::test]
     async fn create_variable_expr() -> Result<()> {
         let tmp_dir = TempDir::new()?;
@@ -0,0 +1,490 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`RunningQueries`] registry of the running and recent queries of a session
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{self, Display};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Utc};
+use datafusion_common::{DataFusionError, Result};
+use datafusion_execution::cancellation::CancellationToken;
+use datafusion_execution::memory_pool::HierarchicalMemoryPool;
+use datafusion_execution::{RecordBatchStream, SendableRecordBatchStream, TaskContext};
+use datafusion_physical_plan::progress::{estimate_completion, leaf_progress};
+use datafusion_physical_plan::{ExecutionPlan, ExecutionProgress};
+use futures::Stream;
+use parking_lot::Mutex;
+
+/// A query executing in a session, see [`RunningQueries`]
+#[derive(Debug, Clone)]
+pub struct RunningQuery {
+    id: u64,
+    session_id: String,
+    sql: Option<String>,
+    start_time: DateTime<Utc>,
+    plan: Arc<dyn ExecutionPlan>,
+    cancellation_token: CancellationToken,
+}
+
+impl RunningQuery {
+    /// Returns the id of this query, unique within its registry
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the id of the session executing this query
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Returns the SQL text of this query, if it was created from SQL
+    pub fn sql(&self) -> Option<&str> {
+        self.sql.as_deref()
+    }
+
+    /// Returns the time at which this query started executing
+    pub fn start_time(&self) -> DateTime<Utc> {
+        self.start_time
+    }
+
+    /// Returns the physical plan being executed
+    pub fn plan(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.plan
+    }
+
+    /// Returns the input consumed so far by the leaves of the plan, such as
+    /// its scans, summed together
+    pub fn progress(&self) -> ExecutionProgress {
+        let mut leaves = leaf_progress(self.plan.as_ref()).into_iter();
+        let first = leaves.next().unwrap_or_default();
+        leaves.fold(first, |acc, p| acc.add(&p))
+    }
+
+    /// Returns the estimated completed fraction of this query between 0 and
+    /// 1, or `None` if it cannot be estimated, see [`estimate_completion`]
+    pub fn estimated_completion(&self) -> Option<f64> {
+        estimate_completion(self.plan.as_ref())
+    }
+
+    /// Cancel this query, see [`CancellationToken`]
+    pub fn cancel(&self) {
+        self.cancellation_token.cancel()
+    }
+}
+
+/// How a query in the history of [`RunningQueries`] finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryStatus {
+    /// The query produced all of its output
+    Completed,
+    /// The query returned an error
+    Failed,
+    /// The query was cancelled, timed out, or its output was dropped before
+    /// it was complete
+    Cancelled,
+}
+
+impl Display for QueryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Completed => write!(f, "completed"),
+            Self::Failed => write!(f, "failed"),
+            Self::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// A finished query in the history of [`RunningQueries`]
+#[derive(Debug, Clone)]
+pub struct QueryRecord {
+    /// The id of the query, see [`RunningQuery::id`]
+    pub id: u64,
+    /// The id of the session that executed the query
+    pub session_id: String,
+    /// The SQL text of the query, if it was created from SQL
+    pub sql: Option<String>,
+    /// The time at which the query started executing
+    pub start_time: DateTime<Utc>,
+    /// The time at which the query finished
+    pub end_time: DateTime<Utc>,
+    /// How the query finished
+    pub status: QueryStatus,
+    /// The error of a failed or cancelled query
+    pub error: Option<String>,
+    /// The number of rows the query produced
+    pub output_rows: usize,
+    /// The peak memory reserved by the query, see
+    /// [`HierarchicalMemoryPool::peak_reserved`]
+    pub memory_used: usize,
+    /// The sum of the spilled bytes metrics of the operators of the query
+    pub spilled_bytes: usize,
+}
+
+/// Registry of the running and recent queries of a session.
+///
+/// The running queries can be polled for their progress while they run,
+/// and are moved to a bounded history when they finish, see
+/// [`ExecutionOptions::query_history_size`].
+///
+/// Queries run through a [`DataFrame`] are registered automatically until
+/// their results have been collected, or their streams are dropped. The
+/// registry is also available as the `system.running_queries` and
+/// `system.queries` tables.
+///
+/// [`DataFrame`]: crate::dataframe::DataFrame
+/// [`ExecutionOptions::query_history_size`]: datafusion_common::config::ExecutionOptions::query_history_size
+#[derive(Debug, Default)]
+pub struct RunningQueries {
+    next_id: AtomicU64,
+    queries: Mutex<BTreeMap<u64, RunningQuery>>,
+    history: Mutex<VecDeque<QueryRecord>>,
+}
+
+impl RunningQueries {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `plan`, created from `sql` if any, as running in
+    /// `task_ctx` until the returned guard is dropped. `memory_pool` is the
+    /// pool of the query only, whose peak reservation is recorded as its
+    /// memory usage
+    pub fn register(
+        self: &Arc<Self>,
+        plan: Arc<dyn ExecutionPlan>,
+        sql: Option<String>,
+        task_ctx: &TaskContext,
+        memory_pool: Arc<HierarchicalMemoryPool>,
+    ) -> RunningQueryGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let query = RunningQuery {
+            id,
+            session_id: task_ctx.session_id(),
+            sql,
+            start_time: Utc::now(),
+            plan,
+            cancellation_token: task_ctx.cancellation_token().clone(),
+        };
+        self.queries.lock().insert(id, query);
+        RunningQueryGuard {
+            id,
+            registry: Arc::clone(self),
+            memory_pool,
+            history_size: task_ctx
+                .session_config()
+                .options()
+                .execution
+                .query_history_size,
+            output_rows: AtomicUsize::new(0),
+            open_streams: AtomicUsize::new(0),
+            completed: AtomicBool::new(false),
+            error: Mutex::new(None),
+        }
+    }
+
+    /// Returns the queries running now, ordered by id
+    pub fn queries(&self) -> Vec<RunningQuery> {
+        self.queries.lock().values().cloned().collect()
+    }
+
+    /// Returns the query with `id`, if it is still running
+    pub fn get(&self, id: u64) -> Option<RunningQuery> {
+        self.queries.lock().get(&id).cloned()
+    }
+
+    /// Returns the most recently finished queries, oldest first
+    pub fn history(&self) -> Vec<QueryRecord> {
+        self.history.lock().iter().cloned().collect()
+    }
+
+    /// Adds `record` to the history, keeping at most `history_size` records
+    fn push_history(&self, record: QueryRecord, history_size: usize) {
+        let mut history = self.history.lock();
+        history.push_back(record);
+        while history.len() > history_size {
+            history.pop_front();
+        }
+    }
+}
+
+/// Keeps a query registered in [`RunningQueries`] until dropped, and
+/// records how it finished, which is added to the history on drop
+#[derive(Debug)]
+pub struct RunningQueryGuard {
+    id: u64,
+    registry: Arc<RunningQueries>,
+    memory_pool: Arc<HierarchicalMemoryPool>,
+    history_size: usize,
+    output_rows: AtomicUsize,
+    /// Number of [`RunningQueryStream`]s that have not reached their end
+    open_streams: AtomicUsize,
+    completed: AtomicBool,
+    error: Mutex<Option<(QueryStatus, String)>>,
+}
+
+impl RunningQueryGuard {
+    /// Returns the id of the registered query
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Records that the query produced `rows` more rows
+    pub fn add_output_rows(&self, rows: usize) {
+        self.output_rows.fetch_add(rows, Ordering::Relaxed);
+    }
+
+    /// Records that the query returned `error`, unless it already did
+    pub fn set_error(&self, error: &DataFusionError) {
+        let status = match error.find_root() {
+            DataFusionError::Cancelled(_) => QueryStatus::Cancelled,
+            _ => QueryStatus::Failed,
+        };
+        self.error
+            .lock()
+            .get_or_insert_with(|| (status, error.to_string()));
+    }
+
+    /// Records that the query produced all of its output
+    pub fn set_completed(&self) {
+        self.completed.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for RunningQueryGuard {
+    fn drop(&mut self) {
+        let Some(query) = self.registry.queries.lock().remove(&self.id) else {
+            return;
+        };
+
+        let (status, error) = match self.error.lock().take() {
+            Some((status, error)) => (status, Some(error)),
+            None if self.completed.load(Ordering::Relaxed) => {
+                (QueryStatus::Completed, None)
+            }
+            None => (QueryStatus::Cancelled, None),
+        };
+        let record = QueryRecord {
+            id: query.id,
+            session_id: query.session_id,
+            sql: query.sql,
+            start_time: query.start_time,
+            end_time: Utc::now(),
+            status,
+            error,
+            output_rows: self.output_rows.load(Ordering::Relaxed),
+            memory_used: self.memory_pool.peak_reserved(),
+            spilled_bytes: spilled_bytes(query.plan.as_ref()),
+        };
+        self.registry.push_history(record, self.history_size);
+    }
+}
+
+/// Returns the sum of the spilled bytes metrics of `plan` and its descendants
+fn spilled_bytes(plan: &dyn ExecutionPlan) -> usize {
+    let spilled: usize = plan
+        .children()
+        .iter()
+        .map(|child| spilled_bytes(child.as_ref()))
+        .sum();
+    let metrics = plan.metrics();
+    spilled + metrics.and_then(|m| m.spilled_bytes()).unwrap_or(0)
+}
+
+/// A stream that keeps its query registered in [`RunningQueries`] until
+/// it is dropped, recording the rows it returns and how it finished
+pub struct RunningQueryStream {
+    inner: SendableRecordBatchStream,
+    guard: Arc<RunningQueryGuard>,
+    /// Whether `inner` reached its end
+    done: bool,
+}
+
+impl RunningQueryStream {
+    /// Create a new stream returning the results of `inner`, which are
+    /// produced by the query registered with `guard`.
+    ///
+    /// The query is completed once all the streams created with `guard`
+    /// reach their end.
+    pub fn new(inner: SendableRecordBatchStream, guard: Arc<RunningQueryGuard>) -> Self {
+        guard.open_streams.fetch_add(1, Ordering::Relaxed);
+        Self {
+            inner,
+            guard,
+            done: false,
+        }
+    }
+}
+
+impl Stream for RunningQueryStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.inner.as_mut().poll_next(cx);
+        match &poll {
+            Poll::Ready(Some(Ok(batch))) => self.guard.add_output_rows(batch.num_rows()),
+            Poll::Ready(Some(Err(e))) => self.guard.set_error(e),
+            Poll::Ready(None) if !self.done => {
+                self.done = true;
+                if self.guard.open_streams.fetch_sub(1, Ordering::Relaxed) == 1 {
+                    self.guard.set_completed();
+                }
+            }
+            _ => {}
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl RecordBatchStream for RunningQueryStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{SessionConfig, SessionContext};
+    use arrow::array::{ArrayRef, Int32Array};
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn register_running_queries() {
+        let ctx = SessionContext::new();
+        let running = ctx.running_queries();
+        assert!(running.queries().is_empty());
+
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![1, 2, 3]));
+        let batch = RecordBatch::try_from_iter(vec![("a", a)]).unwrap();
+        ctx.register_batch("t", batch).unwrap();
+
+        let df = ctx.sql("SELECT a FROM t").await.unwrap();
+        let mut stream = df.execute_stream().await.unwrap();
+
+        let queries = running.queries();
+        assert_eq!(queries.len(), 1);
+        let query = &queries[0];
+        assert_eq!(query.session_id(), ctx.session_id());
+        assert_eq!(query.progress().rows, 0);
+        assert_eq!(query.progress().total_rows, Some(3));
+        assert_eq!(query.estimated_completion(), Some(0.0));
+
+        while let Some(batch) = stream.next().await {
+            batch.unwrap();
+        }
+        let query = running.get(query.id()).unwrap();
+        assert_eq!(query.progress().rows, 3);
+        assert_eq!(query.estimated_completion(), Some(1.0));
+
+        drop(stream);
+        assert!(running.queries().is_empty());
+
+        // collected queries are unregistered once done
+        ctx.sql("SELECT 1").await.unwrap().collect().await.unwrap();
+        assert!(running.queries().is_empty());
+
+        let history = running.history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].sql.as_deref(), Some("SELECT a FROM t"));
+        assert_eq!(history[0].status, QueryStatus::Completed);
+        assert_eq!(history[0].output_rows, 3);
+        assert!(history[0].start_time <= history[0].end_time);
+        assert_eq!(history[1].sql.as_deref(), Some("SELECT 1"));
+        assert_eq!(history[1].output_rows, 1);
+    }
+
+    #[tokio::test]
+    async fn cancel_running_query() {
+        let ctx = SessionContext::new();
+        let mut stream = ctx
+            .sql("SELECT * FROM (VALUES (1), (2)) AS t(a)")
+            .await
+            .unwrap()
+            .execute_stream()
+            .await
+            .unwrap();
+
+        ctx.running_queries().queries()[0].cancel();
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(matches!(err.find_root(), DataFusionError::Cancelled(_)));
+        drop(stream);
+
+        let history = ctx.running_queries().history();
+        assert_eq!(history[0].status, QueryStatus::Cancelled);
+        assert_eq!(
+            history[0].error.as_deref(),
+            Some("Query cancelled: cancel requested")
+        );
+    }
+
+    #[tokio::test]
+    async fn query_history() -> Result<()> {
+        let config =
+            SessionConfig::new().set_usize("datafusion.execution.query_history_size", 2);
+        let ctx = SessionContext::new_with_config(config);
+
+        ctx.sql("SELECT 1").await?.collect().await?;
+        let sql = "SELECT a / 0 FROM (VALUES (1)) AS t(a)";
+        ctx.sql(sql).await?.collect().await.unwrap_err();
+        // dropped before it was complete
+        drop(ctx.sql("SELECT 2").await?.execute_stream().await?);
+
+        // the oldest query was evicted
+        let history = ctx.running_queries().history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].sql.as_deref(), Some(sql));
+        assert_eq!(history[0].status, QueryStatus::Failed);
+        assert!(history[0]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("Divide by zero"));
+        assert_eq!(history[1].status, QueryStatus::Cancelled);
+        assert_eq!(history[1].error, None);
+        assert_eq!(history[1].output_rows, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn query_peak_memory() -> Result<()> {
+        let ctx = SessionContext::new();
+        let sql = "SELECT a FROM (VALUES (3), (1), (2)) AS t(a) ORDER BY a";
+        ctx.sql(sql).await?.collect().await?;
+
+        // the sort released its memory before the query finished
+        let history = ctx.running_queries().history();
+        assert!(history[0].memory_used > 0);
+        assert_eq!(ctx.state().memory_pool().reserved(), 0);
+        Ok(())
+    }
+}
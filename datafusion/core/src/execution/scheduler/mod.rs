@@ -0,0 +1,394 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! A morsel-driven [`Scheduler`] for executing [`ExecutionPlan`]s.
+//!
+//! By default each output partition of a plan is executed by a tokio task
+//! that pulls batches through all the operators of the partition, and
+//! exchanges such as [`RepartitionExec`] spawn tokio tasks of their own.
+//! The scheduler instead:
+//!
+//! 1. Splits the plan into pipelines at its pipeline breakers: the inputs
+//!    of exchanges, and the inputs that operators such as sorts, aggregates
+//!    and the build side of joins consume entirely before producing output.
+//!    The exchanges are implemented by routing the output of a pipeline to
+//!    the partitions of the next one.
+//!
+//! 2. Runs one task per partition of each pipeline on a fixed pool of
+//!    worker threads. Each time a task runs it produces one batch, a
+//!    *morsel*, after which it is queued behind the tasks of the other
+//!    queries, so that a long running query does not hold up short ones.
+//!
+//! 3. Lets idle workers steal tasks from busy ones, and splits the scans
+//!    feeding an exchange into more partitions than there are workers, so
+//!    that skewed inputs are processed by all workers.
+//!
+//! It is enabled with the `datafusion.execution.use_morsel_scheduler`
+//! option, and used by the [`DataFrame`] methods that execute a plan.
+//!
+//! [`RepartitionExec`]: crate::physical_plan::repartition::RepartitionExec
+//! [`DataFrame`]: crate::dataframe::DataFrame
+
+mod pipeline;
+mod task;
+mod worker;
+
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::Result;
+use datafusion_execution::TaskContext;
+use futures::Stream;
+
+use self::pipeline::{split_plan, PartitionBuffer};
+use self::task::Query;
+use self::worker::WorkerPool;
+use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use crate::physical_plan::{ExecutionPlan, RecordBatchStream, SendableRecordBatchStream};
+
+/// Executes [`ExecutionPlan`]s on a pool of worker threads, one batch at a
+/// time per task. See the [module documentation](self) for details.
+///
+/// Dropping the scheduler stops its worker threads once they have finished
+/// the batch they are producing.
+#[derive(Debug)]
+pub struct Scheduler {
+    pool: Arc<WorkerPool>,
+}
+
+impl Scheduler {
+    /// Create a new [`Scheduler`] with `num_threads` worker threads
+    pub fn new(num_threads: usize) -> Self {
+        Self {
+            pool: WorkerPool::start(num_threads),
+        }
+    }
+
+    /// Returns the scheduler shared by all sessions, with one worker
+    /// thread per CPU core
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<Scheduler> = OnceLock::new();
+        GLOBAL.get_or_init(|| Self::new(num_cpus::get()))
+    }
+
+    /// Returns the number of worker threads
+    pub fn num_threads(&self) -> usize {
+        self.pool.num_threads()
+    }
+
+    /// Executes `plan`, returning a stream over its output merged into a
+    /// single partition
+    pub fn execute_stream(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let plan = match plan.output_partitioning().partition_count() {
+            1 => plan,
+            _ => Arc::new(CoalescePartitionsExec::new(plan)),
+        };
+        let mut streams = self.execute_stream_partitioned(plan, context)?;
+        Ok(streams.swap_remove(0))
+    }
+
+    /// Executes `plan`, returning a stream for each of its output
+    /// partitions.
+    ///
+    /// The query runs until all streams have been read to the end or
+    /// dropped, or until it fails, in which case the error is returned by
+    /// one of the streams and the others return a generic error.
+    pub fn execute_stream_partitioned(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        context: Arc<TaskContext>,
+    ) -> Result<Vec<SendableRecordBatchStream>> {
+        let schema = plan.schema();
+        let (pipelines, buffers) = split_plan(
+            plan,
+            context.session_config().options(),
+            self.pool.num_threads(),
+        )?;
+        let query = Query::new(pipelines, context, &self.pool);
+        query.start();
+
+        let handle = Arc::new(QueryHandle(query));
+        Ok(buffers
+            .into_iter()
+            .map(|buffer| -> SendableRecordBatchStream {
+                Box::pin(SchedulerStream {
+                    schema: Arc::clone(&schema),
+                    buffer,
+                    handle: Arc::clone(&handle),
+                    done: false,
+                })
+            })
+            .collect())
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        self.pool.shutdown()
+    }
+}
+
+/// Stops its query when the last output stream of the query is dropped
+struct QueryHandle(Arc<Query>);
+
+impl Drop for QueryHandle {
+    fn drop(&mut self) {
+        self.0.stop()
+    }
+}
+
+/// Reads an output partition of a query run by the [`Scheduler`]
+struct SchedulerStream {
+    schema: SchemaRef,
+    buffer: Arc<PartitionBuffer>,
+    handle: Arc<QueryHandle>,
+    done: bool,
+}
+
+impl Stream for SchedulerStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        match self.buffer.poll_next(cx) {
+            Poll::Ready(Some(batch)) => Poll::Ready(Some(Ok(batch))),
+            Poll::Ready(None) => {
+                self.done = true;
+                Poll::Ready(self.handle.0.take_error().map(Err))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl RecordBatchStream for SchedulerStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrow::array::Int32Array;
+    use crate::arrow::datatypes::{DataType, Field, Schema};
+    use crate::arrow::util::pretty::pretty_format_batches;
+    use crate::datasource::MemTable;
+    use crate::physical_plan::{collect, displayable};
+    use crate::prelude::{SessionConfig, SessionContext};
+    use futures::StreamExt;
+
+    /// A table with 4 partitions of 3 batches of 100 rows each, with
+    /// `a = b % 7` and `b` from 0 to 1199
+    fn table() -> Result<Arc<MemTable>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let partitions = (0..4)
+            .map(|partition| {
+                (0..3)
+                    .map(|batch| {
+                        let start = (partition * 3 + batch) * 100;
+                        let b: Vec<i32> = (start..start + 100).collect();
+                        let a: Vec<i32> = b.iter().map(|b| b % 7).collect();
+                        RecordBatch::try_new(
+                            Arc::clone(&schema),
+                            vec![
+                                Arc::new(Int32Array::from(a)),
+                                Arc::new(Int32Array::from(b)),
+                            ],
+                        )
+                        .map_err(Into::into)
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Arc::new(MemTable::try_new(schema, partitions)?))
+    }
+
+    fn context(morsel_scheduler: bool) -> Result<SessionContext> {
+        let config = SessionConfig::new()
+            .with_target_partitions(4)
+            .with_batch_size(64)
+            .with_morsel_scheduler(morsel_scheduler);
+        let ctx = SessionContext::new_with_config(config);
+        ctx.register_table("t", table()?)?;
+        ctx.register_table("u", table()?)?;
+        Ok(ctx)
+    }
+
+    /// Runs `sql` with and without the scheduler, and checks that both
+    /// return the same rows
+    async fn assert_same_results(sql: &str) -> Result<()> {
+        let expected = context(false)?.sql(sql).await?.collect().await?;
+        let actual = context(true)?.sql(sql).await?.collect().await?;
+        let expected = pretty_format_batches(&expected)?.to_string();
+        let actual = pretty_format_batches(&actual)?.to_string();
+        assert_eq!(expected, actual, "different results for {sql}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn aggregate() -> Result<()> {
+        assert_same_results("SELECT a, count(*), sum(b) FROM t GROUP BY a ORDER BY a")
+            .await
+    }
+
+    #[tokio::test]
+    async fn join() -> Result<()> {
+        assert_same_results(
+            "SELECT t.a, count(*) FROM t JOIN u ON t.b = u.b WHERE u.a > 2 \
+             GROUP BY t.a ORDER BY t.a",
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn sort_and_limit() -> Result<()> {
+        assert_same_results("SELECT b FROM t ORDER BY b DESC").await?;
+        assert_same_results("SELECT a, b FROM t ORDER BY a, b LIMIT 10").await
+    }
+
+    #[tokio::test]
+    async fn union() -> Result<()> {
+        assert_same_results(
+            "SELECT a, b FROM t WHERE a = 1 UNION ALL SELECT a, b FROM u WHERE a = 2 \
+             ORDER BY b, a",
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn window() -> Result<()> {
+        assert_same_results(
+            "SELECT a, b, sum(b) OVER (PARTITION BY a ORDER BY b) FROM t ORDER BY b",
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn partitioned_streams() -> Result<()> {
+        let ctx = context(true)?;
+        let df = ctx.sql("SELECT a, b FROM t WHERE a = 3").await?;
+        let streams = df.execute_stream_partitioned().await?;
+        assert_eq!(streams.len(), 4);
+        let mut rows = 0;
+        for mut stream in streams {
+            while let Some(batch) = stream.next().await {
+                rows += batch?.num_rows();
+            }
+        }
+        assert_eq!(rows, 171);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn error() -> Result<()> {
+        let ctx = context(true)?;
+        let err = ctx
+            .sql("SELECT a, sum(b / (a - a)) FROM t GROUP BY a")
+            .await?
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Divide by zero"), "{err}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn drop_stream() -> Result<()> {
+        let scheduler = Scheduler::new(2);
+        let ctx = context(true)?;
+        let plan = ctx
+            .sql("SELECT t.b FROM t CROSS JOIN u")
+            .await?
+            .create_physical_plan()
+            .await?;
+        let mut stream = scheduler.execute_stream(plan, ctx.task_ctx())?;
+        assert!(stream.next().await.transpose()?.is_some());
+        drop(stream);
+
+        // the workers are not held up by the stopped query
+        let plan = ctx
+            .sql("SELECT count(*) FROM t")
+            .await?
+            .create_physical_plan()
+            .await?;
+        let stream = scheduler.execute_stream(plan, ctx.task_ctx())?;
+        let batches = crate::physical_plan::common::collect(stream).await?;
+        let expected = collect(
+            ctx.sql("SELECT count(*) FROM t")
+                .await?
+                .create_physical_plan()
+                .await?,
+            ctx.task_ctx(),
+        )
+        .await?;
+        assert_eq!(batches, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pipelines() -> Result<()> {
+        let ctx = context(true)?;
+        let plan = ctx
+            .sql("SELECT a, count(*) FROM t GROUP BY a")
+            .await?
+            .create_physical_plan()
+            .await?;
+        let (pipelines, buffers) = split_plan(plan, ctx.state().config_options(), 2)?;
+        assert_eq!(buffers.len(), 4);
+
+        // the exchanges are replaced by the routing of the pipelines, and
+        // the scan runs in a pipeline of its own as the partial aggregate
+        // consumes all of its input
+        let plans: Vec<_> = pipelines
+            .iter()
+            .map(|p| displayable(p.plan.as_ref()).indent(true).to_string())
+            .collect();
+        assert!(
+            plans.iter().all(|p| !p.contains("RepartitionExec")),
+            "{plans:?}"
+        );
+        assert!(plans[0].starts_with("MemoryExec"), "{plans:?}");
+        assert!(
+            plans
+                .last()
+                .unwrap()
+                .starts_with("AggregateExec: mode=FinalPartitioned"),
+            "{plans:?}"
+        );
+        assert!(matches!(
+            pipelines[1].output,
+            pipeline::OutputRouting::Repartition { .. }
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,616 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Splits an [`ExecutionPlan`] into the [`Pipeline`]s run by the scheduler
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::{internal_err, DataFusionError, Result, Statistics};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::{EquivalenceProperties, PhysicalSortExpr};
+use futures::Stream;
+use parking_lot::Mutex;
+
+use crate::physical_plan::aggregates::AggregateExec;
+use crate::physical_plan::analyze::AnalyzeExec;
+use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::joins::{CrossJoinExec, HashJoinExec, NestedLoopJoinExec};
+use crate::physical_plan::metrics;
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::repartition::{BatchPartitioner, RepartitionExec};
+use crate::physical_plan::sorts::sort::SortExec;
+use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use crate::physical_plan::windows::WindowAggExec;
+use crate::physical_plan::{
+    with_new_children_if_necessary, DisplayAs, DisplayFormatType, Distribution,
+    ExecutionPlan, Partitioning, RecordBatchStream, SendableRecordBatchStream,
+};
+
+/// The number of scan partitions per worker thread that the scans feeding
+/// an exchange are split into, so that skewed files are read by several
+/// workers
+const SCAN_MORSELS_PER_WORKER: usize = 4;
+
+/// A part of a plan whose operators are executed together, one task per
+/// output partition, without waiting for each other. The outputs of its
+/// tasks are routed to the [`PipelineSourceExec`] of the next pipeline.
+#[derive(Debug)]
+pub(crate) struct Pipeline {
+    /// The operators of this pipeline, whose leaves are the scans or the
+    /// [`PipelineSourceExec`]s of the pipelines it depends on
+    pub(crate) plan: Arc<dyn ExecutionPlan>,
+    /// Where the output of the tasks goes
+    pub(crate) output: OutputRouting,
+}
+
+impl Pipeline {
+    /// Returns the number of tasks of this pipeline
+    pub(crate) fn partitions(&self) -> usize {
+        self.plan.output_partitioning().partition_count()
+    }
+}
+
+/// Splits `plan` into pipelines at its pipeline breakers, returning the
+/// pipelines in the order they should be started and the buffers holding
+/// the output partitions of `plan`
+pub(crate) fn split_plan(
+    plan: Arc<dyn ExecutionPlan>,
+    config: &ConfigOptions,
+    workers: usize,
+) -> Result<(Vec<Pipeline>, Vec<Arc<PartitionBuffer>>)> {
+    let mut planner = PipelinePlanner {
+        pipelines: vec![],
+        config,
+        scan_partitions: workers * SCAN_MORSELS_PER_WORKER,
+    };
+    let plan = planner.visit(plan)?;
+    let output = OutputRouting::partitions(plan.output_partitioning().partition_count());
+    let buffers = output.buffers().to_vec();
+    planner.pipelines.push(Pipeline { plan, output });
+    Ok((planner.pipelines, buffers))
+}
+
+/// Returns the operator `plan` is, looking through the [`InstrumentedExec`]
+/// wrapping every operator when tracing is enabled
+///
+/// [`InstrumentedExec`]: crate::physical_plan::instrumented::InstrumentedExec
+fn operator(plan: &dyn ExecutionPlan) -> &dyn Any {
+    #[cfg(feature = "tracing")]
+    if let Some(instrumented) =
+        plan.as_any()
+            .downcast_ref::<crate::physical_plan::instrumented::InstrumentedExec>()
+    {
+        return instrumented.inner().as_any();
+    }
+    plan.as_any()
+}
+
+/// The exchanges between partitions that the scheduler implements itself
+enum Exchange {
+    Coalesce,
+    Repartition(Partitioning),
+}
+
+impl Exchange {
+    fn try_new(plan: &dyn ExecutionPlan) -> Option<Self> {
+        if operator(plan).is::<CoalescePartitionsExec>() {
+            return Some(Self::Coalesce);
+        }
+        // order preserving and range repartitions are executed as operators
+        let repartition = operator(plan).downcast_ref::<RepartitionExec>()?;
+        match repartition.partitioning() {
+            partitioning @ (Partitioning::RoundRobinBatch(_)
+            | Partitioning::Hash(_, _))
+                if !repartition.preserve_order() =>
+            {
+                Some(Self::Repartition(partitioning.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns for each child of `plan` whether `plan` consumes it entirely
+/// before producing output, or reads all of its partitions in one
+/// partition, in which case the child is run in a pipeline of its own
+fn pipeline_breaking_children(plan: &dyn ExecutionPlan) -> Vec<bool> {
+    let any = operator(plan);
+    if any.is::<SortExec>()
+        || any.is::<AggregateExec>()
+        || any.is::<WindowAggExec>()
+        || any.is::<SortPreservingMergeExec>()
+    {
+        vec![true]
+    } else if any.is::<HashJoinExec>()
+        || any.is::<CrossJoinExec>()
+        || any.is::<NestedLoopJoinExec>()
+    {
+        // only the build side is collected
+        vec![true, false]
+    } else {
+        vec![false; plan.children().len()]
+    }
+}
+
+struct PipelinePlanner<'a> {
+    /// The pipelines found so far, upstream pipelines first
+    pipelines: Vec<Pipeline>,
+    config: &'a ConfigOptions,
+    /// The target number of partitions of scans feeding an exchange
+    scan_partitions: usize,
+}
+
+impl<'a> PipelinePlanner<'a> {
+    /// Returns `plan` with the subtrees that run in pipelines of their
+    /// own replaced by [`PipelineSourceExec`]s
+    fn visit(&mut self, plan: Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+        // reads the metrics of the operators of its input
+        if operator(plan.as_ref()).is::<AnalyzeExec>() {
+            return Ok(plan);
+        }
+
+        if let Some(exchange) = Exchange::try_new(plan.as_ref()) {
+            let mut input = self.visit(Arc::clone(&plan.children()[0]))?;
+            if plan.output_ordering().is_none() {
+                input = self.split_scan(input)?;
+            }
+            let producers = input.output_partitioning().partition_count();
+            let output = match exchange {
+                Exchange::Coalesce => OutputRouting::coalesce(producers),
+                Exchange::Repartition(partitioning) => {
+                    OutputRouting::repartition(partitioning, producers)?
+                }
+            };
+            return self.push_pipeline(plan.as_ref(), input, output);
+        }
+
+        let children = plan.children();
+        if children.is_empty() {
+            return Ok(plan);
+        }
+        let breaking = pipeline_breaking_children(plan.as_ref());
+        let children = children
+            .into_iter()
+            .zip(breaking)
+            .map(|(child, breaking)| {
+                // an exchange already starts a pipeline of its own
+                if breaking && Exchange::try_new(child.as_ref()).is_none() {
+                    let input = self.visit(Arc::clone(&child))?;
+                    let output = OutputRouting::partitions(
+                        input.output_partitioning().partition_count(),
+                    );
+                    self.push_pipeline(child.as_ref(), input, output)
+                } else {
+                    self.visit(child)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(with_new_children_if_necessary(plan, children)?.into())
+    }
+
+    /// Adds a pipeline running `input`, and returns the source reading its
+    /// `output` in place of `replaced`
+    fn push_pipeline(
+        &mut self,
+        replaced: &dyn ExecutionPlan,
+        input: Arc<dyn ExecutionPlan>,
+        output: OutputRouting,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let source = PipelineSourceExec::try_new(replaced, output.buffers().to_vec())?;
+        self.pipelines.push(Pipeline {
+            plan: input,
+            output,
+        });
+        Ok(Arc::new(source))
+    }
+
+    /// Splits the file ranges of the scan at the bottom of `plan` into more
+    /// partitions, if all the operators above it process each partition
+    /// independently. Only used for pipelines feeding an exchange that does
+    /// not preserve the order of its input, where the number of partitions
+    /// of the input does not matter.
+    fn split_scan(&self, plan: Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+        if !self.config.optimizer.repartition_file_scans {
+            return Ok(plan);
+        }
+        let children = plan.children();
+        match children.as_slice() {
+            [] if plan.output_partitioning().partition_count() < self.scan_partitions => {
+                Ok(plan
+                    .repartitioned(self.scan_partitions, self.config)?
+                    .unwrap_or(plan))
+            }
+            [child]
+                if is_partition_independent(plan.as_ref())
+                    && matches!(
+                        plan.required_input_distribution()[0],
+                        Distribution::UnspecifiedDistribution
+                    ) =>
+            {
+                let child = self.split_scan(Arc::clone(child))?;
+                Ok(with_new_children_if_necessary(plan, vec![child])?.into())
+            }
+            _ => Ok(plan),
+        }
+    }
+}
+
+/// Returns true if each output partition of `plan` only depends on the
+/// same partition of its input
+fn is_partition_independent(plan: &dyn ExecutionPlan) -> bool {
+    let any = operator(plan);
+    any.is::<FilterExec>()
+        || any.is::<ProjectionExec>()
+        || any.is::<CoalesceBatchesExec>()
+}
+
+/// Where the output batches of the tasks of a [`Pipeline`] are sent
+pub(crate) enum OutputRouting {
+    /// Each partition is read by the same partition of the consumer
+    Partitions(Vec<Arc<PartitionBuffer>>),
+    /// All partitions are read by the single partition of the consumer
+    Coalesce(Vec<Arc<PartitionBuffer>>),
+    /// The rows of each partition are split between the partitions of the
+    /// consumer by the partitioner of that partition
+    Repartition {
+        buffers: Vec<Arc<PartitionBuffer>>,
+        partitioners: Vec<Mutex<BatchPartitioner>>,
+    },
+}
+
+impl fmt::Debug for OutputRouting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Partitions(buffers) => {
+                f.debug_tuple("Partitions").field(&buffers.len()).finish()
+            }
+            Self::Coalesce(buffers) => {
+                f.debug_tuple("Coalesce").field(&buffers.len()).finish()
+            }
+            Self::Repartition {
+                buffers,
+                partitioners,
+            } => f
+                .debug_struct("Repartition")
+                .field("buffers", &buffers.len())
+                .field("producers", &partitioners.len())
+                .finish(),
+        }
+    }
+}
+
+impl OutputRouting {
+    fn partitions(partitions: usize) -> Self {
+        Self::Partitions(
+            (0..partitions)
+                .map(|_| Arc::new(PartitionBuffer::new(1)))
+                .collect(),
+        )
+    }
+
+    fn coalesce(producers: usize) -> Self {
+        Self::Coalesce(vec![Arc::new(PartitionBuffer::new(producers))])
+    }
+
+    fn repartition(partitioning: Partitioning, producers: usize) -> Result<Self> {
+        let buffers = (0..partitioning.partition_count())
+            .map(|_| Arc::new(PartitionBuffer::new(producers)))
+            .collect();
+        let partitioners = (0..producers)
+            .map(|_| {
+                BatchPartitioner::try_new(partitioning.clone(), metrics::Time::new())
+                    .map(Mutex::new)
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self::Repartition {
+            buffers,
+            partitioners,
+        })
+    }
+
+    /// Returns the buffers read by the consumer, one per partition
+    pub(crate) fn buffers(&self) -> &[Arc<PartitionBuffer>] {
+        match self {
+            Self::Partitions(buffers)
+            | Self::Coalesce(buffers)
+            | Self::Repartition { buffers, .. } => buffers,
+        }
+    }
+
+    /// Routes `batch`, produced by `partition`, to the consumer
+    pub(crate) fn push(&self, partition: usize, batch: RecordBatch) -> Result<()> {
+        match self {
+            Self::Partitions(buffers) => buffers[partition].push(batch),
+            Self::Coalesce(buffers) => buffers[0].push(batch),
+            Self::Repartition {
+                buffers,
+                partitioners,
+            } => {
+                partitioners[partition]
+                    .lock()
+                    .partition(batch, |i, batch| {
+                        buffers[i].push(batch);
+                        Ok(())
+                    })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Records that `partition` has produced all of its output
+    pub(crate) fn finish(&self, partition: usize) {
+        match self {
+            Self::Partitions(buffers) => buffers[partition].finish_producer(),
+            Self::Coalesce(buffers) | Self::Repartition { buffers, .. } => {
+                buffers.iter().for_each(|b| b.finish_producer())
+            }
+        }
+    }
+
+    /// Returns true if a buffer that `partition` writes to is full, in
+    /// which case `waker` is woken once it has capacity again
+    pub(crate) fn block_if_full(&self, partition: usize, waker: &Waker) -> bool {
+        match self {
+            Self::Partitions(buffers) => buffers[partition].block_if_full(waker),
+            Self::Coalesce(buffers) | Self::Repartition { buffers, .. } => {
+                buffers.iter().any(|b| b.block_if_full(waker))
+            }
+        }
+    }
+}
+
+/// Holds the batches produced for one partition of a [`PipelineSourceExec`]
+/// until they are read.
+///
+/// The buffer has a soft capacity: producers stop producing while it is
+/// full, so that a slow consumer does not cause the batches of a fast
+/// producer to accumulate in memory.
+#[derive(Debug)]
+pub(crate) struct PartitionBuffer {
+    state: Mutex<BufferState>,
+    capacity: usize,
+}
+
+#[derive(Debug)]
+struct BufferState {
+    batches: VecDeque<RecordBatch>,
+    /// The number of producers that have not finished
+    producers: usize,
+    /// Set when the query stopped, after which no batches are returned
+    closed: bool,
+    /// Woken when a batch is pushed or the last producer finishes
+    consumer: Option<Waker>,
+    /// Woken when the buffer is no longer full
+    blocked: Vec<Waker>,
+}
+
+impl PartitionBuffer {
+    fn new(producers: usize) -> Self {
+        Self {
+            state: Mutex::new(BufferState {
+                batches: VecDeque::new(),
+                producers,
+                closed: false,
+                consumer: None,
+                blocked: vec![],
+            }),
+            capacity: 2 * producers.max(1),
+        }
+    }
+
+    fn push(&self, batch: RecordBatch) {
+        let consumer = {
+            let mut state = self.state.lock();
+            if state.closed {
+                return;
+            }
+            state.batches.push_back(batch);
+            state.consumer.take()
+        };
+        consumer.into_iter().for_each(Waker::wake);
+    }
+
+    fn finish_producer(&self) {
+        let consumer = {
+            let mut state = self.state.lock();
+            state.producers = state.producers.saturating_sub(1);
+            if state.producers > 0 {
+                return;
+            }
+            state.consumer.take()
+        };
+        consumer.into_iter().for_each(Waker::wake);
+    }
+
+    fn block_if_full(&self, waker: &Waker) -> bool {
+        let mut state = self.state.lock();
+        if state.closed || state.batches.len() < self.capacity {
+            return false;
+        }
+        if !state.blocked.iter().any(|w| w.will_wake(waker)) {
+            state.blocked.push(waker.clone());
+        }
+        true
+    }
+
+    /// Returns the next batch, or `None` once all producers have finished
+    /// or the query has stopped
+    pub(crate) fn poll_next(&self, cx: &mut Context<'_>) -> Poll<Option<RecordBatch>> {
+        let mut state = self.state.lock();
+        if state.closed {
+            return Poll::Ready(None);
+        }
+        match state.batches.pop_front() {
+            Some(batch) => {
+                if state.batches.len() < self.capacity {
+                    let blocked = std::mem::take(&mut state.blocked);
+                    drop(state);
+                    blocked.into_iter().for_each(Waker::wake);
+                }
+                Poll::Ready(Some(batch))
+            }
+            None if state.producers == 0 => Poll::Ready(None),
+            None => {
+                state.consumer = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Drops the buffered batches and the wakers, and wakes the consumer,
+    /// when the query has stopped
+    pub(crate) fn close(&self) {
+        let (consumer, blocked) = {
+            let mut state = self.state.lock();
+            state.closed = true;
+            state.batches.clear();
+            (state.consumer.take(), std::mem::take(&mut state.blocked))
+        };
+        consumer.into_iter().for_each(Waker::wake);
+        drop(blocked);
+    }
+}
+
+/// A leaf reading the output of an upstream [`Pipeline`] in place of the
+/// subtree that pipeline runs, with the same properties as that subtree
+#[derive(Debug)]
+pub(crate) struct PipelineSourceExec {
+    schema: SchemaRef,
+    partitioning: Partitioning,
+    output_ordering: Option<Vec<PhysicalSortExpr>>,
+    equivalence_properties: EquivalenceProperties,
+    statistics: Statistics,
+    buffers: Vec<Arc<PartitionBuffer>>,
+}
+
+impl PipelineSourceExec {
+    fn try_new(
+        replaced: &dyn ExecutionPlan,
+        buffers: Vec<Arc<PartitionBuffer>>,
+    ) -> Result<Self> {
+        let partitioning = replaced.output_partitioning();
+        if partitioning.partition_count() != buffers.len() {
+            return internal_err!(
+                "Pipeline source with {} buffers for {} partitions",
+                buffers.len(),
+                partitioning.partition_count()
+            );
+        }
+        Ok(Self {
+            schema: replaced.schema(),
+            partitioning,
+            output_ordering: replaced.output_ordering().map(|o| o.to_vec()),
+            equivalence_properties: replaced.equivalence_properties(),
+            statistics: replaced.statistics()?,
+            buffers,
+        })
+    }
+}
+
+impl DisplayAs for PipelineSourceExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "PipelineSourceExec: partitions={}", self.buffers.len())
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for PipelineSourceExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.partitioning.clone()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.output_ordering.as_deref()
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        self.equivalence_properties.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let Some(buffer) = self.buffers.get(partition) else {
+            return internal_err!(
+                "Invalid partition {partition} of PipelineSourceExec with {} partitions",
+                self.buffers.len()
+            );
+        };
+        Ok(Box::pin(PipelineSourceStream {
+            schema: Arc::clone(&self.schema),
+            buffer: Arc::clone(buffer),
+        }))
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        Ok(self.statistics.clone())
+    }
+}
+
+/// Reads the batches of one [`PartitionBuffer`]
+pub(crate) struct PipelineSourceStream {
+    pub(crate) schema: SchemaRef,
+    pub(crate) buffer: Arc<PartitionBuffer>,
+}
+
+impl Stream for PipelineSourceStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.buffer.poll_next(cx).map(|batch| batch.map(Ok))
+    }
+}
+
+impl RecordBatchStream for PipelineSourceStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
@@ -0,0 +1,371 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The [`Task`]s running one partition of a pipeline, one morsel at a time
+
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll, Wake, Waker};
+
+use datafusion_common::{exec_datafusion_err, DataFusionError};
+use datafusion_execution::TaskContext;
+use futures::StreamExt;
+use parking_lot::Mutex;
+use tokio::runtime::Handle;
+
+use super::pipeline::Pipeline;
+use super::worker::{Placement, WorkerPool};
+use crate::physical_plan::SendableRecordBatchStream;
+
+/// The task is waiting to be woken
+const IDLE: u8 = 0;
+/// The task is queued in the worker pool
+const SCHEDULED: u8 = 1;
+/// A worker is running the task
+const RUNNING: u8 = 2;
+/// The task was woken while it was running, and runs again afterwards
+const NOTIFIED: u8 = 3;
+/// The task has finished
+const DONE: u8 = 4;
+
+/// Source of the ids of the queries, which the worker pool uses to let
+/// the queries take turns
+static NEXT_QUERY_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// The state of a query executed by the scheduler
+pub(crate) struct Query {
+    id: usize,
+    pipelines: Vec<Pipeline>,
+    /// The tasks of each pipeline, one per partition
+    tasks: Vec<Vec<TaskSlot>>,
+    context: Arc<TaskContext>,
+    /// The tokio runtime that spawned the query, entered while running its
+    /// tasks, for the operators that spawn tokio tasks or perform async IO
+    runtime: Option<Handle>,
+    pool: Weak<WorkerPool>,
+    /// The number of tasks that have not finished
+    remaining: AtomicUsize,
+    /// The first error of a task
+    error: Mutex<Option<DataFusionError>>,
+    failed: AtomicBool,
+    stopped: AtomicBool,
+}
+
+struct TaskSlot {
+    state: AtomicU8,
+    stream: Mutex<TaskStream>,
+}
+
+enum TaskStream {
+    NotStarted,
+    Running(SendableRecordBatchStream),
+    Done,
+}
+
+impl Query {
+    pub(crate) fn new(
+        pipelines: Vec<Pipeline>,
+        context: Arc<TaskContext>,
+        pool: &Arc<WorkerPool>,
+    ) -> Arc<Self> {
+        let tasks: Vec<Vec<_>> = pipelines
+            .iter()
+            .map(|pipeline| {
+                (0..pipeline.partitions())
+                    .map(|_| TaskSlot {
+                        state: AtomicU8::new(IDLE),
+                        stream: Mutex::new(TaskStream::NotStarted),
+                    })
+                    .collect()
+            })
+            .collect();
+        let remaining = tasks.iter().map(|t| t.len()).sum();
+        Arc::new(Self {
+            id: NEXT_QUERY_ID.fetch_add(1, Ordering::Relaxed),
+            pipelines,
+            tasks,
+            context,
+            runtime: Handle::try_current().ok(),
+            pool: Arc::downgrade(pool),
+            remaining: AtomicUsize::new(remaining),
+            error: Mutex::new(None),
+            failed: AtomicBool::new(false),
+            stopped: AtomicBool::new(false),
+        })
+    }
+
+    /// Queues the tasks of all pipelines, upstream pipelines first
+    pub(crate) fn start(self: &Arc<Self>) {
+        for (pipeline, tasks) in self.tasks.iter().enumerate() {
+            for partition in 0..tasks.len() {
+                Task {
+                    query: Arc::clone(self),
+                    pipeline,
+                    partition,
+                }
+                .wake(Placement::Shared);
+            }
+        }
+    }
+
+    /// Returns true once the query has failed or was cancelled
+    pub(crate) fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Returns the error that made the query fail, once
+    pub(crate) fn take_error(&self) -> Option<DataFusionError> {
+        if !self.failed.load(Ordering::SeqCst) {
+            return None;
+        }
+        let error = self.error.lock().take();
+        Some(
+            error.unwrap_or_else(|| {
+                exec_datafusion_err!("Query failed in another partition")
+            }),
+        )
+    }
+
+    fn fail(&self, error: DataFusionError) {
+        self.error.lock().get_or_insert(error);
+        self.failed.store(true, Ordering::SeqCst);
+        self.stop();
+    }
+
+    /// Stops the query, dropping the streams of its tasks and the buffered
+    /// batches. Tasks that are running drop their streams when they finish
+    /// their current morsel.
+    pub(crate) fn stop(&self) {
+        if self.stopped.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        for slot in self.tasks.iter().flatten() {
+            if let Some(mut stream) = slot.stream.try_lock() {
+                *stream = TaskStream::Done;
+            }
+        }
+        self.close_buffers();
+    }
+
+    /// Closes the buffers between the pipelines, which drops the wakers
+    /// they hold that reference this query
+    fn close_buffers(&self) {
+        for pipeline in &self.pipelines {
+            pipeline.output.buffers().iter().for_each(|b| b.close());
+        }
+    }
+
+    /// Closes the buffers between the pipelines once all tasks have
+    /// finished, as the wakers of finished tasks may still be registered
+    /// in them
+    fn task_finished(&self) {
+        if self.remaining.fetch_sub(1, Ordering::SeqCst) == 1 {
+            // the output of the last pipeline is still read by the caller
+            let upstream = self.pipelines.len().saturating_sub(1);
+            for pipeline in &self.pipelines[..upstream] {
+                pipeline.output.buffers().iter().for_each(|b| b.close());
+            }
+        }
+    }
+}
+
+/// Runs one partition of a pipeline of a [`Query`], producing one batch, or
+/// morsel, each time it runs.
+///
+/// After each morsel the task is queued again behind the tasks of the other
+/// queries, and a task waiting for its input or for room in its output is
+/// queued again when it is woken.
+#[derive(Clone)]
+pub(crate) struct Task {
+    query: Arc<Query>,
+    pipeline: usize,
+    partition: usize,
+}
+
+impl fmt::Debug for Task {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Task")
+            .field("query", &self.query.id)
+            .field("pipeline", &self.pipeline)
+            .field("partition", &self.partition)
+            .finish()
+    }
+}
+
+/// What a task did when it ran
+enum Outcome {
+    /// Produced a morsel, and can produce more
+    Yield,
+    /// Waits for its input, or for room in its output
+    Pending,
+    /// Produced all of its output, or stopped
+    Done,
+}
+
+impl Task {
+    /// Returns the id of the query of this task
+    pub(crate) fn query_id(&self) -> usize {
+        self.query.id
+    }
+
+    fn slot(&self) -> &TaskSlot {
+        &self.query.tasks[self.pipeline][self.partition]
+    }
+
+    /// Queues this task, unless it is already queued or finished. If it
+    /// is running, it runs again once it returns.
+    fn wake(self, placement: Placement) {
+        let state = &self.slot().state;
+        let mut current = state.load(Ordering::SeqCst);
+        loop {
+            let next = match current {
+                IDLE => SCHEDULED,
+                RUNNING => NOTIFIED,
+                _ => return,
+            };
+            match state.compare_exchange(
+                current,
+                next,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) if next == SCHEDULED => return self.spawn(placement),
+                Ok(_) => return,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    fn spawn(self, placement: Placement) {
+        if let Some(pool) = self.query.pool.upgrade() {
+            pool.spawn(self, placement)
+        }
+    }
+
+    /// Runs this task until it has produced a morsel or has to wait
+    pub(crate) fn run(self) {
+        let slot = self.slot();
+        slot.state.store(RUNNING, Ordering::SeqCst);
+
+        let outcome = match self.query.context.cancellation_token().check() {
+            _ if self.query.is_stopped() => Outcome::Done,
+            Ok(()) => self.poll(),
+            Err(e) => {
+                self.query.fail(e);
+                Outcome::Done
+            }
+        };
+
+        match outcome {
+            // the query may have stopped while the stream was polled
+            _ if self.query.is_stopped() => self.finish(),
+            Outcome::Done => self.finish(),
+            Outcome::Yield => {
+                slot.state.store(SCHEDULED, Ordering::SeqCst);
+                self.spawn(Placement::Shared);
+            }
+            Outcome::Pending => {
+                if slot
+                    .state
+                    .compare_exchange(RUNNING, IDLE, Ordering::SeqCst, Ordering::SeqCst)
+                    .is_err()
+                {
+                    // woken while running
+                    slot.state.store(SCHEDULED, Ordering::SeqCst);
+                    self.spawn(Placement::Local);
+                }
+            }
+        }
+    }
+
+    fn finish(self) {
+        let slot = self.slot();
+        slot.state.store(DONE, Ordering::SeqCst);
+        *slot.stream.lock() = TaskStream::Done;
+        self.query.task_finished();
+    }
+
+    fn poll(&self) -> Outcome {
+        let _runtime = self.query.runtime.as_ref().map(|handle| handle.enter());
+        let pipeline = &self.query.pipelines[self.pipeline];
+        let waker = Waker::from(Arc::new(TaskWaker(self.clone())));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut stream = self.slot().stream.lock();
+        if matches!(*stream, TaskStream::NotStarted) {
+            let context = Arc::clone(&self.query.context);
+            match pipeline.plan.execute(self.partition, context) {
+                Ok(s) => *stream = TaskStream::Running(s),
+                Err(e) => {
+                    self.query.fail(e);
+                    return Outcome::Done;
+                }
+            }
+        }
+        let TaskStream::Running(s) = &mut *stream else {
+            return Outcome::Done;
+        };
+
+        if pipeline.output.block_if_full(self.partition, &waker) {
+            return Outcome::Pending;
+        }
+
+        let poll = catch_unwind(AssertUnwindSafe(|| s.poll_next_unpin(&mut cx)))
+            .unwrap_or_else(|_| {
+                Poll::Ready(Some(Err(DataFusionError::Internal(format!(
+                    "Task of pipeline {} partition {} panicked",
+                    self.pipeline, self.partition
+                )))))
+            });
+        match poll {
+            Poll::Ready(Some(Ok(batch))) => {
+                match pipeline.output.push(self.partition, batch) {
+                    Ok(()) => Outcome::Yield,
+                    Err(e) => {
+                        self.query.fail(e);
+                        Outcome::Done
+                    }
+                }
+            }
+            Poll::Ready(Some(Err(e))) => {
+                self.query.fail(e);
+                Outcome::Done
+            }
+            Poll::Ready(None) => {
+                pipeline.output.finish(self.partition);
+                Outcome::Done
+            }
+            Poll::Pending => Outcome::Pending,
+        }
+    }
+}
+
+/// Queues its task when it is woken by the input or output of the task,
+/// on the local queue of the worker that woke it
+struct TaskWaker(Task);
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.clone().wake(Placement::Local)
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.clone().wake(Placement::Local)
+    }
+}
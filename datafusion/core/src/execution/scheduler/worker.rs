@@ -0,0 +1,246 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The pool of worker threads running the tasks of the scheduler
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::debug;
+use parking_lot::{Condvar, Mutex};
+
+use super::task::Task;
+
+/// How often a worker takes a task from the shared queue before its local
+/// queue, so that tasks woken on this worker do not starve other queries
+const SHARED_QUEUE_INTERVAL: usize = 61;
+
+/// How long an idle worker sleeps before checking for shutdown
+const IDLE_TIMEOUT: Duration = Duration::from_millis(100);
+
+thread_local! {
+    /// The pool and index of the worker running on this thread, if any
+    static CURRENT_WORKER: Cell<Option<(*const WorkerPool, usize)>> = const { Cell::new(None) };
+}
+
+/// Where a task is queued when it is spawned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Placement {
+    /// The local queue of the current worker, so that a task woken by the
+    /// output of another task runs on the same thread while its input is
+    /// still in the CPU caches
+    Local,
+    /// The shared queue, in which the queries take turns
+    Shared,
+}
+
+/// A fixed pool of worker threads, each with a local queue of tasks, that
+/// share a queue in which the queries take turns. Idle workers steal half
+/// of the local queue of another worker.
+#[derive(Debug)]
+pub(crate) struct WorkerPool {
+    local: Vec<Mutex<VecDeque<Task>>>,
+    shared: Mutex<FairQueue<Task>>,
+    /// The number of workers about to sleep
+    sleepers: AtomicUsize,
+    idle: Mutex<()>,
+    wakeup: Condvar,
+    shutdown: AtomicBool,
+}
+
+impl WorkerPool {
+    /// Starts a pool of `num_threads` workers
+    pub(crate) fn start(num_threads: usize) -> Arc<Self> {
+        let num_threads = num_threads.max(1);
+        let pool = Arc::new(Self {
+            local: (0..num_threads).map(|_| Mutex::default()).collect(),
+            shared: Mutex::default(),
+            sleepers: AtomicUsize::new(0),
+            idle: Mutex::new(()),
+            wakeup: Condvar::new(),
+            shutdown: AtomicBool::new(false),
+        });
+        for index in 0..num_threads {
+            let pool = Arc::clone(&pool);
+            std::thread::Builder::new()
+                .name(format!("datafusion-worker-{index}"))
+                .spawn(move || pool.run_worker(index))
+                .expect("failed to spawn scheduler worker thread");
+        }
+        debug!("Started scheduler with {num_threads} worker threads");
+        pool
+    }
+
+    /// Returns the number of worker threads
+    pub(crate) fn num_threads(&self) -> usize {
+        self.local.len()
+    }
+
+    /// Queues `task` to be run by a worker
+    pub(crate) fn spawn(&self, task: Task, placement: Placement) {
+        let worker = match placement {
+            Placement::Local => CURRENT_WORKER
+                .with(|current| current.get())
+                .filter(|(pool, _)| std::ptr::eq(*pool, self))
+                .map(|(_, index)| index),
+            Placement::Shared => None,
+        };
+        match worker {
+            Some(index) => self.local[index].lock().push_back(task),
+            None => {
+                let query = task.query_id();
+                self.shared.lock().push(query, task)
+            }
+        }
+        if self.sleepers.load(Ordering::SeqCst) > 0 {
+            let _idle = self.idle.lock();
+            self.wakeup.notify_one();
+        }
+    }
+
+    /// Stops the workers once they have finished their current task
+    pub(crate) fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        let _idle = self.idle.lock();
+        self.wakeup.notify_all();
+    }
+
+    fn run_worker(self: Arc<Self>, index: usize) {
+        CURRENT_WORKER.with(|current| current.set(Some((Arc::as_ptr(&self), index))));
+        let mut tick = 0;
+        while !self.shutdown.load(Ordering::SeqCst) {
+            tick += 1;
+            if let Some(task) = self.next_task(index, tick % SHARED_QUEUE_INTERVAL == 0) {
+                task.run();
+                continue;
+            }
+
+            let mut idle = self.idle.lock();
+            self.sleepers.fetch_add(1, Ordering::SeqCst);
+            // a task spawned before the increment is found here, one
+            // spawned after it notifies this worker
+            match self.next_task(index, false) {
+                Some(task) => {
+                    self.sleepers.fetch_sub(1, Ordering::SeqCst);
+                    drop(idle);
+                    task.run();
+                }
+                None => {
+                    self.wakeup.wait_for(&mut idle, IDLE_TIMEOUT);
+                    self.sleepers.fetch_sub(1, Ordering::SeqCst);
+                }
+            }
+        }
+        debug!("Scheduler worker {index} stopped");
+    }
+
+    fn next_task(&self, index: usize, shared_first: bool) -> Option<Task> {
+        if shared_first {
+            if let Some(task) = self.shared.lock().pop() {
+                return Some(task);
+            }
+        }
+        if let Some(task) = self.local[index].lock().pop_front() {
+            return Some(task);
+        }
+        if let Some(task) = self.shared.lock().pop() {
+            return Some(task);
+        }
+        self.steal(index)
+    }
+
+    /// Takes half of the local queue of the first other worker that has
+    /// queued tasks
+    fn steal(&self, index: usize) -> Option<Task> {
+        let num_threads = self.local.len();
+        for offset in 1..num_threads {
+            let mut stolen = {
+                let mut victim = self.local[(index + offset) % num_threads].lock();
+                let keep = victim.len() / 2;
+                victim.split_off(keep)
+            };
+            if let Some(task) = stolen.pop_front() {
+                self.local[index].lock().extend(stolen);
+                return Some(task);
+            }
+        }
+        None
+    }
+}
+
+/// A queue in which each query, identified by a key, takes a turn: the
+/// items of a query are returned in the order they were pushed, and after
+/// each item the query moves to the back of the queue
+#[derive(Debug)]
+pub(crate) struct FairQueue<T> {
+    queries: VecDeque<(usize, VecDeque<T>)>,
+}
+
+impl<T> Default for FairQueue<T> {
+    fn default() -> Self {
+        Self {
+            queries: VecDeque::new(),
+        }
+    }
+}
+
+impl<T> FairQueue<T> {
+    pub(crate) fn push(&mut self, key: usize, item: T) {
+        match self.queries.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, items)) => items.push_back(item),
+            None => self.queries.push_back((key, VecDeque::from([item]))),
+        }
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<T> {
+        let (key, mut items) = self.queries.pop_front()?;
+        let item = items.pop_front();
+        if !items.is_empty() {
+            self.queries.push_back((key, items));
+        }
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fair_queue() {
+        let mut queue = FairQueue::default();
+        for item in 0..4 {
+            queue.push(1, item);
+        }
+        queue.push(2, 10);
+        queue.push(2, 11);
+        queue.push(3, 20);
+
+        let mut popped = vec![];
+        while let Some(item) = queue.pop() {
+            popped.push(item);
+            if item == 1 {
+                // a query that was drained rejoins at the back
+                queue.push(3, 21);
+            }
+        }
+        assert_eq!(popped, vec![0, 10, 20, 1, 11, 2, 21, 3]);
+    }
+}
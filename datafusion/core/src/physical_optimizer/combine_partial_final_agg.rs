@@ -257,6 +257,9 @@ mod tests {
                 limit: None,
                 table_partition_cols: vec![],
                 output_ordering: vec![],
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
             },
             None,
             None,
@@ -1659,6 +1659,9 @@ pub(crate) mod tests {
                 limit: None,
                 table_partition_cols: vec![],
                 output_ordering,
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
             },
             None,
             None,
@@ -1686,6 +1689,9 @@ pub(crate) mod tests {
                 limit: None,
                 table_partition_cols: vec![],
                 output_ordering,
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
             },
             None,
             None,
@@ -1707,6 +1713,9 @@ pub(crate) mod tests {
                 limit: None,
                 table_partition_cols: vec![],
                 output_ordering,
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
             },
             false,
             b',',
@@ -1737,6 +1746,9 @@ pub(crate) mod tests {
                 limit: None,
                 table_partition_cols: vec![],
                 output_ordering,
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
             },
             false,
             b',',
@@ -3837,6 +3849,9 @@ pub(crate) mod tests {
                         limit: None,
                         table_partition_cols: vec![],
                         output_ordering: vec![],
+                        schema_adapter_factory: None,
+                        metadata_cols: vec![],
+                        projection_paths: vec![],
                     },
                     false,
                     b',',
@@ -48,7 +48,9 @@ use crate::physical_optimizer::utils::{
     is_sort_preserving_merge, is_union, is_window,
 };
 use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
 use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use crate::physical_plan::filter::FilterExec;
 use crate::physical_plan::sorts::sort::SortExec;
 use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
 use crate::physical_plan::windows::{
@@ -56,6 +58,7 @@ use crate::physical_plan::windows::{
 };
 use crate::physical_plan::{
     with_new_children_if_necessary, Distribution, ExecutionPlan, InputOrderMode,
+    Partitioning,
 };
 
 use datafusion_common::tree_node::{Transformed, TreeNode};
@@ -291,6 +294,13 @@ impl PhysicalOptimizerRule for EnforceSorting {
         let mut sort_pushdown = SortPushDown::new(updated_plan.plan);
         sort_pushdown.assign_initial_requirements();
         let adjusted = sort_pushdown.transform_down(&pushdown_sorts)?;
+
+        if config.optimizer.repartition_sorts && config.optimizer.range_partition_sorts {
+            let target_partitions = config.execution.target_partitions;
+            return adjusted
+                .plan
+                .transform_up(&|plan| range_partition_sorts(plan, target_partitions));
+        }
         Ok(adjusted.plan)
     }
 
@@ -371,6 +381,85 @@ fn parallelize_sorts(
     }))
 }
 
+/// This function turns plans of the form
+/// ```text
+///      "SortPreservingMergeExec: \[a@0 ASC\]",
+///      "  SortExec: expr=\[a@0 ASC\]",
+///      "    RepartitionExec: partitioning=RoundRobinBatch(8), input_partitions=1",
+/// ```
+/// to
+/// ```text
+///      "SortPreservingMergeExec: \[a@0 ASC\]",
+///      "  SortExec: expr=\[a@0 ASC\]",
+///      "    RepartitionExec: partitioning=Range(\[a@0 ASC\], 8), input_partitions=1",
+/// ```
+/// so that the sorted partitions are ranges of the output, which the
+/// [`SortPreservingMergeExec`] concatenates instead of merging them. The
+/// round robin repartitioning may also be below filters, other plans are
+/// left as they are, as range partitioning them would add another shuffle.
+fn range_partition_sorts(
+    plan: Arc<dyn ExecutionPlan>,
+    target_partitions: usize,
+) -> Result<Transformed<Arc<dyn ExecutionPlan>>> {
+    let Some(spm) = plan.as_any().downcast_ref::<SortPreservingMergeExec>() else {
+        return Ok(Transformed::No(plan));
+    };
+    let Some(sort) = spm.input().as_any().downcast_ref::<SortExec>() else {
+        return Ok(Transformed::No(plan));
+    };
+    if spm.fetch().is_some()
+        || sort.fetch().is_some()
+        || !sort.preserve_partitioning()
+        || sort.expr() != spm.expr()
+        || sort.output_partitioning().partition_count() <= 1
+        || matches!(sort.output_partitioning(), Partitioning::Range(..))
+    {
+        return Ok(Transformed::No(plan));
+    }
+
+    // Range partitioning replaces a repartitioning below the sort that does
+    // not place rows by value, so that it does not add another shuffle
+    let sort_exprs = spm.expr().to_vec();
+    let partitioning = Partitioning::Range(sort_exprs.clone(), target_partitions);
+    let Some(input) = replace_unordered_repartition(sort.input(), &partitioning)? else {
+        return Ok(Transformed::No(plan));
+    };
+    let sort = SortExec::new(sort_exprs.clone(), input).with_preserve_partitioning(true);
+    let spm = SortPreservingMergeExec::new(sort_exprs, Arc::new(sort));
+    Ok(Transformed::Yes(Arc::new(spm)))
+}
+
+/// Replaces the round robin or unknown [`RepartitionExec`] that `plan` reads,
+/// possibly through filters and batch coalescing which keep the schema, with
+/// a repartitioning by `partitioning`. Returns `None` if there is no such
+/// repartitioning.
+fn replace_unordered_repartition(
+    plan: &Arc<dyn ExecutionPlan>,
+    partitioning: &Partitioning,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    if let Some(repartition) = plan.as_any().downcast_ref::<RepartitionExec>() {
+        if repartition.preserve_order()
+            || !matches!(
+                repartition.partitioning(),
+                Partitioning::RoundRobinBatch(_) | Partitioning::UnknownPartitioning(_)
+            )
+        {
+            return Ok(None);
+        }
+        let repartition =
+            RepartitionExec::try_new(repartition.input().clone(), partitioning.clone())?;
+        return Ok(Some(Arc::new(repartition)));
+    }
+    if !plan.as_any().is::<FilterExec>() && !plan.as_any().is::<CoalesceBatchesExec>() {
+        return Ok(None);
+    }
+    let Some(child) = replace_unordered_repartition(&plan.children()[0], partitioning)?
+    else {
+        return Ok(None);
+    };
+    plan.clone().with_new_children(vec![child]).map(Some)
+}
+
 /// This function enforces sorting requirements and makes optimizations without
 /// violating these requirements whenever possible.
 fn ensure_sorting(
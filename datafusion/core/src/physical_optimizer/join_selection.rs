@@ -367,8 +367,11 @@ fn statistical_join_selection_subrule(
     plan: Arc<dyn ExecutionPlan>,
     collect_left_threshold: usize,
 ) -> Result<Transformed<Arc<dyn ExecutionPlan>>> {
-    let transformed = if let Some(hash_join) =
-        plan.as_any().downcast_ref::<HashJoinExec>()
+    // null aware joins rely on their sides and partition mode, keep them as is
+    let transformed = if let Some(hash_join) = plan
+        .as_any()
+        .downcast_ref::<HashJoinExec>()
+        .filter(|hash_join| !hash_join.null_aware())
     {
         match hash_join.partition_mode() {
             PartitionMode::Auto => {
@@ -437,7 +440,8 @@ fn hash_join_convert_symmetric_subrule(
         let ub_flags = input.children_unbounded();
         let (left_unbounded, right_unbounded) = (ub_flags[0], ub_flags[1]);
         input.unbounded = left_unbounded || right_unbounded;
-        let result = if left_unbounded && right_unbounded {
+        // SymmetricHashJoinExec does not support null aware joins
+        let result = if left_unbounded && right_unbounded && !hash_join.null_aware() {
             let mode = if config_options.optimizer.repartition_joins {
                 StreamJoinPartitionMode::Partitioned
             } else {
@@ -36,6 +36,8 @@ pub mod pruning;
 pub mod replace_with_order_preserving_variants;
 mod sort_pushdown;
 pub mod topk_aggregation;
+#[cfg(feature = "parquet")]
+pub mod topk_dynamic_filter;
 mod utils;
 
 #[cfg(test)]
@@ -31,6 +31,8 @@ use crate::physical_optimizer::limited_distinct_aggregation::LimitedDistinctAggr
 use crate::physical_optimizer::output_requirements::OutputRequirements;
 use crate::physical_optimizer::pipeline_checker::PipelineChecker;
 use crate::physical_optimizer::topk_aggregation::TopKAggregation;
+#[cfg(feature = "parquet")]
+use crate::physical_optimizer::topk_dynamic_filter::TopKDynamicFilterPushdown;
 use crate::{error::Result, physical_plan::ExecutionPlan};
 
 /// `PhysicalOptimizerRule` transforms one ['ExecutionPlan'] into another which
@@ -120,6 +122,11 @@ impl PhysicalOptimizer {
             // are not present, the load of executors such as join or union will be
             // reduced by narrowing their input tables.
             Arc::new(ProjectionPushdown::new()),
+            // The TopKDynamicFilterPushdown rule passes the threshold of a sort with a
+            // limit to the parquet scans below it. It should run last, as the rules
+            // above may replace the scans, which would lose the filters.
+            #[cfg(feature = "parquet")]
+            Arc::new(TopKDynamicFilterPushdown::new()),
         ];
 
         Self::with_rules(rules)
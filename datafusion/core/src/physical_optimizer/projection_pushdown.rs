@@ -20,12 +20,14 @@
 //! projections one by one if the operator below is amenable to this. If a
 //! projection reaches a source, it can even dissappear from the plan entirely.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use super::output_requirements::OutputRequirementExec;
 use super::PhysicalOptimizerRule;
-use crate::datasource::physical_plan::CsvExec;
+#[cfg(feature = "parquet")]
+use crate::datasource::physical_plan::ParquetExec;
+use crate::datasource::physical_plan::{CsvExec, FileScanConfig, NdJsonExec};
 use crate::error::Result;
 use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
 use crate::physical_plan::filter::FilterExec;
@@ -41,11 +43,13 @@ use crate::physical_plan::sorts::sort::SortExec;
 use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
 use crate::physical_plan::{Distribution, ExecutionPlan};
 
-use arrow_schema::SchemaRef;
+use arrow_schema::{DataType, Schema, SchemaRef};
 use datafusion_common::config::ConfigOptions;
 use datafusion_common::tree_node::{Transformed, TreeNode, VisitRecursion};
-use datafusion_common::JoinSide;
-use datafusion_physical_expr::expressions::{Column, Literal};
+use datafusion_common::{JoinSide, ScalarValue};
+use datafusion_physical_expr::expressions::{
+    Column, GetFieldAccessExpr, GetIndexedFieldExpr, Literal,
+};
 use datafusion_physical_expr::{
     Partitioning, PhysicalExpr, PhysicalSortExpr, PhysicalSortRequirement,
 };
@@ -99,6 +103,11 @@ pub fn remove_unnecessary_projections(
         if is_projection_removable(projection) {
             return Ok(Transformed::Yes(projection.input().clone()));
         }
+        // If the projection accesses struct fields, check if the scan below
+        // it can read only these fields:
+        if let Some(input) = try_pushdown_nested_fields(projection, projection.input())? {
+            return Ok(Transformed::Yes(make_with_child(projection, &input)?));
+        }
         // If it does, check if we can push it under its child(ren):
         let input = projection.input().as_any();
         if let Some(csv) = input.downcast_ref::<CsvExec>() {
@@ -167,17 +176,149 @@ fn try_swapping_with_csv(
             new_projections_for_columns(projection, &file_scan.projection);
         file_scan.projection = Some(new_projections);
 
-        Arc::new(CsvExec::new(
-            file_scan,
-            csv.has_header(),
-            csv.delimiter(),
-            csv.quote(),
-            csv.escape(),
-            csv.file_compression_type,
-        )) as _
+        Arc::new(
+            CsvExec::new(
+                file_scan,
+                csv.has_header(),
+                csv.delimiter(),
+                csv.quote(),
+                csv.escape(),
+                csv.file_compression_type,
+            )
+            .with_bad_rows(csv.bad_rows().clone())
+            .with_newlines_in_values(csv.newlines_in_values()),
+        ) as _
     })
 }
 
+/// Tries to restrict the struct columns of the file scan `input` of
+/// `projection` to the nested fields that `projection` accesses, looking
+/// through round robin [`RepartitionExec`]s, which do not use the values of
+/// the columns. If possible, returns `input` reading only these fields.
+fn try_pushdown_nested_fields(
+    projection: &ProjectionExec,
+    input: &Arc<dyn ExecutionPlan>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let any = input.as_any();
+    if let Some(repartition) = any.downcast_ref::<RepartitionExec>() {
+        if !matches!(repartition.partitioning(), Partitioning::RoundRobinBatch(_)) {
+            return Ok(None);
+        }
+        return try_pushdown_nested_fields(projection, repartition.input())?
+            .map(|scan| input.clone().with_new_children(vec![scan]))
+            .transpose();
+    }
+    #[cfg(feature = "parquet")]
+    if let Some(parquet) = any.downcast_ref::<ParquetExec>() {
+        return Ok(nested_field_paths(projection, parquet.base_config())?
+            .map(|paths| Arc::new(parquet.clone().with_projection_paths(paths)) as _));
+    }
+    if let Some(json) = any.downcast_ref::<NdJsonExec>() {
+        return Ok(nested_field_paths(projection, json.base_config())?
+            .map(|paths| Arc::new(json.clone().with_projection_paths(paths)) as _));
+    }
+    Ok(None)
+}
+
+/// Returns the paths to the nested fields that `projection` accesses of the
+/// struct columns of the file scan whose configuration is `config`, if it
+/// does not read these columns whole.
+fn nested_field_paths(
+    projection: &ProjectionExec,
+    config: &FileScanConfig,
+) -> Result<Option<Vec<Vec<String>>>> {
+    // a custom schema adapter may read the struct columns from file columns
+    // with other names
+    if !config.projection_paths.is_empty() || config.schema_adapter_factory.is_some() {
+        return Ok(None);
+    }
+
+    let input_schema = projection.input().schema();
+    let mut field_paths = vec![];
+    let mut whole_columns = HashSet::new();
+    for (expr, _) in projection.expr() {
+        collect_field_paths(expr, &input_schema, &mut field_paths, &mut whole_columns)?;
+    }
+
+    let file_schema = &config.file_schema;
+    let mut paths: Vec<Vec<String>> = field_paths
+        .into_iter()
+        .filter(|(column, _)| !whole_columns.contains(column))
+        .filter_map(|(column, mut path)| {
+            let idx = config.projection.as_ref().map_or(column, |p| p[column]);
+            (idx < file_schema.fields().len()).then(|| {
+                path.insert(0, file_schema.field(idx).name().clone());
+                path
+            })
+        })
+        .collect();
+    paths.sort();
+    paths.dedup();
+    // the fields a path leads to are read whole, including the fields nested
+    // in them that other paths lead to
+    let paths: Vec<_> = paths
+        .iter()
+        .filter(|path| {
+            !paths
+                .iter()
+                .any(|other| other.len() < path.len() && path.starts_with(other))
+        })
+        .cloned()
+        .collect();
+    Ok((!paths.is_empty()).then_some(paths))
+}
+
+/// Collects the paths to the struct fields that `expr` accesses, from the
+/// columns of `schema` they are nested in, into `field_paths`, and the
+/// columns that `expr` uses otherwise into `whole_columns`.
+fn collect_field_paths(
+    expr: &Arc<dyn PhysicalExpr>,
+    schema: &Schema,
+    field_paths: &mut Vec<(usize, Vec<String>)>,
+    whole_columns: &mut HashSet<usize>,
+) -> Result<()> {
+    if let Some(field_path) = struct_field_path(expr, schema)? {
+        field_paths.push(field_path);
+    } else if let Some(column) = expr.as_any().downcast_ref::<Column>() {
+        whole_columns.insert(column.index());
+    } else {
+        for child in expr.children() {
+            collect_field_paths(&child, schema, field_paths, whole_columns)?;
+        }
+    }
+    Ok(())
+}
+
+/// If `expr` accesses a field of a struct nested in a column of `schema`,
+/// returns the index of the column and the names of the fields on the path
+/// from the column to the accessed field
+fn struct_field_path(
+    expr: &Arc<dyn PhysicalExpr>,
+    schema: &Schema,
+) -> Result<Option<(usize, Vec<String>)>> {
+    let Some(get_field) = expr.as_any().downcast_ref::<GetIndexedFieldExpr>() else {
+        return Ok(None);
+    };
+    let GetFieldAccessExpr::NamedStructField {
+        name: ScalarValue::Utf8(Some(name)),
+    } = get_field.field()
+    else {
+        return Ok(None);
+    };
+    let arg = get_field.arg();
+    if !matches!(arg.data_type(schema)?, DataType::Struct(_)) {
+        return Ok(None);
+    }
+    let path = match arg.as_any().downcast_ref::<Column>() {
+        Some(column) => Some((column.index(), vec![name.clone()])),
+        None => struct_field_path(arg, schema)?.map(|(column, mut path)| {
+            path.push(name.clone());
+            (column, path)
+        }),
+    };
+    Ok(path)
+}
+
 /// Tries to embed `projection` to its input (`memory`). If possible, returns
 /// [`MemoryExec`] as the top plan. Otherwise, returns `None`.
 fn try_swapping_with_memory(
@@ -420,6 +561,21 @@ fn try_swapping_with_repartition(
             }
             Partitioning::Hash(new_partitions, *size)
         }
+        Partitioning::Range(sort_exprs, size) => {
+            let mut new_sort_exprs = vec![];
+            for sort_expr in sort_exprs {
+                let Some(new_expr) =
+                    update_expr(&sort_expr.expr, projection.expr(), false)?
+                else {
+                    return Ok(None);
+                };
+                new_sort_exprs.push(PhysicalSortExpr {
+                    expr: new_expr,
+                    options: sort_expr.options,
+                });
+            }
+            Partitioning::Range(new_sort_exprs, *size)
+        }
         others => others.clone(),
     };
 
@@ -571,15 +727,18 @@ fn try_pushdown_through_hash_join(
         hash_join.right(),
     )?;
 
-    Ok(Some(Arc::new(HashJoinExec::try_new(
-        Arc::new(new_left),
-        Arc::new(new_right),
-        new_on,
-        new_filter,
-        hash_join.join_type(),
-        *hash_join.partition_mode(),
-        hash_join.null_equals_null,
-    )?)))
+    Ok(Some(Arc::new(
+        HashJoinExec::try_new(
+            Arc::new(new_left),
+            Arc::new(new_right),
+            new_on,
+            new_filter,
+            hash_join.join_type(),
+            *hash_join.partition_mode(),
+            hash_join.null_equals_null,
+        )?
+        .with_null_aware(hash_join.null_aware())?,
+    )))
 }
 
 /// Tries to swap the projection with its input [`CrossJoinExec`]. If it can be done,
@@ -1166,6 +1325,8 @@ mod tests {
 
     use crate::datasource::file_format::file_compression_type::FileCompressionType;
     use crate::datasource::listing::PartitionedFile;
+    #[cfg(feature = "parquet")]
+    use crate::datasource::physical_plan::ParquetExec;
     use crate::datasource::physical_plan::{CsvExec, FileScanConfig};
     use crate::physical_optimizer::output_requirements::OutputRequirementExec;
     use crate::physical_optimizer::projection_pushdown::{
@@ -1190,7 +1351,8 @@ mod tests {
     use datafusion_execution::{SendableRecordBatchStream, TaskContext};
     use datafusion_expr::{ColumnarValue, Operator};
     use datafusion_physical_expr::expressions::{
-        BinaryExpr, CaseExpr, CastExpr, Column, Literal, NegativeExpr,
+        BinaryExpr, CaseExpr, CastExpr, Column, GetIndexedFieldExpr, Literal,
+        NegativeExpr,
     };
     use datafusion_physical_expr::{
         Distribution, Partitioning, PhysicalExpr, PhysicalSortExpr,
@@ -1545,6 +1707,9 @@ mod tests {
                 limit: None,
                 table_partition_cols: vec![],
                 output_ordering: vec![vec![]],
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
             },
             false,
             0,
@@ -1571,6 +1736,9 @@ mod tests {
                 limit: None,
                 table_partition_cols: vec![],
                 output_ordering: vec![vec![]],
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
             },
             false,
             0,
@@ -1620,6 +1788,152 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "parquet")]
+    fn test_nested_fields_into_parquet() -> Result<()> {
+        let t = Field::new_struct(
+            "t",
+            vec![
+                Field::new("u", DataType::Int32, true),
+                Field::new("v", DataType::Int32, true),
+            ],
+            true,
+        );
+        let x = Field::new("x", DataType::Int32, true);
+        let s = Field::new_struct("s", vec![x.clone(), t], true);
+        let w = Field::new_struct("w", vec![x], true);
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            s,
+            w,
+        ]));
+        let parquet = Arc::new(ParquetExec::new(
+            FileScanConfig {
+                object_store_url: ObjectStoreUrl::parse("test:///").unwrap(),
+                file_schema: schema.clone(),
+                file_groups: vec![vec![PartitionedFile::new("x".to_string(), 100)]],
+                statistics: Statistics::new_unknown(&schema),
+                projection: Some(vec![1, 0, 2]),
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: vec![],
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
+            },
+            None,
+            None,
+        ));
+        let s_t = Arc::new(GetIndexedFieldExpr::new_field(
+            Arc::new(Column::new("s", 0)),
+            "t",
+        ));
+        let projection: Arc<dyn ExecutionPlan> = Arc::new(ProjectionExec::try_new(
+            vec![
+                (
+                    Arc::new(GetIndexedFieldExpr::new_field(
+                        Arc::new(Column::new("s", 0)),
+                        "x",
+                    )),
+                    "sx".to_string(),
+                ),
+                (
+                    Arc::new(GetIndexedFieldExpr::new_field(s_t, "u")),
+                    "su".to_string(),
+                ),
+                (Arc::new(Column::new("a", 1)), "a".to_string()),
+                // `w` is used whole, so all its fields are read
+                (Arc::new(Column::new("w", 2)), "w".to_string()),
+                (
+                    Arc::new(GetIndexedFieldExpr::new_field(
+                        Arc::new(Column::new("w", 2)),
+                        "x",
+                    )),
+                    "wx".to_string(),
+                ),
+            ],
+            parquet,
+        )?);
+        let initial = get_plan_string(&projection);
+        assert_eq!(
+            initial[1],
+            "  ParquetExec: file_groups={1 group: [[x]]}, projection=[s, a, w]"
+        );
+
+        let after_optimize =
+            ProjectionPushdown::new().optimize(projection, &ConfigOptions::new())?;
+
+        let after_optimize = get_plan_string(&after_optimize);
+        assert_eq!(after_optimize[0], initial[0]);
+        assert_eq!(
+            after_optimize[1],
+            "  ParquetExec: file_groups={1 group: [[x]]}, projection=[s, a, w], \
+            projection_paths=[s.t.u, s.x]"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "parquet")]
+    fn test_nested_fields_through_round_robin_repartition() -> Result<()> {
+        let s = Field::new_struct(
+            "s",
+            vec![
+                Field::new("x", DataType::Int32, true),
+                Field::new("y", DataType::Int32, true),
+            ],
+            true,
+        );
+        let schema = Arc::new(Schema::new(vec![s]));
+        let parquet = Arc::new(ParquetExec::new(
+            FileScanConfig {
+                object_store_url: ObjectStoreUrl::parse("test:///").unwrap(),
+                file_schema: schema.clone(),
+                file_groups: vec![vec![PartitionedFile::new("x".to_string(), 100)]],
+                statistics: Statistics::new_unknown(&schema),
+                projection: None,
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: vec![],
+                schema_adapter_factory: None,
+                metadata_cols: vec![],
+                projection_paths: vec![],
+            },
+            None,
+            None,
+        ));
+        let repartition = Arc::new(RepartitionExec::try_new(
+            parquet,
+            Partitioning::RoundRobinBatch(4),
+        )?);
+        let projection: Arc<dyn ExecutionPlan> = Arc::new(ProjectionExec::try_new(
+            vec![(
+                Arc::new(GetIndexedFieldExpr::new_field(
+                    Arc::new(Column::new("s", 0)),
+                    "x",
+                )),
+                "sx".to_string(),
+            )],
+            repartition,
+        )?);
+        let initial = get_plan_string(&projection);
+
+        let after_optimize =
+            ProjectionPushdown::new().optimize(projection, &ConfigOptions::new())?;
+
+        let after_optimize = get_plan_string(&after_optimize);
+        assert_eq!(after_optimize[0], initial[0]);
+        assert_eq!(after_optimize[1], initial[1]);
+        assert_eq!(
+            after_optimize[2],
+            "    ParquetExec: file_groups={1 group: [[x]]}, projection=[s], \
+            projection_paths=[s.x]"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_memory_after_projection() -> Result<()> {
         let memory = create_projecting_memory_exec();
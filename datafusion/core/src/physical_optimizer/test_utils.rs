@@ -278,6 +278,9 @@ pub fn parquet_exec(schema: &SchemaRef) -> Arc<ParquetExec> {
             limit: None,
             table_partition_cols: vec![],
             output_ordering: vec![],
+            schema_adapter_factory: None,
+            metadata_cols: vec![],
+            projection_paths: vec![],
         },
         None,
         None,
@@ -301,6 +304,9 @@ pub fn parquet_exec_sorted(
             limit: None,
             table_partition_cols: vec![],
             output_ordering: vec![sort_exprs],
+            schema_adapter_factory: None,
+            metadata_cols: vec![],
+            projection_paths: vec![],
         },
         None,
         None,
@@ -0,0 +1,353 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An optimizer rule that pushes the threshold and sort keys of a TopK sort
+//! down to the parquet scans below it
+
+use std::sync::Arc;
+
+use crate::datasource::physical_plan::{ParquetExec, ParquetTopK};
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use crate::physical_plan::dynamic_filter::DynamicFilter;
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::sorts::sort::SortExec;
+use crate::physical_plan::ExecutionPlan;
+
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{plan_err, DataFusionError, Result};
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_expr::{PhysicalExpr, PhysicalSortExpr};
+
+/// An optimizer rule that passes the [`DynamicFilter`] of a `SortExec` with
+/// a fetch to the [`ParquetExec`]s below it, so that the scans can skip row
+/// groups and pages that cannot contain any of the top `fetch` rows.
+///
+/// If the sort keys are columns, the sort keys, fetch and the predicates of
+/// the filters in between are also passed as a [`ParquetTopK`], which the
+/// scans use to read only the rows that can be among the top rows when late
+/// materialization is enabled.
+///
+/// These are only pushed through operators that do not change the values of
+/// the rows, such as filters, repartitions and projections of columns.
+pub struct TopKDynamicFilterPushdown {}
+
+impl TopKDynamicFilterPushdown {
+    /// Create a new `TopKDynamicFilterPushdown`
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for TopKDynamicFilterPushdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PhysicalOptimizerRule for TopKDynamicFilterPushdown {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !config.optimizer.enable_topk_dynamic_filter_pushdown {
+            return Ok(plan);
+        }
+        plan.transform_down(&|plan| {
+            let Some(sort) = plan.as_any().downcast_ref::<SortExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+            let Some(filter) = sort.dynamic_filter() else {
+                return Ok(Transformed::No(plan));
+            };
+            let topk = sort
+                .expr()
+                .iter()
+                .all(|e| e.expr.as_any().is::<Column>())
+                .then(|| ParquetTopK {
+                    expr: sort.expr().to_vec(),
+                    fetch: sort.fetch().unwrap_or_default(),
+                    filters: vec![],
+                });
+            match push_down(sort.input(), Some(filter.clone()), topk)? {
+                Some(input) => Ok(Transformed::Yes(plan.with_new_children(vec![input])?)),
+                None => Ok(Transformed::No(plan)),
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "TopKDynamicFilterPushdown"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Returns `plan` with `filter` and `topk` added to the [`ParquetExec`]
+/// below it, if any, or `None` if neither can be pushed down
+fn push_down(
+    plan: &Arc<dyn ExecutionPlan>,
+    filter: Option<DynamicFilter>,
+    topk: Option<ParquetTopK>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    if filter.is_none() && topk.is_none() {
+        return Ok(None);
+    }
+
+    let any = plan.as_any();
+    if let Some(parquet) = any.downcast_ref::<ParquetExec>() {
+        let mut parquet = parquet.clone();
+        if let Some(filter) = filter {
+            parquet = parquet.with_dynamic_filter(filter)?;
+        }
+        if let Some(topk) = topk {
+            parquet = parquet.with_topk(topk);
+        }
+        return Ok(Some(Arc::new(parquet)));
+    }
+
+    let (filter, topk) = if let Some(projection) = any.downcast_ref::<ProjectionExec>() {
+        // refer to the input columns the projected columns are
+        let input_column = |c: &Column| {
+            let (expr, _) = projection.expr().get(c.index())?;
+            expr.as_any().downcast_ref::<Column>().cloned()
+        };
+        let filter = match filter {
+            Some(filter) => {
+                let columns = filter
+                    .columns()
+                    .iter()
+                    .map(input_column)
+                    .collect::<Option<Vec<_>>>();
+                match columns {
+                    Some(columns) => Some(filter.with_columns(columns)?),
+                    None => None,
+                }
+            }
+            None => None,
+        };
+        let topk = topk.and_then(|topk| {
+            let rewrite = |expr: &Arc<dyn PhysicalExpr>| {
+                expr.clone()
+                    .transform_up(&|expr| {
+                        let Some(column) = expr.as_any().downcast_ref::<Column>() else {
+                            return Ok(Transformed::No(expr));
+                        };
+                        match input_column(column) {
+                            Some(column) => Ok(Transformed::Yes(Arc::new(column))),
+                            None => plan_err!("{column} is not a projected column"),
+                        }
+                    })
+                    .ok()
+            };
+            Some(ParquetTopK {
+                expr: topk
+                    .expr
+                    .iter()
+                    .map(|e| {
+                        Some(PhysicalSortExpr {
+                            expr: rewrite(&e.expr)?,
+                            options: e.options,
+                        })
+                    })
+                    .collect::<Option<_>>()?,
+                fetch: topk.fetch,
+                filters: topk.filters.iter().map(rewrite).collect::<Option<_>>()?,
+            })
+        });
+        (filter, topk)
+    } else if let Some(filter_exec) = any.downcast_ref::<FilterExec>() {
+        // the TopK only sees the rows that pass the filter
+        let topk = topk.map(|mut topk| {
+            topk.filters.push(filter_exec.predicate().clone());
+            topk
+        });
+        (filter, topk)
+    } else if any.is::<CoalesceBatchesExec>()
+        || any.is::<CoalescePartitionsExec>()
+        || any.is::<RepartitionExec>()
+    {
+        (filter, topk)
+    } else {
+        return Ok(None);
+    };
+
+    let children = plan.children();
+    let [input] = children.as_slice() else {
+        return Ok(None);
+    };
+    match push_down(input, filter, topk)? {
+        Some(input) => Ok(Some(plan.clone().with_new_children(vec![input])?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_optimizer::test_utils::parquet_exec;
+    use crate::physical_plan::expressions::{binary, col, lit};
+    use crate::physical_plan::get_plan_string;
+    use crate::physical_plan::memory::MemoryExec;
+
+    use arrow::compute::SortOptions;
+    use arrow_schema::{DataType, Field, Schema, SchemaRef};
+    use datafusion_expr::Operator;
+    use datafusion_physical_expr::PhysicalSortExpr;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]))
+    }
+
+    fn top_k(column: &str, input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        let sort_expr = PhysicalSortExpr {
+            expr: col(column, &input.schema()).unwrap(),
+            options: SortOptions::default(),
+        };
+        Arc::new(SortExec::new(vec![sort_expr], input).with_fetch(Some(5)))
+    }
+
+    /// Returns the columns of the dynamic filters of the parquet scan in `plan`
+    fn pushed_down_columns(plan: &Arc<dyn ExecutionPlan>) -> Vec<Vec<Column>> {
+        if let Some(parquet) = plan.as_any().downcast_ref::<ParquetExec>() {
+            return parquet
+                .dynamic_filters()
+                .iter()
+                .map(|f| f.columns().to_vec())
+                .collect();
+        }
+        plan.children()
+            .iter()
+            .flat_map(pushed_down_columns)
+            .collect()
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        TopKDynamicFilterPushdown::new()
+            .optimize(plan, &ConfigOptions::new())
+            .unwrap()
+    }
+
+    #[test]
+    fn push_down_through_projection() -> Result<()> {
+        let scan = parquet_exec(&schema());
+        let input_schema = scan.schema();
+        // SELECT b AS x, a FROM t ORDER BY x LIMIT 5
+        let projection = Arc::new(ProjectionExec::try_new(
+            vec![
+                (col("b", &input_schema)?, "x".to_string()),
+                (col("a", &input_schema)?, "a".to_string()),
+            ],
+            Arc::new(CoalesceBatchesExec::new(scan, 8192)),
+        )?);
+        let plan = optimize(top_k("x", projection));
+
+        assert_eq!(
+            get_plan_string(&plan),
+            vec![
+                "SortExec: TopK(fetch=5), expr=[x@0 ASC]",
+                "  ProjectionExec: expr=[b@1 as x, a@0 as a]",
+                "    CoalesceBatchesExec: target_batch_size=8192",
+                "      ParquetExec: file_groups={1 group: [[x]]}, projection=[a, b]",
+            ]
+        );
+        assert_eq!(pushed_down_columns(&plan), vec![vec![Column::new("b", 1)]]);
+
+        // the sort publishes to the filter of the scan
+        let sort = plan.as_any().downcast_ref::<SortExec>().unwrap();
+        assert_eq!(
+            sort.dynamic_filter().unwrap().columns(),
+            &[Column::new("x", 0)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn push_down_topk() -> Result<()> {
+        let scan = parquet_exec(&schema());
+        let input_schema = scan.schema();
+        // SELECT b AS x, a FROM t WHERE a > 1 ORDER BY x LIMIT 5
+        let filter = Arc::new(FilterExec::try_new(
+            binary(
+                col("a", &input_schema)?,
+                Operator::Gt,
+                lit(1),
+                &input_schema,
+            )?,
+            scan,
+        )?);
+        let projection = Arc::new(ProjectionExec::try_new(
+            vec![
+                (col("b", &input_schema)?, "x".to_string()),
+                (col("a", &input_schema)?, "a".to_string()),
+            ],
+            filter,
+        )?);
+        let plan = optimize(top_k("x", projection));
+
+        let parquet = plan.children()[0].children()[0].children()[0].clone();
+        let parquet = parquet.as_any().downcast_ref::<ParquetExec>().unwrap();
+        let topk = parquet.topk().unwrap();
+        assert_eq!(topk.expr[0].expr.to_string(), "b@1");
+        assert_eq!(topk.fetch, 5);
+        assert_eq!(topk.filters[0].to_string(), "a@0 > 1");
+        Ok(())
+    }
+
+    #[test]
+    fn no_push_down() -> Result<()> {
+        // computed sort key
+        let scan = parquet_exec(&schema());
+        let input_schema = scan.schema();
+        let projection = Arc::new(ProjectionExec::try_new(
+            vec![(
+                binary(
+                    col("a", &input_schema)?,
+                    Operator::Plus,
+                    col("b", &input_schema)?,
+                    &input_schema,
+                )?,
+                "s".to_string(),
+            )],
+            scan,
+        )?);
+        let plan = optimize(top_k("s", projection));
+        assert!(pushed_down_columns(&plan).is_empty());
+        let parquet = plan.children()[0].children()[0].clone();
+        let parquet = parquet.as_any().downcast_ref::<ParquetExec>().unwrap();
+        assert!(parquet.topk().is_none());
+
+        // not a parquet scan
+        let memory = Arc::new(MemoryExec::try_new(&[vec![]], schema(), None)?);
+        let plan = top_k("a", memory);
+        assert_eq!(
+            get_plan_string(&optimize(plan.clone())),
+            get_plan_string(&plan)
+        );
+        Ok(())
+    }
+}
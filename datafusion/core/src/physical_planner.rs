@@ -25,6 +25,8 @@ use crate::datasource::file_format::arrow::ArrowFormat;
 use crate::datasource::file_format::avro::AvroFormat;
 use crate::datasource::file_format::csv::CsvFormat;
 use crate::datasource::file_format::json::JsonFormat;
+#[cfg(feature = "orc")]
+use crate::datasource::file_format::orc::OrcFormat;
 #[cfg(feature = "parquet")]
 use crate::datasource::file_format::parquet::ParquetFormat;
 use crate::datasource::file_format::FileFormat;
@@ -35,8 +37,8 @@ use crate::error::{DataFusionError, Result};
 use crate::execution::context::{ExecutionProps, SessionState};
 use crate::logical_expr::utils::generate_sort_key;
 use crate::logical_expr::{
-    Aggregate, EmptyRelation, Join, Projection, Sort, SubqueryAlias, TableScan, Unnest,
-    Window,
+    Aggregate, EmptyRelation, Filter, Join, JoinType, Projection, Sort, SubqueryAlias,
+    TableScan, Unnest, Window,
 };
 use crate::logical_expr::{
     CrossJoin, Expr, LogicalPlan, Partitioning as LogicalPartitioning, PlanType,
@@ -47,6 +49,7 @@ use crate::physical_expr::create_physical_expr;
 use crate::physical_optimizer::optimizer::PhysicalOptimizerRule;
 use crate::physical_plan::aggregates::{AggregateExec, AggregateMode, PhysicalGroupBy};
 use crate::physical_plan::analyze::AnalyzeExec;
+use crate::physical_plan::async_func::{AsyncFuncExec, AsyncFuncExpr};
 use crate::physical_plan::empty::EmptyExec;
 use crate::physical_plan::explain::ExplainExec;
 use crate::physical_plan::expressions::{Column, PhysicalSortExpr};
@@ -75,8 +78,10 @@ use arrow_array::builder::StringBuilder;
 use arrow_array::RecordBatch;
 use datafusion_common::display::ToStringifiedPlan;
 use datafusion_common::file_options::FileTypeWriterOptions;
+use datafusion_common::tree_node::{Transformed, TreeNode, VisitRecursion};
 use datafusion_common::{
-    exec_err, internal_err, not_impl_err, plan_err, DFSchema, FileType, ScalarValue,
+    exec_err, internal_err, not_impl_err, plan_err, DFField, DFSchema, FileType,
+    ScalarValue,
 };
 use datafusion_expr::dml::{CopyOptions, CopyTo};
 use datafusion_expr::expr::{
@@ -87,8 +92,8 @@ use datafusion_expr::expr::{
 use datafusion_expr::expr_rewriter::unnormalize_cols;
 use datafusion_expr::logical_plan::builder::wrap_projection_for_join_if_necessary;
 use datafusion_expr::{
-    DescribeTable, DmlStatement, ScalarFunctionDefinition, StringifiedPlan, WindowFrame,
-    WindowFrameBound, WriteOp,
+    DescribeTable, DmlStatement, ExprSchemable, ScalarFunctionDefinition,
+    StringifiedPlan, WindowFrame, WindowFrameBound, WriteOp,
 };
 use datafusion_physical_expr::expressions::Literal;
 use datafusion_physical_plan::placeholder_row::PlaceholderRowExec;
@@ -100,6 +105,91 @@ use futures::{FutureExt, StreamExt, TryStreamExt};
 use itertools::{multiunzip, Itertools};
 use log::{debug, trace};
 
+/// Returns true if `expr` is a call to an async scalar function
+fn is_async_call(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::ScalarFunction(expr::ScalarFunction {
+            func_def: ScalarFunctionDefinition::UDF(udf),
+            ..
+        }) if udf.as_async().is_some()
+    )
+}
+
+/// An [`AsyncFuncExec`], its schema, and the expressions with the calls to
+/// async scalar functions replaced by the columns of their results
+type AsyncFuncPlan = (Arc<dyn ExecutionPlan>, DFSchema, Vec<Expr>);
+
+/// Returns true if `expr` calls an async scalar function
+fn contains_async_call(expr: &Expr) -> Result<bool> {
+    let mut found = false;
+    expr.apply(&mut |e| {
+        found = is_async_call(e);
+        Ok(if found {
+            VisitRecursion::Stop
+        } else {
+            VisitRecursion::Continue
+        })
+    })?;
+    Ok(found)
+}
+
+/// Returns the distinct calls to async scalar functions in `exprs`, in the
+/// order they appear
+fn collect_async_calls(exprs: &[Expr]) -> Result<Vec<Expr>> {
+    let mut calls: Vec<Expr> = vec![];
+    for expr in exprs {
+        expr.apply(&mut |e| {
+            let Expr::ScalarFunction(call) = e else {
+                return Ok(VisitRecursion::Continue);
+            };
+            if !is_async_call(e) {
+                return Ok(VisitRecursion::Continue);
+            }
+            for arg in &call.args {
+                if contains_async_call(arg)? {
+                    return not_impl_err!(
+                        "Async functions in the arguments of async functions are not supported: {e}"
+                    );
+                }
+            }
+            if !calls.contains(e) {
+                calls.push(e.clone());
+            }
+            Ok(VisitRecursion::Skip)
+        })?;
+    }
+    Ok(calls)
+}
+
+/// The name of the column with the results of the `i`th call to an async
+/// scalar function
+fn async_call_name(i: usize) -> String {
+    format!("__async_fn_{i}")
+}
+
+/// Replaces the `calls` in `exprs` by the columns of their results, the
+/// first call being named after `first`
+fn replace_async_calls(
+    exprs: &[Expr],
+    calls: &[Expr],
+    first: usize,
+) -> Result<Vec<Expr>> {
+    exprs
+        .iter()
+        .map(|e| {
+            e.clone().transform_down(&|e| {
+                Ok(match calls.iter().position(|call| *call == e) {
+                    Some(i) => Transformed::Yes(Expr::Column(
+                        datafusion_common::Column::from_name(async_call_name(first + i)),
+                    )),
+                    None => Transformed::No(e),
+                })
+            })
+        })
+        .collect()
+}
+
 fn create_function_physical_name(
     fun: &str,
     distinct: bool,
@@ -605,6 +695,8 @@ impl DefaultPhysicalPlanner {
                         FileType::JSON => Arc::new(JsonFormat::default()),
                         FileType::AVRO => Arc::new(AvroFormat {} ),
                         FileType::ARROW => Arc::new(ArrowFormat {}),
+                        #[cfg(feature = "orc")]
+                        FileType::ORC => Arc::new(OrcFormat {}),
                     };
 
                     sink_format.create_writer_physical_plan(input_exec, session_state, config, None).await
@@ -846,12 +938,50 @@ impl DefaultPhysicalPlanner {
                     )?))
                 }
                 LogicalPlan::Projection(Projection { input, expr, .. }) => {
-                    let input_exec = self.create_initial_plan(input, session_state).await?;
                     let input_schema = input.as_ref().schema();
 
+                    // the results of the calls to async functions of a filter
+                    // below the projection are kept, so that the projection
+                    // reads them instead of calling the functions again
+                    let async_filter = match input.as_ref() {
+                        LogicalPlan::Filter(filter) => {
+                            self.plan_async_filter(filter, session_state).await?
+                        }
+                        _ => None,
+                    };
+                    let (input_exec, filter_schema, filter_exprs, filter_calls) =
+                        match async_filter {
+                            Some((exec, schema, calls)) => {
+                                let exprs = replace_async_calls(expr, &calls, 0)?;
+                                (exec, Some(schema), exprs, calls.len())
+                            }
+                            None => {
+                                let exec =
+                                    self.create_initial_plan(input, session_state).await?;
+                                (exec, None, expr.clone(), 0)
+                            }
+                        };
+                    let filter_schema =
+                        filter_schema.as_ref().unwrap_or(input_schema.as_ref());
+
+                    // calls to async functions are evaluated by an AsyncFuncExec
+                    // below the projection, which reads their results
+                    let async_funcs = self.plan_async_funcs(
+                        &filter_exprs,
+                        filter_calls,
+                        Arc::clone(&input_exec),
+                        filter_schema,
+                        session_state,
+                    )?;
+                    let (input_exec, planned_schema, planned_exprs) = match &async_funcs {
+                        Some((exec, schema, exprs)) => (Arc::clone(exec), schema, exprs),
+                        None => (input_exec, filter_schema, &filter_exprs),
+                    };
+
                     let physical_exprs = expr
                         .iter()
-                        .map(|e| {
+                        .zip(planned_exprs)
+                        .map(|(e, planned)| {
                             // For projections, SQL planner and logical plan builder may convert user
                             // provided expressions into logical Column expressions if their results
                             // are already provided from the input plans. Because we work with
@@ -882,8 +1012,8 @@ impl DefaultPhysicalPlanner {
 
                             tuple_err((
                                 self.create_physical_expr(
-                                    e,
-                                    input_schema,
+                                    planned,
+                                    planned_schema,
                                     &input_exec.schema(),
                                     session_state,
                                 ),
@@ -898,19 +1028,37 @@ impl DefaultPhysicalPlanner {
                     )?))
                 }
                 LogicalPlan::Filter(filter) => {
-                    let physical_input = self.create_initial_plan(&filter.input, session_state).await?;
-                    let input_schema = physical_input.as_ref().schema();
-                    let input_dfschema = filter.input.schema();
+                    if let Some((filter_exec, _, _)) = self.plan_async_filter(filter, session_state).await? {
+                        // the results of the calls to async functions are
+                        // removed by a projection above the filter
+                        let projection = filter_exec.schema()
+                            .fields()
+                            .iter()
+                            .take(filter.input.schema().fields().len())
+                            .enumerate()
+                            .map(|(i, field)| {
+                                (Arc::new(Column::new(field.name(), i)) as _, field.name().to_string())
+                            })
+                            .collect();
+                        Ok(Arc::new(ProjectionExec::try_new(
+                            projection,
+                            filter_exec,
+                        )?))
+                    } else {
+                        let physical_input = self.create_initial_plan(&filter.input, session_state).await?;
+                        let input_schema = physical_input.as_ref().schema();
+                        let input_dfschema = filter.input.schema();
 
-                    let runtime_expr = self.create_physical_expr(
-                        &filter.predicate,
-                        input_dfschema,
-                        &input_schema,
-                        session_state,
-                    )?;
-                    let selectivity = session_state.config().options().optimizer.default_filter_selectivity;
-                    let filter = FilterExec::try_new(runtime_expr, physical_input)?;
-                    Ok(Arc::new(filter.with_default_selectivity(selectivity)?))
+                        let selectivity = session_state.config().options().optimizer.default_filter_selectivity;
+                        let runtime_expr = self.create_physical_expr(
+                            &filter.predicate,
+                            input_dfschema,
+                            &input_schema,
+                            session_state,
+                        )?;
+                        let filter = FilterExec::try_new(runtime_expr, physical_input)?;
+                        Ok(Arc::new(filter.with_default_selectivity(selectivity)?))
+                    }
                 }
                 LogicalPlan::Union(Union { inputs, .. }) => {
                     let physical_plans = self.create_initial_plan_multi(inputs.iter().map(|lp| lp.as_ref()), session_state).await?;
@@ -975,6 +1123,7 @@ impl DefaultPhysicalPlanner {
                     filter,
                     join_type,
                     null_equals_null,
+                    null_aware,
                     schema: join_schema,
                     ..
                 }) => {
@@ -1122,7 +1271,31 @@ impl DefaultPhysicalPlanner {
                     };
 
                     let prefer_hash_join = session_state.config_options().optimizer.prefer_hash_join;
-                    if join_on.is_empty() {
+                    if *null_aware {
+                        // The right side is collected into the hash table, to know
+                        // whether it contains a null key before probing the left side.
+                        // This is always a single CollectLeft hash table, a partitioned
+                        // join can not tell the probe partitions that another partition
+                        // saw a null key. The whole subquery output, such as the
+                        // suppliers of TPC-H q16, must then fit in memory, and the join
+                        // is not swapped when the subquery is the larger input.
+                        if join_on.len() != 1 || join_filter.is_some() {
+                            return not_impl_err!(
+                                "Null aware anti join is only supported on a single equijoin key without filter"
+                            );
+                        }
+                        let join_on = join_on.into_iter().map(|(l, r)| (r, l)).collect();
+                        Ok(Arc::new(HashJoinExec::try_new(
+                            physical_right,
+                            physical_left,
+                            join_on,
+                            None,
+                            &JoinType::RightAnti,
+                            PartitionMode::CollectLeft,
+                            null_equals_null,
+                        )?
+                        .with_null_aware(true)?))
+                    } else if join_on.is_empty() {
                         // there is no equal join condition, use the nested loop join
                         // TODO optimize the plan, and use the config of `target_partitions` and `repartition_joins`
                         Ok(Arc::new(NestedLoopJoinExec::try_new(
@@ -1316,6 +1489,110 @@ impl DefaultPhysicalPlanner {
         }.boxed()
     }
 
+    /// Plans `filter` as a [`FilterExec`] on top of an [`AsyncFuncExec`] for
+    /// the calls to async scalar functions of its predicate.
+    ///
+    /// Returns the [`FilterExec`], its schema, which are the columns of the
+    /// input of `filter` followed by the results of the calls, and the calls,
+    /// or `None` if the predicate does not call async functions.
+    async fn plan_async_filter(
+        &self,
+        filter: &Filter,
+        session_state: &SessionState,
+    ) -> Result<Option<(Arc<dyn ExecutionPlan>, DFSchema, Vec<Expr>)>> {
+        let predicate = std::slice::from_ref(&filter.predicate);
+        let calls = collect_async_calls(predicate)?;
+        if calls.is_empty() {
+            return Ok(None);
+        }
+        let physical_input = self
+            .create_initial_plan(&filter.input, session_state)
+            .await?;
+        let Some((async_exec, async_dfschema, predicate)) = self.plan_async_funcs(
+            predicate,
+            0,
+            physical_input,
+            filter.input.schema(),
+            session_state,
+        )?
+        else {
+            return internal_err!("Expected calls to async functions in {predicate:?}");
+        };
+        let runtime_expr = self.create_physical_expr(
+            &predicate[0],
+            &async_dfschema,
+            &async_exec.schema(),
+            session_state,
+        )?;
+        let selectivity = session_state
+            .config()
+            .options()
+            .optimizer
+            .default_filter_selectivity;
+        let filter = FilterExec::try_new(runtime_expr, async_exec)?
+            .with_default_selectivity(selectivity)?;
+        Ok(Some((Arc::new(filter), async_dfschema, calls)))
+    }
+
+    /// Plans the calls to async scalar functions in `exprs` as an
+    /// [`AsyncFuncExec`] on top of `input`, which appends their results to
+    /// the columns of `input`. The results are named `__async_fn_{i}`,
+    /// counting from `first`, as `input` may already contain results of
+    /// other calls.
+    ///
+    /// Returns the [`AsyncFuncExec`], its schema, and `exprs` with the calls
+    /// replaced by the columns of their results, or `None` if `exprs` do not
+    /// call async functions.
+    fn plan_async_funcs(
+        &self,
+        exprs: &[Expr],
+        first: usize,
+        input: Arc<dyn ExecutionPlan>,
+        input_dfschema: &DFSchema,
+        session_state: &SessionState,
+    ) -> Result<Option<AsyncFuncPlan>> {
+        let calls = collect_async_calls(exprs)?;
+        if calls.is_empty() {
+            return Ok(None);
+        }
+
+        let input_schema = input.schema();
+        let mut async_exprs = Vec::with_capacity(calls.len());
+        let mut fields = Vec::with_capacity(calls.len());
+        for (i, call) in calls.iter().enumerate() {
+            let Expr::ScalarFunction(expr::ScalarFunction {
+                func_def: ScalarFunctionDefinition::UDF(udf),
+                args,
+            }) = call
+            else {
+                return internal_err!("Expected a call to an async function, got {call}");
+            };
+            let func = udf.as_async().cloned().ok_or_else(|| {
+                DataFusionError::Internal(format!("{} is not async", udf.name()))
+            })?;
+            let args = args
+                .iter()
+                .map(|arg| {
+                    self.create_physical_expr(
+                        arg,
+                        input_dfschema,
+                        &input_schema,
+                        session_state,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let name = async_call_name(first + i);
+            let return_type = call.get_type(input_dfschema)?;
+            fields.push(DFField::new_unqualified(&name, return_type.clone(), true));
+            async_exprs.push(AsyncFuncExpr::new(name, func, args, return_type));
+        }
+        let schema =
+            input_dfschema.join(&DFSchema::new_with_metadata(fields, HashMap::new())?)?;
+        let exprs = replace_async_calls(exprs, &calls, first)?;
+        let exec = Arc::new(AsyncFuncExec::try_new(async_exprs, input)?);
+        Ok(Some((exec, schema, exprs)))
+    }
+
     fn create_grouping_physical_expr(
         &self,
         group_expr: &[Expr],
@@ -1957,6 +2234,8 @@ impl DefaultPhysicalPlanner {
     where
         F: FnMut(&dyn ExecutionPlan, &dyn PhysicalOptimizerRule),
     {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("physical_optimize").entered();
         let optimizers = session_state.physical_optimizers();
         debug!(
             "Input physical plan:\n{}\n",
@@ -1969,6 +2248,10 @@ impl DefaultPhysicalPlanner {
 
         let mut new_plan = plan;
         for optimizer in optimizers {
+            #[cfg(feature = "tracing")]
+            let _span =
+                tracing::debug_span!("physical_optimizer_rule", rule = optimizer.name())
+                    .entered();
             let before_schema = new_plan.schema();
             new_plan = optimizer
                 .optimize(new_plan, session_state.config_options())
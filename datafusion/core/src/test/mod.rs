@@ -204,6 +204,9 @@ pub fn partitioned_csv_config(
         limit: None,
         table_partition_cols: vec![],
         output_ordering: vec![],
+        schema_adapter_factory: None,
+        metadata_cols: vec![],
+        projection_paths: vec![],
     })
 }
 
@@ -290,6 +293,9 @@ pub fn csv_exec_sorted(
             limit: None,
             table_partition_cols: vec![],
             output_ordering: vec![sort_exprs],
+            schema_adapter_factory: None,
+            metadata_cols: vec![],
+            projection_paths: vec![],
         },
         false,
         0,
@@ -351,6 +357,9 @@ pub fn csv_exec_ordered(
             limit: None,
             table_partition_cols: vec![],
             output_ordering: vec![sort_exprs],
+            schema_adapter_factory: None,
+            metadata_cols: vec![],
+            projection_paths: vec![],
         },
         true,
         0,
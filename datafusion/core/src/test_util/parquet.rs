@@ -60,6 +60,8 @@ pub struct ParquetScanOptions {
     pub reorder_filters: bool,
     /// enable page index
     pub enable_page_index: bool,
+    /// enable late materialization
+    pub late_materialization: bool,
 }
 
 impl ParquetScanOptions {
@@ -69,6 +71,7 @@ impl ParquetScanOptions {
         config.execution.parquet.pushdown_filters = self.pushdown_filters;
         config.execution.parquet.reorder_filters = self.reorder_filters;
         config.execution.parquet.enable_page_index = self.enable_page_index;
+        config.execution.parquet.late_materialization = self.late_materialization;
         config.into()
     }
 }
@@ -156,6 +159,9 @@ impl TestParquetFile {
             limit: None,
             table_partition_cols: vec![],
             output_ordering: vec![],
+            schema_adapter_factory: None,
+            metadata_cols: vec![],
+            projection_paths: vec![],
         };
 
         let df_schema = self.schema.clone().to_dfschema_ref()?;
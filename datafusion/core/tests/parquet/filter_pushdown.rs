@@ -442,6 +442,7 @@ impl<'a> TestCase<'a> {
                     pushdown_filters: false,
                     reorder_filters: false,
                     enable_page_index: false,
+                    late_materialization: false,
                 },
                 filter,
             )
@@ -453,6 +454,7 @@ impl<'a> TestCase<'a> {
                     pushdown_filters: true,
                     reorder_filters: false,
                     enable_page_index: false,
+                    late_materialization: false,
                 },
                 filter,
             )
@@ -466,6 +468,7 @@ impl<'a> TestCase<'a> {
                     pushdown_filters: true,
                     reorder_filters: true,
                     enable_page_index: false,
+                    late_materialization: false,
                 },
                 filter,
             )
@@ -479,6 +482,7 @@ impl<'a> TestCase<'a> {
                     pushdown_filters: false,
                     reorder_filters: false,
                     enable_page_index: true,
+                    late_materialization: false,
                 },
                 filter,
             )
@@ -491,12 +495,27 @@ impl<'a> TestCase<'a> {
                     pushdown_filters: true,
                     reorder_filters: true,
                     enable_page_index: true,
+                    late_materialization: false,
                 },
                 filter,
             )
             .await;
 
         assert_eq!(no_pushdown, pushdown_reordering_and_page_index);
+
+        let pushdown_and_late_materialization = self
+            .read_with_options(
+                ParquetScanOptions {
+                    pushdown_filters: true,
+                    reorder_filters: false,
+                    enable_page_index: true,
+                    late_materialization: true,
+                },
+                filter,
+            )
+            .await;
+
+        assert_eq!(no_pushdown, pushdown_and_late_materialization);
     }
 
     /// Reads data from a test parquet file using the specified scan options
@@ -118,6 +118,17 @@ impl TestOutput {
         self.metric_value("row_groups_pruned")
     }
 
+    /// The number of row groups pruned by dynamic filters
+    fn row_groups_pruned_dynamic_filter(&self) -> Option<usize> {
+        self.metric_value("row_groups_pruned_dynamic_filter")
+    }
+
+    /// The number of rows not read because they cannot be among the rows
+    /// of a TopK above the scan
+    fn topk_rows_pruned(&self) -> Option<usize> {
+        self.metric_value("topk_rows_pruned")
+    }
+
     /// The number of times the pruning predicate evaluation errors
     fn row_pages_pruned(&self) -> Option<usize> {
         self.metric_value("page_index_rows_filtered")
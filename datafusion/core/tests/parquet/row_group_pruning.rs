@@ -516,3 +516,67 @@ async fn prune_periods_in_column_names() {
     )
     .await;
 }
+
+#[tokio::test]
+async fn prune_topk_dynamic_filter() {
+    // Row groups of 5 rows: -5..0, -4..1, 0..5, 5..10. The threshold of the
+    // TopK after reading the first two row groups rules out the last two
+    let config = SessionConfig::new().with_target_partitions(1);
+    let output = ContextWithParquet::with_config(Scenario::Int32, RowGroup, config)
+        .await
+        .query("SELECT * FROM t ORDER BY i LIMIT 3")
+        .await;
+    assert_eq!(output.row_groups_pruned(), Some(0));
+    assert_eq!(output.row_groups_pruned_dynamic_filter(), Some(2));
+    assert_eq!(output.result_rows, 3, "{}", output.description());
+
+    // nothing can be pruned in descending order
+    let config = SessionConfig::new().with_target_partitions(1);
+    let output = ContextWithParquet::with_config(Scenario::Int32, RowGroup, config)
+        .await
+        .query("SELECT * FROM t ORDER BY i DESC LIMIT 3")
+        .await;
+    assert_eq!(output.row_groups_pruned_dynamic_filter(), Some(0));
+    assert_eq!(output.result_rows, 3, "{}", output.description());
+
+    let mut config = SessionConfig::new().with_target_partitions(1);
+    config
+        .options_mut()
+        .optimizer
+        .enable_topk_dynamic_filter_pushdown = false;
+    let output = ContextWithParquet::with_config(Scenario::Int32, RowGroup, config)
+        .await
+        .query("SELECT * FROM t ORDER BY i LIMIT 3")
+        .await;
+    assert_eq!(output.row_groups_pruned_dynamic_filter(), Some(0));
+    assert_eq!(output.result_rows, 3, "{}", output.description());
+}
+
+#[tokio::test]
+async fn prune_topk_late_materialization() {
+    // only the top 3 rows of the first row group are read
+    let mut config = SessionConfig::new().with_target_partitions(1);
+    config.options_mut().execution.parquet.late_materialization = true;
+    let output = ContextWithParquet::with_config(Scenario::Int32, RowGroup, config)
+        .await
+        .query("SELECT * FROM t ORDER BY i DESC LIMIT 3")
+        .await;
+    assert!(output.topk_rows_pruned().unwrap() >= 2);
+    assert_eq!(output.result_rows, 3, "{}", output.description());
+    assert_eq!(
+        output.pretty_results,
+        ContextWithParquet::with_config(Scenario::Int32, RowGroup, SessionConfig::new())
+            .await
+            .query("SELECT * FROM t ORDER BY i DESC LIMIT 3")
+            .await
+            .pretty_results
+    );
+
+    // disabled by default
+    let config = SessionConfig::new().with_target_partitions(1);
+    let output = ContextWithParquet::with_config(Scenario::Int32, RowGroup, config)
+        .await
+        .query("SELECT * FROM t ORDER BY i DESC LIMIT 3")
+        .await;
+    assert_eq!(output.topk_rows_pruned(), Some(0));
+}
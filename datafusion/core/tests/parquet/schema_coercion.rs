@@ -20,10 +20,14 @@ use std::sync::Arc;
 use arrow::datatypes::{Field, Schema};
 use arrow::record_batch::RecordBatch;
 use arrow_array::types::Int32Type;
-use arrow_array::{ArrayRef, DictionaryArray, Float32Array, Int64Array, StringArray};
+use arrow_array::{
+    ArrayRef, DictionaryArray, Float32Array, Float64Array, Int32Array, Int64Array,
+    StringArray,
+};
 use arrow_schema::DataType;
 use datafusion::assert_batches_sorted_eq;
 use datafusion::datasource::physical_plan::{FileScanConfig, ParquetExec};
+use datafusion::datasource::schema_adapter::SchemaEvolutionAdapterFactory;
 use datafusion::physical_plan::collect;
 use datafusion::prelude::SessionContext;
 use datafusion_common::{Result, Statistics};
@@ -69,6 +73,9 @@ async fn multi_parquet_coercion() {
             limit: None,
             table_partition_cols: vec![],
             output_ordering: vec![],
+            schema_adapter_factory: None,
+            metadata_cols: vec![],
+            projection_paths: vec![],
         },
         None,
         None,
@@ -132,6 +139,9 @@ async fn multi_parquet_coercion_projection() {
             limit: None,
             table_partition_cols: vec![],
             output_ordering: vec![],
+            schema_adapter_factory: None,
+            metadata_cols: vec![],
+            projection_paths: vec![],
         },
         None,
         None,
@@ -156,6 +166,66 @@ async fn multi_parquet_coercion_projection() {
     assert_batches_sorted_eq!(expected, &read);
 }
 
+/// Test for reading parquet files whose columns were renamed and widened
+#[tokio::test]
+async fn multi_parquet_schema_evolution() {
+    let old_id: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None]));
+    let old_amount: ArrayRef = Arc::new(Float32Array::from(vec![Some(1.5), Some(2.5)]));
+    let new_id: ArrayRef = Arc::new(Int64Array::from(vec![Some(3)]));
+    let new_amount: ArrayRef = Arc::new(Float64Array::from(vec![Some(3.5)]));
+
+    // batch1: cust_id(int32), amount(float32)
+    let batch1 =
+        RecordBatch::try_from_iter(vec![("cust_id", old_id), ("amount", old_amount)])
+            .unwrap();
+    // batch2: amount(float64), customer_id(int64)
+    let batch2 =
+        RecordBatch::try_from_iter(vec![("amount", new_amount), ("customer_id", new_id)])
+            .unwrap();
+
+    let (meta, _files) = store_parquet(vec![batch1, batch2]).await.unwrap();
+    let file_groups = meta.into_iter().map(Into::into).collect();
+
+    let file_schema = Arc::new(Schema::new(vec![
+        Field::new("customer_id", DataType::Int64, true),
+        Field::new("amount", DataType::Float64, true),
+    ]));
+    let factory =
+        SchemaEvolutionAdapterFactory::new().with_rename("cust_id", "customer_id");
+    let parquet_exec = ParquetExec::new(
+        FileScanConfig {
+            object_store_url: ObjectStoreUrl::local_filesystem(),
+            file_groups: vec![file_groups],
+            statistics: Statistics::new_unknown(&file_schema),
+            file_schema,
+            projection: None,
+            limit: None,
+            table_partition_cols: vec![],
+            output_ordering: vec![],
+            schema_adapter_factory: Some(Arc::new(factory)),
+            metadata_cols: vec![],
+            projection_paths: vec![],
+        },
+        None,
+        None,
+    );
+
+    let session_ctx = SessionContext::new();
+    let task_ctx = session_ctx.task_ctx();
+    let read = collect(Arc::new(parquet_exec), task_ctx).await.unwrap();
+
+    let expected = [
+        "+-------------+--------+",
+        "| customer_id | amount |",
+        "+-------------+--------+",
+        "|             | 2.5    |",
+        "| 1           | 1.5    |",
+        "| 3           | 3.5    |",
+        "+-------------+--------+",
+    ];
+    assert_batches_sorted_eq!(expected, &read);
+}
+
 /// Writes `batches` to a temporary parquet file
 pub async fn store_parquet(
     batches: Vec<RecordBatch>,
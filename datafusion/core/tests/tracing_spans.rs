@@ -0,0 +1,204 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Tests for the tracing spans emitted with the `tracing` feature
+
+#![cfg(feature = "tracing")]
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use datafusion::physical_plan::displayable;
+use datafusion::physical_plan::instrumented::InstrumentedExec;
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::prelude::{SessionConfig, SessionContext};
+use datafusion_common::Result;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// A span recorded by [`RecordingSubscriber`]
+#[derive(Debug, Clone)]
+struct RecordedSpan {
+    id: u64,
+    name: &'static str,
+    parent: Option<u64>,
+    fields: HashMap<String, String>,
+}
+
+impl RecordedSpan {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(|v| v.as_str())
+    }
+}
+
+#[derive(Debug, Default)]
+struct Recorded {
+    spans: Vec<RecordedSpan>,
+    /// The ids of the entered spans, innermost last
+    stack: Vec<u64>,
+}
+
+/// An in-memory subscriber that records all spans and their fields
+#[derive(Debug, Default, Clone)]
+struct RecordingSubscriber {
+    recorded: Arc<Mutex<Recorded>>,
+}
+
+impl RecordingSubscriber {
+    fn spans(&self) -> Vec<RecordedSpan> {
+        self.recorded.lock().unwrap().spans.clone()
+    }
+}
+
+struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+impl<'a> Visit for FieldVisitor<'a> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
+
+impl Subscriber for RecordingSubscriber {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+        let mut recorded = self.recorded.lock().unwrap();
+        let parent = match attrs.parent() {
+            Some(parent) => Some(parent.into_u64()),
+            None if attrs.is_contextual() => recorded.stack.last().copied(),
+            None => None,
+        };
+        let mut fields = HashMap::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+        let id = recorded.spans.len() as u64 + 1;
+        recorded.spans.push(RecordedSpan {
+            id,
+            name: attrs.metadata().name(),
+            parent,
+            fields,
+        });
+        Id::from_u64(id)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut recorded = self.recorded.lock().unwrap();
+        let span = &mut recorded.spans[span.into_u64() as usize - 1];
+        values.record(&mut FieldVisitor(&mut span.fields));
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        self.recorded.lock().unwrap().stack.push(span.into_u64());
+    }
+
+    fn exit(&self, _span: &Id) {
+        self.recorded.lock().unwrap().stack.pop();
+    }
+}
+
+#[tokio::test]
+async fn planning_and_execution_spans() -> Result<()> {
+    let subscriber = RecordingSubscriber::default();
+    let _guard = tracing::subscriber::set_default(subscriber.clone());
+
+    let ctx =
+        SessionContext::new_with_config(SessionConfig::new().with_target_partitions(1));
+    let sql = "SELECT a + 1 FROM (VALUES (1), (2), (3)) AS t(a) WHERE a > 1";
+    let batches = ctx.sql(sql).await?.collect().await?;
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+
+    let spans = subscriber.spans();
+    let find = |name: &str, field: &str, value: &str| {
+        spans
+            .iter()
+            .find(|s| s.name == name && s.field(field) == Some(value))
+            .unwrap_or_else(|| panic!("no {name} span with {field}={value}"))
+    };
+
+    find("sql_parse", "dialect", "generic");
+
+    // each rule has its own span, nested in the span of its phase
+    let analyze = spans.iter().find(|s| s.name == "analyze").unwrap();
+    let rule = find("analyzer_rule", "rule", "type_coercion");
+    assert_eq!(rule.parent, Some(analyze.id));
+
+    let optimize = spans.iter().find(|s| s.name == "optimize").unwrap();
+    let rule = find("optimizer_rule", "rule", "push_down_filter");
+    assert_eq!(rule.parent, Some(optimize.id));
+    assert_eq!(rule.field("pass"), Some("0"));
+    assert!(rule.field("changed").is_some());
+
+    let physical_optimize = spans
+        .iter()
+        .find(|s| s.name == "physical_optimize")
+        .unwrap();
+    let rule = find("physical_optimizer_rule", "rule", "EnforceDistribution");
+    assert_eq!(rule.parent, Some(physical_optimize.id));
+
+    // the spans of the operators are nested in the spans of their parents
+    let projection = find("execute", "operator", "ProjectionExec");
+    assert_eq!(projection.field("partition"), Some("0"));
+    assert_eq!(projection.field("batches"), Some("1"));
+    assert_eq!(projection.field("rows"), Some("2"));
+    assert!(projection.field("poll_time_ns").is_some());
+
+    let values = find("execute", "operator", "ValuesExec");
+    assert_eq!(values.field("rows"), Some("3"));
+    let mut ancestor = values.parent;
+    while let Some(id) = ancestor.filter(|id| *id != projection.id) {
+        ancestor = spans[id as usize - 1].parent;
+    }
+    assert_eq!(ancestor, Some(projection.id));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn instrumented_plan() -> Result<()> {
+    let ctx = SessionContext::new();
+    let sql = "SELECT a + 1 FROM (VALUES (1), (2), (3)) AS t(a)";
+
+    // without a subscriber the plan is not changed
+    let plan = ctx.sql(sql).await?.create_physical_plan().await?;
+    assert!(plan.as_any().is::<ProjectionExec>());
+
+    // with one the operators are wrapped, but displayed as if they were not
+    let _guard = tracing::subscriber::set_default(RecordingSubscriber::default());
+    let plan = ctx.sql(sql).await?.create_physical_plan().await?;
+    let instrumented = plan
+        .as_any()
+        .downcast_ref::<InstrumentedExec>()
+        .expect("instrumented plan");
+    assert!(instrumented.inner().as_any().is::<ProjectionExec>());
+    assert!(displayable(plan.as_ref())
+        .one_line()
+        .to_string()
+        .starts_with("ProjectionExec"));
+    Ok(())
+}
@@ -16,18 +16,24 @@
 // under the License.
 
 use arrow::compute::kernels::numeric::add;
-use arrow_array::{ArrayRef, Float64Array, Int32Array, RecordBatch};
+use arrow_array::{ArrayRef, Float64Array, Int32Array, RecordBatch, StringArray};
 use arrow_schema::{DataType, Field, Schema};
+use async_trait::async_trait;
+use datafusion::physical_plan::displayable;
 use datafusion::prelude::*;
 use datafusion::{
     execution::registry::FunctionRegistry,
     physical_plan::functions::make_scalar_function, test_util,
 };
-use datafusion_common::cast::as_float64_array;
+use datafusion_common::cast::{as_float64_array, as_int64_array};
 use datafusion_common::{assert_batches_eq, cast::as_int32_array, Result, ScalarValue};
 use datafusion_expr::{
-    create_udaf, create_udf, Accumulator, ColumnarValue, LogicalPlanBuilder, Volatility,
+    create_udaf, create_udf, Accumulator, AsyncScalarUDF, ColumnarValue,
+    LogicalPlanBuilder, ScalarUDF, Signature, Volatility,
 };
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 /// test that casting happens on udfs.
@@ -378,6 +384,103 @@ async fn test_user_defined_functions_with_alias() -> Result<()> {
     Ok(())
 }
 
+/// An async function looking up the country of an id in an in-process
+/// stand-in for an external service, counting the requests it receives
+#[derive(Debug)]
+struct CountryLookup {
+    signature: Signature,
+    countries: HashMap<i64, &'static str>,
+    requests: Arc<AtomicUsize>,
+}
+
+impl CountryLookup {
+    fn new(requests: Arc<AtomicUsize>) -> Self {
+        Self {
+            signature: Signature::exact(vec![DataType::Int64], Volatility::Volatile),
+            countries: HashMap::from([(1, "NL"), (2, "DE"), (3, "FR")]),
+            requests,
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncScalarUDF for CountryLookup {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "country"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    async fn invoke_async(
+        &self,
+        args: &[ColumnarValue],
+        num_rows: usize,
+    ) -> Result<ColumnarValue> {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        tokio::task::yield_now().await;
+        let ids = args[0].clone().into_array(num_rows)?;
+        let countries: StringArray = as_int64_array(&ids)?
+            .iter()
+            .map(|id| id.and_then(|id| self.countries.get(&id).copied()))
+            .collect();
+        Ok(ColumnarValue::Array(Arc::new(countries)))
+    }
+}
+
+#[tokio::test]
+async fn async_scalar_udf() -> Result<()> {
+    let ctx =
+        SessionContext::new_with_config(SessionConfig::new().with_target_partitions(1));
+    ctx.sql("CREATE TABLE t AS VALUES (1), (2), (3), (4)")
+        .await?
+        .collect()
+        .await?;
+    let requests = Arc::new(AtomicUsize::new(0));
+    ctx.register_udf(ScalarUDF::new_async(CountryLookup::new(Arc::clone(
+        &requests,
+    ))));
+
+    let sql = "SELECT column1, country(column1) AS c FROM t \
+               WHERE country(column1) <> 'DE' ORDER BY column1";
+    let plan = ctx.sql(sql).await?.create_physical_plan().await?;
+    let plan = displayable(plan.as_ref()).indent(false).to_string();
+    // the projection reads the results of the calls of the filter
+    assert_eq!(plan.matches("AsyncFuncExec").count(), 1, "{plan}");
+    assert!(
+        plan.contains("AsyncFuncExec: async_expr=[country(column1@0) as __async_fn_0]"),
+        "{plan}"
+    );
+    assert!(plan.contains("__async_fn_0@1 as c"), "{plan}");
+
+    let expected = [
+        "+---------+----+",
+        "| column1 | c  |",
+        "+---------+----+",
+        "| 1       | NL |",
+        "| 3       | FR |",
+        "+---------+----+",
+    ];
+    let result = plan_and_collect(&ctx, sql).await?;
+    assert_batches_eq!(expected, &result);
+    // one request for the only batch
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+    let sql = "SELECT country(CAST(length(country(column1)) AS BIGINT)) FROM t";
+    let err = plan_and_collect(&ctx, sql).await.unwrap_err();
+    assert!(err.to_string().contains("not supported"), "{err}");
+    Ok(())
+}
+
 fn create_udf_context() -> SessionContext {
     let ctx = SessionContext::new();
     // register a custom UDF
@@ -15,10 +15,12 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use crate::cache::object_store_cache::{ObjectStoreCache, ObjectStoreCacheConfig};
 use crate::cache::CacheAccessor;
 use datafusion_common::{Result, Statistics};
 use object_store::path::Path;
 use object_store::ObjectMeta;
+use std::any::Any;
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
 
@@ -32,6 +34,24 @@ pub type FileStatisticsCache =
 pub type ListFilesCache =
     Arc<dyn CacheAccessor<Path, Arc<Vec<ObjectMeta>>, Extra = ObjectMeta>>;
 
+/// Decoded metadata of a file, such as the `ParquetMetaData` of a parquet
+/// file, stored in a [`ParquetMetadataCache`].
+pub trait FileMetadata: Any + Send + Sync {
+    /// Returns the metadata as [`Any`] so that it can be downcast to its type
+    fn as_any(&self) -> &dyn Any;
+
+    /// Returns the number of bytes of memory used by the metadata
+    fn memory_size(&self) -> usize;
+}
+
+/// The cache of the decoded metadata of parquet files, including their page
+/// indexes.
+/// if set [`CacheManagerConfig::with_parquet_metadata_cache`]
+/// Will avoid reading and decoding the footers of the same files for every query,
+/// this cache will store in [`crate::runtime_env::RuntimeEnv`].
+pub type ParquetMetadataCache =
+    Arc<dyn CacheAccessor<Path, Arc<dyn FileMetadata>, Extra = ObjectMeta>>;
+
 impl Debug for dyn CacheAccessor<Path, Arc<Statistics>, Extra = ObjectMeta> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "Cache name: {} with length: {}", self.name(), self.len())
@@ -44,10 +64,18 @@ impl Debug for dyn CacheAccessor<Path, Arc<Vec<ObjectMeta>>, Extra = ObjectMeta>
     }
 }
 
+impl Debug for dyn CacheAccessor<Path, Arc<dyn FileMetadata>, Extra = ObjectMeta> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cache name: {} with length: {}", self.name(), self.len())
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct CacheManager {
     file_statistic_cache: Option<FileStatisticsCache>,
     list_files_cache: Option<ListFilesCache>,
+    parquet_metadata_cache: Option<ParquetMetadataCache>,
+    object_store_cache: Option<Arc<ObjectStoreCache>>,
 }
 
 impl CacheManager {
@@ -59,6 +87,13 @@ impl CacheManager {
         if let Some(lc) = &config.list_files_cache {
             manager.list_files_cache = Some(lc.clone())
         }
+        if let Some(pc) = &config.parquet_metadata_cache {
+            manager.parquet_metadata_cache = Some(pc.clone())
+        }
+        if let Some(oc) = &config.object_store_cache {
+            manager.object_store_cache =
+                Some(Arc::new(ObjectStoreCache::try_new(oc.clone())?))
+        }
         Ok(Arc::new(manager))
     }
 
@@ -71,6 +106,16 @@ impl CacheManager {
     pub fn get_list_files_cache(&self) -> Option<ListFilesCache> {
         self.list_files_cache.clone()
     }
+
+    /// Get the cache of the decoded metadata of parquet files.
+    pub fn get_parquet_metadata_cache(&self) -> Option<ParquetMetadataCache> {
+        self.parquet_metadata_cache.clone()
+    }
+
+    /// Get the read cache of the objects of remote object stores.
+    pub fn get_object_store_cache(&self) -> Option<Arc<ObjectStoreCache>> {
+        self.object_store_cache.clone()
+    }
 }
 
 #[derive(Clone, Default)]
@@ -86,6 +131,16 @@ pub struct CacheManagerConfig {
     /// location.  
     /// Default is disable.
     pub list_files_cache: Option<ListFilesCache>,
+    /// Enable cache of the decoded metadata of parquet files, including their
+    /// page indexes. The metadata of a file is read again once its size or
+    /// last modification time has changed.
+    /// Default is disable.
+    pub parquet_metadata_cache: Option<ParquetMetadataCache>,
+    /// Enable the read cache of the objects of remote object stores, which
+    /// keeps the byte ranges read on local disk and the footers of the objects
+    /// in memory. Objects of `file://` urls are not cached.
+    /// Default is disable.
+    pub object_store_cache: Option<ObjectStoreCacheConfig>,
 }
 
 impl CacheManagerConfig {
@@ -101,4 +156,20 @@ impl CacheManagerConfig {
         self.list_files_cache = cache;
         self
     }
+
+    pub fn with_parquet_metadata_cache(
+        mut self,
+        cache: Option<ParquetMetadataCache>,
+    ) -> Self {
+        self.parquet_metadata_cache = cache;
+        self
+    }
+
+    pub fn with_object_store_cache(
+        mut self,
+        config: Option<ObjectStoreCacheConfig>,
+    ) -> Self {
+        self.object_store_cache = config;
+        self
+    }
 }
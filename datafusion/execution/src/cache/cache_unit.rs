@@ -17,19 +17,22 @@
 
 use std::sync::Arc;
 
-use crate::cache::CacheAccessor;
+use crate::cache::cache_manager::FileMetadata;
+use crate::cache::{CacheAccessor, CacheStats, CacheStatsCounter, LruEntries};
 
 use datafusion_common::Statistics;
 
 use dashmap::DashMap;
 use object_store::path::Path;
 use object_store::ObjectMeta;
+use parking_lot::Mutex;
 
 /// Collected statistics for files
 /// Cache is invalided when file size or last modification has changed
 #[derive(Default)]
 pub struct DefaultFileStatisticsCache {
     statistics: DashMap<Path, (ObjectMeta, Arc<Statistics>)>,
+    stats: CacheStatsCounter,
 }
 
 impl CacheAccessor<Path, Arc<Statistics>> for DefaultFileStatisticsCache {
@@ -37,15 +40,18 @@ impl CacheAccessor<Path, Arc<Statistics>> for DefaultFileStatisticsCache {
 
     /// Get `Statistics` for file location.
     fn get(&self, k: &Path) -> Option<Arc<Statistics>> {
-        self.statistics
-            .get(k)
-            .map(|s| Some(s.value().1.clone()))
-            .unwrap_or(None)
+        self.stats.record(
+            self.statistics
+                .get(k)
+                .map(|s| Some(s.value().1.clone()))
+                .unwrap_or(None),
+        )
     }
 
     /// Get `Statistics` for file location. Returns None if file has changed or not found.
     fn get_with_extra(&self, k: &Path, e: &Self::Extra) -> Option<Arc<Statistics>> {
-        self.statistics
+        let statistics = self
+            .statistics
             .get(k)
             .map(|s| {
                 let (saved_meta, statistics) = s.value();
@@ -58,7 +64,8 @@ impl CacheAccessor<Path, Arc<Statistics>> for DefaultFileStatisticsCache {
                     Some(statistics.clone())
                 }
             })
-            .unwrap_or(None)
+            .unwrap_or(None);
+        self.stats.record(statistics)
     }
 
     /// Save collected file statistics
@@ -95,6 +102,10 @@ impl CacheAccessor<Path, Arc<Statistics>> for DefaultFileStatisticsCache {
     fn name(&self) -> String {
         "DefaultFileStatisticsCache".to_string()
     }
+
+    fn stats(&self) -> CacheStats {
+        self.stats.stats()
+    }
 }
 
 /// Collected files metadata for listing files.
@@ -102,13 +113,15 @@ impl CacheAccessor<Path, Arc<Statistics>> for DefaultFileStatisticsCache {
 #[derive(Default)]
 pub struct DefaultListFilesCache {
     statistics: DashMap<Path, Arc<Vec<ObjectMeta>>>,
+    stats: CacheStatsCounter,
 }
 
 impl CacheAccessor<Path, Arc<Vec<ObjectMeta>>> for DefaultListFilesCache {
     type Extra = ObjectMeta;
 
     fn get(&self, k: &Path) -> Option<Arc<Vec<ObjectMeta>>> {
-        self.statistics.get(k).map(|x| x.value().clone())
+        self.stats
+            .record(self.statistics.get(k).map(|x| x.value().clone()))
     }
 
     fn get_with_extra(
@@ -155,17 +168,135 @@ impl CacheAccessor<Path, Arc<Vec<ObjectMeta>>> for DefaultListFilesCache {
     fn name(&self) -> String {
         "DefaultListFilesCache".to_string()
     }
+
+    fn stats(&self) -> CacheStats {
+        self.stats.stats()
+    }
+}
+
+/// The default memory limit of a [`DefaultParquetMetadataCache`]
+pub const DEFAULT_PARQUET_METADATA_CACHE_LIMIT: usize = 50 * 1024 * 1024;
+
+/// The metadata of a file, with the [`ObjectMeta`] it was read for
+type MetadataEntry = (ObjectMeta, Arc<dyn FileMetadata>);
+
+/// Decoded metadata of parquet files.
+/// Cache is invalided when file size or last modification has changed, and
+/// the least recently used entries are evicted once the metadata uses more
+/// memory than the limit.
+pub struct DefaultParquetMetadataCache {
+    metadata: Mutex<LruEntries<Path, MetadataEntry>>,
+    stats: CacheStatsCounter,
+}
+
+impl Default for DefaultParquetMetadataCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_PARQUET_METADATA_CACHE_LIMIT)
+    }
+}
+
+impl DefaultParquetMetadataCache {
+    /// Create a cache that keeps metadata of at most `memory_limit` bytes
+    pub fn new(memory_limit: usize) -> Self {
+        Self {
+            metadata: Mutex::new(LruEntries::new(memory_limit)),
+            stats: CacheStatsCounter::default(),
+        }
+    }
+
+    /// The number of bytes of memory used by the cached metadata
+    pub fn memory_used(&self) -> usize {
+        self.metadata.lock().size()
+    }
+}
+
+impl CacheAccessor<Path, Arc<dyn FileMetadata>> for DefaultParquetMetadataCache {
+    type Extra = ObjectMeta;
+
+    /// Get the metadata of a file.
+    fn get(&self, k: &Path) -> Option<Arc<dyn FileMetadata>> {
+        self.stats
+            .record(self.metadata.lock().get(k).map(|(_, metadata)| metadata))
+    }
+
+    /// Get the metadata of a file. Returns None if file has changed or not found.
+    fn get_with_extra(&self, k: &Path, e: &Self::Extra) -> Option<Arc<dyn FileMetadata>> {
+        let metadata = self
+            .metadata
+            .lock()
+            .get(k)
+            .filter(|(saved_meta, _)| {
+                saved_meta.size == e.size && saved_meta.last_modified == e.last_modified
+            })
+            .map(|(_, metadata)| metadata);
+        self.stats.record(metadata)
+    }
+
+    fn put(
+        &self,
+        _key: &Path,
+        _value: Arc<dyn FileMetadata>,
+    ) -> Option<Arc<dyn FileMetadata>> {
+        panic!("Put cache in DefaultParquetMetadataCache without Extra not supported.")
+    }
+
+    /// Save the metadata of a file, evicting the least recently used metadata
+    /// if the memory limit is exceeded.
+    fn put_with_extra(
+        &self,
+        key: &Path,
+        value: Arc<dyn FileMetadata>,
+        e: &Self::Extra,
+    ) -> Option<Arc<dyn FileMetadata>> {
+        let mut metadata = self.metadata.lock();
+        let old = metadata.remove(key).map(|(_, metadata)| metadata);
+        let size = value.memory_size();
+        metadata.insert(key.clone(), (e.clone(), value), size);
+        old
+    }
+
+    fn remove(&mut self, k: &Path) -> Option<Arc<dyn FileMetadata>> {
+        self.metadata
+            .get_mut()
+            .remove(k)
+            .map(|(_, metadata)| metadata)
+    }
+
+    fn contains_key(&self, k: &Path) -> bool {
+        self.metadata.lock().contains_key(k)
+    }
+
+    fn len(&self) -> usize {
+        self.metadata.lock().len()
+    }
+
+    fn clear(&self) {
+        self.metadata.lock().clear();
+    }
+
+    fn name(&self) -> String {
+        "DefaultParquetMetadataCache".to_string()
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.stats.stats()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::cache::cache_unit::{DefaultFileStatisticsCache, DefaultListFilesCache};
-    use crate::cache::CacheAccessor;
+    use crate::cache::cache_manager::FileMetadata;
+    use crate::cache::cache_unit::{
+        DefaultFileStatisticsCache, DefaultListFilesCache, DefaultParquetMetadataCache,
+    };
+    use crate::cache::{CacheAccessor, CacheStats};
     use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
     use chrono::DateTime;
     use datafusion_common::Statistics;
     use object_store::path::Path;
     use object_store::ObjectMeta;
+    use std::any::Any;
+    use std::sync::Arc;
 
     #[test]
     fn test_statistics_cache() {
@@ -209,6 +340,10 @@ mod tests {
         let mut meta2 = meta;
         meta2.location = Path::from("test2");
         assert!(cache.get_with_extra(&meta2.location, &meta2).is_none());
+
+        let stats = cache.stats();
+        assert_eq!(stats, CacheStats { hits: 1, misses: 4 });
+        assert_eq!(stats.hit_rate(), Some(0.2));
     }
 
     #[test]
@@ -231,5 +366,68 @@ mod tests {
             cache.get(&meta.location).unwrap().first().unwrap().clone(),
             meta.clone()
         );
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+    }
+
+    struct TestMetadata(usize);
+
+    impl FileMetadata for TestMetadata {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn memory_size(&self) -> usize {
+            self.0
+        }
+    }
+
+    fn test_meta(location: &str) -> ObjectMeta {
+        ObjectMeta {
+            location: Path::from(location),
+            last_modified: DateTime::parse_from_rfc3339("2022-09-27T22:36:00+02:00")
+                .unwrap()
+                .into(),
+            size: 1024,
+            e_tag: None,
+            version: None,
+        }
+    }
+
+    #[test]
+    fn test_parquet_metadata_cache() {
+        let cache = DefaultParquetMetadataCache::new(100);
+        let meta = test_meta("test");
+        assert!(cache.get_with_extra(&meta.location, &meta).is_none());
+
+        cache.put_with_extra(&meta.location, Arc::new(TestMetadata(40)), &meta);
+        let metadata = cache.get_with_extra(&meta.location, &meta).unwrap();
+        assert_eq!(
+            metadata.as_any().downcast_ref::<TestMetadata>().unwrap().0,
+            40
+        );
+
+        // file size changed
+        let mut meta2 = meta.clone();
+        meta2.size = 2048;
+        assert!(cache.get_with_extra(&meta2.location, &meta2).is_none());
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 2 });
+
+        // the least recently used metadata is evicted
+        let meta2 = test_meta("test2");
+        cache.put_with_extra(&meta2.location, Arc::new(TestMetadata(40)), &meta2);
+        assert!(cache.get_with_extra(&meta.location, &meta).is_some());
+        let meta3 = test_meta("test3");
+        cache.put_with_extra(&meta3.location, Arc::new(TestMetadata(40)), &meta3);
+        assert!(cache.contains_key(&meta.location));
+        assert!(!cache.contains_key(&meta2.location));
+        assert!(cache.contains_key(&meta3.location));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.memory_used(), 80);
+
+        // metadata larger than the limit is not cached
+        let meta4 = test_meta("test4");
+        cache.put_with_extra(&meta4.location, Arc::new(TestMetadata(200)), &meta4);
+        assert!(!cache.contains_key(&meta4.location));
+        assert_eq!(cache.len(), 2);
     }
 }
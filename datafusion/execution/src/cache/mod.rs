@@ -17,12 +17,16 @@
 
 pub mod cache_manager;
 pub mod cache_unit;
+pub mod object_store_cache;
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// The cache accessor, users usually working on this interface while manipulating caches.
 /// This interface does not get `mut` references and thus has to handle its own
 /// locking via internal mutability. It can be accessed via multiple concurrent queries
 /// during planning and execution.
-
 pub trait CacheAccessor<K, V>: Send + Sync {
     // Extra info but not part of the cache key or cache value.
     type Extra: Clone;
@@ -49,4 +53,136 @@ pub trait CacheAccessor<K, V>: Send + Sync {
     fn clear(&self);
     /// Return the cache name.
     fn name(&self) -> String;
+    /// Return the number of lookups that found or missed an entry, if tracked.
+    fn stats(&self) -> CacheStats {
+        CacheStats::default()
+    }
+}
+
+/// The number of lookups of a cache that found or missed an entry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of lookups that found an entry
+    pub hits: usize,
+    /// Number of lookups that did not find an entry
+    pub misses: usize,
+}
+
+impl CacheStats {
+    /// Returns the fraction of lookups that found an entry, or `None` if
+    /// there were no lookups
+    pub fn hit_rate(&self) -> Option<f64> {
+        let lookups = self.hits + self.misses;
+        (lookups > 0).then(|| self.hits as f64 / lookups as f64)
+    }
+}
+
+/// Counts the hits and misses of the lookups of a cache
+#[derive(Debug, Default)]
+pub(crate) struct CacheStatsCounter {
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl CacheStatsCounter {
+    /// Records the result of a lookup, and returns it
+    pub(crate) fn record<V>(&self, value: Option<V>) -> Option<V> {
+        let counter = match value {
+            Some(_) => &self.hits,
+            None => &self.misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        value
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The entries of a cache, evicted in least recently used order once their
+/// total size exceeds `max_size`
+#[derive(Debug)]
+pub(crate) struct LruEntries<K, V> {
+    /// The value, size and last use of each entry
+    entries: HashMap<K, (V, usize, u64)>,
+    /// The entries by last use
+    uses: BTreeMap<u64, K>,
+    last_use: u64,
+    size: usize,
+    max_size: usize,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> LruEntries<K, V> {
+    pub(crate) fn new(max_size: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            uses: BTreeMap::new(),
+            last_use: 0,
+            size: 0,
+            max_size,
+        }
+    }
+
+    pub(crate) fn get(&mut self, key: &K) -> Option<V> {
+        let (value, _, last_use) = self.entries.get_mut(key)?;
+        self.uses.remove(last_use);
+        self.last_use += 1;
+        *last_use = self.last_use;
+        self.uses.insert(self.last_use, key.clone());
+        Some(value.clone())
+    }
+
+    /// Inserts an entry of `size`, returning the values of the entries that
+    /// are evicted, or `value` itself if it is larger than the cache
+    pub(crate) fn insert(&mut self, key: K, value: V, size: usize) -> Vec<V> {
+        if size > self.max_size {
+            return vec![value];
+        }
+        let mut evicted: Vec<_> = self.remove(&key).into_iter().collect();
+        while self.size + size > self.max_size {
+            let Some((_, key)) = self.uses.pop_first() else {
+                break;
+            };
+            if let Some((value, size, _)) = self.entries.remove(&key) {
+                self.size -= size;
+                evicted.push(value);
+            }
+        }
+        self.last_use += 1;
+        self.uses.insert(self.last_use, key.clone());
+        self.entries.insert(key, (value, size, self.last_use));
+        self.size += size;
+        evicted
+    }
+
+    pub(crate) fn remove(&mut self, key: &K) -> Option<V> {
+        let (value, size, last_use) = self.entries.remove(key)?;
+        self.uses.remove(&last_use);
+        self.size -= size;
+        Some(value)
+    }
+
+    pub(crate) fn contains_key(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// The number of entries
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The total size of the entries
+    pub(crate) fn size(&self) -> usize {
+        self.size
+    }
+
+    pub(crate) fn clear(&mut self) -> Vec<V> {
+        self.uses.clear();
+        self.size = 0;
+        self.entries.drain().map(|(_, (value, ..))| value).collect()
+    }
 }
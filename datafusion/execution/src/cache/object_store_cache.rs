@@ -0,0 +1,799 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! A read cache of the objects of remote [`ObjectStore`]s, which keeps the
+//! byte ranges read on local disk and the footers of the objects in memory
+
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::cache::{CacheStats, CacheStatsCounter, LruEntries};
+
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Utc};
+use datafusion_common::{DataFusionError, Result};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use log::{debug, warn};
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, GetResultPayload, ListResult, MultipartId, ObjectMeta,
+    ObjectStore, PutOptions, PutResult,
+};
+use parking_lot::Mutex;
+use tempfile::TempDir;
+use tokio::io::AsyncWrite;
+
+/// Configuration of an [`ObjectStoreCache`]
+#[derive(Debug, Clone)]
+pub struct ObjectStoreCacheConfig {
+    /// The directory to create the directory of the cached byte ranges in,
+    /// or the temporary directory of the OS if `None`. The cached byte
+    /// ranges are removed when the cache is dropped.
+    pub directory: Option<PathBuf>,
+    /// The maximum number of bytes of the byte ranges cached on disk
+    pub max_disk_bytes: usize,
+    /// The size of the blocks the byte ranges of the objects are read and
+    /// cached in
+    pub block_size: usize,
+    /// The number of bytes at the end of each object cached in memory, where
+    /// file formats such as parquet keep their metadata
+    pub footer_size: usize,
+    /// The maximum number of bytes of the footers cached in memory
+    pub max_memory_bytes: usize,
+    /// The maximum number of objects whose versions are remembered. The
+    /// cached byte ranges of the other objects are not used until their
+    /// version is seen again.
+    pub max_objects: usize,
+    /// How long the version of an object seen by a request is used for,
+    /// before it is checked again with a head request
+    pub version_ttl: Duration,
+}
+
+impl Default for ObjectStoreCacheConfig {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            max_disk_bytes: 1024 * 1024 * 1024,
+            block_size: 1024 * 1024,
+            footer_size: 64 * 1024,
+            max_memory_bytes: 64 * 1024 * 1024,
+            max_objects: 100_000,
+            version_ttl: Duration::from_secs(60),
+        }
+    }
+}
+
+impl ObjectStoreCacheConfig {
+    /// Create the directory of the cached byte ranges in `directory`
+    pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Set the maximum number of bytes of the byte ranges cached on disk
+    pub fn with_max_disk_bytes(mut self, max_disk_bytes: usize) -> Self {
+        self.max_disk_bytes = max_disk_bytes;
+        self
+    }
+
+    /// Set the size of the blocks the byte ranges are read and cached in
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size;
+        self
+    }
+
+    /// Set the number of bytes at the end of each object cached in memory,
+    /// or 0 to cache the footers on disk like the other byte ranges
+    pub fn with_footer_size(mut self, footer_size: usize) -> Self {
+        self.footer_size = footer_size;
+        self
+    }
+
+    /// Set the maximum number of bytes of the footers cached in memory
+    pub fn with_max_memory_bytes(mut self, max_memory_bytes: usize) -> Self {
+        self.max_memory_bytes = max_memory_bytes;
+        self
+    }
+
+    /// Set the maximum number of objects whose versions are remembered
+    pub fn with_max_objects(mut self, max_objects: usize) -> Self {
+        self.max_objects = max_objects;
+        self
+    }
+
+    /// Set how long the version of an object is used for before it is
+    /// checked again
+    pub fn with_version_ttl(mut self, version_ttl: Duration) -> Self {
+        self.version_ttl = version_ttl;
+        self
+    }
+}
+
+/// A version of an object of an object store, identified by its ETag and
+/// modification time. The cached byte ranges of an object are not used once
+/// it has a new version.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ObjectVersion {
+    /// The url of the object store, see [`ObjectStoreUrl`]
+    ///
+    /// [`ObjectStoreUrl`]: crate::object_store::ObjectStoreUrl
+    store: String,
+    location: Path,
+    last_modified: DateTime<Utc>,
+    size: usize,
+    e_tag: Option<String>,
+    version: Option<String>,
+}
+
+impl ObjectVersion {
+    fn new(store: &str, meta: &ObjectMeta) -> Self {
+        Self {
+            store: store.to_string(),
+            location: meta.location.clone(),
+            last_modified: meta.last_modified,
+            size: meta.size,
+            e_tag: meta.e_tag.clone(),
+            version: meta.version.clone(),
+        }
+    }
+
+    fn meta(&self) -> ObjectMeta {
+        ObjectMeta {
+            location: self.location.clone(),
+            last_modified: self.last_modified,
+            size: self.size,
+            e_tag: self.e_tag.clone(),
+            version: self.version.clone(),
+        }
+    }
+}
+
+/// The version of an object and when it was seen
+type SeenVersion = (Arc<ObjectVersion>, Instant);
+
+/// A read cache of the objects of remote [`ObjectStore`]s, shared by the
+/// [`CachingObjectStore`]s that wrap them.
+///
+/// The objects are read in blocks of [`ObjectStoreCacheConfig::block_size`]
+/// that are cached in files on local disk, except for their footers, which
+/// are cached in memory. The cached blocks of an object are keyed by its
+/// path and its version, that is its ETag and modification time, as
+/// last seen by a request through a [`CachingObjectStore`]. The version of
+/// an object is checked again with a head request once it is older than
+/// [`ObjectStoreCacheConfig::version_ttl`].
+pub struct ObjectStoreCache {
+    config: ObjectStoreCacheConfig,
+    /// The directory of the files of the cached blocks
+    directory: TempDir,
+    /// The id of the file of the next cached block
+    next_file: AtomicU64,
+    /// The versions of the objects last seen and when they were seen, by
+    /// object store and path
+    objects: Mutex<LruEntries<(String, Path), SeenVersion>>,
+    /// The files of the cached blocks
+    blocks: Mutex<LruEntries<(Arc<ObjectVersion>, usize), PathBuf>>,
+    /// The footers of the objects
+    footers: Mutex<LruEntries<Arc<ObjectVersion>, Bytes>>,
+    block_stats: CacheStatsCounter,
+    footer_stats: CacheStatsCounter,
+}
+
+impl Debug for ObjectStoreCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObjectStoreCache")
+            .field("config", &self.config)
+            .field("directory", &self.directory.path())
+            .finish()
+    }
+}
+
+impl ObjectStoreCache {
+    /// Create a cache with the given configuration
+    pub fn try_new(config: ObjectStoreCacheConfig) -> Result<Self> {
+        if config.block_size == 0 {
+            return Err(DataFusionError::Configuration(
+                "The block size of the object store cache must be positive".into(),
+            ));
+        }
+        let mut builder = tempfile::Builder::new();
+        builder.prefix("datafusion-object-store-cache");
+        let directory = match &config.directory {
+            Some(directory) => builder.tempdir_in(directory),
+            None => builder.tempdir(),
+        }
+        .map_err(DataFusionError::IoError)?;
+        debug!(
+            "Created directory '{}' for the object store cache",
+            directory.path().to_string_lossy()
+        );
+
+        Ok(Self {
+            objects: Mutex::new(LruEntries::new(config.max_objects)),
+            blocks: Mutex::new(LruEntries::new(config.max_disk_bytes)),
+            footers: Mutex::new(LruEntries::new(config.max_memory_bytes)),
+            config,
+            directory,
+            next_file: AtomicU64::new(0),
+            block_stats: CacheStatsCounter::default(),
+            footer_stats: CacheStatsCounter::default(),
+        })
+    }
+
+    /// The configuration of this cache
+    pub fn config(&self) -> &ObjectStoreCacheConfig {
+        &self.config
+    }
+
+    /// The number of blocks cached on disk
+    pub fn block_count(&self) -> usize {
+        self.blocks.lock().len()
+    }
+
+    /// The number of bytes of the blocks cached on disk
+    pub fn disk_bytes(&self) -> usize {
+        self.blocks.lock().size()
+    }
+
+    /// The lookups of the blocks cached on disk
+    pub fn block_stats(&self) -> CacheStats {
+        self.block_stats.stats()
+    }
+
+    /// The number of footers cached in memory
+    pub fn footer_count(&self) -> usize {
+        self.footers.lock().len()
+    }
+
+    /// The number of bytes of the footers cached in memory
+    pub fn memory_bytes(&self) -> usize {
+        self.footers.lock().size()
+    }
+
+    /// The lookups of the footers cached in memory
+    pub fn footer_stats(&self) -> CacheStats {
+        self.footer_stats.stats()
+    }
+
+    /// Remove all the cached blocks and footers
+    pub fn clear(&self) {
+        self.objects.lock().clear();
+        self.footers.lock().clear();
+        let files = self.blocks.lock().clear();
+        remove_files(files);
+    }
+
+    /// The number of objects whose versions are remembered
+    pub fn object_count(&self) -> usize {
+        self.objects.lock().len()
+    }
+
+    /// Record the version of an object seen by a request
+    fn record_object(&self, store: &str, meta: &ObjectMeta) -> Arc<ObjectVersion> {
+        let version = Arc::new(ObjectVersion::new(store, meta));
+        let key = (version.store.clone(), version.location.clone());
+        self.objects
+            .lock()
+            .insert(key, (version.clone(), Instant::now()), 1);
+        version
+    }
+
+    /// Forget the version of an object that is changed through the cache
+    fn forget_object(&self, store: &str, location: &Path) {
+        self.objects
+            .lock()
+            .remove(&(store.to_string(), location.clone()));
+    }
+
+    /// The version of an object, if it was seen within the version TTL
+    fn object(&self, store: &str, location: &Path) -> Option<Arc<ObjectVersion>> {
+        let key = (store.to_string(), location.clone());
+        let (version, seen) = self.objects.lock().get(&key)?;
+        (seen.elapsed() < self.config.version_ttl).then_some(version)
+    }
+
+    fn get_footer(&self, object: &Arc<ObjectVersion>) -> Option<Bytes> {
+        self.footer_stats.record(self.footers.lock().get(object))
+    }
+
+    fn put_footer(&self, object: Arc<ObjectVersion>, footer: Bytes) {
+        let size = footer.len();
+        self.footers.lock().insert(object, footer, size);
+    }
+
+    /// Read a cached block. The files of blocks that are evicted while they
+    /// are read are missing blocks.
+    async fn get_block(
+        &self,
+        object: &Arc<ObjectVersion>,
+        index: usize,
+    ) -> Option<Bytes> {
+        let file = self.blocks.lock().get(&(object.clone(), index));
+        let block = match file {
+            Some(file) => tokio::fs::read(file).await.ok().map(Bytes::from),
+            None => None,
+        };
+        self.block_stats.record(block)
+    }
+
+    async fn put_block(&self, object: Arc<ObjectVersion>, index: usize, block: &Bytes) {
+        let id = self.next_file.fetch_add(1, Ordering::Relaxed);
+        let file = self.directory.path().join(format!("block-{id}"));
+        if let Err(e) = tokio::fs::write(&file, block).await {
+            warn!("Failed to write block of the object store cache to {file:?}: {e}");
+            return;
+        }
+        let evicted = self
+            .blocks
+            .lock()
+            .insert((object, index), file, block.len());
+        for file in evicted {
+            if let Err(e) = tokio::fs::remove_file(&file).await {
+                warn!("Failed to remove block of the object store cache {file:?}: {e}");
+            }
+        }
+    }
+}
+
+impl Drop for ObjectStoreCache {
+    fn drop(&mut self) {
+        // the TempDir removes the files of the blocks
+        debug!(
+            "Removing directory '{}' of the object store cache",
+            self.directory.path().to_string_lossy()
+        );
+    }
+}
+
+fn remove_files(files: Vec<PathBuf>) {
+    for file in files {
+        if let Err(e) = std::fs::remove_file(&file) {
+            warn!("Failed to remove block of the object store cache {file:?}: {e}");
+        }
+    }
+}
+
+/// An [`ObjectStore`] that reads the byte ranges of the objects of another
+/// object store through an [`ObjectStoreCache`].
+///
+/// The reads of whole objects, and the conditional reads, are not cached.
+/// The other requests are passed to the wrapped object store, and the
+/// objects that are changed through this store are read again.
+#[derive(Debug)]
+pub struct CachingObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    cache: Arc<ObjectStoreCache>,
+    /// The url of `inner`, see [`ObjectStoreUrl`]
+    ///
+    /// [`ObjectStoreUrl`]: crate::object_store::ObjectStoreUrl
+    store: String,
+}
+
+impl Display for CachingObjectStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Caching({})", self.inner)
+    }
+}
+
+impl CachingObjectStore {
+    /// Wrap the object store `inner` of url `store`, such as `s3://bucket`
+    pub fn new(
+        inner: Arc<dyn ObjectStore>,
+        cache: Arc<ObjectStoreCache>,
+        store: impl Into<String>,
+    ) -> Self {
+        Self {
+            inner,
+            cache,
+            store: store.into(),
+        }
+    }
+
+    /// The wrapped object store
+    pub fn inner(&self) -> &Arc<dyn ObjectStore> {
+        &self.inner
+    }
+
+    /// The version of the object at `location`, from a head request if it
+    /// was not seen within the version TTL
+    async fn object(&self, location: &Path) -> object_store::Result<Arc<ObjectVersion>> {
+        if let Some(object) = self.cache.object(&self.store, location) {
+            return Ok(object);
+        }
+        let meta = self.inner.head(location).await?;
+        Ok(self.cache.record_object(&self.store, &meta))
+    }
+
+    /// Read `range` of the object at `location` from the cache, reading the
+    /// missing blocks, or the footer, from the wrapped object store
+    async fn cached_range(
+        &self,
+        location: &Path,
+        range: Range<usize>,
+    ) -> object_store::Result<Bytes> {
+        let object = self.object(location).await?;
+        if range.end > object.size || range.is_empty() {
+            // let the wrapped object store report the invalid ranges
+            return self.inner.get_range(location, range).await;
+        }
+
+        let footer_size = self.cache.config.footer_size;
+        let footer_start = object.size.saturating_sub(footer_size);
+        if footer_size > 0 && range.start >= footer_start {
+            let footer = match self.cache.get_footer(&object) {
+                Some(footer) => footer,
+                None => {
+                    let footer_range = footer_start..object.size;
+                    let footer = self.inner.get_range(location, footer_range).await?;
+                    self.cache.put_footer(object, footer.clone());
+                    footer
+                }
+            };
+            return Ok(footer.slice(range.start - footer_start..range.end - footer_start));
+        }
+
+        let block_size = self.cache.config.block_size;
+        let first_block = range.start / block_size;
+        let blocks = first_block..(range.end - 1) / block_size + 1;
+        let mut cached = futures::future::join_all(
+            blocks
+                .clone()
+                .map(|index| self.cache.get_block(&object, index)),
+        )
+        .await;
+
+        let missing: Vec<_> = blocks
+            .zip(&cached)
+            .filter(|(_, block)| block.is_none())
+            .map(|(index, _)| index)
+            .collect();
+        if !missing.is_empty() {
+            let ranges: Vec<_> = missing
+                .iter()
+                .map(|index| {
+                    index * block_size..((index + 1) * block_size).min(object.size)
+                })
+                .collect();
+            let fetched = self.inner.get_ranges(location, &ranges).await?;
+            for (index, block) in missing.into_iter().zip(fetched) {
+                self.cache.put_block(object.clone(), index, &block).await;
+                cached[index - first_block] = Some(block);
+            }
+        }
+
+        let offset = range.start - first_block * block_size;
+        let len = range.end - range.start;
+        match cached.as_slice() {
+            [Some(block)] => Ok(block.slice(offset..offset + len)),
+            blocks => {
+                let mut bytes = BytesMut::with_capacity(blocks.len() * block_size);
+                for block in blocks.iter().flatten() {
+                    bytes.extend_from_slice(block);
+                }
+                Ok(bytes.freeze().slice(offset..offset + len))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for CachingObjectStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        bytes: Bytes,
+        opts: PutOptions,
+    ) -> object_store::Result<PutResult> {
+        self.cache.forget_object(&self.store, location);
+        self.inner.put_opts(location, bytes, opts).await
+    }
+
+    async fn put_multipart(
+        &self,
+        location: &Path,
+    ) -> object_store::Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+        self.cache.forget_object(&self.store, location);
+        self.inner.put_multipart(location).await
+    }
+
+    async fn abort_multipart(
+        &self,
+        location: &Path,
+        multipart_id: &MultipartId,
+    ) -> object_store::Result<()> {
+        self.inner.abort_multipart(location, multipart_id).await
+    }
+
+    async fn get_opts(
+        &self,
+        location: &Path,
+        options: GetOptions,
+    ) -> object_store::Result<GetResult> {
+        let cacheable = options.if_match.is_none()
+            && options.if_none_match.is_none()
+            && options.if_modified_since.is_none()
+            && options.if_unmodified_since.is_none()
+            && options.version.is_none()
+            && !options.head;
+        match options.range.clone() {
+            Some(range) if cacheable => {
+                let bytes = self.cached_range(location, range.clone()).await?;
+                let meta = self.object(location).await?.meta();
+                let payload = futures::stream::once(async move { Ok(bytes) }).boxed();
+                Ok(GetResult {
+                    payload: GetResultPayload::Stream(payload),
+                    meta,
+                    range,
+                })
+            }
+            _ => {
+                let result = self.inner.get_opts(location, options).await?;
+                self.cache.record_object(&self.store, &result.meta);
+                Ok(result)
+            }
+        }
+    }
+
+    async fn get_range(
+        &self,
+        location: &Path,
+        range: Range<usize>,
+    ) -> object_store::Result<Bytes> {
+        self.cached_range(location, range).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<usize>],
+    ) -> object_store::Result<Vec<Bytes>> {
+        futures::future::try_join_all(
+            ranges
+                .iter()
+                .map(|range| self.cached_range(location, range.clone())),
+        )
+        .await
+    }
+
+    async fn head(&self, location: &Path) -> object_store::Result<ObjectMeta> {
+        let meta = self.inner.head(location).await?;
+        self.cache.record_object(&self.store, &meta);
+        Ok(meta)
+    }
+
+    async fn delete(&self, location: &Path) -> object_store::Result<()> {
+        self.cache.forget_object(&self.store, location);
+        self.inner.delete(location).await
+    }
+
+    fn list(
+        &self,
+        prefix: Option<&Path>,
+    ) -> BoxStream<'_, object_store::Result<ObjectMeta>> {
+        self.inner
+            .list(prefix)
+            .inspect_ok(|meta| {
+                self.cache.record_object(&self.store, meta);
+            })
+            .boxed()
+    }
+
+    async fn list_with_delimiter(
+        &self,
+        prefix: Option<&Path>,
+    ) -> object_store::Result<ListResult> {
+        let result = self.inner.list_with_delimiter(prefix).await?;
+        for meta in &result.objects {
+            self.cache.record_object(&self.store, meta);
+        }
+        Ok(result)
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        self.cache.forget_object(&self.store, to);
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        self.cache.forget_object(&self.store, from);
+        self.cache.forget_object(&self.store, to);
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(
+        &self,
+        from: &Path,
+        to: &Path,
+    ) -> object_store::Result<()> {
+        self.cache.forget_object(&self.store, to);
+        self.inner.copy_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+
+    fn caching_store(
+        config: ObjectStoreCacheConfig,
+    ) -> (Arc<InMemory>, Arc<ObjectStoreCache>, CachingObjectStore) {
+        let remote = Arc::new(InMemory::new());
+        let cache = Arc::new(ObjectStoreCache::try_new(config).unwrap());
+        let store = CachingObjectStore::new(remote.clone(), cache.clone(), "s3://bucket");
+        (remote, cache, store)
+    }
+
+    fn data(len: usize) -> Bytes {
+        (0..len).map(|i| i as u8).collect::<Vec<_>>().into()
+    }
+
+    #[tokio::test]
+    async fn cached_blocks_and_footers() {
+        let config = ObjectStoreCacheConfig::default()
+            .with_block_size(10)
+            .with_footer_size(8);
+        let (remote, cache, store) = caching_store(config);
+        let location = Path::from("data");
+        remote.put(&location, data(50)).await.unwrap();
+
+        let bytes = store.get_range(&location, 5..25).await.unwrap();
+        assert_eq!(bytes, data(50).slice(5..25));
+        assert_eq!(cache.block_stats(), CacheStats { hits: 0, misses: 3 });
+        assert_eq!(cache.block_count(), 3);
+        assert_eq!(cache.disk_bytes(), 30);
+
+        let bytes = store.get_range(&location, 12..18).await.unwrap();
+        assert_eq!(bytes, data(50).slice(12..18));
+        assert_eq!(cache.block_stats(), CacheStats { hits: 1, misses: 3 });
+
+        let ranges = store.get_ranges(&location, &[0..4, 12..14]).await.unwrap();
+        assert_eq!(ranges, vec![data(50).slice(0..4), data(50).slice(12..14)]);
+        assert_eq!(cache.block_stats(), CacheStats { hits: 3, misses: 3 });
+
+        let bytes = store.get_range(&location, 44..48).await.unwrap();
+        assert_eq!(bytes, data(50).slice(44..48));
+        let bytes = store.get_range(&location, 46..50).await.unwrap();
+        assert_eq!(bytes, data(50).slice(46..50));
+        assert_eq!(cache.footer_stats(), CacheStats { hits: 1, misses: 1 });
+        assert_eq!(cache.footer_count(), 1);
+        assert_eq!(cache.memory_bytes(), 8);
+
+        let result = store
+            .get_opts(
+                &location,
+                GetOptions {
+                    range: Some(0..4),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.meta.size, 50);
+        assert_eq!(result.bytes().await.unwrap(), data(50).slice(0..4));
+        assert_eq!(cache.block_stats(), CacheStats { hits: 4, misses: 3 });
+
+        // whole objects are read from the remote store
+        let bytes = store.get(&location).await.unwrap().bytes().await.unwrap();
+        assert_eq!(bytes, data(50));
+        assert_eq!(cache.block_stats(), CacheStats { hits: 4, misses: 3 });
+
+        cache.clear();
+        assert_eq!(cache.block_count(), 0);
+        assert_eq!(cache.footer_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn changed_objects() {
+        let config = ObjectStoreCacheConfig::default().with_block_size(10);
+        let (remote, cache, store) = caching_store(config);
+        let location = Path::from("data");
+        remote.put(&location, data(20)).await.unwrap();
+
+        store.head(&location).await.unwrap();
+        store.get_range(&location, 0..10).await.unwrap();
+        assert_eq!(cache.footer_stats().misses, 1);
+
+        // a new version of the object, seen by a list request
+        remote
+            .put(&location, Bytes::from(vec![7; 30]))
+            .await
+            .unwrap();
+        let objects: Vec<_> = store.list(None).try_collect().await.unwrap();
+        assert_eq!(objects.len(), 1);
+        let bytes = store.get_range(&location, 20..30).await.unwrap();
+        assert_eq!(bytes, Bytes::from(vec![7; 10]));
+        assert_eq!(cache.footer_stats(), CacheStats { hits: 0, misses: 2 });
+
+        // an object written through the cache
+        store.put(&location, data(20)).await.unwrap();
+        let bytes = store.get_range(&location, 0..10).await.unwrap();
+        assert_eq!(bytes, data(20).slice(0..10));
+        assert_eq!(cache.footer_stats(), CacheStats { hits: 0, misses: 3 });
+    }
+
+    #[tokio::test]
+    async fn revalidated_objects() {
+        let config = ObjectStoreCacheConfig::default()
+            .with_block_size(10)
+            .with_footer_size(0)
+            .with_max_objects(1)
+            .with_version_ttl(Duration::ZERO);
+        let (remote, cache, store) = caching_store(config);
+        let location = Path::from("data");
+        remote.put(&location, data(20)).await.unwrap();
+
+        store.get_range(&location, 0..10).await.unwrap();
+        store.get_range(&location, 0..10).await.unwrap();
+        assert_eq!(cache.block_stats(), CacheStats { hits: 1, misses: 1 });
+
+        // a new version of the object, not seen by any request
+        remote
+            .put(&location, Bytes::from(vec![7; 20]))
+            .await
+            .unwrap();
+        let bytes = store.get_range(&location, 0..10).await.unwrap();
+        assert_eq!(bytes, Bytes::from(vec![7; 10]));
+        assert_eq!(cache.block_stats(), CacheStats { hits: 1, misses: 2 });
+
+        // only the version of the last object is remembered
+        let other = Path::from("other");
+        remote.put(&other, data(20)).await.unwrap();
+        store.get_range(&other, 0..10).await.unwrap();
+        assert_eq!(cache.object_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn evicted_blocks() {
+        let directory = tempfile::tempdir().unwrap();
+        let config = ObjectStoreCacheConfig::default()
+            .with_directory(directory.path())
+            .with_block_size(10)
+            .with_footer_size(0)
+            .with_max_disk_bytes(25);
+        let (remote, cache, store) = caching_store(config);
+        let location = Path::from("data");
+        remote.put(&location, data(50)).await.unwrap();
+
+        store.get_range(&location, 0..30).await.unwrap();
+        assert_eq!(cache.block_count(), 2);
+        assert_eq!(cache.disk_bytes(), 20);
+
+        // the first block was evicted and its file removed
+        store.get_range(&location, 20..25).await.unwrap();
+        assert_eq!(cache.block_stats(), CacheStats { hits: 1, misses: 3 });
+        store.get_range(&location, 0..5).await.unwrap();
+        assert_eq!(cache.block_stats(), CacheStats { hits: 1, misses: 4 });
+
+        let cache_dir = std::fs::read_dir(directory.path())
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 2);
+
+        drop(store);
+        drop(cache);
+        assert!(!cache_dir.exists());
+    }
+}
@@ -0,0 +1,108 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`CancellationToken`] to stop a running query
+
+use datafusion_common::{DataFusionError, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Signals the operators of a query that it should stop.
+///
+/// A query is cancelled when [`Self::cancel`] is called on any clone of the
+/// token, or when its timeout, if any, has elapsed. Operators observe the
+/// token by calling [`Self::check`] at points where they can stop, such as
+/// between batches, which then returns a [`DataFusionError::Cancelled`].
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    /// Shared between all the clones of this token
+    cancelled: Arc<AtomicBool>,
+    /// The timeout and the instant it elapses, if any
+    deadline: Option<(Duration, Instant)>,
+}
+
+impl CancellationToken {
+    /// Create a new token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the query once `timeout` has elapsed from now
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Some((timeout, Instant::now() + timeout));
+        self
+    }
+
+    /// Cancel the query
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if the query was cancelled or its timeout has elapsed
+    pub fn is_cancelled(&self) -> bool {
+        self.check().is_err()
+    }
+
+    /// Returns a [`DataFusionError::Cancelled`] if the query was cancelled
+    /// or its timeout has elapsed
+    pub fn check(&self) -> Result<()> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Err(DataFusionError::Cancelled("cancel requested".to_string()));
+        }
+        match self.deadline {
+            Some((timeout, deadline)) if Instant::now() >= deadline => {
+                Err(DataFusionError::Cancelled(format!(
+                    "statement timeout of {timeout:?} exceeded"
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_clones() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        token.check().unwrap();
+
+        clone.cancel();
+        assert!(token.is_cancelled());
+        assert_eq!(
+            token.check().unwrap_err().strip_backtrace(),
+            "Query cancelled: cancel requested"
+        );
+    }
+
+    #[test]
+    fn cancel_timeout() {
+        let token = CancellationToken::new().with_timeout(Duration::from_secs(3600));
+        token.check().unwrap();
+
+        let token = CancellationToken::new().with_timeout(Duration::ZERO);
+        assert_eq!(
+            token.check().unwrap_err().strip_backtrace(),
+            "Query cancelled: statement timeout of 0ns exceeded"
+        );
+    }
+}
@@ -119,6 +119,16 @@ impl SessionConfig {
         self.options.catalog.information_schema
     }
 
+    /// Is the system schema enabled?
+    pub fn system_schema(&self) -> bool {
+        self.options.catalog.system_schema
+    }
+
+    /// Are queries executed by the morsel-driven scheduler?
+    pub fn morsel_scheduler(&self) -> bool {
+        self.options.execution.use_morsel_scheduler
+    }
+
     /// Should the context create the default catalog and schema?
     pub fn create_default_catalog_and_schema(&self) -> bool {
         self.options.catalog.create_default_catalog_and_schema
@@ -181,6 +191,19 @@ impl SessionConfig {
         self
     }
 
+    /// Enables or disables the inclusion of `system` virtual tables
+    pub fn with_system_schema(mut self, enabled: bool) -> Self {
+        self.options.catalog.system_schema = enabled;
+        self
+    }
+
+    /// Enables or disables the execution of queries by the morsel-driven
+    /// scheduler
+    pub fn with_morsel_scheduler(mut self, enabled: bool) -> Self {
+        self.options.execution.use_morsel_scheduler = enabled;
+        self
+    }
+
     /// Enables or disables the use of repartitioning for joins to improve parallelism
     pub fn with_repartition_joins(mut self, enabled: bool) -> Self {
         self.options.optimizer.repartition_joins = enabled;
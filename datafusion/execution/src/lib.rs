@@ -18,6 +18,7 @@
 //! DataFusion execution configuration and runtime structures
 
 pub mod cache;
+pub mod cancellation;
 pub mod config;
 pub mod disk_manager;
 pub mod memory_pool;
@@ -0,0 +1,386 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::memory_pool::{
+    human_readable_size, FairSpillPool, GreedyMemoryPool, MemoryConsumer,
+    MemoryConsumerUsage, MemoryPool, MemoryReservation, UnboundedMemoryPool,
+};
+use datafusion_common::config::MemoryPoolPolicy;
+use datafusion_common::{DataFusionError, Result};
+use log::debug;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// The maximum number of consumers listed in a resources exhausted error
+const TOP_CONSUMERS_IN_ERROR: usize = 3;
+
+/// A [`MemoryPool`] that is a level of a tree of memory pools, such as
+/// a process-wide root, per-session children and per-query grandchildren.
+///
+/// Each level has its own `policy` pool enforcing the limit and spill
+/// policy of that level, for example a [`FairSpillPool`]. Reservations
+/// are forwarded to the parent, so a reservation only succeeds if every
+/// level from the pool up to the root can satisfy it.
+///
+/// ```text
+///                    ┌────────────────────┐
+///                    │ root (RuntimeEnv)  │
+///                    └────────────────────┘
+///                      ▲                ▲
+///          ┌───────────┴──┐          ┌──┴───────────┐
+///          │  session 1   │          │  session 2   │
+///          └──────────────┘          └──────────────┘
+///            ▲          ▲                   ▲
+///     ┌──────┴──┐  ┌────┴────┐         ┌────┴────┐
+///     │ query 1 │  │ query 2 │         │ query 3 │
+///     └─────────┘  └─────────┘         └─────────┘
+/// ```
+///
+/// Every level tracks the current and peak reservation of the consumers
+/// registered with it or any of its descendants, see
+/// [`MemoryPool::consumers`], and the peak of its total reservation, see
+/// [`Self::peak_reserved`]. When this level refuses a reservation, the
+/// error names its largest consumers.
+#[derive(Debug)]
+pub struct HierarchicalMemoryPool {
+    /// Name of this level, used in error messages
+    name: String,
+    /// Enforces the limit and spill policy of this level
+    policy: Arc<dyn MemoryPool>,
+    /// The next level up, if any
+    parent: Option<Arc<dyn MemoryPool>>,
+    /// Usage of the live consumers, keyed by [`MemoryConsumer::id`]
+    consumers: Mutex<HashMap<usize, MemoryConsumerUsage>>,
+    /// High-water mark of the total reservation of this level
+    peak: AtomicUsize,
+}
+
+impl HierarchicalMemoryPool {
+    /// Create a new root level named `name`, enforcing `policy`
+    pub fn new(name: impl Into<String>, policy: Arc<dyn MemoryPool>) -> Self {
+        let name = name.into();
+        debug!("Created new HierarchicalMemoryPool(name={name})");
+        Self {
+            name,
+            policy,
+            parent: None,
+            consumers: Mutex::new(HashMap::new()),
+            peak: AtomicUsize::new(0),
+        }
+    }
+
+    /// Create a new level named `name` with an optional `limit` in bytes,
+    /// shared between consumers according to `policy`
+    pub fn new_with_limit(
+        name: impl Into<String>,
+        limit: Option<usize>,
+        policy: MemoryPoolPolicy,
+    ) -> Self {
+        let policy: Arc<dyn MemoryPool> = match (limit, policy) {
+            (None, _) => Arc::new(UnboundedMemoryPool::default()),
+            (Some(limit), MemoryPoolPolicy::Greedy) => {
+                Arc::new(GreedyMemoryPool::new(limit))
+            }
+            (Some(limit), MemoryPoolPolicy::Fair) => Arc::new(FairSpillPool::new(limit)),
+        };
+        Self::new(name, policy)
+    }
+
+    /// Make this level a child of `parent`
+    pub fn with_parent(mut self, parent: Arc<dyn MemoryPool>) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Returns the name of this level
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the largest total reservation of this level so far,
+    /// including the consumers that have since been unregistered
+    pub fn peak_reserved(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    /// Records `additional` bytes reserved by `reservation` at this level
+    fn record_grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.peak
+            .fetch_max(self.policy.reserved(), Ordering::Relaxed);
+        if let Some(usage) = self.consumers.lock().get_mut(&reservation.consumer().id()) {
+            usage.reserved += additional;
+            usage.peak = usage.peak.max(usage.reserved);
+        }
+    }
+
+    /// Adds the largest consumers of this level to a resources exhausted error
+    fn annotate_err(&self, e: DataFusionError) -> DataFusionError {
+        let DataFusionError::ResourcesExhausted(msg) = e else {
+            return e;
+        };
+
+        let mut consumers = self.consumers();
+        consumers.sort_by_key(|c| std::cmp::Reverse(c.reserved));
+        let top = consumers
+            .iter()
+            .take(TOP_CONSUMERS_IN_ERROR)
+            .map(|c| {
+                format!(
+                    "{} with {} (peak {})",
+                    c.name,
+                    human_readable_size(c.reserved),
+                    human_readable_size(c.peak)
+                )
+            })
+            .collect::<Vec<_>>();
+
+        DataFusionError::ResourcesExhausted(format!(
+            "{msg} in memory pool {}. Top memory consumers: [{}]",
+            self.name,
+            top.join(", ")
+        ))
+    }
+}
+
+impl MemoryPool for HierarchicalMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.policy.register(consumer);
+        if let Some(parent) = &self.parent {
+            parent.register(consumer);
+        }
+        self.consumers.lock().insert(
+            consumer.id(),
+            MemoryConsumerUsage {
+                id: consumer.id(),
+                name: consumer.name().to_string(),
+                can_spill: consumer.can_spill(),
+                reserved: 0,
+                peak: 0,
+            },
+        );
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.consumers.lock().remove(&consumer.id());
+        self.policy.unregister(consumer);
+        if let Some(parent) = &self.parent {
+            parent.unregister(consumer);
+        }
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.policy.grow(reservation, additional);
+        if let Some(parent) = &self.parent {
+            parent.grow(reservation, additional);
+        }
+        self.record_grow(reservation, additional);
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.policy.shrink(reservation, shrink);
+        if let Some(parent) = &self.parent {
+            parent.shrink(reservation, shrink);
+        }
+        if let Some(usage) = self.consumers.lock().get_mut(&reservation.consumer().id()) {
+            usage.reserved = usage.reserved.saturating_sub(shrink);
+        }
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> Result<()> {
+        self.policy
+            .try_grow(reservation, additional)
+            .map_err(|e| self.annotate_err(e))?;
+
+        if let Some(parent) = &self.parent {
+            if let Err(e) = parent.try_grow(reservation, additional) {
+                self.policy.shrink(reservation, additional);
+                return Err(e);
+            }
+        }
+
+        self.record_grow(reservation, additional);
+        Ok(())
+    }
+
+    fn reserved(&self) -> usize {
+        self.policy.reserved()
+    }
+
+    fn consumers(&self) -> Vec<MemoryConsumerUsage> {
+        let mut consumers: Vec<_> = self.consumers.lock().values().cloned().collect();
+        consumers.sort_by_key(|c| c.id);
+        consumers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(pool: &Arc<dyn MemoryPool>) -> Vec<(String, usize, usize)> {
+        pool.consumers()
+            .into_iter()
+            .map(|c| (c.name, c.reserved, c.peak))
+            .collect()
+    }
+
+    #[test]
+    fn test_hierarchical_limits() {
+        let root: Arc<dyn MemoryPool> = Arc::new(HierarchicalMemoryPool::new_with_limit(
+            "root",
+            Some(100),
+            MemoryPoolPolicy::Greedy,
+        ));
+        let session: Arc<dyn MemoryPool> = Arc::new(
+            HierarchicalMemoryPool::new_with_limit(
+                "session",
+                Some(60),
+                MemoryPoolPolicy::Greedy,
+            )
+            .with_parent(Arc::clone(&root)),
+        );
+        let query1: Arc<dyn MemoryPool> = Arc::new(
+            HierarchicalMemoryPool::new_with_limit(
+                "query1",
+                Some(40),
+                MemoryPoolPolicy::Greedy,
+            )
+            .with_parent(Arc::clone(&session)),
+        );
+        let query2: Arc<dyn MemoryPool> = Arc::new(
+            HierarchicalMemoryPool::new_with_limit(
+                "query2",
+                None,
+                MemoryPoolPolicy::Greedy,
+            )
+            .with_parent(Arc::clone(&session)),
+        );
+
+        let mut r1 = MemoryConsumer::new("r1").register(&query1);
+        let mut r2 = MemoryConsumer::new("r2").register(&query2);
+
+        // limited by the query
+        let err = r1.try_grow(50).unwrap_err().strip_backtrace();
+        assert_eq!(err, "Resources exhausted: Failed to allocate additional 50 bytes for r1 with 0 bytes already allocated - maximum available is 40 in memory pool query1. Top memory consumers: [r1 with 0.0 B (peak 0.0 B)]");
+        r1.try_grow(30).unwrap();
+
+        // limited by the session, nothing is reserved in the query on failure
+        let err = r2.try_grow(40).unwrap_err().strip_backtrace();
+        assert_eq!(err, "Resources exhausted: Failed to allocate additional 40 bytes for r2 with 0 bytes already allocated - maximum available is 30 in memory pool session. Top memory consumers: [r1 with 30.0 B (peak 30.0 B), r2 with 0.0 B (peak 0.0 B)]");
+        assert_eq!(query2.reserved(), 0);
+        r2.try_grow(30).unwrap();
+
+        assert_eq!(query1.reserved(), 30);
+        assert_eq!(query2.reserved(), 30);
+        assert_eq!(session.reserved(), 60);
+        assert_eq!(root.reserved(), 60);
+
+        // infallible growth is recorded on every level
+        r2.grow(10);
+        assert_eq!(root.reserved(), 70);
+        r2.shrink(35);
+        assert_eq!(query2.reserved(), 5);
+        assert_eq!(root.reserved(), 35);
+
+        assert_eq!(
+            usage(&root),
+            vec![("r1".to_string(), 30, 30), ("r2".to_string(), 5, 40)]
+        );
+        assert_eq!(usage(&query1), vec![("r1".to_string(), 30, 30)]);
+
+        drop(r1);
+        assert_eq!(usage(&session), vec![("r2".to_string(), 5, 40)]);
+        assert_eq!(root.reserved(), 5);
+
+        drop(r2);
+        assert!(root.consumers().is_empty());
+        assert_eq!(root.reserved(), 0);
+    }
+
+    #[test]
+    fn test_hierarchical_peak() {
+        let root = Arc::new(HierarchicalMemoryPool::new(
+            "root",
+            Arc::new(UnboundedMemoryPool::default()),
+        ));
+        let query = Arc::new(
+            HierarchicalMemoryPool::new(
+                "query",
+                Arc::new(UnboundedMemoryPool::default()),
+            )
+            .with_parent(Arc::clone(&root) as _),
+        );
+        let pool: Arc<dyn MemoryPool> = Arc::clone(&query) as _;
+
+        let mut r1 = MemoryConsumer::new("r1").register(&pool);
+        let mut r2 = MemoryConsumer::new("r2").register(&pool);
+        r1.try_grow(30).unwrap();
+        r2.grow(20);
+        r1.shrink(30);
+        r2.try_grow(10).unwrap();
+
+        // the peak outlives the consumers
+        drop(r1);
+        drop(r2);
+        assert_eq!(query.reserved(), 0);
+        assert_eq!(query.peak_reserved(), 50);
+        assert_eq!(root.peak_reserved(), 50);
+    }
+
+    #[test]
+    fn test_hierarchical_fair() {
+        let root: Arc<dyn MemoryPool> = Arc::new(HierarchicalMemoryPool::new(
+            "root",
+            Arc::new(UnboundedMemoryPool::default()),
+        ));
+        let query: Arc<dyn MemoryPool> = Arc::new(
+            HierarchicalMemoryPool::new_with_limit(
+                "query",
+                Some(100),
+                MemoryPoolPolicy::Fair,
+            )
+            .with_parent(Arc::clone(&root)),
+        );
+
+        let mut r1 = MemoryConsumer::new("r1")
+            .with_can_spill(true)
+            .register(&query);
+        let mut r2 = MemoryConsumer::new("r2")
+            .with_can_spill(true)
+            .register(&query);
+
+        // each spillable consumer gets half of the query limit
+        r1.try_grow(50).unwrap();
+        r1.try_grow(1).unwrap_err();
+        r2.try_grow(50).unwrap();
+        assert_eq!(root.reserved(), 100);
+
+        // split reservations share their consumer
+        let r3 = r1.split(20);
+        assert_eq!(
+            usage(&root),
+            vec![("r1".to_string(), 50, 50), ("r2".to_string(), 50, 50)]
+        );
+        drop(r3);
+        assert_eq!(
+            usage(&root),
+            vec![("r1".to_string(), 30, 50), ("r2".to_string(), 50, 50)]
+        );
+    }
+}
@@ -18,11 +18,14 @@
 //! Manages all available memory during query execution
 
 use datafusion_common::Result;
+use std::sync::atomic::AtomicUsize;
 use std::{cmp::Ordering, sync::Arc};
 
+mod hierarchical;
 mod pool;
 pub mod proxy;
 
+pub use hierarchical::*;
 pub use pool::*;
 
 /// The pool of memory on which [`MemoryReservation`]s record their
@@ -49,6 +52,7 @@ pub use pool::*;
 /// * [`UnboundedMemoryPool`]
 /// * [`GreedyMemoryPool`]
 /// * [`FairSpillPool`]
+/// * [`HierarchicalMemoryPool`]
 pub trait MemoryPool: Send + Sync + std::fmt::Debug {
     /// Registers a new [`MemoryConsumer`]
     ///
@@ -75,13 +79,25 @@ pub trait MemoryPool: Send + Sync + std::fmt::Debug {
 
     /// Return the total amount of memory reserved
     fn reserved(&self) -> usize;
+
+    /// Return the current and peak memory usage of every live
+    /// [`MemoryConsumer`] registered with this pool
+    ///
+    /// Pools that do not track their consumers return an empty list
+    fn consumers(&self) -> Vec<MemoryConsumerUsage> {
+        vec![]
+    }
 }
 
+/// Source of the unique [`MemoryConsumer::id`]s
+static NEXT_CONSUMER_ID: AtomicUsize = AtomicUsize::new(0);
+
 /// A memory consumer that can be tracked by [`MemoryReservation`] in
 /// a [`MemoryPool`]. All allocations are registered to a particular
 /// `MemoryConsumer`;
 #[derive(Debug)]
 pub struct MemoryConsumer {
+    id: usize,
     name: String,
     can_spill: bool,
 }
@@ -90,6 +106,7 @@ impl MemoryConsumer {
     /// Create a new empty [`MemoryConsumer`] that can be grown using [`MemoryReservation`]
     pub fn new(name: impl Into<String>) -> Self {
         Self {
+            id: NEXT_CONSUMER_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
             name: name.into(),
             can_spill: false,
         }
@@ -110,6 +127,11 @@ impl MemoryConsumer {
         &self.name
     }
 
+    /// Returns the process wide unique id of this consumer
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
     /// Registers this [`MemoryConsumer`] with the provided [`MemoryPool`] returning
     /// a [`MemoryReservation`] that can be used to grow or shrink the memory reservation
     pub fn register(self, pool: &Arc<dyn MemoryPool>) -> MemoryReservation {
@@ -124,6 +146,21 @@ impl MemoryConsumer {
     }
 }
 
+/// The memory usage of a [`MemoryConsumer`], see [`MemoryPool::consumers`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryConsumerUsage {
+    /// The [`MemoryConsumer::id`]
+    pub id: usize,
+    /// The [`MemoryConsumer::name`]
+    pub name: String,
+    /// Whether the consumer can spill to disk
+    pub can_spill: bool,
+    /// The number of bytes currently reserved
+    pub reserved: usize,
+    /// The largest number of bytes reserved at the same time
+    pub peak: usize,
+}
+
 /// A registration of a [`MemoryConsumer`] with a [`MemoryPool`].
 ///
 /// Calls [`MemoryPool::unregister`] on drop to return any memory to
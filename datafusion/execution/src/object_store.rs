@@ -210,7 +210,7 @@ impl ObjectStoreRegistry for DefaultObjectStoreRegistry {
 
 /// Get the key of a url for object store registration.
 /// The credential info will be removed
-fn get_url_key(url: &Url) -> String {
+pub(crate) fn get_url_key(url: &Url) -> String {
     format!(
         "{}://{}",
         url.scheme(),
@@ -21,11 +21,12 @@
 use crate::{
     disk_manager::{DiskManager, DiskManagerConfig},
     memory_pool::{GreedyMemoryPool, MemoryPool, UnboundedMemoryPool},
-    object_store::{DefaultObjectStoreRegistry, ObjectStoreRegistry},
+    object_store::{get_url_key, DefaultObjectStoreRegistry, ObjectStoreRegistry},
 };
 
 use crate::cache::cache_manager::{CacheManager, CacheManagerConfig};
-use datafusion_common::{DataFusionError, Result};
+use crate::cache::object_store_cache::{CachingObjectStore, ObjectStoreCacheConfig};
+use datafusion_common::Result;
 use object_store::ObjectStore;
 use std::fmt::{Debug, Formatter};
 use std::path::PathBuf;
@@ -100,10 +101,18 @@ impl RuntimeEnv {
     /// Retrieves a `ObjectStore` instance for a url by consulting the
     /// registry. See [`ObjectStoreRegistry::get_store`] for more
     /// details.
+    ///
+    /// If the [`CacheManager`] has an object store cache, the object stores
+    /// of urls other than `file://` urls read through it.
     pub fn object_store(&self, url: impl AsRef<Url>) -> Result<Arc<dyn ObjectStore>> {
-        self.object_store_registry
-            .get_store(url.as_ref())
-            .map_err(DataFusionError::from)
+        let url = url.as_ref();
+        let store = self.object_store_registry.get_store(url)?;
+        match self.cache_manager.get_object_store_cache() {
+            Some(cache) if url.scheme() != "file" => Ok(Arc::new(
+                CachingObjectStore::new(store, cache, get_url_key(url)),
+            )),
+            _ => Ok(store),
+        }
     }
 }
 
@@ -163,6 +172,12 @@ impl RuntimeConfig {
         self
     }
 
+    /// Read the objects of remote object stores through a cache on local disk
+    pub fn with_object_store_cache(mut self, config: ObjectStoreCacheConfig) -> Self {
+        self.cache_manager.object_store_cache = Some(config);
+        self
+    }
+
     /// Customize object store registry
     pub fn with_object_store_registry(
         mut self,
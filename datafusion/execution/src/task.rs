@@ -27,6 +27,7 @@ use datafusion_common::{
 use datafusion_expr::{AggregateUDF, ScalarUDF, WindowUDF};
 
 use crate::{
+    cancellation::CancellationToken,
     config::SessionConfig,
     memory_pool::MemoryPool,
     registry::FunctionRegistry,
@@ -56,6 +57,10 @@ pub struct TaskContext {
     window_functions: HashMap<String, Arc<WindowUDF>>,
     /// Runtime environment associated with this task context
     runtime: Arc<RuntimeEnv>,
+    /// Memory pool of the query, defaults to the pool of the runtime
+    memory_pool: Arc<dyn MemoryPool>,
+    /// Signals that the query should stop
+    cancellation_token: CancellationToken,
 }
 
 impl Default for TaskContext {
@@ -71,7 +76,9 @@ impl Default for TaskContext {
             scalar_functions: HashMap::new(),
             aggregate_functions: HashMap::new(),
             window_functions: HashMap::new(),
+            memory_pool: runtime.memory_pool.clone(),
             runtime: Arc::new(runtime),
+            cancellation_token: CancellationToken::new(),
         }
     }
 }
@@ -98,7 +105,9 @@ impl TaskContext {
             scalar_functions,
             aggregate_functions,
             window_functions,
+            memory_pool: runtime.memory_pool.clone(),
             runtime,
+            cancellation_token: CancellationToken::new(),
         }
     }
 
@@ -150,9 +159,15 @@ impl TaskContext {
         self.task_id.clone()
     }
 
-    /// Return the [`MemoryPool`] associated with this [TaskContext]
+    /// Return the [`MemoryPool`] operators of this [TaskContext] reserve
+    /// memory from
     pub fn memory_pool(&self) -> &Arc<dyn MemoryPool> {
-        &self.runtime.memory_pool
+        &self.memory_pool
+    }
+
+    /// Return the [`CancellationToken`] of the query of this [TaskContext]
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation_token
     }
 
     /// Return the [RuntimeEnv] associated with this [TaskContext]
@@ -166,11 +181,31 @@ impl TaskContext {
         self
     }
 
-    /// Update the [`RuntimeEnv`]
+    /// Update the [`RuntimeEnv`], resetting the [`MemoryPool`] to the
+    /// pool of the runtime
     pub fn with_runtime(mut self, runtime: Arc<RuntimeEnv>) -> Self {
+        self.memory_pool = runtime.memory_pool.clone();
         self.runtime = runtime;
         self
     }
+
+    /// Update the [`MemoryPool`], such as a per-query
+    /// [`HierarchicalMemoryPool`] that is a child of the pool of the runtime
+    ///
+    /// [`HierarchicalMemoryPool`]: crate::memory_pool::HierarchicalMemoryPool
+    pub fn with_memory_pool(mut self, memory_pool: Arc<dyn MemoryPool>) -> Self {
+        self.memory_pool = memory_pool;
+        self
+    }
+
+    /// Update the [`CancellationToken`]
+    pub fn with_cancellation_token(
+        mut self,
+        cancellation_token: CancellationToken,
+    ) -> Self {
+        self.cancellation_token = cancellation_token;
+        self
+    }
 }
 
 impl FunctionRegistry for TaskContext {
@@ -102,6 +102,16 @@ pub enum AggregateFunction {
     BoolOr,
     /// string_agg
     StringAgg,
+    /// HyperLogLog sketch of the distinct values
+    HllSketch,
+    /// Union of HyperLogLog sketches
+    HllUnion,
+    /// KLL sketch of the quantiles
+    KllSketch,
+    /// Merge of KLL sketches
+    KllMerge,
+    /// Space-Saving sketch of the most frequent values
+    ApproxTopKSketch,
 }
 
 impl AggregateFunction {
@@ -144,6 +154,11 @@ impl AggregateFunction {
             BoolAnd => "BOOL_AND",
             BoolOr => "BOOL_OR",
             StringAgg => "STRING_AGG",
+            HllSketch => "HLL_SKETCH",
+            HllUnion => "HLL_UNION",
+            KllSketch => "KLL_SKETCH",
+            KllMerge => "KLL_MERGE",
+            ApproxTopKSketch => "APPROX_TOP_K_SKETCH",
         }
     }
 }
@@ -202,6 +217,12 @@ impl FromStr for AggregateFunction {
             "approx_percentile_cont_with_weight" => {
                 AggregateFunction::ApproxPercentileContWithWeight
             }
+            "approx_top_k_sketch" => AggregateFunction::ApproxTopKSketch,
+            // sketches
+            "hll_sketch" => AggregateFunction::HllSketch,
+            "hll_union" => AggregateFunction::HllUnion,
+            "kll_sketch" => AggregateFunction::KllSketch,
+            "kll_merge" => AggregateFunction::KllMerge,
             // other
             "grouping" => AggregateFunction::Grouping,
             _ => {
@@ -304,6 +325,11 @@ impl AggregateFunction {
                 Ok(coerced_data_types[0].clone())
             }
             AggregateFunction::StringAgg => Ok(DataType::LargeUtf8),
+            AggregateFunction::HllSketch
+            | AggregateFunction::HllUnion
+            | AggregateFunction::KllSketch
+            | AggregateFunction::KllMerge
+            | AggregateFunction::ApproxTopKSketch => Ok(DataType::Binary),
         }
     }
 }
@@ -416,6 +442,17 @@ impl AggregateFunction {
             AggregateFunction::StringAgg => {
                 Signature::uniform(2, STRINGS.to_vec(), Volatility::Immutable)
             }
+            AggregateFunction::HllSketch => Signature::any(1, Volatility::Immutable),
+            AggregateFunction::HllUnion | AggregateFunction::KllMerge => {
+                Signature::uniform(1, BINARYS.to_vec(), Volatility::Immutable)
+            }
+            // The value with an optional size of the sketch
+            AggregateFunction::KllSketch | AggregateFunction::ApproxTopKSketch => {
+                Signature::one_of(
+                    vec![TypeSignature::Any(1), TypeSignature::Any(2)],
+                    Volatility::Immutable,
+                )
+            }
         }
     }
 }
@@ -0,0 +1,163 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`AsyncScalarUDF`]: Scalar User Defined Functions evaluated asynchronously
+
+use crate::{ColumnarValue, ScalarUDFImpl, Signature};
+use arrow::datatypes::DataType;
+use async_trait::async_trait;
+use datafusion_common::{not_impl_err, DataFusionError, Result};
+use std::any::Any;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// The default for [`AsyncScalarUDF::max_concurrency`]
+pub const DEFAULT_ASYNC_UDF_CONCURRENCY: usize = 8;
+
+/// Trait for implementing scalar functions that `await` their result, such
+/// as functions looking up values in an external service.
+///
+/// An async function is invoked once per batch with the arguments of all
+/// rows of the batch, so that it can issue a single request for the batch.
+/// Calls to async functions in projections and filters are planned as an
+/// `AsyncFuncExec` operator, which evaluates the calls for several batches
+/// at the same time, up to [`Self::max_concurrency`] batches.
+///
+/// Async functions are registered like other functions by wrapping them in
+/// a [`ScalarUDF`] with [`ScalarUDF::new_async`]. They cannot be used in
+/// other places such as join conditions or aggregate arguments.
+///
+/// [`ScalarUDF`]: crate::ScalarUDF
+/// [`ScalarUDF::new_async`]: crate::ScalarUDF::new_async
+///
+/// # Example
+/// ```
+/// # use std::any::Any;
+/// # use arrow::datatypes::DataType;
+/// # use async_trait::async_trait;
+/// # use datafusion_common::Result;
+/// # use datafusion_expr::{col, AsyncScalarUDF, ColumnarValue, ScalarUDF, Signature, Volatility};
+/// #[derive(Debug)]
+/// struct Lookup {
+///   signature: Signature
+/// };
+///
+/// #[async_trait]
+/// impl AsyncScalarUDF for Lookup {
+///    fn as_any(&self) -> &dyn Any { self }
+///    fn name(&self) -> &str { "lookup" }
+///    fn signature(&self) -> &Signature { &self.signature }
+///    fn return_type(&self, args: &[DataType]) -> Result<DataType> {
+///      Ok(DataType::Utf8)
+///    }
+///    // The actual implementation would send the keys to a service
+///    async fn invoke_async(&self, args: &[ColumnarValue], num_rows: usize) -> Result<ColumnarValue> {
+///      unimplemented!()
+///    }
+/// }
+///
+/// let lookup = ScalarUDF::new_async(Lookup {
+///   signature: Signature::exact(vec![DataType::Int64], Volatility::Volatile)
+/// });
+/// let expr = lookup.call(vec![col("key")]);
+/// ```
+#[async_trait]
+pub trait AsyncScalarUDF: Debug + Send + Sync {
+    /// Returns this object as an [`Any`] trait object
+    fn as_any(&self) -> &dyn Any;
+
+    /// Returns this function's name
+    fn name(&self) -> &str;
+
+    /// Returns the function's [`Signature`] for information about what input
+    /// types are accepted and the function's Volatility.
+    fn signature(&self) -> &Signature;
+
+    /// What [`DataType`] will be returned by this function, given the types of
+    /// the arguments
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType>;
+
+    /// Invoke the function on the `args` of a batch of `num_rows` rows,
+    /// returning one value per row or a single scalar value
+    async fn invoke_async(
+        &self,
+        args: &[ColumnarValue],
+        num_rows: usize,
+    ) -> Result<ColumnarValue>;
+
+    /// Returns the maximum number of batches for which this function is
+    /// invoked at the same time by an operator.
+    ///
+    /// Defaults to [`DEFAULT_ASYNC_UDF_CONCURRENCY`]
+    fn max_concurrency(&self) -> usize {
+        DEFAULT_ASYNC_UDF_CONCURRENCY
+    }
+
+    /// Returns any aliases (alternate names) for this function.
+    ///
+    /// Defaults to `[]` (no aliases)
+    fn aliases(&self) -> &[String] {
+        &[]
+    }
+}
+
+/// Adapts an [`AsyncScalarUDF`] to [`ScalarUDFImpl`], so that it can be
+/// resolved and type checked like other functions. Invoking it synchronously
+/// returns an error.
+#[derive(Debug)]
+pub(crate) struct AsyncScalarUDFAdapter {
+    inner: Arc<dyn AsyncScalarUDF>,
+}
+
+impl AsyncScalarUDFAdapter {
+    pub(crate) fn new(inner: Arc<dyn AsyncScalarUDF>) -> Self {
+        Self { inner }
+    }
+
+    pub(crate) fn inner(&self) -> &Arc<dyn AsyncScalarUDF> {
+        &self.inner
+    }
+}
+
+impl ScalarUDFImpl for AsyncScalarUDFAdapter {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn signature(&self) -> &Signature {
+        self.inner.signature()
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        self.inner.return_type(arg_types)
+    }
+
+    fn invoke(&self, _args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        not_impl_err!(
+            "Async function {} can only be used in projections and filters",
+            self.name()
+        )
+    }
+
+    fn aliases(&self) -> &[String] {
+        self.inner.aliases()
+    }
+}
@@ -309,6 +309,12 @@ pub enum BuiltinScalarFunction {
     SubstrIndex,
     /// find_in_set
     FindInSet,
+    /// hll_estimate
+    HllEstimate,
+    /// kll_quantile
+    KllQuantile,
+    /// top_k_items
+    TopKItems,
 }
 
 /// Maps the sql function name to `BuiltinScalarFunction`
@@ -486,6 +492,9 @@ impl BuiltinScalarFunction {
             BuiltinScalarFunction::Levenshtein => Volatility::Immutable,
             BuiltinScalarFunction::SubstrIndex => Volatility::Immutable,
             BuiltinScalarFunction::FindInSet => Volatility::Immutable,
+            BuiltinScalarFunction::HllEstimate => Volatility::Immutable,
+            BuiltinScalarFunction::KllQuantile => Volatility::Immutable,
+            BuiltinScalarFunction::TopKItems => Volatility::Immutable,
 
             // Stable builtin functions
             BuiltinScalarFunction::Now => Volatility::Stable,
@@ -885,6 +894,18 @@ impl BuiltinScalarFunction {
                 utf8_to_int_type(&input_expr_types[0], "levenshtein")
             }
 
+            BuiltinScalarFunction::HllEstimate => Ok(UInt64),
+            BuiltinScalarFunction::KllQuantile => Ok(Float64),
+            BuiltinScalarFunction::TopKItems => Ok(List(Arc::new(Field::new(
+                "item",
+                Struct(Fields::from(vec![
+                    Field::new("item", Utf8, true),
+                    Field::new("count", UInt64, true),
+                    Field::new("error", UInt64, true),
+                ])),
+                true,
+            )))),
+
             BuiltinScalarFunction::Acos
             | BuiltinScalarFunction::Asin
             | BuiltinScalarFunction::Atan
@@ -1374,6 +1395,16 @@ impl BuiltinScalarFunction {
                 vec![Exact(vec![Utf8, Utf8]), Exact(vec![LargeUtf8, LargeUtf8])],
                 self.volatility(),
             ),
+            BuiltinScalarFunction::HllEstimate => {
+                Signature::exact(vec![Binary], self.volatility())
+            }
+            BuiltinScalarFunction::KllQuantile => {
+                Signature::exact(vec![Binary, Float64], self.volatility())
+            }
+            BuiltinScalarFunction::TopKItems => Signature::one_of(
+                vec![Exact(vec![Binary]), Exact(vec![Binary, Int64])],
+                self.volatility(),
+            ),
             BuiltinScalarFunction::Acos
             | BuiltinScalarFunction::Asin
             | BuiltinScalarFunction::Atan
@@ -1577,6 +1608,11 @@ impl BuiltinScalarFunction {
             // other functions
             BuiltinScalarFunction::ArrowTypeof => &["arrow_typeof"],
 
+            // sketch functions
+            BuiltinScalarFunction::HllEstimate => &["hll_estimate"],
+            BuiltinScalarFunction::KllQuantile => &["kll_quantile"],
+            BuiltinScalarFunction::TopKItems => &["top_k_items"],
+
             // array functions
             BuiltinScalarFunction::ArrayAppend => &[
                 "array_append",
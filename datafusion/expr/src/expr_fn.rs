@@ -928,6 +928,18 @@ scalar_expr!(ArrowTypeof, arrow_typeof, val, "data type");
 scalar_expr!(Levenshtein, levenshtein, string1 string2, "Returns the Levenshtein distance between the two given strings");
 scalar_expr!(SubstrIndex, substr_index, string delimiter count, "Returns the substring from str before count occurrences of the delimiter");
 scalar_expr!(FindInSet, find_in_set, str strlist, "Returns a value in the range of 1 to N if the string str is in the string list strlist consisting of N substrings");
+scalar_expr!(
+    HllEstimate,
+    hll_estimate,
+    sketch,
+    "Returns the estimated number of distinct values of an HLL sketch"
+);
+scalar_expr!(KllQuantile, kll_quantile, sketch quantile, "Returns the estimated quantile of the values of a KLL sketch");
+nary_scalar_expr!(
+    TopKItems,
+    top_k_items,
+    "Returns the most frequent items of a top-k sketch, with their estimated counts"
+);
 
 scalar_expr!(
     Struct,
@@ -1354,6 +1366,9 @@ mod test {
         test_scalar_expr!(Levenshtein, levenshtein, string1, string2);
         test_scalar_expr!(SubstrIndex, substr_index, string, delimiter, count);
         test_scalar_expr!(FindInSet, find_in_set, string, stringlist);
+        test_unary_scalar_expr!(HllEstimate, hll_estimate);
+        test_scalar_expr!(KllQuantile, kll_quantile, sketch, quantile);
+        test_nary_scalar_expr!(TopKItems, top_k_items, sketch, k);
     }
 
     #[test]
@@ -26,6 +26,7 @@
 //! The [expr_fn] module contains functions for creating expressions.
 
 mod accumulator;
+mod async_udf;
 mod built_in_function;
 mod built_in_window_function;
 mod columnar_value;
@@ -58,6 +59,7 @@ pub mod window_state;
 
 pub use accumulator::Accumulator;
 pub use aggregate_function::AggregateFunction;
+pub use async_udf::{AsyncScalarUDF, DEFAULT_ASYNC_UDF_CONCURRENCY};
 pub use built_in_function::BuiltinScalarFunction;
 pub use built_in_window_function::BuiltInWindowFunction;
 pub use columnar_value::ColumnarValue;
@@ -701,6 +701,7 @@ impl LogicalPlan {
                 join_constraint,
                 on,
                 null_equals_null,
+                null_aware,
                 ..
             }) => {
                 let schema =
@@ -741,6 +742,7 @@ impl LogicalPlan {
                     filter: filter_expr,
                     schema: DFSchemaRef::new(schema),
                     null_equals_null: *null_equals_null,
+                    null_aware: *null_aware,
                 }))
             }
             LogicalPlan::CrossJoin(_) => {
@@ -1580,6 +1582,7 @@ impl LogicalPlan {
                         filter,
                         join_constraint,
                         join_type,
+                        null_aware,
                         ..
                     }) => {
                         let join_expr: Vec<String> =
@@ -1588,12 +1591,14 @@ impl LogicalPlan {
                             .as_ref()
                             .map(|expr| format!(" Filter: {expr}"))
                             .unwrap_or_else(|| "".to_string());
+                        let null_aware = if *null_aware { " (null aware)" } else { "" };
                         match join_constraint {
                             JoinConstraint::On => {
                                 write!(
                                     f,
-                                    "{} Join: {}{}",
+                                    "{}{} Join: {}{}",
                                     join_type,
+                                    null_aware,
                                     join_expr.join(", "),
                                     filter_expr
                                 )
@@ -1601,8 +1606,9 @@ impl LogicalPlan {
                             JoinConstraint::Using => {
                                 write!(
                                     f,
-                                    "{} Join: Using {}{}",
+                                    "{}{} Join: Using {}{}",
                                     join_type,
+                                    null_aware,
                                     join_expr.join(", "),
                                     filter_expr,
                                 )
@@ -2537,6 +2543,11 @@ pub struct Join {
     pub schema: DFSchemaRef,
     /// If null_equals_null is true, null == null else null != null
     pub null_equals_null: bool,
+    /// If null_aware is true, this is a [`JoinType::LeftAnti`] join with the
+    /// semantics of `left_key NOT IN (right_key, ...)`: no row is returned if
+    /// any right key is null, and rows with a null left key are only returned
+    /// if the right input is empty
+    pub null_aware: bool,
 }
 
 impl Join {
@@ -2570,6 +2581,7 @@ impl Join {
             join_constraint: original_join.join_constraint,
             schema: Arc::new(join_schema),
             null_equals_null: original_join.null_equals_null,
+            null_aware: original_join.null_aware,
         })
     }
 }
@@ -315,6 +315,37 @@ pub fn coerce_types(
             }
             Ok(vec![LargeUtf8, input_types[1].clone()])
         }
+        AggregateFunction::HllSketch => Ok(input_types.to_vec()),
+        AggregateFunction::HllUnion | AggregateFunction::KllMerge => Ok(vec![Binary]),
+        AggregateFunction::KllSketch => {
+            if !is_approx_percentile_cont_supported_arg_type(&input_types[0]) {
+                return plan_err!(
+                    "The function {:?} does not support inputs of type {:?}.",
+                    agg_fun,
+                    input_types[0]
+                );
+            }
+            if input_types.len() == 2 && !is_integer_arg_type(&input_types[1]) {
+                return plan_err!(
+                    "The sketch size for {:?} must be integer, not {:?}.",
+                    agg_fun,
+                    input_types[1]
+                );
+            }
+            let mut result = input_types.to_vec();
+            result[0] = Float64;
+            Ok(result)
+        }
+        AggregateFunction::ApproxTopKSketch => {
+            if input_types.len() == 2 && !is_integer_arg_type(&input_types[1]) {
+                return plan_err!(
+                    "The sketch size for {:?} must be integer, not {:?}.",
+                    agg_fun,
+                    input_types[1]
+                );
+            }
+            Ok(input_types.to_vec())
+        }
     }
 }
 
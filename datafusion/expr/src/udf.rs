@@ -17,8 +17,10 @@
 
 //! [`ScalarUDF`]: Scalar User Defined Functions
 
+use crate::async_udf::AsyncScalarUDFAdapter;
 use crate::{
-    ColumnarValue, Expr, ReturnTypeFunction, ScalarFunctionImplementation, Signature,
+    AsyncScalarUDF, ColumnarValue, Expr, ReturnTypeFunction,
+    ScalarFunctionImplementation, Signature,
 };
 use arrow::datatypes::DataType;
 use datafusion_common::Result;
@@ -99,6 +101,23 @@ impl ScalarUDF {
         }
     }
 
+    /// Create a new `ScalarUDF` from an [`AsyncScalarUDF`] trait object
+    pub fn new_async<F>(fun: F) -> ScalarUDF
+    where
+        F: AsyncScalarUDF + 'static,
+    {
+        Self::new_from_impl(AsyncScalarUDFAdapter::new(Arc::new(fun)))
+    }
+
+    /// Returns the underlying [`AsyncScalarUDF`] if this function was
+    /// created with [`Self::new_async`]
+    pub fn as_async(&self) -> Option<&Arc<dyn AsyncScalarUDF>> {
+        self.inner
+            .as_any()
+            .downcast_ref::<AsyncScalarUDFAdapter>()
+            .map(|adapter| adapter.inner())
+    }
+
     /// Return the underlying [`ScalarUDFImpl`] trait object for this function
     pub fn inner(&self) -> Arc<dyn ScalarUDFImpl> {
         self.inner.clone()
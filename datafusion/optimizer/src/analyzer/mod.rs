@@ -100,11 +100,16 @@ impl Analyzer {
     where
         F: FnMut(&LogicalPlan, &dyn AnalyzerRule),
     {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("analyze").entered();
         let start_time = Instant::now();
         let mut new_plan = plan.clone();
 
         // TODO add common rule executor for Analyzer and Optimizer
         for rule in &self.rules {
+            #[cfg(feature = "tracing")]
+            let _span =
+                tracing::debug_span!("analyzer_rule", rule = rule.name()).entered();
             new_plan = rule.analyze(new_plan, config).map_err(|e| {
                 DataFusionError::Context(rule.name().to_string(), Box::new(e))
             })?;
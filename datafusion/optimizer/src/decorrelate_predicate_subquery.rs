@@ -27,8 +27,8 @@ use datafusion_expr::expr_rewriter::create_col_from_scalar_expr;
 use datafusion_expr::logical_plan::{JoinType, Subquery};
 use datafusion_expr::utils::{conjunction, split_conjunction};
 use datafusion_expr::{
-    exists, in_subquery, not_exists, not_in_subquery, BinaryExpr, Expr, Filter,
-    LogicalPlan, LogicalPlanBuilder, Operator,
+    exists, in_subquery, not_exists, not_in_subquery, BinaryExpr, Expr, ExprSchemable,
+    Filter, LogicalPlan, LogicalPlanBuilder, Operator,
 };
 use log::debug;
 use std::collections::BTreeSet;
@@ -249,6 +249,14 @@ fn build_join(
                 .map(Option::Some)
         })?;
 
+    // `x NOT IN (SELECT y ...)` is not true if `x` or any `y` is null, unlike
+    // the anti join on `x = y`. The null aware anti join only supports
+    // uncorrelated subqueries, correlated ones are left as they are.
+    let null_aware = query_info.negated && is_in_subquery_nullable(query_info, left)?;
+    if null_aware && join_filter_opt.is_some() {
+        return Ok(None);
+    }
+
     if let Some(join_filter) = match (join_filter_opt, in_predicate_opt) {
         (
             Some(join_filter),
@@ -282,9 +290,12 @@ fn build_join(
             true => JoinType::LeftAnti,
             false => JoinType::LeftSemi,
         };
-        let new_plan = LogicalPlanBuilder::from(left.clone())
+        let mut new_plan = LogicalPlanBuilder::from(left.clone())
             .join_on(sub_query_alias, join_type, Some(join_filter))?
             .build()?;
+        if let LogicalPlan::Join(join) = &mut new_plan {
+            join.null_aware = null_aware;
+        }
         debug!(
             "predicate subquery optimized:\n{}",
             new_plan.display_indent()
@@ -295,6 +306,22 @@ fn build_join(
     }
 }
 
+/// Returns true if `query_info` is an IN subquery whose expression or
+/// subquery output may be null
+fn is_in_subquery_nullable(
+    query_info: &SubqueryInfo,
+    left: &LogicalPlan,
+) -> Result<bool> {
+    let Some(where_in_expr) = &query_info.where_in_expr else {
+        return Ok(false);
+    };
+    let subquery_nullable = match query_info.query.subquery.schema().fields().first() {
+        Some(field) => field.is_nullable(),
+        None => false,
+    };
+    Ok(subquery_nullable || where_in_expr.nullable(left.schema())?)
+}
+
 struct SubqueryInfo {
     query: Subquery,
     where_in_expr: Option<Expr>,
@@ -323,7 +350,7 @@ impl SubqueryInfo {
 mod tests {
     use super::*;
     use crate::test::*;
-    use arrow::datatypes::DataType;
+    use arrow::datatypes::{DataType, Field, Schema};
     use datafusion_common::Result;
     use datafusion_expr::{
         and, binary_expr, col, exists, in_subquery, lit,
@@ -1078,6 +1105,50 @@ mod tests {
         Ok(())
     }
 
+    /// Test for NOT IN subquery filter with a nullable subquery output
+    #[test]
+    fn not_in_subquery_nullable() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let schema = Schema::new(vec![Field::new("c", DataType::UInt32, true)]);
+        let subquery = scan_empty(Some("sq"), &schema, None)?
+            .project(vec![col("c")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .filter(not_in_subquery(col("c"), Arc::new(subquery)))?
+            .project(vec![col("test.b")])?
+            .build()?;
+
+        let expected = "Projection: test.b [b:UInt32]\
+        \n  LeftAnti (null aware) Join:  Filter: test.c = __correlated_sq_1.c [a:UInt32, b:UInt32, c:UInt32]\
+        \n    TableScan: test [a:UInt32, b:UInt32, c:UInt32]\
+        \n    SubqueryAlias: __correlated_sq_1 [c:UInt32;N]\
+        \n      Projection: sq.c [c:UInt32;N]\
+        \n        TableScan: sq [c:UInt32;N]";
+
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    /// Test for correlated NOT IN subquery filter with a nullable subquery
+    /// output, which the null aware anti join does not support
+    #[test]
+    fn not_in_subquery_correlated_nullable() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new("c", DataType::UInt32, true),
+        ]);
+        let subquery = scan_empty(Some("sq"), &schema, None)?
+            .filter(out_ref_col(DataType::UInt32, "test.a").eq(col("sq.a")))?
+            .project(vec![col("c")])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .filter(not_in_subquery(col("c"), Arc::new(subquery)))?
+            .project(vec![col("test.b")])?
+            .build()?;
+
+        assert_optimization_skipped(Arc::new(DecorrelatePredicateSubquery::new()), &plan)
+    }
+
     #[test]
     fn in_subquery_both_side_expr() -> Result<()> {
         let table_scan = test_table_scan()?;
@@ -233,6 +233,7 @@ fn find_inner_join(
                 filter: None,
                 schema: join_schema,
                 null_equals_null: false,
+                null_aware: false,
             }));
         }
     }
@@ -105,6 +105,7 @@ impl OptimizerRule for EliminateOuterJoin {
                         filter: join.filter.clone(),
                         schema: join.schema.clone(),
                         null_equals_null: join.null_equals_null,
+                        null_aware: join.null_aware,
                     });
                     let new_plan =
                         plan.with_new_exprs(plan.expressions(), &[new_join])?;
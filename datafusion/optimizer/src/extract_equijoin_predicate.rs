@@ -64,6 +64,7 @@ impl OptimizerRule for ExtractEquijoinPredicate {
                 join_constraint,
                 schema,
                 null_equals_null,
+                null_aware,
             }) => {
                 let left_schema = left.schema();
                 let right_schema = right.schema();
@@ -89,6 +90,7 @@ impl OptimizerRule for ExtractEquijoinPredicate {
                             join_constraint: *join_constraint,
                             schema: schema.clone(),
                             null_equals_null: *null_equals_null,
+                            null_aware: *null_aware,
                         })
                     });
 
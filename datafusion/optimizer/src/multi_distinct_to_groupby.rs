@@ -0,0 +1,639 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! multiple distinct to group by optimizer rule
+
+use std::sync::Arc;
+
+use crate::optimizer::ApplyOrder;
+use crate::{OptimizerConfig, OptimizerRule};
+
+use datafusion_common::{
+    internal_err, DFField, DFSchema, DataFusionError, Result, ScalarValue,
+};
+use datafusion_expr::expr::AggregateFunctionDefinition;
+use datafusion_expr::{
+    aggregate_function::AggregateFunction::{
+        self as AggregateFun, Avg, BitAnd, BitOr, BitXor, BoolAnd, BoolOr, Count, Max,
+        Min, Sum,
+    },
+    coalesce, col,
+    expr::AggregateFunction,
+    lit,
+    logical_plan::{Aggregate, LogicalPlan, Projection, Union},
+    Expr, ExprSchemable,
+};
+
+/// multiple distinct to group by optimizer rule
+///
+/// Rewrites aggregates with DISTINCT aggregates on different arguments,
+/// which would otherwise keep a hash set of distinct values per group, to
+/// two ordinary aggregates. Similar to [`SingleDistinctToGroupBy`], the
+/// input is first grouped by the distinct arguments to remove duplicates.
+/// As a row can only be grouped by one of the arguments at once, the input
+/// is expanded to one copy per distinct argument (plus one for the non
+/// distinct aggregates), tagged with `distinct_id`:
+///
+///  ```text
+///    Before:
+///    SELECT a, COUNT(DISTINCT b), COUNT(DISTINCT c), SUM(d)
+///    FROM t
+///    GROUP BY a
+///
+///    After:
+///    SELECT a, COUNT(alias1), COUNT(alias2), SUM(alias3)
+///    FROM (
+///      SELECT a, distinct_id, alias1, alias2, SUM(alias3) AS alias3
+///      FROM (
+///        SELECT a, 0 AS distinct_id, NULL AS alias1, NULL AS alias2, d AS alias3 FROM t
+///        UNION ALL
+///        SELECT a, 1 AS distinct_id, b AS alias1, NULL AS alias2, NULL AS alias3 FROM t
+///        UNION ALL
+///        SELECT a, 2 AS distinct_id, NULL AS alias1, c AS alias2, NULL AS alias3 FROM t
+///      )
+///      GROUP BY a, distinct_id, alias1, alias2
+///    )
+///    GROUP BY a
+///  ```
+///
+/// The outer aggregates ignore the NULLs of the copies that belong to other
+/// aggregates, so only DISTINCT aggregates that ignore NULLs are rewritten.
+///
+/// [`SingleDistinctToGroupBy`]: crate::single_distinct_to_groupby::SingleDistinctToGroupBy
+#[derive(Default)]
+pub struct MultiDistinctToGroupBy {}
+
+const DISTINCT_ID_ALIAS: &str = "distinct_id";
+
+impl MultiDistinctToGroupBy {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Check whether the aggregate contains DISTINCT aggregates on more than one
+/// argument, and all aggregate exprs can be rewritten.
+fn is_multi_distinct_agg(plan: &LogicalPlan) -> Result<bool> {
+    match plan {
+        LogicalPlan::Aggregate(Aggregate {
+            aggr_expr,
+            group_expr,
+            ..
+        }) => {
+            if matches!(group_expr.first(), Some(Expr::GroupingSet(_))) {
+                return Ok(false);
+            }
+            let mut distinct_args = vec![];
+            for expr in aggr_expr {
+                let Expr::AggregateFunction(AggregateFunction {
+                    func_def: AggregateFunctionDefinition::BuiltIn(fun),
+                    distinct,
+                    args,
+                    filter: None,
+                    order_by: None,
+                }) = expr
+                else {
+                    return Ok(false);
+                };
+                if args.len() != 1 {
+                    return Ok(false);
+                }
+                if *distinct {
+                    if !ignores_nulls(fun) {
+                        return Ok(false);
+                    }
+                    if !distinct_args.contains(&&args[0]) {
+                        distinct_args.push(&args[0]);
+                    }
+                } else if !matches!(fun, Count | Sum | Min | Max) {
+                    return Ok(false);
+                }
+            }
+            Ok(distinct_args.len() > 1)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Whether `fun` ignores NULL inputs, so that the NULLs of the copies of
+/// other aggregates do not change its result.
+fn ignores_nulls(fun: &AggregateFun) -> bool {
+    matches!(
+        fun,
+        Count | Sum | Min | Max | Avg | BitAnd | BitOr | BitXor | BoolAnd | BoolOr
+    )
+}
+
+/// An aggregate expression of the original plan
+enum AggregateSlot {
+    /// A DISTINCT aggregate of the `index`th distinct argument
+    Distinct { fun: AggregateFun, index: usize },
+    /// A non distinct aggregate of the `index`th non distinct argument
+    Common { fun: AggregateFun, index: usize },
+}
+
+impl OptimizerRule for MultiDistinctToGroupBy {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        if !config.options().optimizer.enable_multi_distinct_to_group_by {
+            return Ok(None);
+        }
+        let LogicalPlan::Aggregate(Aggregate {
+            input,
+            aggr_expr,
+            schema,
+            group_expr,
+            ..
+        }) = plan
+        else {
+            return Ok(None);
+        };
+        if !is_multi_distinct_agg(plan)? {
+            return Ok(None);
+        }
+        let input_schema = input.schema();
+        let fields = schema.fields();
+
+        // collect the distinct and non distinct arguments
+        let mut distinct_args: Vec<Expr> = vec![];
+        let mut common_args: Vec<Expr> = vec![];
+        let slots = aggr_expr
+            .iter()
+            .map(|aggr_expr| match aggr_expr {
+                Expr::AggregateFunction(AggregateFunction {
+                    func_def: AggregateFunctionDefinition::BuiltIn(fun),
+                    args,
+                    distinct,
+                    ..
+                }) => {
+                    // is_multi_distinct_agg ensures args.len = 1
+                    let arg = &args[0];
+                    if *distinct {
+                        let index = match distinct_args.iter().position(|e| e == arg) {
+                            Some(index) => index,
+                            None => {
+                                distinct_args.push(arg.clone());
+                                distinct_args.len() - 1
+                            }
+                        };
+                        Ok(AggregateSlot::Distinct {
+                            fun: fun.clone(),
+                            index,
+                        })
+                    } else {
+                        common_args.push(arg.clone());
+                        Ok(AggregateSlot::Common {
+                            fun: fun.clone(),
+                            index: common_args.len() - 1,
+                        })
+                    }
+                }
+                _ => internal_err!("Unexpected aggregate expression {aggr_expr}"),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // the distinct arguments are referred to as alias1, alias2, ...,
+        // followed by the arguments of the non distinct aggregates
+        let arg_aliases = (1..=distinct_args.len() + common_args.len())
+            .map(|i| format!("alias{i}"))
+            .collect::<Vec<_>>();
+        let (distinct_aliases, common_aliases) =
+            arg_aliases.split_at(distinct_args.len());
+
+        // alias all complex group by exprs, see SingleDistinctToGroupBy
+        let branch_group_exprs = group_expr
+            .iter()
+            .enumerate()
+            .map(|(i, group_expr)| match group_expr {
+                Expr::Column(_) => group_expr.clone(),
+                _ => group_expr.clone().alias(format!("group_alias_{i}")),
+            })
+            .collect::<Vec<_>>();
+
+        let typed_null = |arg: &Expr| -> Result<Expr> {
+            Ok(lit(ScalarValue::try_from(arg.get_type(input_schema)?)?))
+        };
+
+        // create one copy of the input for the non distinct aggregates, if
+        // any, and one for every distinct argument
+        let first_id = if common_args.is_empty() { 1 } else { 0 };
+        let branches = (first_id..=distinct_args.len())
+            .map(|id| {
+                let mut exprs = branch_group_exprs.clone();
+                exprs.push(lit(id as u32).alias(DISTINCT_ID_ALIAS));
+                for (i, arg) in distinct_args.iter().enumerate() {
+                    let expr = if i + 1 == id {
+                        arg.clone()
+                    } else {
+                        typed_null(arg)?
+                    };
+                    exprs.push(expr.alias(&distinct_aliases[i]));
+                }
+                for (i, arg) in common_args.iter().enumerate() {
+                    let expr = if id == 0 {
+                        arg.clone()
+                    } else {
+                        typed_null(arg)?
+                    };
+                    exprs.push(expr.alias(&common_aliases[i]));
+                }
+                Ok(Arc::new(LogicalPlan::Projection(Projection::try_new(
+                    exprs,
+                    input.clone(),
+                )?)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // all copies have the same types, a field is nullable if it is
+        // nullable in any of them
+        let union_fields = branches[0]
+            .schema()
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let nullable = branches
+                    .iter()
+                    .any(|branch| branch.schema().field(i).is_nullable());
+                DFField::new(
+                    field.qualifier().cloned(),
+                    field.name(),
+                    field.data_type().clone(),
+                    nullable,
+                )
+            })
+            .collect();
+        let union_schema = Arc::new(DFSchema::new_with_metadata(
+            union_fields,
+            input_schema.metadata().clone(),
+        )?);
+        let union = LogicalPlan::Union(Union {
+            inputs: branches,
+            schema: union_schema.clone(),
+        });
+
+        // group the copies by the group by exprs, the distinct_id and the
+        // distinct arguments, and compute the first phase of the non
+        // distinct aggregates
+        let union_columns = union_schema
+            .fields()
+            .iter()
+            .map(|field| Expr::Column(field.qualified_column()))
+            .collect::<Vec<_>>();
+        let num_inner_groups = group_expr.len() + 1 + distinct_args.len();
+        let outer_group_exprs = union_columns[..group_expr.len()].to_vec();
+        let inner_group_exprs = union_columns[..num_inner_groups].to_vec();
+        let mut inner_aggr_exprs = vec![];
+        for slot in &slots {
+            if let AggregateSlot::Common { fun, index } = slot {
+                let alias = &common_aliases[*index];
+                inner_aggr_exprs.push(
+                    Expr::AggregateFunction(AggregateFunction::new(
+                        fun.clone(),
+                        vec![col(alias)],
+                        false,
+                        None,
+                        None,
+                    ))
+                    .alias(alias),
+                );
+            }
+        }
+        let inner_agg = LogicalPlan::Aggregate(Aggregate::try_new(
+            Arc::new(union),
+            inner_group_exprs,
+            inner_aggr_exprs,
+        )?);
+
+        // the second phase: DISTINCT aggregates over the grouped arguments and
+        // the final phase of the non distinct aggregates
+        let outer_aggr_exprs = slots
+            .iter()
+            .map(|slot| {
+                let (fun, alias) = match slot {
+                    AggregateSlot::Distinct { fun, index } => {
+                        (fun.clone(), &distinct_aliases[*index])
+                    }
+                    // the partial counts are added up
+                    AggregateSlot::Common { fun: Count, index } => {
+                        (Sum, &common_aliases[*index])
+                    }
+                    AggregateSlot::Common { fun, index } => {
+                        (fun.clone(), &common_aliases[*index])
+                    }
+                };
+                Expr::AggregateFunction(AggregateFunction::new(
+                    fun,
+                    vec![col(alias)],
+                    false,
+                    None,
+                    None,
+                ))
+            })
+            .collect::<Vec<_>>();
+        let outer_agg = LogicalPlan::Aggregate(Aggregate::try_new(
+            Arc::new(inner_agg),
+            outer_group_exprs,
+            outer_aggr_exprs,
+        )?);
+
+        // restore the names of the original aggregate
+        let alias_exprs = outer_agg
+            .schema()
+            .fields()
+            .iter()
+            .zip(fields)
+            .enumerate()
+            .map(|(i, (field, original_field))| {
+                let expr = Expr::Column(field.qualified_column());
+                if i < group_expr.len() {
+                    return match &group_expr[i] {
+                        Expr::Column(_) => expr,
+                        _ => expr.alias(original_field.qualified_name()),
+                    };
+                }
+                let expr = match &slots[i - group_expr.len()] {
+                    // the sum of no partial counts is NULL
+                    AggregateSlot::Common { fun: Count, .. } => {
+                        coalesce(vec![expr, lit(0_i64)])
+                    }
+                    _ => expr,
+                };
+                expr.alias(original_field.qualified_name())
+            })
+            .collect();
+
+        Ok(Some(LogicalPlan::Projection(Projection::try_new(
+            alias_exprs,
+            Arc::new(outer_agg),
+        )?)))
+    }
+
+    fn name(&self) -> &str {
+        "multi_distinct_aggregation_to_group_by"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimizer::Optimizer;
+    use crate::test::*;
+    use crate::OptimizerContext;
+    use datafusion_expr::expr::GroupingSet;
+    use datafusion_expr::{
+        avg, count, count_distinct, logical_plan::builder::LogicalPlanBuilder,
+    };
+
+    fn optimize(plan: &LogicalPlan, config: &OptimizerContext) -> Result<LogicalPlan> {
+        let optimizer =
+            Optimizer::with_rules(vec![Arc::new(MultiDistinctToGroupBy::new())]);
+        let optimized_plan = optimizer
+            .optimize_recursively(optimizer.rules.first().unwrap(), plan, config)?
+            .unwrap_or_else(|| plan.clone());
+        Ok(optimized_plan)
+    }
+
+    fn assert_optimized_plan_equal(plan: &LogicalPlan, expected: &str) -> Result<()> {
+        let config = OptimizerContext::new().with_multi_distinct_to_group_by(true);
+        let optimized_plan = optimize(plan, &config)?;
+        assert_eq!(optimized_plan.display_indent_schema().to_string(), expected);
+        Ok(())
+    }
+
+    fn distinct(fun: AggregateFun, arg: Expr) -> Expr {
+        Expr::AggregateFunction(AggregateFunction::new(fun, vec![arg], true, None, None))
+    }
+
+    #[test]
+    fn disabled_by_default() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(
+                vec![col("a")],
+                vec![count_distinct(col("b")), count_distinct(col("c"))],
+            )?
+            .build()?;
+
+        // The input is scanned once, not once per distinct argument
+        let expected = "Aggregate: groupBy=[[test.a]], aggr=[[COUNT(DISTINCT test.b), COUNT(DISTINCT test.c)]] [a:UInt32, COUNT(DISTINCT test.b):Int64;N, COUNT(DISTINCT test.c):Int64;N]\
+                            \n  TableScan: test [a:UInt32, b:UInt32, c:UInt32]";
+
+        let optimized_plan = optimize(&plan, &OptimizerContext::new())?;
+        assert_eq!(optimized_plan.display_indent_schema().to_string(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn single_distinct_arg() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(
+                vec![col("a")],
+                vec![count_distinct(col("b")), distinct(Max, col("b"))],
+            )?
+            .build()?;
+
+        // Do nothing, handled by SingleDistinctToGroupBy
+        let expected = "Aggregate: groupBy=[[test.a]], aggr=[[COUNT(DISTINCT test.b), MAX(DISTINCT test.b)]] [a:UInt32, COUNT(DISTINCT test.b):Int64;N, MAX(DISTINCT test.b):UInt32;N]\
+                            \n  TableScan: test [a:UInt32, b:UInt32, c:UInt32]";
+
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn two_distinct_and_groupby() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(
+                vec![col("a")],
+                vec![count_distinct(col("b")), count_distinct(col("c"))],
+            )?
+            .build()?;
+
+        // Should work
+        let expected = "Projection: test.a, COUNT(alias1) AS COUNT(DISTINCT test.b), COUNT(alias2) AS COUNT(DISTINCT test.c) [a:UInt32, COUNT(DISTINCT test.b):Int64;N, COUNT(DISTINCT test.c):Int64;N]\
+                            \n  Aggregate: groupBy=[[test.a]], aggr=[[COUNT(alias1), COUNT(alias2)]] [a:UInt32, COUNT(alias1):Int64;N, COUNT(alias2):Int64;N]\
+                            \n    Aggregate: groupBy=[[test.a, distinct_id, alias1, alias2]], aggr=[[]] [a:UInt32, distinct_id:UInt32, alias1:UInt32;N, alias2:UInt32;N]\
+                            \n      Union [a:UInt32, distinct_id:UInt32, alias1:UInt32;N, alias2:UInt32;N]\
+                            \n        Projection: test.a, UInt32(1) AS distinct_id, test.b AS alias1, UInt32(NULL) AS alias2 [a:UInt32, distinct_id:UInt32, alias1:UInt32, alias2:UInt32;N]\
+                            \n          TableScan: test [a:UInt32, b:UInt32, c:UInt32]\
+                            \n        Projection: test.a, UInt32(2) AS distinct_id, UInt32(NULL) AS alias1, test.c AS alias2 [a:UInt32, distinct_id:UInt32, alias1:UInt32;N, alias2:UInt32]\
+                            \n          TableScan: test [a:UInt32, b:UInt32, c:UInt32]";
+
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn two_distinct_and_one_common() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(
+                vec![col("a")],
+                vec![
+                    count_distinct(col("b")),
+                    count_distinct(col("c")),
+                    count(col("c")),
+                ],
+            )?
+            .build()?;
+
+        // Should work
+        let expected = "Projection: test.a, COUNT(alias1) AS COUNT(DISTINCT test.b), COUNT(alias2) AS COUNT(DISTINCT test.c), coalesce(SUM(alias3), Int64(0)) AS COUNT(test.c) [a:UInt32, COUNT(DISTINCT test.b):Int64;N, COUNT(DISTINCT test.c):Int64;N, COUNT(test.c):Int64;N]\
+                            \n  Aggregate: groupBy=[[test.a]], aggr=[[COUNT(alias1), COUNT(alias2), SUM(alias3)]] [a:UInt32, COUNT(alias1):Int64;N, COUNT(alias2):Int64;N, SUM(alias3):Int64;N]\
+                            \n    Aggregate: groupBy=[[test.a, distinct_id, alias1, alias2]], aggr=[[COUNT(alias3) AS alias3]] [a:UInt32, distinct_id:UInt32, alias1:UInt32;N, alias2:UInt32;N, alias3:Int64;N]\
+                            \n      Union [a:UInt32, distinct_id:UInt32, alias1:UInt32;N, alias2:UInt32;N, alias3:UInt32;N]\
+                            \n        Projection: test.a, UInt32(0) AS distinct_id, UInt32(NULL) AS alias1, UInt32(NULL) AS alias2, test.c AS alias3 [a:UInt32, distinct_id:UInt32, alias1:UInt32;N, alias2:UInt32;N, alias3:UInt32]\
+                            \n          TableScan: test [a:UInt32, b:UInt32, c:UInt32]\
+                            \n        Projection: test.a, UInt32(1) AS distinct_id, test.b AS alias1, UInt32(NULL) AS alias2, UInt32(NULL) AS alias3 [a:UInt32, distinct_id:UInt32, alias1:UInt32, alias2:UInt32;N, alias3:UInt32;N]\
+                            \n          TableScan: test [a:UInt32, b:UInt32, c:UInt32]\
+                            \n        Projection: test.a, UInt32(2) AS distinct_id, UInt32(NULL) AS alias1, test.c AS alias2, UInt32(NULL) AS alias3 [a:UInt32, distinct_id:UInt32, alias1:UInt32;N, alias2:UInt32, alias3:UInt32;N]\
+                            \n          TableScan: test [a:UInt32, b:UInt32, c:UInt32]";
+
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn group_by_with_expr() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(
+                vec![col("a") + lit(1)],
+                vec![count_distinct(col("b")), distinct(Sum, col("c"))],
+            )?
+            .build()?;
+
+        // Should work
+        let expected = "Projection: group_alias_0 AS test.a + Int32(1), COUNT(alias1) AS COUNT(DISTINCT test.b), SUM(alias2) AS SUM(DISTINCT test.c) [test.a + Int32(1):Int32, COUNT(DISTINCT test.b):Int64;N, SUM(DISTINCT test.c):UInt64;N]\
+                            \n  Aggregate: groupBy=[[group_alias_0]], aggr=[[COUNT(alias1), SUM(alias2)]] [group_alias_0:Int32, COUNT(alias1):Int64;N, SUM(alias2):UInt64;N]\
+                            \n    Aggregate: groupBy=[[group_alias_0, distinct_id, alias1, alias2]], aggr=[[]] [group_alias_0:Int32, distinct_id:UInt32, alias1:UInt32;N, alias2:UInt32;N]\
+                            \n      Union [group_alias_0:Int32, distinct_id:UInt32, alias1:UInt32;N, alias2:UInt32;N]\
+                            \n        Projection: test.a + Int32(1) AS group_alias_0, UInt32(1) AS distinct_id, test.b AS alias1, UInt32(NULL) AS alias2 [group_alias_0:Int32, distinct_id:UInt32, alias1:UInt32, alias2:UInt32;N]\
+                            \n          TableScan: test [a:UInt32, b:UInt32, c:UInt32]\
+                            \n        Projection: test.a + Int32(1) AS group_alias_0, UInt32(2) AS distinct_id, UInt32(NULL) AS alias1, test.c AS alias2 [group_alias_0:Int32, distinct_id:UInt32, alias1:UInt32;N, alias2:UInt32]\
+                            \n          TableScan: test [a:UInt32, b:UInt32, c:UInt32]";
+
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    // Currently this optimization is disabled for CUBE/ROLLUP/GROUPING SET
+    #[test]
+    fn two_distinct_and_grouping_set() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let grouping_set = Expr::GroupingSet(GroupingSet::GroupingSets(vec![
+            vec![col("a")],
+            vec![col("b")],
+        ]));
+
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(
+                vec![grouping_set],
+                vec![count_distinct(col("b")), count_distinct(col("c"))],
+            )?
+            .build()?;
+
+        // Should not be optimized
+        let expected = "Aggregate: groupBy=[[GROUPING SETS ((test.a), (test.b))]], aggr=[[COUNT(DISTINCT test.b), COUNT(DISTINCT test.c)]] [a:UInt32;N, b:UInt32;N, COUNT(DISTINCT test.b):Int64;N, COUNT(DISTINCT test.c):Int64;N]\
+                            \n  TableScan: test [a:UInt32, b:UInt32, c:UInt32]";
+
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn distinct_not_ignoring_nulls() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(
+                vec![col("a")],
+                vec![
+                    count_distinct(col("b")),
+                    distinct(AggregateFun::ArrayAgg, col("c")),
+                ],
+            )?
+            .build()?;
+
+        // Should not be optimized, ARRAY_AGG would collect the NULLs
+        let expected = "Aggregate: groupBy=[[test.a]], aggr=[[COUNT(DISTINCT test.b), ARRAY_AGG(DISTINCT test.c)]] [a:UInt32, COUNT(DISTINCT test.b):Int64;N, ARRAY_AGG(DISTINCT test.c):List(Field { name: \"item\", data_type: UInt32, nullable: true, dict_id: 0, dict_is_ordered: false, metadata: {} });N]\
+                            \n  TableScan: test [a:UInt32, b:UInt32, c:UInt32]";
+
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn common_not_splittable() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(
+                vec![col("a")],
+                vec![
+                    count_distinct(col("b")),
+                    count_distinct(col("c")),
+                    avg(col("c")),
+                ],
+            )?
+            .build()?;
+
+        // Should not be optimized
+        let expected = "Aggregate: groupBy=[[test.a]], aggr=[[COUNT(DISTINCT test.b), COUNT(DISTINCT test.c), AVG(test.c)]] [a:UInt32, COUNT(DISTINCT test.b):Int64;N, COUNT(DISTINCT test.c):Int64;N, AVG(test.c):Float64;N]\
+                            \n  TableScan: test [a:UInt32, b:UInt32, c:UInt32]";
+
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
+    #[test]
+    fn distinct_with_filter() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(
+                vec![col("a")],
+                vec![
+                    count_distinct(col("b")),
+                    Expr::AggregateFunction(AggregateFunction::new(
+                        Count,
+                        vec![col("c")],
+                        true,
+                        Some(Box::new(col("c").gt(lit(5)))),
+                        None,
+                    )),
+                ],
+            )?
+            .build()?;
+
+        // Should not be optimized
+        let expected = "Aggregate: groupBy=[[test.a]], aggr=[[COUNT(DISTINCT test.b), COUNT(DISTINCT test.c) FILTER (WHERE test.c > Int32(5))]] [a:UInt32, COUNT(DISTINCT test.b):Int64;N, COUNT(DISTINCT test.c) FILTER (WHERE test.c > Int32(5)):Int64;N]\
+                            \n  TableScan: test [a:UInt32, b:UInt32, c:UInt32]";
+
+        assert_optimized_plan_equal(&plan, expected)
+    }
+}
@@ -36,7 +36,7 @@ use datafusion_common::{
 use datafusion_expr::expr::{Alias, ScalarFunction, ScalarFunctionDefinition};
 use datafusion_expr::{
     logical_plan::LogicalPlan, projection_schema, Aggregate, BinaryExpr, Cast, Distinct,
-    Expr, GroupingSet, Projection, TableScan, Window,
+    Expr, GetFieldAccess, GetIndexedField, GroupingSet, Projection, TableScan, Window,
 };
 
 use hashbrown::HashMap;
@@ -501,8 +501,17 @@ fn trim_expr(expr: Expr) -> Expr {
 }
 
 // Check whether `expr` is trivial; i.e. it doesn't imply any computation.
+// Struct field accesses are trivial so that they end up right above the
+// scans, which may read only the accessed fields.
 fn is_expr_trivial(expr: &Expr) -> bool {
-    matches!(expr, Expr::Column(_) | Expr::Literal(_))
+    match expr {
+        Expr::Column(_) | Expr::Literal(_) => true,
+        Expr::GetIndexedField(GetIndexedField {
+            expr,
+            field: GetFieldAccess::NamedStructField { .. },
+        }) => is_expr_trivial(expr),
+        _ => false,
+    }
 }
 
 // Exit early when there is no rewrite to do.
@@ -554,6 +563,13 @@ fn rewrite_expr(expr: &Expr, input: &Projection) -> Result<Option<Expr>> {
             let new_expr = rewrite_expr_with_check!(&cast.expr, input);
             Expr::Cast(Cast::new(Box::new(new_expr), cast.data_type.clone()))
         }
+        Expr::GetIndexedField(GetIndexedField {
+            expr,
+            field: field @ GetFieldAccess::NamedStructField { .. },
+        }) => {
+            let new_expr = trim_expr(rewrite_expr_with_check!(expr, input));
+            Expr::GetIndexedField(GetIndexedField::new(Box::new(new_expr), field.clone()))
+        }
         Expr::ScalarFunction(scalar_fn) => {
             // TODO: Support UDFs.
             let ScalarFunctionDefinition::BuiltIn(fun) = scalar_fn.func_def else {
@@ -1092,6 +1108,30 @@ mod tests {
         assert_optimized_plan_equal(&plan, expected)
     }
 
+    #[test]
+    fn merge_projections_with_struct_field_access() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new_struct(
+                "s",
+                vec![
+                    Field::new("x", DataType::Int64, false),
+                    Field::new("y", DataType::Int64, false),
+                ],
+                false,
+            ),
+        ]);
+
+        // the field access is used twice, but is trivial
+        let plan = table_scan(TableReference::none(), &schema, None)?
+            .project(vec![col("s").field("x").alias("sx"), col("a")])?
+            .project(vec![binary_expr(col("sx"), Operator::Plus, col("sx"))])?
+            .build()?;
+        let expected = "Projection: (?table?.s)[x] + (?table?.s)[x] AS sx + sx\
+        \n  TableScan: ?table? projection=[s]";
+        assert_optimized_plan_equal(&plan, expected)
+    }
+
     #[test]
     fn test_neg_push_down() -> Result<()> {
         let table_scan = test_table_scan()?;
@@ -33,6 +33,7 @@ use crate::eliminate_one_union::EliminateOneUnion;
 use crate::eliminate_outer_join::EliminateOuterJoin;
 use crate::extract_equijoin_predicate::ExtractEquijoinPredicate;
 use crate::filter_null_join_keys::FilterNullJoinKeys;
+use crate::multi_distinct_to_groupby::MultiDistinctToGroupBy;
 use crate::optimize_projections::OptimizeProjections;
 use crate::plan_signature::LogicalPlanSignature;
 use crate::propagate_empty_relation::PropagateEmptyRelation;
@@ -123,6 +124,12 @@ impl OptimizerContext {
         self
     }
 
+    /// Specify whether to enable the multi_distinct_aggregation_to_group_by rule
+    pub fn with_multi_distinct_to_group_by(mut self, enabled: bool) -> Self {
+        self.options.optimizer.enable_multi_distinct_to_group_by = enabled;
+        self
+    }
+
     /// Specify whether the optimizer should skip rules that produce
     /// errors, or fail the query
     pub fn with_query_execution_start_time(
@@ -250,6 +257,7 @@ impl Optimizer {
             Arc::new(PushDownLimit::new()),
             Arc::new(PushDownFilter::new()),
             Arc::new(SingleDistinctToGroupBy::new()),
+            Arc::new(MultiDistinctToGroupBy::new()),
             // The previous optimizations added expressions and projections,
             // that might benefit from the following rules
             Arc::new(SimplifyExpressions::new()),
@@ -277,6 +285,8 @@ impl Optimizer {
     where
         F: FnMut(&LogicalPlan, &dyn OptimizerRule),
     {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("optimize").entered();
         let options = config.options();
         let mut new_plan = plan.clone();
 
@@ -290,6 +300,14 @@ impl Optimizer {
             log_plan(&format!("Optimizer input (pass {i})"), &new_plan);
 
             for rule in &self.rules {
+                #[cfg(feature = "tracing")]
+                let span = tracing::debug_span!(
+                    "optimizer_rule",
+                    rule = rule.name(),
+                    pass = i,
+                    changed = tracing::field::Empty
+                )
+                .entered();
                 let result =
                     self.optimize_recursively(rule, &new_plan, config)
                         .and_then(|plan| {
@@ -300,11 +318,15 @@ impl Optimizer {
                         });
                 match result {
                     Ok(Some(plan)) => {
+                        #[cfg(feature = "tracing")]
+                        span.record("changed", true);
                         new_plan = plan;
                         observer(&new_plan, rule.as_ref());
                         log_plan(rule.name(), &new_plan);
                     }
                     Ok(None) => {
+                        #[cfg(feature = "tracing")]
+                        span.record("changed", false);
                         observer(&new_plan, rule.as_ref());
                         debug!(
                             "Plan unchanged by optimizer rule '{}' (pass {})",
@@ -347,8 +347,10 @@ impl<'a> ConstEvaluator<'a> {
                 ScalarFunctionDefinition::BuiltIn(fun) => {
                     Self::volatility_ok(fun.volatility())
                 }
+                // async functions are only evaluated by their operator
                 ScalarFunctionDefinition::UDF(fun) => {
-                    Self::volatility_ok(fun.signature().volatility)
+                    fun.as_async().is_none()
+                        && Self::volatility_ok(fun.signature().volatility)
                 }
                 ScalarFunctionDefinition::Name(_) => false,
             },
@@ -0,0 +1,110 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::array::{ArrayRef, Float64Array, Int64Array};
+use arrow::compute::SortOptions;
+use arrow::datatypes::{DataType, Field, Schema};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use datafusion_physical_expr::expressions::{col, FirstValue, LastValue};
+use datafusion_physical_expr::{AggregateExpr, EmitTo, PhysicalSortExpr};
+use rand::prelude::*;
+use std::sync::Arc;
+
+fn do_bench(
+    c: &mut Criterion,
+    name: &str,
+    aggregate: &dyn AggregateExpr,
+    batches: &[(Vec<ArrayRef>, Vec<usize>)],
+    num_groups: usize,
+) {
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            let mut accumulator = aggregate.create_groups_accumulator().unwrap();
+            for (values, group_indices) in batches {
+                accumulator
+                    .update_batch(values, group_indices, None, num_groups)
+                    .unwrap();
+            }
+            black_box(accumulator.evaluate(EmitTo::All).unwrap())
+        })
+    });
+}
+
+fn do_benches(c: &mut Criterion, num_groups: usize, null_percent: f64) {
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Float64, true),
+        Field::new("b", DataType::Int64, true),
+    ]);
+    let ordering_req = vec![PhysicalSortExpr {
+        expr: col("b", &schema).unwrap(),
+        options: SortOptions::default(),
+    }];
+
+    let mut rng = StdRng::seed_from_u64(120320);
+    let batches: Vec<_> = (0..16)
+        .map(|_| {
+            let values: Float64Array = (0..8192)
+                .map(|_| rng.gen_bool(null_percent).then(|| rng.gen()))
+                .collect();
+            let orderings: Int64Array = (0..8192).map(|_| Some(rng.gen())).collect();
+            let group_indices = (0..8192).map(|_| rng.gen_range(0..num_groups)).collect();
+            let values = vec![Arc::new(values) as ArrayRef, Arc::new(orderings) as _];
+            (values, group_indices)
+        })
+        .collect();
+
+    let first = FirstValue::new(
+        col("a", &schema).unwrap(),
+        "FIRST_VALUE(a)",
+        DataType::Float64,
+        ordering_req.clone(),
+        vec![DataType::Int64],
+    );
+    do_bench(
+        c,
+        &format!("first_value ({num_groups}, {null_percent})"),
+        &first,
+        &batches,
+        num_groups,
+    );
+
+    let last = LastValue::new(
+        col("a", &schema).unwrap(),
+        "LAST_VALUE(a)",
+        DataType::Float64,
+        ordering_req,
+        vec![DataType::Int64],
+    );
+    do_bench(
+        c,
+        &format!("last_value ({num_groups}, {null_percent})"),
+        &last,
+        &batches,
+        num_groups,
+    );
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    for num_groups in [10, 1000, 100000] {
+        for null_percent in [0., 0.2] {
+            do_benches(c, num_groups, null_percent)
+        }
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,90 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::array::Int32Array;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use datafusion_expr::Operator;
+use datafusion_physical_expr::expressions::{binary, col, lit};
+use datafusion_physical_expr::PhysicalExpr;
+use rand::prelude::*;
+use std::sync::Arc;
+
+fn do_bench(
+    c: &mut Criterion,
+    name: &str,
+    batch: &RecordBatch,
+    expr: Arc<dyn PhysicalExpr>,
+) {
+    c.bench_function(name, |b| {
+        b.iter(|| black_box(expr.evaluate(black_box(batch)).unwrap()))
+    });
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int32, true),
+        Field::new("b", DataType::Int32, true),
+    ]);
+    let mut rng = StdRng::seed_from_u64(120320);
+    let a: Int32Array = (0..8192).map(|_| Some(rng.gen_range(0..100))).collect();
+    let b: Int32Array = (0..8192).map(|_| Some(rng.gen_range(1..100))).collect();
+    let batch =
+        RecordBatch::try_new(Arc::new(schema.clone()), vec![Arc::new(a), Arc::new(b)])
+            .unwrap();
+
+    let a = col("a", &schema).unwrap();
+    let b = col("b", &schema).unwrap();
+    for selectivity in [1, 10, 50, 90] {
+        let left = binary(a.clone(), Operator::Lt, lit(selectivity), &schema).unwrap();
+
+        // b > 50 is evaluated for all rows
+        let cheap = binary(b.clone(), Operator::Gt, lit(50), &schema).unwrap();
+        do_bench(
+            c,
+            &format!("a < {selectivity} AND b > 50"),
+            &batch,
+            binary(left.clone(), Operator::And, cheap.clone(), &schema).unwrap(),
+        );
+        do_bench(
+            c,
+            &format!("a < {selectivity} OR b > 50"),
+            &batch,
+            binary(left.clone(), Operator::Or, cheap, &schema).unwrap(),
+        );
+
+        // 1000 / b > 50 is evaluated only for the undetermined rows
+        let div = binary(lit(1000), Operator::Divide, b.clone(), &schema).unwrap();
+        let fallible = binary(div, Operator::Gt, lit(50), &schema).unwrap();
+        do_bench(
+            c,
+            &format!("a < {selectivity} AND 1000 / b > 50"),
+            &batch,
+            binary(left.clone(), Operator::And, fallible.clone(), &schema).unwrap(),
+        );
+        do_bench(
+            c,
+            &format!("a < {selectivity} OR 1000 / b > 50"),
+            &batch,
+            binary(left, Operator::Or, fallible, &schema).unwrap(),
+        );
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
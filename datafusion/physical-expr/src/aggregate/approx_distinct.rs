@@ -17,26 +17,31 @@
 
 //! Defines physical expressions that can evaluated at runtime during query execution
 
-use super::hyperloglog::HyperLogLog;
+use super::hyperloglog::{
+    add_hash, count_registers, merge_registers, registers_to_sketch, sketch_registers,
+    HLLHash, HyperLogLog, NUM_REGISTERS,
+};
+use crate::aggregate::groups_accumulator::accumulate::accumulate_rows;
 use crate::aggregate::utils::down_cast_any_ref;
 use crate::expressions::format_state_name;
-use crate::{AggregateExpr, PhysicalExpr};
+use crate::{AggregateExpr, EmitTo, GroupsAccumulator, PhysicalExpr};
 use arrow::array::{
-    ArrayRef, BinaryArray, GenericBinaryArray, GenericStringArray, OffsetSizeTrait,
-    PrimitiveArray,
+    Array, ArrayRef, AsArray, BinaryArray, BooleanArray, GenericBinaryArray,
+    GenericStringArray, OffsetSizeTrait, PrimitiveArray, UInt64Array,
 };
 use arrow::datatypes::{
     ArrowPrimitiveType, DataType, Field, Int16Type, Int32Type, Int64Type, Int8Type,
     UInt16Type, UInt32Type, UInt64Type, UInt8Type,
 };
+use arrow_buffer::{Buffer, OffsetBuffer};
 use datafusion_common::{
-    downcast_value, internal_err, not_impl_err, DataFusionError, Result, ScalarValue,
+    downcast_value, exec_err, internal_err, not_impl_err, DataFusionError, Result,
+    ScalarValue,
 };
 use datafusion_expr::Accumulator;
 use std::any::Any;
 use std::convert::TryFrom;
 use std::convert::TryInto;
-use std::hash::Hash;
 use std::marker::PhantomData;
 use std::sync::Arc;
 
@@ -114,6 +119,14 @@ impl AggregateExpr for ApproxDistinct {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn groups_accumulator_supported(&self) -> bool {
+        is_hll_supported_type(&self.input_data_type)
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(HLLGroupsAccumulator::new(false, false)))
+    }
 }
 
 impl PartialEq<dyn Any> for ApproxDistinct {
@@ -177,7 +190,7 @@ where
 struct NumericHLLAccumulator<T>
 where
     T: ArrowPrimitiveType,
-    T::Native: Hash,
+    T::Native: HLLHash,
 {
     hll: HyperLogLog<T::Native>,
 }
@@ -185,7 +198,7 @@ where
 impl<T> NumericHLLAccumulator<T>
 where
     T: ArrowPrimitiveType,
-    T::Native: Hash,
+    T::Native: HLLHash,
 {
     /// new approx_distinct accumulator
     pub fn new() -> Self {
@@ -195,14 +208,14 @@ where
     }
 }
 
-impl<T: Hash> From<&HyperLogLog<T>> for ScalarValue {
+impl<T: HLLHash> From<&HyperLogLog<T>> for ScalarValue {
     fn from(v: &HyperLogLog<T>) -> ScalarValue {
         let values = v.as_ref().to_vec();
         ScalarValue::Binary(Some(values))
     }
 }
 
-impl<T: Hash> TryFrom<&[u8]> for HyperLogLog<T> {
+impl<T: HLLHash> TryFrom<&[u8]> for HyperLogLog<T> {
     type Error = DataFusionError;
     fn try_from(v: &[u8]) -> Result<HyperLogLog<T>> {
         let arr: [u8; 16384] = v.try_into().map_err(|_| {
@@ -214,7 +227,7 @@ impl<T: Hash> TryFrom<&[u8]> for HyperLogLog<T> {
     }
 }
 
-impl<T: Hash> TryFrom<&ScalarValue> for HyperLogLog<T> {
+impl<T: HLLHash> TryFrom<&ScalarValue> for HyperLogLog<T> {
     type Error = DataFusionError;
     fn try_from(v: &ScalarValue) -> Result<HyperLogLog<T>> {
         if let ScalarValue::Binary(Some(slice)) = v {
@@ -295,7 +308,7 @@ where
 impl<T> Accumulator for NumericHLLAccumulator<T>
 where
     T: ArrowPrimitiveType + std::fmt::Debug,
-    T::Native: Hash,
+    T::Native: HLLHash,
 {
     fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
         let array: &PrimitiveArray<T> = downcast_value!(values[0], PrimitiveArray, T);
@@ -306,3 +319,281 @@ where
 
     default_accumulator_impl!();
 }
+
+/// Whether values of `data_type` can be added to a [`HyperLogLog`]
+pub(crate) fn is_hll_supported_type(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::Utf8
+            | DataType::LargeUtf8
+            | DataType::Binary
+            | DataType::LargeBinary
+    )
+}
+
+/// Computes the [`HLLHash::hll_hash`] of every value of `array`, including the
+/// values hidden by nulls.
+///
+/// The hashes match the ones of the row accumulators, so that the registers
+/// of both are interchangeable.
+pub(crate) fn hll_hashes(array: &ArrayRef) -> Result<Vec<u64>> {
+    macro_rules! primitive_hashes {
+        ($t:ty) => {
+            array
+                .as_primitive::<$t>()
+                .values()
+                .iter()
+                .map(HLLHash::hll_hash)
+                .collect()
+        };
+    }
+    macro_rules! bytes_hashes {
+        ($array:expr) => {{
+            let array = $array;
+            (0..array.len())
+                .map(|i| array.value(i).hll_hash())
+                .collect()
+        }};
+    }
+
+    Ok(match array.data_type() {
+        DataType::UInt8 => primitive_hashes!(UInt8Type),
+        DataType::UInt16 => primitive_hashes!(UInt16Type),
+        DataType::UInt32 => primitive_hashes!(UInt32Type),
+        DataType::UInt64 => primitive_hashes!(UInt64Type),
+        DataType::Int8 => primitive_hashes!(Int8Type),
+        DataType::Int16 => primitive_hashes!(Int16Type),
+        DataType::Int32 => primitive_hashes!(Int32Type),
+        DataType::Int64 => primitive_hashes!(Int64Type),
+        DataType::Utf8 => bytes_hashes!(array.as_string::<i32>()),
+        DataType::LargeUtf8 => bytes_hashes!(array.as_string::<i64>()),
+        DataType::Binary => bytes_hashes!(array.as_binary::<i32>()),
+        DataType::LargeBinary => bytes_hashes!(array.as_binary::<i64>()),
+        other => {
+            return not_impl_err!(
+                "Support for 'approx_distinct' for data type {other} is not implemented"
+            )
+        }
+    })
+}
+
+/// An accumulator to compute APPROX_DISTINCT, HLL_SKETCH and HLL_UNION for
+/// many groups at once.
+///
+/// The [`HyperLogLog`] registers of all groups are stored contiguously,
+/// `NUM_REGISTERS` bytes per group.
+#[derive(Debug)]
+pub(crate) struct HLLGroupsAccumulator {
+    registers: Vec<u8>,
+    /// Whether the input values are serialized sketches to merge
+    sketch_input: bool,
+    /// Whether to output serialized sketches instead of estimated counts
+    sketch_output: bool,
+}
+
+impl HLLGroupsAccumulator {
+    pub(crate) fn new(sketch_input: bool, sketch_output: bool) -> Self {
+        Self {
+            registers: vec![],
+            sketch_input,
+            sketch_output,
+        }
+    }
+
+    /// Returns the registers of `group_index`
+    fn group_registers(registers: &mut [u8], group_index: usize) -> &mut [u8] {
+        let start = group_index * NUM_REGISTERS;
+        &mut registers[start..start + NUM_REGISTERS]
+    }
+
+    /// Removes the registers of the groups selected by `emit_to`
+    fn emit(&mut self, emit_to: EmitTo) -> Vec<u8> {
+        match emit_to {
+            EmitTo::All => std::mem::take(&mut self.registers),
+            EmitTo::First(n) => self.registers.drain(..n * NUM_REGISTERS).collect(),
+        }
+    }
+}
+
+impl GroupsAccumulator for HLLGroupsAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 1, "single argument to update_batch");
+        let values = &values[0];
+        if self.sketch_input {
+            let sketches = values.as_binary::<i32>();
+            let others = sketches
+                .iter()
+                .map(|sketch| sketch.map(sketch_registers).transpose())
+                .collect::<Result<Vec<_>>>()?;
+            self.registers.resize(total_num_groups * NUM_REGISTERS, 0);
+
+            let registers = &mut self.registers;
+            accumulate_rows(
+                group_indices,
+                sketches.nulls(),
+                opt_filter,
+                |row_index, group_index| {
+                    if let Some(other) = others[row_index] {
+                        merge_registers(
+                            Self::group_registers(registers, group_index),
+                            other,
+                        )
+                    }
+                },
+            );
+            return Ok(());
+        }
+        self.registers.resize(total_num_groups * NUM_REGISTERS, 0);
+
+        let hashes = hll_hashes(values)?;
+        let registers = &mut self.registers;
+        // skip nulls
+        accumulate_rows(
+            group_indices,
+            values.nulls(),
+            opt_filter,
+            |row_index, group_index| {
+                add_hash(
+                    Self::group_registers(registers, group_index),
+                    hashes[row_index],
+                )
+            },
+        );
+        Ok(())
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 1, "one argument to merge_batch");
+        let states = values[0].as_binary::<i32>();
+        if states.iter().flatten().any(|v| v.len() != NUM_REGISTERS) {
+            return internal_err!("Impossibly got invalid binary array from states");
+        }
+        self.registers.resize(total_num_groups * NUM_REGISTERS, 0);
+
+        let registers = &mut self.registers;
+        accumulate_rows(
+            group_indices,
+            states.nulls(),
+            opt_filter,
+            |row_index, group_index| {
+                merge_registers(
+                    Self::group_registers(registers, group_index),
+                    states.value(row_index),
+                )
+            },
+        );
+        Ok(())
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        let registers = self.emit(emit_to);
+        if self.sketch_output {
+            let sketches: BinaryArray = registers
+                .chunks_exact(NUM_REGISTERS)
+                .map(|registers| Some(registers_to_sketch(registers)))
+                .collect();
+            return Ok(Arc::new(sketches));
+        }
+        let counts: UInt64Array = registers
+            .chunks_exact(NUM_REGISTERS)
+            .map(|registers| Some(count_registers(registers) as u64))
+            .collect();
+        Ok(Arc::new(counts))
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let registers = self.emit(emit_to);
+        if registers.len() > i32::MAX as usize {
+            return exec_err!(
+                "Too many groups for the state of 'approx_distinct': {}",
+                registers.len() / NUM_REGISTERS
+            );
+        }
+        let num_groups = registers.len() / NUM_REGISTERS;
+        let offsets =
+            OffsetBuffer::from_lengths(std::iter::repeat(NUM_REGISTERS).take(num_groups));
+        let states = BinaryArray::new(offsets, Buffer::from_vec(registers), None);
+        Ok(vec![Arc::new(states)])
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.registers.capacity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::col;
+    use crate::expressions::tests::assert_groups_accumulator;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::Schema;
+    use arrow::record_batch::RecordBatch;
+
+    #[test]
+    fn approx_distinct_groups_accumulator() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(1),
+            Some(2),
+            None,
+            Some(1),
+            Some(3),
+            Some(2),
+            Some(4),
+            Some(1),
+        ]));
+        let b: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("a"),
+            Some("b"),
+            Some("a"),
+            None,
+            Some("c"),
+            Some("b"),
+            Some("b"),
+            Some("d"),
+        ]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![a, b])?;
+        let group_indices = [0, 1, 0, 1, 2, 0, 1, 0];
+
+        let aggs: Vec<Arc<dyn AggregateExpr>> = vec![
+            Arc::new(ApproxDistinct::new(
+                col("a", &schema)?,
+                "bla",
+                DataType::Int32,
+            )),
+            Arc::new(ApproxDistinct::new(
+                col("b", &schema)?,
+                "bla",
+                DataType::Utf8,
+            )),
+        ];
+        for agg in aggs {
+            assert_groups_accumulator(&batch, agg, &group_indices)?;
+        }
+        Ok(())
+    }
+}
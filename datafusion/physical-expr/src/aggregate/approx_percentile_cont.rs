@@ -133,7 +133,7 @@ impl PartialEq for ApproxPercentileCont {
     }
 }
 
-fn get_lit_value(expr: &Arc<dyn PhysicalExpr>) -> Result<ScalarValue> {
+pub(crate) fn get_lit_value(expr: &Arc<dyn PhysicalExpr>) -> Result<ScalarValue> {
     let empty_schema = Schema::empty();
     let empty_batch = RecordBatch::new_empty(Arc::new(empty_schema));
     let result = expr.evaluate(&empty_batch)?;
@@ -0,0 +1,208 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the APPROX_TOP_K_SKETCH aggregate expression, building serialized
+//! sketches of the most frequent values
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{internal_err, DataFusionError, Result, ScalarValue};
+use datafusion_expr::Accumulator;
+
+use super::kll_sketch::validate_sketch_size_expr;
+use super::space_saving::{SpaceSaving, DEFAULT_CAPACITY, MAX_CAPACITY};
+use crate::aggregate::utils::down_cast_any_ref;
+use crate::expressions::format_state_name;
+use crate::{AggregateExpr, PhysicalExpr};
+
+/// APPROX_TOP_K_SKETCH aggregate expression
+///
+/// Returns a serialized Space-Saving sketch of the most frequent values of
+/// its input, compared by their string representation. The optional second
+/// argument is the number of tracked values, which trades accuracy for size.
+#[derive(Debug)]
+pub struct ApproxTopKSketch {
+    name: String,
+    expr: Vec<Arc<dyn PhysicalExpr>>,
+    capacity: usize,
+}
+
+impl ApproxTopKSketch {
+    /// Create a new ApproxTopKSketch aggregate function.
+    pub fn new(
+        expr: Vec<Arc<dyn PhysicalExpr>>,
+        name: impl Into<String>,
+    ) -> Result<Self> {
+        let capacity = match expr.get(1) {
+            Some(capacity) => validate_sketch_size_expr(
+                capacity,
+                "APPROX_TOP_K_SKETCH",
+                1..=MAX_CAPACITY,
+            )?,
+            None => DEFAULT_CAPACITY,
+        };
+        Ok(Self {
+            name: name.into(),
+            expr,
+            capacity,
+        })
+    }
+}
+
+impl AggregateExpr for ApproxTopKSketch {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, DataType::Binary, false))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![Field::new(
+            format_state_name(&self.name, "top_k_sketch"),
+            DataType::Binary,
+            false,
+        )])
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        self.expr.clone()
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(ApproxTopKSketchAccumulator {
+            sketch: SpaceSaving::new(self.capacity),
+        }))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl PartialEq<dyn Any> for ApproxTopKSketch {
+    fn eq(&self, other: &dyn Any) -> bool {
+        down_cast_any_ref(other)
+            .downcast_ref::<Self>()
+            .map(|x| {
+                self.name == x.name
+                    && self.capacity == x.capacity
+                    && self.expr.len() == x.expr.len()
+                    && self.expr.iter().zip(x.expr.iter()).all(|(a, b)| a.eq(b))
+            })
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Debug)]
+struct ApproxTopKSketchAccumulator {
+    sketch: SpaceSaving,
+}
+
+impl Accumulator for ApproxTopKSketchAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = cast(&values[0], &DataType::Utf8)?;
+        // skip nulls
+        for value in values.as_string::<i32>().iter().flatten() {
+            self.sketch.update(value);
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        if states.len() != 1 {
+            return internal_err!("expect only 1 element in the states");
+        }
+        for sketch in states[0].as_binary::<i32>().iter().flatten() {
+            self.sketch.merge(&SpaceSaving::from_bytes(sketch)?);
+        }
+        Ok(())
+    }
+
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Binary(Some(self.sketch.to_bytes())))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) - std::mem::size_of_val(&self.sketch)
+            + self.sketch.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::tests::aggregate;
+    use crate::expressions::{col, lit};
+    use arrow::array::Int32Array;
+    use arrow::datatypes::Schema;
+    use arrow::record_batch::RecordBatch;
+
+    #[test]
+    fn approx_top_k_sketch() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![
+                Some(1),
+                Some(2),
+                None,
+                Some(1),
+                None,
+                Some(3),
+                Some(1),
+            ]))],
+        )?;
+
+        let agg = Arc::new(ApproxTopKSketch::new(
+            vec![col("a", &schema)?, lit(10_i64)],
+            "bla",
+        )?);
+        let ScalarValue::Binary(Some(sketch)) = aggregate(&batch, agg)? else {
+            return internal_err!("Unexpected sketch");
+        };
+        let sketch = SpaceSaving::from_bytes(&sketch)?;
+        // the nulls are not counted
+        let total: u64 = sketch.top_k(10).iter().map(|(_, c)| c.count).sum();
+        assert_eq!(total, 5);
+        let top_k = sketch
+            .top_k(2)
+            .into_iter()
+            .map(|(item, counter)| (item, counter.count))
+            .collect::<Vec<_>>();
+        assert_eq!(top_k, vec![("1", 3), ("2", 1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn approx_top_k_sketch_invalid_capacity() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let err = ApproxTopKSketch::new(vec![col("a", &schema)?, lit(0_i64)], "bla")
+            .unwrap_err();
+        assert!(err.to_string().contains("0 is invalid"), "{err}");
+        Ok(())
+    }
+}
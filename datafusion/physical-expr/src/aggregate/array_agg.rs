@@ -17,12 +17,15 @@
 
 //! Defines physical expressions that can evaluated at runtime during query execution
 
+use crate::aggregate::groups_accumulator::accumulate::accumulate_rows;
 use crate::aggregate::utils::down_cast_any_ref;
 use crate::expressions::format_state_name;
-use crate::{AggregateExpr, PhysicalExpr};
-use arrow::array::ArrayRef;
+use crate::{AggregateExpr, EmitTo, GroupsAccumulator, PhysicalExpr};
+use arrow::array::{new_empty_array, ArrayRef, BooleanArray, ListArray};
+use arrow::compute::interleave;
 use arrow::datatypes::{DataType, Field};
 use arrow_array::Array;
+use arrow_buffer::OffsetBuffer;
 use datafusion_common::cast::as_list_array;
 use datafusion_common::utils::array_into_list_array;
 use datafusion_common::Result;
@@ -96,6 +99,16 @@ impl AggregateExpr for ArrayAgg {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn groups_accumulator_supported(&self) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(ArrayAggGroupsAccumulator::new(
+            &self.input_data_type,
+        )))
+    }
 }
 
 impl PartialEq<dyn Any> for ArrayAgg {
@@ -187,11 +200,187 @@ impl Accumulator for ArrayAggAccumulator {
     }
 }
 
+/// An accumulator to compute ARRAY_AGG for many groups at once.
+///
+/// The input arrays are retained as they are, together with the group and
+/// position of every aggregated row. The values are only copied into lists
+/// when the groups are emitted.
+#[derive(Debug)]
+struct ArrayAggGroupsAccumulator {
+    datatype: DataType,
+    /// The retained input arrays
+    batches: Vec<ArrayRef>,
+    /// Memory used by `batches`
+    batches_size: usize,
+    /// The group of every aggregated row, in the order of aggregation
+    groups: Vec<usize>,
+    /// The `(batch, row)` of every aggregated row, matching `groups`
+    indices: Vec<(usize, usize)>,
+    num_groups: usize,
+}
+
+impl ArrayAggGroupsAccumulator {
+    fn new(datatype: &DataType) -> Self {
+        Self {
+            datatype: datatype.clone(),
+            batches: vec![],
+            batches_size: 0,
+            groups: vec![],
+            indices: vec![],
+            num_groups: 0,
+        }
+    }
+
+    fn push_batch(&mut self, batch: ArrayRef) {
+        self.batches_size += batch.get_array_memory_size();
+        self.batches.push(batch);
+    }
+
+    /// Gathers the values at `indices` from the retained arrays
+    fn take_values(&self, indices: &[(usize, usize)]) -> Result<ArrayRef> {
+        if indices.is_empty() {
+            return Ok(new_empty_array(&self.datatype));
+        }
+        let batches: Vec<&dyn Array> = self.batches.iter().map(|b| b.as_ref()).collect();
+        Ok(interleave(&batches, indices)?)
+    }
+
+    /// Removes the groups selected by `emit_to` and returns their lists
+    fn emit(&mut self, emit_to: EmitTo) -> Result<ListArray> {
+        let num_emitted = match emit_to {
+            EmitTo::All => self.num_groups,
+            EmitTo::First(n) => n,
+        };
+
+        // Stable counting sort of the emitted rows by group
+        let mut lengths = vec![0; num_emitted];
+        for &group_index in &self.groups {
+            if group_index < num_emitted {
+                lengths[group_index] += 1;
+            }
+        }
+        let mut positions = Vec::with_capacity(num_emitted);
+        let mut num_values = 0;
+        for length in &lengths {
+            positions.push(num_values);
+            num_values += length;
+        }
+        let mut emitted = vec![(0, 0); num_values];
+        let mut retained_groups = vec![];
+        let mut retained_indices = vec![];
+        for (&group_index, &index) in self.groups.iter().zip(&self.indices) {
+            if group_index < num_emitted {
+                emitted[positions[group_index]] = index;
+                positions[group_index] += 1;
+            } else {
+                retained_groups.push(group_index - num_emitted);
+                retained_indices.push(index);
+            }
+        }
+
+        let values = self.take_values(&emitted)?;
+        // Compact the retained rows, so that the arrays of the emitted
+        // rows can be released
+        let retained = self.take_values(&retained_indices)?;
+        self.batches.clear();
+        self.batches_size = 0;
+        self.indices = (0..retained.len()).map(|row| (0, row)).collect();
+        if !retained.is_empty() {
+            self.push_batch(retained);
+        }
+        self.groups = retained_groups;
+        self.num_groups -= num_emitted;
+
+        Ok(ListArray::new(
+            Arc::new(Field::new("item", self.datatype.clone(), true)),
+            OffsetBuffer::from_lengths(lengths),
+            values,
+            None,
+        ))
+    }
+}
+
+impl GroupsAccumulator for ArrayAggGroupsAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 1, "single argument to update_batch");
+        self.num_groups = self.num_groups.max(total_num_groups);
+
+        let num_rows = self.groups.len();
+        let batch_index = self.batches.len();
+        let (groups, indices) = (&mut self.groups, &mut self.indices);
+        // nulls are aggregated as well
+        accumulate_rows(group_indices, None, opt_filter, |row_index, group_index| {
+            groups.push(group_index);
+            indices.push((batch_index, row_index));
+        });
+        if self.groups.len() > num_rows {
+            self.push_batch(values[0].clone());
+        }
+        Ok(())
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 1, "one argument to merge_batch");
+        self.num_groups = self.num_groups.max(total_num_groups);
+
+        let lists = as_list_array(&values[0])?;
+        let offsets = lists.value_offsets();
+        let num_rows = self.groups.len();
+        let batch_index = self.batches.len();
+        let (groups, indices) = (&mut self.groups, &mut self.indices);
+        accumulate_rows(
+            group_indices,
+            lists.nulls(),
+            opt_filter,
+            |row_index, group_index| {
+                let start = offsets[row_index] as usize;
+                let end = offsets[row_index + 1] as usize;
+                for value_index in start..end {
+                    groups.push(group_index);
+                    indices.push((batch_index, value_index));
+                }
+            },
+        );
+        if self.groups.len() > num_rows {
+            self.push_batch(lists.values().clone());
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        Ok(Arc::new(self.emit(emit_to)?))
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        Ok(vec![Arc::new(self.emit(emit_to)?)])
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.batches.capacity() * std::mem::size_of::<ArrayRef>()
+            + self.batches_size
+            + self.groups.capacity() * std::mem::size_of::<usize>()
+            + self.indices.capacity() * std::mem::size_of::<(usize, usize)>()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::expressions::col;
-    use crate::expressions::tests::aggregate;
+    use crate::expressions::tests::{aggregate, assert_groups_accumulator};
     use arrow::array::ArrayRef;
     use arrow::array::Int32Array;
     use arrow::datatypes::*;
@@ -307,4 +496,27 @@ mod tests {
             DataType::List(Arc::new(Field::new("item", DataType::Int32, true,)))
         )
     }
+
+    #[test]
+    fn array_agg_groups_accumulator() -> Result<()> {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(1),
+            None,
+            Some(3),
+            Some(4),
+            Some(5),
+            None,
+            Some(7),
+        ]));
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![a])?;
+        let agg = Arc::new(ArrayAgg::new(
+            col("a", &schema)?,
+            "bla".to_string(),
+            DataType::Int32,
+            true,
+        ));
+        // group 2 has no rows and evaluates to an empty list
+        assert_groups_accumulator(&batch, agg, &[1, 0, 1, 3, 0, 1, 3])
+    }
 }
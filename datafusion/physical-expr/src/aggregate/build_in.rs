@@ -385,6 +385,34 @@ pub fn create_aggregate_expr(
         (AggregateFunction::StringAgg, true) => {
             return not_impl_err!("STRING_AGG(DISTINCT) aggregations are not available");
         }
+        (AggregateFunction::HllSketch, _) => Arc::new(expressions::HllSketch::new(
+            input_phy_exprs[0].clone(),
+            name,
+            data_type,
+        )),
+        (AggregateFunction::HllUnion, _) => {
+            Arc::new(expressions::HllUnion::new(input_phy_exprs[0].clone(), name))
+        }
+        (AggregateFunction::KllSketch, false) => {
+            Arc::new(expressions::KllSketch::new(input_phy_exprs, name)?)
+        }
+        (AggregateFunction::KllSketch, true) => {
+            return not_impl_err!("KLL_SKETCH(DISTINCT) aggregations are not available");
+        }
+        (AggregateFunction::KllMerge, false) => {
+            Arc::new(expressions::KllMerge::new(input_phy_exprs[0].clone(), name))
+        }
+        (AggregateFunction::KllMerge, true) => {
+            return not_impl_err!("KLL_MERGE(DISTINCT) aggregations are not available");
+        }
+        (AggregateFunction::ApproxTopKSketch, false) => {
+            Arc::new(expressions::ApproxTopKSketch::new(input_phy_exprs, name)?)
+        }
+        (AggregateFunction::ApproxTopKSketch, true) => {
+            return not_impl_err!(
+                "APPROX_TOP_K_SKETCH(DISTINCT) aggregations are not available"
+            );
+        }
     })
 }
 
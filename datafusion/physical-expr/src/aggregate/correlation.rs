@@ -17,17 +17,22 @@
 
 //! Defines physical expressions that can evaluated at runtime during query execution
 
-use crate::aggregate::covariance::CovarianceAccumulator;
+use crate::aggregate::covariance::{
+    covariance_merge, covariance_update, CovarianceAccumulator,
+};
+use crate::aggregate::groups_accumulator::accumulate::accumulate_rows;
 use crate::aggregate::stats::StatsType;
 use crate::aggregate::stddev::StddevAccumulator;
 use crate::aggregate::utils::down_cast_any_ref;
+use crate::aggregate::variance::{welford_merge, welford_update};
 use crate::expressions::format_state_name;
-use crate::{AggregateExpr, PhysicalExpr};
+use crate::{AggregateExpr, EmitTo, GroupsAccumulator, PhysicalExpr};
 use arrow::{
-    array::ArrayRef,
-    compute::{and, filter, is_not_null},
-    datatypes::{DataType, Field},
+    array::{Array, ArrayRef, AsArray, BooleanArray, Float64Array, UInt64Array},
+    compute::{and, cast, filter, is_not_null},
+    datatypes::{DataType, Field, Float64Type, UInt64Type},
 };
+use arrow_buffer::{BooleanBuffer, NullBuffer};
 use datafusion_common::Result;
 use datafusion_common::ScalarValue;
 use datafusion_expr::Accumulator;
@@ -116,6 +121,14 @@ impl AggregateExpr for Correlation {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn groups_accumulator_supported(&self) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(CorrelationGroupsAccumulator::default()))
+    }
 }
 
 impl PartialEq<dyn Any> for Correlation {
@@ -245,12 +258,234 @@ impl Accumulator for CorrelationAccumulator {
     }
 }
 
+/// An accumulator to compute correlation for many groups at once
+///
+/// The covariance and the two standard deviations share the same count
+/// and means, so they are only stored once per group.
+#[derive(Debug, Default)]
+struct CorrelationGroupsAccumulator {
+    counts: Vec<u64>,
+    means1: Vec<f64>,
+    m2s_1: Vec<f64>,
+    means2: Vec<f64>,
+    m2s_2: Vec<f64>,
+    algo_consts: Vec<f64>,
+}
+
+impl CorrelationGroupsAccumulator {
+    fn resize(&mut self, total_num_groups: usize) {
+        self.counts.resize(total_num_groups, 0_u64);
+        self.means1.resize(total_num_groups, 0_f64);
+        self.m2s_1.resize(total_num_groups, 0_f64);
+        self.means2.resize(total_num_groups, 0_f64);
+        self.m2s_2.resize(total_num_groups, 0_f64);
+        self.algo_consts.resize(total_num_groups, 0_f64);
+    }
+
+    /// Updates the state of `group_index` with the given partial
+    /// `(count, mean1, m2_1, mean2, m2_2, algo_const)` state
+    fn merge(&mut self, group_index: usize, other: (u64, f64, f64, f64, f64, f64)) {
+        let (count, mean1, m2_1, mean2, m2_2, algo_const) = other;
+        let (new_count, new_mean1, new_m2_1) = welford_merge(
+            self.counts[group_index],
+            self.means1[group_index],
+            self.m2s_1[group_index],
+            count,
+            mean1,
+            m2_1,
+        );
+        let (_, new_mean2, new_m2_2) = welford_merge(
+            self.counts[group_index],
+            self.means2[group_index],
+            self.m2s_2[group_index],
+            count,
+            mean2,
+            m2_2,
+        );
+        let (_, _, _, new_algo_const) = covariance_merge(
+            (
+                self.counts[group_index],
+                self.means1[group_index],
+                self.means2[group_index],
+                self.algo_consts[group_index],
+            ),
+            (count, mean1, mean2, algo_const),
+        );
+
+        self.counts[group_index] = new_count;
+        self.means1[group_index] = new_mean1;
+        self.m2s_1[group_index] = new_m2_1;
+        self.means2[group_index] = new_mean2;
+        self.m2s_2[group_index] = new_m2_2;
+        self.algo_consts[group_index] = new_algo_const;
+    }
+}
+
+impl GroupsAccumulator for CorrelationGroupsAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 2, "two arguments to update_batch");
+        let values1 = cast(&values[0], &DataType::Float64)?;
+        let values1 = values1.as_primitive::<Float64Type>();
+        let values2 = cast(&values[1], &DataType::Float64)?;
+        let values2 = values2.as_primitive::<Float64Type>();
+        // only rows where both values are non null are considered
+        let nulls = NullBuffer::union(values1.nulls(), values2.nulls());
+
+        self.resize(total_num_groups);
+        accumulate_rows(
+            group_indices,
+            nulls.as_ref(),
+            opt_filter,
+            |row_index, group_index| {
+                let value1 = values1.value(row_index);
+                let value2 = values2.value(row_index);
+                let count = self.counts[group_index];
+
+                let (new_count, new_mean1, new_m2_1) = welford_update(
+                    count,
+                    self.means1[group_index],
+                    self.m2s_1[group_index],
+                    value1,
+                );
+                let (_, new_mean2, new_m2_2) = welford_update(
+                    count,
+                    self.means2[group_index],
+                    self.m2s_2[group_index],
+                    value2,
+                );
+                let (_, _, _, new_algo_const) = covariance_update(
+                    (
+                        count,
+                        self.means1[group_index],
+                        self.means2[group_index],
+                        self.algo_consts[group_index],
+                    ),
+                    value1,
+                    value2,
+                );
+
+                self.counts[group_index] = new_count;
+                self.means1[group_index] = new_mean1;
+                self.m2s_1[group_index] = new_m2_1;
+                self.means2[group_index] = new_mean2;
+                self.m2s_2[group_index] = new_m2_2;
+                self.algo_consts[group_index] = new_algo_const;
+            },
+        );
+
+        Ok(())
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 6, "six arguments to merge_batch");
+        let partial_counts = values[0].as_primitive::<UInt64Type>();
+        let partial_means1 = values[1].as_primitive::<Float64Type>();
+        let partial_m2s_1 = values[2].as_primitive::<Float64Type>();
+        let partial_means2 = values[3].as_primitive::<Float64Type>();
+        let partial_m2s_2 = values[4].as_primitive::<Float64Type>();
+        let partial_algo_consts = values[5].as_primitive::<Float64Type>();
+
+        self.resize(total_num_groups);
+        accumulate_rows(
+            group_indices,
+            partial_counts.nulls(),
+            opt_filter,
+            |row_index, group_index| {
+                self.merge(
+                    group_index,
+                    (
+                        partial_counts.value(row_index),
+                        partial_means1.value(row_index),
+                        partial_m2s_1.value(row_index),
+                        partial_means2.value(row_index),
+                        partial_m2s_2.value(row_index),
+                        partial_algo_consts.value(row_index),
+                    ),
+                )
+            },
+        );
+
+        Ok(())
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        let counts = emit_to.take_needed(&mut self.counts);
+        let _ = emit_to.take_needed(&mut self.means1);
+        let m2s_1 = emit_to.take_needed(&mut self.m2s_1);
+        let _ = emit_to.take_needed(&mut self.means2);
+        let m2s_2 = emit_to.take_needed(&mut self.m2s_2);
+        let algo_consts = emit_to.take_needed(&mut self.algo_consts);
+
+        let nulls: BooleanBuffer = counts.iter().map(|count| *count > 0).collect();
+        let values: Vec<f64> = (0..counts.len())
+            .map(|i| {
+                if counts[i] == 0 {
+                    return 0_f64;
+                }
+                let count = counts[i] as f64;
+                let stddev1 = (m2s_1[i] / count).sqrt();
+                let stddev2 = (m2s_2[i] / count).sqrt();
+                if stddev1 == 0_f64 || stddev2 == 0_f64 {
+                    0_f64
+                } else {
+                    algo_consts[i] / count / stddev1 / stddev2
+                }
+            })
+            .collect();
+
+        Ok(Arc::new(Float64Array::new(
+            values.into(),
+            Some(NullBuffer::new(nulls)),
+        )))
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let counts = emit_to.take_needed(&mut self.counts);
+        let means1 = emit_to.take_needed(&mut self.means1);
+        let m2s_1 = emit_to.take_needed(&mut self.m2s_1);
+        let means2 = emit_to.take_needed(&mut self.means2);
+        let m2s_2 = emit_to.take_needed(&mut self.m2s_2);
+        let algo_consts = emit_to.take_needed(&mut self.algo_consts);
+
+        Ok(vec![
+            Arc::new(UInt64Array::new(counts.into(), None)),
+            Arc::new(Float64Array::new(means1.into(), None)),
+            Arc::new(Float64Array::new(m2s_1.into(), None)),
+            Arc::new(Float64Array::new(means2.into(), None)),
+            Arc::new(Float64Array::new(m2s_2.into(), None)),
+            Arc::new(Float64Array::new(algo_consts.into(), None)),
+        ])
+    }
+
+    fn size(&self) -> usize {
+        self.counts.capacity() * std::mem::size_of::<u64>()
+            + (self.means1.capacity()
+                + self.m2s_1.capacity()
+                + self.means2.capacity()
+                + self.m2s_2.capacity()
+                + self.algo_consts.capacity())
+                * std::mem::size_of::<f64>()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::aggregate::utils::get_accum_scalar_values_as_arrays;
     use crate::expressions::col;
-    use crate::expressions::tests::aggregate;
+    use crate::expressions::tests::{aggregate, assert_groups_accumulator};
     use crate::generic_test_op2;
     use arrow::record_batch::RecordBatch;
     use arrow::{array::*, datatypes::*};
@@ -492,6 +727,47 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn correlation_groups_accumulator() -> Result<()> {
+        let a: ArrayRef = Arc::new(Float64Array::from(vec![
+            Some(1.0),
+            Some(2.0),
+            None,
+            Some(3.0),
+            Some(4.5),
+            Some(-1.0),
+            Some(7.0),
+            Some(2.5),
+            Some(3.0),
+        ]));
+        let b: ArrayRef = Arc::new(Float64Array::from(vec![
+            Some(4.0),
+            Some(-5.0),
+            Some(6.0),
+            None,
+            Some(8.0),
+            Some(1.0),
+            Some(0.0),
+            Some(3.0),
+            Some(3.0),
+        ]));
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Float64, true),
+            Field::new("b", DataType::Float64, true),
+        ]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![a, b])?;
+        // group 3 has a single value, so its standard deviations are zero
+        let group_indices = [0, 1, 0, 1, 2, 0, 1, 0, 3];
+
+        let agg = Arc::new(Correlation::new(
+            col("a", &schema)?,
+            col("b", &schema)?,
+            "bla".to_string(),
+            DataType::Float64,
+        ));
+        assert_groups_accumulator(&batch, agg, &group_indices)
+    }
+
     fn merge(
         batch1: &RecordBatch,
         batch2: &RecordBatch,
@@ -23,7 +23,10 @@ use arrow_array::types::{
     TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
     TimestampSecondType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
 };
-use arrow_array::PrimitiveArray;
+use arrow_array::{
+    downcast_primitive, BooleanArray, Int64Array, ListArray, PrimitiveArray,
+};
+use arrow_buffer::OffsetBuffer;
 
 use std::any::Any;
 use std::cmp::Eq;
@@ -35,12 +38,13 @@ use ahash::RandomState;
 use arrow::array::{Array, ArrayRef};
 use std::collections::HashSet;
 
+use crate::aggregate::groups_accumulator::accumulate::accumulate_rows;
 use crate::aggregate::utils::{down_cast_any_ref, Hashable};
 use crate::expressions::format_state_name;
-use crate::{AggregateExpr, PhysicalExpr};
+use crate::{AggregateExpr, EmitTo, GroupsAccumulator, PhysicalExpr};
 use datafusion_common::cast::{as_list_array, as_primitive_array};
 use datafusion_common::utils::array_into_list_array;
-use datafusion_common::{Result, ScalarValue};
+use datafusion_common::{not_impl_err, DataFusionError, Result, ScalarValue};
 use datafusion_expr::Accumulator;
 
 type DistinctScalarValues = ScalarValue;
@@ -162,6 +166,27 @@ impl AggregateExpr for DistinctCount {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn groups_accumulator_supported(&self) -> bool {
+        self.state_data_type.is_primitive()
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        macro_rules! helper {
+            ($t:ty, $dt:expr) => {
+                Ok(Box::new(CountDistinctGroupsAccumulator::<$t>::new($dt)))
+            };
+        }
+        let dt = &self.state_data_type;
+        downcast_primitive! {
+            dt => (helper, dt),
+            _ => not_impl_err!(
+                "CountDistinctGroupsAccumulator not supported for {} with {}",
+                self.name(),
+                self.state_data_type
+            ),
+        }
+    }
 }
 
 impl PartialEq<dyn Any> for DistinctCount {
@@ -438,20 +463,177 @@ where
     }
 }
 
+/// An accumulator to compute COUNT(DISTINCT) of primitive values for many
+/// groups at once.
+///
+/// The distinct values of all groups are kept in a single set, keyed by
+/// their group, and the number of distinct values of every group is
+/// maintained as they are inserted.
+#[derive(Debug)]
+struct CountDistinctGroupsAccumulator<T: ArrowPrimitiveType> {
+    values: HashSet<(usize, Hashable<T::Native>), RandomState>,
+    /// The number of distinct values of every group
+    counts: Vec<i64>,
+    state_data_type: DataType,
+}
+
+impl<T: ArrowPrimitiveType> CountDistinctGroupsAccumulator<T> {
+    fn new(state_data_type: &DataType) -> Self {
+        Self {
+            values: HashSet::default(),
+            counts: vec![],
+            state_data_type: state_data_type.clone(),
+        }
+    }
+
+    /// Removes the groups selected by `emit_to`, returning their counts and,
+    /// if `with_values` is set, their distinct values ordered by group
+    fn emit(&mut self, emit_to: EmitTo, with_values: bool) -> (Vec<i64>, Vec<T::Native>) {
+        let counts = emit_to.take_needed(&mut self.counts);
+        if matches!(emit_to, EmitTo::All) && !with_values {
+            self.values.clear();
+            return (counts, vec![]);
+        }
+
+        let num_emitted = counts.len();
+        let mut positions = Vec::with_capacity(num_emitted);
+        let mut num_values = 0;
+        for count in &counts {
+            positions.push(num_values);
+            num_values += *count as usize;
+        }
+        let mut emitted = if with_values {
+            vec![T::Native::default(); num_values]
+        } else {
+            vec![]
+        };
+        for (group_index, value) in std::mem::take(&mut self.values) {
+            if group_index >= num_emitted {
+                self.values.insert((group_index - num_emitted, value));
+            } else if with_values {
+                emitted[positions[group_index]] = value.0;
+                positions[group_index] += 1;
+            }
+        }
+        (counts, emitted)
+    }
+}
+
+impl<T: ArrowPrimitiveType + Debug> GroupsAccumulator
+    for CountDistinctGroupsAccumulator<T>
+{
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 1, "single argument to update_batch");
+        let values = as_primitive_array::<T>(&values[0])?;
+        self.counts.resize(total_num_groups, 0);
+
+        let (distinct_values, counts) = (&mut self.values, &mut self.counts);
+        // skip nulls
+        accumulate_rows(
+            group_indices,
+            values.nulls(),
+            opt_filter,
+            |row_index, group_index| {
+                let value = Hashable(values.value(row_index));
+                if distinct_values.insert((group_index, value)) {
+                    counts[group_index] += 1;
+                }
+            },
+        );
+        Ok(())
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 1, "one argument to merge_batch");
+        let lists = as_list_array(&values[0])?;
+        let list_values = as_primitive_array::<T>(lists.values())?;
+        self.counts.resize(total_num_groups, 0);
+
+        let offsets = lists.value_offsets();
+        let (distinct_values, counts) = (&mut self.values, &mut self.counts);
+        accumulate_rows(
+            group_indices,
+            lists.nulls(),
+            opt_filter,
+            |row_index, group_index| {
+                let start = offsets[row_index] as usize;
+                let end = offsets[row_index + 1] as usize;
+                for value_index in start..end {
+                    if list_values.is_null(value_index) {
+                        continue;
+                    }
+                    let value = Hashable(list_values.value(value_index));
+                    if distinct_values.insert((group_index, value)) {
+                        counts[group_index] += 1;
+                    }
+                }
+            },
+        );
+        Ok(())
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        let (counts, _) = self.emit(emit_to, false);
+        Ok(Arc::new(Int64Array::from(counts)))
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let (counts, values) = self.emit(emit_to, true);
+        let values = PrimitiveArray::<T>::new(values.into(), None)
+            .with_data_type(self.state_data_type.clone());
+        let lists = ListArray::new(
+            Arc::new(Field::new("item", self.state_data_type.clone(), true)),
+            OffsetBuffer::from_lengths(counts.into_iter().map(|c| c as usize)),
+            Arc::new(values),
+            None,
+        );
+        Ok(vec![Arc::new(lists)])
+    }
+
+    fn size(&self) -> usize {
+        let estimated_buckets = (self.values.len().checked_mul(8).unwrap_or(usize::MAX)
+            / 7)
+        .next_power_of_two();
+
+        // Size of accumulator
+        // + size of entry * number of buckets
+        // + 1 byte for each bucket
+        // + size of the counts
+        std::mem::size_of_val(self)
+            + std::mem::size_of::<(usize, T::Native)>() * estimated_buckets
+            + estimated_buckets
+            + self.counts.capacity() * std::mem::size_of::<i64>()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::expressions::NoOp;
+    use crate::expressions::tests::assert_groups_accumulator;
+    use crate::expressions::{col, NoOp};
 
     use super::*;
     use arrow::array::{
         ArrayRef, BooleanArray, Float32Array, Float64Array, Int16Array, Int32Array,
         Int64Array, Int8Array, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
     };
-    use arrow::datatypes::DataType;
+    use arrow::datatypes::{DataType, Schema};
     use arrow::datatypes::{
         Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type,
         UInt32Type, UInt64Type, UInt8Type,
     };
+    use arrow::record_batch::RecordBatch;
     use arrow_array::Decimal256Array;
     use arrow_buffer::i256;
     use datafusion_common::cast::{as_boolean_array, as_list_array, as_primitive_array};
@@ -844,4 +1026,47 @@ mod tests {
         assert_eq!(result, ScalarValue::Int64(Some(2)));
         Ok(())
     }
+
+    #[test]
+    fn count_distinct_groups_accumulator() -> Result<()> {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(1),
+            Some(1),
+            None,
+            Some(3),
+            Some(2),
+            Some(1),
+            Some(2),
+            Some(3),
+            None,
+        ]));
+        let b: ArrayRef = Arc::new(Float64Array::from(vec![
+            Some(f64::NAN),
+            Some(1.0),
+            Some(-0.0),
+            Some(f64::NAN),
+            Some(0.0),
+            None,
+            Some(1.0),
+            Some(-0.0),
+            Some(2.5),
+        ]));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Float64, true),
+        ]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![a, b])?;
+        let group_indices = [0, 1, 0, 0, 1, 0, 2, 1, 2];
+
+        for name in ["a", "b"] {
+            let expr = col(name, &schema)?;
+            let agg = DistinctCount::new(
+                expr.data_type(&schema)?,
+                expr,
+                String::from("__col_name__"),
+            );
+            assert_groups_accumulator(&batch, Arc::new(agg), &group_indices)?;
+        }
+        Ok(())
+    }
 }
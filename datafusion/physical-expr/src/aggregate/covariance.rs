@@ -20,18 +20,21 @@
 use std::any::Any;
 use std::sync::Arc;
 
-use crate::{AggregateExpr, PhysicalExpr};
-use arrow::array::Float64Array;
+use crate::{AggregateExpr, EmitTo, GroupsAccumulator, PhysicalExpr};
+use arrow::array::{Array, AsArray, BooleanArray, Float64Array};
+use arrow::datatypes::{Float64Type, UInt64Type};
 use arrow::{
     array::{ArrayRef, UInt64Array},
     compute::cast,
     datatypes::DataType,
     datatypes::Field,
 };
+use arrow_buffer::{BooleanBuffer, NullBuffer};
 use datafusion_common::{downcast_value, unwrap_or_internal_err, ScalarValue};
 use datafusion_common::{DataFusionError, Result};
 use datafusion_expr::Accumulator;
 
+use crate::aggregate::groups_accumulator::accumulate::accumulate_rows;
 use crate::aggregate::stats::StatsType;
 use crate::aggregate::utils::down_cast_any_ref;
 use crate::expressions::format_state_name;
@@ -116,6 +119,16 @@ impl AggregateExpr for Covariance {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn groups_accumulator_supported(&self) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(CovarianceGroupsAccumulator::new(
+            StatsType::Sample,
+        )))
+    }
 }
 
 impl PartialEq<dyn Any> for Covariance {
@@ -195,6 +208,16 @@ impl AggregateExpr for CovariancePop {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn groups_accumulator_supported(&self) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(CovarianceGroupsAccumulator::new(
+            StatsType::Population,
+        )))
+    }
 }
 
 impl PartialEq<dyn Any> for CovariancePop {
@@ -294,17 +317,11 @@ impl Accumulator for CovarianceAccumulator {
 
             let value1 = unwrap_or_internal_err!(value1);
             let value2 = unwrap_or_internal_err!(value2);
-            let new_count = self.count + 1;
-            let delta1 = value1 - self.mean1;
-            let new_mean1 = delta1 / new_count as f64 + self.mean1;
-            let delta2 = value2 - self.mean2;
-            let new_mean2 = delta2 / new_count as f64 + self.mean2;
-            let new_c = delta1 * (value2 - new_mean2) + self.algo_const;
-
-            self.count += 1;
-            self.mean1 = new_mean1;
-            self.mean2 = new_mean2;
-            self.algo_const = new_c;
+            (self.count, self.mean1, self.mean2, self.algo_const) = covariance_update(
+                (self.count, self.mean1, self.mean2, self.algo_const),
+                value1,
+                value2,
+            );
         }
 
         Ok(())
@@ -358,25 +375,15 @@ impl Accumulator for CovarianceAccumulator {
         let cs = downcast_value!(states[3], Float64Array);
 
         for i in 0..counts.len() {
-            let c = counts.value(i);
-            if c == 0_u64 {
-                continue;
-            }
-            let new_count = self.count + c;
-            let new_mean1 = self.mean1 * self.count as f64 / new_count as f64
-                + means1.value(i) * c as f64 / new_count as f64;
-            let new_mean2 = self.mean2 * self.count as f64 / new_count as f64
-                + means2.value(i) * c as f64 / new_count as f64;
-            let delta1 = self.mean1 - means1.value(i);
-            let delta2 = self.mean2 - means2.value(i);
-            let new_c = self.algo_const
-                + cs.value(i)
-                + delta1 * delta2 * self.count as f64 * c as f64 / new_count as f64;
-
-            self.count = new_count;
-            self.mean1 = new_mean1;
-            self.mean2 = new_mean2;
-            self.algo_const = new_c;
+            (self.count, self.mean1, self.mean2, self.algo_const) = covariance_merge(
+                (self.count, self.mean1, self.mean2, self.algo_const),
+                (
+                    counts.value(i),
+                    means1.value(i),
+                    means2.value(i),
+                    cs.value(i),
+                ),
+            );
         }
         Ok(())
     }
@@ -405,12 +412,237 @@ impl Accumulator for CovarianceAccumulator {
     }
 }
 
+/// The running state of a covariance computation: `(count, mean1, mean2, algo_const)`
+pub(crate) type CovarianceState = (u64, f64, f64, f64);
+
+/// Adds the pair `(value1, value2)` to the running covariance `state`,
+/// returning the new state
+#[inline]
+pub(crate) fn covariance_update(
+    state: CovarianceState,
+    value1: f64,
+    value2: f64,
+) -> CovarianceState {
+    let (count, mean1, mean2, algo_const) = state;
+    let new_count = count + 1;
+    let delta1 = value1 - mean1;
+    let new_mean1 = delta1 / new_count as f64 + mean1;
+    let delta2 = value2 - mean2;
+    let new_mean2 = delta2 / new_count as f64 + mean2;
+    let new_c = delta1 * (value2 - new_mean2) + algo_const;
+
+    (new_count, new_mean1, new_mean2, new_c)
+}
+
+/// Merges the partial covariance state `other` into `state`, returning
+/// the new state
+#[inline]
+pub(crate) fn covariance_merge(
+    state: CovarianceState,
+    other: CovarianceState,
+) -> CovarianceState {
+    let (count, mean1, mean2, algo_const) = state;
+    let (c, other_mean1, other_mean2, other_algo_const) = other;
+    if c == 0_u64 {
+        return state;
+    }
+    let new_count = count + c;
+    let new_mean1 = mean1 * count as f64 / new_count as f64
+        + other_mean1 * c as f64 / new_count as f64;
+    let new_mean2 = mean2 * count as f64 / new_count as f64
+        + other_mean2 * c as f64 / new_count as f64;
+    let delta1 = mean1 - other_mean1;
+    let delta2 = mean2 - other_mean2;
+    let new_c = algo_const
+        + other_algo_const
+        + delta1 * delta2 * count as f64 * c as f64 / new_count as f64;
+
+    (new_count, new_mean1, new_mean2, new_c)
+}
+
+/// Computes the final covariance from `(count, algo_const)`, or `None`
+/// if there are not enough values
+#[inline]
+pub(crate) fn covariance(
+    count: u64,
+    algo_const: f64,
+    stats_type: StatsType,
+) -> Option<f64> {
+    match stats_type {
+        StatsType::Population if count > 0 => Some(algo_const / count as f64),
+        StatsType::Sample if count > 1 => Some(algo_const / (count - 1) as f64),
+        _ => None,
+    }
+}
+
+/// An accumulator to compute covariance for many groups at once, using
+/// the same algorithm as [`CovarianceAccumulator`]
+#[derive(Debug)]
+pub(crate) struct CovarianceGroupsAccumulator {
+    algo_consts: Vec<f64>,
+    means1: Vec<f64>,
+    means2: Vec<f64>,
+    counts: Vec<u64>,
+    stats_type: StatsType,
+}
+
+impl CovarianceGroupsAccumulator {
+    /// Creates a new `CovarianceGroupsAccumulator`
+    pub fn new(s_type: StatsType) -> Self {
+        Self {
+            algo_consts: vec![],
+            means1: vec![],
+            means2: vec![],
+            counts: vec![],
+            stats_type: s_type,
+        }
+    }
+
+    fn resize(&mut self, total_num_groups: usize) {
+        self.algo_consts.resize(total_num_groups, 0_f64);
+        self.means1.resize(total_num_groups, 0_f64);
+        self.means2.resize(total_num_groups, 0_f64);
+        self.counts.resize(total_num_groups, 0_u64);
+    }
+
+    fn get(&self, group_index: usize) -> CovarianceState {
+        (
+            self.counts[group_index],
+            self.means1[group_index],
+            self.means2[group_index],
+            self.algo_consts[group_index],
+        )
+    }
+
+    fn set(&mut self, group_index: usize, state: CovarianceState) {
+        (
+            self.counts[group_index],
+            self.means1[group_index],
+            self.means2[group_index],
+            self.algo_consts[group_index],
+        ) = state;
+    }
+}
+
+impl GroupsAccumulator for CovarianceGroupsAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 2, "two arguments to update_batch");
+        let values1 = cast(&values[0], &DataType::Float64)?;
+        let values1 = values1.as_primitive::<Float64Type>();
+        let values2 = cast(&values[1], &DataType::Float64)?;
+        let values2 = values2.as_primitive::<Float64Type>();
+        // only rows where both values are non null are considered
+        let nulls = NullBuffer::union(values1.nulls(), values2.nulls());
+
+        self.resize(total_num_groups);
+        accumulate_rows(
+            group_indices,
+            nulls.as_ref(),
+            opt_filter,
+            |row_index, group_index| {
+                let state = covariance_update(
+                    self.get(group_index),
+                    values1.value(row_index),
+                    values2.value(row_index),
+                );
+                self.set(group_index, state);
+            },
+        );
+
+        Ok(())
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 4, "four arguments to merge_batch");
+        let partial_counts = values[0].as_primitive::<UInt64Type>();
+        let partial_means1 = values[1].as_primitive::<Float64Type>();
+        let partial_means2 = values[2].as_primitive::<Float64Type>();
+        let partial_algo_consts = values[3].as_primitive::<Float64Type>();
+
+        self.resize(total_num_groups);
+        accumulate_rows(
+            group_indices,
+            partial_counts.nulls(),
+            opt_filter,
+            |row_index, group_index| {
+                let state = covariance_merge(
+                    self.get(group_index),
+                    (
+                        partial_counts.value(row_index),
+                        partial_means1.value(row_index),
+                        partial_means2.value(row_index),
+                        partial_algo_consts.value(row_index),
+                    ),
+                );
+                self.set(group_index, state);
+            },
+        );
+
+        Ok(())
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        let counts = emit_to.take_needed(&mut self.counts);
+        let _ = emit_to.take_needed(&mut self.means1);
+        let _ = emit_to.take_needed(&mut self.means2);
+        let algo_consts = emit_to.take_needed(&mut self.algo_consts);
+
+        let covariances: Vec<Option<f64>> = counts
+            .into_iter()
+            .zip(algo_consts)
+            .map(|(count, algo_const)| covariance(count, algo_const, self.stats_type))
+            .collect();
+        let nulls: BooleanBuffer = covariances.iter().map(Option::is_some).collect();
+        let values: Vec<f64> = covariances
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect();
+
+        Ok(Arc::new(Float64Array::new(
+            values.into(),
+            Some(NullBuffer::new(nulls)),
+        )))
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let counts = emit_to.take_needed(&mut self.counts);
+        let means1 = emit_to.take_needed(&mut self.means1);
+        let means2 = emit_to.take_needed(&mut self.means2);
+        let algo_consts = emit_to.take_needed(&mut self.algo_consts);
+
+        Ok(vec![
+            Arc::new(UInt64Array::new(counts.into(), None)),
+            Arc::new(Float64Array::new(means1.into(), None)),
+            Arc::new(Float64Array::new(means2.into(), None)),
+            Arc::new(Float64Array::new(algo_consts.into(), None)),
+        ])
+    }
+
+    fn size(&self) -> usize {
+        (self.algo_consts.capacity() + self.means1.capacity() + self.means2.capacity())
+            * std::mem::size_of::<f64>()
+            + self.counts.capacity() * std::mem::size_of::<u64>()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::aggregate::utils::get_accum_scalar_values_as_arrays;
     use crate::expressions::col;
-    use crate::expressions::tests::aggregate;
+    use crate::expressions::tests::{aggregate, assert_groups_accumulator};
     use crate::generic_test_op2;
     use arrow::record_batch::RecordBatch;
     use arrow::{array::*, datatypes::*};
@@ -741,6 +973,55 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn covariance_groups_accumulator() -> Result<()> {
+        let a: ArrayRef = Arc::new(Float64Array::from(vec![
+            Some(1.1),
+            Some(2.0),
+            None,
+            Some(3.5),
+            Some(4.0),
+            Some(-1.0),
+            Some(7.0),
+            Some(2.5),
+        ]));
+        let b: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(4),
+            Some(-5),
+            Some(6),
+            None,
+            Some(8),
+            Some(1),
+            Some(0),
+            Some(3),
+        ]));
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Float64, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![a, b])?;
+        let group_indices = [0, 1, 0, 1, 2, 0, 1, 0];
+
+        let aggs: Vec<Arc<dyn AggregateExpr>> = vec![
+            Arc::new(Covariance::new(
+                col("a", &schema)?,
+                col("b", &schema)?,
+                "bla".to_string(),
+                DataType::Float64,
+            )),
+            Arc::new(CovariancePop::new(
+                col("a", &schema)?,
+                col("b", &schema)?,
+                "bla".to_string(),
+                DataType::Float64,
+            )),
+        ];
+        for agg in aggs {
+            assert_groups_accumulator(&batch, agg, &group_indices)?;
+        }
+        Ok(())
+    }
+
     fn merge(
         batch1: &RecordBatch,
         batch2: &RecordBatch,
@@ -20,15 +20,20 @@
 use std::any::Any;
 use std::sync::Arc;
 
+use crate::aggregate::groups_accumulator::accumulate::accumulate_rows;
 use crate::aggregate::utils::{down_cast_any_ref, get_sort_options, ordering_fields};
 use crate::expressions::format_state_name;
 use crate::{
-    reverse_order_bys, AggregateExpr, LexOrdering, PhysicalExpr, PhysicalSortExpr,
+    reverse_order_bys, AggregateExpr, EmitTo, GroupsAccumulator, LexOrdering,
+    PhysicalExpr, PhysicalSortExpr,
 };
 
-use arrow::array::{Array, ArrayRef, AsArray, BooleanArray};
+use arrow::array::{
+    new_empty_array, new_null_array, Array, ArrayRef, AsArray, BooleanArray,
+};
 use arrow::compute::{self, lexsort_to_indices, SortColumn};
 use arrow::datatypes::{DataType, Field};
+use arrow::row::{OwnedRow, RowConverter, SortField};
 use datafusion_common::utils::{compare_rows, get_arrayref_at_indices, get_row_at_idx};
 use datafusion_common::{
     arrow_datafusion_err, internal_err, DataFusionError, Result, ScalarValue,
@@ -174,6 +179,22 @@ impl AggregateExpr for FirstValue {
         Some(Arc::new(self.clone().convert_to_last()))
     }
 
+    fn groups_accumulator_supported(&self) -> bool {
+        supports_row_format(&self.input_data_type)
+            && self.order_by_data_types.iter().all(supports_row_format)
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        FirstLastGroupsAccumulator::try_new(
+            &self.input_data_type,
+            &self.ordering_req,
+            &self.order_by_data_types,
+            false,
+            self.requirement_satisfied,
+        )
+        .map(|acc| Box::new(acc) as _)
+    }
+
     fn create_sliding_accumulator(&self) -> Result<Box<dyn Accumulator>> {
         FirstValueAccumulator::try_new(
             &self.input_data_type,
@@ -247,9 +268,12 @@ impl FirstValueAccumulator {
         let [value, ordering_values @ ..] = values else {
             return internal_err!("Empty row in FIRST_VALUE");
         };
+        if value.is_empty() {
+            return Ok(None);
+        }
         if self.requirement_satisfied {
             // Get first entry according to the pre-existing ordering (0th index):
-            return Ok((!value.is_empty()).then_some(0));
+            return Ok(Some(0));
         }
         let sort_columns = ordering_values
             .iter()
@@ -487,6 +511,22 @@ impl AggregateExpr for LastValue {
         Some(Arc::new(self.clone().convert_to_first()))
     }
 
+    fn groups_accumulator_supported(&self) -> bool {
+        supports_row_format(&self.input_data_type)
+            && self.order_by_data_types.iter().all(supports_row_format)
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        FirstLastGroupsAccumulator::try_new(
+            &self.input_data_type,
+            &self.ordering_req,
+            &self.order_by_data_types,
+            true,
+            self.requirement_satisfied,
+        )
+        .map(|acc| Box::new(acc) as _)
+    }
+
     fn create_sliding_accumulator(&self) -> Result<Box<dyn Accumulator>> {
         LastValueAccumulator::try_new(
             &self.input_data_type,
@@ -559,9 +599,12 @@ impl LastValueAccumulator {
         let [value, ordering_values @ ..] = values else {
             return internal_err!("Empty row in LAST_VALUE");
         };
+        if value.is_empty() {
+            return Ok(None);
+        }
         if self.requirement_satisfied {
             // Get last entry according to the order of data:
-            return Ok((!value.is_empty()).then_some(value.len() - 1));
+            return Ok(Some(value.len() - 1));
         }
         let sort_columns = ordering_values
             .iter()
@@ -693,15 +736,321 @@ fn convert_to_sort_cols(
         .collect::<Vec<_>>()
 }
 
+/// Returns true if values of `data_type` can be round tripped through the
+/// row format by [`FirstLastGroupsAccumulator`]
+fn supports_row_format(data_type: &DataType) -> bool {
+    data_type.is_primitive()
+        || matches!(
+            data_type,
+            DataType::Boolean
+                | DataType::Utf8
+                | DataType::LargeUtf8
+                | DataType::Binary
+                | DataType::LargeBinary
+                | DataType::FixedSizeBinary(_)
+        )
+}
+
+/// Returns the number of heap bytes used by a group value
+fn value_size(value: &Option<ScalarValue>) -> usize {
+    value
+        .as_ref()
+        .map_or(0, |value| value.size() - std::mem::size_of_val(value))
+}
+
+/// The ordering of a group's value, encoded in the row format
+type EncodedOrdering = Option<Box<[u8]>>;
+
+/// Returns the number of bytes used by the encoding of an ordering
+fn ordering_size(ordering: &EncodedOrdering) -> usize {
+    ordering.as_ref().map_or(0, |ordering| ordering.len())
+}
+
+/// A [`GroupsAccumulator`] for `FIRST_VALUE` and `LAST_VALUE`.
+///
+/// The ordering each value was selected by is stored in the
+/// [row format](arrow::row) so that orderings can be compared across
+/// batches without converting them to [`ScalarValue`]s. Only the bytes of
+/// the rows are kept, the selected values are kept as [`ScalarValue`]s, to
+/// keep the size of the accumulator small when there are few groups.
+///
+/// This follows the semantics of [`FirstValueAccumulator`] and
+/// [`LastValueAccumulator`]: without an ordering requirement (or when the
+/// input already satisfies it) the position in the input decides, otherwise
+/// a value is only replaced by one with a strictly earlier (`FIRST_VALUE`)
+/// or later (`LAST_VALUE`) ordering.
+struct FirstLastGroupsAccumulator {
+    /// The type of the aggregated values
+    data_type: DataType,
+    /// Converts the ordering columns to and from the row format, if there
+    /// is an ordering requirement
+    ordering_converter: Option<RowConverter>,
+    /// Encoded null ordering, emitted for groups without a value
+    null_ordering: Option<OwnedRow>,
+    /// The value of each group, `None` if no value has been seen yet
+    values: Vec<Option<ScalarValue>>,
+    /// The encoded ordering of the value of each group
+    orderings: Vec<EncodedOrdering>,
+    /// `true` for `LAST_VALUE`, `false` for `FIRST_VALUE`
+    is_last: bool,
+    /// Whether incoming data already satisfies the ordering requirement
+    requirement_satisfied: bool,
+    /// The row of the current batch selected for each group, `usize::MAX`
+    /// if no row of the current batch belongs to the group
+    candidates: Vec<usize>,
+    /// Number of heap bytes used by `values` and `orderings`
+    heap_size: usize,
+}
+
+impl FirstLastGroupsAccumulator {
+    fn try_new(
+        data_type: &DataType,
+        ordering_req: &[PhysicalSortExpr],
+        ordering_dtypes: &[DataType],
+        is_last: bool,
+        requirement_satisfied: bool,
+    ) -> Result<Self> {
+        let (ordering_converter, null_ordering) = if ordering_req.is_empty() {
+            (None, None)
+        } else {
+            let fields = ordering_req
+                .iter()
+                .zip(ordering_dtypes)
+                .map(|(req, data_type)| {
+                    SortField::new_with_options(data_type.clone(), req.options)
+                })
+                .collect();
+            let converter = RowConverter::new(fields)?;
+            let nulls = ordering_dtypes
+                .iter()
+                .map(|data_type| new_null_array(data_type, 1))
+                .collect::<Vec<_>>();
+            let null_ordering = converter.convert_columns(&nulls)?.row(0).owned();
+            (Some(converter), Some(null_ordering))
+        };
+
+        Ok(Self {
+            data_type: data_type.clone(),
+            ordering_converter,
+            null_ordering,
+            values: vec![],
+            orderings: vec![],
+            is_last,
+            requirement_satisfied,
+            candidates: vec![],
+            heap_size: 0,
+        })
+    }
+
+    /// Updates the groups with the rows of `values` (the value followed by
+    /// the ordering columns) selected by `opt_filter`.
+    ///
+    /// Orderings are only compared if `use_ordering` is set. Otherwise the
+    /// first (last) row of the batch is selected for `FIRST_VALUE`
+    /// (`LAST_VALUE`), and it replaces an existing value only if
+    /// `replace_unordered` is set.
+    fn update_groups(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+        use_ordering: bool,
+        replace_unordered: bool,
+    ) -> Result<()> {
+        let [value, ordering_values @ ..] = values else {
+            return internal_err!("Empty row in FIRST_VALUE/LAST_VALUE");
+        };
+        self.values.resize(total_num_groups, None);
+        self.orderings.resize(total_num_groups, None);
+        self.candidates.resize(total_num_groups, usize::MAX);
+
+        let ordering_rows = self
+            .ordering_converter
+            .as_ref()
+            .map(|converter| converter.convert_columns(ordering_values))
+            .transpose()?;
+        let compared_rows = ordering_rows.as_ref().filter(|_| use_ordering);
+
+        // Select the best row of this batch for every group. Rows are
+        // visited in input order, so on ties the earliest row is kept for
+        // FIRST_VALUE and the latest row for LAST_VALUE.
+        let is_last = self.is_last;
+        let candidates = &mut self.candidates;
+        accumulate_rows(group_indices, None, opt_filter, |row_index, group_index| {
+            let candidate = &mut candidates[group_index];
+            if *candidate == usize::MAX {
+                *candidate = row_index;
+            } else if let Some(rows) = compared_rows {
+                let ordering = rows.row(row_index).cmp(&rows.row(*candidate));
+                if (is_last && ordering.is_ge()) || (!is_last && ordering.is_lt()) {
+                    *candidate = row_index;
+                }
+            } else if is_last {
+                *candidate = row_index;
+            }
+        });
+
+        // Visit the groups with a selected row, resetting their candidate
+        for &group_index in group_indices {
+            let row_index =
+                std::mem::replace(&mut self.candidates[group_index], usize::MAX);
+            if row_index == usize::MAX {
+                continue;
+            }
+            let replace = match (compared_rows, &self.orderings[group_index]) {
+                _ if self.values[group_index].is_none() => true,
+                (Some(rows), Some(current)) => {
+                    let ordering = rows.row(row_index).as_ref().cmp(current.as_ref());
+                    if is_last {
+                        ordering.is_gt()
+                    } else {
+                        ordering.is_lt()
+                    }
+                }
+                _ => replace_unordered,
+            };
+            if !replace {
+                continue;
+            }
+
+            let new_value = ScalarValue::try_from_array(value, row_index)?;
+            let old_value = self.values[group_index].replace(new_value);
+            let old_ordering = std::mem::replace(
+                &mut self.orderings[group_index],
+                ordering_rows
+                    .as_ref()
+                    .map(|rows| rows.row(row_index).as_ref().into()),
+            );
+            self.heap_size += value_size(&self.values[group_index])
+                + ordering_size(&self.orderings[group_index]);
+            self.heap_size -= value_size(&old_value) + ordering_size(&old_ordering);
+        }
+        Ok(())
+    }
+
+    /// Removes the groups selected by `emit_to`, returning their values and
+    /// orderings
+    fn emit(
+        &mut self,
+        emit_to: EmitTo,
+    ) -> (Vec<Option<ScalarValue>>, Vec<EncodedOrdering>) {
+        let values = emit_to.take_needed(&mut self.values);
+        let orderings = emit_to.take_needed(&mut self.orderings);
+        emit_to.take_needed(&mut self.candidates);
+        self.heap_size -= values.iter().map(value_size).sum::<usize>()
+            + orderings.iter().map(ordering_size).sum::<usize>();
+        (values, orderings)
+    }
+
+    /// Converts `values` back to an array, using nulls for unset groups
+    fn convert_values(&self, values: Vec<Option<ScalarValue>>) -> Result<ArrayRef> {
+        if values.is_empty() {
+            return Ok(new_empty_array(&self.data_type));
+        }
+        let null = ScalarValue::try_from(&self.data_type)?;
+        ScalarValue::iter_to_array(
+            values
+                .into_iter()
+                .map(|value| value.unwrap_or_else(|| null.clone())),
+        )
+    }
+}
+
+impl GroupsAccumulator for FirstLastGroupsAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        let use_ordering = !self.requirement_satisfied;
+        let replace_unordered = self.is_last;
+        self.update_groups(
+            values,
+            group_indices,
+            opt_filter,
+            total_num_groups,
+            use_ordering,
+            replace_unordered,
+        )
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        // The last column contains the is_set flag, only rows that have it
+        // set contain a value
+        let Some((is_set, values)) = values.split_last() else {
+            return internal_err!("Empty state in FIRST_VALUE/LAST_VALUE");
+        };
+        let is_set = is_set.as_boolean();
+        let filter = match opt_filter {
+            Some(filter) => compute::and(is_set, filter)?,
+            None => is_set.clone(),
+        };
+        self.update_groups(
+            values,
+            group_indices,
+            Some(&filter),
+            total_num_groups,
+            true,
+            false,
+        )
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        let (values, _) = self.emit(emit_to);
+        self.convert_values(values)
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let (values, orderings) = self.emit(emit_to);
+        let is_set = values.iter().map(Option::is_some).collect::<Vec<_>>();
+        let mut state = vec![self.convert_values(values)?];
+        if let (Some(converter), Some(null_ordering)) =
+            (&self.ordering_converter, &self.null_ordering)
+        {
+            let parser = converter.parser();
+            let rows = orderings.iter().map(|ordering| match ordering {
+                Some(ordering) => parser.parse(ordering),
+                None => null_ordering.row(),
+            });
+            state.extend(converter.convert_rows(rows)?);
+        }
+        state.push(Arc::new(BooleanArray::from(is_set)));
+        Ok(state)
+    }
+
+    fn size(&self) -> usize {
+        self.ordering_converter.as_ref().map_or(0, |c| c.size())
+            + self.values.capacity() * std::mem::size_of::<Option<ScalarValue>>()
+            + self.orderings.capacity() * std::mem::size_of::<EncodedOrdering>()
+            + self.candidates.capacity() * std::mem::size_of::<usize>()
+            + self.heap_size
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
-    use crate::aggregate::first_last::{FirstValueAccumulator, LastValueAccumulator};
-
-    use arrow::compute::concat;
-    use arrow_array::{ArrayRef, Int64Array};
-    use arrow_schema::DataType;
+    use crate::aggregate::first_last::{
+        FirstValue, FirstValueAccumulator, LastValue, LastValueAccumulator,
+    };
+    use crate::expressions::col;
+    use crate::expressions::tests::assert_groups_accumulator;
+    use crate::{AggregateExpr, PhysicalSortExpr};
+
+    use arrow::compute::{concat, SortOptions};
+    use arrow::record_batch::RecordBatch;
+    use arrow_array::{ArrayRef, Int64Array, StringArray};
+    use arrow_schema::{DataType, Field, Schema};
     use datafusion_common::{Result, ScalarValue};
     use datafusion_expr::Accumulator;
 
@@ -808,4 +1157,66 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_first_last_groups_accumulator() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new("b", DataType::Int64, true),
+        ]));
+        let a: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("foo"),
+            None,
+            Some("bar"),
+            Some("baz"),
+            None,
+            Some("qux"),
+            Some("quux"),
+            Some("corge"),
+        ]));
+        let b: ArrayRef = Arc::new(Int64Array::from(vec![
+            Some(5),
+            Some(3),
+            None,
+            Some(8),
+            Some(-1),
+            Some(2),
+            Some(7),
+            Some(4),
+        ]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![a, b])?;
+        let group_indices = [0, 1, 0, 2, 1, 0, 1, 3];
+
+        for options in [
+            SortOptions::default(),
+            SortOptions {
+                descending: true,
+                nulls_first: false,
+            },
+        ] {
+            let ordering_req = vec![PhysicalSortExpr {
+                expr: col("b", &schema)?,
+                options,
+            }];
+            let first = FirstValue::new(
+                col("a", &schema)?,
+                "first",
+                DataType::Utf8,
+                ordering_req,
+                vec![DataType::Int64],
+            );
+            let last = first.clone().convert_to_last();
+            assert_groups_accumulator(&batch, Arc::new(first), &group_indices)?;
+            assert_groups_accumulator(&batch, Arc::new(last), &group_indices)?;
+        }
+
+        let first =
+            FirstValue::new(col("a", &schema)?, "first", DataType::Utf8, vec![], vec![]);
+        let last =
+            LastValue::new(col("a", &schema)?, "last", DataType::Utf8, vec![], vec![]);
+        for agg in [Arc::new(first) as Arc<dyn AggregateExpr>, Arc::new(last)] {
+            assert_groups_accumulator(&batch, agg, &group_indices)?;
+        }
+        Ok(())
+    }
 }
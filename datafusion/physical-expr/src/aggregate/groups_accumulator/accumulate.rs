@@ -15,7 +15,8 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! [`GroupsAccumulator`] helpers: [`NullState`] and [`accumulate_indices`]
+//! [`GroupsAccumulator`] helpers: [`NullState`], [`accumulate_indices`]
+//! and [`accumulate_rows`]
 //!
 //! [`GroupsAccumulator`]: crate::GroupsAccumulator
 
@@ -437,6 +438,54 @@ pub fn accumulate_indices<F>(
     }
 }
 
+/// This function is called to update the accumulator state per row
+/// when the accumulator needs the row index rather than a single
+/// primitive value (e.g. `COVAR`, which reads two input arrays, or
+/// `ARRAY_AGG`, which stores arbitrary values)
+///
+/// `F`: Invoked like `row_fn(row_index, group_index)` for all rows
+/// that are valid according to `nulls` and pass the filter. Note that
+/// no tracking is done for null inputs or which groups have seen any
+/// values
+///
+/// See [`NullState::accumulate`], for more details on other
+/// arguments.
+pub fn accumulate_rows<F>(
+    group_indices: &[usize],
+    nulls: Option<&NullBuffer>,
+    opt_filter: Option<&BooleanArray>,
+    mut row_fn: F,
+) where
+    F: FnMut(usize, usize),
+{
+    let filter = opt_filter.map(filter_to_buffer);
+    let valids = match (nulls, filter) {
+        (None, None) => {
+            for (row_index, &group_index) in group_indices.iter().enumerate() {
+                row_fn(row_index, group_index)
+            }
+            return;
+        }
+        (Some(valids), None) => valids.inner().clone(),
+        (None, Some(filter)) => filter,
+        (Some(valids), Some(filter)) => valids.inner() & &filter,
+    };
+    assert_eq!(valids.len(), group_indices.len());
+
+    for row_index in valids.set_indices() {
+        row_fn(row_index, group_indices[row_index])
+    }
+}
+
+/// Converts a filter into a [`BooleanBuffer`] that is `true` only for
+/// rows where the filter is `Some(true)`
+fn filter_to_buffer(filter: &BooleanArray) -> BooleanBuffer {
+    match filter.nulls() {
+        Some(nulls) => filter.values() & nulls.inner(),
+        None => filter.values().clone(),
+    }
+}
+
 /// Ensures that `builder` contains a `BooleanBufferBuilder with at
 /// least `total_num_groups`.
 ///
@@ -635,6 +684,7 @@ mod test {
                 total_num_groups,
             );
             Self::accumulate_indices_test(group_indices, values.nulls(), opt_filter);
+            Self::accumulate_rows_test(group_indices, values.nulls(), opt_filter);
 
             // Convert values into a boolean array (anything above the
             // average is true, otherwise false)
@@ -765,6 +815,37 @@ mod test {
                        "\n\naccumulated_values:{accumulated_values:#?}\n\nexpected_values:{expected_values:#?}");
         }
 
+        // Calls `accumulate_rows` and ensures it visits the same rows,
+        // in the same order, as `accumulate_indices`
+        fn accumulate_rows_test(
+            group_indices: &[usize],
+            nulls: Option<&NullBuffer>,
+            opt_filter: Option<&BooleanArray>,
+        ) {
+            let mut accumulated_rows = vec![];
+            accumulate_rows(
+                group_indices,
+                nulls,
+                opt_filter,
+                |row_index, group_index| {
+                    assert_eq!(group_indices[row_index], group_index);
+                    accumulated_rows.push(row_index);
+                },
+            );
+
+            let mut expected_groups = vec![];
+            accumulate_indices(group_indices, nulls, opt_filter, |group_index| {
+                expected_groups.push(group_index);
+            });
+
+            let accumulated_groups: Vec<_> = accumulated_rows
+                .iter()
+                .map(|&row_index| group_indices[row_index])
+                .collect();
+            assert_eq!(accumulated_groups, expected_groups);
+            assert!(accumulated_rows.windows(2).all(|w| w[0] < w[1]));
+        }
+
         /// This is effectively a different implementation of
         /// accumulate_boolean that we compare with the above implementation
         fn accumulate_boolean_test(
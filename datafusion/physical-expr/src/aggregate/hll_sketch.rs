@@ -0,0 +1,337 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the HLL_SKETCH and HLL_UNION aggregate expressions, building
+//! serialized HyperLogLog sketches that can be stored and merged later
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{
+    internal_err, not_impl_err, DataFusionError, Result, ScalarValue,
+};
+use datafusion_expr::Accumulator;
+
+use super::approx_distinct::{hll_hashes, is_hll_supported_type, HLLGroupsAccumulator};
+use super::hyperloglog::{
+    add_hash, merge_registers, registers_to_sketch, sketch_registers, NUM_REGISTERS,
+};
+use crate::aggregate::utils::down_cast_any_ref;
+use crate::expressions::format_state_name;
+use crate::{AggregateExpr, GroupsAccumulator, PhysicalExpr};
+
+/// HLL_SKETCH aggregate expression
+///
+/// Returns a serialized HyperLogLog sketch of the distinct values of its
+/// input, see [`registers_to_sketch`] for the layout
+#[derive(Debug)]
+pub struct HllSketch {
+    name: String,
+    input_data_type: DataType,
+    expr: Arc<dyn PhysicalExpr>,
+}
+
+impl HllSketch {
+    /// Create a new HllSketch aggregate function.
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        name: impl Into<String>,
+        input_data_type: DataType,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            input_data_type,
+            expr,
+        }
+    }
+}
+
+impl AggregateExpr for HllSketch {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, DataType::Binary, false))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![hll_state_field(&self.name)])
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        if !is_hll_supported_type(&self.input_data_type) {
+            return not_impl_err!(
+                "Support for 'hll_sketch' for data type {} is not implemented",
+                self.input_data_type
+            );
+        }
+        Ok(Box::new(HllSketchAccumulator::new(false)))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn groups_accumulator_supported(&self) -> bool {
+        is_hll_supported_type(&self.input_data_type)
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(HLLGroupsAccumulator::new(false, true)))
+    }
+}
+
+impl PartialEq<dyn Any> for HllSketch {
+    fn eq(&self, other: &dyn Any) -> bool {
+        down_cast_any_ref(other)
+            .downcast_ref::<Self>()
+            .map(|x| {
+                self.name == x.name
+                    && self.input_data_type == x.input_data_type
+                    && self.expr.eq(&x.expr)
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// HLL_UNION aggregate expression
+///
+/// Merges serialized HyperLogLog sketches created by [`HllSketch`] into a
+/// sketch of the union of their values
+#[derive(Debug)]
+pub struct HllUnion {
+    name: String,
+    expr: Arc<dyn PhysicalExpr>,
+}
+
+impl HllUnion {
+    /// Create a new HllUnion aggregate function.
+    pub fn new(expr: Arc<dyn PhysicalExpr>, name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            expr,
+        }
+    }
+}
+
+impl AggregateExpr for HllUnion {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, DataType::Binary, false))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![hll_state_field(&self.name)])
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(HllSketchAccumulator::new(true)))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn groups_accumulator_supported(&self) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(HLLGroupsAccumulator::new(true, true)))
+    }
+}
+
+impl PartialEq<dyn Any> for HllUnion {
+    fn eq(&self, other: &dyn Any) -> bool {
+        down_cast_any_ref(other)
+            .downcast_ref::<Self>()
+            .map(|x| self.name == x.name && self.expr.eq(&x.expr))
+            .unwrap_or(false)
+    }
+}
+
+/// The state holds the raw registers, the same as the one of APPROX_DISTINCT
+fn hll_state_field(name: &str) -> Field {
+    Field::new(
+        format_state_name(name, "hll_registers"),
+        DataType::Binary,
+        false,
+    )
+}
+
+/// An accumulator for HLL_SKETCH and HLL_UNION
+#[derive(Debug)]
+struct HllSketchAccumulator {
+    registers: Vec<u8>,
+    /// Whether the input values are serialized sketches to merge
+    sketch_input: bool,
+}
+
+impl HllSketchAccumulator {
+    fn new(sketch_input: bool) -> Self {
+        Self {
+            registers: vec![0; NUM_REGISTERS],
+            sketch_input,
+        }
+    }
+}
+
+impl Accumulator for HllSketchAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let values = &values[0];
+        if self.sketch_input {
+            for sketch in values.as_binary::<i32>().iter().flatten() {
+                merge_registers(&mut self.registers, sketch_registers(sketch)?);
+            }
+            return Ok(());
+        }
+
+        let hashes = hll_hashes(values)?;
+        for (row_index, hash) in hashes.into_iter().enumerate() {
+            // skip nulls
+            if values.is_valid(row_index) {
+                add_hash(&mut self.registers, hash);
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        assert_eq!(1, states.len(), "expect only 1 element in the states");
+        for registers in states[0].as_binary::<i32>().iter() {
+            match registers {
+                Some(registers) if registers.len() == NUM_REGISTERS => {
+                    merge_registers(&mut self.registers, registers)
+                }
+                _ => {
+                    return internal_err!(
+                        "Impossibly got invalid binary array from states"
+                    )
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::Binary(Some(self.registers.clone()))])
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Binary(Some(registers_to_sketch(
+            &self.registers,
+        ))))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.registers.capacity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregate::hyperloglog::count_registers;
+    use crate::expressions::col;
+    use crate::expressions::tests::{aggregate, assert_groups_accumulator};
+    use arrow::array::{BinaryArray, Int32Array};
+    use arrow::datatypes::Schema;
+    use arrow::record_batch::RecordBatch;
+
+    fn estimate(sketch: ScalarValue) -> Result<usize> {
+        match sketch {
+            ScalarValue::Binary(Some(sketch)) => {
+                Ok(count_registers(sketch_registers(&sketch)?))
+            }
+            other => internal_err!("Unexpected sketch {other}"),
+        }
+    }
+
+    #[test]
+    fn hll_sketch_and_union() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let first = RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(Int32Array::from(vec![
+                Some(1),
+                Some(2),
+                None,
+                Some(2),
+            ]))],
+        )?;
+        let second = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Int32Array::from(vec![2, 3, 4]))],
+        )?;
+
+        let agg = Arc::new(HllSketch::new(
+            col("a", &first.schema())?,
+            "bla",
+            DataType::Int32,
+        ));
+        let first = aggregate(&first, agg.clone())?;
+        let second = aggregate(&second, agg)?;
+        assert_eq!(estimate(first.clone())?, 2);
+        assert_eq!(estimate(second.clone())?, 3);
+
+        let schema = Schema::new(vec![Field::new("s", DataType::Binary, true)]);
+        let sketches = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(BinaryArray::from(vec![
+                first.to_array()?.as_binary::<i32>().value(0),
+                second.to_array()?.as_binary::<i32>().value(0),
+            ]))],
+        )?;
+        let agg = Arc::new(HllUnion::new(col("s", &sketches.schema())?, "bla"));
+        assert_eq!(estimate(aggregate(&sketches, agg)?)?, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn hll_sketch_groups_accumulator() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(1),
+            Some(2),
+            None,
+            Some(1),
+            Some(3),
+            Some(4),
+            Some(3),
+            None,
+        ]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![a])?;
+        let group_indices = [0, 1, 0, 2, 1, 2, 0, 1];
+
+        let agg = Arc::new(HllSketch::new(col("a", &schema)?, "bla", DataType::Int32));
+        assert_groups_accumulator(&batch, agg, &group_indices)
+    }
+}
@@ -33,44 +33,50 @@
 //! conversion, largely to simplify the code.
 //!
 //! This module also borrows some code structure from [pdatastructs.rs](https://github.com/crepererum/pdatastructs.rs/blob/3997ed50f6b6871c9e53c4c5e0f48f431405fc63/src/hyperloglog.rs).
+//!
+//! # Serialized sketch
+//!
+//! The registers are exchanged as a sketch (see `hll_sketch`, `hll_union` and
+//! `hll_estimate`) with the following stable binary layout:
+//!
+//! | offset | size    | content                                   |
+//! |--------|---------|-------------------------------------------|
+//! | 0      | 1       | format version, currently `2`             |
+//! | 1      | 1       | precision `p`, currently `14`             |
+//! | 2      | 1       | hash algorithm, currently `1`             |
+//! | 3      | 2\*\*p   | the registers, one byte per register      |
+//!
+//! The only hash algorithm, `1`, is the 64 bit [xxHash] with seed `0` of the
+//! canonical encoding of the values (see [`HLLHash`]), so that sketches built
+//! by different versions, platforms and sessions can be merged.
+//!
+//! [xxHash]: https://github.com/Cyan4973/xxHash/blob/dev/doc/xxhash_spec.md
 
-use ahash::{AHasher, RandomState};
-use std::hash::{BuildHasher, Hash, Hasher};
+use datafusion_common::{exec_err, DataFusionError, Result};
+use std::hash::Hasher;
 use std::marker::PhantomData;
+use twox_hash::XxHash64;
 
 /// The greater is P, the smaller the error.
 const HLL_P: usize = 14_usize;
 /// The number of bits of the hash value used determining the number of leading zeros
 const HLL_Q: usize = 64_usize - HLL_P;
-const NUM_REGISTERS: usize = 1_usize << HLL_P;
+pub(crate) const NUM_REGISTERS: usize = 1_usize << HLL_P;
 /// Mask to obtain index into the registers
 const HLL_P_MASK: u64 = (NUM_REGISTERS as u64) - 1;
 
 #[derive(Clone, Debug)]
 pub(crate) struct HyperLogLog<T>
 where
-    T: Hash + ?Sized,
+    T: HLLHash + ?Sized,
 {
     registers: [u8; NUM_REGISTERS],
     phantom: PhantomData<T>,
 }
 
-/// Fixed seed for the hashing so that values are consistent across runs
-///
-/// Note that when we later move on to have serialized HLL register binaries
-/// shared across cluster, this SEED will have to be consistent across all
-/// parties otherwise we might have corruption. So ideally for later this seed
-/// shall be part of the serialized form (or stay unchanged across versions).
-const SEED: RandomState = RandomState::with_seeds(
-    0x885f6cab121d01a3_u64,
-    0x71e4379f2976ad8f_u64,
-    0xbf30173dd28a8816_u64,
-    0x0eaea5d736d733a4_u64,
-);
-
 impl<T> Default for HyperLogLog<T>
 where
-    T: Hash + ?Sized,
+    T: HLLHash + ?Sized,
 {
     fn default() -> Self {
         Self::new()
@@ -79,7 +85,7 @@ where
 
 impl<T> HyperLogLog<T>
 where
-    T: Hash + ?Sized,
+    T: HLLHash + ?Sized,
 {
     /// Creates a new, empty HyperLogLog.
     pub fn new() -> Self {
@@ -97,60 +103,168 @@ where
         }
     }
 
-    /// choice of hash function: ahash is already an dependency
-    /// and it fits the requirements of being a 64bit hash with
-    /// reasonable performance.
-    #[inline]
-    fn hash_value(&self, obj: &T) -> u64 {
-        let mut hasher: AHasher = SEED.build_hasher();
-        obj.hash(&mut hasher);
-        hasher.finish()
-    }
-
     /// Adds an element to the HyperLogLog.
     pub fn add(&mut self, obj: &T) {
-        let hash = self.hash_value(obj);
-        let index = (hash & HLL_P_MASK) as usize;
-        let p = ((hash >> HLL_P) | (1_u64 << HLL_Q)).trailing_zeros() + 1;
-        self.registers[index] = self.registers[index].max(p as u8);
-    }
-
-    /// Get the register histogram (each value in register index into
-    /// the histogram; u32 is enough because we only have 2**14=16384 registers
-    #[inline]
-    fn get_histogram(&self) -> [u32; HLL_Q + 2] {
-        let mut histogram = [0; HLL_Q + 2];
-        // hopefully this can be unrolled
-        for r in self.registers {
-            histogram[r as usize] += 1;
-        }
-        histogram
+        add_hash(&mut self.registers, obj.hll_hash());
     }
 
     /// Merge the other [`HyperLogLog`] into this one
     pub fn merge(&mut self, other: &HyperLogLog<T>) {
-        assert!(
-            self.registers.len() == other.registers.len(),
-            "unexpected got unequal register size, expect {}, got {}",
-            self.registers.len(),
-            other.registers.len()
-        );
-        for i in 0..self.registers.len() {
-            self.registers[i] = self.registers[i].max(other.registers[i]);
-        }
+        merge_registers(&mut self.registers, &other.registers);
     }
 
     /// Guess the number of unique elements seen by the HyperLogLog.
     pub fn count(&self) -> usize {
-        let histogram = self.get_histogram();
-        let m = NUM_REGISTERS as f64;
-        let mut z = m * hll_tau((m - histogram[HLL_Q + 1] as f64) / m);
-        for i in histogram[1..=HLL_Q].iter().rev() {
-            z += *i as f64;
-            z *= 0.5;
+        count_registers(&self.registers)
+    }
+}
+
+/// The seed of the [`XxHash64`] of the values
+const HLL_HASH_SEED: u64 = 0;
+
+/// Returns the [`XxHash64`] of the canonical encoding `bytes` of a value
+#[inline]
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = XxHash64::with_seed(HLL_HASH_SEED);
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+/// A value that can be added to a [`HyperLogLog`], hashed with a specified
+/// hash function over a canonical byte encoding so that the registers do not
+/// depend on the version or the platform.
+///
+/// Integers are encoded as the little endian bytes of the value widened to
+/// an `i128`, so that equal values of different integer types, such as `1`
+/// as `Int32` and as `Int64`, have the same hash. Strings and binaries are
+/// encoded as their bytes.
+pub(crate) trait HLLHash {
+    /// Returns the hash of the canonical encoding of this value
+    fn hll_hash(&self) -> u64;
+}
+
+macro_rules! integer_hll_hash {
+    ($($t:ty),*) => {
+        $(
+            impl HLLHash for $t {
+                #[inline]
+                fn hll_hash(&self) -> u64 {
+                    hash_bytes(&(*self as i128).to_le_bytes())
+                }
+            }
+        )*
+    };
+}
+
+integer_hll_hash!(i8, i16, i32, i64, i128, u8, u16, u32, u64);
+
+impl HLLHash for [u8] {
+    #[inline]
+    fn hll_hash(&self) -> u64 {
+        hash_bytes(self)
+    }
+}
+
+impl HLLHash for Vec<u8> {
+    #[inline]
+    fn hll_hash(&self) -> u64 {
+        hash_bytes(self)
+    }
+}
+
+impl HLLHash for str {
+    #[inline]
+    fn hll_hash(&self) -> u64 {
+        hash_bytes(self.as_bytes())
+    }
+}
+
+impl HLLHash for String {
+    #[inline]
+    fn hll_hash(&self) -> u64 {
+        hash_bytes(self.as_bytes())
+    }
+}
+
+/// Adds an element with the given [`HLLHash::hll_hash`] to `registers`
+#[inline]
+pub(crate) fn add_hash(registers: &mut [u8], hash: u64) {
+    let index = (hash & HLL_P_MASK) as usize;
+    let p = ((hash >> HLL_P) | (1_u64 << HLL_Q)).trailing_zeros() + 1;
+    registers[index] = registers[index].max(p as u8);
+}
+
+/// Get the register histogram (each value in register index into
+/// the histogram; u32 is enough because we only have 2**14=16384 registers
+#[inline]
+fn get_histogram(registers: &[u8]) -> [u32; HLL_Q + 2] {
+    let mut histogram = [0; HLL_Q + 2];
+    // hopefully this can be unrolled
+    for r in registers {
+        histogram[*r as usize] += 1;
+    }
+    histogram
+}
+
+/// Merge the `other` registers into `registers`
+pub(crate) fn merge_registers(registers: &mut [u8], other: &[u8]) {
+    assert!(
+        registers.len() == other.len(),
+        "unexpected got unequal register size, expect {}, got {}",
+        registers.len(),
+        other.len()
+    );
+    for (r, o) in registers.iter_mut().zip(other) {
+        *r = (*r).max(*o);
+    }
+}
+
+/// Guess the number of unique elements added to `registers`
+pub(crate) fn count_registers(registers: &[u8]) -> usize {
+    let histogram = get_histogram(registers);
+    let m = NUM_REGISTERS as f64;
+    let mut z = m * hll_tau((m - histogram[HLL_Q + 1] as f64) / m);
+    for i in histogram[1..=HLL_Q].iter().rev() {
+        z += *i as f64;
+        z *= 0.5;
+    }
+    z += m * hll_sigma(histogram[0] as f64 / m);
+    (0.5 / 2_f64.ln() * m * m / z).round() as usize
+}
+
+/// The version of the serialized sketch layout
+const HLL_SKETCH_VERSION: u8 = 2;
+/// The id of the hash algorithm of the serialized sketches, see the
+/// [module docs](self)
+const HLL_HASH_XXHASH64: u8 = 1;
+/// The size of a serialized sketch
+pub(crate) const HLL_SKETCH_SIZE: usize = 3 + NUM_REGISTERS;
+
+/// Serializes `registers` to a sketch, see the [module docs](self)
+pub(crate) fn registers_to_sketch(registers: &[u8]) -> Vec<u8> {
+    let mut sketch = Vec::with_capacity(HLL_SKETCH_SIZE);
+    sketch.push(HLL_SKETCH_VERSION);
+    sketch.push(HLL_P as u8);
+    sketch.push(HLL_HASH_XXHASH64);
+    sketch.extend_from_slice(registers);
+    sketch
+}
+
+/// Returns the registers of a sketch created by [`registers_to_sketch`]
+pub(crate) fn sketch_registers(sketch: &[u8]) -> Result<&[u8]> {
+    match sketch {
+        [HLL_SKETCH_VERSION, p, HLL_HASH_XXHASH64, registers @ ..]
+            if *p as usize == HLL_P && registers.len() == NUM_REGISTERS =>
+        {
+            Ok(registers)
+        }
+        [version, ..] if *version != HLL_SKETCH_VERSION => {
+            exec_err!("Unsupported HLL sketch version {version}")
         }
-        z += m * hll_sigma(histogram[0] as f64 / m);
-        (0.5 / 2_f64.ln() * m * m / z).round() as usize
+        [_, _, hash, ..] if *hash != HLL_HASH_XXHASH64 => {
+            exec_err!("Unsupported HLL sketch hash algorithm {hash}")
+        }
+        _ => exec_err!("Invalid HLL sketch of {} bytes", sketch.len()),
     }
 }
 
@@ -204,7 +318,7 @@ fn hll_tau(x: f64) -> f64 {
 
 impl<T> AsRef<[u8]> for HyperLogLog<T>
 where
-    T: Hash + ?Sized,
+    T: HLLHash + ?Sized,
 {
     fn as_ref(&self) -> &[u8] {
         &self.registers
@@ -213,7 +327,7 @@ where
 
 impl<T> Extend<T> for HyperLogLog<T>
 where
-    T: Hash,
+    T: HLLHash,
 {
     fn extend<S: IntoIterator<Item = T>>(&mut self, iter: S) {
         for elem in iter {
@@ -224,7 +338,7 @@ where
 
 impl<'a, T> Extend<&'a T> for HyperLogLog<T>
 where
-    T: 'a + Hash + ?Sized,
+    T: 'a + HLLHash + ?Sized,
 {
     fn extend<S: IntoIterator<Item = &'a T>>(&mut self, iter: S) {
         for elem in iter {
@@ -235,7 +349,10 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{HyperLogLog, NUM_REGISTERS};
+    use super::{
+        registers_to_sketch, sketch_registers, HLLHash, HyperLogLog, HLL_SKETCH_SIZE,
+        NUM_REGISTERS,
+    };
 
     fn compare_with_delta(got: usize, expected: usize) {
         let expected = expected as f64;
@@ -269,7 +386,6 @@ mod tests {
     macro_rules! typed_large_number_test {
         ($SIZE: expr) => {{
             sized_number_test!($SIZE, u64);
-            sized_number_test!($SIZE, u128);
             sized_number_test!($SIZE, i64);
             sized_number_test!($SIZE, i128);
         }};
@@ -285,6 +401,41 @@ mod tests {
         }};
     }
 
+    #[test]
+    fn test_sketch_round_trip() {
+        let mut hll = HyperLogLog::<u64>::new();
+        hll.extend(0..100);
+        let sketch = registers_to_sketch(hll.as_ref());
+        assert_eq!(sketch.len(), HLL_SKETCH_SIZE);
+        assert_eq!(&sketch[..3], &[2, 14, 1]);
+        assert_eq!(sketch_registers(&sketch).unwrap(), hll.as_ref());
+
+        let err = sketch_registers(&sketch[1..]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unsupported HLL sketch version 14"));
+        let mut other_hash = sketch.clone();
+        other_hash[2] = 2;
+        let err = sketch_registers(&other_hash).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unsupported HLL sketch hash algorithm 2"));
+        let err = sketch_registers(&sketch[..100]).unwrap_err();
+        assert!(err.to_string().contains("Invalid HLL sketch of 100 bytes"));
+    }
+
+    #[test]
+    fn test_hash() {
+        // the hashes are part of the serialized sketches, so must not change
+        assert_eq!(1_i64.hll_hash(), 1_i32.hll_hash());
+        assert_eq!(1_i64.hll_hash(), 1_u8.hll_hash());
+        assert_ne!((-1_i64).hll_hash(), u64::MAX.hll_hash());
+        assert_eq!("foo".hll_hash(), b"foo".as_slice().hll_hash());
+        assert_eq!("".hll_hash(), 0xef46db3751d8e999);
+        assert_eq!("foo".hll_hash(), 0x33bf00a859c4ba3f);
+        assert_eq!(1_i64.hll_hash(), 0x6c1cff0459c8bb10);
+    }
+
     #[test]
     fn test_empty() {
         let hll = HyperLogLog::<u64>::new();
@@ -0,0 +1,358 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An implementation of the [KLL sketch] providing approximate quantiles
+//! with a mergeable state.
+//!
+//! The sketch is a stack of compactors. Values are added to the compactor of
+//! level 0; once a level exceeds its capacity its items are sorted and every
+//! other item is promoted to the next level, doubling its weight. The
+//! capacity of a level shrinks by a factor of 2/3 per level below the top
+//! level, so the size of the sketch is bounded by about `3 * k` items.
+//!
+//! Compactions choose the promoted items deterministically, so the same
+//! input in the same order always results in the same sketch. NaN values
+//! are ignored.
+//!
+//! # Serialized sketch
+//!
+//! The sketch is exchanged (see `kll_sketch`, `kll_merge` and
+//! `kll_quantile`) with the following stable binary layout. All numbers are
+//! little endian.
+//!
+//! | offset      | size        | content                                  |
+//! |-------------|-------------|------------------------------------------|
+//! | 0           | 1           | format version, currently `1`            |
+//! | 1           | 1           | number of levels `L`                     |
+//! | 2           | 2           | `k`, u16                                 |
+//! | 4           | 8           | number of added values `n`, u64          |
+//! | 12          | 8           | minimum added value, f64                 |
+//! | 20          | 8           | maximum added value, f64                 |
+//! | 28          | 4 \* L       | number of items of each level, u32       |
+//! | 28 + 4 \* L  | 8 \* items   | the items, f64, starting with level 0    |
+//!
+//! [KLL sketch]: https://arxiv.org/abs/1603.05346
+
+use datafusion_common::{exec_err, DataFusionError, Result};
+
+/// The default `k`, which results in a rank error of about 1.65%
+pub(crate) const DEFAULT_K: usize = 200;
+/// The smallest supported `k`
+pub(crate) const MIN_K: usize = 8;
+/// The largest supported `k`
+pub(crate) const MAX_K: usize = u16::MAX as usize;
+
+/// The version of the serialized sketch layout
+const KLL_SKETCH_VERSION: u8 = 1;
+/// The size of the fixed part of a serialized sketch
+const HEADER_SIZE: usize = 28;
+/// The factor by which the capacity shrinks per level
+const CAPACITY_FACTOR: f64 = 2.0 / 3.0;
+/// The capacity of the lowest levels
+const MIN_CAPACITY: usize = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Kll {
+    k: usize,
+    n: u64,
+    min: f64,
+    max: f64,
+    /// The items of level `h` have a weight of `2^h`
+    levels: Vec<Vec<f64>>,
+}
+
+impl Kll {
+    /// Creates an empty sketch, `k` must be between [`MIN_K`] and [`MAX_K`]
+    pub(crate) fn new(k: usize) -> Self {
+        debug_assert!((MIN_K..=MAX_K).contains(&k));
+        Self {
+            k,
+            n: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            levels: vec![vec![]],
+        }
+    }
+
+    /// Adds `value` to the sketch
+    pub(crate) fn update(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.n += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.levels[0].push(value);
+        if self.levels[0].len() > self.capacity(0) {
+            self.compress();
+        }
+    }
+
+    /// Merges `other` into this sketch. The merged sketch uses the smaller
+    /// `k` of both.
+    pub(crate) fn merge(&mut self, other: &Kll) {
+        if other.n == 0 {
+            return;
+        }
+        self.k = self.k.min(other.k);
+        if self.levels.len() < other.levels.len() {
+            self.levels.resize(other.levels.len(), vec![]);
+        }
+        for (items, other_items) in self.levels.iter_mut().zip(&other.levels) {
+            items.extend_from_slice(other_items);
+        }
+        self.n += other.n;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.compress();
+    }
+
+    /// Estimates the `q` quantile of the added values, or `None` if the
+    /// sketch is empty. `q` is clamped to `[0, 1]`.
+    pub(crate) fn quantile(&self, q: f64) -> Option<f64> {
+        if self.n == 0 {
+            return None;
+        }
+        if q <= 0.0 {
+            return Some(self.min);
+        }
+        if q >= 1.0 {
+            return Some(self.max);
+        }
+        let mut items = self
+            .levels
+            .iter()
+            .enumerate()
+            .flat_map(|(level, items)| items.iter().map(move |v| (*v, 1_u64 << level)))
+            .collect::<Vec<_>>();
+        items.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // the compactions preserve the total weight
+        let rank = (q * self.n as f64).ceil() as u64;
+        let mut cumulative = 0;
+        for (value, weight) in items {
+            cumulative += weight;
+            if cumulative >= rank {
+                return Some(value);
+            }
+        }
+        Some(self.max)
+    }
+
+    /// The capacity of `level`
+    fn capacity(&self, level: usize) -> usize {
+        let depth = (self.levels.len() - level - 1) as i32;
+        let capacity = (self.k as f64 * CAPACITY_FACTOR.powi(depth)).ceil() as usize;
+        capacity.max(MIN_CAPACITY)
+    }
+
+    /// Compacts levels until all levels are within their capacity
+    fn compress(&mut self) {
+        let mut compacted = true;
+        while compacted {
+            compacted = false;
+            for level in 0..self.levels.len() {
+                if self.levels[level].len() > self.capacity(level) {
+                    self.compact(level);
+                    compacted = true;
+                }
+            }
+        }
+    }
+
+    /// Promotes every other item of `level` to the next level
+    fn compact(&mut self, level: usize) {
+        if level + 1 == self.levels.len() {
+            self.levels.push(vec![]);
+        }
+        let mut items = std::mem::take(&mut self.levels[level]);
+        items.sort_by(f64::total_cmp);
+        // an odd item stays at this level
+        if items.len() % 2 == 1 {
+            self.levels[level].extend(items.pop());
+        }
+        // choose the promoted items by a deterministic coin flip, as
+        // compactions happen at regular intervals, alternating them would
+        // bias the sketch
+        let offset = (mix(self.n ^ ((level as u64) << 56)) & 1) as usize;
+        let promoted = items.iter().skip(offset).step_by(2);
+        self.levels[level + 1].extend(promoted);
+    }
+
+    /// Returns the approximate memory used by the sketch, in bytes
+    pub(crate) fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.levels.capacity() * std::mem::size_of::<Vec<f64>>()
+            + self
+                .levels
+                .iter()
+                .map(|items| items.capacity() * std::mem::size_of::<f64>())
+                .sum::<usize>()
+    }
+
+    /// Serializes the sketch, see the [module docs](self)
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let num_items = self.levels.iter().map(Vec::len).sum::<usize>();
+        let mut bytes =
+            Vec::with_capacity(HEADER_SIZE + 4 * self.levels.len() + 8 * num_items);
+        bytes.push(KLL_SKETCH_VERSION);
+        bytes.push(self.levels.len() as u8);
+        bytes.extend_from_slice(&(self.k as u16).to_le_bytes());
+        bytes.extend_from_slice(&self.n.to_le_bytes());
+        bytes.extend_from_slice(&self.min.to_le_bytes());
+        bytes.extend_from_slice(&self.max.to_le_bytes());
+        for items in &self.levels {
+            bytes.extend_from_slice(&(items.len() as u32).to_le_bytes());
+        }
+        for item in self.levels.iter().flatten() {
+            bytes.extend_from_slice(&item.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Deserializes a sketch created by [`Self::to_bytes`]
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_SIZE {
+            return exec_err!("Invalid KLL sketch of {} bytes", bytes.len());
+        }
+        if bytes[0] != KLL_SKETCH_VERSION {
+            return exec_err!("Unsupported KLL sketch version {}", bytes[0]);
+        }
+        let num_levels = bytes[1] as usize;
+        let k = u16::from_le_bytes(bytes[2..4].try_into().unwrap()) as usize;
+        let n = u64::from_le_bytes(bytes[4..12].try_into().unwrap());
+        let min = f64::from_le_bytes(bytes[12..20].try_into().unwrap());
+        let max = f64::from_le_bytes(bytes[20..28].try_into().unwrap());
+
+        let items_start = HEADER_SIZE + 4 * num_levels;
+        if num_levels == 0 || k < MIN_K || bytes.len() < items_start {
+            return exec_err!("Invalid KLL sketch of {} bytes", bytes.len());
+        }
+        let level_sizes = bytes[HEADER_SIZE..items_start]
+            .chunks_exact(4)
+            .map(|size| u32::from_le_bytes(size.try_into().unwrap()) as usize)
+            .collect::<Vec<_>>();
+        let num_items = level_sizes.iter().sum::<usize>();
+        if bytes.len() != items_start + 8 * num_items {
+            return exec_err!("Invalid KLL sketch of {} bytes", bytes.len());
+        }
+
+        let mut items = bytes[items_start..]
+            .chunks_exact(8)
+            .map(|item| f64::from_le_bytes(item.try_into().unwrap()));
+        let levels = level_sizes
+            .iter()
+            .map(|size| items.by_ref().take(*size).collect())
+            .collect();
+        Ok(Self {
+            k,
+            n,
+            min,
+            max,
+            levels,
+        })
+    }
+}
+
+/// A 64 bit mixing function (the finalizer of SplitMix64)
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that the estimated quantiles of the values `0..n` are within
+    /// the expected rank error
+    fn assert_quantiles(sketch: &Kll, n: usize) {
+        for q in [0.01, 0.1, 0.25, 0.5, 0.75, 0.9, 0.99] {
+            let estimate = sketch.quantile(q).unwrap();
+            let error = (estimate - q * n as f64).abs() / n as f64;
+            assert!(error < 0.03, "quantile {q} estimated as {estimate}");
+        }
+        assert_eq!(sketch.quantile(0.0), Some(0.0));
+        assert_eq!(sketch.quantile(1.0), Some((n - 1) as f64));
+    }
+
+    #[test]
+    fn test_empty() {
+        let sketch = Kll::new(DEFAULT_K);
+        assert_eq!(sketch.n, 0);
+        assert_eq!(sketch.quantile(0.5), None);
+    }
+
+    #[test]
+    fn test_exact_below_capacity() {
+        let mut sketch = Kll::new(DEFAULT_K);
+        for v in [5.0, 1.0, f64::NAN, 3.0, 2.0, 4.0] {
+            sketch.update(v);
+        }
+        assert_eq!(sketch.n, 5);
+        assert_eq!(sketch.quantile(0.2), Some(1.0));
+        assert_eq!(sketch.quantile(0.5), Some(3.0));
+        assert_eq!(sketch.quantile(0.9), Some(5.0));
+    }
+
+    #[test]
+    fn test_quantiles() {
+        let mut sketch = Kll::new(DEFAULT_K);
+        // shuffled input
+        for i in 0..100_000_u64 {
+            sketch.update((i * 7919 % 100_000) as f64);
+        }
+        assert_eq!(sketch.n, 100_000);
+        assert!(sketch.levels.iter().map(Vec::len).sum::<usize>() < 3 * DEFAULT_K);
+        assert_quantiles(&sketch, 100_000);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut sketches = (0..4).map(|_| Kll::new(DEFAULT_K)).collect::<Vec<_>>();
+        for i in 0..100_000_u64 {
+            sketches[(i % 4) as usize].update((i * 7919 % 100_000) as f64);
+        }
+        let mut merged = Kll::new(DEFAULT_K);
+        for sketch in &sketches {
+            merged.merge(sketch);
+        }
+        assert_eq!(merged.n, 100_000);
+        assert_quantiles(&merged, 100_000);
+    }
+
+    #[test]
+    fn test_serialization() -> Result<()> {
+        let mut sketch = Kll::new(MIN_K);
+        for i in 0..1000 {
+            sketch.update(i as f64);
+        }
+        let bytes = sketch.to_bytes();
+        assert_eq!(&bytes[..4], &[1, sketch.levels.len() as u8, 8, 0]);
+        assert_eq!(Kll::from_bytes(&bytes)?, sketch);
+
+        let empty = Kll::new(DEFAULT_K);
+        assert_eq!(Kll::from_bytes(&empty.to_bytes())?, empty);
+
+        let err = Kll::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert!(err.to_string().contains("Invalid KLL sketch"));
+        let err = Kll::from_bytes(&[2; 40]).unwrap_err();
+        assert!(err.to_string().contains("Unsupported KLL sketch version 2"));
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the KLL_SKETCH and KLL_MERGE aggregate expressions, building
+//! serialized KLL quantile sketches that can be stored and merged later
+
+use std::any::Any;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray};
+use arrow::datatypes::{DataType, Field, Float64Type};
+use datafusion_common::{internal_err, plan_err, DataFusionError, Result, ScalarValue};
+use datafusion_expr::Accumulator;
+
+use super::approx_percentile_cont::get_lit_value;
+use super::kll::{Kll, DEFAULT_K, MAX_K, MIN_K};
+use crate::aggregate::utils::down_cast_any_ref;
+use crate::expressions::format_state_name;
+use crate::{AggregateExpr, PhysicalExpr};
+
+/// KLL_SKETCH aggregate expression
+///
+/// Returns a serialized KLL sketch of its input, see [`Kll`] for the layout.
+/// The optional second argument is the `k` of the sketch, which trades
+/// accuracy for size.
+#[derive(Debug)]
+pub struct KllSketch {
+    name: String,
+    expr: Vec<Arc<dyn PhysicalExpr>>,
+    k: usize,
+}
+
+impl KllSketch {
+    /// Create a new KllSketch aggregate function.
+    pub fn new(
+        expr: Vec<Arc<dyn PhysicalExpr>>,
+        name: impl Into<String>,
+    ) -> Result<Self> {
+        let k = match expr.get(1) {
+            Some(k) => validate_sketch_size_expr(k, "KLL_SKETCH", MIN_K..=MAX_K)?,
+            None => DEFAULT_K,
+        };
+        Ok(Self {
+            name: name.into(),
+            expr,
+            k,
+        })
+    }
+}
+
+/// Returns the sketch size given by the literal `expr`, which must be within
+/// `valid`
+pub(crate) fn validate_sketch_size_expr(
+    expr: &Arc<dyn PhysicalExpr>,
+    fun: &str,
+    valid: RangeInclusive<usize>,
+) -> Result<usize> {
+    let size = match get_lit_value(expr)? {
+        ScalarValue::Int8(Some(v)) => v as i64,
+        ScalarValue::Int16(Some(v)) => v as i64,
+        ScalarValue::Int32(Some(v)) => v as i64,
+        ScalarValue::Int64(Some(v)) => v,
+        ScalarValue::UInt8(Some(v)) => v as i64,
+        ScalarValue::UInt16(Some(v)) => v as i64,
+        ScalarValue::UInt32(Some(v)) => v as i64,
+        ScalarValue::UInt64(Some(v)) => v.min(i64::MAX as u64) as i64,
+        got => {
+            return plan_err!(
+                "The sketch size for '{fun}' must be an integer literal (got {got})"
+            )
+        }
+    };
+    match usize::try_from(size) {
+        Ok(size) if valid.contains(&size) => Ok(size),
+        _ => plan_err!(
+            "The sketch size for '{fun}' must be between {} and {}, {size} is invalid",
+            valid.start(),
+            valid.end()
+        ),
+    }
+}
+
+impl AggregateExpr for KllSketch {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, DataType::Binary, false))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![kll_state_field(&self.name)])
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        self.expr.clone()
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(KllAccumulator::new(self.k, false)))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl PartialEq<dyn Any> for KllSketch {
+    fn eq(&self, other: &dyn Any) -> bool {
+        down_cast_any_ref(other)
+            .downcast_ref::<Self>()
+            .map(|x| {
+                self.name == x.name
+                    && self.k == x.k
+                    && self.expr.len() == x.expr.len()
+                    && self.expr.iter().zip(x.expr.iter()).all(|(a, b)| a.eq(b))
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// KLL_MERGE aggregate expression
+///
+/// Merges serialized KLL sketches created by [`KllSketch`]. The merged
+/// sketch uses the smallest `k` of its inputs.
+#[derive(Debug)]
+pub struct KllMerge {
+    name: String,
+    expr: Arc<dyn PhysicalExpr>,
+}
+
+impl KllMerge {
+    /// Create a new KllMerge aggregate function.
+    pub fn new(expr: Arc<dyn PhysicalExpr>, name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            expr,
+        }
+    }
+}
+
+impl AggregateExpr for KllMerge {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, DataType::Binary, false))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![kll_state_field(&self.name)])
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(KllAccumulator::new(MAX_K, true)))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl PartialEq<dyn Any> for KllMerge {
+    fn eq(&self, other: &dyn Any) -> bool {
+        down_cast_any_ref(other)
+            .downcast_ref::<Self>()
+            .map(|x| self.name == x.name && self.expr.eq(&x.expr))
+            .unwrap_or(false)
+    }
+}
+
+/// The state is the serialized sketch
+fn kll_state_field(name: &str) -> Field {
+    Field::new(
+        format_state_name(name, "kll_sketch"),
+        DataType::Binary,
+        false,
+    )
+}
+
+/// An accumulator for KLL_SKETCH and KLL_MERGE
+#[derive(Debug)]
+struct KllAccumulator {
+    kll: Kll,
+    /// Whether the input values are serialized sketches to merge
+    sketch_input: bool,
+}
+
+impl KllAccumulator {
+    fn new(k: usize, sketch_input: bool) -> Self {
+        Self {
+            kll: Kll::new(k),
+            sketch_input,
+        }
+    }
+
+    fn merge_sketches(&mut self, sketches: &ArrayRef) -> Result<()> {
+        for sketch in sketches.as_binary::<i32>().iter().flatten() {
+            self.kll.merge(&Kll::from_bytes(sketch)?);
+        }
+        Ok(())
+    }
+}
+
+impl Accumulator for KllAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        if self.sketch_input {
+            return self.merge_sketches(&values[0]);
+        }
+        // skip nulls
+        for value in values[0].as_primitive::<Float64Type>().iter().flatten() {
+            self.kll.update(value);
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        if states.len() != 1 {
+            return internal_err!("expect only 1 element in the states");
+        }
+        self.merge_sketches(&states[0])
+    }
+
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        Ok(ScalarValue::Binary(Some(self.kll.to_bytes())))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) - std::mem::size_of_val(&self.kll) + self.kll.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::tests::aggregate;
+    use crate::expressions::{col, lit};
+    use arrow::array::{BinaryArray, Float64Array};
+    use arrow::datatypes::Schema;
+    use arrow::record_batch::RecordBatch;
+
+    fn median(sketch: ScalarValue) -> Result<Option<f64>> {
+        match sketch {
+            ScalarValue::Binary(Some(sketch)) => {
+                Ok(Kll::from_bytes(&sketch)?.quantile(0.5))
+            }
+            other => internal_err!("Unexpected sketch {other}"),
+        }
+    }
+
+    #[test]
+    fn kll_sketch_and_merge() -> Result<()> {
+        let schema =
+            Arc::new(Schema::new(vec![Field::new("a", DataType::Float64, true)]));
+        let first = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Float64Array::from(vec![
+                Some(1.0),
+                None,
+                Some(2.0),
+            ]))],
+        )?;
+        let second = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Float64Array::from(vec![3.0, 4.0, 5.0]))],
+        )?;
+
+        let agg = Arc::new(KllSketch::new(
+            vec![col("a", &schema)?, lit(100_i64)],
+            "bla",
+        )?);
+        let first = aggregate(&first, agg.clone())?;
+        let second = aggregate(&second, agg)?;
+        assert_eq!(median(first.clone())?, Some(1.0));
+        assert_eq!(median(second.clone())?, Some(4.0));
+
+        let schema = Schema::new(vec![Field::new("s", DataType::Binary, true)]);
+        let sketches = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(BinaryArray::from(vec![
+                first.to_array()?.as_binary::<i32>().value(0),
+                second.to_array()?.as_binary::<i32>().value(0),
+            ]))],
+        )?;
+        let agg = Arc::new(KllMerge::new(col("s", &sketches.schema())?, "bla"));
+        assert_eq!(median(aggregate(&sketches, agg)?)?, Some(3.0));
+        Ok(())
+    }
+
+    #[test]
+    fn kll_sketch_invalid_k() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Float64, true)]);
+        let err =
+            KllSketch::new(vec![col("a", &schema)?, lit(1_i64)], "bla").unwrap_err();
+        assert!(err.to_string().contains("1 is invalid"), "{err}");
+        Ok(())
+    }
+}
@@ -17,14 +17,15 @@
 
 //! # Median
 
+use crate::aggregate::groups_accumulator::accumulate::accumulate_rows;
 use crate::aggregate::utils::down_cast_any_ref;
 use crate::expressions::format_state_name;
-use crate::{AggregateExpr, PhysicalExpr};
-use arrow::array::{Array, ArrayRef};
+use crate::{AggregateExpr, EmitTo, GroupsAccumulator, PhysicalExpr};
+use arrow::array::{Array, ArrayRef, BooleanArray, ListArray, PrimitiveArray};
 use arrow::datatypes::{DataType, Field};
 use arrow_array::cast::AsArray;
 use arrow_array::{downcast_integer, ArrowNativeTypeOp, ArrowNumericType};
-use arrow_buffer::ArrowNativeType;
+use arrow_buffer::{ArrowNativeType, OffsetBuffer};
 use datafusion_common::{DataFusionError, Result, ScalarValue};
 use datafusion_expr::Accumulator;
 use std::any::Any;
@@ -111,6 +112,48 @@ impl AggregateExpr for Median {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn groups_accumulator_supported(&self) -> bool {
+        matches!(
+            self.data_type,
+            DataType::Int8
+                | DataType::Int16
+                | DataType::Int32
+                | DataType::Int64
+                | DataType::UInt8
+                | DataType::UInt16
+                | DataType::UInt32
+                | DataType::UInt64
+                | DataType::Float16
+                | DataType::Float32
+                | DataType::Float64
+                | DataType::Decimal128(_, _)
+                | DataType::Decimal256(_, _)
+        )
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        use arrow_array::types::*;
+        macro_rules! helper {
+            ($t:ty, $dt:expr) => {
+                Ok(Box::new(MedianGroupsAccumulator::<$t>::new($dt)))
+            };
+        }
+        let dt = &self.data_type;
+        downcast_integer! {
+            dt => (helper, dt),
+            DataType::Float16 => helper!(Float16Type, dt),
+            DataType::Float32 => helper!(Float32Type, dt),
+            DataType::Float64 => helper!(Float64Type, dt),
+            DataType::Decimal128(_, _) => helper!(Decimal128Type, dt),
+            DataType::Decimal256(_, _) => helper!(Decimal256Type, dt),
+            _ => Err(DataFusionError::NotImplemented(format!(
+                "MedianGroupsAccumulator not supported for {} with {}",
+                self.name(),
+                self.data_type
+            ))),
+        }
+    }
 }
 
 impl PartialEq<dyn Any> for Median {
@@ -174,21 +217,7 @@ impl<T: ArrowNumericType> Accumulator for MedianAccumulator<T> {
     fn evaluate(&self) -> Result<ScalarValue> {
         // TODO: evaluate could pass &mut self
         let mut d = self.all_values.clone();
-        let cmp = |x: &T::Native, y: &T::Native| x.compare(*y);
-
-        let len = d.len();
-        let median = if len == 0 {
-            None
-        } else if len % 2 == 0 {
-            let (low, high, _) = d.select_nth_unstable_by(len / 2, cmp);
-            let (_, low, _) = low.select_nth_unstable_by(low.len() - 1, cmp);
-            let median = low.add_wrapping(*high).div_wrapping(T::Native::usize_as(2));
-            Some(median)
-        } else {
-            let (_, median, _) = d.select_nth_unstable_by(len / 2, cmp);
-            Some(*median)
-        };
-        ScalarValue::new_primitive::<T>(median, &self.data_type)
+        ScalarValue::new_primitive::<T>(median::<T>(&mut d), &self.data_type)
     }
 
     fn size(&self) -> usize {
@@ -197,11 +226,193 @@ impl<T: ArrowNumericType> Accumulator for MedianAccumulator<T> {
     }
 }
 
+/// Computes the median of `values`, reordering them in the process.
+/// Returns `None` if `values` is empty.
+fn median<T: ArrowNumericType>(values: &mut [T::Native]) -> Option<T::Native> {
+    let cmp = |x: &T::Native, y: &T::Native| x.compare(*y);
+
+    let len = values.len();
+    if len == 0 {
+        None
+    } else if len % 2 == 0 {
+        let (low, high, _) = values.select_nth_unstable_by(len / 2, cmp);
+        let (_, low, _) = low.select_nth_unstable_by(low.len() - 1, cmp);
+        let median = low.add_wrapping(*high).div_wrapping(T::Native::usize_as(2));
+        Some(median)
+    } else {
+        let (_, median, _) = values.select_nth_unstable_by(len / 2, cmp);
+        Some(*median)
+    }
+}
+
+/// An accumulator to compute the median for many groups at once.
+///
+/// Like [`MedianAccumulator`], all non-null input values are retained, in a
+/// single `Vec` together with their groups. They are only sorted by group
+/// when the groups are emitted.
+struct MedianGroupsAccumulator<T: ArrowNumericType> {
+    data_type: DataType,
+    /// The group of every value in `values`
+    group_indices: Vec<usize>,
+    values: Vec<T::Native>,
+    num_groups: usize,
+}
+
+impl<T: ArrowNumericType> std::fmt::Debug for MedianGroupsAccumulator<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MedianGroupsAccumulator({})", self.data_type)
+    }
+}
+
+impl<T: ArrowNumericType> MedianGroupsAccumulator<T> {
+    fn new(data_type: &DataType) -> Self {
+        Self {
+            data_type: data_type.clone(),
+            group_indices: vec![],
+            values: vec![],
+            num_groups: 0,
+        }
+    }
+
+    /// Removes the groups selected by `emit_to`, returning the number of
+    /// values of every emitted group and their values, ordered by group
+    fn emit(&mut self, emit_to: EmitTo) -> (Vec<usize>, Vec<T::Native>) {
+        let num_emitted = match emit_to {
+            EmitTo::All => self.num_groups,
+            EmitTo::First(n) => n,
+        };
+
+        // Stable counting sort of the emitted values by group
+        let mut lengths = vec![0; num_emitted];
+        for &group_index in &self.group_indices {
+            if group_index < num_emitted {
+                lengths[group_index] += 1;
+            }
+        }
+        let mut positions = Vec::with_capacity(num_emitted);
+        let mut num_values = 0;
+        for length in &lengths {
+            positions.push(num_values);
+            num_values += length;
+        }
+        let mut emitted = vec![T::Native::default(); num_values];
+        let mut retained_groups = vec![];
+        let mut retained_values = vec![];
+        for (&group_index, &value) in self.group_indices.iter().zip(&self.values) {
+            if group_index < num_emitted {
+                emitted[positions[group_index]] = value;
+                positions[group_index] += 1;
+            } else {
+                retained_groups.push(group_index - num_emitted);
+                retained_values.push(value);
+            }
+        }
+
+        self.group_indices = retained_groups;
+        self.values = retained_values;
+        self.num_groups -= num_emitted;
+        (lengths, emitted)
+    }
+}
+
+impl<T: ArrowNumericType> GroupsAccumulator for MedianGroupsAccumulator<T> {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 1, "single argument to update_batch");
+        let values = values[0].as_primitive::<T>();
+        self.num_groups = self.num_groups.max(total_num_groups);
+
+        let data = values.values();
+        let (groups, all_values) = (&mut self.group_indices, &mut self.values);
+        // skip nulls
+        accumulate_rows(
+            group_indices,
+            values.nulls(),
+            opt_filter,
+            |row_index, group_index| {
+                groups.push(group_index);
+                all_values.push(data[row_index]);
+            },
+        );
+        Ok(())
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 1, "one argument to merge_batch");
+        let lists = values[0].as_list::<i32>();
+        self.num_groups = self.num_groups.max(total_num_groups);
+
+        let offsets = lists.value_offsets();
+        let data = lists.values().as_primitive::<T>();
+        let (groups, all_values) = (&mut self.group_indices, &mut self.values);
+        accumulate_rows(
+            group_indices,
+            lists.nulls(),
+            opt_filter,
+            |row_index, group_index| {
+                let start = offsets[row_index] as usize;
+                let end = offsets[row_index + 1] as usize;
+                for value_index in start..end {
+                    if data.is_valid(value_index) {
+                        groups.push(group_index);
+                        all_values.push(data.value(value_index));
+                    }
+                }
+            },
+        );
+        Ok(())
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        let (lengths, mut values) = self.emit(emit_to);
+        let mut start = 0;
+        let medians: PrimitiveArray<T> = lengths
+            .into_iter()
+            .map(|length| {
+                let group_values = &mut values[start..start + length];
+                start += length;
+                median::<T>(group_values)
+            })
+            .collect();
+        Ok(Arc::new(medians.with_data_type(self.data_type.clone())))
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let (lengths, values) = self.emit(emit_to);
+        let values = PrimitiveArray::<T>::new(values.into(), None)
+            .with_data_type(self.data_type.clone());
+        let lists = ListArray::new(
+            Arc::new(Field::new("item", self.data_type.clone(), true)),
+            OffsetBuffer::from_lengths(lengths),
+            Arc::new(values),
+            None,
+        );
+        Ok(vec![Arc::new(lists)])
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.group_indices.capacity() * std::mem::size_of::<usize>()
+            + self.values.capacity() * std::mem::size_of::<T::Native>()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::expressions::col;
-    use crate::expressions::tests::aggregate;
+    use crate::expressions::tests::{aggregate, assert_groups_accumulator};
     use crate::generic_test_op;
     use arrow::record_batch::RecordBatch;
     use arrow::{array::*, datatypes::*};
@@ -331,4 +542,52 @@ mod tests {
         ]));
         generic_test_op!(a, DataType::Float64, Median, ScalarValue::from(3.5_f64))
     }
+
+    #[test]
+    fn median_groups_accumulator() -> Result<()> {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(4),
+            Some(1),
+            None,
+            Some(7),
+            Some(2),
+            Some(9),
+            Some(3),
+            Some(5),
+        ]));
+        let b: ArrayRef = Arc::new(
+            vec![
+                Some(10),
+                None,
+                Some(30),
+                Some(40),
+                Some(50),
+                Some(60),
+                None,
+                Some(80),
+            ]
+            .into_iter()
+            .collect::<Decimal128Array>()
+            .with_precision_and_scale(10, 4)?,
+        );
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Decimal128(10, 4), true),
+        ]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![a, b])?;
+        let group_indices = [0, 1, 0, 0, 1, 2, 1, 0];
+
+        let aggs: Vec<Arc<dyn AggregateExpr>> = vec![
+            Arc::new(Median::new(col("a", &schema)?, "bla", DataType::Int32)),
+            Arc::new(Median::new(
+                col("b", &schema)?,
+                "bla",
+                DataType::Decimal128(10, 4),
+            )),
+        ];
+        for agg in aggs {
+            assert_groups_accumulator(&batch, agg, &group_indices)?;
+        }
+        Ok(())
+    }
 }
@@ -27,13 +27,16 @@ use arrow::datatypes::Field;
 use datafusion_common::{not_impl_err, DataFusionError, Result};
 use datafusion_expr::Accumulator;
 
-mod hyperloglog;
+pub(crate) mod hyperloglog;
+pub(crate) mod kll;
+pub(crate) mod space_saving;
 mod tdigest;
 
 pub(crate) mod approx_distinct;
 pub(crate) mod approx_median;
 pub(crate) mod approx_percentile_cont;
 pub(crate) mod approx_percentile_cont_with_weight;
+pub(crate) mod approx_top_k_sketch;
 pub(crate) mod array_agg;
 pub(crate) mod array_agg_distinct;
 pub(crate) mod array_agg_ordered;
@@ -46,6 +49,8 @@ pub(crate) mod count_distinct;
 pub(crate) mod covariance;
 pub(crate) mod first_last;
 pub(crate) mod grouping;
+pub(crate) mod hll_sketch;
+pub(crate) mod kll_sketch;
 pub(crate) mod median;
 pub(crate) mod string_agg;
 #[macro_use]
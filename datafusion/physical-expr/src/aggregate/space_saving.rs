@@ -0,0 +1,351 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An implementation of the [Space-Saving] algorithm to find the most
+//! frequent items with a mergeable state.
+//!
+//! The sketch keeps at most `capacity` counters. An item without a counter
+//! replaces the item with the smallest count, inheriting that count as its
+//! error. The count of an item overestimates its frequency by at most its
+//! error, and any item more frequent than `n / capacity` has a counter.
+//! Sketches are merged as described in [Mergeable Summaries].
+//!
+//! # Serialized sketch
+//!
+//! The sketch is exchanged (see `approx_top_k_sketch` and `top_k_items`)
+//! with the following stable binary layout. All numbers are little endian.
+//!
+//! | offset | size | content                                      |
+//! |--------|------|----------------------------------------------|
+//! | 0      | 1    | format version, currently `1`                |
+//! | 1      | 4    | capacity, u32                                |
+//! | 5      | 8    | number of added items `n`, u64               |
+//! | 13     | 4    | number of counters `m`, u32                  |
+//! | 17     |      | `m` counters, by descending count            |
+//!
+//! Each counter is laid out as
+//!
+//! | size   | content                             |
+//! |--------|-------------------------------------|
+//! | 8      | count, u64                          |
+//! | 8      | error, u64                          |
+//! | 4      | length of the item in bytes, u32    |
+//! | length | the item, UTF-8                     |
+//!
+//! [Space-Saving]: https://doi.org/10.1007/978-3-540-30570-5_27
+//! [Mergeable Summaries]: https://doi.org/10.1145/2213556.2213562
+
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+
+use datafusion_common::{exec_err, DataFusionError, Result};
+use hashbrown::HashMap;
+
+/// The default number of counters
+pub(crate) const DEFAULT_CAPACITY: usize = 1000;
+/// The largest supported number of counters
+pub(crate) const MAX_CAPACITY: usize = 1 << 20;
+
+/// The version of the serialized sketch layout
+const SPACE_SAVING_VERSION: u8 = 1;
+/// The size of the fixed part of a serialized sketch
+const HEADER_SIZE: usize = 17;
+/// The size of the fixed part of a serialized counter
+const COUNTER_HEADER_SIZE: usize = 20;
+
+/// The count of an item and by how much it may overestimate the frequency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Counter {
+    pub(crate) count: u64,
+    pub(crate) error: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SpaceSaving {
+    capacity: usize,
+    n: u64,
+    counters: HashMap<String, Counter>,
+    /// The counters ordered by count and, for equal counts, by descending
+    /// item, to find the smallest one
+    by_count: BTreeSet<(u64, Reverse<String>)>,
+}
+
+impl SpaceSaving {
+    /// Creates an empty sketch with `capacity` counters
+    pub(crate) fn new(capacity: usize) -> Self {
+        debug_assert!((1..=MAX_CAPACITY).contains(&capacity));
+        Self {
+            capacity,
+            n: 0,
+            counters: HashMap::new(),
+            by_count: BTreeSet::new(),
+        }
+    }
+
+    /// Adds `item` to the sketch
+    pub(crate) fn update(&mut self, item: &str) {
+        self.n += 1;
+        if let Some(counter) = self.counters.get_mut(item) {
+            self.by_count
+                .remove(&(counter.count, Reverse(item.to_string())));
+            counter.count += 1;
+            self.by_count
+                .insert((counter.count, Reverse(item.to_string())));
+            return;
+        }
+
+        let counter = if self.counters.len() < self.capacity {
+            Counter { count: 1, error: 0 }
+        } else {
+            // replace the smallest counter
+            let (min_count, Reverse(min_item)) = self.by_count.pop_first().unwrap();
+            self.counters.remove(&min_item);
+            Counter {
+                count: min_count + 1,
+                error: min_count,
+            }
+        };
+        self.by_count
+            .insert((counter.count, Reverse(item.to_string())));
+        self.counters.insert(item.to_string(), counter);
+    }
+
+    /// The smallest count, if all counters are in use
+    fn min_count(&self) -> u64 {
+        match self.by_count.first() {
+            Some((count, _)) if self.counters.len() >= self.capacity => *count,
+            _ => 0,
+        }
+    }
+
+    /// Merges `other` into this sketch. The merged sketch uses the smaller
+    /// capacity of both.
+    pub(crate) fn merge(&mut self, other: &SpaceSaving) {
+        if other.n == 0 {
+            return;
+        }
+        // an item without a counter may have occurred up to min_count times
+        let min_count = self.min_count();
+        let other_min_count = other.min_count();
+
+        let mut counters = std::mem::take(&mut self.counters);
+        for (item, counter) in counters.iter_mut() {
+            let other_counter = other.counters.get(item).copied().unwrap_or(Counter {
+                count: other_min_count,
+                error: other_min_count,
+            });
+            counter.count += other_counter.count;
+            counter.error += other_counter.error;
+        }
+        for (item, other_counter) in &other.counters {
+            if !counters.contains_key(item) {
+                counters.insert(
+                    item.clone(),
+                    Counter {
+                        count: other_counter.count + min_count,
+                        error: other_counter.error + min_count,
+                    },
+                );
+            }
+        }
+
+        self.capacity = self.capacity.min(other.capacity);
+        self.n += other.n;
+        self.set_counters(counters);
+    }
+
+    /// Replaces the counters by the `capacity` largest of `counters`
+    fn set_counters(&mut self, counters: HashMap<String, Counter>) {
+        let mut counters = counters.into_iter().collect::<Vec<_>>();
+        if counters.len() > self.capacity {
+            counters.sort_unstable_by(|(a_item, a), (b_item, b)| {
+                b.count.cmp(&a.count).then_with(|| a_item.cmp(b_item))
+            });
+            counters.truncate(self.capacity);
+        }
+        self.by_count = counters
+            .iter()
+            .map(|(item, counter)| (counter.count, Reverse(item.clone())))
+            .collect();
+        self.counters = counters.into_iter().collect();
+    }
+
+    /// Returns the `k` most frequent items, by descending count and item
+    pub(crate) fn top_k(&self, k: usize) -> Vec<(&str, Counter)> {
+        self.by_count
+            .iter()
+            .rev()
+            .take(k)
+            .map(|(_, Reverse(item))| (item.as_str(), self.counters[item]))
+            .collect()
+    }
+
+    /// Returns the approximate memory used by the sketch, in bytes
+    pub(crate) fn size(&self) -> usize {
+        let items_size = self
+            .counters
+            .keys()
+            .map(|item| item.capacity())
+            .sum::<usize>();
+        std::mem::size_of_val(self)
+            + self.counters.capacity() * std::mem::size_of::<(String, Counter)>()
+            + self.by_count.len() * std::mem::size_of::<(u64, Reverse<String>)>()
+            + 2 * items_size
+    }
+
+    /// Serializes the sketch, see the [module docs](self)
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_SIZE);
+        bytes.push(SPACE_SAVING_VERSION);
+        bytes.extend_from_slice(&(self.capacity as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.n.to_le_bytes());
+        bytes.extend_from_slice(&(self.counters.len() as u32).to_le_bytes());
+        for (item, counter) in self.top_k(self.counters.len()) {
+            bytes.extend_from_slice(&counter.count.to_le_bytes());
+            bytes.extend_from_slice(&counter.error.to_le_bytes());
+            bytes.extend_from_slice(&(item.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(item.as_bytes());
+        }
+        bytes
+    }
+
+    /// Deserializes a sketch created by [`Self::to_bytes`]
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_SIZE {
+            return exec_err!("Invalid top-k sketch of {} bytes", bytes.len());
+        }
+        if bytes[0] != SPACE_SAVING_VERSION {
+            return exec_err!("Unsupported top-k sketch version {}", bytes[0]);
+        }
+        let capacity = u32::from_le_bytes(bytes[1..5].try_into().unwrap()) as usize;
+        let n = u64::from_le_bytes(bytes[5..13].try_into().unwrap());
+        let num_counters = u32::from_le_bytes(bytes[13..17].try_into().unwrap()) as usize;
+        if !(1..=MAX_CAPACITY).contains(&capacity) || num_counters > capacity {
+            return exec_err!("Invalid top-k sketch of {} bytes", bytes.len());
+        }
+
+        let mut counters = HashMap::with_capacity(num_counters);
+        let mut rest = &bytes[HEADER_SIZE..];
+        for _ in 0..num_counters {
+            if rest.len() < COUNTER_HEADER_SIZE {
+                return exec_err!("Invalid top-k sketch of {} bytes", bytes.len());
+            }
+            let count = u64::from_le_bytes(rest[0..8].try_into().unwrap());
+            let error = u64::from_le_bytes(rest[8..16].try_into().unwrap());
+            let len = u32::from_le_bytes(rest[16..20].try_into().unwrap()) as usize;
+            rest = &rest[COUNTER_HEADER_SIZE..];
+            if rest.len() < len {
+                return exec_err!("Invalid top-k sketch of {} bytes", bytes.len());
+            }
+            let Ok(item) = std::str::from_utf8(&rest[..len]) else {
+                return exec_err!("Invalid item in top-k sketch");
+            };
+            counters.insert(item.to_string(), Counter { count, error });
+            rest = &rest[len..];
+        }
+        if !rest.is_empty() || counters.len() != num_counters {
+            return exec_err!("Invalid top-k sketch of {} bytes", bytes.len());
+        }
+
+        let mut sketch = Self::new(capacity);
+        sketch.n = n;
+        sketch.set_counters(counters);
+        Ok(sketch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(sketch: &SpaceSaving, k: usize) -> Vec<(&str, u64)> {
+        sketch
+            .top_k(k)
+            .into_iter()
+            .map(|(item, counter)| (item, counter.count))
+            .collect()
+    }
+
+    #[test]
+    fn test_exact_below_capacity() {
+        let mut sketch = SpaceSaving::new(10);
+        for item in ["a", "b", "a", "c", "a", "b"] {
+            sketch.update(item);
+        }
+        assert_eq!(sketch.n, 6);
+        assert_eq!(items(&sketch, 2), vec![("a", 3), ("b", 2)]);
+        assert_eq!(items(&sketch, 5), vec![("a", 3), ("b", 2), ("c", 1)]);
+    }
+
+    #[test]
+    fn test_heavy_hitters() {
+        let mut sketch = SpaceSaving::new(100);
+        for i in 0..10_000 {
+            // "x" and "y" occur 1000 and 500 times, all other items once
+            match i % 10 {
+                0 => sketch.update("x"),
+                5 if i % 20 == 5 => sketch.update("y"),
+                _ => sketch.update(&i.to_string()),
+            }
+        }
+        let top = sketch.top_k(2);
+        assert_eq!(top[0].0, "x");
+        assert_eq!(top[1].0, "y");
+        // items more frequent than n / capacity are guaranteed to be found
+        assert!(top[0].1.count >= 1000 && top[0].1.count - top[0].1.error <= 1000);
+        assert!(top[1].1.count >= 500 && top[1].1.count - top[1].1.error <= 500);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut left = SpaceSaving::new(3);
+        let mut right = SpaceSaving::new(3);
+        for item in ["a", "a", "a", "b", "b", "c"] {
+            left.update(item);
+        }
+        for item in ["a", "d", "d", "d", "e"] {
+            right.update(item);
+        }
+        left.merge(&right);
+        assert_eq!(left.n, 11);
+        // d was not seen by left, which may have dropped up to 1 occurrence
+        assert_eq!(items(&left, 2), vec![("a", 4), ("d", 4)]);
+        assert_eq!(left.top_k(3).len(), 3);
+    }
+
+    #[test]
+    fn test_serialization() -> Result<()> {
+        let mut sketch = SpaceSaving::new(2);
+        for item in ["a", "b", "a", "ü"] {
+            sketch.update(item);
+        }
+        let bytes = sketch.to_bytes();
+        assert_eq!(&bytes[..5], &[1, 2, 0, 0, 0]);
+        assert_eq!(SpaceSaving::from_bytes(&bytes)?, sketch);
+
+        let empty = SpaceSaving::new(DEFAULT_CAPACITY);
+        assert_eq!(SpaceSaving::from_bytes(&empty.to_bytes())?, empty);
+
+        let err = SpaceSaving::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert!(err.to_string().contains("Invalid top-k sketch"));
+        let err = SpaceSaving::from_bytes(&[3; 20]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unsupported top-k sketch version 3"));
+        Ok(())
+    }
+}
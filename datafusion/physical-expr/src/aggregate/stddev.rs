@@ -22,9 +22,11 @@ use std::sync::Arc;
 
 use crate::aggregate::stats::StatsType;
 use crate::aggregate::utils::down_cast_any_ref;
-use crate::aggregate::variance::VarianceAccumulator;
+use crate::aggregate::variance::{VarianceAccumulator, VarianceGroupsAccumulator};
 use crate::expressions::format_state_name;
-use crate::{AggregateExpr, PhysicalExpr};
+use crate::{AggregateExpr, EmitTo, GroupsAccumulator, PhysicalExpr};
+use arrow::array::BooleanArray;
+use arrow::datatypes::Float64Type;
 use arrow::{array::ArrayRef, datatypes::DataType, datatypes::Field};
 use datafusion_common::ScalarValue;
 use datafusion_common::{internal_err, DataFusionError, Result};
@@ -101,6 +103,14 @@ impl AggregateExpr for Stddev {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn groups_accumulator_supported(&self) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(StddevGroupsAccumulator::new(StatsType::Sample)))
+    }
 }
 
 impl PartialEq<dyn Any> for Stddev {
@@ -169,6 +179,16 @@ impl AggregateExpr for StddevPop {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn groups_accumulator_supported(&self) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(StddevGroupsAccumulator::new(
+            StatsType::Population,
+        )))
+    }
 }
 
 impl PartialEq<dyn Any> for StddevPop {
@@ -240,12 +260,65 @@ impl Accumulator for StddevAccumulator {
     }
 }
 
+/// An accumulator to compute the standard deviation for many groups
+/// at once
+#[derive(Debug)]
+struct StddevGroupsAccumulator {
+    variance: VarianceGroupsAccumulator,
+}
+
+impl StddevGroupsAccumulator {
+    /// Creates a new `StddevGroupsAccumulator`
+    pub fn new(s_type: StatsType) -> Self {
+        Self {
+            variance: VarianceGroupsAccumulator::new(s_type),
+        }
+    }
+}
+
+impl GroupsAccumulator for StddevGroupsAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        self.variance
+            .update_batch(values, group_indices, opt_filter, total_num_groups)
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        self.variance
+            .merge_batch(values, group_indices, opt_filter, total_num_groups)
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        let variance = self.variance.variance(emit_to);
+        Ok(Arc::new(variance.unary::<_, Float64Type>(f64::sqrt)))
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        self.variance.state(emit_to)
+    }
+
+    fn size(&self) -> usize {
+        self.variance.size()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::aggregate::utils::get_accum_scalar_values_as_arrays;
     use crate::expressions::col;
-    use crate::expressions::tests::aggregate;
+    use crate::expressions::tests::{aggregate, assert_groups_accumulator};
     use crate::generic_test_op;
     use arrow::record_batch::RecordBatch;
     use arrow::{array::*, datatypes::*};
@@ -432,6 +505,39 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn stddev_groups_accumulator() -> Result<()> {
+        let a: ArrayRef = Arc::new(Float64Array::from(vec![
+            Some(1.5),
+            Some(2.0),
+            None,
+            Some(4.25),
+            Some(-3.0),
+            Some(8.0),
+            Some(0.5),
+        ]));
+        let schema = Schema::new(vec![Field::new("a", DataType::Float64, true)]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![a])?;
+        let group_indices = [1, 0, 2, 1, 0, 1, 0];
+
+        let aggs: Vec<Arc<dyn AggregateExpr>> = vec![
+            Arc::new(Stddev::new(
+                col("a", &schema)?,
+                "bla".to_string(),
+                DataType::Float64,
+            )),
+            Arc::new(StddevPop::new(
+                col("a", &schema)?,
+                "bla".to_string(),
+                DataType::Float64,
+            )),
+        ];
+        for agg in aggs {
+            assert_groups_accumulator(&batch, agg, &group_indices)?;
+        }
+        Ok(())
+    }
+
     fn merge(
         batch1: &RecordBatch,
         batch2: &RecordBatch,
@@ -20,17 +20,20 @@
 use std::any::Any;
 use std::sync::Arc;
 
+use crate::aggregate::groups_accumulator::accumulate::accumulate_rows;
 use crate::aggregate::stats::StatsType;
 use crate::aggregate::utils::down_cast_any_ref;
 use crate::expressions::format_state_name;
-use crate::{AggregateExpr, PhysicalExpr};
-use arrow::array::Float64Array;
+use crate::{AggregateExpr, EmitTo, GroupsAccumulator, PhysicalExpr};
+use arrow::array::{Array, AsArray, BooleanArray, Float64Array};
+use arrow::datatypes::{Float64Type, UInt64Type};
 use arrow::{
     array::{ArrayRef, UInt64Array},
     compute::cast,
     datatypes::DataType,
     datatypes::Field,
 };
+use arrow_buffer::{BooleanBuffer, NullBuffer};
 use datafusion_common::downcast_value;
 use datafusion_common::{DataFusionError, Result, ScalarValue};
 use datafusion_expr::Accumulator;
@@ -106,6 +109,14 @@ impl AggregateExpr for Variance {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn groups_accumulator_supported(&self) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(VarianceGroupsAccumulator::new(StatsType::Sample)))
+    }
 }
 
 impl PartialEq<dyn Any> for Variance {
@@ -178,6 +189,16 @@ impl AggregateExpr for VariancePop {
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn groups_accumulator_supported(&self) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(VarianceGroupsAccumulator::new(
+            StatsType::Population,
+        )))
+    }
 }
 
 impl PartialEq<dyn Any> for VariancePop {
@@ -244,15 +265,8 @@ impl Accumulator for VarianceAccumulator {
         let arr = downcast_value!(values, Float64Array).iter().flatten();
 
         for value in arr {
-            let new_count = self.count + 1;
-            let delta1 = value - self.mean;
-            let new_mean = delta1 / new_count as f64 + self.mean;
-            let delta2 = value - new_mean;
-            let new_m2 = self.m2 + delta1 * delta2;
-
-            self.count += 1;
-            self.mean = new_mean;
-            self.m2 = new_m2;
+            (self.count, self.mean, self.m2) =
+                welford_update(self.count, self.mean, self.m2, value);
         }
 
         Ok(())
@@ -283,21 +297,14 @@ impl Accumulator for VarianceAccumulator {
         let m2s = downcast_value!(states[2], Float64Array);
 
         for i in 0..counts.len() {
-            let c = counts.value(i);
-            if c == 0_u64 {
-                continue;
-            }
-            let new_count = self.count + c;
-            let new_mean = self.mean * self.count as f64 / new_count as f64
-                + means.value(i) * c as f64 / new_count as f64;
-            let delta = self.mean - means.value(i);
-            let new_m2 = self.m2
-                + m2s.value(i)
-                + delta * delta * self.count as f64 * c as f64 / new_count as f64;
-
-            self.count = new_count;
-            self.mean = new_mean;
-            self.m2 = new_m2;
+            (self.count, self.mean, self.m2) = welford_merge(
+                self.count,
+                self.mean,
+                self.m2,
+                counts.value(i),
+                means.value(i),
+                m2s.value(i),
+            );
         }
         Ok(())
     }
@@ -332,12 +339,212 @@ impl Accumulator for VarianceAccumulator {
     }
 }
 
+/// Adds `value` to the running `(count, mean, m2)` of a variance
+/// computation, returning the new `(count, mean, m2)`
+#[inline]
+pub(crate) fn welford_update(
+    count: u64,
+    mean: f64,
+    m2: f64,
+    value: f64,
+) -> (u64, f64, f64) {
+    let new_count = count + 1;
+    let delta1 = value - mean;
+    let new_mean = delta1 / new_count as f64 + mean;
+    let delta2 = value - new_mean;
+    let new_m2 = m2 + delta1 * delta2;
+
+    (new_count, new_mean, new_m2)
+}
+
+/// Merges the partial state `(count, mean, m2)` of another variance
+/// computation into the running one, returning the new `(count, mean, m2)`
+#[inline]
+pub(crate) fn welford_merge(
+    count: u64,
+    mean: f64,
+    m2: f64,
+    count2: u64,
+    mean2: f64,
+    m22: f64,
+) -> (u64, f64, f64) {
+    if count2 == 0_u64 {
+        return (count, mean, m2);
+    }
+    let new_count = count + count2;
+    let new_mean =
+        mean * count as f64 / new_count as f64 + mean2 * count2 as f64 / new_count as f64;
+    let delta = mean - mean2;
+    let new_m2 =
+        m2 + m22 + delta * delta * count as f64 * count2 as f64 / new_count as f64;
+
+    (new_count, new_mean, new_m2)
+}
+
+/// Computes the final variance from `(count, m2)`, or `None` if there
+/// are not enough values
+#[inline]
+fn variance(count: u64, m2: f64, stats_type: StatsType) -> Option<f64> {
+    match stats_type {
+        StatsType::Population if count > 0 => Some(m2 / count as f64),
+        StatsType::Sample if count > 1 => Some(m2 / (count - 1) as f64),
+        _ => None,
+    }
+}
+
+/// An accumulator to compute variance for many groups at once, using
+/// the same algorithm as [`VarianceAccumulator`]
+///
+/// The `count`, `mean` and `m2` of every group are stored in three
+/// vectors, indexed by `group_index`.
+#[derive(Debug)]
+pub(crate) struct VarianceGroupsAccumulator {
+    m2s: Vec<f64>,
+    means: Vec<f64>,
+    counts: Vec<u64>,
+    stats_type: StatsType,
+}
+
+impl VarianceGroupsAccumulator {
+    /// Creates a new `VarianceGroupsAccumulator`
+    pub fn new(s_type: StatsType) -> Self {
+        Self {
+            m2s: vec![],
+            means: vec![],
+            counts: vec![],
+            stats_type: s_type,
+        }
+    }
+
+    fn resize(&mut self, total_num_groups: usize) {
+        self.m2s.resize(total_num_groups, 0_f64);
+        self.means.resize(total_num_groups, 0_f64);
+        self.counts.resize(total_num_groups, 0_u64);
+    }
+
+    /// Computes the variances of the groups specified by `emit_to`,
+    /// removing them from the accumulator
+    ///
+    /// Groups that did not see enough values are null
+    pub fn variance(&mut self, emit_to: EmitTo) -> Float64Array {
+        let counts = emit_to.take_needed(&mut self.counts);
+        let _ = emit_to.take_needed(&mut self.means);
+        let m2s = emit_to.take_needed(&mut self.m2s);
+
+        let variances: Vec<Option<f64>> = counts
+            .into_iter()
+            .zip(m2s)
+            .map(|(count, m2)| variance(count, m2, self.stats_type))
+            .collect();
+        let nulls: BooleanBuffer = variances.iter().map(Option::is_some).collect();
+        let values: Vec<f64> = variances
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect();
+
+        Float64Array::new(values.into(), Some(NullBuffer::new(nulls)))
+    }
+}
+
+impl GroupsAccumulator for VarianceGroupsAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 1, "single argument to update_batch");
+        let values = cast(&values[0], &DataType::Float64)?;
+        let values = values.as_primitive::<Float64Type>();
+        let data = values.values();
+
+        self.resize(total_num_groups);
+        accumulate_rows(
+            group_indices,
+            values.nulls(),
+            opt_filter,
+            |row_index, group_index| {
+                let (count, mean, m2) = welford_update(
+                    self.counts[group_index],
+                    self.means[group_index],
+                    self.m2s[group_index],
+                    data[row_index],
+                );
+                self.counts[group_index] = count;
+                self.means[group_index] = mean;
+                self.m2s[group_index] = m2;
+            },
+        );
+
+        Ok(())
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        assert_eq!(values.len(), 3, "three arguments to merge_batch");
+        // first batch is counts, second is partial means, third is partial m2s
+        let partial_counts = values[0].as_primitive::<UInt64Type>();
+        let partial_means = values[1].as_primitive::<Float64Type>();
+        let partial_m2s = values[2].as_primitive::<Float64Type>();
+
+        self.resize(total_num_groups);
+        accumulate_rows(
+            group_indices,
+            partial_counts.nulls(),
+            opt_filter,
+            |row_index, group_index| {
+                let (count, mean, m2) = welford_merge(
+                    self.counts[group_index],
+                    self.means[group_index],
+                    self.m2s[group_index],
+                    partial_counts.value(row_index),
+                    partial_means.value(row_index),
+                    partial_m2s.value(row_index),
+                );
+                self.counts[group_index] = count;
+                self.means[group_index] = mean;
+                self.m2s[group_index] = m2;
+            },
+        );
+
+        Ok(())
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        Ok(Arc::new(self.variance(emit_to)))
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let counts = emit_to.take_needed(&mut self.counts);
+        let means = emit_to.take_needed(&mut self.means);
+        let m2s = emit_to.take_needed(&mut self.m2s);
+
+        Ok(vec![
+            Arc::new(UInt64Array::new(counts.into(), None)),
+            Arc::new(Float64Array::new(means.into(), None)),
+            Arc::new(Float64Array::new(m2s.into(), None)),
+        ])
+    }
+
+    fn size(&self) -> usize {
+        self.m2s.capacity() * std::mem::size_of::<f64>()
+            + self.means.capacity() * std::mem::size_of::<f64>()
+            + self.counts.capacity() * std::mem::size_of::<u64>()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::aggregate::utils::get_accum_scalar_values_as_arrays;
     use crate::expressions::col;
-    use crate::expressions::tests::aggregate;
+    use crate::expressions::tests::{aggregate, assert_groups_accumulator};
     use crate::generic_test_op;
     use arrow::record_batch::RecordBatch;
     use arrow::{array::*, datatypes::*};
@@ -506,6 +713,42 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn variance_groups_accumulator() -> Result<()> {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(1),
+            None,
+            Some(3),
+            Some(4),
+            Some(5),
+            Some(8),
+            None,
+            Some(2),
+            Some(7),
+        ]));
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![a])?;
+        // group 3 only sees a null, group 2 a single value
+        let group_indices = [0, 1, 0, 2, 1, 0, 3, 1, 0];
+
+        let aggs: Vec<Arc<dyn AggregateExpr>> = vec![
+            Arc::new(Variance::new(
+                col("a", &schema)?,
+                "bla".to_string(),
+                DataType::Float64,
+            )),
+            Arc::new(VariancePop::new(
+                col("a", &schema)?,
+                "bla".to_string(),
+                DataType::Float64,
+            )),
+        ];
+        for agg in aggs {
+            assert_groups_accumulator(&batch, agg, &group_indices)?;
+        }
+        Ok(())
+    }
+
     fn merge(
         batch1: &RecordBatch,
         batch2: &RecordBatch,
@@ -22,6 +22,7 @@ use std::{any::Any, sync::Arc};
 
 use crate::array_expressions::array_has_all;
 use crate::expressions::datum::{apply, apply_cmp};
+use crate::expressions::{Column, IsNotNullExpr, IsNullExpr, Literal, NotExpr};
 use crate::intervals::cp_solver::{propagate_arithmetic, propagate_comparison};
 use crate::physical_expr::down_cast_any_ref;
 use crate::sort_properties::SortProperties;
@@ -260,6 +261,9 @@ impl PhysicalExpr for BinaryExpr {
         use arrow::compute::kernels::numeric::*;
 
         let lhs = self.left.evaluate(batch)?;
+        if let Some(result) = self.evaluate_short_circuit(batch, &lhs)? {
+            return Ok(result);
+        }
         let rhs = self.right.evaluate(batch)?;
         let left_data_type = lhs.data_type();
         let right_data_type = rhs.data_type();
@@ -494,7 +498,94 @@ fn to_result_type_array(
     }
 }
 
+/// Returns true if `expr` cannot fail and is cheap enough that evaluating it
+/// for all rows is faster than evaluating it for a selection of the rows.
+fn is_cheap_and_infallible(expr: &Arc<dyn PhysicalExpr>) -> bool {
+    let any = expr.as_any();
+    if any.is::<Column>() || any.is::<Literal>() {
+        return true;
+    }
+    if let Some(binary) = any.downcast_ref::<BinaryExpr>() {
+        return matches!(
+            binary.op,
+            Operator::Eq
+                | Operator::NotEq
+                | Operator::Lt
+                | Operator::LtEq
+                | Operator::Gt
+                | Operator::GtEq
+                | Operator::IsDistinctFrom
+                | Operator::IsNotDistinctFrom
+                | Operator::And
+                | Operator::Or
+        ) && is_cheap_and_infallible(&binary.left)
+            && is_cheap_and_infallible(&binary.right);
+    }
+    if let Some(not) = any.downcast_ref::<NotExpr>() {
+        return is_cheap_and_infallible(not.arg());
+    }
+    if let Some(is_null) = any.downcast_ref::<IsNullExpr>() {
+        return is_cheap_and_infallible(is_null.arg());
+    }
+    if let Some(is_not_null) = any.downcast_ref::<IsNotNullExpr>() {
+        return is_cheap_and_infallible(is_not_null.arg());
+    }
+    false
+}
+
 impl BinaryExpr {
+    /// Evaluate `AND` and `OR` by evaluating the right input only for the
+    /// rows whose result is not already determined by the left input `lhs`.
+    ///
+    /// Returns `None` if the right input is needed for all rows, or if it is
+    /// cheap and cannot fail, in which case evaluating it for all rows is
+    /// faster than building a selection.
+    fn evaluate_short_circuit(
+        &self,
+        batch: &RecordBatch,
+        lhs: &ColumnarValue,
+    ) -> Result<Option<ColumnarValue>> {
+        let ColumnarValue::Array(array) = lhs else {
+            return Ok(None);
+        };
+        if array.data_type() != &DataType::Boolean {
+            return Ok(None);
+        }
+        let left = as_boolean_array(array)?;
+
+        // the rows where the left input is not false for AND, and not true
+        // for OR
+        let values = left.values();
+        let undetermined = match (self.op, left.nulls()) {
+            (Operator::And, Some(nulls)) => values | &!nulls.inner(),
+            (Operator::And, None) => values.clone(),
+            (Operator::Or, Some(nulls)) => !&(values & nulls.inner()),
+            (Operator::Or, None) => !values,
+            _ => return Ok(None),
+        };
+        let undetermined_count = undetermined.count_set_bits();
+        if undetermined_count == 0 {
+            return Ok(Some(lhs.clone()));
+        }
+        if undetermined_count == left.len() || is_cheap_and_infallible(&self.right) {
+            return Ok(None);
+        }
+
+        // the right input is null for the other rows, which does not
+        // change their result
+        let selection = BooleanArray::new(undetermined, None);
+        let right = self
+            .right
+            .evaluate_selection(batch, &selection)?
+            .into_array(batch.num_rows())?;
+        let right = as_boolean_array(&right)?;
+        let result = match self.op {
+            Operator::And => and_kleene(left, right)?,
+            _ => or_kleene(left, right)?,
+        };
+        Ok(Some(ColumnarValue::Array(Arc::new(result))))
+    }
+
     /// Evaluate the expression of the left input is an array and
     /// right is literal - use scalar operations
     fn evaluate_array_scalar(
@@ -2384,6 +2475,48 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn and_or_short_circuit() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let a = Int32Array::from(vec![Some(0), Some(5), Some(20), None]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![Arc::new(a)])?;
+
+        // 10 / a fails for a = 0, which the left input rules out
+        let ten_div_a_gt_one = binary_op(
+            binary_op(lit(10i32), Operator::Divide, col("a", &schema)?, &schema)?,
+            Operator::Gt,
+            lit(1i32),
+            &schema,
+        )?;
+        assert!(ten_div_a_gt_one.evaluate(&batch).is_err());
+
+        // a > 0 AND 10 / a > 1
+        let and = binary_op(
+            binary_op(col("a", &schema)?, Operator::Gt, lit(0i32), &schema)?,
+            Operator::And,
+            ten_div_a_gt_one.clone(),
+            &schema,
+        )?;
+        let result = and.evaluate(&batch)?.into_array(batch.num_rows())?;
+        let expected =
+            BooleanArray::from(vec![Some(false), Some(true), Some(false), None]);
+        assert_eq!(result.as_ref(), &expected);
+
+        // a = 0 OR 10 / a > 1
+        let or = binary_op(
+            binary_op(col("a", &schema)?, Operator::Eq, lit(0i32), &schema)?,
+            Operator::Or,
+            ten_div_a_gt_one,
+            &schema,
+        )?;
+        let result = or.evaluate(&batch)?.into_array(batch.num_rows())?;
+        let expected =
+            BooleanArray::from(vec![Some(true), Some(true), Some(false), None]);
+        assert_eq!(result.as_ref(), &expected);
+
+        Ok(())
+    }
+
     /// Returns (schema, a: BooleanArray, b: BooleanArray) with all possible inputs
     ///
     /// a: [true, true, true,  NULL, NULL, NULL,  false, false, false]
@@ -134,6 +134,10 @@ impl CaseExpr {
         // We only consider non-null values while comparing with whens
         let mut remainder = not(&base_nulls)?;
         for i in 0..self.when_then_expr.len() {
+            // all rows have been matched
+            if remainder.true_count() == 0 {
+                break;
+            }
             let when_value = self.when_then_expr[i]
                 .0
                 .evaluate_selection(batch, &remainder)?;
@@ -148,6 +152,11 @@ impl CaseExpr {
             // Make sure we only consider rows that have not been matched yet
             let when_match = and(&when_match, &remainder)?;
 
+            // no rows to evaluate `then` for
+            if when_match.true_count() == 0 {
+                continue;
+            }
+
             let then_value = self.when_then_expr[i]
                 .1
                 .evaluate_selection(batch, &when_match)?;
@@ -170,10 +179,12 @@ impl CaseExpr {
                 .unwrap_or_else(|_| e.clone());
             // null and unmatched tuples should be assigned else value
             remainder = or(&base_nulls, &remainder)?;
-            let else_ = expr
-                .evaluate_selection(batch, &remainder)?
-                .into_array(batch.num_rows())?;
-            current_value = zip(&remainder, else_.as_ref(), current_value.as_ref())?;
+            if remainder.true_count() > 0 {
+                let else_ = expr
+                    .evaluate_selection(batch, &remainder)?
+                    .into_array(batch.num_rows())?;
+                current_value = zip(&remainder, else_.as_ref(), current_value.as_ref())?;
+            }
         }
 
         Ok(ColumnarValue::Array(current_value))
@@ -193,6 +204,10 @@ impl CaseExpr {
         let mut current_value = new_null_array(&return_type, batch.num_rows());
         let mut remainder = BooleanArray::from(vec![true; batch.num_rows()]);
         for i in 0..self.when_then_expr.len() {
+            // all rows have been matched
+            if remainder.true_count() == 0 {
+                break;
+            }
             let when_value = self.when_then_expr[i]
                 .0
                 .evaluate_selection(batch, &remainder)?;
@@ -211,6 +226,11 @@ impl CaseExpr {
             // Make sure we only consider rows that have not been matched yet
             let when_value = and(&when_value, &remainder)?;
 
+            // no rows to evaluate `then` for
+            if when_value.true_count() == 0 {
+                continue;
+            }
+
             let then_value = self.when_then_expr[i]
                 .1
                 .evaluate_selection(batch, &when_value)?;
@@ -233,10 +253,12 @@ impl CaseExpr {
             // keep `else_expr`'s data type and return type consistent
             let expr = try_cast(e.clone(), &batch.schema(), return_type.clone())
                 .unwrap_or_else(|_| e.clone());
-            let else_ = expr
-                .evaluate_selection(batch, &remainder)?
-                .into_array(batch.num_rows())?;
-            current_value = zip(&remainder, else_.as_ref(), current_value.as_ref())?;
+            if remainder.true_count() > 0 {
+                let else_ = expr
+                    .evaluate_selection(batch, &remainder)?
+                    .into_array(batch.num_rows())?;
+                current_value = zip(&remainder, else_.as_ref(), current_value.as_ref())?;
+            }
         }
 
         Ok(ColumnarValue::Array(current_value))
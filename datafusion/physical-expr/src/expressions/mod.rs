@@ -44,6 +44,7 @@ pub use crate::aggregate::approx_distinct::ApproxDistinct;
 pub use crate::aggregate::approx_median::ApproxMedian;
 pub use crate::aggregate::approx_percentile_cont::ApproxPercentileCont;
 pub use crate::aggregate::approx_percentile_cont_with_weight::ApproxPercentileContWithWeight;
+pub use crate::aggregate::approx_top_k_sketch::ApproxTopKSketch;
 pub use crate::aggregate::array_agg::ArrayAgg;
 pub use crate::aggregate::array_agg_distinct::DistinctArrayAgg;
 pub use crate::aggregate::array_agg_ordered::OrderSensitiveArrayAgg;
@@ -57,6 +58,8 @@ pub use crate::aggregate::count_distinct::DistinctCount;
 pub use crate::aggregate::covariance::{Covariance, CovariancePop};
 pub use crate::aggregate::first_last::{FirstValue, LastValue};
 pub use crate::aggregate::grouping::Grouping;
+pub use crate::aggregate::hll_sketch::{HllSketch, HllUnion};
+pub use crate::aggregate::kll_sketch::{KllMerge, KllSketch};
 pub use crate::aggregate::median::Median;
 pub use crate::aggregate::min_max::{Max, Min};
 pub use crate::aggregate::min_max::{MaxAccumulator, MinAccumulator};
@@ -102,10 +105,12 @@ pub use crate::PhysicalSortExpr;
 
 #[cfg(test)]
 pub(crate) mod tests {
+    use crate::aggregate::utils::get_accum_scalar_values_as_arrays;
     use crate::expressions::{col, create_aggregate_expr, try_cast};
     use crate::{AggregateExpr, EmitTo};
+    use arrow::compute::{concat, filter_record_batch};
     use arrow::record_batch::RecordBatch;
-    use arrow_array::ArrayRef;
+    use arrow_array::{ArrayRef, BooleanArray};
     use arrow_schema::{Field, Schema};
     use datafusion_common::Result;
     use datafusion_common::ScalarValue;
@@ -274,4 +279,91 @@ pub(crate) mod tests {
         accum.update_batch(&values, &indices, None, 1)?;
         accum.evaluate(EmitTo::All)
     }
+
+    /// Evaluates the arguments of `agg` (including its ordering
+    /// requirement, if any) against `batch`
+    fn aggregate_arguments(
+        batch: &RecordBatch,
+        agg: &Arc<dyn AggregateExpr>,
+    ) -> Result<Vec<ArrayRef>> {
+        let mut exprs = agg.expressions();
+        if let Some(ordering_req) = agg.order_bys() {
+            exprs.extend(ordering_req.iter().map(|item| item.expr.clone()));
+        }
+        exprs
+            .iter()
+            .map(|e| {
+                e.evaluate(batch)
+                    .and_then(|v| v.into_array(batch.num_rows()))
+            })
+            .collect()
+    }
+
+    /// Computes `agg` for every group in `group_indices` with both its
+    /// [`datafusion_expr::Accumulator`] and its [`crate::GroupsAccumulator`],
+    /// and checks that the results agree.
+    ///
+    /// The input is split in two partial aggregations that are merged
+    /// afterwards, so `state` and `merge_batch` are exercised as well
+    /// as emitting the groups in two steps.
+    pub fn assert_groups_accumulator(
+        batch: &RecordBatch,
+        agg: Arc<dyn AggregateExpr>,
+        group_indices: &[usize],
+    ) -> Result<()> {
+        assert!(agg.groups_accumulator_supported());
+        assert_eq!(batch.num_rows(), group_indices.len());
+        let total_num_groups = group_indices.iter().max().map_or(0, |g| g + 1);
+        let mid = batch.num_rows() / 2;
+        let partitions = [
+            (batch.slice(0, mid), &group_indices[..mid]),
+            (
+                batch.slice(mid, batch.num_rows() - mid),
+                &group_indices[mid..],
+            ),
+        ];
+
+        let mut groups_accum = agg.create_groups_accumulator()?;
+        let all_groups: Vec<usize> = (0..total_num_groups).collect();
+        for (batch, group_indices) in &partitions {
+            let mut partial = agg.create_groups_accumulator()?;
+            partial.update_batch(
+                &aggregate_arguments(batch, &agg)?,
+                group_indices,
+                None,
+                total_num_groups,
+            )?;
+            let state = partial.state(EmitTo::All)?;
+            groups_accum.merge_batch(&state, &all_groups, None, total_num_groups)?;
+        }
+        let actual = if total_num_groups > 1 {
+            let first = groups_accum.evaluate(EmitTo::First(1))?;
+            let rest = groups_accum.evaluate(EmitTo::All)?;
+            concat(&[first.as_ref(), rest.as_ref()])?
+        } else {
+            groups_accum.evaluate(EmitTo::All)?
+        };
+        assert_eq!(actual.len(), total_num_groups);
+
+        for group_index in 0..total_num_groups {
+            let mut accum = agg.create_accumulator()?;
+            for (batch, group_indices) in &partitions {
+                let mask: BooleanArray = group_indices
+                    .iter()
+                    .map(|g| Some(*g == group_index))
+                    .collect();
+                let batch = filter_record_batch(batch, &mask)?;
+                let mut partial = agg.create_accumulator()?;
+                partial.update_batch(&aggregate_arguments(&batch, &agg)?)?;
+                accum
+                    .merge_batch(&get_accum_scalar_values_as_arrays(partial.as_ref())?)?;
+            }
+            assert_eq!(
+                ScalarValue::try_from_array(&actual, group_index)?,
+                accum.evaluate()?,
+                "mismatch for group {group_index}"
+            );
+        }
+        Ok(())
+    }
 }
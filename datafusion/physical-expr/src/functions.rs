@@ -34,8 +34,8 @@ use crate::execution_props::ExecutionProps;
 use crate::sort_properties::SortProperties;
 use crate::{
     array_expressions, conditional_expressions, datetime_expressions,
-    expressions::nullif_func, math_expressions, string_expressions, struct_expressions,
-    PhysicalExpr, ScalarFunctionExpr,
+    expressions::nullif_func, math_expressions, sketch_expressions, string_expressions,
+    struct_expressions, PhysicalExpr, ScalarFunctionExpr,
 };
 use arrow::{
     array::ArrayRef,
@@ -912,6 +912,17 @@ pub fn create_physical_fun(
                 "Unsupported data type {other:?} for function find_in_set",
             ))),
         }),
+
+        // sketch functions
+        BuiltinScalarFunction::HllEstimate => {
+            Arc::new(|args| make_scalar_function(sketch_expressions::hll_estimate)(args))
+        }
+        BuiltinScalarFunction::KllQuantile => {
+            Arc::new(|args| make_scalar_function(sketch_expressions::kll_quantile)(args))
+        }
+        BuiltinScalarFunction::TopKItems => {
+            Arc::new(|args| make_scalar_function(sketch_expressions::top_k_items)(args))
+        }
     })
 }
 
@@ -36,6 +36,7 @@ pub mod planner;
 #[cfg(feature = "regex_expressions")]
 pub mod regex_expressions;
 mod scalar_function;
+pub mod sketch_expressions;
 mod sort_expr;
 pub mod sort_properties;
 pub mod string_expressions;
@@ -20,7 +20,9 @@
 use std::fmt;
 use std::sync::Arc;
 
-use crate::{physical_exprs_equal, EquivalenceProperties, PhysicalExpr};
+use crate::{
+    physical_exprs_equal, EquivalenceProperties, PhysicalExpr, PhysicalSortExpr,
+};
 
 /// Output partitioning supported by [`ExecutionPlan`]s.
 ///
@@ -111,6 +113,12 @@ pub enum Partitioning {
     /// Allocate rows based on a hash of one of more expressions and the specified number of
     /// partitions
     Hash(Vec<Arc<dyn PhysicalExpr>>, usize),
+    /// Allocate rows based on ranges of the values of the sort expressions and
+    /// the specified number of partitions. All rows of a partition order before
+    /// the rows of the next partition, so the concatenation of the partitions,
+    /// each sorted by the same expressions, is sorted. The split points between
+    /// the ranges are chosen by sampling the input when executing.
+    Range(Vec<PhysicalSortExpr>, usize),
     /// Unknown partitioning scheme with a known number of partitions
     UnknownPartitioning(usize),
 }
@@ -127,6 +135,14 @@ impl fmt::Display for Partitioning {
                     .join(", ");
                 write!(f, "Hash([{phy_exprs_str}], {size})")
             }
+            Partitioning::Range(sort_exprs, size) => {
+                let sort_exprs_str = sort_exprs
+                    .iter()
+                    .map(|e| format!("{e}"))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "Range([{sort_exprs_str}], {size})")
+            }
             Partitioning::UnknownPartitioning(size) => {
                 write!(f, "UnknownPartitioning({size})")
             }
@@ -138,7 +154,7 @@ impl Partitioning {
     pub fn partition_count(&self) -> usize {
         use Partitioning::*;
         match self {
-            RoundRobinBatch(n) | Hash(_, n) | UnknownPartitioning(n) => *n,
+            RoundRobinBatch(n) | Hash(_, n) | Range(_, n) | UnknownPartitioning(n) => *n,
         }
     }
 
@@ -154,6 +170,10 @@ impl Partitioning {
             Distribution::SinglePartition if self.partition_count() == 1 => true,
             Distribution::HashPartitioned(required_exprs) => {
                 match self {
+                    // Range partitioning also keeps equal values together, but its split points depend
+                    // on the sampled input, so it is not co-partitioned with other inputs and does not
+                    // satisfy the hash distribution.
+                    //
                     // Here we do not check the partition count for hash partitioning and assumes the partition count
                     // and hash functions in the system are the same. In future if we plan to support storage partition-wise joins,
                     // then we need to have the partition count and hash functions validation.
@@ -202,6 +222,10 @@ impl PartialEq for Partitioning {
             {
                 true
             }
+            (
+                Partitioning::Range(exprs1, count1),
+                Partitioning::Range(exprs2, count2),
+            ) if exprs1 == exprs2 && count1 == count2 => true,
             _ => false,
         }
     }
@@ -308,4 +332,38 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn range_partitioning() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "column_1",
+            DataType::Int64,
+            false,
+        )]));
+        let column: Arc<dyn PhysicalExpr> =
+            Arc::new(Column::new_with_schema("column_1", &schema)?);
+        let sort_exprs = vec![PhysicalSortExpr {
+            expr: column.clone(),
+            options: Default::default(),
+        }];
+        let range_partition = Partitioning::Range(sort_exprs.clone(), 4);
+
+        assert_eq!(range_partition.partition_count(), 4);
+        assert_eq!(range_partition.to_string(), "Range([column_1@0 ASC], 4)");
+        assert_eq!(range_partition, Partitioning::Range(sort_exprs.clone(), 4));
+        assert_ne!(range_partition, Partitioning::Range(sort_exprs, 2));
+        assert_ne!(range_partition, Partitioning::Hash(vec![column.clone()], 4));
+
+        // the split points of different inputs do not match
+        assert!(!range_partition
+            .satisfy(Distribution::HashPartitioned(vec![column]), || {
+                EquivalenceProperties::new(schema.clone())
+            }));
+        assert!(
+            range_partition.satisfy(Distribution::UnspecifiedDistribution, || {
+                EquivalenceProperties::new(schema.clone())
+            })
+        );
+        Ok(())
+    }
 }
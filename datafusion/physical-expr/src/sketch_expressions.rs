@@ -0,0 +1,231 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Sketch expressions, estimating results from the sketches created by the
+//! `hll_sketch`, `kll_sketch` and `approx_top_k_sketch` aggregates
+
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, Float64Array, ListArray, StringBuilder, StructArray, UInt64Array,
+    UInt64Builder,
+};
+use arrow::datatypes::{DataType, Field, Fields};
+use arrow_buffer::{NullBuffer, OffsetBuffer};
+use datafusion_common::cast::{as_binary_array, as_float64_array, as_int64_array};
+use datafusion_common::{exec_err, DataFusionError, Result};
+
+use crate::aggregate::hyperloglog::{count_registers, sketch_registers};
+use crate::aggregate::kll::Kll;
+use crate::aggregate::space_saving::SpaceSaving;
+
+/// Returns the estimated number of distinct values of HLL sketches
+///
+/// hll_estimate(hll_sketch(x)) returns the same result as approx_distinct(x)
+pub fn hll_estimate(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let sketches = as_binary_array(&args[0])?;
+    let estimates = sketches
+        .iter()
+        .map(|sketch| {
+            sketch
+                .map(|sketch| Ok(count_registers(sketch_registers(sketch)?) as u64))
+                .transpose()
+        })
+        .collect::<Result<UInt64Array>>()?;
+    Ok(Arc::new(estimates))
+}
+
+/// Returns the estimated quantiles of the values of KLL sketches
+///
+/// kll_quantile(sketch, 0.5) returns the approximate median
+pub fn kll_quantile(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let sketches = as_binary_array(&args[0])?;
+    let quantiles = as_float64_array(&args[1])?;
+    let estimates = sketches
+        .iter()
+        .zip(quantiles.iter())
+        .map(|(sketch, quantile)| match (sketch, quantile) {
+            (Some(sketch), Some(quantile)) => {
+                if !(0.0..=1.0).contains(&quantile) {
+                    return exec_err!(
+                        "Quantile value must be between 0.0 and 1.0 inclusive, {quantile} is invalid"
+                    );
+                }
+                Ok(Kll::from_bytes(sketch)?.quantile(quantile))
+            }
+            _ => Ok(None),
+        })
+        .collect::<Result<Float64Array>>()?;
+    Ok(Arc::new(estimates))
+}
+
+/// Returns the most frequent items of top-k sketches, as a list of
+/// `{item, count, error}` structs by descending count. The count of an item
+/// overestimates its frequency by at most error.
+///
+/// top_k_items(sketch, 3) returns the 3 most frequent items, all tracked
+/// items are returned without the second argument.
+pub fn top_k_items(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let sketches = as_binary_array(&args[0])?;
+    let ks = args.get(1).map(|k| as_int64_array(k)).transpose()?;
+
+    let mut items = StringBuilder::new();
+    let mut counts = UInt64Builder::new();
+    let mut errors = UInt64Builder::new();
+    let mut lengths = Vec::with_capacity(sketches.len());
+    let mut validity = Vec::with_capacity(sketches.len());
+    for (row, sketch) in sketches.iter().enumerate() {
+        let k = match ks {
+            Some(ks) if ks.is_null(row) => None,
+            Some(ks) if ks.value(row) < 0 => {
+                return exec_err!(
+                    "The number of items for top_k_items must not be negative, got {}",
+                    ks.value(row)
+                );
+            }
+            Some(ks) => Some(ks.value(row) as usize),
+            None => Some(usize::MAX),
+        };
+        let (Some(sketch), Some(k)) = (sketch, k) else {
+            lengths.push(0);
+            validity.push(false);
+            continue;
+        };
+
+        let sketch = SpaceSaving::from_bytes(sketch)?;
+        let top_k = sketch.top_k(k);
+        for (item, counter) in &top_k {
+            items.append_value(item);
+            counts.append_value(counter.count);
+            errors.append_value(counter.error);
+        }
+        lengths.push(top_k.len());
+        validity.push(true);
+    }
+
+    let fields = Fields::from(vec![
+        Field::new("item", DataType::Utf8, true),
+        Field::new("count", DataType::UInt64, true),
+        Field::new("error", DataType::UInt64, true),
+    ]);
+    let values = StructArray::new(
+        fields.clone(),
+        vec![
+            Arc::new(items.finish()),
+            Arc::new(counts.finish()),
+            Arc::new(errors.finish()),
+        ],
+        None,
+    );
+    let list = ListArray::try_new(
+        Arc::new(Field::new("item", DataType::Struct(fields), true)),
+        OffsetBuffer::from_lengths(lengths),
+        Arc::new(values),
+        Some(NullBuffer::from(validity)),
+    )?;
+    Ok(Arc::new(list))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregate::hyperloglog::registers_to_sketch;
+    use crate::aggregate::hyperloglog::HyperLogLog;
+    use arrow::array::{AsArray, BinaryArray, Int64Array, StringArray};
+    use arrow::datatypes::UInt64Type;
+
+    #[test]
+    fn test_hll_estimate() -> Result<()> {
+        let mut hll = HyperLogLog::<u64>::new();
+        hll.extend(0..100);
+        let sketch = registers_to_sketch(hll.as_ref());
+        let sketches: ArrayRef =
+            Arc::new(BinaryArray::from(vec![Some(sketch.as_slice()), None]));
+
+        let result = hll_estimate(&[sketches])?;
+        let result = result.as_any().downcast_ref::<UInt64Array>().unwrap();
+        let expected = hll.count() as u64;
+        assert_eq!(result, &UInt64Array::from(vec![Some(expected), None]));
+
+        let invalid: ArrayRef = Arc::new(BinaryArray::from(vec![Some(b"abc".as_ref())]));
+        let err = hll_estimate(&[invalid]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unsupported HLL sketch version 97"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_kll_quantile() -> Result<()> {
+        let mut kll = Kll::new(200);
+        for v in 1..=5 {
+            kll.update(v as f64);
+        }
+        let sketch = kll.to_bytes();
+        let sketches: ArrayRef = Arc::new(BinaryArray::from(vec![
+            Some(sketch.as_slice()),
+            Some(sketch.as_slice()),
+            None,
+        ]));
+        let quantiles: ArrayRef =
+            Arc::new(Float64Array::from(vec![Some(0.5), None, Some(0.5)]));
+
+        let result = kll_quantile(&[sketches.clone(), quantiles])?;
+        let result = result.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(result, &Float64Array::from(vec![Some(3.0), None, None]));
+
+        let quantiles: ArrayRef = Arc::new(Float64Array::from(vec![1.5, 0.5, 0.5]));
+        let err = kll_quantile(&[sketches, quantiles]).unwrap_err();
+        assert!(err.to_string().contains("1.5 is invalid"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_k_items() -> Result<()> {
+        let mut top_k = SpaceSaving::new(10);
+        for item in ["a", "b", "a", "c", "a", "b"] {
+            top_k.update(item);
+        }
+        let sketch = top_k.to_bytes();
+        let sketches: ArrayRef = Arc::new(BinaryArray::from(vec![
+            Some(sketch.as_slice()),
+            None,
+            Some(sketch.as_slice()),
+        ]));
+        let ks: ArrayRef = Arc::new(Int64Array::from(vec![Some(2), Some(2), None]));
+
+        let result = top_k_items(&[sketches.clone(), ks])?;
+        let result = result.as_list::<i32>();
+        assert_eq!(result.len(), 3);
+        assert!(result.is_valid(0) && result.is_null(1) && result.is_null(2));
+        let items = result.value(0);
+        let items = items.as_struct();
+        assert_eq!(
+            items.column(0).as_string::<i32>(),
+            &StringArray::from(vec!["a", "b"])
+        );
+        assert_eq!(
+            items.column(1).as_primitive::<UInt64Type>(),
+            &UInt64Array::from(vec![3, 2])
+        );
+
+        // all items without k
+        let result = top_k_items(&[sketches])?;
+        assert_eq!(result.as_list::<i32>().value(0).len(), 3);
+        Ok(())
+    }
+}
@@ -2013,7 +2013,13 @@ mod tests {
             schema,
         )?) as Arc<dyn ExecutionPlan>;
 
-        let result = crate::collect(aggregate_final, task_ctx).await?;
+        let result = crate::collect(aggregate_final.clone(), task_ctx).await?;
+        // With coalesced input the final aggregation receives a single batch,
+        // so there is nothing buffered to spill before it
+        if spill && !use_coalesce_batches {
+            let spill_count = aggregate_final.metrics().unwrap().spill_count();
+            assert!(spill_count.unwrap() > 0);
+        }
         if is_first_acc {
             let expected = [
                 "+---+----------------+",
@@ -28,7 +28,7 @@ use crate::aggregates::{
     PhysicalGroupBy,
 };
 use crate::common::IPCWriter;
-use crate::metrics::{BaselineMetrics, RecordOutput};
+use crate::metrics::{BaselineMetrics, Count, MetricBuilder, RecordOutput};
 use crate::sorts::sort::{read_spill_as_stream, sort_batch};
 use crate::sorts::streaming_merge;
 use crate::stream::RecordBatchStreamAdapter;
@@ -86,6 +86,12 @@ struct SpillState {
 
     /// GROUP BY expressions for merging spilled data
     merging_group_by: PhysicalGroupBy,
+
+    /// Number of times data was spilled
+    spill_count: Count,
+
+    /// Number of bytes spilled to disk
+    spilled_bytes: Count,
 }
 
 /// HashTable based Grouping Aggregator
@@ -360,6 +366,8 @@ impl GroupedHashAggregateStream {
             is_stream_merging: false,
             merging_aggregate_arguments,
             merging_group_by: PhysicalGroupBy::new_single(agg_group_by.expr.clone()),
+            spill_count: MetricBuilder::new(&agg.metrics).spill_count(partition),
+            spilled_bytes: MetricBuilder::new(&agg.metrics).spilled_bytes(partition),
         };
 
         Ok(GroupedHashAggregateStream {
@@ -699,6 +707,10 @@ impl GroupedHashAggregateStream {
 
         writer.finish()?;
         self.spill_state.spills.push(spillfile);
+        self.spill_state.spill_count.add(1);
+        self.spill_state
+            .spilled_bytes
+            .add(writer.num_bytes as usize);
         Ok(())
     }
 
@@ -0,0 +1,449 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the [`AsyncFuncExec`] operator, which evaluates calls to
+//! [`AsyncScalarUDF`]s
+
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use datafusion_common::stats::Precision;
+use datafusion_common::{ColumnStatistics, Result, Statistics};
+use datafusion_execution::TaskContext;
+use datafusion_expr::{AsyncScalarUDF, ColumnarValue};
+use datafusion_physical_expr::{EquivalenceProperties, PhysicalExpr, PhysicalSortExpr};
+use futures::future::try_join_all;
+use futures::StreamExt;
+use itertools::Itertools;
+
+use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use crate::stream::RecordBatchStreamAdapter;
+use crate::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+};
+
+/// A call to an [`AsyncScalarUDF`] evaluated by an [`AsyncFuncExec`]
+#[derive(Debug, Clone)]
+pub struct AsyncFuncExpr {
+    /// The name of the output column
+    name: String,
+    func: Arc<dyn AsyncScalarUDF>,
+    args: Vec<Arc<dyn PhysicalExpr>>,
+    return_type: DataType,
+}
+
+impl AsyncFuncExpr {
+    /// Create a new call to `func` with `args`, whose result is the column
+    /// `name`
+    pub fn new(
+        name: impl Into<String>,
+        func: Arc<dyn AsyncScalarUDF>,
+        args: Vec<Arc<dyn PhysicalExpr>>,
+        return_type: DataType,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            func,
+            args,
+            return_type,
+        }
+    }
+
+    /// The name of the output column
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The function called
+    pub fn func(&self) -> &Arc<dyn AsyncScalarUDF> {
+        &self.func
+    }
+
+    /// The arguments of the call
+    pub fn args(&self) -> &[Arc<dyn PhysicalExpr>] {
+        &self.args
+    }
+
+    /// The type of the output column
+    pub fn return_type(&self) -> &DataType {
+        &self.return_type
+    }
+
+    /// Evaluates the call on `batch`
+    async fn evaluate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| arg.evaluate(batch))
+            .collect::<Result<Vec<_>>>()?;
+        self.func.invoke_async(&args, batch.num_rows()).await
+    }
+}
+
+impl fmt::Display for AsyncFuncExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let args = self.args.iter().map(|arg| arg.to_string()).join(", ");
+        write!(f, "{}({args}) as {}", self.func.name(), self.name)
+    }
+}
+
+/// Evaluates calls to [`AsyncScalarUDF`]s, appending their results to the
+/// columns of its input.
+///
+/// The calls for several batches are in flight at the same time, up to the
+/// smallest [`AsyncScalarUDF::max_concurrency`] of the functions, and the
+/// batches are returned in the order of the input.
+#[derive(Debug)]
+pub struct AsyncFuncExec {
+    input: Arc<dyn ExecutionPlan>,
+    async_exprs: Vec<AsyncFuncExpr>,
+    schema: SchemaRef,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl AsyncFuncExec {
+    /// Create a new [`AsyncFuncExec`] evaluating `async_exprs` on the
+    /// batches of `input`
+    pub fn try_new(
+        async_exprs: Vec<AsyncFuncExpr>,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Self> {
+        let input_schema = input.schema();
+        let fields = input_schema
+            .fields()
+            .iter()
+            .cloned()
+            .chain(async_exprs.iter().map(|expr| {
+                Arc::new(Field::new(expr.name(), expr.return_type().clone(), true))
+            }))
+            .collect::<Vec<_>>();
+        let schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            input_schema.metadata().clone(),
+        ));
+        Ok(Self {
+            input,
+            async_exprs,
+            schema,
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// The calls evaluated by this operator
+    pub fn async_exprs(&self) -> &[AsyncFuncExpr] {
+        &self.async_exprs
+    }
+
+    /// Returns the number of batches whose calls are evaluated at the same
+    /// time
+    pub fn concurrency(&self) -> usize {
+        self.async_exprs
+            .iter()
+            .map(|expr| expr.func().max_concurrency())
+            .min()
+            .unwrap_or(1)
+            .max(1)
+    }
+}
+
+impl DisplayAs for AsyncFuncExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let exprs = self.async_exprs.iter().map(|e| e.to_string()).join(", ");
+                write!(f, "AsyncFuncExec: async_expr=[{exprs}]")
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for AsyncFuncExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![Arc::clone(&self.input)]
+    }
+
+    /// The columns of the input keep their indices, so the partitioning and
+    /// ordering of the input are preserved
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn unbounded_output(&self, children: &[bool]) -> Result<bool> {
+        Ok(children[0])
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        EquivalenceProperties::new(self.schema())
+            .extend(self.input.equivalence_properties())
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self::try_new(
+            self.async_exprs.clone(),
+            Arc::clone(&children[0]),
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let input = self.input.execute(partition, context)?;
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let schema = self.schema();
+        let async_exprs = Arc::new(self.async_exprs.clone());
+
+        let stream = {
+            let schema = Arc::clone(&schema);
+            input
+                .map(move |batch| {
+                    let schema = Arc::clone(&schema);
+                    let async_exprs = Arc::clone(&async_exprs);
+                    async move {
+                        let batch = batch?;
+                        let results =
+                            try_join_all(async_exprs.iter().map(|e| e.evaluate(&batch)))
+                                .await?;
+                        let mut columns = batch.columns().to_vec();
+                        for result in results {
+                            columns.push(result.into_array(batch.num_rows())?);
+                        }
+                        Ok(RecordBatch::try_new(schema, columns)?)
+                    }
+                })
+                .buffered(self.concurrency())
+                .map(move |batch: Result<RecordBatch>| {
+                    if let Ok(batch) = &batch {
+                        baseline_metrics.record_output(batch.num_rows());
+                    }
+                    batch
+                })
+        };
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        let mut statistics = self.input.statistics()?;
+        statistics.column_statistics.extend(
+            self.async_exprs
+                .iter()
+                .map(|_| ColumnStatistics::new_unknown()),
+        );
+        statistics.total_byte_size = Precision::Absent;
+        Ok(statistics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use async_trait::async_trait;
+    use datafusion_common::{exec_err, DataFusionError, ScalarValue};
+    use datafusion_expr::{Signature, Volatility};
+    use datafusion_physical_expr::expressions::col;
+
+    use super::*;
+    use crate::common::collect;
+    use crate::memory::MemoryExec;
+
+    /// Formats its argument, counting the calls in flight
+    #[derive(Debug)]
+    struct Describe {
+        signature: Signature,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    impl Describe {
+        fn new() -> Self {
+            Self {
+                signature: Signature::exact(vec![DataType::Int64], Volatility::Volatile),
+                in_flight: AtomicUsize::new(0),
+                max_in_flight: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl AsyncScalarUDF for Describe {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn name(&self) -> &str {
+            "describe"
+        }
+
+        fn signature(&self) -> &Signature {
+            &self.signature
+        }
+
+        fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+            Ok(DataType::Utf8)
+        }
+
+        async fn invoke_async(
+            &self,
+            args: &[ColumnarValue],
+            num_rows: usize,
+        ) -> Result<ColumnarValue> {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            // let the calls for the other batches start
+            for _ in 0..4 {
+                tokio::task::yield_now().await;
+            }
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            let values = args[0].clone().into_array(num_rows)?;
+            let values = values.as_any().downcast_ref::<Int64Array>().unwrap();
+            if values.iter().any(|v| v == Some(-1)) {
+                return exec_err!("invalid key -1");
+            }
+            let described: StringArray =
+                values.iter().map(|v| v.map(|v| format!("#{v}"))).collect();
+            Ok(ColumnarValue::Array(Arc::new(described)))
+        }
+
+        fn max_concurrency(&self) -> usize {
+            2
+        }
+    }
+
+    fn input(values: Vec<Vec<i64>>) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
+        let batches = values
+            .into_iter()
+            .map(|values| {
+                RecordBatch::try_new(
+                    Arc::clone(&schema),
+                    vec![Arc::new(Int64Array::from(values))],
+                )
+                .map_err(Into::into)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Arc::new(MemoryExec::try_new(&[batches], schema, None)?))
+    }
+
+    #[tokio::test]
+    async fn evaluates_calls_in_order() -> Result<()> {
+        let input = input((0..6).map(|i| vec![i * 10, i * 10 + 1]).collect())?;
+        let func = Arc::new(Describe::new());
+        let exec = AsyncFuncExec::try_new(
+            vec![AsyncFuncExpr::new(
+                "described",
+                Arc::clone(&func) as _,
+                vec![col("a", &input.schema())?],
+                DataType::Utf8,
+            )],
+            input,
+        )?;
+        assert_eq!(exec.concurrency(), 2);
+        assert_eq!(exec.schema().field(1).name(), "described");
+
+        let batches = collect(exec.execute(0, Arc::new(TaskContext::default()))?).await?;
+        let described: Vec<_> = batches
+            .iter()
+            .flat_map(|batch| {
+                let column = batch.column(1).as_any().downcast_ref::<StringArray>();
+                column.unwrap().iter().map(|v| v.unwrap().to_string())
+            })
+            .collect();
+        let expected: Vec<_> = (0..6)
+            .flat_map(|i| [format!("#{}", i * 10), format!("#{}", i * 10 + 1)])
+            .collect();
+        assert_eq!(described, expected);
+
+        // the calls for the batches overlapped, up to the concurrency limit
+        assert_eq!(func.max_in_flight.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn scalar_arguments_and_errors() -> Result<()> {
+        let input = input(vec![vec![1, 2], vec![3, -1]])?;
+        let func: Arc<dyn AsyncScalarUDF> = Arc::new(Describe::new());
+        let literal =
+            datafusion_physical_expr::expressions::lit(ScalarValue::Int64(Some(7)));
+        let exec = AsyncFuncExec::try_new(
+            vec![
+                AsyncFuncExpr::new("x", Arc::clone(&func), vec![literal], DataType::Utf8),
+                AsyncFuncExpr::new(
+                    "y",
+                    func,
+                    vec![col("a", &input.schema())?],
+                    DataType::Utf8,
+                ),
+            ],
+            input,
+        )?;
+        assert_eq!(
+            crate::displayable(&exec).one_line().to_string(),
+            "AsyncFuncExec: async_expr=[describe(7) as x, describe(a@0) as y]\n"
+        );
+
+        let mut stream = exec.execute(0, Arc::new(TaskContext::default()))?;
+        let batch = stream.next().await.unwrap()?;
+        assert_eq!(batch.num_columns(), 3);
+        let x = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(x.value(0), "#7");
+        assert_eq!(x.value(1), "#7");
+
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("invalid key -1"), "{err}");
+        Ok(())
+    }
+}
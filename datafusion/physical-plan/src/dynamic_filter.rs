@@ -0,0 +1,156 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`DynamicFilter`]: a predicate that is tightened during execution
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{internal_err, DataFusionError, Result};
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_expr::PhysicalExpr;
+use parking_lot::RwLock;
+
+/// A predicate that an operator publishes, and tightens, while it executes,
+/// such as the threshold of the top K rows seen so far by a `SortExec` with
+/// a fetch. Scans below the operator use the current predicate to skip
+/// data that cannot be part of the result.
+///
+/// Clones share the same predicate. The predicate refers to the columns of
+/// the operator publishing it, and [`Self::with_columns`] returns a handle
+/// that refers to the same columns in the schema of another operator.
+#[derive(Debug, Clone)]
+pub struct DynamicFilter {
+    inner: Arc<DynamicFilterInner>,
+    /// The columns of the predicate, as seen by this handle
+    columns: Vec<Column>,
+}
+
+#[derive(Debug)]
+struct DynamicFilterInner {
+    /// The columns the published predicate refers to
+    columns: Vec<Column>,
+    predicate: RwLock<Option<Arc<dyn PhysicalExpr>>>,
+    /// Incremented each time the predicate is updated
+    generation: AtomicU64,
+}
+
+impl DynamicFilter {
+    /// Create a new [`DynamicFilter`] whose predicates refer to `columns`
+    pub fn new(columns: Vec<Column>) -> Self {
+        Self {
+            inner: Arc::new(DynamicFilterInner {
+                columns: columns.clone(),
+                predicate: RwLock::new(None),
+                generation: AtomicU64::new(0),
+            }),
+            columns,
+        }
+    }
+
+    /// The columns of the predicate, as seen by this handle
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Returns a handle to the same predicate, with [`Self::columns`]
+    /// replaced by `columns`
+    pub fn with_columns(&self, columns: Vec<Column>) -> Result<Self> {
+        if columns.len() != self.columns.len() {
+            return internal_err!(
+                "DynamicFilter has {} columns, got {}",
+                self.columns.len(),
+                columns.len()
+            );
+        }
+        Ok(Self {
+            inner: Arc::clone(&self.inner),
+            columns,
+        })
+    }
+
+    /// Replace the predicate. `predicate` must only refer to the columns
+    /// this filter was created with, and must be at least as selective as
+    /// the predicate it replaces.
+    pub fn update(&self, predicate: Arc<dyn PhysicalExpr>) {
+        *self.inner.predicate.write() = Some(predicate);
+        self.inner.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Returns a number that changes each time the predicate is updated
+    pub fn generation(&self) -> u64 {
+        self.inner.generation.load(Ordering::SeqCst)
+    }
+
+    /// Returns the current predicate, if any, referring to [`Self::columns`]
+    pub fn current(&self) -> Result<Option<Arc<dyn PhysicalExpr>>> {
+        let Some(predicate) = self.inner.predicate.read().clone() else {
+            return Ok(None);
+        };
+        if self.columns == self.inner.columns {
+            return Ok(Some(predicate));
+        }
+        let predicate = predicate.transform_up(&|expr| {
+            let Some(column) = expr.as_any().downcast_ref::<Column>() else {
+                return Ok(Transformed::No(expr));
+            };
+            match self.inner.columns.iter().position(|c| c == column) {
+                Some(i) => Ok(Transformed::Yes(Arc::new(self.columns[i].clone()))),
+                None => Ok(Transformed::No(expr)),
+            }
+        })?;
+        Ok(Some(predicate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion_common::ScalarValue;
+    use datafusion_expr::Operator;
+    use datafusion_physical_expr::expressions::{binary, col, lit};
+
+    use arrow_schema::{DataType, Field, Schema};
+
+    #[test]
+    fn test_dynamic_filter() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        let filter = DynamicFilter::new(vec![Column::new("b", 1)]);
+        let remapped = filter.with_columns(vec![Column::new("b", 0)])?;
+        assert!(filter.with_columns(vec![]).is_err());
+
+        assert!(remapped.current()?.is_none());
+        assert_eq!(remapped.generation(), 0);
+
+        let predicate = binary(
+            col("b", &schema)?,
+            Operator::Lt,
+            lit(ScalarValue::Int32(Some(10))),
+            &schema,
+        )?;
+        filter.update(predicate);
+
+        assert_eq!(remapped.generation(), 1);
+        assert_eq!(filter.current()?.unwrap().to_string(), "b@1 < 10");
+        assert_eq!(remapped.current()?.unwrap().to_string(), "b@0 < 10");
+        Ok(())
+    }
+}
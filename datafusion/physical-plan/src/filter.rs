@@ -109,6 +109,12 @@ impl FilterExec {
     pub fn default_selectivity(&self) -> u8 {
         self.default_selectivity
     }
+
+    /// The metrics of `partition` of this filter, for operators that
+    /// evaluate the predicate themselves
+    pub(crate) fn baseline_metrics(&self, partition: usize) -> BaselineMetrics {
+        BaselineMetrics::new(&self.metrics, partition)
+    }
 }
 
 impl DisplayAs for FilterExec {
@@ -0,0 +1,251 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`InstrumentedExec`] emits [`tracing`] spans for the execution of an
+//! [`ExecutionPlan`]
+
+use std::any::Any;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{Result, Statistics};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::{
+    EquivalenceProperties, PhysicalSortExpr, PhysicalSortRequirement,
+};
+use futures::{Stream, StreamExt};
+use tracing::field::Empty;
+use tracing::Span;
+
+use crate::metrics::MetricsSet;
+use crate::{
+    displayable, DisplayAs, DisplayFormatType, Distribution, ExecutionPlan,
+    ExecutionProgress, Partitioning, RecordBatchStream, SendableRecordBatchStream,
+};
+
+/// Wraps every operator of `plan` in an [`InstrumentedExec`] if the current
+/// subscriber records `execute` spans. Otherwise `plan` is returned as is,
+/// so that its operators can still be downcast.
+pub fn instrument_plan(plan: Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+    if !tracing::span_enabled!(tracing::Level::INFO) {
+        return Ok(plan);
+    }
+    plan.transform_up(&|plan| {
+        Ok(Transformed::Yes(Arc::new(InstrumentedExec::new(plan)) as _))
+    })
+}
+
+/// Emits an `execute` [`tracing`] span for each partition of the wrapped
+/// operator that is executed.
+///
+/// The span carries the name of the operator and the partition, and when
+/// the stream of the partition is dropped it records the number of
+/// batches and rows it produced and the time spent polling it, including
+/// the time spent polling its inputs. The span is entered during each
+/// poll, so the spans of the inputs are nested in it.
+///
+/// All methods except [`ExecutionPlan::as_any`], including the display, are
+/// delegated to the wrapped operator, so that plans can be inspected and
+/// displayed as if they were not instrumented. Use [`Self::inner`] to
+/// downcast the wrapped operator. Plans are only instrumented by
+/// [`instrument_plan`] while a subscriber is interested in the spans.
+#[derive(Debug)]
+pub struct InstrumentedExec {
+    inner: Arc<dyn ExecutionPlan>,
+    /// The name of the operator, such as `ProjectionExec`
+    name: String,
+}
+
+impl InstrumentedExec {
+    /// Create a new [`InstrumentedExec`] wrapping `inner`
+    pub fn new(inner: Arc<dyn ExecutionPlan>) -> Self {
+        let line = displayable(inner.as_ref()).one_line().to_string();
+        let name = line
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        Self { inner, name }
+    }
+
+    /// Returns the wrapped operator
+    pub fn inner(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.inner
+    }
+}
+
+impl DisplayAs for InstrumentedExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.fmt_as(t, f)
+    }
+}
+
+impl ExecutionPlan for InstrumentedExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.inner.output_partitioning()
+    }
+
+    fn unbounded_output(&self, children: &[bool]) -> Result<bool> {
+        self.inner.unbounded_output(children)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.inner.output_ordering()
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        self.inner.required_input_distribution()
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortRequirement>>> {
+        self.inner.required_input_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        self.inner.maintains_input_order()
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        self.inner.benefits_from_input_partitioning()
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        self.inner.equivalence_properties()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        self.inner.children()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let inner = Arc::clone(&self.inner).with_new_children(children)?;
+        Ok(Arc::new(Self::new(inner)))
+    }
+
+    fn repartitioned(
+        &self,
+        target_partitions: usize,
+        config: &ConfigOptions,
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        Ok(self
+            .inner
+            .repartitioned(target_partitions, config)?
+            .map(|inner| Arc::new(Self::new(inner)) as _))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let span = tracing::info_span!(
+            "execute",
+            operator = self.name.as_str(),
+            partition,
+            batches = Empty,
+            rows = Empty,
+            poll_time_ns = Empty,
+        );
+        let inner = span.in_scope(|| self.inner.execute(partition, context))?;
+        Ok(Box::pin(InstrumentedStream {
+            inner,
+            span,
+            batches: 0,
+            rows: 0,
+            poll_time: Duration::ZERO,
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        self.inner.metrics()
+    }
+
+    fn statistics(&self) -> Result<Statistics> {
+        self.inner.statistics()
+    }
+
+    fn progress(&self) -> ExecutionProgress {
+        self.inner.progress()
+    }
+}
+
+/// The stream of a partition of an [`InstrumentedExec`]
+struct InstrumentedStream {
+    inner: SendableRecordBatchStream,
+    span: Span,
+    batches: usize,
+    rows: usize,
+    poll_time: Duration,
+}
+
+impl Stream for InstrumentedStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let _entered = this.span.enter();
+        let start = Instant::now();
+        let poll = this.inner.poll_next_unpin(cx);
+        this.poll_time += start.elapsed();
+        if let Poll::Ready(Some(Ok(batch))) = &poll {
+            this.batches += 1;
+            this.rows += batch.num_rows();
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl RecordBatchStream for InstrumentedStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+impl Drop for InstrumentedStream {
+    fn drop(&mut self) {
+        self.span.record("batches", self.batches);
+        self.span.record("rows", self.rows);
+        self.span
+            .record("poll_time_ns", self.poll_time.as_nanos() as u64);
+    }
+}
@@ -301,6 +301,11 @@ pub struct HashJoinExec {
     /// Otherwise, rows that have `null`s in the join columns will not be
     /// matched and thus will not appear in the output.
     pub null_equals_null: bool,
+    /// If `null_aware` is true, this is a [`JoinType::RightAnti`] join with the
+    /// semantics of `NOT IN`: no row is returned if any build-side key is
+    /// `null`, and probe-side rows with a `null` key are only returned if the
+    /// build side is empty. See [`Self::with_null_aware`].
+    null_aware: bool,
 }
 
 impl HashJoinExec {
@@ -353,10 +358,37 @@ impl HashJoinExec {
             metrics: ExecutionPlanMetricsSet::new(),
             column_indices,
             null_equals_null,
+            null_aware: false,
             output_order,
         })
     }
 
+    /// Sets whether this join evaluates `NOT IN` semantics, see
+    /// [`Self::null_aware`].
+    ///
+    /// # Error
+    /// A null aware join must be a [`JoinType::RightAnti`] join on a single
+    /// key, with the build side collected in a single partition, so that
+    /// every probe partition sees all the `null`s of the build side.
+    pub fn with_null_aware(mut self, null_aware: bool) -> Result<Self> {
+        if null_aware
+            && (self.join_type != JoinType::RightAnti
+                || self.on.len() != 1
+                || self.mode != PartitionMode::CollectLeft
+                || self.null_equals_null)
+        {
+            return plan_err!(
+                "Null aware HashJoinExec requires a RightAnti join on a single key \
+                 in CollectLeft mode, got {:?} join on {} keys in {:?} mode",
+                self.join_type,
+                self.on.len(),
+                self.mode
+            );
+        }
+        self.null_aware = null_aware;
+        Ok(self)
+    }
+
     /// left (build) side which gets hashed
     pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
         &self.left
@@ -392,6 +424,11 @@ impl HashJoinExec {
         self.null_equals_null
     }
 
+    /// Get null_aware
+    pub fn null_aware(&self) -> bool {
+        self.null_aware
+    }
+
     /// Calculate order preservation flags for this hash join.
     fn maintains_input_order(join_type: JoinType) -> Vec<bool> {
         vec![
@@ -424,10 +461,15 @@ impl DisplayAs for HashJoinExec {
                     .map(|(c1, c2)| format!("({}, {})", c1, c2))
                     .collect::<Vec<String>>()
                     .join(", ");
+                let display_null_aware = if self.null_aware {
+                    ", null_aware=true"
+                } else {
+                    ""
+                };
                 write!(
                     f,
-                    "HashJoinExec: mode={:?}, join_type={:?}, on=[{}]{}",
-                    self.mode, self.join_type, on, display_filter
+                    "HashJoinExec: mode={:?}, join_type={:?}, on=[{}]{}{}",
+                    self.mode, self.join_type, on, display_filter, display_null_aware
                 )
             }
         }
@@ -577,15 +619,18 @@ impl ExecutionPlan for HashJoinExec {
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(HashJoinExec::try_new(
-            children[0].clone(),
-            children[1].clone(),
-            self.on.clone(),
-            self.filter.clone(),
-            &self.join_type,
-            self.mode,
-            self.null_equals_null,
-        )?))
+        Ok(Arc::new(
+            HashJoinExec::try_new(
+                children[0].clone(),
+                children[1].clone(),
+                self.on.clone(),
+                self.filter.clone(),
+                &self.join_type,
+                self.mode,
+                self.null_equals_null,
+            )?
+            .with_null_aware(self.null_aware)?,
+        ))
     }
 
     fn execute(
@@ -662,6 +707,7 @@ impl ExecutionPlan for HashJoinExec {
             random_state: self.random_state.clone(),
             join_metrics,
             null_equals_null: self.null_equals_null,
+            null_aware: self.null_aware,
             reservation,
             state: HashJoinStreamState::WaitBuildSide,
             build_side: BuildSide::Initial(BuildSideInitialState { left_fut }),
@@ -954,6 +1000,8 @@ struct HashJoinStream {
     column_indices: Vec<ColumnIndex>,
     /// If null_equals_null is true, null == null else null != null
     null_equals_null: bool,
+    /// If null_aware is true, the join evaluates `NOT IN` semantics
+    null_aware: bool,
     /// Memory reservation
     reservation: MemoryReservation,
     /// State of the stream
@@ -1165,6 +1213,34 @@ pub fn equal_rows_arr(
     ))
 }
 
+/// Returns true if any of the `on` keys of `batch` is null
+fn has_null_keys(on: &[Column], batch: &RecordBatch) -> Result<bool> {
+    for column in on {
+        let keys = column.evaluate(batch)?.into_array(batch.num_rows())?;
+        if keys.null_count() > 0 {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Removes the `indices` of the rows of `batch` with a null `on` key
+fn filter_null_keys(
+    on: &[Column],
+    batch: &RecordBatch,
+    indices: UInt32Array,
+) -> Result<UInt32Array> {
+    let keys = on
+        .iter()
+        .map(|c| c.evaluate(batch)?.into_array(batch.num_rows()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(indices
+        .iter()
+        .flatten()
+        .filter(|index| keys.iter().all(|key| key.is_valid(*index as usize)))
+        .collect())
+}
+
 impl HashJoinStream {
     /// Separate implementation function that unpins the [`HashJoinStream`] so
     /// that partial borrows work correctly
@@ -1207,6 +1283,12 @@ impl HashJoinStream {
             .get_shared(cx))?;
         build_timer.done();
 
+        // `x NOT IN (...)` is never true if the build side contains a null
+        if self.null_aware && has_null_keys(&self.on_left, left_data.batch())? {
+            self.state = HashJoinStreamState::Completed;
+            return Poll::Ready(Ok(StatefulStreamResult::Continue));
+        }
+
         // Reserving memory for visited_left_side bitmap in case it hasn't been initialized yet
         // and join_type requires to store it
         if need_produce_result_in_final(self.join_type) {
@@ -1312,6 +1394,14 @@ impl HashJoinStream {
                     self.join_type,
                 );
 
+                // `NULL NOT IN (...)` is only true if the build side is empty
+                let right_side = if self.null_aware && build_side.left_data.num_rows() > 0
+                {
+                    filter_null_keys(&self.on_right, &state.batch, right_side)?
+                } else {
+                    right_side
+                };
+
                 let result = build_batch_from_indices(
                     &self.schema,
                     build_side.left_data.batch(),
@@ -2701,6 +2791,72 @@ mod tests {
         Ok(())
     }
 
+    fn build_nullable_table(
+        name: &str,
+        values: Vec<Option<i32>>,
+    ) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![Field::new(name, DataType::Int32, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(values))],
+        )
+        .unwrap();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
+
+    async fn null_aware_join_collect(
+        left: Vec<Option<i32>>,
+        right: Vec<Option<i32>>,
+    ) -> Result<Vec<RecordBatch>> {
+        let left = build_nullable_table("a", left);
+        let right = build_nullable_table("b", right);
+        let on = vec![(
+            Column::new_with_schema("a", &left.schema())?,
+            Column::new_with_schema("b", &right.schema())?,
+        )];
+        let join =
+            join(left, right, on, &JoinType::RightAnti, false)?.with_null_aware(true)?;
+        let stream = join.execute(0, Arc::new(TaskContext::default()))?;
+        common::collect(stream).await
+    }
+
+    #[tokio::test]
+    async fn join_right_anti_null_aware() -> Result<()> {
+        // null probe keys are not returned for a non empty build side
+        let batches =
+            null_aware_join_collect(vec![Some(1), Some(2)], vec![Some(1), None, Some(3)])
+                .await?;
+        let expected = ["+---+", "| b |", "+---+", "| 3 |", "+---+"];
+        assert_batches_eq!(expected, &batches);
+
+        // no row is returned if the build side contains a null
+        let batches =
+            null_aware_join_collect(vec![Some(1), None], vec![Some(1), None, Some(3)])
+                .await?;
+        assert_batches_eq!(["++", "++"], &batches);
+
+        // all rows are returned for an empty build side
+        let batches = null_aware_join_collect(vec![], vec![Some(1), None]).await?;
+        let expected = ["+---+", "| b |", "+---+", "| 1 |", "|   |", "+---+"];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[test]
+    fn join_null_aware_invalid() -> Result<()> {
+        let left = build_semi_anti_left_table();
+        let right = build_semi_anti_right_table();
+        let on = vec![(
+            Column::new_with_schema("b1", &left.schema())?,
+            Column::new_with_schema("b2", &right.schema())?,
+        )];
+        let err = join(left, right, on, &JoinType::LeftAnti, false)?
+            .with_null_aware(true)
+            .unwrap_err();
+        assert_contains!(err.to_string(), "Null aware HashJoinExec requires");
+        Ok(())
+    }
+
     #[tokio::test]
     async fn join_right_one() -> Result<()> {
         let task_ctx = Arc::new(TaskContext::default());
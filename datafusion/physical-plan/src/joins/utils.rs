@@ -339,6 +339,16 @@ pub fn adjust_right_output_partitioning(
                 .collect();
             Partitioning::Hash(new_exprs, size)
         }
+        Partitioning::Range(sort_exprs, size) => {
+            let new_sort_exprs = sort_exprs
+                .into_iter()
+                .map(|sort_expr| PhysicalSortExpr {
+                    expr: add_offset_to_expr(sort_expr.expr, left_columns_len),
+                    options: sort_expr.options,
+                })
+                .collect();
+            Partitioning::Range(new_sort_exprs, size)
+        }
     }
 }
 
@@ -26,6 +26,7 @@ use crate::display::DisplayableExecutionPlan;
 use crate::metrics::MetricsSet;
 use crate::repartition::RepartitionExec;
 use crate::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use crate::stream::CooperativeStream;
 
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
@@ -46,22 +47,28 @@ mod visitor;
 
 pub mod aggregates;
 pub mod analyze;
+pub mod async_func;
 pub mod coalesce_batches;
 pub mod coalesce_partitions;
 pub mod common;
 pub mod display;
+pub mod dynamic_filter;
 pub mod empty;
 pub mod explain;
 pub mod filter;
 pub mod insert;
+#[cfg(feature = "tracing")]
+pub mod instrumented;
 pub mod joins;
 pub mod limit;
 pub mod memory;
 pub mod metrics;
 mod ordering;
 pub mod placeholder_row;
+pub mod progress;
 pub mod projection;
 pub mod repartition;
+pub mod selection;
 pub mod sorts;
 pub mod stream;
 pub mod streaming;
@@ -75,6 +82,7 @@ pub mod windows;
 pub use crate::display::{DefaultDisplay, DisplayAs, DisplayFormatType, VerboseDisplay};
 pub use crate::metrics::Metric;
 pub use crate::ordering::InputOrderMode;
+pub use crate::progress::ExecutionProgress;
 pub use crate::topk::TopK;
 pub use crate::visitor::{accept, visit_execution_plan, ExecutionPlanVisitor};
 
@@ -442,6 +450,27 @@ pub trait ExecutionPlan: Debug + DisplayAs + Send + Sync {
     fn statistics(&self) -> Result<Statistics> {
         Ok(Statistics::new_unknown(&self.schema()))
     }
+
+    /// Returns how much input this `ExecutionPlan` has consumed so far,
+    /// which may be polled while the plan is executing.
+    ///
+    /// The default returns the number of rows produced by the children
+    /// according to their [`Self::metrics`], or for a leaf the rows it
+    /// produced itself, without any totals. Scans should override this to
+    /// report the totals they know up front, which are used to estimate
+    /// the completion of a query, see [`progress::estimate_completion`].
+    fn progress(&self) -> ExecutionProgress {
+        let children = self.children();
+        let rows = if children.is_empty() {
+            self.metrics().and_then(|m| m.output_rows()).unwrap_or(0)
+        } else {
+            children
+                .iter()
+                .filter_map(|c| c.metrics().and_then(|m| m.output_rows()))
+                .sum()
+        };
+        ExecutionProgress::new(rows)
+    }
 }
 
 /// Indicate whether a data exchange is needed for the input of `plan`, which will be very helpful
@@ -515,21 +544,27 @@ pub async fn collect(
 ///
 /// Dropping the stream will abort the execution of the query, and free up
 /// any allocated resources
+///
+/// Cancelling the [`CancellationToken`] of `context`, or exceeding its
+/// timeout, ends the stream with a [`DataFusionError::Cancelled`]
+///
+/// [`CancellationToken`]: datafusion_execution::cancellation::CancellationToken
 pub fn execute_stream(
     plan: Arc<dyn ExecutionPlan>,
     context: Arc<TaskContext>,
 ) -> Result<SendableRecordBatchStream> {
-    match plan.output_partitioning().partition_count() {
-        0 => Ok(Box::pin(EmptyRecordBatchStream::new(plan.schema()))),
-        1 => plan.execute(0, context),
+    let stream = match plan.output_partitioning().partition_count() {
+        0 => return Ok(Box::pin(EmptyRecordBatchStream::new(plan.schema()))),
+        1 => plan.execute(0, context.clone())?,
         _ => {
             // merge into a single partition
             let plan = CoalescePartitionsExec::new(plan.clone());
             // CoalescePartitionsExec must produce a single partition
             assert_eq!(1, plan.output_partitioning().partition_count());
-            plan.execute(0, context)
+            plan.execute(0, context.clone())?
         }
-    }
+    };
+    Ok(Box::pin(CooperativeStream::new(stream, &context)))
 }
 
 /// Execute the [ExecutionPlan] and collect the results in memory
@@ -586,7 +621,10 @@ pub fn execute_stream_partitioned(
     let num_partitions = plan.output_partitioning().partition_count();
     let mut streams = Vec::with_capacity(num_partitions);
     for i in 0..num_partitions {
-        streams.push(plan.execute(i, context.clone())?);
+        let stream = plan.execute(i, context.clone())?;
+        let stream: SendableRecordBatchStream =
+            Box::pin(CooperativeStream::new(stream, &context));
+        streams.push(stream);
     }
     Ok(streams)
 }
@@ -23,6 +23,9 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use super::expressions::PhysicalSortExpr;
+use super::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use super::progress::ExecutionProgress;
+use super::stream::{CooperativeStream, ObservedStream};
 use super::{
     common, DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream,
     SendableRecordBatchStream, Statistics,
@@ -48,6 +51,8 @@ pub struct MemoryExec {
     projection: Option<Vec<usize>>,
     // Sort information: one or more equivalent orderings
     sort_information: Vec<LexOrdering>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
 }
 
 impl fmt::Debug for MemoryExec {
@@ -140,13 +145,30 @@ impl ExecutionPlan for MemoryExec {
     fn execute(
         &self,
         partition: usize,
-        _context: Arc<TaskContext>,
+        context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        Ok(Box::pin(MemoryStream::try_new(
+        let stream = MemoryStream::try_new(
             self.partitions[partition].clone(),
             self.projected_schema.clone(),
             self.projection.clone(),
-        )?))
+        )?;
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let stream = ObservedStream::new(Box::pin(stream), baseline_metrics);
+        Ok(Box::pin(CooperativeStream::new(Box::pin(stream), &context)))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    /// The totals are known as all the batches are in memory
+    fn progress(&self) -> ExecutionProgress {
+        let rows = self.metrics.clone_inner().output_rows().unwrap_or(0);
+        let total_rows = self.partitions.iter().flatten().map(|b| b.num_rows()).sum();
+        ExecutionProgress {
+            total_rows: Some(total_rows),
+            ..ExecutionProgress::new(rows)
+        }
     }
 
     /// We recompute the statistics dynamically from the arrow metadata as it is pretty cheap to do so
@@ -174,6 +196,7 @@ impl MemoryExec {
             projected_schema,
             projection,
             sort_information: vec![],
+            metrics: ExecutionPlanMetricsSet::new(),
         })
     }
 
@@ -0,0 +1,179 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`ExecutionProgress`] of a running [`ExecutionPlan`]
+
+use crate::ExecutionPlan;
+
+/// How much input an [`ExecutionPlan`] has consumed so far, see
+/// [`ExecutionPlan::progress`].
+///
+/// Scans also report the totals they expect to read when these are known
+/// up front, for example from the size of the files or the row counts in
+/// parquet footers, from which the fraction of the scan that has completed
+/// can be estimated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionProgress {
+    /// Rows consumed so far
+    pub rows: usize,
+    /// Bytes consumed so far, or 0 if the operator does not track bytes
+    pub bytes: usize,
+    /// Total number of rows that will be consumed, if known
+    pub total_rows: Option<usize>,
+    /// Total number of bytes that will be consumed, if known
+    pub total_bytes: Option<usize>,
+}
+
+impl ExecutionProgress {
+    /// Create a new progress with `rows` consumed and no known totals
+    pub fn new(rows: usize) -> Self {
+        Self {
+            rows,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the completed fraction between 0 and 1, estimated from the
+    /// rows consumed if the total row count is known, or else from the
+    /// bytes consumed. Returns `None` if neither total is known.
+    pub fn fraction(&self) -> Option<f64> {
+        let (done, total) = match (self.total_rows, self.total_bytes) {
+            (Some(total_rows), _) => (self.rows, total_rows),
+            (None, Some(total_bytes)) => (self.bytes, total_bytes),
+            (None, None) => return None,
+        };
+        if total == 0 {
+            return Some(1.0);
+        }
+        Some((done as f64 / total as f64).min(1.0))
+    }
+
+    /// Adds the progress of `other` to this progress. Totals are only
+    /// known if they are known for both.
+    pub fn add(&self, other: &Self) -> Self {
+        Self {
+            rows: self.rows + other.rows,
+            bytes: self.bytes + other.bytes,
+            total_rows: self.total_rows.zip(other.total_rows).map(|(a, b)| a + b),
+            total_bytes: self.total_bytes.zip(other.total_bytes).map(|(a, b)| a + b),
+        }
+    }
+}
+
+/// Returns the progress of the leaves of `plan`, such as its scans, which
+/// are the operators that know the totals of their input
+pub fn leaf_progress(plan: &dyn ExecutionPlan) -> Vec<ExecutionProgress> {
+    let children = plan.children();
+    if children.is_empty() {
+        return vec![plan.progress()];
+    }
+    children
+        .iter()
+        .flat_map(|child| leaf_progress(child.as_ref()))
+        .collect()
+}
+
+/// Estimates the completed fraction of `plan` between 0 and 1, as the
+/// average completed fraction of its leaves that know their totals.
+/// Returns `None` if no leaf knows its totals.
+pub fn estimate_completion(plan: &dyn ExecutionPlan) -> Option<f64> {
+    let fractions = leaf_progress(plan)
+        .iter()
+        .filter_map(|p| p.fraction())
+        .collect::<Vec<_>>();
+    if fractions.is_empty() {
+        return None;
+    }
+    Some(fractions.iter().sum::<f64>() / fractions.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::collect;
+    use crate::memory::MemoryExec;
+    use crate::union::UnionExec;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow_array::{Int32Array, RecordBatch};
+    use datafusion_execution::TaskContext;
+    use std::sync::Arc;
+
+    #[test]
+    fn fraction() {
+        assert_eq!(ExecutionProgress::new(10).fraction(), None);
+
+        let progress = ExecutionProgress {
+            rows: 10,
+            bytes: 100,
+            total_rows: Some(40),
+            total_bytes: Some(200),
+        };
+        assert_eq!(progress.fraction(), Some(0.25));
+
+        let progress = ExecutionProgress {
+            total_rows: None,
+            ..progress
+        };
+        assert_eq!(progress.fraction(), Some(0.5));
+
+        let progress = ExecutionProgress {
+            rows: 0,
+            bytes: 0,
+            total_rows: Some(0),
+            total_bytes: None,
+        };
+        assert_eq!(progress.fraction(), Some(1.0));
+
+        // statistics may be inexact
+        let progress = ExecutionProgress {
+            rows: 50,
+            bytes: 0,
+            total_rows: Some(40),
+            total_bytes: None,
+        };
+        assert_eq!(progress.fraction(), Some(1.0));
+    }
+
+    #[tokio::test]
+    async fn memory_exec_progress() {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3, 4]))],
+        )
+        .unwrap();
+        let scanned = Arc::new(
+            MemoryExec::try_new(&[vec![batch.clone()]], Arc::clone(&schema), None)
+                .unwrap(),
+        );
+        let pending: Arc<dyn ExecutionPlan> = Arc::new(
+            MemoryExec::try_new(&[vec![batch.clone(), batch]], schema, None).unwrap(),
+        );
+        let union = UnionExec::new(vec![Arc::clone(&scanned) as _, pending]);
+
+        assert_eq!(estimate_completion(&union), Some(0.0));
+
+        let ctx = Arc::new(TaskContext::default());
+        collect(scanned.execute(0, ctx).unwrap()).await.unwrap();
+
+        let progress = scanned.progress();
+        assert_eq!(progress.rows, 4);
+        assert_eq!(progress.total_rows, Some(4));
+        assert_eq!(progress.fraction(), Some(1.0));
+        assert_eq!(estimate_completion(&union), Some(0.5));
+    }
+}
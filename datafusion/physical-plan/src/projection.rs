@@ -29,6 +29,8 @@ use std::task::{Context, Poll};
 use super::expressions::{Column, PhysicalSortExpr};
 use super::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
 use super::{DisplayAs, RecordBatchStream, SendableRecordBatchStream, Statistics};
+use crate::filter::FilterExec;
+use crate::selection::SelectionProjectionStream;
 use crate::{
     ColumnStatistics, DisplayFormatType, ExecutionPlan, Partitioning, PhysicalExpr,
 };
@@ -186,6 +188,24 @@ impl ExecutionPlan for ProjectionExec {
                 })
                 .collect();
             Partitioning::Hash(normalized_exprs, part)
+        } else if let Partitioning::Range(sort_exprs, part) = input_partition {
+            // The ranges of a prefix of the sort expressions are still ordered
+            let projected_sort_exprs = sort_exprs
+                .into_iter()
+                .map_while(|sort_expr| {
+                    let expr = input_eq_properties
+                        .project_expr(&sort_expr.expr, &self.projection_mapping)?;
+                    Some(PhysicalSortExpr {
+                        expr,
+                        options: sort_expr.options,
+                    })
+                })
+                .collect::<Vec<_>>();
+            if projected_sort_exprs.is_empty() {
+                Partitioning::UnknownPartitioning(part)
+            } else {
+                Partitioning::Range(projected_sort_exprs, part)
+            }
         } else {
             input_partition
         }
@@ -230,6 +250,33 @@ impl ExecutionPlan for ProjectionExec {
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         trace!("Start ProjectionExec::execute for partition {} of context session_id {} and task_id {:?}", partition, context.session_id(), context.task_id());
+        let options = &context.session_config().options().execution;
+        if options.enable_selection_vectors {
+            // the filters directly below the projection, in the order they
+            // are applied
+            let mut filters = vec![];
+            let mut input = &self.input;
+            while let Some(filter) = input.as_any().downcast_ref::<FilterExec>() {
+                filters.push((
+                    filter.predicate().clone(),
+                    filter.baseline_metrics(partition),
+                ));
+                input = filter.input();
+            }
+            filters.reverse();
+
+            if !filters.is_empty() {
+                return Ok(Box::pin(SelectionProjectionStream {
+                    schema: self.schema.clone(),
+                    expr: self.expr.iter().map(|x| x.0.clone()).collect(),
+                    filters,
+                    compaction_threshold: options.selection_compaction_threshold,
+                    input: input.execute(partition, context)?,
+                    baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+                }));
+            }
+        }
+
         Ok(Box::pin(ProjectionStream {
             schema: self.schema.clone(),
             expr: self.expr.iter().map(|x| x.0.clone()).collect(),
@@ -368,6 +415,8 @@ mod tests {
 
     use arrow_schema::DataType;
     use datafusion_common::ScalarValue;
+    use datafusion_execution::config::SessionConfig;
+    use datafusion_expr::Operator;
 
     #[tokio::test]
     async fn project_no_column() -> Result<()> {
@@ -384,6 +433,49 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn project_filters_with_selection_vectors() -> Result<()> {
+        let exec = test::scan_partitioned(1);
+        let schema = exec.schema();
+        // i > 10 AND 1000 / i < 20 fails for i = 0 if not short circuited
+        let i_gt_10 = expressions::binary(
+            expressions::col("i", &schema)?,
+            Operator::Gt,
+            expressions::lit(10),
+            &schema,
+        )?;
+        let div_lt_20 = expressions::binary(
+            expressions::binary(
+                expressions::lit(1000),
+                Operator::Divide,
+                expressions::col("i", &schema)?,
+                &schema,
+            )?,
+            Operator::Lt,
+            expressions::lit(20),
+            &schema,
+        )?;
+        let filter = Arc::new(FilterExec::try_new(
+            div_lt_20,
+            Arc::new(FilterExec::try_new(i_gt_10, exec)?),
+        )?);
+        let projection = Arc::new(ProjectionExec::try_new(
+            vec![(expressions::col("i", &schema)?, "i".to_string())],
+            filter.clone(),
+        )?);
+
+        let mut config = SessionConfig::new();
+        config.options_mut().execution.enable_selection_vectors = true;
+        let task_ctx = Arc::new(TaskContext::default().with_session_config(config));
+        let output = collect(projection.execute(0, task_ctx)?).await?;
+
+        let rows: usize = output.iter().map(|b| b.num_rows()).sum();
+        // 1000 / i < 20 for i > 50
+        assert_eq!(rows, 49);
+        assert_eq!(filter.metrics().unwrap().output_rows(), Some(49));
+        Ok(())
+    }
+
     fn get_stats() -> Statistics {
         Statistics {
             num_rows: Precision::Exact(5),
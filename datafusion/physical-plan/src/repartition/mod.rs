@@ -27,6 +27,7 @@ use std::{any::Any, vec};
 use arrow::array::{ArrayRef, UInt64Builder};
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
+use arrow::row::{Row, RowConverter, Rows, SortField};
 use futures::stream::Stream;
 use futures::{FutureExt, StreamExt};
 use hashbrown::HashMap;
@@ -34,7 +35,9 @@ use log::trace;
 use parking_lot::Mutex;
 use tokio::task::JoinHandle;
 
-use datafusion_common::{arrow_datafusion_err, not_impl_err, DataFusionError, Result};
+use datafusion_common::{
+    arrow_datafusion_err, internal_err, not_impl_err, DataFusionError, Result,
+};
 use datafusion_execution::memory_pool::MemoryConsumer;
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::{EquivalenceProperties, PhysicalExpr};
@@ -44,6 +47,7 @@ use crate::hash_utils::create_hashes;
 use crate::metrics::BaselineMetrics;
 use crate::repartition::distributor_channels::{channels, partition_aware_channels};
 use crate::sorts::streaming_merge;
+use crate::stream::RecordBatchStreamAdapter;
 use crate::{DisplayFormatType, ExecutionPlan, Partitioning, Statistics};
 
 use super::common::{AbortOnDropMany, AbortOnDropSingle, SharedMemoryReservation};
@@ -52,8 +56,10 @@ use super::metrics::{self, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet};
 use super::{DisplayAs, RecordBatchStream, SendableRecordBatchStream};
 
 use self::distributor_channels::{DistributionReceiver, DistributionSender};
+use self::range::RangeSampler;
 
 mod distributor_channels;
+mod range;
 
 type MaybeBatch = Option<Result<RecordBatch>>;
 type InputPartitionsToCurrentPartitionSender = Vec<DistributionSender<MaybeBatch>>;
@@ -94,6 +100,13 @@ enum BatchPartitionerState {
         num_partitions: usize,
         next_idx: usize,
     },
+    Range {
+        exprs: Vec<PhysicalSortExpr>,
+        converter: RowConverter,
+        /// The split points between the partitions, in ascending order
+        bounds: Rows,
+        num_partitions: usize,
+    },
 }
 
 impl BatchPartitioner {
@@ -121,6 +134,51 @@ impl BatchPartitioner {
         Ok(Self { state, timer })
     }
 
+    /// Create a new [`BatchPartitioner`] for a [`Partitioning::Range`] on
+    /// `exprs` into `num_partitions` partitions, separated by `split_points`.
+    ///
+    /// `split_points` holds one array per sort expression, each with
+    /// `num_partitions - 1` values in ascending order, or none at all to
+    /// send all rows to the first partition.
+    ///
+    /// The time spent repartitioning will be recorded to `timer`
+    pub fn try_new_range(
+        exprs: Vec<PhysicalSortExpr>,
+        num_partitions: usize,
+        split_points: &[ArrayRef],
+        timer: metrics::Time,
+    ) -> Result<Self> {
+        if exprs.len() != split_points.len() {
+            return internal_err!(
+                "Expected split points for {} sort expressions, got {}",
+                exprs.len(),
+                split_points.len()
+            );
+        }
+        let sort_fields = exprs
+            .iter()
+            .zip(split_points)
+            .map(|(expr, split_points)| {
+                SortField::new_with_options(
+                    split_points.data_type().clone(),
+                    expr.options,
+                )
+            })
+            .collect();
+        let converter = RowConverter::new(sort_fields)?;
+        let bounds = converter.convert_columns(split_points)?;
+
+        Ok(Self {
+            state: BatchPartitionerState::Range {
+                exprs,
+                converter,
+                bounds,
+                num_partitions,
+            },
+            timer,
+        })
+    }
+
     /// Partition the provided [`RecordBatch`] into one or more partitioned [`RecordBatch`]
     /// based on the [`Partitioning`] specified on construction
     ///
@@ -186,34 +244,35 @@ impl BatchPartitioner {
                             .append_value(index as u64);
                     }
 
-                    let it = indices
-                        .into_iter()
-                        .enumerate()
-                        .filter_map(|(partition, mut indices)| {
-                            let indices = indices.finish();
-                            (!indices.is_empty()).then_some((partition, indices))
-                        })
-                        .map(move |(partition, indices)| {
-                            // Produce batches based on indices
-                            let columns = batch
-                                .columns()
-                                .iter()
-                                .map(|c| {
-                                    arrow::compute::take(c.as_ref(), &indices, None)
-                                        .map_err(|e| arrow_datafusion_err!(e))
-                                })
-                                .collect::<Result<Vec<ArrayRef>>>()?;
-
-                            let batch =
-                                RecordBatch::try_new(batch.schema(), columns).unwrap();
-
-                            // bind timer so it drops w/ this iterator
-                            let _ = &timer;
-
-                            Ok((partition, batch))
-                        });
-
-                    Box::new(it)
+                    Box::new(take_partitions(batch, indices, timer))
+                }
+                BatchPartitionerState::Range {
+                    exprs,
+                    converter,
+                    bounds,
+                    num_partitions: partitions,
+                } => {
+                    let timer = self.timer.timer();
+
+                    let arrays = exprs
+                        .iter()
+                        .map(|e| e.expr.evaluate(&batch)?.into_array(batch.num_rows()))
+                        .collect::<Result<Vec<_>>>()?;
+                    let rows = converter.convert_columns(&arrays)?;
+
+                    let mut indices: Vec<_> = (0..*partitions)
+                        .map(|_| UInt64Builder::with_capacity(batch.num_rows()))
+                        .collect();
+
+                    for (index, row) in rows.iter().enumerate() {
+                        // the number of split points at or before the row
+                        let partition =
+                            bounds_partition_point(bounds, |bound| bound <= row);
+                        indices[partition.min(*partitions - 1)]
+                            .append_value(index as u64);
+                    }
+
+                    Box::new(take_partitions(batch, indices, timer))
                 }
             };
 
@@ -225,10 +284,60 @@ impl BatchPartitioner {
         match self.state {
             BatchPartitionerState::RoundRobin { num_partitions, .. } => num_partitions,
             BatchPartitionerState::Hash { num_partitions, .. } => num_partitions,
+            BatchPartitionerState::Range { num_partitions, .. } => num_partitions,
         }
     }
 }
 
+/// Returns the index of the first of `bounds` that does not satisfy `pred`,
+/// assuming `bounds` are partitioned by it
+fn bounds_partition_point(bounds: &Rows, pred: impl Fn(Row<'_>) -> bool) -> usize {
+    let (mut low, mut high) = (0, bounds.num_rows());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if pred(bounds.row(mid)) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+/// Takes the rows at `indices` of `batch` for each partition, skipping the
+/// partitions without any row
+fn take_partitions(
+    batch: RecordBatch,
+    indices: Vec<UInt64Builder>,
+    timer: metrics::ScopedTimerGuard<'_>,
+) -> impl Iterator<Item = Result<(usize, RecordBatch)>> + Send + '_ {
+    indices
+        .into_iter()
+        .enumerate()
+        .filter_map(|(partition, mut indices)| {
+            let indices = indices.finish();
+            (!indices.is_empty()).then_some((partition, indices))
+        })
+        .map(move |(partition, indices)| {
+            // Produce batches based on indices
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|c| {
+                    arrow::compute::take(c.as_ref(), &indices, None)
+                        .map_err(|e| arrow_datafusion_err!(e))
+                })
+                .collect::<Result<Vec<ArrayRef>>>()?;
+
+            let batch = RecordBatch::try_new(batch.schema(), columns).unwrap();
+
+            // bind timer so it drops w/ this iterator
+            let _ = &timer;
+
+            Ok((partition, batch))
+        })
+}
+
 /// Maps `N` input partitions to `M` output partitions based on a
 /// [`Partitioning`] scheme.
 ///
@@ -442,7 +551,10 @@ impl ExecutionPlan for RepartitionExec {
     }
 
     fn benefits_from_input_partitioning(&self) -> Vec<bool> {
-        vec![matches!(self.partitioning, Partitioning::Hash(_, _))]
+        vec![matches!(
+            self.partitioning,
+            Partitioning::Hash(_, _) | Partitioning::Range(_, _)
+        )]
     }
 
     fn output_partitioning(&self) -> Partitioning {
@@ -521,6 +633,17 @@ impl ExecutionPlan for RepartitionExec {
                 state.channels.insert(partition, (tx, rx, reservation));
             }
 
+            // the split points of range partitioning are chosen from a
+            // sample of all input partitions
+            let range_sampler = match &self.partitioning {
+                Partitioning::Range(sort_exprs, _) => Some(Arc::new(RangeSampler::new(
+                    sort_exprs.clone(),
+                    num_input_partitions,
+                    num_output_partitions,
+                ))),
+                _ => None,
+            };
+
             // launch one async task per *input* partition
             let mut join_handles = Vec::with_capacity(num_input_partitions);
             for i in 0..num_input_partitions {
@@ -540,6 +663,7 @@ impl ExecutionPlan for RepartitionExec {
                         i,
                         txs.clone(),
                         self.partitioning.clone(),
+                        range_sampler.clone(),
                         r_metrics,
                         context.clone(),
                     ));
@@ -675,25 +799,93 @@ impl RepartitionExec {
     /// output partitions based on the desired partitioning
     ///
     /// txs hold the output sending channels for each output partition
+    ///
+    /// For [`Partitioning::Range`], a prefix of the input partition is read
+    /// and sampled by `range_sampler` before anything is sent
     async fn pull_from_input(
         input: Arc<dyn ExecutionPlan>,
         partition: usize,
-        mut output_channels: HashMap<
+        output_channels: HashMap<
             usize,
             (DistributionSender<MaybeBatch>, SharedMemoryReservation),
         >,
         partitioning: Partitioning,
+        range_sampler: Option<Arc<RangeSampler>>,
         metrics: RepartitionMetrics,
         context: Arc<TaskContext>,
     ) -> Result<()> {
-        let mut partitioner =
-            BatchPartitioner::try_new(partitioning, metrics.repartition_time.clone())?;
+        let Some(range_sampler) = range_sampler else {
+            let partitioner = BatchPartitioner::try_new(
+                partitioning,
+                metrics.repartition_time.clone(),
+            )?;
+
+            // execute the child operator
+            let timer = metrics.fetch_time.timer();
+            let stream = input.execute(partition, context)?;
+            timer.done();
+
+            return Self::send_partitioned(
+                stream,
+                partitioner,
+                output_channels,
+                &metrics,
+            )
+            .await;
+        };
+
+        let Partitioning::Range(sort_exprs, num_partitions) = partitioning else {
+            return internal_err!("Range sampling requires range partitioning");
+        };
 
         // execute the child operator
         let timer = metrics.fetch_time.timer();
-        let mut stream = input.execute(partition, context)?;
+        let stream = input.execute(partition, Arc::clone(&context));
         timer.done();
+        let mut stream = stream.map_err(|e| range_sampler.abort(e))?;
+
+        let mut reservation =
+            MemoryConsumer::new(format!("RepartitionExec[range sample {partition}]"))
+                .register(context.memory_pool());
+        let (batches, split_points) = range_sampler
+            .sample(
+                &mut stream,
+                partition,
+                &mut reservation,
+                &metrics.fetch_time,
+            )
+            .await?;
+        let partitioner = BatchPartitioner::try_new_range(
+            sort_exprs,
+            num_partitions,
+            &split_points,
+            metrics.repartition_time.clone(),
+        )?;
+
+        // release the memory of the sampled batches as they are sent, then
+        // continue with the rest of the input
+        let schema = stream.schema();
+        let batches = futures::stream::iter(batches)
+            .map(move |batch| {
+                reservation.shrink(batch.get_array_memory_size());
+                Ok(batch)
+            })
+            .chain(stream);
+        let stream = Box::pin(RecordBatchStreamAdapter::new(schema, batches));
+        Self::send_partitioned(stream, partitioner, output_channels, &metrics).await
+    }
 
+    /// Sends the batches of `stream`, partitioned by `partitioner`, to the
+    /// output partitions that did not hang up yet
+    async fn send_partitioned(
+        mut stream: SendableRecordBatchStream,
+        mut partitioner: BatchPartitioner,
+        mut output_channels: HashMap<
+            usize,
+            (DistributionSender<MaybeBatch>, SharedMemoryReservation),
+        >,
+        metrics: &RepartitionMetrics,
+    ) -> Result<()> {
         // While there are still outputs to send to, keep pulling inputs
         let mut batches_until_yield = partitioner.num_partitions();
         while !output_channels.is_empty() {
@@ -920,8 +1112,8 @@ impl RecordBatchStream for PerPartitionStream {
 mod tests {
     use std::collections::HashSet;
 
-    use arrow::array::{ArrayRef, StringArray};
-    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::array::{ArrayRef, AsArray, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema, UInt32Type};
     use arrow::record_batch::RecordBatch;
     use arrow_array::UInt32Array;
     use futures::FutureExt;
@@ -1027,6 +1219,56 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn many_to_many_range_partition() -> Result<()> {
+        // define input partitions with interleaved values
+        let schema = test_schema();
+        let partitions = (0..3)
+            .map(|partition| {
+                let values = (0..100).map(|i| i * 3 + partition).collect::<Vec<u32>>();
+                let batch = RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(UInt32Array::from(values))],
+                )?;
+                Ok(vec![batch.slice(0, 50), batch.slice(50, 50)])
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let sort_exprs = vec![PhysicalSortExpr {
+            expr: col("c0", &schema)?,
+            options: Default::default(),
+        }];
+        let output_partitions =
+            repartition(&schema, partitions, Partitioning::Range(sort_exprs, 4)).await?;
+        assert_eq!(4, output_partitions.len());
+
+        let ranges = output_partitions
+            .iter()
+            .map(|batches| {
+                let values = batches
+                    .iter()
+                    .flat_map(|batch| batch.column(0).as_primitive::<UInt32Type>())
+                    .map(Option::unwrap)
+                    .collect::<Vec<_>>();
+                assert!(!values.is_empty());
+                (
+                    values.len(),
+                    values.iter().min().copied(),
+                    values.iter().max().copied(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // each output partition holds a range of the values
+        let total_rows: usize = ranges.iter().map(|(rows, _, _)| rows).sum();
+        assert_eq!(total_rows, 300);
+        for window in ranges.windows(2) {
+            assert!(window[0].2 < window[1].1, "{ranges:?}");
+        }
+
+        Ok(())
+    }
+
     fn test_schema() -> Arc<Schema> {
         Arc::new(Schema::new(vec![Field::new("c0", DataType::UInt32, false)]))
     }
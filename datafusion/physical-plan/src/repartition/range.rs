@@ -0,0 +1,352 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Chooses the split points of a [`Partitioning::Range`] by sampling the
+//! input partitions of a [`RepartitionExec`]
+//!
+//! [`Partitioning::Range`]: crate::Partitioning::Range
+//! [`RepartitionExec`]: super::RepartitionExec
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, UInt32Array, UInt64Array};
+use arrow::compute::{concat, take};
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use arrow::row::{RowConverter, SortField};
+use datafusion_common::{DataFusionError, Result};
+use datafusion_execution::memory_pool::MemoryReservation;
+use datafusion_physical_expr::PhysicalSortExpr;
+use futures::StreamExt;
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tokio::sync::watch;
+
+use crate::metrics;
+use crate::SendableRecordBatchStream;
+
+/// The number of sampled rows per input partition and output partition
+const SAMPLES_PER_PARTITION: usize = 100;
+
+/// The number of rows of an input partition that are buffered and sampled
+/// before the split points are chosen
+const MAX_BUFFERED_ROWS: usize = 64 * 1024;
+
+/// The values of the sort expressions at the split points between the
+/// ranges, one array per sort expression with one row less than the number
+/// of output partitions
+pub(super) type SplitPoints = Arc<Vec<ArrayRef>>;
+
+/// Collects a sample of the sort keys of every input partition and chooses
+/// the split points once all input partitions contributed theirs.
+///
+/// Only a prefix of at most [`MAX_BUFFERED_ROWS`] rows of each input
+/// partition is buffered and sampled, so that the memory used while waiting
+/// for the split points is bounded. Inputs whose prefix is not representative
+/// of the rest result in unbalanced ranges, but are still partitioned
+/// correctly.
+#[derive(Debug)]
+pub(super) struct RangeSampler {
+    sort_exprs: Vec<PhysicalSortExpr>,
+    num_output_partitions: usize,
+    state: Mutex<SamplerState>,
+    split_points: watch::Sender<Option<Result<SplitPoints, Arc<DataFusionError>>>>,
+}
+
+#[derive(Debug)]
+struct SamplerState {
+    /// The sampled sort keys of the input partitions read so far
+    samples: Vec<Vec<ArrayRef>>,
+    /// The number of input partitions that did not contribute yet
+    pending: usize,
+}
+
+impl RangeSampler {
+    pub(super) fn new(
+        sort_exprs: Vec<PhysicalSortExpr>,
+        num_input_partitions: usize,
+        num_output_partitions: usize,
+    ) -> Self {
+        let (split_points, _) = watch::channel(None);
+        Self {
+            sort_exprs,
+            num_output_partitions,
+            state: Mutex::new(SamplerState {
+                samples: vec![],
+                pending: num_input_partitions,
+            }),
+            split_points,
+        }
+    }
+
+    /// Reads a prefix of `input`, contributes its sample and waits for the
+    /// split points of all input partitions. Returns the buffered batches of
+    /// the prefix, whose memory is accounted to `reservation`, and the split
+    /// points. The rest of `input` is left unread.
+    pub(super) async fn sample(
+        &self,
+        input: &mut SendableRecordBatchStream,
+        input_partition: usize,
+        reservation: &mut MemoryReservation,
+        fetch_time: &metrics::Time,
+    ) -> Result<(Vec<RecordBatch>, SplitPoints)> {
+        // subscribe before contributing to not miss the split points
+        let mut split_points = self.split_points.subscribe();
+
+        let capacity = SAMPLES_PER_PARTITION * self.num_output_partitions;
+        let mut rng = StdRng::seed_from_u64(input_partition as u64);
+        let mut batches = vec![];
+        // reservoir of (batch index, row index)
+        let mut reservoir: Vec<(usize, usize)> = Vec::with_capacity(capacity);
+        let mut num_rows = 0;
+        while num_rows < MAX_BUFFERED_ROWS {
+            let timer = fetch_time.timer();
+            let result = input.next().await;
+            timer.done();
+            let batch = match result {
+                Some(Ok(batch)) => batch,
+                Some(Err(e)) => return Err(self.abort(e)),
+                None => break,
+            };
+            if let Err(e) = reservation.try_grow(batch.get_array_memory_size()) {
+                return Err(self.abort(e));
+            }
+
+            for row in 0..batch.num_rows() {
+                if reservoir.len() < capacity {
+                    reservoir.push((batches.len(), row));
+                } else {
+                    let index = rng.gen_range(0..=num_rows);
+                    if index < capacity {
+                        reservoir[index] = (batches.len(), row);
+                    }
+                }
+                num_rows += 1;
+            }
+            batches.push(batch);
+        }
+        reservoir.sort_unstable();
+
+        match self.sample_sort_keys(input.schema(), &batches, &reservoir) {
+            Ok(sample) => self.contribute(Some(sample)),
+            Err(e) => return Err(self.abort(e)),
+        }
+
+        let split_points = loop {
+            if let Some(split_points) = split_points.borrow_and_update().clone() {
+                break split_points;
+            }
+            split_points.changed().await.map_err(|e| {
+                DataFusionError::Execution(format!(
+                    "Range partitioning stopped before choosing the split points: {e}"
+                ))
+            })?;
+        };
+        let split_points =
+            split_points.map_err(|e| DataFusionError::External(Box::new(e)))?;
+        Ok((batches, split_points))
+    }
+
+    /// Gives up on sampling an input partition because of `error`, so that
+    /// the other input partitions do not wait for its sample
+    pub(super) fn abort(&self, error: DataFusionError) -> DataFusionError {
+        self.contribute(None);
+        error
+    }
+
+    /// Returns the sort keys of the `reservoir` rows of `batches`
+    fn sample_sort_keys(
+        &self,
+        schema: SchemaRef,
+        batches: &[RecordBatch],
+        reservoir: &[(usize, usize)],
+    ) -> Result<Vec<ArrayRef>> {
+        let mut selected: Vec<(usize, Vec<u32>)> = vec![];
+        for &(batch_index, row) in reservoir {
+            match selected.last_mut() {
+                Some((last, rows)) if *last == batch_index => rows.push(row as u32),
+                _ => selected.push((batch_index, vec![row as u32])),
+            }
+        }
+        // start with an empty batch, to get the keys even without any row
+        let empty = RecordBatch::new_empty(schema);
+        let selected = std::iter::once((&empty, vec![])).chain(
+            selected
+                .into_iter()
+                .map(|(batch_index, rows)| (&batches[batch_index], rows)),
+        );
+
+        let mut sort_keys: Vec<Vec<ArrayRef>> = vec![vec![]; self.sort_exprs.len()];
+        for (batch, rows) in selected {
+            let rows = UInt32Array::from(rows);
+            for (sort_expr, keys) in self.sort_exprs.iter().zip(sort_keys.iter_mut()) {
+                let values = sort_expr
+                    .expr
+                    .evaluate(batch)?
+                    .into_array(batch.num_rows())?;
+                keys.push(take(values.as_ref(), &rows, None)?);
+            }
+        }
+        sort_keys
+            .iter()
+            .map(|keys| {
+                let keys = keys.iter().map(|k| k.as_ref()).collect::<Vec<_>>();
+                Ok(concat(&keys)?)
+            })
+            .collect()
+    }
+
+    /// Adds the sample of an input partition, choosing the split points once
+    /// all input partitions contributed
+    fn contribute(&self, sample: Option<Vec<ArrayRef>>) {
+        let mut state = self.state.lock();
+        state.samples.extend(sample);
+        state.pending -= 1;
+        if state.pending > 0 {
+            return;
+        }
+
+        let samples = std::mem::take(&mut state.samples);
+        let split_points =
+            choose_split_points(&self.sort_exprs, samples, self.num_output_partitions)
+                .map(Arc::new)
+                .map_err(Arc::new);
+        self.split_points.send_replace(Some(split_points));
+    }
+}
+
+/// Sorts the sampled sort keys and returns the ones at the boundaries of
+/// `num_partitions` equally sized ranges
+fn choose_split_points(
+    sort_exprs: &[PhysicalSortExpr],
+    samples: Vec<Vec<ArrayRef>>,
+    num_partitions: usize,
+) -> Result<Vec<ArrayRef>> {
+    let columns = (0..sort_exprs.len())
+        .map(|i| {
+            let arrays = samples.iter().map(|s| s[i].as_ref()).collect::<Vec<_>>();
+            Ok(concat(&arrays)?)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let num_rows = columns.first().map_or(0, |c| c.len());
+
+    let sort_fields = sort_exprs
+        .iter()
+        .zip(&columns)
+        .map(|(sort_expr, column)| {
+            SortField::new_with_options(column.data_type().clone(), sort_expr.options)
+        })
+        .collect();
+    let converter = RowConverter::new(sort_fields)?;
+    let rows = converter.convert_columns(&columns)?;
+    let mut sorted = (0..num_rows).collect::<Vec<_>>();
+    sorted.sort_unstable_by(|a, b| rows.row(*a).cmp(&rows.row(*b)));
+
+    let split_indices: UInt64Array = if num_rows == 0 {
+        UInt64Array::from(Vec::<u64>::new())
+    } else {
+        (1..num_partitions)
+            .map(|i| Some(sorted[i * num_rows / num_partitions] as u64))
+            .collect()
+    };
+    columns
+        .iter()
+        .map(|column| Ok(take(column.as_ref(), &split_indices, None)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::RecordBatchStreamAdapter;
+    use arrow::array::{AsArray, Int32Array};
+    use arrow::datatypes::{DataType, Field, Int32Type, Schema};
+    use datafusion_execution::memory_pool::{
+        MemoryConsumer, MemoryPool, UnboundedMemoryPool,
+    };
+    use datafusion_physical_expr::expressions::col;
+
+    #[tokio::test]
+    async fn test_sample_buffers_prefix() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let sort_exprs = vec![PhysicalSortExpr {
+            expr: col("a", &schema)?,
+            options: Default::default(),
+        }];
+        let batch_size = 8192;
+        let num_batches = 2 * MAX_BUFFERED_ROWS / batch_size;
+        let batches = (0..num_batches)
+            .map(|i| {
+                let values = (0..batch_size as i32).map(|v| v + (i * batch_size) as i32);
+                Ok(RecordBatch::try_new(
+                    Arc::clone(&schema),
+                    vec![Arc::new(Int32Array::from_iter_values(values))],
+                )?)
+            })
+            .collect::<Vec<_>>();
+        let mut input: SendableRecordBatchStream =
+            Box::pin(RecordBatchStreamAdapter::new(
+                Arc::clone(&schema),
+                futures::stream::iter(batches),
+            ));
+
+        let pool: Arc<dyn MemoryPool> = Arc::new(UnboundedMemoryPool::default());
+        let mut reservation = MemoryConsumer::new("test").register(&pool);
+        let sampler = RangeSampler::new(sort_exprs, 1, 2);
+        let (buffered, split_points) = sampler
+            .sample(&mut input, 0, &mut reservation, &metrics::Time::new())
+            .await?;
+
+        // only the prefix is buffered, the rest of the input is left unread
+        let buffered_rows: usize = buffered.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(buffered_rows, MAX_BUFFERED_ROWS);
+        let mut remaining_rows = 0;
+        while let Some(batch) = input.next().await {
+            remaining_rows += batch?.num_rows();
+        }
+        assert_eq!(remaining_rows, MAX_BUFFERED_ROWS);
+        assert_eq!(split_points[0].len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_choose_split_points() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let sort_exprs = vec![PhysicalSortExpr {
+            expr: col("a", &schema)?,
+            options: Default::default(),
+        }];
+        let samples = vec![
+            vec![Arc::new(Int32Array::from((0..50).rev().collect::<Vec<_>>())) as _],
+            vec![Arc::new(Int32Array::from((50..100).collect::<Vec<_>>())) as _],
+        ];
+
+        let split_points = choose_split_points(&sort_exprs, samples, 4)?;
+        assert_eq!(split_points.len(), 1);
+        assert_eq!(
+            split_points[0].as_primitive::<Int32Type>(),
+            &Int32Array::from(vec![25, 50, 75])
+        );
+
+        // no rows at all
+        let samples = vec![vec![Arc::new(Int32Array::from(Vec::<i32>::new())) as _]];
+        let split_points = choose_split_points(&sort_exprs, samples, 4)?;
+        assert_eq!(split_points[0].len(), 0);
+        Ok(())
+    }
+}
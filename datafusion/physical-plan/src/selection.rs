@@ -0,0 +1,356 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`SelectionBatch`]: evaluating filters and projections on the selected
+//! rows of a batch, without copying the batch for each filter
+
+use std::collections::BTreeSet;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arrow::array::{Array, BooleanArray};
+use arrow::compute::{and, filter, filter_record_batch, prep_null_mask_filter};
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use datafusion_common::cast::as_boolean_array;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::Result;
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_expr::utils::{collect_columns, scatter};
+use datafusion_physical_expr::PhysicalExpr;
+use futures::stream::{Stream, StreamExt};
+
+use crate::metrics::BaselineMetrics;
+use crate::{RecordBatchStream, SendableRecordBatchStream};
+
+/// A [`RecordBatch`] with a selection vector of the rows that are part of
+/// it.
+///
+/// Filtering a [`SelectionBatch`] only narrows the selection, and the
+/// selected rows are copied ("compacted") when the batch is projected, or
+/// when the fraction of rows that are selected drops below a threshold.
+/// Projections only copy the columns they refer to.
+#[derive(Debug, Clone)]
+pub struct SelectionBatch {
+    batch: RecordBatch,
+    /// The selected rows of `batch`, or `None` if all rows are selected
+    selection: Option<BooleanArray>,
+}
+
+impl SelectionBatch {
+    /// Create a [`SelectionBatch`] with all the rows of `batch` selected
+    pub fn new(batch: RecordBatch) -> Self {
+        Self {
+            batch,
+            selection: None,
+        }
+    }
+
+    /// The rows of the batch, including the rows that are not selected
+    pub fn batch(&self) -> &RecordBatch {
+        &self.batch
+    }
+
+    /// The selected rows of [`Self::batch`], or `None` if all rows are
+    /// selected
+    pub fn selection(&self) -> Option<&BooleanArray> {
+        self.selection.as_ref()
+    }
+
+    /// The number of selected rows
+    pub fn num_rows(&self) -> usize {
+        match &self.selection {
+            Some(selection) => selection.true_count(),
+            None => self.batch.num_rows(),
+        }
+    }
+
+    /// The fraction of the rows of [`Self::batch`] that are selected
+    pub fn selectivity(&self) -> f64 {
+        match self.batch.num_rows() {
+            0 => 1.0,
+            n => self.num_rows() as f64 / n as f64,
+        }
+    }
+
+    /// Deselects the rows for which `predicate` is not true, evaluating it
+    /// only on the selected rows.
+    ///
+    /// The batch is compacted if less than `compaction_threshold` of its
+    /// rows are selected afterwards.
+    pub fn filter(
+        self,
+        predicate: &Arc<dyn PhysicalExpr>,
+        compaction_threshold: f64,
+    ) -> Result<Self> {
+        let mask = match &self.selection {
+            None => predicate
+                .evaluate(&self.batch)?
+                .into_array(self.batch.num_rows())?,
+            Some(selection) => {
+                let (batch, predicate) =
+                    self.compact_columns(std::slice::from_ref(predicate))?;
+                let mask = predicate[0]
+                    .evaluate(&batch)?
+                    .into_array(batch.num_rows())?;
+                // unselected rows are null
+                scatter(selection, mask.as_ref())?
+            }
+        };
+        let mut mask = as_boolean_array(&mask)?.clone();
+        if mask.null_count() > 0 {
+            mask = prep_null_mask_filter(&mask);
+        }
+        let selection = match &self.selection {
+            Some(selection) => and(selection, &mask)?,
+            None => mask,
+        };
+
+        let filtered = Self {
+            batch: self.batch,
+            selection: Some(selection),
+        };
+        if filtered.selectivity() < compaction_threshold {
+            Ok(Self::new(filtered.compact()?))
+        } else {
+            Ok(filtered)
+        }
+    }
+
+    /// Evaluates `exprs` on the selected rows, returning a batch of
+    /// `schema` with a column for each expression
+    pub fn project(
+        &self,
+        exprs: &[Arc<dyn PhysicalExpr>],
+        schema: SchemaRef,
+    ) -> Result<RecordBatch> {
+        let (batch, exprs) = match &self.selection {
+            Some(_) => self.compact_columns(exprs)?,
+            None => (self.batch.clone(), exprs.to_vec()),
+        };
+        let arrays = exprs
+            .iter()
+            .map(|expr| expr.evaluate(&batch)?.into_array(batch.num_rows()))
+            .collect::<Result<Vec<_>>>()?;
+        let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+        Ok(RecordBatch::try_new_with_options(schema, arrays, &options)?)
+    }
+
+    /// Returns the selected rows of the batch
+    pub fn compact(self) -> Result<RecordBatch> {
+        match self.selection {
+            Some(selection) => Ok(filter_record_batch(&self.batch, &selection)?),
+            None => Ok(self.batch),
+        }
+    }
+
+    /// Returns the selected rows of the columns `exprs` refer to, and
+    /// `exprs` rewritten to refer to the columns of the returned batch
+    fn compact_columns(
+        &self,
+        exprs: &[Arc<dyn PhysicalExpr>],
+    ) -> Result<(RecordBatch, Vec<Arc<dyn PhysicalExpr>>)> {
+        let indices: Vec<_> = exprs
+            .iter()
+            .flat_map(|expr| collect_columns(expr).into_iter().map(|c| c.index()))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let batch = self.batch.project(&indices)?;
+        let batch = match &self.selection {
+            Some(selection) => {
+                let columns = batch
+                    .columns()
+                    .iter()
+                    .map(|c| filter(c, selection))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let options = RecordBatchOptions::new()
+                    .with_row_count(Some(selection.true_count()));
+                RecordBatch::try_new_with_options(batch.schema(), columns, &options)?
+            }
+            None => batch,
+        };
+
+        let exprs = exprs
+            .iter()
+            .map(|expr| {
+                expr.clone().transform_up(&|expr| {
+                    let Some(column) = expr.as_any().downcast_ref::<Column>() else {
+                        return Ok(Transformed::No(expr));
+                    };
+                    // `indices` contains all the columns of `exprs`
+                    let index = indices.binary_search(&column.index()).unwrap();
+                    Ok(Transformed::Yes(Arc::new(Column::new(
+                        column.name(),
+                        index,
+                    ))))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((batch, exprs))
+    }
+}
+
+/// Evaluates the predicates of a chain of filters and the expressions of the
+/// projection above them on [`SelectionBatch`]es, so that the batches are
+/// compacted once, for the columns of the projection
+pub(crate) struct SelectionProjectionStream {
+    /// The output schema of the projection
+    pub(crate) schema: SchemaRef,
+    /// The expressions of the projection
+    pub(crate) expr: Vec<Arc<dyn PhysicalExpr>>,
+    /// The predicates of the filters, in the order they are applied, with
+    /// the metrics of each filter
+    pub(crate) filters: Vec<(Arc<dyn PhysicalExpr>, BaselineMetrics)>,
+    /// Batches are compacted when less than this fraction of their rows
+    /// are selected
+    pub(crate) compaction_threshold: f64,
+    /// The input of the filters
+    pub(crate) input: SendableRecordBatchStream,
+    /// The metrics of the projection
+    pub(crate) baseline_metrics: BaselineMetrics,
+}
+
+impl SelectionProjectionStream {
+    /// Returns the projection of the rows of `batch` that pass the filters,
+    /// or `None` if no rows pass
+    fn filter_project(&self, batch: RecordBatch) -> Result<Option<RecordBatch>> {
+        let mut batch = SelectionBatch::new(batch);
+        for (predicate, metrics) in &self.filters {
+            // records time on drop
+            let _timer = metrics.elapsed_compute().timer();
+            batch = batch.filter(predicate, self.compaction_threshold)?;
+            metrics.record_output(batch.num_rows());
+            if batch.num_rows() == 0 {
+                return Ok(None);
+            }
+        }
+
+        // records time on drop
+        let _timer = self.baseline_metrics.elapsed_compute().timer();
+        batch.project(&self.expr, self.schema.clone()).map(Some)
+    }
+}
+
+impl Stream for SelectionProjectionStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = loop {
+            match self.input.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(batch))) => match self.filter_project(batch) {
+                    // skip entirely filtered batches
+                    Ok(None) => continue,
+                    Ok(Some(batch)) => break Poll::Ready(Some(Ok(batch))),
+                    Err(e) => break Poll::Ready(Some(Err(e))),
+                },
+                other => break other,
+            }
+        };
+        self.baseline_metrics.record_poll(poll)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // same number of record batches
+        self.input.size_hint()
+    }
+}
+
+impl RecordBatchStream for SelectionProjectionStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::{binary, col, lit};
+
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_expr::Operator;
+
+    fn batch() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("s", DataType::Utf8, true),
+            Field::new("b", DataType::Int32, true),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), Some(2), None, Some(4)])),
+                Arc::new(StringArray::from(vec!["w", "x", "y", "z"])),
+                Arc::new(Int32Array::from(vec![0, 10, 20, 30])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn filter_and_project() -> Result<()> {
+        let batch = batch();
+        let schema = batch.schema();
+        let a_gt_1 = binary(col("a", &schema)?, Operator::Gt, lit(1), &schema)?;
+        // 100 / b fails for b = 0, which the first filter rules out
+        let div_b_lt_5 = binary(
+            binary(lit(100), Operator::Divide, col("b", &schema)?, &schema)?,
+            Operator::Lt,
+            lit(5),
+            &schema,
+        )?;
+
+        let selected = SelectionBatch::new(batch).filter(&a_gt_1, 0.0)?;
+        assert_eq!(selected.num_rows(), 2);
+        assert_eq!(selected.selectivity(), 0.5);
+        let selected = selected.filter(&div_b_lt_5, 0.0)?;
+        assert_eq!(
+            selected.selection().unwrap(),
+            &BooleanArray::from(vec![false, false, false, true])
+        );
+
+        let output_schema =
+            Arc::new(Schema::new(vec![Field::new("b", DataType::Int32, true)]));
+        let output = selected.project(&[col("b", &schema)?], output_schema)?;
+        assert_eq!(
+            output.column(0).as_ref(),
+            &Int32Array::from(vec![30]) as &dyn Array
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn compaction_threshold() -> Result<()> {
+        let batch = batch();
+        let schema = batch.schema();
+        let a_gt_3 = binary(col("a", &schema)?, Operator::Gt, lit(3), &schema)?;
+
+        let selected = SelectionBatch::new(batch.clone()).filter(&a_gt_3, 0.2)?;
+        assert!(selected.selection().is_some());
+
+        let selected = SelectionBatch::new(batch).filter(&a_gt_3, 0.5)?;
+        assert!(selected.selection().is_none());
+        assert_eq!(selected.batch().num_rows(), 1);
+        Ok(())
+    }
+}
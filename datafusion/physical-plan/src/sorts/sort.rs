@@ -28,13 +28,14 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::common::{spawn_buffered, IPCWriter};
+use crate::dynamic_filter::DynamicFilter;
 use crate::expressions::PhysicalSortExpr;
 use crate::metrics::{
     BaselineMetrics, Count, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet,
 };
 use crate::sorts::streaming_merge::streaming_merge;
 use crate::stream::{RecordBatchReceiverStream, RecordBatchStreamAdapter};
-use crate::topk::TopK;
+use crate::topk::{TopK, TopKDynamicFilter};
 use crate::{
     DisplayAs, DisplayFormatType, Distribution, EmptyRecordBatchStream, ExecutionPlan,
     Partitioning, SendableRecordBatchStream, Statistics,
@@ -659,6 +660,21 @@ fn read_spill(sender: Sender<Result<RecordBatch>>, path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Returns the [`RuntimeEnv`] of `context`, reserving memory from the
+/// memory pool of the query instead of the pool of the runtime
+fn query_runtime_env(context: &TaskContext) -> Arc<RuntimeEnv> {
+    let runtime = context.runtime_env();
+    if Arc::ptr_eq(&runtime.memory_pool, context.memory_pool()) {
+        return runtime;
+    }
+    Arc::new(RuntimeEnv {
+        memory_pool: Arc::clone(context.memory_pool()),
+        disk_manager: Arc::clone(&runtime.disk_manager),
+        cache_manager: Arc::clone(&runtime.cache_manager),
+        object_store_registry: Arc::clone(&runtime.object_store_registry),
+    })
+}
+
 /// Sort execution plan.
 ///
 /// Support sorting datasets that are larger than the memory allotted
@@ -676,6 +692,8 @@ pub struct SortExec {
     preserve_partitioning: bool,
     /// Fetch highest/lowest n results
     fetch: Option<usize>,
+    /// Threshold of the top `fetch` rows, published while executing
+    dynamic_filter: Option<Arc<TopKDynamicFilter>>,
 }
 
 impl SortExec {
@@ -698,6 +716,7 @@ impl SortExec {
             metrics_set: ExecutionPlanMetricsSet::new(),
             preserve_partitioning: false,
             fetch: None,
+            dynamic_filter: None,
         }
     }
 
@@ -744,6 +763,9 @@ impl SortExec {
     /// can be dropped.
     pub fn with_fetch(mut self, fetch: Option<usize>) -> Self {
         self.fetch = fetch;
+        self.dynamic_filter = fetch
+            .and_then(|_| TopKDynamicFilter::try_new(&self.expr, &self.input.schema()))
+            .map(Arc::new);
         self
     }
 
@@ -761,6 +783,15 @@ impl SortExec {
     pub fn fetch(&self) -> Option<usize> {
         self.fetch
     }
+
+    /// The threshold of the top `fetch` rows as a predicate on the first
+    /// sort key, which is tightened while the sort executes. Scans below
+    /// the sort can use it to skip rows that cannot be part of the result.
+    ///
+    /// Only set if `fetch` is set and the first sort key is a column.
+    pub fn dynamic_filter(&self) -> Option<&DynamicFilter> {
+        self.dynamic_filter.as_ref().map(|f| f.filter())
+    }
 }
 
 impl DisplayAs for SortExec {
@@ -845,9 +876,11 @@ impl ExecutionPlan for SortExec {
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        let new_sort = SortExec::new(self.expr.clone(), children[0].clone())
+        let mut new_sort = SortExec::new(self.expr.clone(), children[0].clone())
             .with_fetch(self.fetch)
             .with_preserve_partitioning(self.preserve_partitioning);
+        // keep publishing to the scans the filter was pushed down to
+        new_sort.dynamic_filter = self.dynamic_filter.clone();
 
         Ok(Arc::new(new_sort))
     }
@@ -872,10 +905,11 @@ impl ExecutionPlan for SortExec {
                 self.expr.clone(),
                 *fetch,
                 context.session_config().batch_size(),
-                context.runtime_env(),
+                query_runtime_env(&context),
                 &self.metrics_set,
                 partition,
-            )?;
+            )?
+            .with_dynamic_filter(self.dynamic_filter.clone());
 
             Ok(Box::pin(RecordBatchStreamAdapter::new(
                 self.schema(),
@@ -898,7 +932,7 @@ impl ExecutionPlan for SortExec {
                 execution_options.sort_spill_reservation_bytes,
                 execution_options.sort_in_place_threshold_bytes,
                 &self.metrics_set,
-                context.runtime_env(),
+                query_runtime_env(&context),
             );
 
             Ok(Box::pin(RecordBatchStreamAdapter::new(
@@ -1138,6 +1172,83 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_sort_fetch_dynamic_filter() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let batch = |a: Vec<Option<i32>>| {
+            let b: StringArray = a.iter().map(|a| a.map(|a| a.to_string())).collect();
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int32Array::from(a)), Arc::new(b)],
+            )
+            .unwrap()
+        };
+        let partitions = vec![
+            vec![batch(vec![Some(5), None, Some(8)]), batch(vec![Some(2)])],
+            vec![batch(vec![Some(7), Some(1), Some(9), Some(4)])],
+        ];
+        let input = Arc::new(MemoryExec::try_new(&partitions, schema.clone(), None)?);
+
+        let sort = |options: SortOptions, input: Arc<dyn ExecutionPlan>| {
+            SortExec::new(
+                vec![PhysicalSortExpr {
+                    expr: col("a", &schema).unwrap(),
+                    options,
+                }],
+                input,
+            )
+        };
+
+        // no filter without a fetch
+        assert!(sort(SortOptions::default(), input.clone())
+            .dynamic_filter()
+            .is_none());
+
+        let cases = [
+            (
+                SortOptions {
+                    descending: false,
+                    nulls_first: false,
+                },
+                "a@0 < 4",
+            ),
+            (
+                SortOptions {
+                    descending: true,
+                    nulls_first: false,
+                },
+                "a@0 > 7",
+            ),
+            (
+                SortOptions {
+                    descending: false,
+                    nulls_first: true,
+                },
+                "a@0 IS NULL OR a@0 < 2",
+            ),
+        ];
+        for (options, expected) in cases {
+            let sort_exec = Arc::new(
+                sort(options, input.clone())
+                    .with_fetch(Some(2))
+                    .with_preserve_partitioning(true),
+            );
+            let filter = sort_exec.dynamic_filter().unwrap().clone();
+            assert!(filter.current()?.is_none());
+
+            collect(sort_exec, task_ctx.clone()).await?;
+
+            // the lowest threshold of the two partitions
+            assert_eq!(filter.current()?.unwrap().to_string(), expected);
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_lex_sort_by_float() -> Result<()> {
         let task_ctx = Arc::new(TaskContext::default());
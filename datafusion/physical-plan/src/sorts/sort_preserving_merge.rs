@@ -24,6 +24,7 @@ use crate::common::spawn_buffered;
 use crate::expressions::PhysicalSortExpr;
 use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
 use crate::sorts::streaming_merge;
+use crate::stream::RecordBatchStreamAdapter;
 use crate::{
     DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, Partitioning,
     SendableRecordBatchStream, Statistics,
@@ -35,6 +36,7 @@ use datafusion_execution::memory_pool::MemoryConsumer;
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::{EquivalenceProperties, PhysicalSortRequirement};
 
+use futures::StreamExt;
 use log::{debug, trace};
 
 /// Sort preserving merge execution plan
@@ -106,6 +108,17 @@ impl SortPreservingMergeExec {
     pub fn fetch(&self) -> Option<usize> {
         self.fetch
     }
+
+    /// Whether the input partitions are sorted ranges of the output, as
+    /// produced by a [`Partitioning::Range`] on the same expressions, so that
+    /// they can be concatenated in order instead of merged
+    fn concatenates_ranges(&self) -> bool {
+        self.fetch.is_none()
+            && matches!(
+                self.input.output_partitioning(),
+                Partitioning::Range(exprs, _) if exprs == self.expr
+            )
+    }
 }
 
 impl DisplayAs for SortPreservingMergeExec {
@@ -215,7 +228,7 @@ impl ExecutionPlan for SortPreservingMergeExec {
 
         let reservation =
             MemoryConsumer::new(format!("SortPreservingMergeExec[{partition}]"))
-                .register(&context.runtime_env().memory_pool);
+                .register(context.memory_pool());
 
         match input_partitions {
             0 => internal_err!(
@@ -227,6 +240,28 @@ impl ExecutionPlan for SortPreservingMergeExec {
                 debug!("Done getting stream for SortPreservingMergeExec::execute with 1 input");
                 result
             }
+            _ if self.concatenates_ranges() => {
+                // start all input partitions, but read them one after the other
+                let receivers = (0..input_partitions)
+                    .map(|partition| {
+                        let stream = self.input.execute(partition, context.clone())?;
+                        Ok(spawn_buffered(stream, 1))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                debug!("Concatenating the ranges for SortPreservingMergeExec::execute");
+
+                let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+                let stream =
+                    futures::stream::iter(receivers)
+                        .flatten()
+                        .inspect(move |batch| {
+                            if let Ok(batch) = batch {
+                                baseline_metrics.record_output(batch.num_rows());
+                            }
+                        });
+                Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+            }
             _ => {
                 let receivers = (0..input_partitions)
                     .map(|partition| {
@@ -272,6 +307,7 @@ mod tests {
     use crate::expressions::col;
     use crate::memory::MemoryExec;
     use crate::metrics::{MetricValue, Timestamp};
+    use crate::repartition::RepartitionExec;
     use crate::sorts::sort::SortExec;
     use crate::stream::RecordBatchReceiverStream;
     use crate::test::exec::{assert_strong_count_converges_to_zero, BlockingExec};
@@ -604,6 +640,47 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_range_partition_sort() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let csv = test::scan_partitioned(3);
+        let schema = csv.schema();
+
+        let sort = vec![PhysicalSortExpr {
+            expr: col("i", &schema).unwrap(),
+            options: SortOptions {
+                descending: true,
+                nulls_first: true,
+            },
+        }];
+
+        let basic = basic_sort(csv.clone(), sort.clone(), task_ctx.clone()).await;
+
+        // sort the ranges in parallel and concatenate them
+        let repartition = Arc::new(RepartitionExec::try_new(
+            csv,
+            Partitioning::Range(sort.clone(), 4),
+        )?);
+        let sort_exec = Arc::new(
+            SortExec::new(sort.clone(), repartition).with_preserve_partitioning(true),
+        );
+        let merge = Arc::new(SortPreservingMergeExec::new(sort, sort_exec));
+        assert!(merge.concatenates_ranges());
+        let result = collect(merge, task_ctx).await?;
+        let range = arrow::compute::concat_batches(&schema, &result)?;
+
+        let basic = arrow::util::pretty::pretty_format_batches(&[basic])
+            .unwrap()
+            .to_string();
+        let range = arrow::util::pretty::pretty_format_batches(&[range])
+            .unwrap()
+            .to_string();
+
+        assert_eq!(basic, range, "basic:\n\n{basic}\n\nrange:\n\n{range}\n\n");
+
+        Ok(())
+    }
+
     // Split the provided record batch into multiple batch_size record batches
     fn split_batch(sorted: &RecordBatch, batch_size: usize) -> Vec<RecordBatch> {
         let batches = (sorted.num_rows() + batch_size - 1) / batch_size;
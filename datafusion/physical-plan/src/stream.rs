@@ -27,6 +27,7 @@ use arrow::{datatypes::SchemaRef, record_batch::RecordBatch};
 use datafusion_common::DataFusionError;
 use datafusion_common::Result;
 use datafusion_common::{exec_err, internal_err};
+use datafusion_execution::cancellation::CancellationToken;
 use datafusion_execution::TaskContext;
 use futures::stream::BoxStream;
 use futures::{Future, Stream, StreamExt};
@@ -49,7 +50,6 @@ use super::{ExecutionPlan, RecordBatchStream, SendableRecordBatchStream};
 /// 3. Automatically cancels any outstanding tasks when the receiver stream is dropped.
 ///
 /// [`ReceiverStream` from tokio-stream]: https://docs.rs/tokio-stream/latest/tokio_stream/wrappers/struct.ReceiverStream.html
-
 pub(crate) struct ReceiverStreamBuilder<O> {
     tx: Sender<Result<O>>,
     rx: Receiver<Result<O>>,
@@ -407,12 +407,85 @@ impl futures::Stream for ObservedStream {
     }
 }
 
+/// The number of batches a [`CooperativeStream`] produces before it
+/// yields to the runtime
+const COOPERATIVE_BUDGET: usize = 16;
+
+/// Stream wrapper that makes the operators above it cooperate with the
+/// runtime and stop when the query is cancelled.
+///
+/// Streams that never return [`Poll::Pending`], such as the streams of
+/// in-memory sources, let CPU-bound operators above them loop without ever
+/// yielding, so that neither tokio timeouts nor other tasks on the same
+/// worker thread get a chance to run. This stream returns
+/// [`Poll::Pending`] after [`COOPERATIVE_BUDGET`] consecutive batches and
+/// checks the [`CancellationToken`] of the query before every batch.
+///
+/// [`CancellationToken`]: datafusion_execution::cancellation::CancellationToken
+pub struct CooperativeStream {
+    inner: SendableRecordBatchStream,
+    cancellation_token: CancellationToken,
+    /// Batches left before the next yield
+    budget: usize,
+    /// Set once the cancellation error was returned
+    done: bool,
+}
+
+impl CooperativeStream {
+    /// Wrap `inner`, observing the cancellation token of `context`
+    pub fn new(inner: SendableRecordBatchStream, context: &TaskContext) -> Self {
+        Self {
+            inner,
+            cancellation_token: context.cancellation_token().clone(),
+            budget: COOPERATIVE_BUDGET,
+            done: false,
+        }
+    }
+}
+
+impl RecordBatchStream for CooperativeStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+impl Stream for CooperativeStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        if let Err(e) = self.cancellation_token.check() {
+            self.done = true;
+            return Poll::Ready(Some(Err(e)));
+        }
+        if self.budget == 0 {
+            self.budget = COOPERATIVE_BUDGET;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        let poll = self.inner.poll_next_unpin(cx);
+        match &poll {
+            Poll::Ready(Some(_)) => self.budget -= 1,
+            Poll::Ready(None) => {}
+            Poll::Pending => self.budget = COOPERATIVE_BUDGET,
+        }
+        poll
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use arrow_schema::{DataType, Field, Schema};
     use datafusion_common::exec_err;
 
+    use crate::memory::MemoryStream;
     use crate::test::exec::{
         assert_strong_count_converges_to_zero, BlockingExec, MockExec, PanicExec,
     };
@@ -499,6 +572,51 @@ mod test {
         assert!(stream.next().await.is_none());
     }
 
+    #[test]
+    fn cooperative_stream_yields() -> Result<()> {
+        let schema = schema();
+        let batch = RecordBatch::new_empty(schema.clone());
+        let input = MemoryStream::try_new(vec![batch; 20], schema, None)?;
+        let mut stream = CooperativeStream::new(Box::pin(input), &TaskContext::default());
+
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut batches = 0;
+        let mut pending = 0;
+        loop {
+            match stream.poll_next_unpin(&mut cx) {
+                Poll::Ready(Some(batch)) => {
+                    batch?;
+                    batches += 1;
+                }
+                Poll::Ready(None) => break,
+                Poll::Pending => pending += 1,
+            }
+        }
+
+        // yields once after the first COOPERATIVE_BUDGET batches
+        assert_eq!(batches, 20);
+        assert_eq!(pending, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cooperative_stream_cancelled() -> Result<()> {
+        let schema = schema();
+        let batch = RecordBatch::new_empty(schema.clone());
+        let input = MemoryStream::try_new(vec![batch; 20], schema, None)?;
+        let task_ctx = TaskContext::default();
+        let mut stream = CooperativeStream::new(Box::pin(input), &task_ctx);
+
+        stream.next().await.unwrap()?;
+        task_ctx.cancellation_token().cancel();
+
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(matches!(err, DataFusionError::Cancelled(_)));
+        assert!(stream.next().await.is_none());
+        Ok(())
+    }
+
     /// Consumes all the input's partitions into a
     /// RecordBatchReceiverStream and runs it to completion
     ///
@@ -22,7 +22,7 @@ use std::sync::Arc;
 
 use super::{DisplayAs, DisplayFormatType};
 use crate::display::{OutputOrderingDisplay, ProjectSchemaDisplay};
-use crate::stream::RecordBatchStreamAdapter;
+use crate::stream::{CooperativeStream, RecordBatchStreamAdapter};
 use crate::{ExecutionPlan, Partitioning, SendableRecordBatchStream};
 
 use arrow::datatypes::SchemaRef;
@@ -217,7 +217,9 @@ impl ExecutionPlan for StreamingTableExec {
         partition: usize,
         ctx: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let stream = self.partitions[partition].execute(ctx);
+        let stream = self.partitions[partition].execute(ctx.clone());
+        let stream: SendableRecordBatchStream =
+            Box::pin(CooperativeStream::new(stream, &ctx));
         Ok(match self.projection.clone() {
             Some(projection) => Box::pin(RecordBatchStreamAdapter::new(
                 self.projected_schema.clone(),
@@ -19,20 +19,24 @@
 
 use arrow::{
     compute::interleave,
-    row::{RowConverter, Rows, SortField},
+    row::{OwnedRow, RowConverter, Rows, SortField},
 };
 use std::{cmp::Ordering, collections::BinaryHeap, sync::Arc};
 
 use arrow_array::{Array, ArrayRef, RecordBatch};
-use arrow_schema::SchemaRef;
-use datafusion_common::Result;
+use arrow_schema::{DataType, Schema, SchemaRef, SortOptions};
+use datafusion_common::{Result, ScalarValue};
 use datafusion_execution::{
     memory_pool::{MemoryConsumer, MemoryReservation},
     runtime_env::RuntimeEnv,
 };
-use datafusion_physical_expr::PhysicalSortExpr;
+use datafusion_expr::Operator;
+use datafusion_physical_expr::expressions::{lit, BinaryExpr, Column, IsNullExpr};
+use datafusion_physical_expr::{PhysicalExpr, PhysicalSortExpr};
 use hashbrown::HashMap;
+use parking_lot::Mutex;
 
+use crate::dynamic_filter::DynamicFilter;
 use crate::{stream::RecordBatchStreamAdapter, SendableRecordBatchStream};
 
 use super::metrics::{BaselineMetrics, Count, ExecutionPlanMetricsSet, MetricBuilder};
@@ -89,6 +93,8 @@ pub struct TopK {
     scratch_rows: Rows,
     /// stores the top k values and their sort key values, in order
     heap: TopKHeap,
+    /// publishes the threshold of the heap, if any
+    dynamic_filter: Option<Arc<TopKDynamicFilter>>,
 }
 
 impl TopK {
@@ -139,9 +145,19 @@ impl TopK {
             row_converter,
             scratch_rows,
             heap: TopKHeap::new(k, batch_size, schema),
+            dynamic_filter: None,
         })
     }
 
+    /// Publish the threshold of this [`TopK`] to `dynamic_filter`
+    pub(crate) fn with_dynamic_filter(
+        mut self,
+        dynamic_filter: Option<Arc<TopKDynamicFilter>>,
+    ) -> Self {
+        self.dynamic_filter = dynamic_filter;
+        self
+    }
+
     /// Insert `batch`, remembering if any of its values are among
     /// the top k seen so far.
     pub fn insert_batch(&mut self, batch: RecordBatch) -> Result<()> {
@@ -185,6 +201,31 @@ impl TopK {
 
         // update memory reservation
         self.reservation.try_resize(self.size())?;
+
+        self.update_dynamic_filter()
+    }
+
+    /// Publish the largest of the top k rows, if the heap is full and it
+    /// is lower than the threshold published by other partitions
+    fn update_dynamic_filter(&self) -> Result<()> {
+        let (Some(dynamic_filter), Some(max_row)) =
+            (&self.dynamic_filter, self.heap.max())
+        else {
+            return Ok(());
+        };
+        let parser = self.row_converter.parser();
+        let row = parser.parse(max_row.row());
+
+        let mut threshold = dynamic_filter.threshold.lock();
+        if matches!(&*threshold, Some(t) if t.row() <= row) {
+            return Ok(());
+        }
+        let sort_keys = self.row_converter.convert_rows([row])?;
+        let value = ScalarValue::try_from_array(&sort_keys[0], 0)?;
+        if let Some(predicate) = dynamic_filter.predicate(value) {
+            dynamic_filter.filter.update(predicate);
+        }
+        *threshold = Some(row.owned());
         Ok(())
     }
 
@@ -199,6 +240,7 @@ impl TopK {
             row_converter: _,
             scratch_rows: _,
             mut heap,
+            dynamic_filter: _,
         } = self;
         let _timer = metrics.baseline.elapsed_compute().timer(); // time updated on drop
 
@@ -232,6 +274,76 @@ impl TopK {
     }
 }
 
+/// Publishes the threshold of the top k rows of the partitions of a
+/// `SortExec` as a [`DynamicFilter`] on its first sort key, so that scans
+/// below it can skip rows that cannot be part of the result.
+///
+/// The threshold is the lowest of the largest rows of the heaps of the
+/// partitions, and only ever decreases.
+#[derive(Debug)]
+pub(crate) struct TopKDynamicFilter {
+    filter: DynamicFilter,
+    options: SortOptions,
+    /// True if there is a single sort key, so that rows equal to the
+    /// threshold cannot be part of the result either
+    single_key: bool,
+    threshold: Mutex<Option<OwnedRow>>,
+}
+
+impl TopKDynamicFilter {
+    /// Create a [`TopKDynamicFilter`] for sorting rows of `schema` by `expr`,
+    /// if the first sort key is a column whose values can be compared to a
+    /// literal
+    pub(crate) fn try_new(expr: &[PhysicalSortExpr], schema: &Schema) -> Option<Self> {
+        let first = expr.first()?;
+        let column = first.expr.as_any().downcast_ref::<Column>()?;
+        let data_type = schema.fields().get(column.index())?.data_type();
+        let supported = (data_type.is_primitive() && data_type != &DataType::Float16)
+            || matches!(
+                data_type,
+                DataType::Boolean
+                    | DataType::Utf8
+                    | DataType::LargeUtf8
+                    | DataType::Binary
+                    | DataType::LargeBinary
+            );
+        supported.then(|| Self {
+            filter: DynamicFilter::new(vec![column.clone()]),
+            options: first.options,
+            single_key: expr.len() == 1,
+            threshold: Mutex::new(None),
+        })
+    }
+
+    pub(crate) fn filter(&self) -> &DynamicFilter {
+        &self.filter
+    }
+
+    /// Returns the predicate selecting the rows whose first sort key
+    /// sorts before, or with more than one key also equal to, `value`
+    fn predicate(&self, value: ScalarValue) -> Option<Arc<dyn PhysicalExpr>> {
+        let column: Arc<dyn PhysicalExpr> = Arc::new(self.filter.columns()[0].clone());
+        let is_null: Arc<dyn PhysicalExpr> = Arc::new(IsNullExpr::new(column.clone()));
+        if value.is_null() {
+            // with nulls last, all values sort before null
+            return self.options.nulls_first.then_some(is_null);
+        }
+        let op = match (self.options.descending, self.single_key) {
+            (false, true) => Operator::Lt,
+            (false, false) => Operator::LtEq,
+            (true, true) => Operator::Gt,
+            (true, false) => Operator::GtEq,
+        };
+        let cmp: Arc<dyn PhysicalExpr> =
+            Arc::new(BinaryExpr::new(column, op, lit(value)));
+        Some(if self.options.nulls_first {
+            Arc::new(BinaryExpr::new(is_null, Operator::Or, cmp))
+        } else {
+            cmp
+        })
+    }
+}
+
 struct TopKMetrics {
     /// metrics
     pub baseline: BaselineMetrics,
@@ -23,8 +23,8 @@ use std::sync::Arc;
 use super::expressions::PhysicalSortExpr;
 use super::{common, DisplayAs, SendableRecordBatchStream, Statistics};
 use crate::{
-    memory::MemoryStream, ColumnarValue, DisplayFormatType, ExecutionPlan, Partitioning,
-    PhysicalExpr,
+    memory::MemoryStream, stream::CooperativeStream, ColumnarValue, DisplayFormatType,
+    ExecutionPlan, Partitioning, PhysicalExpr,
 };
 
 use arrow::array::new_null_array;
@@ -172,7 +172,7 @@ impl ExecutionPlan for ValuesExec {
     fn execute(
         &self,
         partition: usize,
-        _context: Arc<TaskContext>,
+        context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         // GlobalLimitExec has a single output partition
         if 0 != partition {
@@ -181,11 +181,8 @@ impl ExecutionPlan for ValuesExec {
             );
         }
 
-        Ok(Box::pin(MemoryStream::try_new(
-            self.data(),
-            self.schema.clone(),
-            None,
-        )?))
+        let stream = MemoryStream::try_new(self.data(), self.schema.clone(), None)?;
+        Ok(Box::pin(CooperativeStream::new(Box::pin(stream), &context)))
     }
 
     fn statistics(&self) -> Result<Statistics> {
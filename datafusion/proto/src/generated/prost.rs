@@ -483,6 +483,8 @@ pub struct JoinNode {
     pub null_equals_null: bool,
     #[prost(message, optional, tag = "8")]
     pub filter: ::core::option::Option<LogicalExprNode>,
+    #[prost(bool, tag = "9")]
+    pub null_aware: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -2105,6 +2107,18 @@ pub struct FileScanExecConf {
     pub object_store_url: ::prost::alloc::string::String,
     #[prost(message, repeated, tag = "9")]
     pub output_ordering: ::prost::alloc::vec::Vec<PhysicalSortExprNodeCollection>,
+    /// The names of the metadata columns, e.g. `_file_path`
+    #[prost(string, repeated, tag = "11")]
+    pub metadata_cols: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// The paths of the nested fields to read
+    #[prost(message, repeated, tag = "12")]
+    pub projection_paths: ::prost::alloc::vec::Vec<ProjectionPath>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProjectionPath {
+    #[prost(string, repeated, tag = "1")]
+    pub path: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -2160,6 +2174,8 @@ pub struct HashJoinExecNode {
     pub null_equals_null: bool,
     #[prost(message, optional, tag = "8")]
     pub filter: ::core::option::Option<JoinFilter>,
+    #[prost(bool, tag = "9")]
+    pub null_aware: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -2409,10 +2425,18 @@ pub struct PhysicalHashRepartition {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PhysicalRangeRepartition {
+    #[prost(message, repeated, tag = "1")]
+    pub sort_expr: ::prost::alloc::vec::Vec<PhysicalSortExprNode>,
+    #[prost(uint64, tag = "2")]
+    pub partition_count: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RepartitionExecNode {
     #[prost(message, optional, boxed, tag = "1")]
     pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
-    #[prost(oneof = "repartition_exec_node::PartitionMethod", tags = "2, 3, 4")]
+    #[prost(oneof = "repartition_exec_node::PartitionMethod", tags = "2, 3, 4, 5")]
     pub partition_method: ::core::option::Option<repartition_exec_node::PartitionMethod>,
 }
 /// Nested message and enum types in `RepartitionExecNode`.
@@ -2426,6 +2450,8 @@ pub mod repartition_exec_node {
         Hash(super::PhysicalHashRepartition),
         #[prost(uint64, tag = "4")]
         Unknown(u64),
+        #[prost(message, tag = "5")]
+        Range(super::PhysicalRangeRepartition),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2754,6 +2780,9 @@ pub enum ScalarFunction {
     FindInSet = 127,
     ArraySort = 128,
     ArrayDistinct = 129,
+    HllEstimate = 130,
+    KllQuantile = 131,
+    TopKItems = 132,
 }
 impl ScalarFunction {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -2892,6 +2921,9 @@ impl ScalarFunction {
             ScalarFunction::FindInSet => "FindInSet",
             ScalarFunction::ArraySort => "ArraySort",
             ScalarFunction::ArrayDistinct => "ArrayDistinct",
+            ScalarFunction::HllEstimate => "HllEstimate",
+            ScalarFunction::KllQuantile => "KllQuantile",
+            ScalarFunction::TopKItems => "TopKItems",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -3027,6 +3059,9 @@ impl ScalarFunction {
             "FindInSet" => Some(Self::FindInSet),
             "ArraySort" => Some(Self::ArraySort),
             "ArrayDistinct" => Some(Self::ArrayDistinct),
+            "HllEstimate" => Some(Self::HllEstimate),
+            "KllQuantile" => Some(Self::KllQuantile),
+            "TopKItems" => Some(Self::TopKItems),
             _ => None,
         }
     }
@@ -3072,6 +3107,11 @@ pub enum AggregateFunction {
     RegrSyy = 33,
     RegrSxy = 34,
     StringAgg = 35,
+    HllSketch = 36,
+    HllUnion = 37,
+    KllSketch = 38,
+    KllMerge = 39,
+    ApproxTopKSketch = 40,
 }
 impl AggregateFunction {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -3118,6 +3158,11 @@ impl AggregateFunction {
             AggregateFunction::RegrSyy => "REGR_SYY",
             AggregateFunction::RegrSxy => "REGR_SXY",
             AggregateFunction::StringAgg => "STRING_AGG",
+            AggregateFunction::HllSketch => "HLL_SKETCH",
+            AggregateFunction::HllUnion => "HLL_UNION",
+            AggregateFunction::KllSketch => "KLL_SKETCH",
+            AggregateFunction::KllMerge => "KLL_MERGE",
+            AggregateFunction::ApproxTopKSketch => "APPROX_TOP_K_SKETCH",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -3161,6 +3206,11 @@ impl AggregateFunction {
             "REGR_SYY" => Some(Self::RegrSyy),
             "REGR_SXY" => Some(Self::RegrSxy),
             "STRING_AGG" => Some(Self::StringAgg),
+            "HLL_SKETCH" => Some(Self::HllSketch),
+            "HLL_UNION" => Some(Self::HllUnion),
+            "KLL_SKETCH" => Some(Self::KllSketch),
+            "KLL_MERGE" => Some(Self::KllMerge),
+            "APPROX_TOP_K_SKETCH" => Some(Self::ApproxTopKSketch),
             _ => None,
         }
     }
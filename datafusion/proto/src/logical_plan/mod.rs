@@ -707,7 +707,11 @@ impl AsLogicalPlan for LogicalPlanNode {
                     }
                 };
 
-                builder.build()
+                let mut plan = builder.build()?;
+                if let LogicalPlan::Join(join_plan) = &mut plan {
+                    join_plan.null_aware = join.null_aware;
+                }
+                Ok(plan)
             }
             LogicalPlanType::Union(union) => {
                 let mut input_plans: Vec<LogicalPlan> = union
@@ -1228,6 +1232,7 @@ impl AsLogicalPlan for LogicalPlanNode {
                 join_type,
                 join_constraint,
                 null_equals_null,
+                null_aware,
                 ..
             }) => {
                 let left: protobuf::LogicalPlanNode =
@@ -1264,6 +1269,7 @@ impl AsLogicalPlan for LogicalPlanNode {
                             right_join_key,
                             null_equals_null: *null_equals_null,
                             filter,
+                            null_aware: *null_aware,
                         },
                     ))),
                 })
@@ -409,6 +409,11 @@ impl From<&AggregateFunction> for protobuf::AggregateFunction {
             AggregateFunction::FirstValue => Self::FirstValueAgg,
             AggregateFunction::LastValue => Self::LastValueAgg,
             AggregateFunction::StringAgg => Self::StringAgg,
+            AggregateFunction::HllSketch => Self::HllSketch,
+            AggregateFunction::HllUnion => Self::HllUnion,
+            AggregateFunction::KllSketch => Self::KllSketch,
+            AggregateFunction::KllMerge => Self::KllMerge,
+            AggregateFunction::ApproxTopKSketch => Self::ApproxTopKSketch,
         }
     }
 }
@@ -730,6 +735,21 @@ impl TryFrom<&Expr> for protobuf::LogicalExprNode {
                             AggregateFunction::StringAgg => {
                                 protobuf::AggregateFunction::StringAgg
                             }
+                            AggregateFunction::HllSketch => {
+                                protobuf::AggregateFunction::HllSketch
+                            }
+                            AggregateFunction::HllUnion => {
+                                protobuf::AggregateFunction::HllUnion
+                            }
+                            AggregateFunction::KllSketch => {
+                                protobuf::AggregateFunction::KllSketch
+                            }
+                            AggregateFunction::KllMerge => {
+                                protobuf::AggregateFunction::KllMerge
+                            }
+                            AggregateFunction::ApproxTopKSketch => {
+                                protobuf::AggregateFunction::ApproxTopKSketch
+                            }
                         };
 
                         let aggregate_expr = protobuf::AggregateExprNode {
@@ -1596,6 +1616,9 @@ impl TryFrom<&BuiltinScalarFunction> for protobuf::ScalarFunction {
             BuiltinScalarFunction::Levenshtein => Self::Levenshtein,
             BuiltinScalarFunction::SubstrIndex => Self::SubstrIndex,
             BuiltinScalarFunction::FindInSet => Self::FindInSet,
+            BuiltinScalarFunction::HllEstimate => Self::HllEstimate,
+            BuiltinScalarFunction::KllQuantile => Self::KllQuantile,
+            BuiltinScalarFunction::TopKItems => Self::TopKItems,
         };
 
         Ok(scalar_function)
@@ -28,7 +28,9 @@ use datafusion::datasource::file_format::json::JsonSink;
 use datafusion::datasource::file_format::parquet::ParquetSink;
 use datafusion::datasource::listing::{FileRange, ListingTableUrl, PartitionedFile};
 use datafusion::datasource::object_store::ObjectStoreUrl;
-use datafusion::datasource::physical_plan::{FileScanConfig, FileSinkConfig};
+use datafusion::datasource::physical_plan::{
+    FileScanConfig, FileSinkConfig, MetadataColumn,
+};
 use datafusion::execution::context::ExecutionProps;
 use datafusion::execution::FunctionRegistry;
 use datafusion::logical_expr::WindowFunctionDefinition;
@@ -502,6 +504,15 @@ pub fn parse_protobuf_file_scan_config(
         .map(|col| Ok(schema.field_with_name(col)?.clone()))
         .collect::<Result<Vec<_>>>()?;
 
+    let metadata_cols = proto
+        .metadata_cols
+        .iter()
+        .map(|name| {
+            MetadataColumn::from_name(name)
+                .ok_or_else(|| proto_error(format!("Unknown metadata column {name}")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
     let mut output_ordering = vec![];
     for node_collection in &proto.output_ordering {
         let sort_expr = node_collection
@@ -534,6 +545,13 @@ pub fn parse_protobuf_file_scan_config(
         limit: proto.limit.as_ref().map(|sl| sl.limit as usize),
         table_partition_cols,
         output_ordering,
+        schema_adapter_factory: None,
+        metadata_cols,
+        projection_paths: proto
+            .projection_paths
+            .iter()
+            .map(|p| p.path.clone())
+            .collect(),
     })
 }
 
@@ -274,6 +274,27 @@ impl AsExecutionPlan for PhysicalPlanNode {
                             ),
                         )?))
                     }
+                    Some(PartitionMethod::Range(ref range_part)) => {
+                        let sort_exprs = range_part
+                            .sort_expr
+                            .iter()
+                            .map(|e| {
+                                parse_physical_sort_expr(
+                                    e,
+                                    registry,
+                                    input.schema().as_ref(),
+                                )
+                            })
+                            .collect::<Result<Vec<_>>>()?;
+
+                        Ok(Arc::new(RepartitionExec::try_new(
+                            input,
+                            Partitioning::Range(
+                                sort_exprs,
+                                range_part.partition_count.try_into().unwrap(),
+                            ),
+                        )?))
+                    }
                     _ => internal_err!("Invalid partitioning scheme"),
                 }
             }
@@ -571,15 +592,18 @@ impl AsExecutionPlan for PhysicalPlanNode {
                     protobuf::PartitionMode::Partitioned => PartitionMode::Partitioned,
                     protobuf::PartitionMode::Auto => PartitionMode::Auto,
                 };
-                Ok(Arc::new(HashJoinExec::try_new(
-                    left,
-                    right,
-                    on,
-                    filter,
-                    &join_type.into(),
-                    partition_mode,
-                    hashjoin.null_equals_null,
-                )?))
+                Ok(Arc::new(
+                    HashJoinExec::try_new(
+                        left,
+                        right,
+                        on,
+                        filter,
+                        &join_type.into(),
+                        partition_mode,
+                        hashjoin.null_equals_null,
+                    )?
+                    .with_null_aware(hashjoin.null_aware)?,
+                ))
             }
             PhysicalPlanType::SymmetricHashJoin(sym_join) => {
                 let left = into_physical_plan(
@@ -1170,6 +1194,7 @@ impl AsExecutionPlan for PhysicalPlanNode {
                         partition_mode: partition_mode.into(),
                         null_equals_null: exec.null_equals_null(),
                         filter,
+                        null_aware: exec.null_aware(),
                     },
                 ))),
             });
@@ -1470,6 +1495,15 @@ impl AsExecutionPlan for PhysicalPlanNode {
                 Partitioning::UnknownPartitioning(partition_count) => {
                     PartitionMethod::Unknown(*partition_count as u64)
                 }
+                Partitioning::Range(sort_exprs, partition_count) => {
+                    PartitionMethod::Range(protobuf::PhysicalRangeRepartition {
+                        sort_expr: sort_exprs
+                            .iter()
+                            .map(|expr| expr.clone().try_into())
+                            .collect::<Result<Vec<_>>>()?,
+                        partition_count: *partition_count as u64,
+                    })
+                }
             };
 
             return Ok(protobuf::PhysicalPlanNode {
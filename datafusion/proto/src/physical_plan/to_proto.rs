@@ -30,6 +30,8 @@ use crate::protobuf::{
 
 #[cfg(feature = "parquet")]
 use datafusion::datasource::file_format::parquet::ParquetSink;
+#[cfg(feature = "orc")]
+use datafusion_common::file_options::orc_writer::OrcWriterOptions;
 
 use crate::logical_plan::{csv_writer_options_to_proto, writer_properties_to_proto};
 use datafusion::datasource::{
@@ -45,13 +47,13 @@ use datafusion::physical_expr::window::{NthValueKind, SlidingAggregateWindowExpr
 use datafusion::physical_expr::{PhysicalSortExpr, ScalarFunctionExpr};
 use datafusion::physical_plan::expressions::{
     ApproxDistinct, ApproxMedian, ApproxPercentileCont, ApproxPercentileContWithWeight,
-    ArrayAgg, Avg, BinaryExpr, BitAnd, BitOr, BitXor, BoolAnd, BoolOr, CaseExpr,
-    CastExpr, Column, Correlation, Count, Covariance, CovariancePop, CumeDist,
+    ApproxTopKSketch, ArrayAgg, Avg, BinaryExpr, BitAnd, BitOr, BitXor, BoolAnd, BoolOr,
+    CaseExpr, CastExpr, Column, Correlation, Count, Covariance, CovariancePop, CumeDist,
     DistinctArrayAgg, DistinctBitXor, DistinctCount, DistinctSum, FirstValue, Grouping,
-    InListExpr, IsNotNullExpr, IsNullExpr, LastValue, LikeExpr, Literal, Max, Median,
-    Min, NegativeExpr, NotExpr, NthValue, Ntile, OrderSensitiveArrayAgg, Rank, RankType,
-    Regr, RegrType, RowNumber, Stddev, StddevPop, Sum, TryCastExpr, Variance,
-    VariancePop, WindowShift,
+    HllSketch, HllUnion, InListExpr, IsNotNullExpr, IsNullExpr, KllMerge, KllSketch,
+    LastValue, LikeExpr, Literal, Max, Median, Min, NegativeExpr, NotExpr, NthValue,
+    Ntile, OrderSensitiveArrayAgg, Rank, RankType, Regr, RegrType, RowNumber, Stddev,
+    StddevPop, Sum, TryCastExpr, Variance, VariancePop, WindowShift,
 };
 use datafusion::physical_plan::udaf::AggregateFunctionExpr;
 use datafusion::physical_plan::windows::{BuiltInWindowExpr, PlainAggregateWindowExpr};
@@ -363,6 +365,16 @@ fn aggr_expr_to_aggr_fn(expr: &dyn AggregateExpr) -> Result<AggrFn> {
         protobuf::AggregateFunction::FirstValueAgg
     } else if aggr_expr.downcast_ref::<LastValue>().is_some() {
         protobuf::AggregateFunction::LastValueAgg
+    } else if aggr_expr.downcast_ref::<HllSketch>().is_some() {
+        protobuf::AggregateFunction::HllSketch
+    } else if aggr_expr.downcast_ref::<HllUnion>().is_some() {
+        protobuf::AggregateFunction::HllUnion
+    } else if aggr_expr.downcast_ref::<KllSketch>().is_some() {
+        protobuf::AggregateFunction::KllSketch
+    } else if aggr_expr.downcast_ref::<KllMerge>().is_some() {
+        protobuf::AggregateFunction::KllMerge
+    } else if aggr_expr.downcast_ref::<ApproxTopKSketch>().is_some() {
+        protobuf::AggregateFunction::ApproxTopKSketch
     } else {
         return not_impl_err!("Aggregate function not supported: {expr:?}");
     };
@@ -709,6 +721,9 @@ impl TryFrom<&FileScanConfig> for protobuf::FileScanExecConf {
     fn try_from(
         conf: &FileScanConfig,
     ) -> Result<protobuf::FileScanExecConf, Self::Error> {
+        if conf.schema_adapter_factory.is_some() {
+            return not_impl_err!("SchemaAdapterFactory protobuf serialization");
+        }
         let file_groups = conf
             .file_groups
             .iter()
@@ -755,6 +770,16 @@ impl TryFrom<&FileScanConfig> for protobuf::FileScanExecConf {
                     physical_sort_expr_nodes: e,
                 })
                 .collect::<Vec<_>>(),
+            metadata_cols: conf
+                .metadata_cols
+                .iter()
+                .map(|col| col.name().to_owned())
+                .collect(),
+            projection_paths: conf
+                .projection_paths
+                .iter()
+                .map(|path| protobuf::ProjectionPath { path: path.clone() })
+                .collect(),
         })
     }
 }
@@ -919,12 +944,16 @@ impl TryFrom<&FileTypeWriterOptions> for protobuf::FileTypeWriterOptions {
                     },
                 )
             }
-            FileTypeWriterOptions::Avro(AvroWriterOptions {}) => {
+            FileTypeWriterOptions::Avro(AvroWriterOptions { .. }) => {
                 return not_impl_err!("Avro file sink protobuf serialization")
             }
             FileTypeWriterOptions::Arrow(ArrowWriterOptions {}) => {
                 return not_impl_err!("Arrow file sink protobuf serialization")
             }
+            #[cfg(feature = "orc")]
+            FileTypeWriterOptions::Orc(OrcWriterOptions { .. }) => {
+                return not_impl_err!("ORC file sink protobuf serialization")
+            }
         };
         Ok(Self {
             file_type: Some(file_type),
@@ -28,7 +28,7 @@ use datafusion::datasource::file_format::parquet::ParquetSink;
 use datafusion::datasource::listing::{ListingTableUrl, PartitionedFile};
 use datafusion::datasource::object_store::ObjectStoreUrl;
 use datafusion::datasource::physical_plan::{
-    FileScanConfig, FileSinkConfig, ParquetExec,
+    FileScanConfig, FileSinkConfig, MetadataColumn, ParquetExec,
 };
 use datafusion::execution::context::ExecutionProps;
 use datafusion::logical_expr::{
@@ -496,6 +496,23 @@ fn roundtrip_sort_preserve_partitioning() -> Result<()> {
     ))
 }
 
+#[test]
+fn roundtrip_range_repartition() -> Result<()> {
+    let field_a = Field::new("a", DataType::Int64, false);
+    let schema = Arc::new(Schema::new(vec![field_a]));
+    let sort_exprs = vec![PhysicalSortExpr {
+        expr: col("a", &schema)?,
+        options: SortOptions {
+            descending: true,
+            nulls_first: false,
+        },
+    }];
+    roundtrip_test(Arc::new(RepartitionExec::try_new(
+        Arc::new(EmptyExec::new(schema)),
+        Partitioning::Range(sort_exprs, 4),
+    )?))
+}
+
 #[test]
 fn roundtrip_parquet_exec_with_pruning_predicate() -> Result<()> {
     let scan_config = FileScanConfig {
@@ -520,6 +537,9 @@ fn roundtrip_parquet_exec_with_pruning_predicate() -> Result<()> {
         limit: None,
         table_partition_cols: vec![],
         output_ordering: vec![],
+        schema_adapter_factory: None,
+        metadata_cols: vec![],
+        projection_paths: vec![],
     };
 
     let predicate = Arc::new(BinaryExpr::new(
@@ -534,6 +554,60 @@ fn roundtrip_parquet_exec_with_pruning_predicate() -> Result<()> {
     )))
 }
 
+#[test]
+fn roundtrip_parquet_exec_with_metadata_cols() -> Result<()> {
+    let file_schema =
+        Arc::new(Schema::new(vec![Field::new("col", DataType::Utf8, false)]));
+    let scan_config = FileScanConfig {
+        object_store_url: ObjectStoreUrl::local_filesystem(),
+        file_schema: file_schema.clone(),
+        file_groups: vec![vec![PartitionedFile::new(
+            "/path/to/file.parquet".to_string(),
+            1024,
+        )]],
+        statistics: Statistics::new_unknown(&file_schema),
+        projection: Some(vec![0, 2, 1]),
+        limit: None,
+        table_partition_cols: vec![],
+        output_ordering: vec![],
+        schema_adapter_factory: None,
+        metadata_cols: vec![MetadataColumn::FilePath, MetadataColumn::RowIndex],
+        projection_paths: vec![],
+    };
+
+    roundtrip_test(Arc::new(ParquetExec::new(scan_config, None, None)))
+}
+
+#[test]
+fn roundtrip_parquet_exec_with_projection_paths() -> Result<()> {
+    let file_schema = Arc::new(Schema::new(vec![Field::new_struct(
+        "s",
+        vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Utf8, true),
+        ],
+        true,
+    )]));
+    let scan_config = FileScanConfig {
+        object_store_url: ObjectStoreUrl::local_filesystem(),
+        file_schema: file_schema.clone(),
+        file_groups: vec![vec![PartitionedFile::new(
+            "/path/to/file.parquet".to_string(),
+            1024,
+        )]],
+        statistics: Statistics::new_unknown(&file_schema),
+        projection: None,
+        limit: None,
+        table_partition_cols: vec![],
+        output_ordering: vec![],
+        schema_adapter_factory: None,
+        metadata_cols: vec![],
+        projection_paths: vec![vec!["s".to_string(), "b".to_string()]],
+    };
+
+    roundtrip_test(Arc::new(ParquetExec::new(scan_config, None, None)))
+}
+
 #[test]
 fn roundtrip_builtin_scalar_function() -> Result<()> {
     let field_a = Field::new("a", DataType::Int64, false);
@@ -760,11 +760,14 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         let inline_constraints = calc_inline_constraints_from_columns(&columns);
         all_constraints.extend(inline_constraints);
 
-        if (file_type == "PARQUET" || file_type == "AVRO" || file_type == "ARROW")
+        if (file_type == "PARQUET"
+            || file_type == "AVRO"
+            || file_type == "ARROW"
+            || file_type == "ORC")
             && file_compression_type != CompressionTypeVariant::UNCOMPRESSED
         {
             plan_err!(
-                "File compression type cannot be set for PARQUET, AVRO, or ARROW files."
+                "File compression type cannot be set for PARQUET, AVRO, ARROW, or ORC files."
             )?;
         }
 
@@ -1877,11 +1877,12 @@ fn create_external_table_with_compression_type() {
         "CREATE EXTERNAL TABLE t STORED AS PARQUET COMPRESSION TYPE BZIP2 LOCATION 'foo.parquet'",
         "CREATE EXTERNAL TABLE t STORED AS ARROW COMPRESSION TYPE GZIP LOCATION 'foo.arrow'",
         "CREATE EXTERNAL TABLE t STORED AS ARROW COMPRESSION TYPE BZIP2 LOCATION 'foo.arrow'",
+        "CREATE EXTERNAL TABLE t STORED AS ORC COMPRESSION TYPE GZIP LOCATION 'foo.orc'",
     ];
     for sql in sqls {
         let err = logical_plan(sql).expect_err("query should have failed");
         assert_eq!(
-            "Error during planning: File compression type cannot be set for PARQUET, AVRO, or ARROW files.",
+            "Error during planning: File compression type cannot be set for PARQUET, AVRO, ARROW, or ORC files.",
             err.strip_backtrace()
         );
     }
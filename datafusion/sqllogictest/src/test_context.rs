@@ -102,6 +102,13 @@ impl TestContext {
                     return None;
                 }
             }
+            "orc.slt" => {
+                #[cfg(not(feature = "orc"))]
+                {
+                    info!("Skipping {file_name} because orc feature is not enabled");
+                    return None;
+                }
+            }
             "joins.slt" => {
                 info!("Registering partition table tables");
                 let example_udf = create_example_udf();
@@ -112,6 +112,9 @@ pub async fn from_substrait_rel(
                         limit: None,
                         table_partition_cols: vec![],
                         output_ordering: vec![],
+                        schema_adapter_factory: None,
+                        metadata_cols: vec![],
+                        projection_paths: vec![],
                     };
 
                     if let Some(MaskExpression { select, .. }) = &read.projection {
@@ -49,6 +49,9 @@ async fn parquet_exec() -> Result<()> {
         limit: None,
         table_partition_cols: vec![],
         output_ordering: vec![],
+        schema_adapter_factory: None,
+        metadata_cols: vec![],
+        projection_paths: vec![],
     };
     let parquet_exec: Arc<dyn ExecutionPlan> =
         Arc::new(ParquetExec::new(scan_config, None, None));